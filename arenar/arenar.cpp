@@ -1,6 +1,11 @@
+#include <algorithm>
 #include <cassert>
+#include <cmath>
+#include <cstring>
+#include <mutex>
 
 #include "arenar.h"
+#include "../libsrc/bullet3-3.24/LinearMath/btQuickprof.h"
 
 const CarConfig& getOctane() {
     return CAR_CONFIG_OCTANE;
@@ -54,20 +59,750 @@ std::unique_ptr<Arenar> CreateArena(GameMode game_mode, EArenaConfig arenaConfig
 	return std::make_unique<Arenar>(game_mode, arenaConfig, tick_rate);
 }
 
+std::unique_ptr<Arenar> CreateArenaWithBoostPads(GameMode game_mode, EArenaConfig arenaConfig, uint8_t tick_rate, rust::Slice<const BoostPadConfig> boost_pads) {
+	return std::make_unique<Arenar>(game_mode, arenaConfig, tick_rate, boost_pads);
+}
+
 void Arenar::SetGoalScoreCallback(rust::Fn<void(Arenar&, Team, size_t)> callback, size_t user_info) {
+    goalScoreCallback = callback;
+    goalScoreUserInfo = user_info;
+
     a->SetGoalScoreCallback([callback](class Arena* arena, Team team, void* userInfo) {
         std::pair<Arenar*, size_t>* userInfoPair = (std::pair<Arenar*, size_t>*) userInfo;
+
+        // custom goal zones replace the normal goals
+        if (userInfoPair->first->goalZones) {
+            return;
+        }
+
+        CallbackTimer timer(*userInfoPair->first);
         callback(*(userInfoPair->first), team, userInfoPair->second);
     }, new std::pair(this, user_info));
 }
 
+void Arenar::SetGoalZoneCallback(rust::Fn<void(Arenar&, Team, size_t, size_t)> callback, size_t user_info) {
+    goalZoneCallback = callback;
+    goalZoneUserInfo = user_info;
+}
+
+void Arenar::SetGoalZones(rust::Slice<const EGoalZone> zones) {
+    goalZones = std::vector<EGoalZone>(zones.begin(), zones.end());
+    ballInGoalZone = std::vector<bool>(zones.size(), false);
+}
+
+void Arenar::UseDefaultGoalZones() {
+    goalZones = std::nullopt;
+    ballInGoalZone.clear();
+}
+
+void Arenar::SetForceCallback(rust::Fn<void(Arenar&, size_t)> callback, size_t user_info) {
+    forceCallback = callback;
+    forceUserInfo = user_info;
+}
+
+void Arenar::SetTriggerCallback(rust::Fn<void(Arenar&, uint32_t, uint32_t, bool, size_t)> callback, size_t user_info) {
+    triggerCallback = callback;
+    triggerUserInfo = user_info;
+}
+
 void Arenar::SetCarBumpCallback(rust::Fn<void(Arenar&, uint32_t, uint32_t, bool, size_t)> callback, size_t user_info) {
     a->SetCarBumpCallback([callback](class Arena* arena, Car* bumper, Car* victim, bool isDemo, void* userInfo) {
         std::pair<Arenar*, size_t>* userInfoPair = (std::pair<Arenar*, size_t>*) userInfo;
+        CallbackTimer timer(*userInfoPair->first);
         callback(*(userInfoPair->first), bumper->id, victim->id, isDemo, userInfoPair->second);
     }, new std::pair(this, user_info));
 }
 
+void Arenar::SetCarContactCallback(rust::Fn<void(Arenar&, uint32_t, uint32_t, Vec, float, size_t)> callback, size_t user_info) {
+    carContactCallback = callback;
+    carContactUserInfo = user_info;
+}
+
+namespace {
+    using Clock = std::chrono::steady_clock;
+
+    double SecondsSince(Clock::time_point start) {
+        return std::chrono::duration<double>(Clock::now() - start).count();
+    }
+
+    // Bullet's profiler hooks are global, so the arena that is being stepped is tracked per thread
+    thread_local EStepTimings* profiledTimings = nullptr;
+
+    struct ProfileZone {
+        const char* name;
+        Clock::time_point start;
+    };
+
+    thread_local std::vector<ProfileZone> openZones;
+
+    void EnterProfileZone(const char* name) {
+        if (profiledTimings) {
+            openZones.push_back({ name, Clock::now() });
+        }
+    }
+
+    void LeaveProfileZone() {
+        if (!profiledTimings || openZones.empty()) {
+            return;
+        }
+
+        ProfileZone zone = openZones.back();
+        openZones.pop_back();
+        double elapsed = SecondsSince(zone.start);
+
+        // the sub-zones are moved out of the total for the whole Bullet step
+        if (std::strcmp(zone.name, "stepSimulation") == 0) {
+            profiledTimings->otherBullet += elapsed;
+        } else if (std::strcmp(zone.name, "updateAabbs") == 0 || std::strcmp(zone.name, "calculateOverlappingPairs") == 0) {
+            profiledTimings->broadphase += elapsed;
+            profiledTimings->otherBullet -= elapsed;
+        } else if (std::strcmp(zone.name, "dispatchAllCollisionPairs") == 0) {
+            profiledTimings->narrowphase += elapsed;
+            profiledTimings->otherBullet -= elapsed;
+        } else if (std::strcmp(zone.name, "solveConstraints") == 0) {
+            profiledTimings->solver += elapsed;
+            profiledTimings->otherBullet -= elapsed;
+        }
+    }
+
+    double BulletTime(const EStepTimings& timings) {
+        return timings.broadphase + timings.narrowphase + timings.solver + timings.otherBullet;
+    }
+
+    // callbacks that are fired from inside of Bullet are already part of its timings
+    thread_local double callbacksOutsideBullet = 0;
+}
+
+CallbackTimer::CallbackTimer(Arenar& arena) : arena(arena), start(Clock::now()) {}
+
+CallbackTimer::~CallbackTimer() {
+    if (!arena.timeSteps) {
+        return;
+    }
+
+    double elapsed = SecondsSince(start);
+    arena.stepTimings.callbacks += elapsed;
+
+    if (openZones.empty()) {
+        callbacksOutsideBullet += elapsed;
+    }
+}
+
+void Arenar::SetStepTimingsEnabled(bool enabled) {
+    static std::once_flag installHooks;
+    std::call_once(installHooks, []() {
+        btSetCustomEnterProfileZoneFunc(EnterProfileZone);
+        btSetCustomLeaveProfileZoneFunc(LeaveProfileZone);
+    });
+
+    timeSteps = enabled;
+}
+
+void Arenar::TimedStep() {
+    Clock::time_point start = Clock::now();
+    double bulletBefore = BulletTime(stepTimings);
+    callbacksOutsideBullet = 0;
+
+    if (forceCallback) {
+        DispatchForces();
+    }
+
+    profiledTimings = &stepTimings;
+    a->Step(1);
+    profiledTimings = nullptr;
+
+    if (carContactCallback) {
+        DispatchCarContacts();
+    }
+
+    if (goalZones) {
+        DispatchGoalZones();
+    }
+
+    if (!triggerVolumes.empty()) {
+        DispatchTriggerVolumes();
+    }
+
+    double total = SecondsSince(start);
+    stepTimings.ticks++;
+    stepTimings.total += total;
+    stepTimings.carsAndBall += total - (BulletTime(stepTimings) - bulletBefore) - callbacksOutsideBullet;
+}
+
+void Arenar::Step(uint32_t ticks) {
+    if (paused) {
+        return;
+    }
+
+    if (!timeSteps && !carContactCallback && !goalZones && !forceCallback && triggerVolumes.empty() && historyCapacity == 0) {
+        a->Step(ticks);
+        return;
+    }
+
+    // contacts have to be inspected after every tick, before Bullet refreshes the manifolds
+    for (uint32_t i = 0; i < ticks; i++) {
+        if (timeSteps) {
+            TimedStep();
+        } else {
+            if (forceCallback) {
+                DispatchForces();
+            }
+
+            a->Step(1);
+
+            if (carContactCallback) {
+                DispatchCarContacts();
+            }
+
+            if (goalZones) {
+                DispatchGoalZones();
+            }
+
+            if (!triggerVolumes.empty()) {
+                DispatchTriggerVolumes();
+            }
+        }
+
+        if (historyCapacity != 0) {
+            RecordHistory();
+        }
+    }
+}
+
+void Arenar::RecordHistory() {
+    uint64_t tick = a->tickCount;
+
+    // the history only covers consecutive ticks
+    if (!history.empty() && HistoryAt(history.size() - 1).tick + 1 != tick) {
+        ClearHistory();
+    }
+
+    HistoryEntry* entry;
+    if (history.size() < historyCapacity) {
+        entry = &history.emplace_back();
+    } else {
+        // reuse the oldest entry, so the car list doesn't need to be reallocated
+        entry = &history[historyStart];
+        historyStart = (historyStart + 1) % history.size();
+    }
+
+    entry->tick = tick;
+    entry->ball = a->ball->GetState();
+    entry->cars.clear();
+    for (Car* car : a->_cars) {
+        entry->cars.emplace_back(car->id, car->GetState());
+    }
+
+    entry->pads.clear();
+    for (size_t i = 0; i < a->_boostPads.size(); i++) {
+        entry->pads.push_back(GetPadState(i));
+    }
+}
+
+void Arenar::RestoreHistory(size_t index) {
+    const HistoryEntry& entry = HistoryAt(index);
+
+    for (const auto& [id, state] : entry.cars) {
+        Car* car = a->GetCar(id);
+        if (car != NULL) {
+            car->SetState(state);
+        }
+    }
+
+    for (size_t i = 0; i < entry.pads.size() && i < a->_boostPads.size(); i++) {
+        SetPadState(i, entry.pads[i]);
+    }
+
+    a->ball->SetState(entry.ball);
+    a->tickCount = entry.tick;
+
+    // the restored tick becomes the newest entry
+    std::vector<HistoryEntry> kept;
+    kept.reserve(index + 1);
+    for (size_t i = 0; i <= index; i++) {
+        kept.push_back(std::move(history[(historyStart + i) % history.size()]));
+    }
+
+    history = std::move(kept);
+    historyStart = 0;
+}
+
+rust::Vec<uint32_t> Arenar::HistoryCars(size_t index) const {
+    rust::Vec<uint32_t> cars = rust::Vec<uint32_t>();
+    for (const auto& [id, state] : HistoryAt(index).cars) {
+        cars.push_back(id);
+    }
+    return cars;
+}
+
+void Arenar::PushHistory(uint64_t tick, BallState ball, rust::Slice<const uint32_t> carIDs, rust::Slice<const CarState> cars, rust::Slice<const EBoostPadState> pads) {
+    if (historyCapacity == 0) {
+        return;
+    }
+
+    // the history only covers consecutive ticks
+    if (!history.empty() && HistoryAt(history.size() - 1).tick + 1 != tick) {
+        ClearHistory();
+    }
+
+    HistoryEntry entry = HistoryEntry {
+        .tick = tick,
+        .ball = ball,
+        .cars = {},
+        .pads = std::vector<EBoostPadState>(pads.begin(), pads.end()),
+    };
+
+    for (size_t i = 0; i < carIDs.size() && i < cars.size(); i++) {
+        entry.cars.emplace_back(carIDs[i], cars[i]);
+    }
+
+    if (history.size() < historyCapacity) {
+        history.push_back(std::move(entry));
+    } else {
+        history[historyStart] = std::move(entry);
+        historyStart = (historyStart + 1) % history.size();
+    }
+}
+
+bool Arenar::HistoryHasCar(size_t index, uint32_t carID) const {
+    for (const auto& [id, state] : HistoryAt(index).cars) {
+        if (id == carID) {
+            return true;
+        }
+    }
+
+    return false;
+}
+
+CarState Arenar::HistoryCar(size_t index, uint32_t carID) const {
+    for (const auto& [id, state] : HistoryAt(index).cars) {
+        if (id == carID) {
+            return state;
+        }
+    }
+
+    assert(false);
+    return CarState();
+}
+
+void Arenar::DispatchGoalZones() {
+    Vec ballPos = a->ball->GetState().pos;
+
+    // copied because the callbacks are allowed to change the zones
+    std::vector<EGoalZone> zones = *goalZones;
+    std::vector<bool> wasInZone = ballInGoalZone;
+    std::vector<bool> isInZone = std::vector<bool>(zones.size(), false);
+
+    for (size_t i = 0; i < zones.size(); i++) {
+        const EGoalZone& zone = zones[i];
+        isInZone[i] =
+            ballPos.x >= zone.min.x && ballPos.x <= zone.max.x &&
+            ballPos.y >= zone.min.y && ballPos.y <= zone.max.y &&
+            ballPos.z >= zone.min.z && ballPos.z <= zone.max.z;
+
+        // only trigger once per entry
+        if (!isInZone[i] || wasInZone[i]) {
+            continue;
+        }
+
+        CallbackTimer timer(*this);
+
+        if (goalScoreCallback) {
+            (*goalScoreCallback)(*this, zone.team, goalScoreUserInfo);
+        }
+
+        if (goalZoneCallback) {
+            (*goalZoneCallback)(*this, zone.team, i, goalZoneUserInfo);
+        }
+    }
+
+    // if the zones were replaced, SetGoalZones already reset the tracking
+    if (ballInGoalZone == wasInZone && goalZones && goalZones->size() == zones.size()) {
+        ballInGoalZone = isInZone;
+    }
+}
+
+void Arenar::DispatchCarContacts() {
+    btDispatcher* dispatcher = a->_bulletWorld->getDispatcher();
+
+    for (int i = 0; i < dispatcher->getNumManifolds(); i++) {
+        btPersistentManifold* manifold = dispatcher->getManifoldByIndexInternal(i);
+        const btCollisionObject* bodyA = manifold->getBody0();
+        const btCollisionObject* bodyB = manifold->getBody1();
+
+        if (bodyA->getUserIndex() != BT_USERINFO_TYPE_CAR || bodyB->getUserIndex() != BT_USERINFO_TYPE_CAR) {
+            continue;
+        }
+
+        float totalImpulse = 0;
+        float maxImpulse = -1;
+        btVector3 contactPoint;
+
+        for (int j = 0; j < manifold->getNumContacts(); j++) {
+            const btManifoldPoint& point = manifold->getContactPoint(j);
+            if (point.getDistance() > 0) {
+                continue;
+            }
+
+            totalImpulse += point.getAppliedImpulse();
+            if (point.getAppliedImpulse() > maxImpulse) {
+                maxImpulse = point.getAppliedImpulse();
+                contactPoint = point.getPositionWorldOnA();
+            }
+        }
+
+        if (maxImpulse < 0) {
+            continue;
+        }
+
+        Car* carA = (Car*) bodyA->getUserPointer();
+        Car* carB = (Car*) bodyB->getUserPointer();
+        Vec point = Vec(contactPoint.x(), contactPoint.y(), contactPoint.z()) * BT_TO_UU;
+
+        CallbackTimer timer(*this);
+        (*carContactCallback)(*this, carA->id, carB->id, point, totalImpulse * BT_TO_UU, carContactUserInfo);
+    }
+}
+
+void Arenar::DispatchForces() {
+    CallbackTimer timer(*this);
+    (*forceCallback)(*this, forceUserInfo);
+}
+
+// Matches the friction and restitution of RocketSim's own arena geometry
+constexpr float EXTRA_GEOMETRY_FRICTION = 0.6f;
+constexpr float EXTRA_GEOMETRY_RESTITUTION = 0.3f;
+
+void Arenar::AddStaticShape(std::shared_ptr<btCollisionShape> shape, const btTransform& transform, std::shared_ptr<btTriangleMesh> mesh) {
+    btRigidBody::btRigidBodyConstructionInfo info(0, nullptr, shape.get());
+    info.m_startWorldTransform = transform;
+    info.m_friction = EXTRA_GEOMETRY_FRICTION;
+    info.m_restitution = EXTRA_GEOMETRY_RESTITUTION;
+
+    std::unique_ptr<btRigidBody> body = std::make_unique<btRigidBody>(info);
+    a->_bulletWorld->addRigidBody(body.get());
+    extraBodies.push_back(ExtraStaticBody { shape, mesh, std::move(body) });
+}
+
+btVector3 ToBullet(Vec vec) {
+    return btVector3(vec.x, vec.y, vec.z) * UU_TO_BT;
+}
+
+btMatrix3x3 ToBulletBasis(RotMat rot_mat) {
+    return btMatrix3x3(
+        rot_mat.forward.x, rot_mat.right.x, rot_mat.up.x,
+        rot_mat.forward.y, rot_mat.right.y, rot_mat.up.y,
+        rot_mat.forward.z, rot_mat.right.z, rot_mat.up.z
+    );
+}
+
+void Arenar::ApplyBallImpulse(Vec impulse) {
+    btRigidBody& body = a->ball->_rigidBody;
+    body.activate();
+    body.applyCentralImpulse(ToBullet(impulse));
+}
+
+bool Arenar::ApplyCarImpulse(uint32_t carID, Vec impulse, Vec point) {
+    Car* car = a->GetCar(carID);
+    if (car == NULL) {
+        return false;
+    }
+
+    btRigidBody& body = car->_rigidBody;
+    body.activate();
+    body.applyImpulse(ToBullet(impulse), ToBullet(point) - body.getCenterOfMassPosition());
+    return true;
+}
+
+void Arenar::AddStaticBox(Vec center, Vec half_extents, RotMat rot_mat) {
+    AddStaticShape(std::make_shared<btBoxShape>(ToBullet(half_extents)), btTransform(ToBulletBasis(rot_mat), ToBullet(center)));
+}
+
+void Arenar::AddStaticSphere(Vec center, float radius) {
+    btTransform transform = btTransform::getIdentity();
+    transform.setOrigin(ToBullet(center));
+
+    AddStaticShape(std::make_shared<btSphereShape>(radius * UU_TO_BT), transform);
+}
+
+void Arenar::AddStaticPlane(Vec normal, float offset) {
+    btVector3 btNormal = btVector3(normal.x, normal.y, normal.z).normalized();
+    AddStaticShape(std::make_shared<btStaticPlaneShape>(btNormal, offset * UU_TO_BT), btTransform::getIdentity());
+}
+
+void Arenar::AddStaticMesh(rust::Slice<const Vec> vertices, rust::Slice<const uint32_t> indices) {
+    assert(indices.size() % 3 == 0);
+
+    std::shared_ptr<btTriangleMesh> mesh = std::make_shared<btTriangleMesh>();
+    for (size_t i = 0; i + 2 < indices.size(); i += 3) {
+        mesh->addTriangle(ToBullet(vertices[indices[i]]), ToBullet(vertices[indices[i + 1]]), ToBullet(vertices[indices[i + 2]]));
+    }
+
+    AddStaticShape(std::make_shared<btBvhTriangleMeshShape>(mesh.get(), true), btTransform::getIdentity(), mesh);
+}
+
+constexpr float PROP_FRICTION = 0.6f;
+constexpr float PROP_RESTITUTION = 0.6f;
+
+Vec FromBullet(const btVector3& vec) {
+    return Vec(vec.x(), vec.y(), vec.z()) * BT_TO_UU;
+}
+
+RotMat FromBulletBasis(const btMatrix3x3& basis) {
+    btVector3 forward = basis.getColumn(0), right = basis.getColumn(1), up = basis.getColumn(2);
+    return RotMat(
+        Vec(forward.x(), forward.y(), forward.z()),
+        Vec(right.x(), right.y(), right.z()),
+        Vec(up.x(), up.y(), up.z())
+    );
+}
+
+void SetBodyState(btRigidBody* body, EPropState state) {
+    btTransform transform(ToBulletBasis(state.rotMat), ToBullet(state.pos));
+    body->setWorldTransform(transform);
+    body->setInterpolationWorldTransform(transform);
+    body->setLinearVelocity(ToBullet(state.vel));
+    body->setAngularVelocity(btVector3(state.angVel.x, state.angVel.y, state.angVel.z));
+    body->clearForces();
+}
+
+void Arenar::AddPropBody(uint32_t id, EPropConfig config, std::shared_ptr<btCollisionShape> shape, EPropState state) {
+    btVector3 inertia;
+    shape->calculateLocalInertia(config.mass, inertia);
+
+    btRigidBody::btRigidBodyConstructionInfo info(config.mass, nullptr, shape.get(), inertia);
+    info.m_friction = PROP_FRICTION;
+    info.m_restitution = PROP_RESTITUTION;
+
+    std::unique_ptr<btRigidBody> body = std::make_unique<btRigidBody>(info);
+    // props are few and cheap, and sleeping bodies would ignore state sets
+    body->setActivationState(DISABLE_DEACTIVATION);
+    SetBodyState(body.get(), state);
+
+    a->_bulletWorld->addRigidBody(body.get());
+    props.push_back(Prop { id, config, shape, std::move(body) });
+}
+
+uint32_t Arenar::AddProp(EPropConfig config, EPropState state) {
+    assert(config.mass > 0);
+
+    std::shared_ptr<btCollisionShape> shape;
+    if (config.shape == PropShape::BOX) {
+        shape = std::make_shared<btBoxShape>(ToBullet(config.halfExtents));
+    } else {
+        shape = std::make_shared<btSphereShape>(config.radius * UU_TO_BT);
+    }
+
+    uint32_t id = ++lastPropId;
+    AddPropBody(id, config, shape, state);
+    return id;
+}
+
+bool Arenar::RemoveProp(uint32_t propID) {
+    for (auto it = props.begin(); it != props.end(); it++) {
+        if (it->id == propID) {
+            a->_bulletWorld->removeRigidBody(it->body.get());
+            props.erase(it);
+            return true;
+        }
+    }
+
+    return false;
+}
+
+rust::Vec<uint32_t> Arenar::GetProps() const {
+    rust::Vec<uint32_t> ids = rust::Vec<uint32_t>();
+    for (const Prop& prop : props) {
+        ids.push_back(prop.id);
+    }
+    return ids;
+}
+
+const Prop* Arenar::FindProp(uint32_t propID) const {
+    for (const Prop& prop : props) {
+        if (prop.id == propID) {
+            return &prop;
+        }
+    }
+
+    return NULL;
+}
+
+bool Arenar::HasProp(uint32_t propID) const {
+    return FindProp(propID) != NULL;
+}
+
+EPropConfig Arenar::GetPropConfig(uint32_t propID) const {
+    const Prop* prop = FindProp(propID);
+    assert(prop != NULL);
+
+    return prop->config;
+}
+
+EPropState Arenar::GetProp(uint32_t propID) const {
+    const Prop* prop = FindProp(propID);
+    assert(prop != NULL);
+
+    const btRigidBody* body = prop->body.get();
+    const btTransform& transform = body->getWorldTransform();
+    btVector3 angVel = body->getAngularVelocity();
+
+    return EPropState {
+        FromBullet(transform.getOrigin()),
+        FromBulletBasis(transform.getBasis()),
+        FromBullet(body->getLinearVelocity()),
+        Vec(angVel.x(), angVel.y(), angVel.z()),
+    };
+}
+
+bool Arenar::SetProp(uint32_t propID, EPropState state) {
+    const Prop* prop = FindProp(propID);
+    if (prop == NULL) {
+        return false;
+    }
+
+    SetBodyState(prop->body.get(), state);
+    return true;
+}
+
+bool TriggerVolumeContains(const ETriggerVolume& volume, Vec pos) {
+    Vec delta = pos - volume.center;
+
+    switch (volume.shape) {
+    case TriggerShape::SPHERE:
+        return delta.LengthSq() <= volume.radius * volume.radius;
+    case TriggerShape::OBB:
+        // check against the box in its own local coordinates
+        delta = Vec(delta.Dot(volume.rotMat.forward), delta.Dot(volume.rotMat.right), delta.Dot(volume.rotMat.up));
+        [[fallthrough]];
+    case TriggerShape::AABB:
+    default:
+        return std::abs(delta.x) <= volume.halfExtents.x &&
+            std::abs(delta.y) <= volume.halfExtents.y &&
+            std::abs(delta.z) <= volume.halfExtents.z;
+    }
+}
+
+uint32_t Arenar::AddTriggerVolume(ETriggerVolume config) {
+    uint32_t id = ++lastTriggerVolumeId;
+    TriggerVolume volume = TriggerVolume { id, config, false, {} };
+
+    // the volume starts out with whatever is already inside of it, so that doesn't count as entering
+    volume.ballInside = TriggerVolumeContains(config, BallPos(*a->ball));
+    for (Car* car : a->_cars) {
+        if (TriggerVolumeContains(config, CarPos(*car))) {
+            volume.carsInside.push_back(car->id);
+        }
+    }
+
+    triggerVolumes.push_back(std::move(volume));
+    return id;
+}
+
+bool Arenar::RemoveTriggerVolume(uint32_t volumeID) {
+    for (auto it = triggerVolumes.begin(); it != triggerVolumes.end(); it++) {
+        if (it->id == volumeID) {
+            triggerVolumes.erase(it);
+            return true;
+        }
+    }
+
+    return false;
+}
+
+rust::Vec<uint32_t> Arenar::GetTriggerVolumes() const {
+    rust::Vec<uint32_t> ids = rust::Vec<uint32_t>();
+    for (const TriggerVolume& volume : triggerVolumes) {
+        ids.push_back(volume.id);
+    }
+    return ids;
+}
+
+bool Arenar::HasTriggerVolume(uint32_t volumeID) const {
+    for (const TriggerVolume& volume : triggerVolumes) {
+        if (volume.id == volumeID) {
+            return true;
+        }
+    }
+
+    return false;
+}
+
+ETriggerVolume Arenar::GetTriggerVolume(uint32_t volumeID) const {
+    for (const TriggerVolume& volume : triggerVolumes) {
+        if (volume.id == volumeID) {
+            return volume.config;
+        }
+    }
+
+    assert(false);
+    return ETriggerVolume();
+}
+
+void Arenar::DispatchTriggerVolumes() {
+    struct TriggerEvent {
+        uint32_t volumeID;
+        // 0 for the ball
+        uint32_t carID;
+        bool entered;
+    };
+
+    // the events are collected first, because the callback is allowed to add and remove volumes
+    std::vector<TriggerEvent> events;
+    Vec ballPos = BallPos(*a->ball);
+
+    for (TriggerVolume& volume : triggerVolumes) {
+        bool ballInside = TriggerVolumeContains(volume.config, ballPos);
+        if (ballInside != volume.ballInside) {
+            events.push_back({ volume.id, 0, ballInside });
+            volume.ballInside = ballInside;
+        }
+
+        std::vector<uint32_t> carsInside;
+        for (Car* car : a->_cars) {
+            if (!TriggerVolumeContains(volume.config, CarPos(*car))) {
+                continue;
+            }
+
+            carsInside.push_back(car->id);
+            if (std::find(volume.carsInside.begin(), volume.carsInside.end(), car->id) == volume.carsInside.end()) {
+                events.push_back({ volume.id, car->id, true });
+            }
+        }
+
+        // cars that were removed from the arena don't leave
+        for (uint32_t carID : volume.carsInside) {
+            if (a->GetCar(carID) != NULL && std::find(carsInside.begin(), carsInside.end(), carID) == carsInside.end()) {
+                events.push_back({ volume.id, carID, false });
+            }
+        }
+
+        volume.carsInside = std::move(carsInside);
+    }
+
+    if (!triggerCallback) {
+        return;
+    }
+
+    for (const TriggerEvent& event : events) {
+        CallbackTimer timer(*this);
+        (*triggerCallback)(*this, event.volumeID, event.carID, event.entered, triggerUserInfo);
+    }
+}
+
+EArenaConfig Arenar::GetArenaConfig() const {
+    const ArenaConfig& config = a->GetArenaConfig();
+    return EArenaConfig {
+        .memWeightMode = config.memWeightMode,
+        .minPos = config.minPos,
+        .maxPos = config.maxPos,
+        .maxAABBLen = config.maxAABBLen,
+        .noBallRot = config.noBallRot,
+        .useCustomBroadphase = config.useCustomBroadphase,
+        .maxObjects = config.maxObjects,
+    };
+}
+
 rust::Vec<uint32_t> Arenar::GetCars() const {
     rust::Vec<uint32_t> cars = rust::Vec<uint32_t>();
     for (Car* car : a->_cars) {
@@ -103,6 +838,54 @@ bool Arenar::RemoveCar(uint32_t carID) {
     return true;
 }
 
+bool Arenar::AddCarWithId(uint32_t carID, Team team, const CarConfig& config) {
+    // 0 is used for "no car", like in the boost pad states
+    if (carID == 0 || a->GetCar(carID) != NULL || IsFull()) {
+        return false;
+    }
+
+    SetCarId(a->AddCar(team, config), carID);
+    return true;
+}
+
+bool Arenar::SetCarTeam(uint32_t carID, Team team) {
+    Car* car = a->GetCar(carID);
+    if (car == NULL) {
+        return false;
+    }
+
+    car->team = team;
+    return true;
+}
+
+// The config is baked into the car's rigid body, so the car is replaced by a new one with the same id
+bool Arenar::SetCarConfig(uint32_t carID, const CarConfig& config) {
+    Car* car = a->GetCar(carID);
+    if (car == NULL) {
+        return false;
+    }
+
+    Team team = car->team;
+    CarState state = car->GetState();
+    CarControls controls = car->controls;
+    a->RemoveCar(car);
+
+    Car* newCar = a->AddCar(team, config);
+    SetCarId(newCar, carID);
+    newCar->SetState(state);
+    newCar->controls = controls;
+    return true;
+}
+
+void Arenar::SetCarId(Car* car, uint32_t carID) {
+    a->_carIDMap.erase(car->id);
+    car->id = carID;
+    a->_carIDMap[carID] = car;
+
+    // make sure that new cars never reuse the id
+    a->_lastCarID = std::max(a->_lastCarID, carID);
+}
+
 bool Arenar::SetCarControls(uint32_t carID, const CarControls controls) {
     Car* car = a->GetCar(carID);
     if (car == NULL) {
@@ -113,6 +896,14 @@ bool Arenar::SetCarControls(uint32_t carID, const CarControls controls) {
     return true;
 }
 
+void Arenar::SetTeamControls(Team team, const CarControls controls) {
+    for (Car* car : a->_cars) {
+        if (car->team == team) {
+            car->controls = controls;
+        }
+    }
+}
+
 bool Arenar::DemolishCar(uint32_t carID) {
     Car* car = a->GetCar(carID);
     if (car == NULL) {
@@ -164,3 +955,45 @@ EBoostPadState Arenar::GetPadState(size_t index) const {
         state.prevLockedCarID,
     };
 }
+
+const CarState& CarInternalState(const Car& car) {
+    return car._internalState;
+}
+
+Vec CarPos(const Car& car) {
+    return FromBullet(car._rigidBody.getWorldTransform().getOrigin());
+}
+
+RotMat CarRotMat(const Car& car) {
+    return FromBulletBasis(car._rigidBody.getWorldTransform().getBasis());
+}
+
+Vec CarVel(const Car& car) {
+    return FromBullet(car._rigidBody.getLinearVelocity());
+}
+
+Vec CarAngVel(const Car& car) {
+    btVector3 angVel = car._rigidBody.getAngularVelocity();
+    return Vec(angVel.x(), angVel.y(), angVel.z());
+}
+
+const BallState& BallInternalState(const Ball& ball) {
+    return ball._internalState;
+}
+
+Vec BallPos(const Ball& ball) {
+    return FromBullet(ball._rigidBody.getWorldTransform().getOrigin());
+}
+
+RotMat BallRotMat(const Ball& ball) {
+    return FromBulletBasis(ball._rigidBody.getWorldTransform().getBasis());
+}
+
+Vec BallVel(const Ball& ball) {
+    return FromBullet(ball._rigidBody.getLinearVelocity());
+}
+
+Vec BallAngVel(const Ball& ball) {
+    btVector3 angVel = ball._rigidBody.getAngularVelocity();
+    return Vec(angVel.x(), angVel.y(), angVel.z());
+}
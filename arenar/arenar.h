@@ -2,6 +2,17 @@
 
 #include "RocketSim.h"
 #include "rust/cxx.h"
+#include "../libsrc/bullet3-3.24/BulletCollision/CollisionShapes/btBoxShape.h"
+#include "../libsrc/bullet3-3.24/BulletCollision/CollisionShapes/btBvhTriangleMeshShape.h"
+#include "../libsrc/bullet3-3.24/BulletCollision/CollisionShapes/btSphereShape.h"
+#include "../libsrc/bullet3-3.24/BulletCollision/CollisionShapes/btStaticPlaneShape.h"
+#include "../libsrc/bullet3-3.24/BulletCollision/CollisionShapes/btTriangleMesh.h"
+
+#include <cassert>
+#include <chrono>
+#include <memory>
+#include <optional>
+#include <vector>
 
 using namespace RocketSim;
 
@@ -25,6 +36,20 @@ struct EArenaConfig {
 	int maxObjects;
 };
 
+inline ArenaConfig ToArenaConfig(const EArenaConfig& arenaConfig) {
+	return ArenaConfig {
+		.memWeightMode = (ArenaMemWeightMode) arenaConfig.memWeightMode,
+		.minPos = Vec(arenaConfig.minPos.x, arenaConfig.minPos.y, arenaConfig.minPos.z),
+		.maxPos = Vec(arenaConfig.maxPos.x, arenaConfig.maxPos.y, arenaConfig.maxPos.z),
+		.maxAABBLen = arenaConfig.maxAABBLen,
+		.noBallRot = arenaConfig.noBallRot,
+		.useCustomBroadphase = arenaConfig.useCustomBroadphase,
+		.maxObjects = arenaConfig.maxObjects,
+		.useCustomBoostPads = false,
+		.customBoostPads = {}
+	};
+}
+
 struct EBoostPadState {
 	bool isActive;
 	float cooldown;
@@ -32,35 +57,169 @@ struct EBoostPadState {
 	uint32_t prevLockedCarID = 0;
 };
 
+struct EGoalZone {
+	Vec min, max;
+	Team team;
+};
+
+// Seconds spent in each part of Step, summed over all timed ticks
+struct EStepTimings {
+	uint64_t ticks;
+	double total;
+	double carsAndBall;
+	double broadphase;
+	double narrowphase;
+	double solver;
+	double otherBullet;
+	double callbacks;
+};
+
+enum class PropShape : uint8_t {
+	SPHERE,
+	BOX,
+};
+
+struct EPropConfig {
+	PropShape shape;
+	float radius;
+	Vec halfExtents;
+	float mass;
+};
+
+struct EPropState {
+	Vec pos;
+	RotMat rotMat;
+	Vec vel, angVel;
+};
+
+enum class TriggerShape : uint8_t {
+	AABB,
+	SPHERE,
+	OBB,
+};
+
+struct ETriggerVolume {
+	TriggerShape shape;
+	Vec center;
+	float radius;
+	Vec halfExtents;
+	RotMat rotMat;
+};
+
+// The states of the ball, cars and boost pads after a tick
+struct HistoryEntry {
+	uint64_t tick;
+	BallState ball;
+	std::vector<std::pair<uint32_t, CarState>> cars;
+	std::vector<EBoostPadState> pads;
+};
+
+// A simple dynamic rigid body that isn't part of RocketSim's own simulation
+struct Prop {
+	uint32_t id;
+	EPropConfig config;
+	std::shared_ptr<btCollisionShape> shape;
+	std::unique_ptr<btRigidBody> body;
+};
+
+// A region of the arena that reports when the ball or a car enters or leaves it
+struct TriggerVolume {
+	uint32_t id;
+	ETriggerVolume config;
+	bool ballInside;
+	std::vector<uint32_t> carsInside;
+};
+
+// Static collision geometry that was added on top of RocketSim's own arena geometry
+struct ExtraStaticBody {
+	std::shared_ptr<btCollisionShape> shape;
+	// triangle mesh shapes don't own their triangles
+	std::shared_ptr<btTriangleMesh> mesh;
+	std::unique_ptr<btRigidBody> body;
+};
+
 struct Arenar {
     Arena* a;
+	std::optional<rust::Fn<void(Arenar&, uint32_t, uint32_t, Vec, float, size_t)>> carContactCallback;
+	size_t carContactUserInfo = 0;
+	std::vector<ExtraStaticBody> extraBodies;
+	std::vector<Prop> props;
+	uint32_t lastPropId = 0;
+	std::optional<rust::Fn<void(Arenar&, Team, size_t)>> goalScoreCallback;
+	size_t goalScoreUserInfo = 0;
+	std::optional<rust::Fn<void(Arenar&, Team, size_t, size_t)>> goalZoneCallback;
+	size_t goalZoneUserInfo = 0;
+	// called before every tick, for applying custom forces
+	std::optional<rust::Fn<void(Arenar&, size_t)>> forceCallback;
+	size_t forceUserInfo = 0;
+	std::vector<TriggerVolume> triggerVolumes;
+	uint32_t lastTriggerVolumeId = 0;
+	std::optional<rust::Fn<void(Arenar&, uint32_t, uint32_t, bool, size_t)>> triggerCallback;
+	size_t triggerUserInfo = 0;
+	// if set, these replace the normal goals
+	std::optional<std::vector<EGoalZone>> goalZones;
+	std::vector<bool> ballInGoalZone;
+	bool timeSteps = false;
+	EStepTimings stepTimings = {};
+	// while paused, Step doesn't do anything
+	bool paused = false;
+	// the most cars the arena can have, 0 for no limit
+	size_t maxCars = 0;
+	// ring buffer of the last historyCapacity ticks, starting at historyStart
+	size_t historyCapacity = 0;
+	size_t historyStart = 0;
+	std::vector<HistoryEntry> history;
 
 	Arenar(Arena* arena) {
 		a = arena;
 	}
 
     Arenar(GameMode game_mode, EArenaConfig arenaConfig, float tick_rate) {
-		ArenaConfig config = ArenaConfig {
-			.memWeightMode = (ArenaMemWeightMode) arenaConfig.memWeightMode,
-			.minPos = Vec(arenaConfig.minPos.x, arenaConfig.minPos.y, arenaConfig.minPos.z),
-			.maxPos = Vec(arenaConfig.maxPos.x, arenaConfig.maxPos.y, arenaConfig.maxPos.z),
-			.maxAABBLen = arenaConfig.maxAABBLen,
-			.noBallRot = arenaConfig.noBallRot,
-			.useCustomBroadphase = arenaConfig.useCustomBroadphase,
-			.maxObjects = arenaConfig.maxObjects,
-			.useCustomBoostPads = false,
-			.customBoostPads = {}
-		};
+        a = Arena::Create(game_mode, ToArenaConfig(arenaConfig), tick_rate);
+	}
+
+	// replaces the game mode's boost pads with the given ones
+	Arenar(GameMode game_mode, EArenaConfig arenaConfig, float tick_rate, rust::Slice<const BoostPadConfig> boostPads) {
+		ArenaConfig config = ToArenaConfig(arenaConfig);
+		config.useCustomBoostPads = true;
+		config.customBoostPads = std::vector<BoostPadConfig>(boostPads.begin(), boostPads.end());
 
-        a = Arena::Create(game_mode, config, tick_rate);
+		a = Arena::Create(game_mode, config, tick_rate);
 	}
 
     ~Arenar() {
+		// the bodies have to leave the world before either is destroyed
+		for (ExtraStaticBody& extra : extraBodies) {
+			a->_bulletWorld->removeRigidBody(extra.body.get());
+		}
+
+		for (Prop& prop : props) {
+			a->_bulletWorld->removeRigidBody(prop.body.get());
+		}
+
         delete a;
     }
 
 	void SetGoalScoreCallback(rust::Fn<void(Arenar&, Team, size_t)> callback, size_t);
 	void SetCarBumpCallback(rust::Fn<void(Arenar&, uint32_t, uint32_t, bool, size_t)> callback, size_t user_info);
+	void SetCarContactCallback(rust::Fn<void(Arenar&, uint32_t, uint32_t, Vec, float, size_t)> callback, size_t user_info);
+	void SetGoalZoneCallback(rust::Fn<void(Arenar&, Team, size_t, size_t)> callback, size_t user_info);
+	void SetForceCallback(rust::Fn<void(Arenar&, size_t)> callback, size_t user_info);
+	void SetTriggerCallback(rust::Fn<void(Arenar&, uint32_t, uint32_t, bool, size_t)> callback, size_t user_info);
+
+	void SetGoalZones(rust::Slice<const EGoalZone> zones);
+	void UseDefaultGoalZones();
+	size_t NumGoalZones() const {
+		return goalZones ? goalZones->size() : 0;
+	}
+
+	EGoalZone GetGoalZone(size_t index) const {
+		assert(goalZones && index < goalZones->size());
+		return (*goalZones)[index];
+	}
+	bool HasCustomGoalZones() const {
+		return goalZones.has_value();
+	}
 
     // No copy constructor
     Arenar(const Arenar & other) = delete;
@@ -71,7 +230,42 @@ struct Arenar {
     Arenar& operator =(Arenar && other) = default;
 
 	std::unique_ptr<Arenar> Clone(bool copy_callbacks) const {
-		return std::make_unique<Arenar>(a->Clone(copy_callbacks));
+		std::unique_ptr<Arenar> arena = std::make_unique<Arenar>(a->Clone(copy_callbacks));
+
+		if (copy_callbacks) {
+			arena->carContactCallback = carContactCallback;
+			arena->carContactUserInfo = carContactUserInfo;
+			arena->goalScoreCallback = goalScoreCallback;
+			arena->goalScoreUserInfo = goalScoreUserInfo;
+			arena->goalZoneCallback = goalZoneCallback;
+			arena->goalZoneUserInfo = goalZoneUserInfo;
+			arena->forceCallback = forceCallback;
+			arena->forceUserInfo = forceUserInfo;
+			arena->triggerCallback = triggerCallback;
+			arena->triggerUserInfo = triggerUserInfo;
+		}
+
+		arena->maxCars = maxCars;
+		arena->historyCapacity = historyCapacity;
+		arena->historyStart = historyStart;
+		arena->history = history;
+
+		arena->goalZones = goalZones;
+		arena->ballInGoalZone = ballInGoalZone;
+
+		for (const ExtraStaticBody& extra : extraBodies) {
+			arena->AddStaticShape(extra.shape, extra.body->getWorldTransform(), extra.mesh);
+		}
+
+		for (const Prop& prop : props) {
+			arena->AddPropBody(prop.id, prop.config, prop.shape, GetProp(prop.id));
+		}
+		arena->lastPropId = lastPropId;
+
+		arena->triggerVolumes = triggerVolumes;
+		arena->lastTriggerVolumeId = lastTriggerVolumeId;
+
+		return arena;
 	}
 
 	// extra car stuff
@@ -85,10 +279,22 @@ struct Arenar {
 		return a->GetCar(car_id)->config;
 	}
 
+	EArenaConfig GetArenaConfig() const;
+
 	Team GetCarTeam(uint32_t car_id) const {
 		return a->GetCar(car_id)->team;
 	}
 
+	bool HasCar(uint32_t car_id) const {
+		return a->GetCar(car_id) != NULL;
+	}
+
+	const Car& GetCarRef(uint32_t car_id) const {
+		const Car* car = a->GetCar(car_id);
+		assert(car != NULL);
+		return *car;
+	}
+
 	CarState GetCar(uint32_t car_id);
 	/// @brief Sets the state of a car in the arena
 	/// @param arena
@@ -97,17 +303,39 @@ struct Arenar {
 	/// @return True if the car was found and the state was set, false otherwise
 	bool SetCar(uint32_t car_id, const CarState state);
 
+	// returns 0 if the arena already has maxCars cars
 	uint32_t AddCar(Team team, const CarConfig& config) {
+		if (IsFull()) {
+			return 0;
+		}
+
 		return a->AddCar(team, config)->id;
 	}
 
+	bool IsFull() const {
+		return maxCars != 0 && a->_cars.size() >= maxCars;
+	}
+
+	void SetMaxCars(size_t max_cars) {
+		maxCars = max_cars;
+	}
+
+	size_t GetMaxCars() const {
+		return maxCars;
+	}
+
+	bool AddCarWithId(uint32_t car_id, Team team, const CarConfig& config);
 	bool RemoveCar(uint32_t car_id);
+	bool SetCarTeam(uint32_t car_id, Team team);
+	bool SetCarConfig(uint32_t car_id, const CarConfig& config);
+	void SetCarId(Car* car, uint32_t car_id);
 	/// @brief Sets the controls of a car for the next tick
 	/// @param arena
 	/// @param state
 	/// @param carID
 	/// @return True if the car was found and the state was set, false otherwise
 	bool SetCarControls(uint32_t car_id, const CarControls controls);
+	void SetTeamControls(Team team, const CarControls controls);
 	bool DemolishCar(uint32_t car_id);
 	bool RespawnCar(uint32_t car_id, int32_t seed, float boost_amount);
 
@@ -121,6 +349,10 @@ struct Arenar {
 		a->ball->SetState(state);
 	}
 
+	const Ball& GetBallRef() const {
+		return *a->ball;
+	}
+
 	float GetBallRadius() const {
 		return a->ball->GetRadius();
 	}
@@ -141,6 +373,10 @@ struct Arenar {
 		a->tickCount = 0;
 	}
 
+	void SetTickCount(uint64_t tick_count) {
+		a->tickCount = tick_count;
+	}
+
 	uint64_t GetTickCount() const {
 		return a->tickCount;
 	}
@@ -149,6 +385,10 @@ struct Arenar {
 		return 1 / a->tickTime;
 	}
 
+	void SetTickRate(float tick_rate) {
+		a->tickTime = 1 / tick_rate;
+	}
+
 	GameMode GetGameMode() const {
 		return a->gameMode;
 	}
@@ -157,10 +397,115 @@ struct Arenar {
 		a->ResetToRandomKickoff(seed);
 	}
 
-	void Step(uint32_t ticks = 1) {
-		a->Step(ticks);
+	void Step(uint32_t ticks = 1);
+	void TimedStep();
+
+	void SetPaused(bool isPaused) {
+		paused = isPaused;
+	}
+
+	bool IsPaused() const {
+		return paused;
+	}
+	void DispatchCarContacts();
+	void DispatchGoalZones();
+	void DispatchForces();
+	void DispatchTriggerVolumes();
+
+	// custom impulses, in Bullet mass units times UU/s
+	void ApplyBallImpulse(Vec impulse);
+	bool ApplyCarImpulse(uint32_t car_id, Vec impulse, Vec point);
+
+	void SetStepTimingsEnabled(bool enabled);
+
+	bool StepTimingsEnabled() const {
+		return timeSteps;
+	}
+
+	EStepTimings GetStepTimings() const {
+		return stepTimings;
+	}
+
+	void ResetStepTimings() {
+		stepTimings = {};
+	}
+
+	// history stuff
+
+	void RecordHistory();
+
+	void SetHistoryCapacity(size_t capacity) {
+		historyCapacity = capacity;
+		ClearHistory();
+	}
+
+	size_t HistoryCapacity() const {
+		return historyCapacity;
 	}
 
+	void ClearHistory() {
+		history.clear();
+		historyStart = 0;
+	}
+
+	size_t HistoryLen() const {
+		return history.size();
+	}
+
+	// index 0 is the oldest entry
+	const HistoryEntry& HistoryAt(size_t index) const {
+		assert(index < history.size());
+		return history[(historyStart + index) % history.size()];
+	}
+
+	uint64_t HistoryTick(size_t index) const {
+		return HistoryAt(index).tick;
+	}
+
+	BallState HistoryBall(size_t index) const {
+		return HistoryAt(index).ball;
+	}
+
+	bool HistoryHasCar(size_t index, uint32_t car_id) const;
+	CarState HistoryCar(size_t index, uint32_t car_id) const;
+	rust::Vec<uint32_t> HistoryCars(size_t index) const;
+
+	EBoostPadState HistoryPad(size_t index, size_t pad_index) const {
+		const HistoryEntry& entry = HistoryAt(index);
+		assert(pad_index < entry.pads.size());
+		return entry.pads[pad_index];
+	}
+
+	void RestoreHistory(size_t index);
+	// appends an entry as if it had just been recorded, for restoring a saved history
+	void PushHistory(uint64_t tick, BallState ball, rust::Slice<const uint32_t> car_ids, rust::Slice<const CarState> cars, rust::Slice<const EBoostPadState> pads);
+
+	// extra geometry stuff
+
+	void AddStaticShape(std::shared_ptr<btCollisionShape> shape, const btTransform& transform, std::shared_ptr<btTriangleMesh> mesh = nullptr);
+	void AddStaticBox(Vec center, Vec half_extents, RotMat rot_mat);
+	void AddStaticSphere(Vec center, float radius);
+	void AddStaticPlane(Vec normal, float offset);
+	void AddStaticMesh(rust::Slice<const Vec> vertices, rust::Slice<const uint32_t> indices);
+
+	// props
+	void AddPropBody(uint32_t id, EPropConfig config, std::shared_ptr<btCollisionShape> shape, EPropState state);
+	uint32_t AddProp(EPropConfig config, EPropState state);
+	bool RemoveProp(uint32_t prop_id);
+	rust::Vec<uint32_t> GetProps() const;
+	bool HasProp(uint32_t prop_id) const;
+	EPropConfig GetPropConfig(uint32_t prop_id) const;
+	EPropState GetProp(uint32_t prop_id) const;
+	bool SetProp(uint32_t prop_id, EPropState state);
+	const Prop* FindProp(uint32_t prop_id) const;
+
+	// trigger volumes
+	uint32_t AddTriggerVolume(ETriggerVolume config);
+	bool RemoveTriggerVolume(uint32_t volume_id);
+	rust::Vec<uint32_t> GetTriggerVolumes() const;
+	bool HasTriggerVolume(uint32_t volume_id) const;
+	ETriggerVolume GetTriggerVolume(uint32_t volume_id) const;
+
 	bool IsBallProbablyGoingIn(float maxTime = 2.f, float extraMargin = 0) const {
 		return a->IsBallProbablyGoingIn(maxTime, extraMargin);
 	}
@@ -176,6 +521,39 @@ struct Arenar {
 	void SetMutatorConfig(MutatorConfig mutatorConfig) {
 		a->SetMutatorConfig(mutatorConfig);
 	}
+
+	btDiscreteDynamicsWorld* BulletWorld() {
+		return a->_bulletWorld;
+	}
+
+	size_t BulletNumCollisionObjects() const {
+		return a->_bulletWorld->getNumCollisionObjects();
+	}
 };
 
+// Adds the time spent in a Rust callback to the arena's step timings, if they're enabled
+struct CallbackTimer {
+	Arenar& arena;
+	std::chrono::steady_clock::time_point start;
+
+	CallbackTimer(Arenar& arena);
+	~CallbackTimer();
+};
+
+// Borrowed state views
+// RocketSim only copies the rigid body into the internal state when the full state is fetched,
+// so the kinematics are read from the rigid body directly
+const CarState& CarInternalState(const Car& car);
+Vec CarPos(const Car& car);
+RotMat CarRotMat(const Car& car);
+Vec CarVel(const Car& car);
+Vec CarAngVel(const Car& car);
+
+const BallState& BallInternalState(const Ball& ball);
+Vec BallPos(const Ball& ball);
+RotMat BallRotMat(const Ball& ball);
+Vec BallVel(const Ball& ball);
+Vec BallAngVel(const Ball& ball);
+
 std::unique_ptr<Arenar> CreateArena(GameMode game_mode, EArenaConfig arenaConfig, uint8_t tick_rate);
+std::unique_ptr<Arenar> CreateArenaWithBoostPads(GameMode game_mode, EArenaConfig arenaConfig, uint8_t tick_rate, rust::Slice<const BoostPadConfig> boost_pads);
@@ -30,6 +30,10 @@ fn main() {
         builder.define("RS_DONT_LOG", "1");
     }
 
+    if cfg!(feature = "double_precision") {
+        builder.define("BT_USE_DOUBLE_PRECISION", None);
+    }
+
     if !cfg!(debug_assertions) {
         builder.define("RS_MAX_SPEED", "1");
         builder.flag_if_supported("-flto=thin");
@@ -61,7 +61,7 @@ fn main() {
 
         // set all the controls
         // returns an error if any of the car_ids are invalid
-        arena.pin_mut().set_all_controls(&all_controls).unwrap();
+        arena.pin_mut().set_all_controls(all_controls).unwrap();
 
         // Advance the simulation by TICK_SKIP
         arena.pin_mut().step(TICK_SKIP);
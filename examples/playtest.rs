@@ -0,0 +1,28 @@
+use rocketsim_rs::{
+    input::{Controller, KeyboardController},
+    playtest,
+    sim::CarControls,
+    GameState,
+};
+
+// Start rlviser first, then run this and drive with WASD (Esc to quit)
+fn main() {
+    rocketsim_rs::init(None, true);
+
+    let human = KeyboardController::new().expect("Couldn't read the keyboard from the terminal");
+
+    // chases the ball, without turning or jumping
+    let bot: Box<dyn Controller> = Box::new(|car_id: u32, game_state: &GameState| {
+        let car = game_state.cars.iter().find(|car| car.id == car_id).unwrap();
+        let to_ball = game_state.ball.pos - car.state.pos;
+        let right = car.state.rot_mat.right;
+
+        CarControls {
+            throttle: 1.,
+            steer: (right.dot(to_ball) / 500.).clamp(-1., 1.),
+            ..Default::default()
+        }
+    });
+
+    playtest::play(human, [bot]).unwrap();
+}
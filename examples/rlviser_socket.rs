@@ -1,17 +1,19 @@
 use rocketsim_rs::{
-    bytes::{FromBytes, FromBytesExact, ToBytes},
+    bytes::ToBytes,
     cxx::UniquePtr,
     math::Vec3,
+    protocol::{SessionCommand, SessionControl, StateSetAuth, StateSetPolicy, UdpPacketTypes},
+    realtime::RealTimeStepper,
+    replay::ReplayWriter,
     sim::{Arena, ArenaConfig, BallState, CarConfig, CarControls, GameMode, Team},
     GameState,
 };
 use std::{
-    io,
+    fs::File,
+    io::{self, BufWriter},
     net::{IpAddr, SocketAddr, UdpSocket},
     str::FromStr,
     sync::mpsc::{channel, Receiver},
-    thread::sleep,
-    time::{Duration, Instant},
 };
 
 // Pass this into rlviser as the first argument
@@ -22,31 +24,6 @@ const RLVISER_PORT: u16 = 45243;
 // default: 34254
 const ROCKETSIM_PORT: u16 = 34254;
 
-#[repr(u8)]
-#[derive(Debug, Clone, Copy)]
-enum UdpPacketTypes {
-    Quit,
-    GameState,
-    Connection,
-    Paused,
-    Speed,
-    Render,
-}
-
-impl From<u8> for UdpPacketTypes {
-    fn from(val: u8) -> Self {
-        match val {
-            0 => Self::Quit,
-            1 => Self::GameState,
-            2 => Self::Connection,
-            3 => Self::Paused,
-            4 => Self::Speed,
-            5 => Self::Render,
-            _ => panic!("Invalid packet type"),
-        }
-    }
-}
-
 fn ctrl_channel() -> Result<Receiver<()>, ctrlc::Error> {
     let (sender, receiver) = channel();
 
@@ -72,7 +49,19 @@ fn main() -> io::Result<()> {
         _ => GameMode::Soccar,
     };
 
-    RLViserSocketHandler::new()?.run(arena_type)?;
+    // optionally restrict who can set the game state
+    // "deny" = viewers can't set the game state
+    // any other value = viewers must sign game states with this value as the key
+    let state_set_policy = match args.next().as_deref() {
+        None | Some("allow") => StateSetPolicy::Allow,
+        Some("deny") => StateSetPolicy::Deny,
+        Some(key) => StateSetPolicy::Authenticated(StateSetAuth::new(key)),
+    };
+
+    // optionally record every game state sent to rlviser to a replay file
+    let recorder = args.next().map(ReplayWriter::create).transpose()?;
+
+    RLViserSocketHandler::new(state_set_policy, recorder)?.run(arena_type)?;
 
     Ok(())
 }
@@ -82,11 +71,13 @@ struct RLViserSocketHandler {
     rlviser_addr: SocketAddr,
     min_game_state_buf: [u8; GameState::MIN_NUM_BYTES],
     game_state_buffer: Vec<u8>,
-    paused: bool,
+    session: SessionControl,
+    state_set_policy: StateSetPolicy,
+    recorder: Option<ReplayWriter<BufWriter<File>>>,
 }
 
 impl RLViserSocketHandler {
-    pub fn new() -> io::Result<Self> {
+    pub fn new(state_set_policy: StateSetPolicy, recorder: Option<ReplayWriter<BufWriter<File>>>) -> io::Result<Self> {
         let socket = UdpSocket::bind(("0.0.0.0", ROCKETSIM_PORT))?;
         // print the socket address
         println!("Listening on {}", socket.local_addr()?);
@@ -105,12 +96,21 @@ impl RLViserSocketHandler {
         // otherwise things like packet interpolation will be off
         socket.send_to(&[UdpPacketTypes::Connection as u8], rlviser_addr)?;
 
+        // let rlviser know if it isn't allowed to set the game state freely,
+        // which is what it assumes (and all older versions of rlviser understand)
+        if !matches!(state_set_policy, StateSetPolicy::Allow) {
+            socket.send_to(&[UdpPacketTypes::StateSetPermission as u8], rlviser_addr)?;
+            socket.send_to(&state_set_policy.permission_packet(), rlviser_addr)?;
+        }
+
         Ok(Self {
             socket,
             rlviser_addr,
             min_game_state_buf: [0; GameState::MIN_NUM_BYTES],
             game_state_buffer: Vec::new(),
-            paused: false,
+            session: SessionControl::new(),
+            state_set_policy,
+            recorder,
         })
     }
 
@@ -124,8 +124,7 @@ impl RLViserSocketHandler {
         // we only want to loop at 120hz
         // speed 0.5 = half speed
         // speed 2 = double speed
-        let mut interval = Duration::from_secs_f32(1. / 120.);
-        let mut next_time = Instant::now() + interval;
+        let mut stepper = RealTimeStepper::from_arena(&arena);
 
         // we loop forever - can be broken by pressing Ctrl+C in terminal
         loop {
@@ -133,39 +132,66 @@ impl RLViserSocketHandler {
                 self.socket.send_to(&[UdpPacketTypes::Quit as u8], self.rlviser_addr)?;
                 println!("Sent quit signal to rlviser");
 
+                if let Some(recorder) = &mut self.recorder {
+                    recorder.flush()?;
+                    println!("Recorded {} game states", recorder.num_frames());
+                }
+
                 // Then break the loop
                 break Ok(());
             }
 
-            self.handle_return_message(&mut arena, &mut interval)?;
+            self.handle_return_message(&mut arena)?;
+
+            // wait for the next tick, then advance the simulation
+            // this may step multiple ticks at once if we fell behind,
+            // or none at all if rlviser paused the game
+            if self.session.step(&mut stepper, arena.pin_mut()) == 0 {
+                continue;
+            }
+
+            // send the new game state back
+            let game_state = arena.pin_mut().get_game_state();
+            self.send_game_state(&game_state.to_bytes())?;
+        }
+    }
 
-            if !self.paused {
-                // advance the simulation by 1 tick
-                arena.pin_mut().step(1);
+    fn send_game_state(&mut self, game_state: &[u8]) -> io::Result<()> {
+        // Send the packet type
+        self.socket.send_to(&[UdpPacketTypes::GameState as u8], self.rlviser_addr)?;
+        // Then send the packet
+        self.socket.send_to(game_state, self.rlviser_addr)?;
 
-                // send the new game state back
-                let game_state = arena.pin_mut().get_game_state();
+        // and archive everything that's sent
+        if let Some(recorder) = &mut self.recorder {
+            recorder.write_bytes(game_state)?;
+        }
 
-                // Send the packet type
-                self.socket.send_to(&[UdpPacketTypes::GameState as u8], self.rlviser_addr)?;
-                // Then send the packet
-                self.socket.send_to(&game_state.to_bytes(), self.rlviser_addr)?;
-            }
+        Ok(())
+    }
 
-            // ensure we only calculate 120 steps per second
-            let wait_time = next_time - Instant::now();
-            if wait_time > Duration::default() {
-                sleep(wait_time);
+    fn set_game_state(&mut self, arena: &mut UniquePtr<Arena>, packet_type: UdpPacketTypes) {
+        let game_state = match self.state_set_policy.accept(packet_type, &self.game_state_buffer) {
+            Ok(game_state) => game_state,
+            Err(e) => {
+                println!("Rejected game state: {e}");
+                return;
             }
-            next_time += interval;
-        }
+        };
+
+        if let Err(e) = arena.pin_mut().set_game_state(&game_state) {
+            println!("Error setting game state: {e}");
+        };
     }
 
-    fn handle_return_message(&mut self, arena: &mut UniquePtr<Arena>, interval: &mut Duration) -> io::Result<()> {
+    fn handle_return_message(&mut self, arena: &mut UniquePtr<Arena>) -> io::Result<()> {
         let mut byte_buffer = [0];
 
         while let Ok((_, src)) = self.socket.recv_from(&mut byte_buffer) {
-            let packet_type = UdpPacketTypes::from(byte_buffer[0]);
+            let Ok(packet_type) = UdpPacketTypes::try_from(byte_buffer[0]) else {
+                println!("Ignoring packet of unknown type {}", byte_buffer[0]);
+                continue;
+            };
 
             match packet_type {
                 UdpPacketTypes::GameState => {
@@ -175,26 +201,30 @@ impl RLViserSocketHandler {
                     self.game_state_buffer.resize(num_bytes, 0);
                     self.socket.recv_from(&mut self.game_state_buffer)?;
 
-                    // set the game state
-                    let game_state = GameState::from_bytes(&self.game_state_buffer);
-                    if let Err(e) = arena.pin_mut().set_game_state(&game_state) {
-                        println!("Error setting game state: {e}");
-                    };
+                    self.set_game_state(arena, packet_type);
+                }
+                UdpPacketTypes::AuthGameState => {
+                    // the game state is after the nonce & signature
+                    let mut header_buf = [0; StateSetAuth::HEADER_NUM_BYTES + GameState::MIN_NUM_BYTES];
+                    self.socket.peek_from(&mut header_buf)?;
+
+                    let num_bytes = GameState::get_num_bytes(&header_buf[StateSetAuth::HEADER_NUM_BYTES..]);
+                    self.game_state_buffer.resize(StateSetAuth::HEADER_NUM_BYTES + num_bytes, 0);
+                    self.socket.recv_from(&mut self.game_state_buffer)?;
+
+                    self.set_game_state(arena, packet_type);
                 }
                 UdpPacketTypes::Connection => {
                     println!("Connection established to {src}");
                 }
-                UdpPacketTypes::Speed => {
-                    let mut speed_buffer = [0; f32::NUM_BYTES];
-                    self.socket.recv_from(&mut speed_buffer)?;
-                    let speed = f32::from_bytes(&speed_buffer);
-                    *interval = Duration::from_secs_f32(1. / (120. * speed));
-                }
-                UdpPacketTypes::Paused => {
-                    self.socket.recv_from(&mut byte_buffer)?;
-                    self.paused = byte_buffer[0] == 1;
+                UdpPacketTypes::Speed | UdpPacketTypes::Paused | UdpPacketTypes::Step | UdpPacketTypes::Seek => {
+                    // pause, resume, single-step, and speed changes from rlviser
+                    let mut payload = [0; 8];
+                    let num_bytes = SessionCommand::payload_num_bytes(packet_type).unwrap();
+                    self.socket.recv_from(&mut payload[..num_bytes])?;
+                    self.session.handle_packet(packet_type, &payload[..num_bytes]);
                 }
-                UdpPacketTypes::Quit | UdpPacketTypes::Render => {
+                UdpPacketTypes::Quit | UdpPacketTypes::Render | UdpPacketTypes::StateSetPermission => {
                     panic!("We shouldn't be receiving packets of type {packet_type:?}")
                 }
             }
@@ -230,23 +260,18 @@ fn setup_arena(arena_type: GameMode) -> UniquePtr<Arena> {
 
     arena
         .pin_mut()
-        .set_all_controls(
-            (1..=6u32)
-                .map(|i| {
-                    (
-                        i,
-                        CarControls {
-                            steer: 0.2,
-                            throttle: 1.,
-                            pitch: -0.1,
-                            boost: true,
-                            ..Default::default()
-                        },
-                    )
-                })
-                .collect::<Vec<_>>()
-                .as_slice(),
-        )
+        .set_all_controls((1..=6u32).map(|i| {
+            (
+                i,
+                CarControls {
+                    steer: 0.2,
+                    throttle: 1.,
+                    pitch: -0.1,
+                    boost: true,
+                    ..Default::default()
+                },
+            )
+        }))
         .unwrap();
 
     arena
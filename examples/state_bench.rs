@@ -0,0 +1,37 @@
+use rocketsim_rs::{
+    sim::{Arena, CarConfig, Team},
+    GameState,
+};
+use std::{hint::black_box, time::Instant};
+
+fn main() {
+    const ITERATIONS: u32 = 200_000;
+
+    // load in assets
+    rocketsim_rs::init(None, true);
+
+    let mut arena = Arena::default_standard();
+
+    for team in [Team::Blue, Team::Orange] {
+        for _ in 0..3 {
+            let _ = arena.pin_mut().add_car(team, CarConfig::octane());
+        }
+    }
+
+    println!("GameState is {} bytes", size_of::<GameState>());
+
+    let start_time = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(arena.pin_mut().get_game_state());
+    }
+    let elapsed = start_time.elapsed().as_secs_f32();
+    println!("get_game_state: {:.3}us", elapsed / ITERATIONS as f32 * 1e6);
+
+    let state = arena.pin_mut().get_game_state();
+    let start_time = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(state.clone());
+    }
+    let elapsed = start_time.elapsed().as_secs_f32();
+    println!("clone: {:.3}us", elapsed / ITERATIONS as f32 * 1e6);
+}
@@ -258,11 +258,11 @@ for namespace, types in consts.items():
 
                             parts = vals[i].split()
 
-                            for i in range(len(parts)):
-                                if parts[i] == "PI_4":
-                                    parts[i] = "FRAC_PI_4"
+                            for j in range(len(parts)):
+                                if parts[j] == "PI_4":
+                                    parts[j] = "FRAC_PI_4"
                                 else:
-                                    parts[i] = ensure_rust_float(parts[i])
+                                    parts[j] = ensure_rust_float(parts[j])
 
                             vals[i] = " ".join(parts)
                     conv_func = to_car_spawn_pos_str
@@ -0,0 +1,122 @@
+use crate::{
+    consts,
+    math::{RotMat, Vec3},
+    prediction::BallPrediction,
+    sim::CarState,
+};
+
+/// Maximum angular speed a car can rotate at in the air (radians/s)
+const AERIAL_TURN_RATE: f32 = consts::CAR_MAX_ANG_SPEED;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Intercept {
+    /// Seconds from now until the car reaches the ball
+    pub time: f32,
+    /// Index of the ball prediction slice that was intercepted
+    pub slice_index: usize,
+    /// Where the ball will be at the moment of the intercept
+    pub target: Vec3,
+    /// Amount of boost (out of 100) that will be spent getting there
+    pub boost_needed: f32,
+    /// Orientation the car should boost in, its forward vector is the required acceleration direction
+    pub orientation: RotMat,
+}
+
+/// Builds a rotation matrix facing `forward`, keeping `up` as close as possible to the given up vector
+fn facing(forward: Vec3, up: Vec3) -> RotMat {
+    let right = up.cross(forward).normalize_or_zero();
+    let right = if right == Vec3::ZERO {
+        Vec3::Z.cross(forward).normalize_or_zero()
+    } else {
+        right
+    };
+
+    RotMat::new(forward, right, forward.cross(right))
+}
+
+#[must_use]
+/// Finds the earliest ball slice that the car can reach by boosting in a straight line through the air
+///
+/// For every slice, this solves for the constant acceleration that would take the car from its current
+/// position and velocity (under gravity) to the ball, after first spending time turning towards it.
+/// If the car is on the ground, a full first jump is assumed to be performed at the start.
+///
+/// The intercept is feasible if the acceleration can be produced by boosting some fraction of the time
+/// and the boost that would be used is less than what the car has.
+pub fn aerial_intercept(car_state: &CarState, ball_prediction: &BallPrediction) -> Option<Intercept> {
+    let start_vel = aerial_start_vel(car_state);
+
+    ball_prediction.slices.iter().enumerate().find_map(|(slice_index, slice)| {
+        let time = slice.time;
+        let target = slice.state.pos;
+
+        let (direction, boost_time, distance) = aerial_plan(car_state, start_vel, time, target);
+        if boost_time <= 0. {
+            return None;
+        }
+
+        // constant acceleration over the boosting window that covers the remaining distance
+        let required_accel = 2. * distance / (boost_time * boost_time);
+        let boost_fraction = required_accel / consts::BOOST_ACCEL_AIR;
+        if boost_fraction > 1. {
+            return None;
+        }
+
+        let boost_needed = boost_fraction * boost_time * consts::BOOST_USED_PER_SECOND;
+        if boost_needed > car_state.boost {
+            return None;
+        }
+
+        Some(Intercept {
+            time,
+            slice_index,
+            target,
+            boost_needed,
+            orientation: facing(direction, car_state.rot_mat.up),
+        })
+    })
+}
+
+/// The velocity of the car at the start of the aerial, including a full first jump if it's on the ground
+fn aerial_start_vel(car_state: &CarState) -> Vec3 {
+    let jump_vel = if car_state.is_on_ground {
+        car_state.rot_mat.up * (consts::JUMP_IMMEDIATE_FORCE + consts::JUMP_ACCEL * consts::JUMP_MAX_TIME)
+    } else {
+        Vec3::ZERO
+    };
+
+    car_state.vel + jump_vel
+}
+
+/// Returns the direction to boost in, how long the car can boost for after turning towards it,
+/// and the distance that boosting has to cover to reach the target in `time` seconds
+fn aerial_plan(car_state: &CarState, start_vel: Vec3, time: f32, target: Vec3) -> (Vec3, f32, f32) {
+    let gravity = Vec3::new(0., 0., consts::GRAVITY_Z);
+
+    // where the car ends up if it doesn't boost at all
+    let drift_pos = car_state.pos + start_vel * time + gravity * (0.5 * time * time);
+    let delta = target - drift_pos;
+    let direction = delta.normalize_or_zero();
+
+    let turn_angle = car_state.rot_mat.forward.dot(direction).clamp(-1., 1.).acos();
+    (direction, time - turn_angle / AERIAL_TURN_RATE, delta.length())
+}
+
+/// The seconds to spare when aerialing to the target so that the car arrives in exactly `time` seconds,
+/// using the same model as `aerial_intercept`
+///
+/// The margin is how much shorter the boosting window could be while still covering the distance with full boost,
+/// and is negative if the target can't be reached in time. Returns `None` if the car doesn't have enough boost.
+pub(super) fn aerial_margin(car_state: &CarState, time: f32, target: Vec3) -> Option<f32> {
+    let (_, boost_time, distance) = aerial_plan(car_state, aerial_start_vel(car_state), time, target);
+    let min_boost_time = (2. * distance / consts::BOOST_ACCEL_AIR).sqrt();
+
+    if boost_time > 0. {
+        let boost_needed = 2. * distance * consts::BOOST_USED_PER_SECOND / (consts::BOOST_ACCEL_AIR * boost_time);
+        if boost_needed > car_state.boost {
+            return None;
+        }
+    }
+
+    Some(boost_time - min_boost_time)
+}
@@ -0,0 +1,126 @@
+use crate::{
+    consts,
+    math::Vec3,
+    sim::{BallState, GameMode, MutatorConfig},
+};
+
+/// Normal speeds (uu/s) below this are considered rolling instead of bouncing
+const ROLL_MAX_NORMAL_SPEED: f32 = 5.;
+
+#[derive(Clone, Copy, Debug)]
+/// The parameters used by `propagate_ball`
+pub struct BallPhysics {
+    pub gravity: Vec3,
+    pub drag: f32,
+    pub restitution: f32,
+    pub friction: f32,
+    pub radius: f32,
+    pub max_speed: f32,
+    /// Distance from the center of the field to the side walls (x) and back walls (y), and the ceiling height (z)
+    pub extents: Vec3,
+    /// The time step to integrate with, smaller is more accurate but slower
+    pub tick_time: f32,
+}
+
+impl Default for BallPhysics {
+    #[inline]
+    fn default() -> Self {
+        Self::new(GameMode::Soccar, &MutatorConfig::default(GameMode::Soccar))
+    }
+}
+
+impl BallPhysics {
+    #[must_use]
+    pub fn new(game_mode: GameMode, mutators: &MutatorConfig) -> Self {
+        let extents = if game_mode == GameMode::Hoops {
+            Vec3::new(
+                consts::ARENA_EXTENT_X_HOOPS,
+                consts::ARENA_EXTENT_Y_HOOPS,
+                consts::ARENA_HEIGHT_HOOPS,
+            )
+        } else {
+            Vec3::new(consts::ARENA_EXTENT_X, consts::ARENA_EXTENT_Y, consts::ARENA_HEIGHT)
+        };
+
+        Self {
+            gravity: mutators.gravity,
+            drag: mutators.ball_drag,
+            restitution: mutators.ball_world_restitution,
+            friction: mutators.ball_world_friction,
+            radius: mutators.ball_radius,
+            max_speed: mutators.ball_max_speed,
+            extents,
+            tick_time: 1. / 120.,
+        }
+    }
+}
+
+/// Bounces the ball off of a plane with an inward-facing `normal` that is `offset` away from the origin
+fn bounce(pos: &mut Vec3, vel: &mut Vec3, normal: Vec3, offset: f32, physics: &BallPhysics) {
+    let dist = pos.dot(normal) - offset - physics.radius;
+    let normal_speed = vel.dot(normal);
+
+    if dist >= 0. || normal_speed >= 0. {
+        return;
+    }
+
+    *pos -= normal * dist;
+
+    let tangent_vel = *vel - normal * normal_speed;
+    if -normal_speed < ROLL_MAX_NORMAL_SPEED {
+        *vel = tangent_vel;
+        return;
+    }
+
+    // coulomb friction on the tangent velocity, bounded by the normal impulse
+    let tangent_speed = tangent_vel.length();
+    let friction_loss = (physics.friction * (1. + physics.restitution) * -normal_speed).min(tangent_speed);
+    let tangent_scale = if tangent_speed > 0. {
+        1. - friction_loss / tangent_speed
+    } else {
+        0.
+    };
+
+    *vel = tangent_vel * tangent_scale - normal * (normal_speed * physics.restitution);
+}
+
+#[must_use]
+/// Propagates the ball forward by `time` seconds without needing an arena
+///
+/// The arena is approximated by an axis-aligned box (no goals, corners or ramps)
+/// and car touches are ignored, so this is only exact while the ball is in the air.
+/// It is however many orders of magnitude faster than cloning and stepping an arena.
+pub fn propagate_ball(ball: &BallState, time: f32, physics: &BallPhysics) -> BallState {
+    let planes = [
+        (Vec3::Z, 0.),
+        (-Vec3::Z, -physics.extents.z),
+        (Vec3::X, -physics.extents.x),
+        (-Vec3::X, -physics.extents.x),
+        (Vec3::Y, -physics.extents.y),
+        (-Vec3::Y, -physics.extents.y),
+    ];
+
+    let mut pos = ball.pos;
+    let mut vel = ball.vel;
+
+    let num_steps = (time / physics.tick_time).round() as u32;
+    let damping = (1. - physics.drag).powf(physics.tick_time);
+
+    for _ in 0..num_steps {
+        // same ordering as Bullet: apply forces, damp, then integrate position
+        vel = (vel + physics.gravity * physics.tick_time) * damping;
+
+        let speed = vel.length();
+        if speed > physics.max_speed {
+            vel *= physics.max_speed / speed;
+        }
+
+        pos += vel * physics.tick_time;
+
+        for (normal, offset) in planes {
+            bounce(&mut pos, &mut vel, normal, offset, physics);
+        }
+    }
+
+    BallState { pos, vel, ..*ball }
+}
@@ -0,0 +1,170 @@
+use super::drive::drive_time;
+use crate::{
+    consts,
+    math::{RotMat, Vec3},
+    sim::CarState,
+    BoostPad,
+};
+
+/// The most extra time (in seconds) that picking up pads is allowed to add on top of driving straight to the destination
+pub const BOOST_PATH_MAX_DETOUR: f32 = 0.5;
+/// The most pads that `best_boost_path` will pick up on the way to the destination
+pub const BOOST_PATH_MAX_PICKUPS: usize = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoostStop {
+    /// The index of the pad in the given pads
+    pub pad_index: usize,
+    pub pos: Vec3,
+    /// Seconds from now until the car reaches the pad
+    pub time: f32,
+    /// The estimated boost of the car right after picking up the pad
+    pub boost: f32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+/// A route to a destination, through zero or more boost pads
+pub struct BoostPath {
+    pub stops: Vec<BoostStop>,
+    /// Seconds from now until the car reaches the destination
+    pub time: f32,
+    /// The estimated boost of the car when it reaches the destination
+    pub boost: f32,
+}
+
+/// Where the car is, and how fast and in what direction it's moving, at some point along a route
+#[derive(Clone, Copy)]
+struct Waypoint {
+    car_state: CarState,
+    time: f32,
+}
+
+impl Waypoint {
+    /// Drives from this waypoint to the target, assuming the car arrives at its average speed
+    fn drive_to(&self, target: Vec3) -> Self {
+        let duration = drive_time(&self.car_state, target, self.car_state.boost);
+        let delta = Vec3::new(target.x - self.car_state.pos.x, target.y - self.car_state.pos.y, 0.);
+        let distance = delta.length();
+        let speed = if duration > 0. {
+            (distance / duration).min(consts::CAR_MAX_SPEED)
+        } else {
+            0.
+        };
+
+        let rot_mat = if distance > 0. {
+            RotMat::look_at(delta, Vec3::Z)
+        } else {
+            self.car_state.rot_mat
+        };
+
+        // the drive time model boosts the whole way, until the car hits max speed
+        let boost_used = (consts::BOOST_USED_PER_SECOND * duration).min(self.car_state.boost);
+
+        Self {
+            car_state: CarState {
+                pos: Vec3::new(target.x, target.y, self.car_state.pos.z),
+                vel: rot_mat.forward * speed,
+                rot_mat,
+                boost: self.car_state.boost - boost_used,
+                ..self.car_state
+            },
+            time: self.time + duration,
+        }
+    }
+}
+
+fn pad_amount(pad: &BoostPad) -> f32 {
+    if pad.config.is_big {
+        consts::boostpads::BOOST_AMOUNT_BIG
+    } else {
+        consts::boostpads::BOOST_AMOUNT_SMALL
+    }
+}
+
+/// Finds the best routes from `from` through every combination of up to `pickups_left` more pads
+fn search(
+    from: Waypoint,
+    stops: &mut Vec<BoostStop>,
+    pads: &[BoostPad],
+    destination: Vec3,
+    max_time: f32,
+    pickups_left: usize,
+    best: &mut BoostPath,
+) {
+    if !stops.is_empty() {
+        let end = from.drive_to(destination);
+        let is_better = end.car_state.boost > best.boost || (end.car_state.boost == best.boost && end.time < best.time);
+
+        if end.time <= max_time && is_better {
+            *best = BoostPath {
+                stops: stops.clone(),
+                time: end.time,
+                boost: end.car_state.boost,
+            };
+        }
+    }
+
+    if pickups_left == 0 {
+        return;
+    }
+
+    for (pad_index, pad) in pads.iter().enumerate() {
+        if stops.iter().any(|stop| stop.pad_index == pad_index) {
+            continue;
+        }
+
+        let mut at_pad = from.drive_to(pad.config.position);
+        // the pad has to be active by the time the car gets there
+        if at_pad.time > max_time || (!pad.state.is_active && pad.state.cooldown > at_pad.time) {
+            continue;
+        }
+
+        at_pad.car_state.boost = (at_pad.car_state.boost + pad_amount(pad)).min(consts::BOOST_MAX);
+        stops.push(BoostStop {
+            pad_index,
+            pos: pad.config.position,
+            time: at_pad.time,
+            boost: at_pad.car_state.boost,
+        });
+
+        search(at_pad, stops, pads, destination, max_time, pickups_left - 1, best);
+        stops.pop();
+    }
+}
+
+#[must_use]
+/// Suggests which boost pads a car on the ground should pick up on its way to `destination`
+///
+/// Every route through up to `BOOST_PATH_MAX_PICKUPS` pads is timed with `drive_time`,
+/// skipping pads that won't have respawned by the time the car gets to them.
+/// Of the routes that take at most `BOOST_PATH_MAX_DETOUR` seconds longer than driving straight there,
+/// the one that arrives with the most boost is returned (the fastest one if they're tied).
+/// The returned path has no stops if no detour is worth it.
+///
+/// Boost usage is estimated pessimistically, assuming the car boosts whenever it's below max speed.
+pub fn best_boost_path(car_state: &CarState, pads: &[BoostPad], destination: Vec3) -> BoostPath {
+    let start = Waypoint {
+        car_state: *car_state,
+        time: 0.,
+    };
+
+    let direct = start.drive_to(destination);
+    let mut best = BoostPath {
+        stops: Vec::new(),
+        time: direct.time,
+        boost: direct.car_state.boost,
+    };
+
+    let max_time = direct.time + BOOST_PATH_MAX_DETOUR;
+    search(
+        start,
+        &mut Vec::with_capacity(BOOST_PATH_MAX_PICKUPS),
+        pads,
+        destination,
+        max_time,
+        BOOST_PATH_MAX_PICKUPS,
+        &mut best,
+    );
+
+    best
+}
@@ -0,0 +1,99 @@
+use crate::{
+    consts,
+    math::Vec3,
+    sim::{CarConfig, CarState},
+};
+use std::f32::consts::{PI, TAU};
+
+/// Acceleration (uu/s^2) from full throttle at zero speed, scaled by `DRIVE_SPEED_TORQUE_FACTOR_CURVE`
+pub const THROTTLE_ACCEL: f32 = 1600.;
+
+const DRIVE_TIME_STEP: f32 = 1. / 120.;
+/// Give up after this many simulated seconds, the target is effectively unreachable
+const DRIVE_TIME_MAX: f32 = 10.;
+
+#[inline]
+#[must_use]
+/// Returns the acceleration from driving (and optionally boosting) at the given forward speed
+pub fn ground_accel(speed: f32, boosting: bool) -> f32 {
+    let throttle = THROTTLE_ACCEL * consts::DRIVE_SPEED_TORQUE_FACTOR_CURVE.get_output(speed, None);
+    if boosting {
+        throttle + consts::BOOST_ACCEL_GROUND
+    } else {
+        throttle
+    }
+}
+
+#[must_use]
+/// Returns the minimum turning radius of an octane-like car at the given speed
+pub fn turn_radius(speed: f32) -> f32 {
+    let config = CarConfig::octane();
+    let wheelbase = config.front_wheels.connection_point_offset.x - config.back_wheels.connection_point_offset.x;
+    let steer_angle = consts::STEER_ANGLE_FROM_SPEED_CURVE.get_output(speed.abs(), None);
+
+    wheelbase / steer_angle.tan()
+}
+
+/// Simulates 1D acceleration along a path of the given length, returning the time it takes
+fn straight_line_time(length: f32, start_speed: f32, mut boost: f32) -> f32 {
+    let mut speed = start_speed.max(0.);
+    let mut dist = 0.;
+    let mut time = 0.;
+
+    while dist < length && time < DRIVE_TIME_MAX {
+        let boosting = boost > 0. && speed < consts::CAR_MAX_SPEED;
+        if boosting {
+            boost -= consts::BOOST_USED_PER_SECOND * DRIVE_TIME_STEP;
+        }
+
+        speed = (speed + ground_accel(speed, boosting) * DRIVE_TIME_STEP).min(consts::CAR_MAX_SPEED);
+        dist += speed * DRIVE_TIME_STEP;
+        time += DRIVE_TIME_STEP;
+    }
+
+    // remove the overshoot of the last step
+    if dist > length && speed > 0. {
+        time -= (dist - length) / speed;
+    }
+
+    time
+}
+
+#[must_use]
+/// Estimates the time (in seconds) it takes a car on the ground to drive to the given target
+///
+/// The path is modeled as a turn at the car's current speed (using the RL steering curve)
+/// followed by a straight line, during which the car accelerates with full throttle
+/// and boosts until `boost_available` runs out.
+pub fn drive_time(car_state: &CarState, target: Vec3, boost_available: f32) -> f32 {
+    let forward = Vec3::new(car_state.rot_mat.forward.x, car_state.rot_mat.forward.y, 0.).normalize_or_zero();
+    let speed = car_state.vel.dot(forward);
+
+    let to_target = Vec3::new(target.x - car_state.pos.x, target.y - car_state.pos.y, 0.);
+    let left = Vec3::Z.cross(forward);
+    // 1 if the target is to the left of the car, -1 if it's to the right
+    let side = if to_target.dot(left) >= 0. { 1. } else { -1. };
+
+    let mut radius = turn_radius(speed);
+    let center = left * (side * radius);
+    let mut center_to_target = to_target - center;
+    let mut center_dist = center_to_target.length();
+
+    if center_dist < radius {
+        // the target is inside of the turning circle, so turn as tightly as possible instead
+        radius = turn_radius(0.);
+        center_to_target = to_target - left * (side * radius);
+        center_dist = center_to_target.length().max(radius);
+    }
+
+    // angle swept around the turning circle until the car points at the target
+    let start_angle = (-side * left.y).atan2(-side * left.x);
+    let target_angle = center_to_target.y.atan2(center_to_target.x) - side * (radius / center_dist).acos();
+    let arc_angle = ((target_angle - start_angle) * side).rem_euclid(TAU);
+    let arc_angle = if arc_angle > PI * 1.9 { 0. } else { arc_angle };
+
+    let straight_dist = (center_dist * center_dist - radius * radius).max(0.).sqrt();
+    let path_length = arc_angle * radius + straight_dist;
+
+    straight_line_time(path_length, speed, boost_available)
+}
@@ -0,0 +1,125 @@
+use super::{aerial::aerial_margin, drive::drive_time};
+use crate::{consts, math::Vec3, prediction::BallPrediction, sim::CarState};
+
+/// The highest the ball can be (uu) for a car on the ground to hit it without jumping
+pub const DRIVE_MAX_BALL_Z: f32 = 150.;
+
+const JUMP_TIME_STEP: f32 = 1. / 120.;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReachMethod {
+    /// Driving along the ground
+    Drive,
+    /// Driving along the ground, then a single held jump
+    Jump,
+    /// Boosting in a straight line through the air, see `aerial_intercept`
+    Aerial,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SliceReach {
+    /// Index of the ball prediction slice
+    pub slice_index: usize,
+    /// Seconds from now until the ball is at the slice
+    pub time: f32,
+    /// Seconds that the car would have to spare when getting to the ball, negative if it would be too late
+    ///
+    /// This is `f32::NEG_INFINITY` if none of the methods can reach the slice at all
+    pub margin: f32,
+    /// The method with the largest margin, `None` if none of them can reach the slice at all
+    pub method: Option<ReachMethod>,
+}
+
+impl SliceReach {
+    #[inline]
+    #[must_use]
+    pub fn is_reachable(&self) -> bool {
+        self.margin >= 0.
+    }
+}
+
+#[must_use]
+/// Returns the time it takes a single held jump to rise by `height`, if it can get that high at all
+pub fn jump_time(height: f32) -> Option<f32> {
+    let mut z = 0.;
+    let mut vel = consts::JUMP_IMMEDIATE_FORCE;
+    let mut time = 0.;
+
+    while z < height {
+        let accel = if time < consts::JUMP_MAX_TIME {
+            consts::JUMP_ACCEL + consts::GRAVITY_Z
+        } else {
+            consts::GRAVITY_Z
+        };
+
+        vel += accel * JUMP_TIME_STEP;
+        if vel <= 0. {
+            return None;
+        }
+
+        z += vel * JUMP_TIME_STEP;
+        time += JUMP_TIME_STEP;
+    }
+
+    Some(time)
+}
+
+fn slice_reach(car_state: &CarState, slice_index: usize, time: f32, target: Vec3) -> SliceReach {
+    let mut best = SliceReach {
+        slice_index,
+        time,
+        margin: f32::NEG_INFINITY,
+        method: None,
+    };
+
+    let mut consider = |margin: f32, method| {
+        if margin > best.margin {
+            best.margin = margin;
+            best.method = Some(method);
+        }
+    };
+
+    if car_state.is_on_ground {
+        let arrival = drive_time(car_state, target, car_state.boost);
+        let height = target.z - DRIVE_MAX_BALL_Z;
+
+        if height <= 0. {
+            consider(time - arrival, ReachMethod::Drive);
+        } else if let Some(jump) = jump_time(height) {
+            // the jump can start while the car is still driving to the ball
+            consider(time - arrival.max(jump), ReachMethod::Jump);
+        }
+    }
+
+    if let Some(margin) = aerial_margin(car_state, time, target) {
+        consider(margin, ReachMethod::Aerial);
+    }
+
+    best
+}
+
+#[must_use]
+/// Returns how much time the car would have to spare at every slice of the prediction, using the best method
+///
+/// Driving and jumping are timed with `drive_time`, and aerials use the same model as `aerial_intercept`.
+/// All of the models are optimistic about the car's path and ignore the car's hitbox,
+/// so a small positive margin doesn't guarantee the car will actually get there.
+pub fn slice_margins(car_state: &CarState, ball_prediction: &BallPrediction) -> Vec<SliceReach> {
+    ball_prediction
+        .slices
+        .iter()
+        .enumerate()
+        .map(|(slice_index, slice)| slice_reach(car_state, slice_index, slice.time, slice.state.pos))
+        .collect()
+}
+
+#[must_use]
+/// Returns the earliest slice of the prediction that the car can reach in time, see `slice_margins`
+pub fn first_reachable_slice(car_state: &CarState, ball_prediction: &BallPrediction) -> Option<SliceReach> {
+    ball_prediction
+        .slices
+        .iter()
+        .enumerate()
+        .map(|(slice_index, slice)| slice_reach(car_state, slice_index, slice.time, slice.state.pos))
+        .find(SliceReach::is_reachable)
+}
@@ -0,0 +1,93 @@
+use crate::{
+    consts,
+    field::Field,
+    math::Vec3,
+    sim::{Arena, CarControls, CarState},
+};
+
+/// How far ahead (in seconds) `predict_landing` looks for a landing
+pub const LANDING_MAX_TIME: f32 = 5.;
+const LANDING_TIME_STEP: f32 = 1. / 120.;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Landing {
+    /// Seconds from now until the car lands
+    pub time: f32,
+    /// Where the car's center will be when it lands
+    pub pos: Vec3,
+    /// The normal of the surface the car lands on, facing into the field
+    pub surface_normal: Vec3,
+}
+
+#[must_use]
+/// Predicts where and when an airborne car will land on the soccar field, see `predict_landing_in`
+pub fn predict_landing(car_state: &CarState) -> Option<Landing> {
+    predict_landing_in(car_state, &Field::SOCCAR)
+}
+
+#[must_use]
+/// Predicts where and when an airborne car will land, if it does within `LANDING_MAX_TIME`
+///
+/// The car's center follows a ballistic path under gravity (and the car's max speed) until it gets within
+/// resting height of a surface of the field. The car's inputs, its rotation and the curved ramps
+/// between surfaces are ignored, use `predict_landing_sim` to check the prediction against the full simulation.
+pub fn predict_landing_in(car_state: &CarState, field: &Field) -> Option<Landing> {
+    let gravity = Vec3::new(0., 0., consts::GRAVITY_Z);
+    let mut pos = car_state.pos;
+    let mut vel = car_state.vel;
+    let mut time = 0.;
+
+    while time < LANDING_MAX_TIME {
+        vel += gravity * LANDING_TIME_STEP;
+        let speed = vel.length();
+        if speed > consts::CAR_MAX_SPEED {
+            vel *= consts::CAR_MAX_SPEED / speed;
+        }
+
+        pos += vel * LANDING_TIME_STEP;
+        time += LANDING_TIME_STEP;
+
+        let surface = field.nearest_surface_point(pos);
+        if surface.distance <= consts::CAR_SPAWN_REST_Z {
+            return Some(Landing {
+                time,
+                pos,
+                surface_normal: surface.normal,
+            });
+        }
+    }
+
+    None
+}
+
+#[must_use]
+/// Finds where and when the car will land by cloning the arena and stepping it with no inputs on the car
+///
+/// This is exact (other than the inputs of the other cars staying the same), but a lot slower than `predict_landing`.
+/// The landing is the first tick where the car's wheels or body touch the world,
+/// and the surface normal is the car's up direction if it landed on its wheels.
+/// Returns `None` if the car doesn't exist or doesn't land within `max_time` seconds.
+pub fn predict_landing_sim(arena: &Arena, car_id: u32, max_time: f32) -> Option<Landing> {
+    let mut arena = arena.clone(false);
+    arena.pin_mut().set_car_controls(car_id, CarControls::default()).ok()?;
+
+    let num_ticks = (max_time * arena.get_tick_rate()).ceil() as u32;
+    for i in 1..=num_ticks {
+        arena.pin_mut().step(1);
+
+        let car_state = arena.pin_mut().get_car(car_id);
+        if car_state.is_on_ground || car_state.world_contact.has_contact {
+            return Some(Landing {
+                time: i as f32 / arena.get_tick_rate(),
+                pos: car_state.pos,
+                surface_normal: if car_state.is_on_ground {
+                    car_state.rot_mat.up
+                } else {
+                    car_state.world_contact.contact_normal
+                },
+            });
+        }
+    }
+
+    None
+}
@@ -0,0 +1,15 @@
+mod aerial;
+mod ball;
+mod boost;
+mod drive;
+mod intercept;
+mod landing;
+mod shot;
+
+pub use aerial::{aerial_intercept, Intercept};
+pub use ball::{propagate_ball, BallPhysics};
+pub use boost::{best_boost_path, BoostPath, BoostStop, BOOST_PATH_MAX_DETOUR, BOOST_PATH_MAX_PICKUPS};
+pub use drive::{drive_time, ground_accel, turn_radius, THROTTLE_ACCEL};
+pub use intercept::{first_reachable_slice, jump_time, slice_margins, ReachMethod, SliceReach, DRIVE_MAX_BALL_Z};
+pub use landing::{predict_landing, predict_landing_in, predict_landing_sim, Landing, LANDING_MAX_TIME};
+pub use shot::{expected_goal, is_shot_on_target, predict_goal_crossing, GoalCrossing, SHOT_MAX_TIME};
@@ -0,0 +1,128 @@
+use super::ball::{propagate_ball, BallPhysics};
+use crate::{
+    consts,
+    field::soccar,
+    math::Vec3,
+    sim::{BallState, Team},
+};
+use std::f32::consts::PI;
+
+/// How far ahead (in seconds) `is_shot_on_target` looks for the ball crossing a goal line
+pub const SHOT_MAX_TIME: f32 = 4.;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// The moment that the ball fully crosses a goal line
+pub struct GoalCrossing {
+    /// The team that owns the net, the other team would be awarded the goal
+    pub net_team: Team,
+    /// Seconds from the given ball state until the crossing
+    pub time: f32,
+    pub pos: Vec3,
+    pub vel: Vec3,
+}
+
+impl GoalCrossing {
+    #[inline]
+    #[must_use]
+    pub const fn scoring_team(&self) -> Team {
+        match self.net_team {
+            Team::Blue => Team::Orange,
+            Team::Orange => Team::Blue,
+        }
+    }
+}
+
+#[must_use]
+/// Predicts whether the ball will go into either soccar net within `SHOT_MAX_TIME`, without needing an arena
+///
+/// See `predict_goal_crossing`
+pub fn is_shot_on_target(ball: &BallState) -> Option<GoalCrossing> {
+    predict_goal_crossing(ball, SHOT_MAX_TIME, &BallPhysics::default())
+}
+
+#[must_use]
+/// Predicts the first time within `max_time` seconds that the ball fully crosses a goal line
+///
+/// The ball is propagated with `propagate_ball`, so bounces off of the floor, walls and ceiling are taken into account.
+/// When the ball reaches the back wall it's checked against the goal mouth,
+/// and if it misses the net it's treated as a miss, even if it could bounce in later.
+pub fn predict_goal_crossing(ball: &BallState, max_time: f32, physics: &BallPhysics) -> Option<GoalCrossing> {
+    // move the back walls out of the way, the goal mouth is checked manually instead
+    let physics = BallPhysics {
+        extents: Vec3::new(physics.extents.x, f32::INFINITY, physics.extents.z),
+        ..*physics
+    };
+    let back_wall = consts::ARENA_EXTENT_Y - physics.radius;
+    let goal_line = consts::SOCCAR_GOAL_SCORE_BASE_THRESHOLD_Y + physics.radius;
+
+    let mut state = *ball;
+    let mut time = 0.;
+    let mut in_mouth = state.pos.y.abs() > back_wall;
+
+    while time < max_time {
+        state = propagate_ball(&state, physics.tick_time, &physics);
+        time += physics.tick_time;
+
+        if !in_mouth && state.pos.y.abs() > back_wall {
+            if state.pos.x.abs() > soccar::GOAL_HALF_WIDTH - physics.radius
+                || state.pos.z > soccar::GOAL_HEIGHT - physics.radius
+            {
+                return None;
+            }
+
+            in_mouth = true;
+        }
+
+        if state.pos.y.abs() > goal_line {
+            return Some(GoalCrossing {
+                net_team: if state.pos.y < 0. { Team::Blue } else { Team::Orange },
+                time,
+                pos: state.pos,
+                vel: state.vel,
+            });
+        }
+    }
+
+    None
+}
+
+/// The xG of a shot from right in front of an open net
+const XG_MAX_BASE: f32 = 0.9;
+/// The distance (uu) at which a shot is half as likely to go in, all else being equal
+const XG_HALF_DISTANCE: f32 = 4000.;
+/// The speed (uu/s) towards the goal at which the xG is boosted by half of what's left
+const XG_HALF_SPEED: f32 = 2000.;
+
+#[must_use]
+/// A simple expected goals (xG) estimate, between 0 and 1, of the ball going into the given team's net
+///
+/// This isn't a trained model, just a hand-tuned heuristic that increases with the angle that the
+/// goal mouth covers as seen from the ball, decreases with the distance to the net,
+/// and increases with the speed of the ball towards the net.
+/// It's meant for comparing shots (e.g. in stats or as a reward), not as an actual probability.
+pub fn expected_goal(ball: &BallState, net_team: Team) -> f32 {
+    let dir = match net_team {
+        Team::Blue => -1.,
+        Team::Orange => 1.,
+    };
+    let goal_center = Vec3::new(0., consts::ARENA_EXTENT_Y * dir, soccar::GOAL_HEIGHT / 2.);
+
+    // the angle between the two posts, as seen from above
+    let to_left = Vec3::new(-soccar::GOAL_HALF_WIDTH, goal_center.y, 0.) - Vec3::new(ball.pos.x, ball.pos.y, 0.);
+    let to_right = Vec3::new(soccar::GOAL_HALF_WIDTH, goal_center.y, 0.) - Vec3::new(ball.pos.x, ball.pos.y, 0.);
+    let angle = to_left.angle_between(to_right);
+    if !angle.is_finite() || (goal_center.y - ball.pos.y) * dir < 0. {
+        return 0.;
+    }
+
+    let to_goal = goal_center - ball.pos;
+    let distance = to_goal.length();
+    let speed = ball.vel.dot(to_goal.normalize_or_zero()).max(0.);
+
+    let angle_factor = angle / PI;
+    let distance_factor = XG_HALF_DISTANCE / (XG_HALF_DISTANCE + distance);
+    let speed_factor = speed / (XG_HALF_SPEED + speed);
+
+    let base = XG_MAX_BASE * angle_factor.sqrt() * distance_factor;
+    (base + (1. - base) * base * speed_factor).clamp(0., 1.)
+}
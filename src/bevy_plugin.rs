@@ -0,0 +1,165 @@
+//! A [bevy](https://bevyengine.org) plugin that runs an arena inside of a bevy app
+//!
+//! The arena is stored in the [`SimArena`] resource and is stepped once per `FixedUpdate`,
+//! which runs at the arena's tick rate. Every car gets an entity with [`SimCar`], [`SimCarState`] and
+//! [`SimCarControls`] components, and the ball gets an entity with [`SimBall`] and [`SimBallState`].
+//!
+//! State components are only written to after the arena has been stepped,
+//! so `Changed<SimCarState>` and `Changed<SimBallState>` filters only match on ticks where the physics ran.
+//!
+//! `rocketsim_rs::init` has to be called before the plugin is added,
+//! and the app needs a `TimePlugin` (included in `MinimalPlugins` and `DefaultPlugins`) for `FixedUpdate` to run.
+
+pub use bevy_app;
+pub use bevy_ecs;
+pub use bevy_time;
+
+use crate::sim::{Arena, ArenaConfig, BallState, CarControls, CarState, GameMode, Team};
+use bevy_app::{App, FixedUpdate, Plugin, Startup};
+use bevy_ecs::prelude::*;
+use bevy_time::{Fixed, Time};
+use core::pin::Pin;
+use cxx::UniquePtr;
+use std::collections::HashMap;
+
+#[derive(Resource)]
+/// The arena that is being simulated
+pub struct SimArena {
+    arena: UniquePtr<Arena>,
+}
+
+// SAFETY: The arena can only be accessed through `&mut self`, so it is never shared between threads
+unsafe impl Sync for SimArena {}
+
+impl SimArena {
+    #[inline]
+    #[must_use]
+    pub fn new(arena: UniquePtr<Arena>) -> Self {
+        Self { arena }
+    }
+
+    #[inline]
+    pub fn pin_mut(&mut self) -> Pin<&mut Arena> {
+        self.arena.pin_mut()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> UniquePtr<Arena> {
+        self.arena
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+/// A car in the arena
+///
+/// Entities with this component are spawned and despawned automatically as cars are added to and removed from the arena
+pub struct SimCar {
+    pub id: u32,
+    pub team: Team,
+}
+
+#[derive(Component, Clone, Copy, Debug)]
+/// The state of the car after the last tick
+pub struct SimCarState(pub CarState);
+
+#[derive(Component, Clone, Copy, Debug, Default)]
+/// The controls that are applied to the car before each tick
+pub struct SimCarControls(pub CarControls);
+
+#[derive(Component, Clone, Copy, Debug, Default)]
+/// Marks the ball entity
+pub struct SimBall;
+
+#[derive(Component, Clone, Copy, Debug)]
+/// The state of the ball after the last tick
+pub struct SimBallState(pub BallState);
+
+#[derive(SystemSet, Clone, Copy, Debug, Hash, PartialEq, Eq)]
+/// The systems that step the arena and sync the components, in `FixedUpdate`
+///
+/// Order systems that write `SimCarControls` before this set, and systems that read the states after it.
+pub struct RocketSimSet;
+
+#[derive(Clone, Copy, Debug)]
+pub struct RocketSimPlugin {
+    pub game_mode: GameMode,
+    pub config: ArenaConfig,
+    pub tick_rate: u8,
+}
+
+impl Default for RocketSimPlugin {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            game_mode: GameMode::Soccar,
+            config: ArenaConfig::default(),
+            tick_rate: 120,
+        }
+    }
+}
+
+impl Plugin for RocketSimPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SimArena::new(Arena::new(self.game_mode, self.config, self.tick_rate)))
+            .insert_resource(Time::<Fixed>::from_hz(f64::from(self.tick_rate)))
+            .add_systems(Startup, (spawn_ball, sync_cars).chain())
+            .add_systems(
+                FixedUpdate,
+                (apply_controls, step_arena, sync_cars, sync_ball)
+                    .chain()
+                    .in_set(RocketSimSet),
+            );
+    }
+}
+
+fn spawn_ball(mut commands: Commands, mut arena: ResMut<SimArena>) {
+    commands.spawn((SimBall, SimBallState(arena.pin_mut().get_ball())));
+}
+
+fn apply_controls(mut arena: ResMut<SimArena>, cars: Query<(&SimCar, &SimCarControls)>) {
+    for (car, controls) in &cars {
+        // the car may have been removed since the last sync
+        let _ = arena.pin_mut().set_car_controls(car.id, controls.0);
+    }
+}
+
+fn step_arena(mut arena: ResMut<SimArena>) {
+    arena.pin_mut().step(1);
+}
+
+/// Updates the car states, and spawns/despawns car entities to match the arena
+fn sync_cars(mut commands: Commands, mut arena: ResMut<SimArena>, mut cars: Query<(Entity, &SimCar, &mut SimCarState)>) {
+    let mut infos = arena
+        .pin_mut()
+        .get_car_infos()
+        .into_iter()
+        .map(|info| (info.id, info))
+        .collect::<HashMap<_, _>>();
+
+    for (entity, car, mut state) in &mut cars {
+        match infos.remove(&car.id) {
+            Some(info) => state.0 = info.state,
+            None => commands.entity(entity).despawn(),
+        }
+    }
+
+    for info in infos.into_values() {
+        commands.spawn((
+            SimCar {
+                id: info.id,
+                team: info.team,
+            },
+            SimCarState(info.state),
+            SimCarControls::default(),
+        ));
+    }
+}
+
+fn sync_ball(mut arena: ResMut<SimArena>, mut ball: Query<&mut SimBallState, With<SimBall>>) {
+    let state = arena.pin_mut().get_ball();
+
+    for mut ball in &mut ball {
+        ball.0 = state;
+    }
+}
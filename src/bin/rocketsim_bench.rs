@@ -0,0 +1,246 @@
+//! Standardized benchmark workloads, for comparing hardware and RocketSim versions
+//!
+//! Usage: `rocketsim-bench [--json] [--ticks <n>] [--collision-meshes <folder>] [workload...]`
+//!
+//! With `--json`, the human-readable output is replaced with a single JSON report on stdout.
+
+use rocketsim_rs::{
+    cxx::UniquePtr,
+    prediction::BallPrediction,
+    sim::{Arena, CarConfig, CarControls, Team},
+};
+use std::{
+    env,
+    fmt::Write,
+    process::exit,
+    thread::{available_parallelism, scope},
+    time::Instant,
+};
+
+const DEFAULT_TICKS: u32 = 100_000;
+const BATCH_ARENAS: usize = 500;
+/// How far ahead the prediction-heavy workload predicts the ball every tick
+const PREDICTION_TICKS: u32 = 120;
+
+#[derive(Clone, Copy)]
+struct Workload {
+    name: &'static str,
+    description: &'static str,
+    /// Fraction of `--ticks` that each arena in the workload runs for
+    tick_scale: f32,
+    run: fn(u32) -> u64,
+}
+
+const WORKLOADS: [Workload; 4] = [
+    Workload {
+        name: "1v1",
+        description: "one arena with 2 cars",
+        tick_scale: 1.,
+        run: |ticks| run_single(1, ticks),
+    },
+    Workload {
+        name: "3v3",
+        description: "one arena with 6 cars",
+        tick_scale: 1.,
+        run: |ticks| run_single(3, ticks),
+    },
+    Workload {
+        name: "batch",
+        description: "500 3v3 arenas, spread over all threads",
+        tick_scale: 0.01,
+        run: run_batch,
+    },
+    Workload {
+        name: "prediction",
+        description: "one 1v1 arena, predicting the ball 1 second ahead every tick",
+        tick_scale: 0.01,
+        run: run_prediction,
+    },
+];
+
+struct Report {
+    workload: Workload,
+    /// Total ticks simulated, summed over all arenas (but not including ball prediction)
+    ticks: u64,
+    seconds: f64,
+}
+
+impl Report {
+    fn ticks_per_sec(&self) -> f64 {
+        self.ticks as f64 / self.seconds
+    }
+}
+
+fn new_arena(cars_per_team: usize) -> UniquePtr<Arena> {
+    let mut arena = Arena::default_standard();
+
+    for team in [Team::Blue, Team::Orange] {
+        for _ in 0..cars_per_team {
+            let _ = arena.pin_mut().add_car(team, CarConfig::octane());
+        }
+    }
+
+    arena.pin_mut().reset_to_random_kickoff(Some(0));
+    arena
+}
+
+/// Deterministic controls that keep the cars driving, turning, jumping and boosting around the field
+fn controls_for(tick: u32, car_id: u32) -> CarControls {
+    let phase = (tick / 60 + car_id) % 8;
+
+    CarControls {
+        throttle: 1.,
+        steer: if phase < 4 { 0.5 } else { -0.5 },
+        boost: phase.is_multiple_of(3),
+        jump: phase == 5,
+        ..Default::default()
+    }
+}
+
+/// Steps in chunks of 60 ticks, changing the controls between chunks
+fn drive(arena: &mut UniquePtr<Arena>, ticks: u32) {
+    let car_ids = arena.get_cars();
+
+    let mut tick = 0;
+    while tick < ticks {
+        for &car_id in &car_ids {
+            arena.pin_mut().set_car_controls(car_id, controls_for(tick, car_id)).unwrap();
+        }
+
+        let chunk = (ticks - tick).min(60);
+        arena.pin_mut().step(chunk);
+        tick += chunk;
+    }
+}
+
+fn run_single(cars_per_team: usize, ticks: u32) -> u64 {
+    let mut arena = new_arena(cars_per_team);
+    drive(&mut arena, ticks);
+    u64::from(ticks)
+}
+
+fn run_batch(ticks: u32) -> u64 {
+    let num_threads = available_parallelism().map_or(1, |n| n.get());
+    let mut arenas = (0..BATCH_ARENAS).map(|_| new_arena(3)).collect::<Vec<_>>();
+
+    scope(|s| {
+        for chunk in arenas.chunks_mut(BATCH_ARENAS.div_ceil(num_threads)) {
+            s.spawn(move || {
+                for arena in chunk {
+                    drive(arena, ticks);
+                }
+            });
+        }
+    });
+
+    BATCH_ARENAS as u64 * u64::from(ticks)
+}
+
+fn run_prediction(ticks: u32) -> u64 {
+    let mut arena = new_arena(1);
+
+    for _ in 0..ticks {
+        let prediction = BallPrediction::new(&arena, PREDICTION_TICKS);
+        assert_eq!(prediction.slices.len(), PREDICTION_TICKS as usize);
+        arena.pin_mut().step(1);
+    }
+
+    u64::from(ticks)
+}
+
+fn json_report(base_ticks: u32, reports: &[Report]) -> String {
+    let mut json = String::new();
+
+    write!(
+        json,
+        "{{\"version\":\"{}\",\"threads\":{},\"base_ticks\":{base_ticks},\"workloads\":[",
+        env!("CARGO_PKG_VERSION"),
+        available_parallelism().map_or(1, |n| n.get())
+    )
+    .unwrap();
+
+    for (i, report) in reports.iter().enumerate() {
+        if i != 0 {
+            json.push(',');
+        }
+
+        write!(
+            json,
+            "{{\"name\":\"{}\",\"ticks\":{},\"seconds\":{},\"ticks_per_sec\":{}}}",
+            report.workload.name,
+            report.ticks,
+            report.seconds,
+            report.ticks_per_sec()
+        )
+        .unwrap();
+    }
+
+    json.push_str("]}");
+    json
+}
+
+fn usage() -> ! {
+    eprintln!("Usage: rocketsim-bench [--json] [--ticks <n>] [--collision-meshes <folder>] [workload...]");
+    eprintln!();
+    eprintln!("Workloads:");
+    for workload in WORKLOADS {
+        eprintln!("  {:<12}{}", workload.name, workload.description);
+    }
+
+    exit(2)
+}
+
+fn main() {
+    let mut json = false;
+    let mut ticks = DEFAULT_TICKS;
+    let mut meshes = None;
+    let mut selected = Vec::new();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--ticks" => ticks = args.next().and_then(|n| n.parse().ok()).unwrap_or_else(|| usage()),
+            "--collision-meshes" => meshes = Some(args.next().unwrap_or_else(|| usage())),
+            "-h" | "--help" => usage(),
+            name => selected.push(WORKLOADS.into_iter().find(|w| w.name == name).unwrap_or_else(|| usage())),
+        }
+    }
+
+    if selected.is_empty() {
+        selected = WORKLOADS.to_vec();
+    }
+
+    rocketsim_rs::init(meshes.as_deref(), true);
+
+    let reports = selected
+        .into_iter()
+        .map(|workload| {
+            let workload_ticks = ((ticks as f32 * workload.tick_scale) as u32).max(1);
+
+            let start = Instant::now();
+            let total_ticks = (workload.run)(workload_ticks);
+            let report = Report {
+                workload,
+                ticks: total_ticks,
+                seconds: start.elapsed().as_secs_f64(),
+            };
+
+            if !json {
+                println!(
+                    "{:<12}{:>14.0} ticks/sec ({} ticks in {:.3}s)",
+                    workload.name,
+                    report.ticks_per_sec(),
+                    report.ticks,
+                    report.seconds
+                );
+            }
+
+            report
+        })
+        .collect::<Vec<_>>();
+
+    if json {
+        println!("{}", json_report(ticks, &reports));
+    }
+}
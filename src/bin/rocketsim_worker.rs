@@ -0,0 +1,90 @@
+//! A worker process for distributed simulation, see the `distributed` module
+//!
+//! Usage: `rocketsim-worker [--listen <addr>] [--unix <path>] [--collision-meshes <folder>]`
+//!
+//! Coordinators are served one at a time, and every connection starts with no arenas.
+
+use rocketsim_rs::distributed::{Worker, DEFAULT_WORKER_ADDR};
+use std::{env, net::TcpListener, process::exit};
+
+fn usage() -> ! {
+    eprintln!("Usage: rocketsim-worker [--listen <addr>] [--unix <path>] [--collision-meshes <folder>]");
+    eprintln!();
+    eprintln!("Listens on {DEFAULT_WORKER_ADDR} by default");
+
+    exit(2)
+}
+
+#[cfg(unix)]
+fn serve_unix(path: &str) -> ! {
+    use std::os::unix::net::UnixListener;
+
+    let listener = UnixListener::bind(path).unwrap_or_else(|e| {
+        eprintln!("Failed to listen on {path}: {e}");
+        exit(1)
+    });
+    println!("Listening on {path}");
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                if let Err(e) = Worker::new().serve(stream) {
+                    eprintln!("Connection ended with an error: {e}");
+                }
+            }
+            Err(e) => eprintln!("Failed to accept a connection: {e}"),
+        }
+    }
+
+    exit(0)
+}
+
+#[cfg(not(unix))]
+fn serve_unix(_path: &str) -> ! {
+    eprintln!("Unix sockets aren't supported on this platform");
+    exit(1)
+}
+
+fn main() {
+    let mut addr = DEFAULT_WORKER_ADDR.to_string();
+    let mut unix = None;
+    let mut meshes = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--listen" => addr = args.next().unwrap_or_else(|| usage()),
+            "--unix" => unix = Some(args.next().unwrap_or_else(|| usage())),
+            "--collision-meshes" => meshes = Some(args.next().unwrap_or_else(|| usage())),
+            _ => usage(),
+        }
+    }
+
+    rocketsim_rs::init(meshes.as_deref(), true);
+
+    if let Some(path) = unix {
+        serve_unix(&path);
+    }
+
+    let listener = TcpListener::bind(&addr).unwrap_or_else(|e| {
+        eprintln!("Failed to listen on {addr}: {e}");
+        exit(1)
+    });
+    println!("Listening on {addr}");
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("Failed to accept a connection: {e}");
+                continue;
+            }
+        };
+
+        // frames are small and answered right away, so don't wait to fill packets
+        let _ = stream.set_nodelay(true);
+        if let Err(e) = Worker::new().serve(stream) {
+            eprintln!("Connection ended with an error: {e}");
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! Conversions between the batches of an `ArenaPool` and [burn](https://burn.dev) tensors
+//!
+//! Observations are stacked by `InferenceHook` as `n_envs * obs_len` floats, which become a `[n_envs, obs_len]` tensor,
+//! and a policy's output of shape `[n_envs, n_cars, CONTROLS_LEN]` becomes the controls of every car in every arena.
+//! Tensors are created on the given device, and read back to the host (waiting for the device if needed).
+//! The `burn_ndarray` feature also re-exports burn's `NdArray` backend, for running small policies on the CPU.
+//!
+//! ```ignore
+//! let device = Default::default();
+//! let hook = InferenceHook::new(obs_len, observe, move |obs, n_envs| {
+//!     let actions = model.forward(observations_to_tensor::<B>(obs, n_envs, &device));
+//!     controls_from_tensor(actions)
+//!         .into_iter()
+//!         .map(|controls| car_ids.iter().copied().zip(controls).collect())
+//!         .collect()
+//! });
+//! ```
+
+#[cfg(feature = "burn_ndarray")]
+pub use burn_ndarray;
+pub use burn_tensor;
+
+use crate::sim::CarControls;
+use burn_tensor::{backend::Backend, Tensor, TensorData};
+
+/// The number of values of a car's controls in a tensor:
+/// throttle, steer, pitch, yaw, roll, boost, jump and handbrake
+pub const CONTROLS_LEN: usize = 8;
+
+/// The threshold above which a value is a pressed button, for boost, jump and handbrake
+pub const BUTTON_THRESHOLD: f32 = 0.5;
+
+#[must_use]
+/// Turns stacked observations into a `[n_envs, obs_len]` tensor on the device
+///
+/// # Panics
+///
+/// Panics if the number of observations isn't a multiple of `n_envs`
+pub fn observations_to_tensor<B: Backend>(observations: &[f32], n_envs: usize, device: &B::Device) -> Tensor<B, 2> {
+    assert!(
+        n_envs != 0 && observations.len().is_multiple_of(n_envs),
+        "{} observations can't be split into {n_envs} environments",
+        observations.len()
+    );
+
+    let data = TensorData::new(observations.to_vec(), [n_envs, observations.len() / n_envs]);
+    Tensor::from_data(data.convert::<B::FloatElem>(), device)
+}
+
+#[must_use]
+/// Turns the controls of cars into a `[n_cars, CONTROLS_LEN]` tensor on the device, with buttons as 0 or 1
+pub fn controls_to_tensor<B: Backend>(controls: &[CarControls], device: &B::Device) -> Tensor<B, 2> {
+    let values = controls
+        .iter()
+        .flat_map(|controls| {
+            [
+                controls.throttle,
+                controls.steer,
+                controls.pitch,
+                controls.yaw,
+                controls.roll,
+                f32::from(u8::from(controls.boost)),
+                f32::from(u8::from(controls.jump)),
+                f32::from(u8::from(controls.handbrake)),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let data = TensorData::new(values, [controls.len(), CONTROLS_LEN]);
+    Tensor::from_data(data.convert::<B::FloatElem>(), device)
+}
+
+#[must_use]
+/// Turns a `[n_envs, n_cars, CONTROLS_LEN]` tensor into the controls of every car in every environment
+///
+/// Analog values are clamped to `[-1, 1]`, and buttons are pressed above `BUTTON_THRESHOLD`.
+///
+/// # Panics
+///
+/// Panics if the last dimension isn't `CONTROLS_LEN`
+pub fn controls_from_tensor<B: Backend>(actions: Tensor<B, 3>) -> Vec<Vec<CarControls>> {
+    let [n_envs, n_cars, len] = actions.dims();
+    assert_eq!(
+        len, CONTROLS_LEN,
+        "Expected {CONTROLS_LEN} values for the controls of each car"
+    );
+
+    let values = actions
+        .into_data()
+        .convert::<f32>()
+        .to_vec::<f32>()
+        .expect("The data was converted to f32");
+
+    let mut cars = values.chunks_exact(CONTROLS_LEN).map(|values| CarControls {
+        throttle: values[0].clamp(-1., 1.),
+        steer: values[1].clamp(-1., 1.),
+        pitch: values[2].clamp(-1., 1.),
+        yaw: values[3].clamp(-1., 1.),
+        roll: values[4].clamp(-1., 1.),
+        boost: values[5] > BUTTON_THRESHOLD,
+        jump: values[6] > BUTTON_THRESHOLD,
+        handbrake: values[7] > BUTTON_THRESHOLD,
+    });
+
+    (0..n_envs).map(|_| cars.by_ref().take(n_cars).collect()).collect()
+}
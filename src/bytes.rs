@@ -2,8 +2,8 @@ use crate::{
     math::{RotMat, Vec3},
     render::{Color, Render, RenderMessage, Vec2},
     sim::{
-        BallHitInfo, BallState, BoostPadConfig, BoostPadState, CarConfig, CarContact, CarControls, CarState, GameMode,
-        HeatseekerInfo, Team, WheelPairConfig, WorldContact,
+        ArenaConfig, ArenaMemWeightMode, BallHitInfo, BallState, BoostPadConfig, BoostPadState, CarConfig, CarContact,
+        CarControls, CarState, DemoMode, GameMode, HeatseekerInfo, MutatorConfig, Team, WheelPairConfig, WorldContact,
     },
     BoostPad, CarInfo, GameState,
 };
@@ -34,6 +34,15 @@ impl<'a> ByteReader<'a> {
         item
     }
 
+    /// Reads a string written by `write_string`, cut short if there aren't enough bytes left
+    pub fn read_string(&mut self) -> String {
+        let len = self.read::<u16>() as usize;
+        let end = (self.idx + len).min(self.bytes.len());
+        let text = String::from_utf8_lossy(&self.bytes[self.idx..end]).into_owned();
+        self.idx = end;
+        text
+    }
+
     #[inline]
     #[track_caller]
     pub fn debug_assert_num_bytes(&self, num_bytes: usize) {
@@ -165,6 +174,37 @@ impl FromBytes for GameMode {
     }
 }
 
+impl FromBytesExact for DemoMode {
+    const NUM_BYTES: usize = 1;
+}
+
+impl FromBytes for DemoMode {
+    #[inline]
+    fn from_bytes(bytes: &[u8]) -> Self {
+        match bytes[0] {
+            0 => Self::Normal,
+            1 => Self::OnContact,
+            2 => Self::Disabled,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl FromBytesExact for ArenaMemWeightMode {
+    const NUM_BYTES: usize = 1;
+}
+
+impl FromBytes for ArenaMemWeightMode {
+    #[inline]
+    fn from_bytes(bytes: &[u8]) -> Self {
+        match bytes[0] {
+            0 => Self::Heavy,
+            1 => Self::Light,
+            _ => unreachable!(),
+        }
+    }
+}
+
 impl FromBytesExact for Vec3 {
     const NUM_BYTES: usize = f32::NUM_BYTES * 3;
 }
@@ -276,7 +316,7 @@ macro_rules! impl_to_bytes_exact_as_u8 {
     };
 }
 
-impl_to_bytes_exact_as_u8!(bool, Team, GameMode);
+impl_to_bytes_exact_as_u8!(bool, Team, GameMode, DemoMode, ArenaMemWeightMode);
 
 macro_rules! impl_to_bytes_exact {
     ($t:ty, $($p:ident),+) => {
@@ -412,6 +452,48 @@ impl_bytes_exact!(
     back_wheels,
     dodge_deadzone
 );
+impl_bytes_exact!(
+    ArenaConfig,
+    1 + Vec3::NUM_BYTES * 2 + f32::NUM_BYTES + 2 + u32::NUM_BYTES,
+    mem_weight_mode,
+    min_pos,
+    max_pos,
+    max_aabb_len,
+    no_ball_rot,
+    use_custom_broadphase,
+    max_objects
+);
+impl_bytes_exact!(
+    MutatorConfig,
+    Vec3::NUM_BYTES + f32::NUM_BYTES * 22 + 4,
+    gravity,
+    car_mass,
+    car_world_friction,
+    car_world_restitution,
+    ball_mass,
+    ball_max_speed,
+    ball_drag,
+    ball_world_friction,
+    ball_world_restitution,
+    jump_accel,
+    jump_immediate_force,
+    boost_accel_ground,
+    boost_accel_air,
+    boost_used_per_second,
+    respawn_delay,
+    bump_cooldown_time,
+    boost_pad_cooldown_big,
+    boost_pad_cooldown_small,
+    car_spawn_boost_amount,
+    ball_hit_extra_force_scale,
+    bump_force_scale,
+    ball_radius,
+    unlimited_flips,
+    unlimited_double_jumps,
+    demo_mode,
+    enable_team_demos,
+    goal_base_threshold_y
+);
 impl_bytes_exact!(
     CarInfo,
     u32::NUM_BYTES + Team::NUM_BYTES + CarState::NUM_BYTES + CarConfig::NUM_BYTES,
@@ -421,12 +503,29 @@ impl_bytes_exact!(
     config
 );
 
+impl ArenaConfig {
+    #[inline]
+    #[must_use]
+    /// Checks that the bytes start with an arena config whose memory weight mode is valid,
+    /// so `from_bytes` won't panic on them
+    pub fn check_bytes(bytes: &[u8]) -> bool {
+        bytes.len() >= Self::NUM_BYTES && bytes[0] <= ArenaMemWeightMode::Light as u8
+    }
+}
+
 impl Render {
     fn count_bytes(&self) -> usize {
         match self {
             Self::Line2D { .. } => 1 + Vec2::NUM_BYTES * 2 + Color::NUM_BYTES,
             Self::Line { .. } => 1 + Vec3::NUM_BYTES * 2 + Color::NUM_BYTES,
             Self::LineStrip { positions, .. } => 1 + u16::NUM_BYTES + positions.len() * Vec3::NUM_BYTES + Color::NUM_BYTES,
+            Self::Sphere { .. } => 1 + Vec3::NUM_BYTES + f32::NUM_BYTES + Color::NUM_BYTES,
+            Self::Text2D { text, .. } => {
+                1 + Vec2::NUM_BYTES + u16::NUM_BYTES + truncated(text).len() + f32::NUM_BYTES + Color::NUM_BYTES
+            }
+            Self::Text { text, .. } => {
+                1 + Vec3::NUM_BYTES + u16::NUM_BYTES + truncated(text).len() + f32::NUM_BYTES + Color::NUM_BYTES
+            }
         }
     }
 
@@ -446,11 +545,47 @@ impl Render {
                 positions: (0..reader.read::<u16>()).map(|_| reader.read()).collect(),
                 color: reader.read(),
             },
+            3 => Self::Sphere {
+                center: reader.read(),
+                radius: reader.read(),
+                color: reader.read(),
+            },
+            4 => Self::Text2D {
+                pos: reader.read(),
+                text: reader.read_string(),
+                scale: reader.read(),
+                color: reader.read(),
+            },
+            5 => Self::Text {
+                pos: reader.read(),
+                text: reader.read_string(),
+                scale: reader.read(),
+                color: reader.read(),
+            },
             _ => unreachable!(),
         }
     }
 }
 
+/// Cuts the string down to the most characters that fit in `u16::MAX` bytes
+fn truncated(text: &str) -> &str {
+    let mut len = text.len().min(u16::MAX as usize);
+    while !text.is_char_boundary(len) {
+        len -= 1;
+    }
+
+    &text[..len]
+}
+
+/// Writes the length of the string as a `u16`, followed by the UTF-8 bytes
+///
+/// Strings longer than `u16::MAX` bytes are truncated
+fn write_string(bytes: &mut Vec<u8>, text: &str) {
+    let text = truncated(text);
+    bytes.extend_from_slice(&(text.len() as u16).to_bytes());
+    bytes.extend_from_slice(text.as_bytes());
+}
+
 impl ToBytes for Render {
     fn to_bytes(&self) -> Vec<u8> {
         let num_bytes = self.count_bytes();
@@ -479,6 +614,26 @@ impl ToBytes for Render {
 
                 bytes.extend_from_slice(&color.to_bytes());
             }
+            Self::Sphere { center, radius, color } => {
+                bytes.push(3);
+                bytes.extend_from_slice(&center.to_bytes());
+                bytes.extend_from_slice(&radius.to_bytes());
+                bytes.extend_from_slice(&color.to_bytes());
+            }
+            Self::Text2D { pos, text, scale, color } => {
+                bytes.push(4);
+                bytes.extend_from_slice(&pos.to_bytes());
+                write_string(&mut bytes, text);
+                bytes.extend_from_slice(&scale.to_bytes());
+                bytes.extend_from_slice(&color.to_bytes());
+            }
+            Self::Text { pos, text, scale, color } => {
+                bytes.push(5);
+                bytes.extend_from_slice(&pos.to_bytes());
+                write_string(&mut bytes, text);
+                bytes.extend_from_slice(&scale.to_bytes());
+                bytes.extend_from_slice(&color.to_bytes());
+            }
         }
 
         debug_assert_eq!(bytes.len(), num_bytes);
@@ -592,6 +747,30 @@ impl GameState {
             + Self::read_num_cars(bytes) * CarInfo::NUM_BYTES
     }
 
+    #[must_use]
+    /// Checks that the bytes start with a whole game state whose game mode and car teams are valid,
+    /// so `from_bytes` won't panic on them, and returns the number of bytes of the game state
+    ///
+    /// Use this before `from_bytes` for bytes that didn't come from `to_bytes`, like bytes from the network.
+    pub fn check_bytes(bytes: &[u8]) -> Option<usize> {
+        if bytes.len() < Self::MIN_NUM_BYTES || bytes[u64::NUM_BYTES + f32::NUM_BYTES] > GameMode::TheVoid as u8 {
+            return None;
+        }
+
+        let pads_start = Self::MIN_NUM_BYTES + BallState::NUM_BYTES;
+        let cars_start = Self::read_num_pads(bytes)
+            .checked_mul(BoostPad::NUM_BYTES)?
+            .checked_add(pads_start)?;
+        let num_bytes = Self::read_num_cars(bytes)
+            .checked_mul(CarInfo::NUM_BYTES)?
+            .checked_add(cars_start)?;
+
+        let cars = bytes.get(cars_start..num_bytes)?;
+        cars.chunks_exact(CarInfo::NUM_BYTES)
+            .all(|car| car[u32::NUM_BYTES] <= Team::Orange as u8)
+            .then_some(num_bytes)
+    }
+
     #[inline]
     #[must_use]
     pub fn read_tick_count(bytes: &[u8]) -> u64 {
@@ -644,3 +823,161 @@ impl ToBytes for GameState {
         bytes
     }
 }
+
+#[cfg(feature = "glam")]
+mod glam_bytes {
+    //! The glam variants use the exact same byte layout as their non-glam counterparts
+
+    use super::*;
+    use crate::glam_ext::{
+        glam::{Mat3A, Vec3A},
+        BallA, BallHitInfoA, BoostPadA, BoostPadConfigA, CarConfigA, CarContactA, CarInfoA, CarStateA, GameStateA,
+        WheelPairConfigA, WorldContactA,
+    };
+
+    impl FromBytesExact for Vec3A {
+        const NUM_BYTES: usize = Vec3::NUM_BYTES;
+    }
+
+    impl FromBytes for Vec3A {
+        fn from_bytes(bytes: &[u8]) -> Self {
+            let mut reader = ByteReader::new(bytes);
+            Self::new(reader.read(), reader.read(), reader.read())
+        }
+    }
+
+    impl FromBytesExact for Mat3A {
+        const NUM_BYTES: usize = RotMat::NUM_BYTES;
+    }
+
+    impl FromBytes for Mat3A {
+        fn from_bytes(bytes: &[u8]) -> Self {
+            let mut reader = ByteReader::new(bytes);
+            Self::from_cols(reader.read(), reader.read(), reader.read())
+        }
+    }
+
+    impl_to_bytes_exact!(Vec3A, x, y, z);
+    impl_to_bytes_exact!(Mat3A, x_axis, y_axis, z_axis);
+    impl_bytes_exact!(
+        BallA,
+        BallState::NUM_BYTES,
+        update_counter,
+        pos,
+        rot_mat,
+        vel,
+        ang_vel,
+        hs_info
+    );
+    impl_bytes_exact!(BoostPadConfigA, BoostPadConfig::NUM_BYTES, is_big, position);
+    impl_bytes_exact!(BoostPadA, BoostPad::NUM_BYTES, config, state);
+    impl_bytes_exact!(
+        BallHitInfoA,
+        BallHitInfo::NUM_BYTES,
+        is_valid,
+        relative_pos_on_ball,
+        ball_pos,
+        extra_hit_vel,
+        tick_count_when_hit,
+        tick_count_when_extra_impulse_applied
+    );
+    impl_bytes_exact!(WorldContactA, WorldContact::NUM_BYTES, has_contact, contact_normal);
+    impl_bytes_exact!(CarContactA, CarContact::NUM_BYTES, other_car_id, cooldown_timer);
+    impl_bytes_exact!(
+        CarStateA,
+        CarState::NUM_BYTES,
+        update_counter,
+        pos,
+        rot_mat,
+        vel,
+        ang_vel,
+        is_on_ground,
+        wheels_with_contact,
+        has_jumped,
+        has_double_jumped,
+        has_flipped,
+        flip_rel_torque,
+        jump_time,
+        flip_time,
+        is_flipping,
+        is_jumping,
+        air_time,
+        air_time_since_jump,
+        boost,
+        time_spent_boosting,
+        is_supersonic,
+        supersonic_time,
+        handbrake_val,
+        is_auto_flipping,
+        auto_flip_timer,
+        auto_flip_torque_scale,
+        world_contact,
+        car_contact,
+        is_demoed,
+        demo_respawn_timer,
+        ball_hit_info,
+        last_controls
+    );
+    impl_bytes_exact!(
+        WheelPairConfigA,
+        WheelPairConfig::NUM_BYTES,
+        wheel_radius,
+        suspension_rest_length,
+        connection_point_offset
+    );
+    impl_bytes_exact!(
+        CarConfigA,
+        CarConfig::NUM_BYTES,
+        hitbox_size,
+        hitbox_pos_offset,
+        front_wheels,
+        back_wheels,
+        dodge_deadzone
+    );
+    impl_bytes_exact!(CarInfoA, CarInfo::NUM_BYTES, id, team, state, config);
+
+    impl FromBytes for GameStateA {
+        #[inline]
+        fn from_bytes(bytes: &[u8]) -> Self {
+            let pads_start = GameState::MIN_NUM_BYTES + BallState::NUM_BYTES;
+            let cars_start = pads_start + GameState::read_num_pads(bytes) * BoostPad::NUM_BYTES;
+
+            Self {
+                tick_count: GameState::read_tick_count(bytes),
+                tick_rate: GameState::read_tick_rate(bytes),
+                game_mode: GameState::read_game_mode(bytes),
+                ball: BallA::from_bytes(&bytes[GameState::MIN_NUM_BYTES..pads_start]),
+                pads: bytes[pads_start..cars_start]
+                    .chunks_exact(BoostPadA::NUM_BYTES)
+                    .map(BoostPadA::from_bytes)
+                    .collect(),
+                cars: bytes[cars_start..]
+                    .chunks_exact(CarInfoA::NUM_BYTES)
+                    .map(CarInfoA::from_bytes)
+                    .collect(),
+            }
+        }
+    }
+
+    impl ToBytes for GameStateA {
+        fn to_bytes(&self) -> Vec<u8> {
+            let mut bytes = Vec::with_capacity(
+                GameState::MIN_NUM_BYTES
+                    + BallA::NUM_BYTES
+                    + self.pads.len() * BoostPadA::NUM_BYTES
+                    + self.cars.len() * CarInfoA::NUM_BYTES,
+            );
+
+            bytes.extend(self.tick_count.to_bytes());
+            bytes.extend(self.tick_rate.to_bytes());
+            bytes.extend(self.game_mode.to_bytes());
+            bytes.extend(&(self.pads.len() as u32).to_bytes());
+            bytes.extend(&(self.cars.len() as u32).to_bytes());
+            bytes.extend(self.ball.to_bytes());
+            bytes.extend(self.pads.iter().flat_map(ToBytesExact::<{ BoostPadA::NUM_BYTES }>::to_bytes));
+            bytes.extend(self.cars.iter().flat_map(ToBytesExact::<{ CarInfoA::NUM_BYTES }>::to_bytes));
+
+            bytes
+        }
+    }
+}
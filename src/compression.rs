@@ -0,0 +1,222 @@
+//! Streaming zstd and lz4 compression for replays and batches of game states
+//!
+//! Game states compress very well, since most of each state is the same as the one before it.
+//! `CompressedReader` detects the compression from the first bytes of the stream,
+//! so it can read compressed and uncompressed streams alike.
+//!
+//! ```no_run
+//! use rocketsim_rs::{
+//!     compression::Compression,
+//!     replay::{ReplayReader, ReplayWriter},
+//! };
+//!
+//! let mut writer = ReplayWriter::create_compressed("game.rsrp.zst", Compression::zstd()).unwrap();
+//! // ... write the game states ...
+//! writer.finish().unwrap();
+//!
+//! for state in ReplayReader::open_compressed("game.rsrp.zst").unwrap() {
+//!     let state = state.unwrap();
+//! }
+//! ```
+
+use crate::{
+    bytes::{FromBytes, ToBytes},
+    GameState,
+};
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+#[cfg(feature = "zstd")]
+/// The first bytes of a zstd frame
+pub const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+#[cfg(feature = "lz4")]
+/// The first bytes of an lz4 frame
+pub const LZ4_MAGIC: [u8; 4] = [0x04, 0x22, 0x4D, 0x18];
+
+#[cfg(feature = "zstd")]
+/// The zstd level that's used by `Compression::zstd`
+pub const DEFAULT_ZSTD_LEVEL: i32 = 3;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Compression {
+    #[default]
+    None,
+    #[cfg(feature = "zstd")]
+    /// Smaller output, for recordings that are kept around
+    Zstd { level: i32 },
+    #[cfg(feature = "lz4")]
+    /// Much faster, for transferring states between processes
+    Lz4,
+}
+
+impl Compression {
+    #[cfg(feature = "zstd")]
+    #[inline]
+    #[must_use]
+    pub const fn zstd() -> Self {
+        Self::Zstd {
+            level: DEFAULT_ZSTD_LEVEL,
+        }
+    }
+}
+
+/// Compresses everything that's written to it, with the compression it was created with
+///
+/// `finish` must be called once everything has been written, or the end of the stream is lost
+pub enum CompressedWriter<W: Write> {
+    None(W),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::stream::write::Encoder<'static, W>),
+    #[cfg(feature = "lz4")]
+    Lz4(lz4_flex::frame::FrameEncoder<W>),
+}
+
+impl<W: Write> CompressedWriter<W> {
+    /// # Errors
+    ///
+    /// Returns an error if the zstd encoder can't be created (e.g. the level is invalid)
+    pub fn new(writer: W, compression: Compression) -> io::Result<Self> {
+        Ok(match compression {
+            Compression::None => Self::None(writer),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd { level } => Self::Zstd(zstd::stream::write::Encoder::new(writer, level)?),
+            #[cfg(feature = "lz4")]
+            Compression::Lz4 => Self::Lz4(lz4_flex::frame::FrameEncoder::new(writer)),
+        })
+    }
+
+    /// Writes the end of the compressed stream, and returns the underlying writer
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the end of the stream can't be written
+    pub fn finish(self) -> io::Result<W> {
+        match self {
+            Self::None(writer) => Ok(writer),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(encoder) => encoder.finish(),
+            #[cfg(feature = "lz4")]
+            Self::Lz4(encoder) => Ok(encoder.finish()?),
+        }
+    }
+}
+
+impl<W: Write> Write for CompressedWriter<W> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::None(writer) => writer.write(buf),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(encoder) => encoder.write(buf),
+            #[cfg(feature = "lz4")]
+            Self::Lz4(encoder) => encoder.write(buf),
+        }
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::None(writer) => writer.flush(),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(encoder) => encoder.flush(),
+            #[cfg(feature = "lz4")]
+            Self::Lz4(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// Decompresses a stream, with the compression detected from its first bytes
+pub enum CompressedReader<R: Read> {
+    None(BufReader<R>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::stream::read::Decoder<'static, BufReader<R>>),
+    #[cfg(feature = "lz4")]
+    Lz4(lz4_flex::frame::FrameDecoder<BufReader<R>>),
+}
+
+impl<R: Read> CompressedReader<R> {
+    /// Detects the compression of the stream, streams that aren't zstd or lz4 are read as-is
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the start of the stream can't be read
+    pub fn new(reader: R) -> io::Result<Self> {
+        let mut reader = BufReader::new(reader);
+        let start = reader.fill_buf()?;
+
+        #[cfg(feature = "zstd")]
+        if start.starts_with(&ZSTD_MAGIC) {
+            return Ok(Self::Zstd(zstd::stream::read::Decoder::with_buffer(reader)?));
+        }
+
+        #[cfg(feature = "lz4")]
+        if start.starts_with(&LZ4_MAGIC) {
+            return Ok(Self::Lz4(lz4_flex::frame::FrameDecoder::new(reader)));
+        }
+
+        Ok(Self::None(reader))
+    }
+
+    #[must_use]
+    /// The compression that was detected
+    pub const fn compression(&self) -> Compression {
+        match self {
+            Self::None(_) => Compression::None,
+            #[cfg(feature = "zstd")]
+            // the level isn't stored in the stream
+            Self::Zstd(_) => Compression::zstd(),
+            #[cfg(feature = "lz4")]
+            Self::Lz4(_) => Compression::Lz4,
+        }
+    }
+}
+
+impl<R: Read> Read for CompressedReader<R> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::None(reader) => reader.read(buf),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(decoder) => decoder.read(buf),
+            #[cfg(feature = "lz4")]
+            Self::Lz4(decoder) => decoder.read(buf),
+        }
+    }
+}
+
+/// Compresses a batch of game states into a single buffer, e.g. to send them to another process
+///
+/// # Errors
+///
+/// Returns an error if the states can't be compressed
+pub fn compress_states(states: &[GameState], compression: Compression) -> io::Result<Vec<u8>> {
+    let mut writer = CompressedWriter::new(Vec::new(), compression)?;
+    for state in states {
+        writer.write_all(&state.to_bytes())?;
+    }
+
+    writer.finish()
+}
+
+/// Decompresses a batch of game states that was compressed with `compress_states`
+///
+/// # Errors
+///
+/// Returns an error if the buffer can't be decompressed, or ends in the middle of a game state
+pub fn decompress_states(bytes: &[u8]) -> io::Result<Vec<GameState>> {
+    let mut decompressed = Vec::new();
+    CompressedReader::new(bytes)?.read_to_end(&mut decompressed)?;
+
+    let mut states = Vec::new();
+    let mut rest = decompressed.as_slice();
+    while !rest.is_empty() {
+        if rest.len() < GameState::MIN_NUM_BYTES || rest.len() < GameState::get_num_bytes(rest) {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        let (state, next) = rest.split_at(GameState::get_num_bytes(rest));
+        states.push(GameState::from_bytes(state));
+        rest = next;
+    }
+
+    Ok(states)
+}
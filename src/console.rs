@@ -0,0 +1,397 @@
+//! A small command interpreter for driving an arena from text, like `ball vel 0 0 2000`
+//!
+//! `execute` runs a single command against an arena, and `Console` adds commands that need state
+//! (speed control and snapshots) and a loop for serving commands from stdin or a network stream.
+//! Type `help` for the list of commands.
+
+use crate::{
+    math::Vec3,
+    realtime::RealTimeStepper,
+    sim::{Arena, CarConfig, Team},
+    tuning::{get_mutator, InvalidMutator},
+    NoCarFound,
+};
+use core::pin::Pin;
+use cxx::UniquePtr;
+use std::{
+    error::Error,
+    fmt,
+    fmt::Write as _,
+    io::{self, BufRead, Write},
+};
+
+/// The commands that `execute` understands
+pub const HELP: &str = "\
+ball                            show the ball
+ball <pos|vel|angvel> <x y z>   set the ball's position, velocity or angular velocity
+cars                            list the cars
+car <id>                        show a car
+car <id> <pos|vel|angvel> <x y z>
+car <id> boost <amount>         set a car's state
+spawn <blue|orange> [preset]    add a car, the preset is octane by default
+remove <id>                     remove a car
+kickoff [seed]                  reset to a random kickoff
+step [ticks]                    step the arena, 1 tick by default
+pause | resume
+get <mutator>                   show a mutator
+set <mutator> <value>           change a mutator, see the tuning module";
+
+/// The extra commands that `Console` understands
+pub const CONSOLE_HELP: &str = "\
+speed [multiplier]              show or set the speed the arena should run at, from 0.01 to 100
+snapshot save <name>            keep a copy of the arena in memory
+snapshot load <name>            replace the arena with a saved copy
+snapshot list
+quit";
+
+#[derive(Debug)]
+/// Reasons that a command can't be run
+pub enum ConsoleError {
+    UnknownCommand(String),
+    /// The arguments don't match the command, with the expected usage
+    Usage(&'static str),
+    /// An argument couldn't be parsed
+    InvalidArgument(String),
+    NoCar(NoCarFound),
+    Mutator(InvalidMutator),
+    NoSnapshot(String),
+    Io(io::Error),
+}
+
+impl Error for ConsoleError {}
+impl fmt::Display for ConsoleError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownCommand(command) => write!(f, "Unknown command {command}, type help for the list of commands."),
+            Self::Usage(usage) => write!(f, "Usage: {usage}"),
+            Self::InvalidArgument(arg) => write!(f, "Invalid argument {arg}."),
+            Self::NoCar(e) => e.fmt(f),
+            Self::Mutator(e) => e.fmt(f),
+            Self::NoSnapshot(name) => write!(f, "There's no snapshot named {name}."),
+            Self::Io(e) => e.fmt(f),
+        }
+    }
+}
+
+impl From<NoCarFound> for ConsoleError {
+    #[inline]
+    fn from(e: NoCarFound) -> Self {
+        Self::NoCar(e)
+    }
+}
+
+impl From<InvalidMutator> for ConsoleError {
+    #[inline]
+    fn from(e: InvalidMutator) -> Self {
+        Self::Mutator(e)
+    }
+}
+
+impl From<io::Error> for ConsoleError {
+    #[inline]
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+fn parse<T: std::str::FromStr>(arg: &str) -> Result<T, ConsoleError> {
+    arg.parse().map_err(|_| ConsoleError::InvalidArgument(arg.to_string()))
+}
+
+fn parse_vec(args: &[&str], usage: &'static str) -> Result<Vec3, ConsoleError> {
+    match args {
+        [x, y, z] => Ok(Vec3::new(parse(x)?, parse(y)?, parse(z)?)),
+        _ => Err(ConsoleError::Usage(usage)),
+    }
+}
+
+fn format_vec(vec: Vec3) -> String {
+    format!("{:.2} {:.2} {:.2}", vec.x, vec.y, vec.z)
+}
+
+fn car_preset(name: &str) -> Option<&'static CarConfig> {
+    Some(match name {
+        "octane" => CarConfig::octane(),
+        "dominus" => CarConfig::dominus(),
+        "plank" => CarConfig::plank(),
+        "breakout" => CarConfig::breakout(),
+        "hybrid" => CarConfig::hybrid(),
+        "merc" => CarConfig::merc(),
+        _ => return None,
+    })
+}
+
+fn ball_command(mut arena: Pin<&mut Arena>, args: &[&str]) -> Result<String, ConsoleError> {
+    const USAGE: &str = "ball [pos|vel|angvel <x y z>]";
+
+    let mut ball = arena.as_mut().get_ball();
+    let Some((&field, values)) = args.split_first() else {
+        return Ok(format!(
+            "pos {}\nvel {}\nangvel {}",
+            format_vec(ball.pos),
+            format_vec(ball.vel),
+            format_vec(ball.ang_vel)
+        ));
+    };
+
+    let value = parse_vec(values, USAGE)?;
+    match field {
+        "pos" => ball.pos = value,
+        "vel" => ball.vel = value,
+        "angvel" => ball.ang_vel = value,
+        _ => return Err(ConsoleError::Usage(USAGE)),
+    }
+
+    arena.set_ball(ball);
+    Ok(String::new())
+}
+
+fn car_command(mut arena: Pin<&mut Arena>, args: &[&str]) -> Result<String, ConsoleError> {
+    const USAGE: &str = "car <id> [pos|vel|angvel <x y z> | boost <amount>]";
+
+    let Some((id, args)) = args.split_first() else {
+        return Err(ConsoleError::Usage(USAGE));
+    };
+
+    let car_id = parse(id)?;
+    if !arena.has_car(car_id) {
+        return Err(NoCarFound(car_id).into());
+    }
+
+    let mut car = arena.as_mut().get_car(car_id);
+    let Some((&field, values)) = args.split_first() else {
+        return Ok(format!(
+            "pos {}\nvel {}\nangvel {}\nboost {:.1}\non ground {}\ndemoed {}",
+            format_vec(car.pos),
+            format_vec(car.vel),
+            format_vec(car.ang_vel),
+            car.boost,
+            car.is_on_ground,
+            car.is_demoed
+        ));
+    };
+
+    match (field, values) {
+        ("boost", [amount]) => car.boost = parse(amount)?,
+        ("pos", _) => car.pos = parse_vec(values, USAGE)?,
+        ("vel", _) => car.vel = parse_vec(values, USAGE)?,
+        ("angvel", _) => car.ang_vel = parse_vec(values, USAGE)?,
+        _ => return Err(ConsoleError::Usage(USAGE)),
+    }
+
+    arena.set_car(car_id, car)?;
+    Ok(String::new())
+}
+
+/// Runs a single command against the arena, and returns its output (which may be empty)
+///
+/// # Errors
+///
+/// Returns an error if the command isn't known, or its arguments are invalid
+pub fn execute(mut arena: Pin<&mut Arena>, line: &str) -> Result<String, ConsoleError> {
+    let args = line.split_whitespace().collect::<Vec<_>>();
+    let Some((&command, args)) = args.split_first() else {
+        return Ok(String::new());
+    };
+
+    match (command, args) {
+        ("help", []) => Ok(HELP.to_string()),
+        ("ball", _) => ball_command(arena, args),
+        ("car", _) => car_command(arena, args),
+        ("cars", []) => {
+            let mut output = String::new();
+            for car_id in arena.as_mut().get_cars() {
+                let team = arena.as_mut().get_car_team(car_id);
+                let car = arena.as_mut().get_car(car_id);
+                writeln!(output, "{car_id}: {team:?} at {}", format_vec(car.pos)).unwrap();
+            }
+
+            output.pop();
+            Ok(output)
+        }
+        ("spawn", [team, preset @ ..]) if preset.len() <= 1 => {
+            let team = match *team {
+                "blue" => Team::Blue,
+                "orange" => Team::Orange,
+                _ => return Err(ConsoleError::InvalidArgument(team.to_string())),
+            };
+            let preset = preset.first().copied().unwrap_or("octane");
+            let config = car_preset(preset).ok_or_else(|| ConsoleError::InvalidArgument(preset.to_string()))?;
+
+            let car_id = arena
+                .try_add_car(team, config)
+                .map_err(|e| ConsoleError::InvalidArgument(e.to_string()))?;
+            Ok(format!("Spawned car {car_id}"))
+        }
+        ("spawn", _) => Err(ConsoleError::Usage("spawn <blue|orange> [preset]")),
+        ("remove", [id]) => {
+            arena.remove_car(parse(id)?)?;
+            Ok(String::new())
+        }
+        ("remove", _) => Err(ConsoleError::Usage("remove <id>")),
+        ("kickoff", []) => {
+            arena.reset_to_random_kickoff(None);
+            Ok(String::new())
+        }
+        ("kickoff", [seed]) => {
+            arena.reset_to_random_kickoff(Some(parse(seed)?));
+            Ok(String::new())
+        }
+        ("step", [] | [_]) => {
+            let ticks = args.first().map_or(Ok(1), |ticks| parse(ticks))?;
+            arena.as_mut().step(ticks);
+            Ok(format!("Tick {}", arena.get_tick_count()))
+        }
+        ("pause", []) => {
+            arena.set_paused(true);
+            Ok(String::new())
+        }
+        ("resume", []) => {
+            arena.set_paused(false);
+            Ok(String::new())
+        }
+        ("get", [name]) => get_mutator(&arena.get_mutator_config(), name)
+            .map(|value| value.to_string())
+            .ok_or_else(|| InvalidMutator::UnknownName(name.to_string()).into()),
+        ("set", [name, value @ ..]) if !value.is_empty() => {
+            let change = arena.set_mutator(name, &value.join(" "))?;
+            Ok(change.map(|change| change.to_string()).unwrap_or_default())
+        }
+        ("get" | "set", _) => Err(ConsoleError::Usage("get <mutator> | set <mutator> <value>")),
+        ("help" | "cars" | "step" | "pause" | "resume" | "kickoff", _) => Err(ConsoleError::Usage(HELP)),
+        _ => Err(ConsoleError::UnknownCommand(command.to_string())),
+    }
+}
+
+/// Runs commands with state that's kept between them: the speed and in-memory snapshots
+pub struct Console {
+    speed: f32,
+    snapshots: Vec<(String, UniquePtr<Arena>)>,
+    quit: bool,
+}
+
+impl fmt::Debug for Console {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Console")
+            .field("speed", &self.speed)
+            .field("snapshots", &self.snapshots.iter().map(|(name, _)| name).collect::<Vec<_>>())
+            .field("quit", &self.quit)
+            .finish()
+    }
+}
+
+impl Default for Console {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Console {
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            speed: 1.,
+            snapshots: Vec::new(),
+            quit: false,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The speed that was set with the `speed` command, for whatever is pacing the arena
+    ///
+    /// See `RealTimeStepper::set_speed`
+    pub const fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    #[inline]
+    #[must_use]
+    /// Whether the `quit` command was run
+    pub const fn quit_requested(&self) -> bool {
+        self.quit
+    }
+
+    /// Runs a single command, see `execute`
+    ///
+    /// `snapshot load` replaces the arena with a copy of the snapshot, without callbacks
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the command isn't known, or its arguments are invalid
+    pub fn execute(&mut self, arena: &mut UniquePtr<Arena>, line: &str) -> Result<String, ConsoleError> {
+        let args = line.split_whitespace().collect::<Vec<_>>();
+
+        match args.as_slice() {
+            ["help"] => Ok(format!("{HELP}\n{CONSOLE_HELP}")),
+            ["speed"] => Ok(self.speed.to_string()),
+            ["speed", speed] => {
+                let speed = parse::<f32>(speed)?;
+                if !RealTimeStepper::SPEED_RANGE.contains(&speed) {
+                    return Err(ConsoleError::InvalidArgument(speed.to_string()));
+                }
+
+                self.speed = speed;
+                Ok(String::new())
+            }
+            ["snapshot", "save", name] => {
+                let snapshot = Arena::clone(arena, false);
+                match self.snapshots.iter_mut().find(|(other, _)| other == name) {
+                    Some((_, old)) => *old = snapshot,
+                    None => self.snapshots.push((name.to_string(), snapshot)),
+                }
+
+                Ok(format!("Saved snapshot {name} at tick {}", arena.get_tick_count()))
+            }
+            ["snapshot", "load", name] => {
+                let (_, snapshot) = self
+                    .snapshots
+                    .iter()
+                    .find(|(other, _)| other == name)
+                    .ok_or_else(|| ConsoleError::NoSnapshot(name.to_string()))?;
+
+                *arena = Arena::clone(snapshot, false);
+                Ok(format!("Loaded snapshot {name} at tick {}", arena.get_tick_count()))
+            }
+            ["snapshot", "list"] => Ok(self
+                .snapshots
+                .iter()
+                .map(|(name, snapshot)| format!("{name}: tick {}", snapshot.get_tick_count()))
+                .collect::<Vec<_>>()
+                .join("\n")),
+            ["speed" | "snapshot", ..] => Err(ConsoleError::Usage(CONSOLE_HELP)),
+            ["quit"] => {
+                self.quit = true;
+                Ok(String::new())
+            }
+            _ => execute(arena.pin_mut(), line),
+        }
+    }
+
+    /// Runs commands from the reader, one per line, and writes their output (or errors) to the writer
+    ///
+    /// Stops at the end of the input or after `quit`, so it can be wired to stdin or a network stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading or writing fails
+    pub fn run<R: BufRead, W: Write>(&mut self, arena: &mut UniquePtr<Arena>, reader: R, mut writer: W) -> io::Result<()> {
+        for line in reader.lines() {
+            match self.execute(arena, &line?) {
+                Ok(output) if output.is_empty() => {}
+                Ok(output) => writeln!(writer, "{output}")?,
+                Err(e) => writeln!(writer, "Error: {e}")?,
+            }
+            writer.flush()?;
+
+            if self.quit {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
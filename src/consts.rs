@@ -112,36 +112,36 @@ pub const CAR_SPAWN_LOCATION_AMOUNT: i32 = 5;
 pub const CAR_SPAWN_LOCATION_AMOUNT_HEATSEEKER: i32 = 4;
 pub const CAR_RESPAWN_LOCATION_AMOUNT: i32 = 4;
 pub const CAR_SPAWN_LOCATIONS_SOCCAR: [CarSpawnPos; CAR_SPAWN_LOCATION_AMOUNT as usize] = [
-    CarSpawnPos::new(-2560., -2560., FRAC_PI_4 * 1.),
-    CarSpawnPos::new(-2560., -2560., FRAC_PI_4 * 3.),
-    CarSpawnPos::new(-3840., -3840., FRAC_PI_4 * 2.),
-    CarSpawnPos::new(-3840., -3840., FRAC_PI_4 * 2.),
-    CarSpawnPos::new(-4608., -4608., FRAC_PI_4 * 2.),
+    CarSpawnPos::new(-2048., -2560., FRAC_PI_4 * 1.),
+    CarSpawnPos::new(2048., -2560., FRAC_PI_4 * 3.),
+    CarSpawnPos::new(-256., -3840., FRAC_PI_4 * 2.),
+    CarSpawnPos::new(256., -3840., FRAC_PI_4 * 2.),
+    CarSpawnPos::new(0., -4608., FRAC_PI_4 * 2.),
 ];
 pub const CAR_SPAWN_LOCATIONS_HOOPS: [CarSpawnPos; CAR_SPAWN_LOCATION_AMOUNT as usize] = [
-    CarSpawnPos::new(-3072., -3072., FRAC_PI_4 * 2.),
-    CarSpawnPos::new(-3072., -3072., FRAC_PI_4 * 2.),
-    CarSpawnPos::new(-2816., -2816., FRAC_PI_4 * 2.),
-    CarSpawnPos::new(-2816., -2816., FRAC_PI_4 * 2.),
-    CarSpawnPos::new(-3200., -3200., FRAC_PI_4 * 2.),
+    CarSpawnPos::new(-1536., -3072., FRAC_PI_4 * 2.),
+    CarSpawnPos::new(1536., -3072., FRAC_PI_4 * 2.),
+    CarSpawnPos::new(-256., -2816., FRAC_PI_4 * 2.),
+    CarSpawnPos::new(256., -2816., FRAC_PI_4 * 2.),
+    CarSpawnPos::new(0., -3200., FRAC_PI_4 * 2.),
 ];
 pub const CAR_SPAWN_LOCATIONS_HEATSEEKER: [CarSpawnPos; CAR_SPAWN_LOCATION_AMOUNT_HEATSEEKER as usize] = [
-    CarSpawnPos::new(-4620., -4620., FRAC_PI_2),
-    CarSpawnPos::new(-4620., -4620., FRAC_PI_2),
-    CarSpawnPos::new(-4620., -4620., FRAC_PI_2),
-    CarSpawnPos::new(-4620., -4620., FRAC_PI_2),
+    CarSpawnPos::new(-1000., -4620., FRAC_PI_2),
+    CarSpawnPos::new(1000., -4620., FRAC_PI_2),
+    CarSpawnPos::new(-2000., -4620., FRAC_PI_2),
+    CarSpawnPos::new(2000., -4620., FRAC_PI_2),
 ];
 pub const CAR_RESPAWN_LOCATIONS_SOCCAR: [CarSpawnPos; CAR_RESPAWN_LOCATION_AMOUNT as usize] = [
-    CarSpawnPos::new(-4608., -4608., FRAC_PI_2),
-    CarSpawnPos::new(-4608., -4608., FRAC_PI_2),
-    CarSpawnPos::new(-4608., -4608., FRAC_PI_2),
-    CarSpawnPos::new(-4608., -4608., FRAC_PI_2),
+    CarSpawnPos::new(-2304., -4608., FRAC_PI_2),
+    CarSpawnPos::new(-2688., -4608., FRAC_PI_2),
+    CarSpawnPos::new(2304., -4608., FRAC_PI_2),
+    CarSpawnPos::new(2688., -4608., FRAC_PI_2),
 ];
 pub const CAR_RESPAWN_LOCATIONS_HOOPS: [CarSpawnPos; CAR_RESPAWN_LOCATION_AMOUNT as usize] = [
-    CarSpawnPos::new(-3072., -3072., FRAC_PI_2),
-    CarSpawnPos::new(-3072., -3072., FRAC_PI_2),
-    CarSpawnPos::new(-3072., -3072., FRAC_PI_2),
-    CarSpawnPos::new(-3072., -3072., FRAC_PI_2),
+    CarSpawnPos::new(-1920., -3072., FRAC_PI_2),
+    CarSpawnPos::new(-1152., -3072., FRAC_PI_2),
+    CarSpawnPos::new(1920., -3072., FRAC_PI_2),
+    CarSpawnPos::new(1152., -3072., FRAC_PI_2),
 ];
 pub const STEER_ANGLE_FROM_SPEED_CURVE: LinearPieceCurve<6> = LinearPieceCurve {
     value_mappings: [
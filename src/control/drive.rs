@@ -0,0 +1,147 @@
+use crate::{
+    analysis::ground_accel,
+    consts,
+    math::Vec3,
+    sim::{CarControls, CarState},
+};
+
+/// Deceleration (uu/s^2) from braking, by throttling against the direction the car is moving in
+pub const BRAKE_ACCEL: f32 = 3500.;
+/// Deceleration (uu/s^2) from coasting, with no throttle at all
+pub const COAST_ACCEL: f32 = BRAKE_ACCEL * consts::COASTING_BRAKE_FACTOR;
+/// The top speed (uu/s) that can be reached by throttling without boosting
+pub const THROTTLE_MAX_SPEED: f32 = 1410.;
+
+/// How strongly `steer_toward` steers per radian of angle to the target
+const STEER_GAIN: f32 = 3.;
+/// How strongly `steer_toward` counter-steers against the car's yaw rate (rad/s), to avoid oscillating
+const STEER_DAMPING: f32 = 0.2;
+/// How quickly (in seconds) `speed_controls` tries to reach the target speed
+const SPEED_RESPONSE_TIME: f32 = 0.1;
+
+/// The minimum angle (in radians) to the target for `powerslide_toward` to start a powerslide
+pub const POWERSLIDE_MIN_ANGLE: f32 = 1.2;
+/// The angle (in radians) to the target at which `powerslide_toward` lets go of the handbrake
+pub const POWERSLIDE_RELEASE_ANGLE: f32 = 0.4;
+/// The minimum forward speed (uu/s) for `powerslide_toward` to powerslide, a slower car can just turn
+pub const POWERSLIDE_MIN_SPEED: f32 = 600.;
+
+#[inline]
+#[must_use]
+/// Returns the angle (in radians) from the car's forward direction to the target, ignoring height
+///
+/// Positive angles are to the car's right, like positive steering.
+pub fn angle_to(car_state: &CarState, target: Vec3) -> f32 {
+    let delta = target - car_state.pos;
+    delta.dot(car_state.rot_mat.right).atan2(delta.dot(car_state.rot_mat.forward))
+}
+
+#[must_use]
+/// Returns the steer input that turns a car on the ground towards the target
+///
+/// The car steers proportionally to the angle to the target, with full lock from about 20 degrees,
+/// and counter-steers against its current turning rate so that it doesn't overshoot.
+pub fn steer_toward(car_state: &CarState, target: Vec3) -> f32 {
+    let yaw_rate = car_state.ang_vel.dot(car_state.rot_mat.up);
+    (angle_to(car_state, target) * STEER_GAIN - yaw_rate * STEER_DAMPING).clamp(-1., 1.)
+}
+
+#[must_use]
+/// Returns the `(throttle, boost)` inputs that bring the car's forward speed to `target_speed`
+///
+/// This follows RocketSim's ground model: throttle accelerates along `analysis::ground_accel`,
+/// releasing the throttle coasts at `COAST_ACCEL`, and throttling backwards brakes at `BRAKE_ACCEL`.
+/// Boost is only used for speeds that can't be reached (quickly enough) with throttle alone.
+pub fn speed_controls(car_state: &CarState, target_speed: f32) -> (f32, bool) {
+    let speed = car_state.vel.dot(car_state.rot_mat.forward);
+    let target_speed = target_speed.clamp(0., consts::CAR_MAX_SPEED);
+    let wanted = (target_speed - speed) / SPEED_RESPONSE_TIME;
+
+    if wanted >= 0. {
+        let throttle_accel = ground_accel(speed, false);
+        let boost = car_state.boost > 0. && (target_speed > THROTTLE_MAX_SPEED || wanted > throttle_accel * 2.);
+
+        // there's no drag on the ground, so only a tiny bit of throttle is needed to keep the speed up
+        let throttle = if throttle_accel > 0. {
+            (wanted / throttle_accel).clamp(consts::THROTTLE_DEADZONE * 2., 1.)
+        } else {
+            1.
+        };
+
+        (if boost { 1. } else { throttle }, boost)
+    } else if -wanted < COAST_ACCEL {
+        // coasting would slow down too much, so keep the throttle on a little
+        (consts::THROTTLE_DEADZONE * 2., false)
+    } else if -wanted < BRAKE_ACCEL {
+        (0., false)
+    } else {
+        (-1., false)
+    }
+}
+
+#[must_use]
+/// Returns the speed (uu/s) that the car has to drive at to reach the target in `time` seconds
+///
+/// The path is approximated by a straight line, see `analysis::drive_time` for a more accurate estimate.
+/// Returns the max car speed if the time has already run out.
+pub fn arrival_speed(car_state: &CarState, target: Vec3, time: f32) -> f32 {
+    let delta = target - car_state.pos;
+    let distance = Vec3::new(delta.x, delta.y, 0.).length();
+
+    if time > 0. {
+        (distance / time).min(consts::CAR_MAX_SPEED)
+    } else {
+        consts::CAR_MAX_SPEED
+    }
+}
+
+#[must_use]
+/// Returns the controls that drive the car to the target, arriving in `time` seconds
+///
+/// Combines `steer_toward`, `arrival_speed` and `speed_controls`.
+/// The car slows down for sharp turns so it doesn't circle around the target.
+pub fn arrive(car_state: &CarState, target: Vec3, time: f32) -> CarControls {
+    let angle = angle_to(car_state, target).abs();
+    let mut target_speed = arrival_speed(car_state, target, time);
+
+    if angle > POWERSLIDE_RELEASE_ANGLE {
+        // the tighter the turn, the slower the car has to go to make it
+        target_speed = target_speed.min(consts::CAR_MAX_SPEED * (1. - angle / std::f32::consts::PI).max(0.3));
+    }
+
+    let (throttle, boost) = speed_controls(car_state, target_speed);
+
+    CarControls {
+        throttle,
+        steer: steer_toward(car_state, target),
+        boost: boost && angle < POWERSLIDE_RELEASE_ANGLE,
+        ..Default::default()
+    }
+}
+
+#[must_use]
+/// Returns the controls for a powerslide turn towards the target
+///
+/// The handbrake is held when the car is fast enough and the angle to the target is above
+/// `POWERSLIDE_MIN_ANGLE`, and is kept held (if the car is already sliding) until the angle is
+/// below `POWERSLIDE_RELEASE_ANGLE`. The throttle is kept on so the car keeps its speed out of the turn.
+pub fn powerslide_toward(car_state: &CarState, target: Vec3) -> CarControls {
+    let angle = angle_to(car_state, target);
+    let speed = car_state.vel.dot(car_state.rot_mat.forward);
+    let is_sliding = car_state.handbrake_val > 0.;
+
+    let handbrake = car_state.is_on_ground
+        && speed > POWERSLIDE_MIN_SPEED
+        && (angle.abs() > POWERSLIDE_MIN_ANGLE || (is_sliding && angle.abs() > POWERSLIDE_RELEASE_ANGLE));
+
+    CarControls {
+        throttle: 1.,
+        steer: if handbrake {
+            angle.signum()
+        } else {
+            steer_toward(car_state, target)
+        },
+        handbrake,
+        ..Default::default()
+    }
+}
@@ -0,0 +1,13 @@
+//! Low-level controllers that turn a desired car motion into `CarControls`,
+//! for use inside of `input::Controller` implementations
+
+mod drive;
+mod orient;
+mod recovery;
+
+pub use drive::{
+    angle_to, arrival_speed, arrive, powerslide_toward, speed_controls, steer_toward, BRAKE_ACCEL, COAST_ACCEL,
+    POWERSLIDE_MIN_ANGLE, POWERSLIDE_MIN_SPEED, POWERSLIDE_RELEASE_ANGLE, THROTTLE_MAX_SPEED,
+};
+pub use orient::{air_angular_accel, orient_toward, orientation_error, ORIENT_RESPONSE_TIME};
+pub use recovery::recover;
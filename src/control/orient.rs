@@ -0,0 +1,147 @@
+use crate::{
+    consts,
+    math::{RotMat, Vec3},
+    sim::CarState,
+};
+use std::f32::consts::PI;
+
+/// How quickly (in seconds) `orient_toward` tries to reach the angular velocity it wants
+pub const ORIENT_RESPONSE_TIME: f32 = 0.05;
+/// Fraction of the maximum angular deceleration that's planned for when slowing down,
+/// so the car doesn't overshoot when the damping is a bit off
+const BRAKE_MARGIN: f32 = 0.8;
+
+/// The axes that the pitch, yaw and roll inputs torque around, in the same order as `CAR_AIR_CONTROL_TORQUE`
+fn torque_axes(rot_mat: &RotMat) -> [Vec3; 3] {
+    [-rot_mat.right, rot_mat.up, -rot_mat.forward]
+}
+
+#[must_use]
+/// Returns the angular acceleration (rad/s^2) that the given air control inputs produce, using RocketSim's air torque model
+///
+/// Pitch and yaw are damped by how far their inputs are from full, and roll is always damped.
+/// This ignores the reduced pitch torque while flipping.
+pub fn air_angular_accel(car_state: &CarState, pitch: f32, yaw: f32, roll: f32) -> Vec3 {
+    let axes = torque_axes(&car_state.rot_mat);
+    let inputs = [pitch, yaw, roll];
+    let torques = [
+        consts::CAR_AIR_CONTROL_TORQUE.x,
+        consts::CAR_AIR_CONTROL_TORQUE.y,
+        consts::CAR_AIR_CONTROL_TORQUE.z,
+    ];
+    let dampings = [
+        consts::CAR_AIR_CONTROL_DAMPING.x * (1. - pitch.abs()),
+        consts::CAR_AIR_CONTROL_DAMPING.y * (1. - yaw.abs()),
+        consts::CAR_AIR_CONTROL_DAMPING.z,
+    ];
+
+    (0..3).fold(Vec3::ZERO, |accel, i| {
+        let ang_vel = car_state.ang_vel.dot(axes[i]);
+        accel + axes[i] * ((torques[i] * inputs[i] - dampings[i] * ang_vel) * consts::CAR_TORQUE_SCALE)
+    })
+}
+
+/// Converts a rotation matrix (given as dot products between the basis vectors) to a rotation vector
+fn rotation_vector(m: [[f32; 3]; 3]) -> Vec3 {
+    let cos = ((m[0][0] + m[1][1] + m[2][2] - 1.) / 2.).clamp(-1., 1.);
+    let angle = cos.acos();
+    let skew = Vec3::new(m[2][1] - m[1][2], m[0][2] - m[2][0], m[1][0] - m[0][1]);
+
+    if angle < 1e-4 {
+        return skew * 0.5;
+    }
+
+    let sin = angle.sin();
+    if sin > 1e-3 {
+        return skew * (angle / (2. * sin));
+    }
+
+    // close to a half turn, so take the axis from the diagonal
+    let diag = [m[0][0], m[1][1], m[2][2]];
+    let k = (0..3).max_by(|&a, &b| diag[a].total_cmp(&diag[b])).unwrap_or_default();
+    let mut axis = [0.; 3];
+    axis[k] = ((diag[k] + 1.) / 2.).max(0.).sqrt();
+    for i in (0..3).filter(|&i| i != k) {
+        axis[i] = (m[k][i] + m[i][k]) / (4. * axis[k]);
+    }
+
+    Vec3::new(axis[0], axis[1], axis[2]).normalize_or_zero() * PI
+}
+
+#[must_use]
+/// Returns the rotation (in radians) that's needed around the pitch, yaw and roll torque axes
+/// to go from the current orientation to the target orientation, as `(pitch, yaw, roll)` in `x`, `y` and `z`
+pub fn orientation_error(rot_mat: &RotMat, target: &RotMat) -> Vec3 {
+    let current = [rot_mat.forward, rot_mat.right, rot_mat.up];
+    let target = [target.forward, target.right, target.up];
+
+    // the rotation from the current orientation to the target, in the car's local coordinates
+    let mut m = [[0.; 3]; 3];
+    for (i, row) in m.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value = current[i].dot(target[j]);
+        }
+    }
+
+    // local coordinates are (forward, right, up), the torque axes are (-right, up, -forward)
+    let local = rotation_vector(m);
+    Vec3::new(-local.y, local.z, -local.x)
+}
+
+/// Finds the input for one axis that gets closest to the wanted angular acceleration
+fn axis_input(error: f32, ang_vel: f32, torque: f32, damping: f32, always_damped: bool) -> f32 {
+    let max_accel = torque * consts::CAR_TORQUE_SCALE;
+
+    // the fastest the car can be rotating and still stop at the target in time
+    let desired_vel = (error.signum() * (2. * BRAKE_MARGIN * max_accel * error.abs()).sqrt())
+        .clamp(-consts::CAR_MAX_ANG_SPEED, consts::CAR_MAX_ANG_SPEED);
+    let wanted = (desired_vel - ang_vel) / ORIENT_RESPONSE_TIME / consts::CAR_TORQUE_SCALE;
+
+    let accel = |input: f32| {
+        let damping = if always_damped {
+            damping
+        } else {
+            damping * (1. - input.abs())
+        };
+        torque * input - damping * ang_vel
+    };
+
+    let candidates = if always_damped {
+        [((wanted + damping * ang_vel) / torque).clamp(-1., 1.), 0., 0., 0., 0.]
+    } else {
+        // the damping depends on the input, so solve for each sign of the input separately
+        [
+            ((wanted + damping * ang_vel) / (torque + damping * ang_vel)).clamp(0., 1.),
+            ((wanted + damping * ang_vel) / (torque - damping * ang_vel)).clamp(-1., 0.),
+            0.,
+            1.,
+            -1.,
+        ]
+    };
+
+    candidates
+        .into_iter()
+        .filter(|input| input.is_finite())
+        .min_by(|&a, &b| (accel(a) - wanted).abs().total_cmp(&(accel(b) - wanted).abs()))
+        .unwrap_or_default()
+}
+
+#[must_use]
+/// Returns the `(pitch, yaw, roll)` inputs that rotate a car in the air to face `target_forward`,
+/// with its roof as close to `target_up` as possible
+///
+/// Each axis is controlled separately, inverting RocketSim's air torque model (see `air_angular_accel`)
+/// to reach the angular velocity that would bring the car to a stop at the target.
+/// Call it every tick, it doesn't keep any state.
+pub fn orient_toward(car_state: &CarState, target_forward: Vec3, target_up: Vec3) -> (f32, f32, f32) {
+    let error = orientation_error(&car_state.rot_mat, &RotMat::look_at(target_forward, target_up));
+    let axes = torque_axes(&car_state.rot_mat);
+    let torque = consts::CAR_AIR_CONTROL_TORQUE;
+    let damping = consts::CAR_AIR_CONTROL_DAMPING;
+
+    let pitch = axis_input(error.x, car_state.ang_vel.dot(axes[0]), torque.x, damping.x, false);
+    let yaw = axis_input(error.y, car_state.ang_vel.dot(axes[1]), torque.y, damping.y, false);
+    let roll = axis_input(error.z, car_state.ang_vel.dot(axes[2]), torque.z, damping.z, true);
+
+    (pitch, yaw, roll)
+}
@@ -0,0 +1,40 @@
+use super::orient::orient_toward;
+use crate::{
+    analysis::predict_landing_in,
+    field::Field,
+    math::Vec3,
+    sim::{CarControls, CarState},
+};
+
+#[must_use]
+/// Returns the controls that turn an airborne car wheels-down towards the surface it's going to land on,
+/// which is found with `analysis::predict_landing_in`
+///
+/// The car is rotated so that its roof faces away from the landing surface (the floor, a wall or the ceiling),
+/// and so that it faces the direction it's moving in along that surface, so it keeps its speed after landing.
+/// If the landing can't be predicted, the car is turned wheels-down towards the floor.
+/// Cars on the ground get no inputs.
+pub fn recover(car_state: &CarState, field: &Field) -> CarControls {
+    if car_state.is_on_ground {
+        return CarControls::default();
+    }
+
+    let normal = predict_landing_in(car_state, field).map_or(Vec3::Z, |landing| landing.surface_normal);
+
+    // face along the surface, in the direction of travel if there is one
+    let along = |dir: Vec3| dir - normal * dir.dot(normal);
+    let mut forward = along(car_state.vel);
+    if forward.length() < 100. {
+        forward = along(car_state.rot_mat.forward);
+    }
+
+    let (pitch, yaw, roll) = orient_toward(car_state, forward, normal);
+
+    CarControls {
+        pitch,
+        yaw,
+        roll,
+        throttle: 1.,
+        ..Default::default()
+    }
+}
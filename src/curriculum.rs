@@ -0,0 +1,240 @@
+//! Curriculum training: a sequence of stages that the arena advances through as the agent improves
+//!
+//! Each stage generates the starting state of every episode (e.g. with the generators in `scenario`),
+//! and has a promotion criterion that's checked against the episodes played in it.
+//! Stages are only advanced when the arena is reset, so an episode is never changed mid-way.
+//!
+//! ```no_run
+//! use rand::{rngs::StdRng, SeedableRng};
+//! use rocketsim_rs::{
+//!     curriculum::{Curriculum, Promotion, Stage},
+//!     scenario::{random_aerial, random_dribble},
+//!     sim::{Arena, CarConfig, Team},
+//! };
+//!
+//! rocketsim_rs::init(None, true);
+//!
+//! let mut arena = Arena::default_standard();
+//! let _ = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+//!
+//! let mut curriculum = Curriculum::new(StdRng::seed_from_u64(0))
+//!     .with_stage(Stage::new(
+//!         "easy dribbles",
+//!         |rng, template| random_dribble(rng, template, 0.1),
+//!         Promotion::SuccessRate { rate: 0.8, window: 100 },
+//!     ))
+//!     .with_stage(Stage::new(
+//!         "aerials",
+//!         |rng, template| random_aerial(rng, template, 0.5),
+//!         Promotion::Never,
+//!     ));
+//!
+//! loop {
+//!     curriculum.reset(arena.pin_mut()).unwrap();
+//!     // ... play the episode ...
+//!     # let success = true;
+//!     curriculum.end_episode(success);
+//! }
+//! ```
+
+use crate::{sim::Arena, GameState, NoCarFound};
+use rand::Rng;
+use std::pin::Pin;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+/// The episodes that were played in a stage
+pub struct StageStats {
+    /// Whether each episode was a success, in the order they were played
+    pub results: Vec<bool>,
+}
+
+impl StageStats {
+    #[inline]
+    #[must_use]
+    pub fn episodes(&self) -> usize {
+        self.results.len()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn successes(&self) -> usize {
+        self.results.iter().filter(|&&success| success).count()
+    }
+
+    #[must_use]
+    /// The success rate of the last `window` episodes, or `None` if fewer than `window` episodes have been played
+    pub fn success_rate(&self, window: usize) -> Option<f32> {
+        if window == 0 || self.results.len() < window {
+            return None;
+        }
+
+        let recent = &self.results[self.results.len() - window..];
+        Some(recent.iter().filter(|&&success| success).count() as f32 / window as f32)
+    }
+}
+
+/// When a stage is done, and the next one should be started
+pub enum Promotion {
+    /// Stay in the stage forever, for the last stage
+    Never,
+    /// After this many episodes, regardless of how they went
+    Episodes(usize),
+    /// Once the success rate over the last `window` episodes is at least `rate` (`0..=1`)
+    SuccessRate { rate: f32, window: usize },
+    /// Once the function returns true
+    Custom(Box<dyn Fn(&StageStats) -> bool>),
+}
+
+impl Promotion {
+    #[must_use]
+    pub fn is_met(&self, stats: &StageStats) -> bool {
+        match self {
+            Self::Never => false,
+            Self::Episodes(episodes) => stats.episodes() >= *episodes,
+            Self::SuccessRate { rate, window } => {
+                stats.success_rate(*window).is_some_and(|success_rate| success_rate >= *rate)
+            }
+            Self::Custom(is_met) => is_met(stats),
+        }
+    }
+}
+
+type Generator<R> = Box<dyn FnMut(&mut R, &GameState) -> GameState>;
+
+/// A stage of a curriculum
+pub struct Stage<R: Rng> {
+    pub name: String,
+    /// Creates the starting state of an episode from the arena's current state
+    pub generator: Generator<R>,
+    pub promotion: Promotion,
+}
+
+impl<R: Rng> Stage<R> {
+    #[inline]
+    #[must_use]
+    pub fn new(
+        name: impl Into<String>,
+        generator: impl FnMut(&mut R, &GameState) -> GameState + 'static,
+        promotion: Promotion,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            generator: Box::new(generator),
+            promotion,
+        }
+    }
+}
+
+/// Advances through stages as their promotion criteria are met
+pub struct Curriculum<R: Rng> {
+    stages: Vec<Stage<R>>,
+    stats: Vec<StageStats>,
+    current: usize,
+    rng: R,
+}
+
+impl<R: Rng> Curriculum<R> {
+    #[inline]
+    #[must_use]
+    pub const fn new(rng: R) -> Self {
+        Self {
+            stages: Vec::new(),
+            stats: Vec::new(),
+            current: 0,
+            rng,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn with_stage(mut self, stage: Stage<R>) -> Self {
+        self.add_stage(stage);
+        self
+    }
+
+    /// Adds a stage after all of the current ones
+    pub fn add_stage(&mut self, stage: Stage<R>) {
+        self.stages.push(stage);
+        self.stats.push(StageStats::default());
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn stages(&self) -> &[Stage<R>] {
+        &self.stages
+    }
+
+    #[inline]
+    #[must_use]
+    /// The index of the current stage
+    pub const fn stage_index(&self) -> usize {
+        self.current
+    }
+
+    #[inline]
+    #[must_use]
+    /// The current stage, or `None` if no stages have been added
+    pub fn stage(&self) -> Option<&Stage<R>> {
+        self.stages.get(self.current)
+    }
+
+    #[inline]
+    #[must_use]
+    /// The episodes that were played in each stage, in the same order as the stages
+    pub fn stats(&self) -> &[StageStats] {
+        &self.stats
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns true if the current stage is the last one
+    pub fn is_last_stage(&self) -> bool {
+        self.current + 1 >= self.stages.len()
+    }
+
+    /// Records the result of the episode that was played in the current stage
+    pub fn end_episode(&mut self, success: bool) {
+        if let Some(stats) = self.stats.get_mut(self.current) {
+            stats.results.push(success);
+        }
+    }
+
+    /// Moves to the next stage if the current one's promotion criterion is met, and returns true if it did
+    ///
+    /// This is called by `reset`, so it usually doesn't need to be called directly
+    pub fn advance(&mut self) -> bool {
+        if self.is_last_stage() || !self.stages[self.current].promotion.is_met(&self.stats[self.current]) {
+            return false;
+        }
+
+        self.current += 1;
+        true
+    }
+
+    /// Goes back to a stage, e.g. to resume training from a checkpoint
+    ///
+    /// The stats of the stages are kept
+    pub fn set_stage_index(&mut self, index: usize) {
+        self.current = index.min(self.stages.len().saturating_sub(1));
+    }
+
+    /// Advances the stage if needed, then puts the arena into the starting state of a new episode
+    ///
+    /// Returns the index of the stage that the episode is in.
+    /// The arena isn't changed if no stages have been added.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the generator removed a car from the game state, or added a car to it
+    pub fn reset(&mut self, mut arena: Pin<&mut Arena>) -> Result<usize, NoCarFound> {
+        self.advance();
+
+        if let Some(stage) = self.stages.get_mut(self.current) {
+            let template = arena.as_mut().get_game_state();
+            let state = (stage.generator)(&mut self.rng, &template);
+            arena.set_game_state(&state)?;
+        }
+
+        Ok(self.current)
+    }
+}
@@ -0,0 +1,327 @@
+//! Arrow record batches (and Parquet files, with the `parquet` feature) of per-tick states and actions,
+//! for offline RL and behavior cloning datasets
+//!
+//! # Schema
+//!
+//! There is one row per car per tick, so the ball columns are repeated for every car.
+//! All of the columns are non-nullable.
+//!
+//! | Column | Type | Description |
+//! | --- | --- | --- |
+//! | `tick_count` | `UInt64` | The tick count of the game state |
+//! | `car_id` | `UInt32` | |
+//! | `team` | `UInt8` | 0 for blue, 1 for orange |
+//! | `ball_pos_{x,y,z}` | `Float32` | |
+//! | `ball_vel_{x,y,z}` | `Float32` | |
+//! | `ball_ang_vel_{x,y,z}` | `Float32` | |
+//! | `pos_{x,y,z}` | `Float32` | The car's position |
+//! | `vel_{x,y,z}` | `Float32` | |
+//! | `ang_vel_{x,y,z}` | `Float32` | |
+//! | `forward_{x,y,z}` | `Float32` | The forward axis of the car's rotation matrix |
+//! | `up_{x,y,z}` | `Float32` | The up axis of the car's rotation matrix |
+//! | `boost` | `Float32` | The car's boost amount, from 0 to 100 |
+//! | `throttle`, `steer`, `pitch`, `yaw`, `roll` | `Float32` | The car's controls |
+//! | `is_on_ground`, `has_flipped`, `is_demoed` | `Boolean` | |
+//! | `boost_pressed`, `jump`, `handbrake` | `Boolean` | The car's controls |
+//!
+//! The states are the states after the tick, and the controls are the ones that the car used during that tick
+//! (`CarState::last_controls`). Shift the controls back by a row per car to pair each state with the next action.
+
+use crate::{
+    sim::{BallState, CarState, Team},
+    GameState,
+};
+use arrow_array::{ArrayRef, BooleanArray, Float32Array, RecordBatch, UInt32Array, UInt64Array, UInt8Array};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef};
+use std::sync::{Arc, OnceLock};
+
+const FLOAT_COLUMNS: [&str; 30] = [
+    "ball_pos_x",
+    "ball_pos_y",
+    "ball_pos_z",
+    "ball_vel_x",
+    "ball_vel_y",
+    "ball_vel_z",
+    "ball_ang_vel_x",
+    "ball_ang_vel_y",
+    "ball_ang_vel_z",
+    "pos_x",
+    "pos_y",
+    "pos_z",
+    "vel_x",
+    "vel_y",
+    "vel_z",
+    "ang_vel_x",
+    "ang_vel_y",
+    "ang_vel_z",
+    "forward_x",
+    "forward_y",
+    "forward_z",
+    "up_x",
+    "up_y",
+    "up_z",
+    "boost",
+    "throttle",
+    "steer",
+    "pitch",
+    "yaw",
+    "roll",
+];
+
+const BOOL_COLUMNS: [&str; 6] = [
+    "is_on_ground",
+    "has_flipped",
+    "is_demoed",
+    "boost_pressed",
+    "jump",
+    "handbrake",
+];
+
+fn float_values(ball: &BallState, car: &CarState) -> [f32; FLOAT_COLUMNS.len()] {
+    let controls = car.last_controls;
+
+    [
+        ball.pos.x,
+        ball.pos.y,
+        ball.pos.z,
+        ball.vel.x,
+        ball.vel.y,
+        ball.vel.z,
+        ball.ang_vel.x,
+        ball.ang_vel.y,
+        ball.ang_vel.z,
+        car.pos.x,
+        car.pos.y,
+        car.pos.z,
+        car.vel.x,
+        car.vel.y,
+        car.vel.z,
+        car.ang_vel.x,
+        car.ang_vel.y,
+        car.ang_vel.z,
+        car.rot_mat.forward.x,
+        car.rot_mat.forward.y,
+        car.rot_mat.forward.z,
+        car.rot_mat.up.x,
+        car.rot_mat.up.y,
+        car.rot_mat.up.z,
+        car.boost,
+        controls.throttle,
+        controls.steer,
+        controls.pitch,
+        controls.yaw,
+        controls.roll,
+    ]
+}
+
+fn bool_values(car: &CarState) -> [bool; BOOL_COLUMNS.len()] {
+    let controls = car.last_controls;
+
+    [
+        car.is_on_ground,
+        car.has_flipped,
+        car.is_demoed,
+        controls.boost,
+        controls.jump,
+        controls.handbrake,
+    ]
+}
+
+#[must_use]
+/// The schema of the record batches, see the module documentation
+pub fn schema() -> SchemaRef {
+    static SCHEMA: OnceLock<SchemaRef> = OnceLock::new();
+
+    SCHEMA
+        .get_or_init(|| {
+            let fields = [
+                Field::new("tick_count", DataType::UInt64, false),
+                Field::new("car_id", DataType::UInt32, false),
+                Field::new("team", DataType::UInt8, false),
+            ]
+            .into_iter()
+            .chain(FLOAT_COLUMNS.iter().map(|name| Field::new(*name, DataType::Float32, false)))
+            .chain(BOOL_COLUMNS.iter().map(|name| Field::new(*name, DataType::Boolean, false)))
+            .collect::<Vec<_>>();
+
+            Arc::new(Schema::new(fields))
+        })
+        .clone()
+}
+
+#[derive(Clone, Debug)]
+/// Collects game states into Arrow record batches
+pub struct BatchBuilder {
+    tick_counts: Vec<u64>,
+    car_ids: Vec<u32>,
+    teams: Vec<u8>,
+    floats: Vec<Vec<f32>>,
+    bools: Vec<Vec<bool>>,
+}
+
+impl BatchBuilder {
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            tick_counts: Vec::new(),
+            car_ids: Vec::new(),
+            teams: Vec::new(),
+            floats: vec![Vec::new(); FLOAT_COLUMNS.len()],
+            bools: vec![Vec::new(); BOOL_COLUMNS.len()],
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The number of rows that have been added since the last batch was finished
+    pub fn len(&self) -> usize {
+        self.tick_counts.len()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tick_counts.is_empty()
+    }
+
+    /// Adds a row for every car in the game state
+    pub fn push(&mut self, game_state: &GameState) {
+        for car in &game_state.cars {
+            self.tick_counts.push(game_state.tick_count);
+            self.car_ids.push(car.id);
+            self.teams.push(u8::from(car.team == Team::Orange));
+
+            for (column, value) in self.floats.iter_mut().zip(float_values(&game_state.ball, &car.state)) {
+                column.push(value);
+            }
+
+            for (column, value) in self.bools.iter_mut().zip(bool_values(&car.state)) {
+                column.push(value);
+            }
+        }
+    }
+
+    /// Turns all of the rows that have been added into a record batch, leaving the builder empty
+    ///
+    /// # Errors
+    ///
+    /// Never, in practice; the columns always match the schema
+    pub fn finish(&mut self) -> Result<RecordBatch, ArrowError> {
+        let builder = std::mem::take(self);
+
+        let columns = [
+            Arc::new(UInt64Array::from(builder.tick_counts)) as ArrayRef,
+            Arc::new(UInt32Array::from(builder.car_ids)),
+            Arc::new(UInt8Array::from(builder.teams)),
+        ]
+        .into_iter()
+        .chain(
+            builder
+                .floats
+                .into_iter()
+                .map(|column| Arc::new(Float32Array::from(column)) as ArrayRef),
+        )
+        .chain(
+            builder
+                .bools
+                .into_iter()
+                .map(|column| Arc::new(BooleanArray::from(column)) as ArrayRef),
+        )
+        .collect();
+
+        RecordBatch::try_new(schema(), columns)
+    }
+}
+
+impl Default for BatchBuilder {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "parquet")]
+pub use parquet_writer::{DatasetWriter, ROWS_PER_GROUP};
+
+#[cfg(feature = "parquet")]
+mod parquet_writer {
+    use super::{schema, BatchBuilder};
+    use crate::GameState;
+    use parquet::{arrow::ArrowWriter, errors::ParquetError};
+    use std::{
+        fs::File,
+        io::{BufWriter, Write},
+        path::Path,
+    };
+
+    /// The number of rows in each Parquet row group
+    pub const ROWS_PER_GROUP: usize = 64 * 1024;
+
+    /// Writes game states to a Parquet file, see the module documentation for the schema
+    pub struct DatasetWriter<W: Write + Send> {
+        writer: ArrowWriter<W>,
+        builder: BatchBuilder,
+    }
+
+    impl DatasetWriter<BufWriter<File>> {
+        /// Creates (or truncates) the file at the given path
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the file can't be created
+        pub fn create<P: AsRef<Path>>(path: P) -> Result<Self, ParquetError> {
+            Self::new(BufWriter::new(File::create(path)?))
+        }
+    }
+
+    impl<W: Write + Send> DatasetWriter<W> {
+        /// # Errors
+        ///
+        /// Returns an error if the Parquet header can't be written
+        pub fn new(writer: W) -> Result<Self, ParquetError> {
+            Ok(Self {
+                writer: ArrowWriter::try_new(writer, schema(), None)?,
+                builder: BatchBuilder::new(),
+            })
+        }
+
+        /// Adds a row for every car in the game state
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if a full row group can't be written
+        pub fn write(&mut self, game_state: &GameState) -> Result<(), ParquetError> {
+            self.builder.push(game_state);
+
+            if self.builder.len() >= ROWS_PER_GROUP {
+                self.flush()?;
+            }
+
+            Ok(())
+        }
+
+        /// Writes all of the buffered rows
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the rows can't be written
+        pub fn flush(&mut self) -> Result<(), ParquetError> {
+            if !self.builder.is_empty() {
+                self.writer.write(&self.builder.finish()?)?;
+            }
+
+            Ok(())
+        }
+
+        /// Writes the remaining rows and the Parquet footer, returning the inner writer
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the rows or the footer can't be written
+        pub fn finish(mut self) -> Result<W, ParquetError> {
+            self.flush()?;
+            self.writer.into_inner()
+        }
+    }
+}
@@ -0,0 +1,253 @@
+//! Tools for finding where two simulations stop matching
+//!
+//! Useful for validating determinism across platforms, or after updating RocketSim.
+
+use crate::{
+    math::{RotMat, Vec3},
+    sim::{Arena, BallState, BoostPadState, CarState},
+};
+use core::pin::Pin;
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+/// The part of the arena that diverged
+pub enum Part {
+    /// Arena-wide values, like the tick count
+    Arena,
+    Ball,
+    Car(u32),
+    /// The boost pad with the given index
+    Pad(usize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Difference {
+    /// A number that differs by more than the tolerance
+    Value {
+        a: f64,
+        b: f64,
+    },
+    Flag {
+        a: bool,
+        b: bool,
+    },
+    /// The part only exists in one of the arenas
+    Missing {
+        in_a: bool,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Divergence {
+    /// The tick count of the first arena
+    pub tick_count: u64,
+    pub part: Part,
+    /// The name of the field, with vector components separated by a `.` (e.g. `"vel.z"`)
+    ///
+    /// Empty if the whole part is missing
+    pub field: &'static str,
+    pub difference: Difference,
+}
+
+struct Comparer {
+    tick_count: u64,
+    tolerance: f32,
+    divergences: Vec<Divergence>,
+}
+
+impl Comparer {
+    fn push(&mut self, part: Part, field: &'static str, difference: Difference) {
+        self.divergences.push(Divergence {
+            tick_count: self.tick_count,
+            part,
+            field,
+            difference,
+        });
+    }
+
+    fn num(&mut self, part: Part, field: &'static str, a: f32, b: f32) {
+        // NaNs are only equal to each other
+        let same = if a.is_nan() || b.is_nan() {
+            a.is_nan() && b.is_nan()
+        } else {
+            a == b || (a - b).abs() <= self.tolerance
+        };
+
+        if !same {
+            self.push(
+                part,
+                field,
+                Difference::Value {
+                    a: f64::from(a),
+                    b: f64::from(b),
+                },
+            );
+        }
+    }
+
+    fn int(&mut self, part: Part, field: &'static str, a: u64, b: u64) {
+        if a != b {
+            self.push(
+                part,
+                field,
+                Difference::Value {
+                    a: a as f64,
+                    b: b as f64,
+                },
+            );
+        }
+    }
+
+    fn flag(&mut self, part: Part, field: &'static str, a: bool, b: bool) {
+        if a != b {
+            self.push(part, field, Difference::Flag { a, b });
+        }
+    }
+
+    fn vec(&mut self, part: Part, fields: [&'static str; 3], a: Vec3, b: Vec3) {
+        self.num(part, fields[0], a.x, b.x);
+        self.num(part, fields[1], a.y, b.y);
+        self.num(part, fields[2], a.z, b.z);
+    }
+
+    fn rot_mat(&mut self, part: Part, a: RotMat, b: RotMat) {
+        self.vec(
+            part,
+            ["rot_mat.forward.x", "rot_mat.forward.y", "rot_mat.forward.z"],
+            a.forward,
+            b.forward,
+        );
+        self.vec(
+            part,
+            ["rot_mat.right.x", "rot_mat.right.y", "rot_mat.right.z"],
+            a.right,
+            b.right,
+        );
+        self.vec(part, ["rot_mat.up.x", "rot_mat.up.y", "rot_mat.up.z"], a.up, b.up);
+    }
+
+    fn ball(&mut self, a: &BallState, b: &BallState) {
+        let part = Part::Ball;
+
+        self.vec(part, ["pos.x", "pos.y", "pos.z"], a.pos, b.pos);
+        self.rot_mat(part, a.rot_mat, b.rot_mat);
+        self.vec(part, ["vel.x", "vel.y", "vel.z"], a.vel, b.vel);
+        self.vec(part, ["ang_vel.x", "ang_vel.y", "ang_vel.z"], a.ang_vel, b.ang_vel);
+        self.num(part, "hs_info.y_target_dir", a.hs_info.y_target_dir, b.hs_info.y_target_dir);
+        self.num(
+            part,
+            "hs_info.cur_target_speed",
+            a.hs_info.cur_target_speed,
+            b.hs_info.cur_target_speed,
+        );
+        self.num(
+            part,
+            "hs_info.time_since_hit",
+            a.hs_info.time_since_hit,
+            b.hs_info.time_since_hit,
+        );
+    }
+
+    fn car(&mut self, id: u32, a: &CarState, b: &CarState) {
+        let part = Part::Car(id);
+
+        self.vec(part, ["pos.x", "pos.y", "pos.z"], a.pos, b.pos);
+        self.rot_mat(part, a.rot_mat, b.rot_mat);
+        self.vec(part, ["vel.x", "vel.y", "vel.z"], a.vel, b.vel);
+        self.vec(part, ["ang_vel.x", "ang_vel.y", "ang_vel.z"], a.ang_vel, b.ang_vel);
+        self.num(part, "boost", a.boost, b.boost);
+        self.flag(part, "is_on_ground", a.is_on_ground, b.is_on_ground);
+        self.flag(part, "has_jumped", a.has_jumped, b.has_jumped);
+        self.flag(part, "has_double_jumped", a.has_double_jumped, b.has_double_jumped);
+        self.flag(part, "has_flipped", a.has_flipped, b.has_flipped);
+        self.flag(part, "is_flipping", a.is_flipping, b.is_flipping);
+        self.flag(part, "is_jumping", a.is_jumping, b.is_jumping);
+        self.num(part, "jump_time", a.jump_time, b.jump_time);
+        self.num(part, "flip_time", a.flip_time, b.flip_time);
+        self.num(part, "air_time", a.air_time, b.air_time);
+        self.num(part, "air_time_since_jump", a.air_time_since_jump, b.air_time_since_jump);
+        self.flag(part, "is_supersonic", a.is_supersonic, b.is_supersonic);
+        self.num(part, "supersonic_time", a.supersonic_time, b.supersonic_time);
+        self.num(part, "handbrake_val", a.handbrake_val, b.handbrake_val);
+        self.flag(part, "is_auto_flipping", a.is_auto_flipping, b.is_auto_flipping);
+        self.flag(part, "is_demoed", a.is_demoed, b.is_demoed);
+        self.num(part, "demo_respawn_timer", a.demo_respawn_timer, b.demo_respawn_timer);
+        self.int(
+            part,
+            "ball_hit_info.tick_count_when_hit",
+            a.ball_hit_info.tick_count_when_hit,
+            b.ball_hit_info.tick_count_when_hit,
+        );
+    }
+
+    fn pad(&mut self, index: usize, a: &BoostPadState, b: &BoostPadState) {
+        let part = Part::Pad(index);
+
+        self.flag(part, "is_active", a.is_active, b.is_active);
+        self.num(part, "cooldown", a.cooldown, b.cooldown);
+    }
+}
+
+#[must_use]
+/// Compares the full states of two arenas, returning every field that differs by more than `tolerance`
+///
+/// Booleans and integers have to match exactly, and cars are matched up by their IDs
+pub fn compare_arenas(a: &Arena, b: &Arena, tolerance: f32) -> Vec<Divergence> {
+    let mut comparer = Comparer {
+        tick_count: a.get_tick_count(),
+        tolerance,
+        divergences: Vec::new(),
+    };
+
+    comparer.int(Part::Arena, "tick_count", a.get_tick_count(), b.get_tick_count());
+    comparer.ball(&a.ball_state_ref().to_state(), &b.ball_state_ref().to_state());
+
+    let b_cars = b.get_cars();
+    for car_id in a.get_cars() {
+        match (a.car_state_ref(car_id), b.car_state_ref(car_id)) {
+            (Ok(car_a), Ok(car_b)) => comparer.car(car_id, &car_a.to_state(), &car_b.to_state()),
+            _ => comparer.push(Part::Car(car_id), "", Difference::Missing { in_a: true }),
+        }
+    }
+
+    for car_id in b_cars {
+        if !a.has_car(car_id) {
+            comparer.push(Part::Car(car_id), "", Difference::Missing { in_a: false });
+        }
+    }
+
+    let num_pads = a.num_pads().max(b.num_pads());
+    for i in 0..num_pads {
+        match (i < a.num_pads(), i < b.num_pads()) {
+            (true, true) => comparer.pad(i, &a.get_pad_state(i), &b.get_pad_state(i)),
+            (in_a, _) => comparer.push(Part::Pad(i), "", Difference::Missing { in_a }),
+        }
+    }
+
+    comparer.divergences
+}
+
+#[must_use]
+/// Steps both arenas one tick at a time (with whatever controls are set), until they diverge
+///
+/// Returns the divergences from the first tick where there are any,
+/// or `None` if the arenas still match after `max_ticks` ticks
+pub fn find_divergence(
+    mut a: Pin<&mut Arena>,
+    mut b: Pin<&mut Arena>,
+    max_ticks: u32,
+    tolerance: f32,
+) -> Option<Vec<Divergence>> {
+    for tick in 0..=max_ticks {
+        if tick != 0 {
+            a.as_mut().step(1);
+            b.as_mut().step(1);
+        }
+
+        let divergences = compare_arenas(&a, &b, tolerance);
+        if !divergences.is_empty() {
+            return Some(divergences);
+        }
+    }
+
+    None
+}
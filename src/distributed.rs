@@ -0,0 +1,784 @@
+//! A small protocol for farming batches of arenas out to worker processes, possibly on other machines
+//!
+//! A coordinator connects to every worker (see the `rocketsim-worker` binary) over any reliable byte stream,
+//! like a `TcpStream` or a `UnixStream`, and sends it `Request`s. The worker answers every request with
+//! exactly one `Response`, except for `Request::Quit`, which closes the connection.
+//!
+//! Every message is a frame: the message type as a `u8`, the length of the payload as a `u32`, then the payload.
+//! Game states are encoded exactly like they're sent to rlviser, and everything is little-endian.
+
+use crate::{
+    bytes::{FromBytes, FromBytesExact, ToBytes, ToBytesExact},
+    sim::{Arena, ArenaConfig, CarControls},
+    GameState,
+};
+use cxx::UniquePtr;
+use std::{
+    error::Error,
+    fmt,
+    io::{self, ErrorKind, Read, Write},
+    ops::Range,
+    thread::{available_parallelism, scope},
+};
+
+pub const PROTOCOL_VERSION: u8 = 1;
+/// The largest payload that will be read, anything bigger is rejected as invalid
+pub const MAX_PAYLOAD_LEN: usize = 1 << 30;
+/// The address that `rocketsim-worker` listens on by default
+pub const DEFAULT_WORKER_ADDR: &str = "127.0.0.1:34254";
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageType {
+    Hello,
+    Load,
+    Step,
+    Reset,
+    GetStates,
+    GetStats,
+    Quit,
+    Ok,
+    States,
+    Stats,
+    Error,
+}
+
+#[derive(Clone, Copy, Debug)]
+/// Error for when a message type byte isn't known
+pub struct InvalidMessageType(pub u8);
+
+impl Error for InvalidMessageType {}
+impl fmt::Display for InvalidMessageType {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid message type {}.", self.0)
+    }
+}
+
+impl TryFrom<u8> for MessageType {
+    type Error = InvalidMessageType;
+
+    #[inline]
+    fn try_from(val: u8) -> Result<Self, InvalidMessageType> {
+        Ok(match val {
+            0 => Self::Hello,
+            1 => Self::Load,
+            2 => Self::Step,
+            3 => Self::Reset,
+            4 => Self::GetStates,
+            5 => Self::GetStats,
+            6 => Self::Quit,
+            7 => Self::Ok,
+            8 => Self::States,
+            9 => Self::Stats,
+            10 => Self::Error,
+            _ => return Err(InvalidMessageType(val)),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+/// A message from the coordinator to a worker
+pub enum Request {
+    /// Asks for the worker's protocol version and number of threads, answered with `Response::Hello`
+    Hello,
+    /// Replaces all of the worker's arenas with new ones, created from the game states with the config
+    Load {
+        config: ArenaConfig,
+        states: Vec<GameState>,
+    },
+    /// Sets the controls of the cars, steps every arena and returns the new game states
+    ///
+    /// `controls[i]` are the controls for the cars in arena `i`, and cars that aren't listed keep their controls.
+    /// If `reset_on_goal` is set, arenas where a goal was scored are reset to a random kickoff after the step.
+    Step {
+        ticks: u32,
+        controls: Vec<Vec<(u32, CarControls)>>,
+        reset_on_goal: bool,
+    },
+    /// Resets every arena to a random kickoff, if there's a seed arena `i` is seeded with `seed + i`
+    Reset {
+        seed: Option<i32>,
+    },
+    GetStates,
+    GetStats,
+    /// Ends the session, which the worker doesn't respond to
+    Quit,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// Counters for a single arena of a worker, since it was loaded
+pub struct ArenaStats {
+    /// The number of ticks the arena has been stepped for
+    pub ticks: u64,
+    /// The number of goals scored by blue, only counted on steps with `reset_on_goal`
+    pub blue_goals: u32,
+    /// The number of goals scored by orange, only counted on steps with `reset_on_goal`
+    pub orange_goals: u32,
+}
+
+#[derive(Clone, Debug)]
+/// A message from a worker to the coordinator
+pub enum Response {
+    Hello {
+        version: u8,
+        num_threads: u32,
+    },
+    Ok,
+    /// The game states of every arena, in order
+    States(Vec<GameState>),
+    /// The stats of every arena, in order
+    Stats(Vec<ArenaStats>),
+    /// The request couldn't be handled, the worker is still usable
+    Error(String),
+}
+
+fn invalid_data<E: Into<Box<dyn Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, error)
+}
+
+fn write_len(payload: &mut Vec<u8>, len: usize) -> io::Result<()> {
+    let len = u32::try_from(len).map_err(|_| io::Error::new(ErrorKind::InvalidInput, "Too many items in the message"))?;
+    payload.extend_from_slice(&len.to_le_bytes());
+    Ok(())
+}
+
+fn write_frame<W: Write>(writer: &mut W, message_type: MessageType, payload: &[u8]) -> io::Result<()> {
+    if payload.len() > MAX_PAYLOAD_LEN {
+        return Err(io::Error::new(ErrorKind::InvalidInput, "The message is too big"));
+    }
+
+    let mut header = [0; 1 + u32::NUM_BYTES];
+    header[0] = message_type as u8;
+    header[1..].copy_from_slice(&(payload.len() as u32).to_le_bytes());
+    writer.write_all(&header)?;
+    writer.write_all(payload)?;
+    writer.flush()
+}
+
+fn read_frame<R: Read>(reader: &mut R) -> io::Result<(MessageType, Vec<u8>)> {
+    let mut header = [0; 1 + u32::NUM_BYTES];
+    reader.read_exact(&mut header)?;
+
+    let message_type = MessageType::try_from(header[0]).map_err(invalid_data)?;
+    let len = u32::from_bytes(&header[1..]) as usize;
+    if len > MAX_PAYLOAD_LEN {
+        return Err(invalid_data("The message is too big"));
+    }
+
+    let mut payload = vec![0; len];
+    reader.read_exact(&mut payload)?;
+    Ok((message_type, payload))
+}
+
+/// Reads items from a payload, returning an error instead of panicking when it's too short
+struct PayloadReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> PayloadReader<'a> {
+    fn take(&mut self, num_bytes: usize) -> io::Result<&'a [u8]> {
+        if self.bytes.len() < num_bytes {
+            return Err(invalid_data("The message is too short"));
+        }
+
+        let (bytes, rest) = self.bytes.split_at(num_bytes);
+        self.bytes = rest;
+        Ok(bytes)
+    }
+
+    fn read<T: FromBytesExact>(&mut self) -> io::Result<T> {
+        self.take(T::NUM_BYTES).map(T::from_bytes)
+    }
+
+    fn read_len(&mut self) -> io::Result<usize> {
+        self.read::<u32>().map(|len| len as usize)
+    }
+
+    fn read_arena_config(&mut self) -> io::Result<ArenaConfig> {
+        if !ArenaConfig::check_bytes(self.bytes) {
+            return Err(invalid_data("The message has an invalid arena config"));
+        }
+
+        self.read()
+    }
+
+    fn read_game_state(&mut self) -> io::Result<GameState> {
+        let num_bytes =
+            GameState::check_bytes(self.bytes).ok_or_else(|| invalid_data("The message has an invalid game state"))?;
+        self.take(num_bytes).map(GameState::from_bytes)
+    }
+
+    fn finish(&self) -> io::Result<()> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(invalid_data("The message has trailing bytes"))
+        }
+    }
+}
+
+fn write_states(payload: &mut Vec<u8>, states: &[GameState]) -> io::Result<()> {
+    write_len(payload, states.len())?;
+    for state in states {
+        payload.extend_from_slice(&state.to_bytes());
+    }
+
+    Ok(())
+}
+
+fn read_states(reader: &mut PayloadReader) -> io::Result<Vec<GameState>> {
+    let len = reader.read_len()?;
+    (0..len).map(|_| reader.read_game_state()).collect()
+}
+
+impl Request {
+    /// Writes the request as a single frame
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request can't be written, or is too big
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let mut payload = Vec::new();
+
+        let message_type = match self {
+            Self::Hello => MessageType::Hello,
+            Self::Load { config, states } => {
+                payload.extend_from_slice(&config.to_bytes());
+                write_states(&mut payload, states)?;
+                MessageType::Load
+            }
+            Self::Step {
+                ticks,
+                controls,
+                reset_on_goal,
+            } => {
+                payload.extend_from_slice(&ticks.to_le_bytes());
+                payload.push(u8::from(*reset_on_goal));
+                write_len(&mut payload, controls.len())?;
+                for arena_controls in controls {
+                    write_len(&mut payload, arena_controls.len())?;
+                    for (car_id, car_controls) in arena_controls {
+                        payload.extend_from_slice(&car_id.to_le_bytes());
+                        payload.extend_from_slice(&car_controls.to_bytes());
+                    }
+                }
+                MessageType::Step
+            }
+            Self::Reset { seed } => {
+                payload.push(u8::from(seed.is_some()));
+                payload.extend_from_slice(&seed.unwrap_or_default().to_le_bytes());
+                MessageType::Reset
+            }
+            Self::GetStates => MessageType::GetStates,
+            Self::GetStats => MessageType::GetStats,
+            Self::Quit => MessageType::Quit,
+        };
+
+        write_frame(&mut writer, message_type, &payload)
+    }
+
+    /// Reads a single request frame
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request can't be read, or isn't a valid request
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let (message_type, payload) = read_frame(&mut reader)?;
+        let mut reader = PayloadReader { bytes: &payload };
+
+        let request = match message_type {
+            MessageType::Hello => Self::Hello,
+            MessageType::Load => Self::Load {
+                config: reader.read_arena_config()?,
+                states: read_states(&mut reader)?,
+            },
+            MessageType::Step => {
+                let ticks = reader.read()?;
+                let reset_on_goal = reader.read()?;
+                let num_arenas = reader.read_len()?;
+                let controls = (0..num_arenas)
+                    .map(|_| {
+                        let num_cars = reader.read_len()?;
+                        (0..num_cars)
+                            .map(|_| Ok((reader.read()?, reader.read()?)))
+                            .collect::<io::Result<Vec<_>>>()
+                    })
+                    .collect::<io::Result<Vec<_>>>()?;
+
+                Self::Step {
+                    ticks,
+                    controls,
+                    reset_on_goal,
+                }
+            }
+            MessageType::Reset => {
+                let has_seed: bool = reader.read()?;
+                let seed: i32 = reader.read()?;
+                Self::Reset {
+                    seed: has_seed.then_some(seed),
+                }
+            }
+            MessageType::GetStates => Self::GetStates,
+            MessageType::GetStats => Self::GetStats,
+            MessageType::Quit => Self::Quit,
+            _ => return Err(invalid_data(format!("{message_type:?} isn't a request"))),
+        };
+
+        reader.finish()?;
+        Ok(request)
+    }
+}
+
+impl Response {
+    /// Writes the response as a single frame
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the response can't be written, or is too big
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let mut payload = Vec::new();
+
+        let message_type = match self {
+            Self::Hello { version, num_threads } => {
+                payload.push(*version);
+                payload.extend_from_slice(&num_threads.to_le_bytes());
+                MessageType::Hello
+            }
+            Self::Ok => MessageType::Ok,
+            Self::States(states) => {
+                write_states(&mut payload, states)?;
+                MessageType::States
+            }
+            Self::Stats(stats) => {
+                write_len(&mut payload, stats.len())?;
+                for stats in stats {
+                    payload.extend_from_slice(&stats.ticks.to_le_bytes());
+                    payload.extend_from_slice(&stats.blue_goals.to_le_bytes());
+                    payload.extend_from_slice(&stats.orange_goals.to_le_bytes());
+                }
+                MessageType::Stats
+            }
+            Self::Error(error) => {
+                payload.extend_from_slice(error.as_bytes());
+                MessageType::Error
+            }
+        };
+
+        write_frame(&mut writer, message_type, &payload)
+    }
+
+    /// Reads a single response frame
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the response can't be read, or isn't a valid response
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let (message_type, payload) = read_frame(&mut reader)?;
+        let mut reader = PayloadReader { bytes: &payload };
+
+        let response = match message_type {
+            MessageType::Hello => Self::Hello {
+                version: reader.read()?,
+                num_threads: reader.read()?,
+            },
+            MessageType::Ok => Self::Ok,
+            MessageType::States => Self::States(read_states(&mut reader)?),
+            MessageType::Stats => {
+                let len = reader.read_len()?;
+                Self::Stats(
+                    (0..len)
+                        .map(|_| {
+                            Ok(ArenaStats {
+                                ticks: reader.read()?,
+                                blue_goals: reader.read()?,
+                                orange_goals: reader.read()?,
+                            })
+                        })
+                        .collect::<io::Result<_>>()?,
+                )
+            }
+            MessageType::Error => Self::Error(String::from_utf8_lossy(reader.take(payload.len())?).into_owned()),
+            _ => return Err(invalid_data(format!("{message_type:?} isn't a response"))),
+        };
+
+        reader.finish()?;
+        Ok(response)
+    }
+}
+
+/// Creates an arena that matches the game state, like `Arena::from_game_state`,
+/// but returns an error instead of passing RocketSim a tick rate it can't run at
+fn load_arena(state: &GameState, config: ArenaConfig) -> Result<UniquePtr<Arena>, Box<dyn Error>> {
+    let tick_rate = state.tick_rate.round().clamp(0., f32::from(Arena::MAX_NATIVE_TICK_RATE)) as u8;
+    let mut arena = Arena::try_new(state.game_mode, config, tick_rate)?;
+    arena.pin_mut().set_tick_rate(state.tick_rate)?;
+
+    for car in &state.cars {
+        arena.pin_mut().add_car_with_id(car.id, car.team, &car.config)?;
+    }
+
+    arena.pin_mut().set_game_state(state)?;
+    arena.pin_mut().set_tick_count(state.tick_count);
+    Ok(arena)
+}
+
+struct WorkerArena {
+    arena: UniquePtr<Arena>,
+    stats: ArenaStats,
+}
+
+/// Owns a batch of arenas and handles the requests of a coordinator
+///
+/// Arenas are stepped in parallel, spread over all of the available threads
+pub struct Worker {
+    arenas: Vec<WorkerArena>,
+    num_threads: usize,
+}
+
+impl fmt::Debug for Worker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Worker")
+            .field("num_arenas", &self.arenas.len())
+            .field("num_threads", &self.num_threads)
+            .finish()
+    }
+}
+
+impl Default for Worker {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Worker {
+    #[inline]
+    #[must_use]
+    /// A worker without any arenas, which uses all of the available threads
+    pub fn new() -> Self {
+        Self {
+            arenas: Vec::new(),
+            num_threads: available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn num_arenas(&self) -> usize {
+        self.arenas.len()
+    }
+
+    fn states(&mut self) -> Vec<GameState> {
+        self.arenas
+            .iter_mut()
+            .map(|arena| arena.arena.pin_mut().get_game_state())
+            .collect()
+    }
+
+    fn step(&mut self, ticks: u32, controls: &[Vec<(u32, CarControls)>], reset_on_goal: bool) -> Result<(), String> {
+        if controls.len() > self.arenas.len() {
+            return Err(format!(
+                "Got controls for {} arenas, but there are only {}",
+                controls.len(),
+                self.arenas.len()
+            ));
+        }
+
+        for (i, (arena, controls)) in self.arenas.iter_mut().zip(controls).enumerate() {
+            for &(car_id, car_controls) in controls {
+                arena
+                    .arena
+                    .pin_mut()
+                    .set_car_controls(car_id, car_controls)
+                    .map_err(|e| format!("Arena {i}: {e}"))?;
+            }
+        }
+
+        let chunk_size = self.arenas.len().div_ceil(self.num_threads).max(1);
+        scope(|s| {
+            for chunk in self.arenas.chunks_mut(chunk_size) {
+                s.spawn(move || {
+                    for arena in chunk {
+                        arena.arena.pin_mut().step(ticks);
+                        arena.stats.ticks += u64::from(ticks);
+
+                        if reset_on_goal && arena.arena.is_ball_scored() {
+                            // the goal on the positive y side belongs to orange
+                            if arena.arena.pin_mut().get_ball().pos.y > 0. {
+                                arena.stats.blue_goals += 1;
+                            } else {
+                                arena.stats.orange_goals += 1;
+                            }
+
+                            arena.arena.pin_mut().reset_to_random_kickoff(None);
+                        }
+                    }
+                });
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Handles a single request, returning `None` for `Request::Quit`
+    pub fn handle(&mut self, request: Request) -> Option<Response> {
+        Some(match request {
+            Request::Hello => Response::Hello {
+                version: PROTOCOL_VERSION,
+                num_threads: self.num_threads as u32,
+            },
+            Request::Load { config, states } => {
+                if let Some(i) = states.iter().position(|state| {
+                    state
+                        .cars
+                        .iter()
+                        .enumerate()
+                        .any(|(j, car)| car.id == 0 || state.cars[..j].iter().any(|other| other.id == car.id))
+                }) {
+                    return Some(Response::Error(format!("Game state {i} has invalid or duplicate car ids")));
+                }
+
+                let arenas = states
+                    .iter()
+                    .enumerate()
+                    .map(|(i, state)| {
+                        Ok(WorkerArena {
+                            arena: load_arena(state, config).map_err(|e| format!("Game state {i}: {e}"))?,
+                            stats: ArenaStats::default(),
+                        })
+                    })
+                    .collect::<Result<Vec<_>, String>>();
+
+                match arenas {
+                    Ok(arenas) => {
+                        self.arenas = arenas;
+                        Response::Ok
+                    }
+                    Err(e) => Response::Error(e),
+                }
+            }
+            Request::Step {
+                ticks,
+                controls,
+                reset_on_goal,
+            } => match self.step(ticks, &controls, reset_on_goal) {
+                Ok(()) => Response::States(self.states()),
+                Err(e) => Response::Error(e),
+            },
+            Request::Reset { seed } => {
+                for (i, arena) in self.arenas.iter_mut().enumerate() {
+                    let seed = seed.map(|seed| seed.wrapping_add(i as i32));
+                    arena.arena.pin_mut().reset_to_random_kickoff(seed);
+                }
+                Response::Ok
+            }
+            Request::GetStates => Response::States(self.states()),
+            Request::GetStats => Response::Stats(self.arenas.iter().map(|arena| arena.stats).collect()),
+            Request::Quit => return None,
+        })
+    }
+
+    /// Handles requests from the stream until the coordinator quits or disconnects
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream fails or a request is invalid
+    pub fn serve<S: Read + Write>(&mut self, mut stream: S) -> io::Result<()> {
+        loop {
+            let request = match Request::read(&mut stream) {
+                Ok(request) => request,
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(()),
+                Err(e) => return Err(e),
+            };
+
+            match self.handle(request) {
+                Some(response) => response.write(&mut stream)?,
+                None => return Ok(()),
+            }
+        }
+    }
+}
+
+fn unexpected(response: Response) -> io::Error {
+    match response {
+        Response::Error(e) => io::Error::other(e),
+        response => invalid_data(format!("Unexpected response {response:?}")),
+    }
+}
+
+/// Farms batches of arenas out to workers, and gathers their states and stats
+///
+/// Requests are sent to every worker before any of the responses are read, so the workers run in parallel.
+#[derive(Debug)]
+pub struct Coordinator<S: Read + Write> {
+    workers: Vec<S>,
+    /// The number of arenas on each worker
+    num_arenas: Vec<usize>,
+}
+
+impl<S: Read + Write> Coordinator<S> {
+    /// Checks that every worker speaks the same protocol version
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a worker can't be reached or has a different protocol version
+    pub fn new(workers: Vec<S>) -> io::Result<Self> {
+        let mut coordinator = Self {
+            num_arenas: vec![0; workers.len()],
+            workers,
+        };
+
+        for response in coordinator.broadcast(|_| Request::Hello)? {
+            match response {
+                Response::Hello { version, .. } if version == PROTOCOL_VERSION => {}
+                Response::Hello { version, .. } => {
+                    return Err(invalid_data(format!("Worker has protocol version {version}")));
+                }
+                response => return Err(unexpected(response)),
+            }
+        }
+
+        Ok(coordinator)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn num_workers(&self) -> usize {
+        self.workers.len()
+    }
+
+    #[inline]
+    #[must_use]
+    /// The total number of arenas, over all workers
+    pub fn num_arenas(&self) -> usize {
+        self.num_arenas.iter().sum()
+    }
+
+    /// The range of arena indices on each worker
+    fn ranges(&self) -> Vec<Range<usize>> {
+        let mut start = 0;
+        self.num_arenas
+            .iter()
+            .map(|&len| {
+                start += len;
+                start - len..start
+            })
+            .collect()
+    }
+
+    fn broadcast(&mut self, mut request: impl FnMut(usize) -> Request) -> io::Result<Vec<Response>> {
+        for (i, worker) in self.workers.iter_mut().enumerate() {
+            request(i).write(&mut *worker)?;
+        }
+
+        self.workers.iter_mut().map(Response::read).collect()
+    }
+
+    fn gather_states(responses: Vec<Response>) -> io::Result<Vec<GameState>> {
+        let mut states = Vec::new();
+        for response in responses {
+            match response {
+                Response::States(worker_states) => states.extend(worker_states),
+                response => return Err(unexpected(response)),
+            }
+        }
+
+        Ok(states)
+    }
+
+    fn expect_ok(responses: Vec<Response>) -> io::Result<()> {
+        responses.into_iter().try_for_each(|response| match response {
+            Response::Ok => Ok(()),
+            response => Err(unexpected(response)),
+        })
+    }
+
+    /// Spreads the game states as evenly as possible over the workers, replacing all of their arenas
+    ///
+    /// Arena `i` is the `i`th game state, which keeps its index in every other method
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a worker can't be reached or rejects its game states
+    pub fn load(&mut self, config: ArenaConfig, states: &[GameState]) -> io::Result<()> {
+        let num_workers = self.workers.len().max(1);
+        self.num_arenas = (0..self.workers.len())
+            .map(|i| states.len() / num_workers + usize::from(i < states.len() % num_workers))
+            .collect();
+
+        let ranges = self.ranges();
+        let responses = self.broadcast(|i| Request::Load {
+            config,
+            states: states[ranges[i].clone()].to_vec(),
+        })?;
+        Self::expect_ok(responses)
+    }
+
+    /// Steps every arena, returning the new game states of all of them
+    ///
+    /// `controls[i]` are the controls for arena `i`, see `Request::Step`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a worker can't be reached or fails to step
+    pub fn step(
+        &mut self,
+        ticks: u32,
+        controls: &[Vec<(u32, CarControls)>],
+        reset_on_goal: bool,
+    ) -> io::Result<Vec<GameState>> {
+        let ranges = self.ranges();
+        let responses = self.broadcast(|i| Request::Step {
+            ticks,
+            controls: controls[ranges[i].start.min(controls.len())..ranges[i].end.min(controls.len())].to_vec(),
+            reset_on_goal,
+        })?;
+        Self::gather_states(responses)
+    }
+
+    /// Resets every arena to a random kickoff, if there's a seed arena `i` is seeded with `seed + i`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a worker can't be reached
+    pub fn reset(&mut self, seed: Option<i32>) -> io::Result<()> {
+        let ranges = self.ranges();
+        let responses = self.broadcast(|i| Request::Reset {
+            seed: seed.map(|seed| seed.wrapping_add(ranges[i].start as i32)),
+        })?;
+        Self::expect_ok(responses)
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if a worker can't be reached
+    pub fn get_states(&mut self) -> io::Result<Vec<GameState>> {
+        let responses = self.broadcast(|_| Request::GetStates)?;
+        Self::gather_states(responses)
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if a worker can't be reached
+    pub fn get_stats(&mut self) -> io::Result<Vec<ArenaStats>> {
+        let mut stats = Vec::with_capacity(self.num_arenas());
+        for response in self.broadcast(|_| Request::GetStats)? {
+            match response {
+                Response::Stats(worker_stats) => stats.extend(worker_stats),
+                response => return Err(unexpected(response)),
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Tells every worker to end the session
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a worker can't be reached
+    pub fn quit(mut self) -> io::Result<()> {
+        self.workers.iter_mut().try_for_each(|worker| Request::Quit.write(worker))
+    }
+}
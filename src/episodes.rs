@@ -0,0 +1,316 @@
+//! Running episodes: reset the arena, step it with a controller for every car until a terminal condition is met,
+//! and collect stats along the way
+//!
+//! `Runner` takes care of the loop that's at the center of both RL data collection and evaluation,
+//! and `EpisodeHooks` can be used to record observations, rewards or anything else on every step.
+
+use crate::{
+    input::Controller,
+    sim::{Arena, CarControls, Team},
+    stats::CarballTracker,
+    GameState, NoCarFound,
+};
+use core::pin::Pin;
+use std::fmt;
+
+/// A function that resets the arena for the episode with the given index
+pub type ResetFn = Box<dyn FnMut(usize, Pin<&mut Arena>)>;
+
+/// How the arena is reset at the start of every episode
+pub enum EpisodeReset {
+    /// A random kickoff, see `Arena::reset_to_random_kickoff`
+    ///
+    /// If there's a seed, the episode index is added to it, so every episode is different but reproducible
+    RandomKickoff { seed: Option<i32> },
+    /// Calls the function with the episode index
+    Custom(ResetFn),
+}
+
+impl Default for EpisodeReset {
+    #[inline]
+    fn default() -> Self {
+        Self::RandomKickoff { seed: None }
+    }
+}
+
+impl fmt::Debug for EpisodeReset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RandomKickoff { seed } => f.debug_struct("RandomKickoff").field("seed", seed).finish(),
+            Self::Custom(_) => f.write_str("Custom"),
+        }
+    }
+}
+
+/// A condition for ending an episode, checked after every step
+pub enum TerminalCondition {
+    /// Ends the episode when a goal is scored
+    GoalScored,
+    /// Ends the episode after this many ticks
+    MaxTicks(u64),
+    /// Ends the episode once the function returns true
+    Custom(Box<dyn Fn(&GameState) -> bool>),
+}
+
+impl fmt::Debug for TerminalCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::GoalScored => f.write_str("GoalScored"),
+            Self::MaxTicks(ticks) => f.debug_tuple("MaxTicks").field(ticks).finish(),
+            Self::Custom(_) => f.write_str("Custom"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EpisodeEnd {
+    /// A goal was scored by the team
+    Goal(Team),
+    MaxTicks,
+    /// The custom terminal condition at the index was met
+    Custom(usize),
+    /// A controller requested to quit, see `Controller::quit_requested`
+    Quit,
+}
+
+#[derive(Clone, Debug)]
+pub struct EpisodeSummary {
+    /// The index of the episode, starting at 0
+    pub index: usize,
+    /// How many ticks the episode lasted
+    pub ticks: u64,
+    pub end: EpisodeEnd,
+    /// The stats of the episode, from every step
+    pub stats: CarballTracker,
+}
+
+/// Functions that are called at each point of an episode, which do nothing by default
+pub trait EpisodeHooks {
+    #[inline]
+    /// Called after the arena has been reset, before the first step
+    fn on_reset(&mut self, _episode: usize, _game_state: &GameState) {}
+
+    #[inline]
+    /// Called after every step, with the controls that were used for the step and the state after it
+    fn on_step(&mut self, _controls: &[(u32, CarControls)], _game_state: &GameState) {}
+
+    #[inline]
+    /// Called at the end of every episode
+    fn on_end(&mut self, _summary: &EpisodeSummary) {}
+}
+
+impl EpisodeHooks for () {}
+
+#[derive(Default)]
+/// Runs episodes in an arena: reset, then step with the controllers until a terminal condition is met
+///
+/// ```ignore
+/// let mut runner = Runner::new()
+///     .with_controller(blue, |_, _: &GameState| CarControls { throttle: 1., ..Default::default() })
+///     .with_terminal_condition(TerminalCondition::GoalScored)
+///     .with_terminal_condition(TerminalCondition::MaxTicks(120 * 60));
+/// let summaries = runner.run(arena.pin_mut(), 10, &mut ())?;
+/// ```
+pub struct Runner {
+    pub reset: EpisodeReset,
+    /// How many ticks each step lasts, with the same controls, at least 1
+    pub tick_skip: u32,
+    controllers: Vec<(u32, Box<dyn Controller>)>,
+    terminal_conditions: Vec<TerminalCondition>,
+    episode: usize,
+}
+
+impl fmt::Debug for Runner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Runner")
+            .field("reset", &self.reset)
+            .field("tick_skip", &self.tick_skip)
+            .field(
+                "controllers",
+                &self.controllers.iter().map(|(car_id, _)| car_id).collect::<Vec<_>>(),
+            )
+            .field("terminal_conditions", &self.terminal_conditions)
+            .field("episode", &self.episode)
+            .finish()
+    }
+}
+
+impl Runner {
+    #[inline]
+    #[must_use]
+    /// A runner with random kickoffs, 1 tick per step, and no controllers or terminal conditions
+    pub fn new() -> Self {
+        Self {
+            tick_skip: 1,
+            ..Default::default()
+        }
+    }
+
+    #[must_use]
+    pub fn with_reset(mut self, reset: EpisodeReset) -> Self {
+        self.reset = reset;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_tick_skip(mut self, tick_skip: u32) -> Self {
+        self.tick_skip = tick_skip;
+        self
+    }
+
+    #[must_use]
+    pub fn with_controller(mut self, car_id: u32, controller: impl Controller + 'static) -> Self {
+        self.set_controller(car_id, controller);
+        self
+    }
+
+    #[must_use]
+    pub fn with_terminal_condition(mut self, condition: TerminalCondition) -> Self {
+        self.terminal_conditions.push(condition);
+        self
+    }
+
+    /// Sets the controller of the car, replacing its previous controller
+    ///
+    /// Cars without a controller keep whatever controls they had
+    pub fn set_controller(&mut self, car_id: u32, controller: impl Controller + 'static) {
+        self.remove_controller(car_id);
+        self.controllers.push((car_id, Box::new(controller)));
+    }
+
+    pub fn remove_controller(&mut self, car_id: u32) {
+        self.controllers.retain(|(id, _)| *id != car_id);
+    }
+
+    pub fn add_terminal_condition(&mut self, condition: TerminalCondition) {
+        self.terminal_conditions.push(condition);
+    }
+
+    #[inline]
+    #[must_use]
+    /// The index of the next episode
+    pub const fn episode(&self) -> usize {
+        self.episode
+    }
+
+    fn reset_arena(&mut self, arena: Pin<&mut Arena>) {
+        match &mut self.reset {
+            EpisodeReset::RandomKickoff { seed } => {
+                let seed = seed.map(|seed| seed.wrapping_add(self.episode as i32));
+                arena.reset_to_random_kickoff(seed);
+            }
+            EpisodeReset::Custom(reset) => reset(self.episode, arena),
+        }
+    }
+
+    fn terminal(&self, arena: &Arena, game_state: &GameState, start_tick: u64) -> Option<EpisodeEnd> {
+        self.terminal_conditions
+            .iter()
+            .enumerate()
+            .find_map(|(i, condition)| match condition {
+                TerminalCondition::GoalScored => {
+                    // the goal on the positive y side belongs to orange
+                    let team = if game_state.ball.pos.y > 0. {
+                        Team::Blue
+                    } else {
+                        Team::Orange
+                    };
+                    arena.is_ball_scored().then_some(EpisodeEnd::Goal(team))
+                }
+                TerminalCondition::MaxTicks(ticks) => {
+                    (game_state.tick_count - start_tick >= *ticks).then_some(EpisodeEnd::MaxTicks)
+                }
+                TerminalCondition::Custom(is_met) => is_met(game_state).then_some(EpisodeEnd::Custom(i)),
+            })
+    }
+
+    /// Runs a single episode, calling the hooks along the way
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a controller was set for a car that isn't in the arena
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no terminal conditions, since the episode would never end
+    pub fn run_episode(
+        &mut self,
+        mut arena: Pin<&mut Arena>,
+        hooks: &mut impl EpisodeHooks,
+    ) -> Result<EpisodeSummary, NoCarFound> {
+        assert!(!self.terminal_conditions.is_empty(), "An episode needs a terminal condition");
+
+        self.reset_arena(arena.as_mut());
+        let mut stats = CarballTracker::new();
+        let mut game_state = arena.as_mut().get_game_state();
+        let start_tick = game_state.tick_count;
+        stats.update(&game_state);
+        hooks.on_reset(self.episode, &game_state);
+
+        let mut controls = Vec::with_capacity(self.controllers.len());
+        let end = loop {
+            controls.clear();
+            for (car_id, controller) in &mut self.controllers {
+                controls.push((*car_id, controller.controls(*car_id, &game_state)));
+            }
+
+            if self.controllers.iter().any(|(_, controller)| controller.quit_requested()) {
+                break EpisodeEnd::Quit;
+            }
+
+            for &(car_id, car_controls) in &controls {
+                arena.as_mut().set_car_controls(car_id, car_controls)?;
+            }
+
+            arena.as_mut().step(self.tick_skip.max(1));
+            game_state = arena.as_mut().get_game_state();
+            stats.update(&game_state);
+            hooks.on_step(&controls, &game_state);
+
+            if let Some(end) = self.terminal(&arena, &game_state, start_tick) {
+                break end;
+            }
+        };
+
+        let summary = EpisodeSummary {
+            index: self.episode,
+            ticks: game_state.tick_count - start_tick,
+            end,
+            stats,
+        };
+
+        self.episode += 1;
+        hooks.on_end(&summary);
+        Ok(summary)
+    }
+
+    /// Runs up to `num_episodes` episodes, stopping early if a controller requests to quit
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a controller was set for a car that isn't in the arena
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no terminal conditions, since the episodes would never end
+    pub fn run(
+        &mut self,
+        mut arena: Pin<&mut Arena>,
+        num_episodes: usize,
+        hooks: &mut impl EpisodeHooks,
+    ) -> Result<Vec<EpisodeSummary>, NoCarFound> {
+        let mut summaries = Vec::with_capacity(num_episodes);
+
+        for _ in 0..num_episodes {
+            let summary = self.run_episode(arena.as_mut(), hooks)?;
+            let quit = summary.end == EpisodeEnd::Quit;
+            summaries.push(summary);
+
+            if quit {
+                break;
+            }
+        }
+
+        Ok(summaries)
+    }
+}
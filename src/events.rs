@@ -0,0 +1,716 @@
+use crate::{
+    analysis::{expected_goal, is_shot_on_target, GoalCrossing},
+    consts::{self, boostpads},
+    field::hoops,
+    math::Vec3,
+    sim::{BallState, CarConfig, Team, WheelPairConfig},
+    GameState,
+};
+
+/// Minimum absolute z component of a contact normal for the surface to count as the floor or ceiling
+pub const FLAT_SURFACE_NORMAL_Z: f32 = 0.7;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The kind of surface a car is in contact with
+pub enum Surface {
+    Floor,
+    Wall,
+    Ceiling,
+}
+
+impl Surface {
+    #[inline]
+    #[must_use]
+    /// Classifies a surface from its (normalized) contact normal
+    pub fn from_normal(normal: Vec3) -> Self {
+        if normal.z >= FLAT_SURFACE_NORMAL_Z {
+            Self::Floor
+        } else if normal.z <= -FLAT_SURFACE_NORMAL_Z {
+            Self::Ceiling
+        } else {
+            Self::Wall
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CarEventKind {
+    /// The car's wheels lost contact with every surface
+    LeftGround,
+    /// The car's wheels regained contact with a surface
+    Landed { surface: Surface, normal: Vec3 },
+    /// The car started touching a wall (with its wheels or its body)
+    TouchedWall { normal: Vec3 },
+    /// The car started touching the ceiling (with its wheels or its body)
+    TouchedCeiling { normal: Vec3 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CarEvent {
+    pub car_id: u32,
+    /// The tick count of the state in which the transition was first observed
+    pub tick_count: u64,
+    pub kind: CarEventKind,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct CarContactInfo {
+    is_on_ground: bool,
+    surface: Option<Surface>,
+}
+
+#[derive(Clone, Debug, Default)]
+/// Detects ground/wall/ceiling transitions of cars between consecutive game states
+///
+/// Feed it every game state you observe (e.g. after each call to `step`) and it will
+/// return the transitions that happened since the last update.
+/// Cars that appear for the first time never produce events.
+pub struct CarEventTracker {
+    prev: Vec<(u32, CarContactInfo)>,
+}
+
+impl CarEventTracker {
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { prev: Vec::new() }
+    }
+
+    /// Forget all previously observed car states
+    pub fn clear(&mut self) {
+        self.prev.clear();
+    }
+
+    /// Compares the given game state against the previous one and returns all new events
+    pub fn update(&mut self, game_state: &GameState) -> Vec<CarEvent> {
+        let mut events = Vec::new();
+        let mut next = Vec::with_capacity(game_state.cars.len());
+
+        for car in &game_state.cars {
+            let state = &car.state;
+            let normal = if state.is_on_ground || !state.world_contact.has_contact {
+                state.rot_mat.up
+            } else {
+                state.world_contact.contact_normal
+            };
+
+            let info = CarContactInfo {
+                is_on_ground: state.is_on_ground,
+                surface: (state.is_on_ground || state.world_contact.has_contact).then(|| Surface::from_normal(normal)),
+            };
+
+            if let Some(prev) = self.prev.iter().find(|(id, _)| *id == car.id).map(|(_, prev)| prev) {
+                let mut push = |kind| {
+                    events.push(CarEvent {
+                        car_id: car.id,
+                        tick_count: game_state.tick_count,
+                        kind,
+                    });
+                };
+
+                match (prev.is_on_ground, info.is_on_ground) {
+                    (true, false) => push(CarEventKind::LeftGround),
+                    (false, true) => push(CarEventKind::Landed {
+                        surface: info.surface.unwrap_or(Surface::Floor),
+                        normal,
+                    }),
+                    _ => {}
+                }
+
+                if info.surface != prev.surface {
+                    match info.surface {
+                        Some(Surface::Wall) => push(CarEventKind::TouchedWall { normal }),
+                        Some(Surface::Ceiling) => push(CarEventKind::TouchedCeiling { normal }),
+                        _ => {}
+                    }
+                }
+            }
+
+            next.push((car.id, info));
+        }
+
+        self.prev = next;
+        events
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RosterEventKind {
+    /// The car was added to the arena
+    Added { team: Team },
+    /// The car was removed from the arena
+    Removed { team: Team },
+    /// The car was moved from one team to the other
+    TeamChanged { from: Team, to: Team },
+    /// The car's hitbox or wheels were changed
+    ConfigChanged,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RosterEvent {
+    pub car_id: u32,
+    /// The tick count of the state in which the change was first observed
+    pub tick_count: u64,
+    pub kind: RosterEventKind,
+}
+
+#[inline]
+fn wheels_eq(a: &WheelPairConfig, b: &WheelPairConfig) -> bool {
+    a.wheel_radius == b.wheel_radius
+        && a.suspension_rest_length == b.suspension_rest_length
+        && a.connection_point_offset == b.connection_point_offset
+}
+
+#[inline]
+fn configs_eq(a: &CarConfig, b: &CarConfig) -> bool {
+    a.hitbox_size == b.hitbox_size
+        && a.hitbox_pos_offset == b.hitbox_pos_offset
+        && wheels_eq(&a.front_wheels, &b.front_wheels)
+        && wheels_eq(&a.back_wheels, &b.back_wheels)
+        && a.dodge_deadzone == b.dodge_deadzone
+}
+
+#[derive(Clone, Debug, Default)]
+/// Detects cars being added, removed, or changed between consecutive game states
+///
+/// Unlike `CarEventTracker`, the first update reports every car in the game state as added.
+pub struct RosterTracker {
+    prev: Vec<(u32, Team, CarConfig)>,
+}
+
+impl RosterTracker {
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { prev: Vec::new() }
+    }
+
+    /// Forget all previously observed cars
+    pub fn clear(&mut self) {
+        self.prev.clear();
+    }
+
+    /// Compares the given game state against the previous one and returns all roster changes
+    pub fn update(&mut self, game_state: &GameState) -> Vec<RosterEvent> {
+        let mut events = Vec::new();
+        let mut push = |car_id, kind| {
+            events.push(RosterEvent {
+                car_id,
+                tick_count: game_state.tick_count,
+                kind,
+            });
+        };
+
+        for &(id, team, _) in &self.prev {
+            if !game_state.cars.iter().any(|car| car.id == id) {
+                push(id, RosterEventKind::Removed { team });
+            }
+        }
+
+        for car in &game_state.cars {
+            match self.prev.iter().find(|(id, _, _)| *id == car.id) {
+                None => push(car.id, RosterEventKind::Added { team: car.team }),
+                Some(&(_, team, config)) => {
+                    if team != car.team {
+                        push(
+                            car.id,
+                            RosterEventKind::TeamChanged {
+                                from: team,
+                                to: car.team,
+                            },
+                        );
+                    }
+
+                    if !configs_eq(&config, &car.config) {
+                        push(car.id, RosterEventKind::ConfigChanged);
+                    }
+                }
+            }
+        }
+
+        self.prev = game_state.cars.iter().map(|car| (car.id, car.team, car.config)).collect();
+        events
+    }
+}
+
+/// Minimum unexplained change in ball velocity (uu/s) between two states for it to count as a contact
+pub const BALL_CONTACT_MIN_DELTA_VEL: f32 = 50.;
+/// Extra distance (on top of the ball's radius) within which a ball contact is attributed to the rim or backboard
+pub const HOOPS_CONTACT_MARGIN: f32 = 40.;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HoopsEventKind {
+    /// The ball bounced off of the rim of `team`'s hoop
+    RimContact { team: Team },
+    /// The ball bounced off of the backboard above `team`'s hoop
+    BackboardContact { team: Team },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HoopsEvent {
+    /// The tick count of the state in which the contact was first observed
+    pub tick_count: u64,
+    pub kind: HoopsEventKind,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct PrevBall {
+    tick_count: u64,
+    state: BallState,
+}
+
+#[derive(Clone, Debug, Default)]
+/// Detects ball contacts with the hoops rims and backboards between consecutive game states
+///
+/// Contacts are found from changes in the ball's velocity that gravity doesn't explain,
+/// and are ignored if a car touched the ball in the meantime.
+pub struct HoopsEventTracker {
+    prev: Option<PrevBall>,
+}
+
+impl HoopsEventTracker {
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { prev: None }
+    }
+
+    /// Forget the previously observed state
+    pub fn clear(&mut self) {
+        self.prev = None;
+    }
+
+    /// Compares the given game state against the previous one and returns all new events
+    pub fn update(&mut self, game_state: &GameState) -> Vec<HoopsEvent> {
+        let ball = game_state.ball;
+        let mut events = Vec::new();
+
+        if let Some(prev) = self.prev {
+            let ticks = game_state.tick_count.saturating_sub(prev.tick_count);
+            let dt = ticks as f32 / game_state.tick_rate;
+
+            let car_touched = game_state.cars.iter().any(|car| {
+                car.state.ball_hit_info.is_valid && car.state.ball_hit_info.tick_count_when_hit > prev.tick_count
+            });
+
+            let unexplained_vel = ball.vel - prev.state.vel - Vec3::new(0., 0., consts::GRAVITY_Z * dt);
+
+            if ticks != 0 && !car_touched && unexplained_vel.length() > BALL_CONTACT_MIN_DELTA_VEL {
+                let max_dist = consts::BALL_COLLISION_RADIUS_HOOPS + HOOPS_CONTACT_MARGIN;
+                let team = hoops::hoop_team(ball.pos);
+
+                let kind = if hoops::distance_to_rim(ball.pos) < max_dist {
+                    Some(HoopsEventKind::RimContact { team })
+                } else if hoops::distance_to_backboard(ball.pos).is_some_and(|dist| dist < max_dist) {
+                    Some(HoopsEventKind::BackboardContact { team })
+                } else {
+                    None
+                };
+
+                if let Some(kind) = kind {
+                    events.push(HoopsEvent {
+                        tick_count: game_state.tick_count,
+                        kind,
+                    });
+                }
+            }
+        }
+
+        self.prev = Some(PrevBall {
+            tick_count: game_state.tick_count,
+            state: ball,
+        });
+
+        events
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// A boost pad being collected by a car
+pub struct BoostPickup {
+    pub car_id: u32,
+    /// The tick count of the state in which the pickup was first observed
+    pub tick_count: u64,
+    /// The index of the pad in `GameState::pads`
+    pub pad_index: usize,
+    pub is_big: bool,
+    /// The boost that the car gained from the pad, which is less than the pad's amount if the car was nearly full
+    pub amount: f32,
+}
+
+#[derive(Clone, Debug, Default)]
+/// Attributes boost pad pickups to cars between consecutive game states, and keeps a history of them
+///
+/// The pickup is attributed to the car that RocketSim says was touching the pad,
+/// falling back to the nearest car whose boost went up.
+pub struct BoostPickupTracker {
+    prev_pads_active: Vec<bool>,
+    prev_boosts: Vec<(u32, f32)>,
+    history: Vec<BoostPickup>,
+}
+
+impl BoostPickupTracker {
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            prev_pads_active: Vec::new(),
+            prev_boosts: Vec::new(),
+            history: Vec::new(),
+        }
+    }
+
+    /// Forget the previously observed state and the history of pickups
+    pub fn clear(&mut self) {
+        self.prev_pads_active.clear();
+        self.prev_boosts.clear();
+        self.history.clear();
+    }
+
+    #[inline]
+    #[must_use]
+    /// Every pickup since the tracker was created or cleared, in the order they happened
+    pub fn history(&self) -> &[BoostPickup] {
+        &self.history
+    }
+
+    /// The pickups of a single car, in the order they happened
+    pub fn pickups_by(&self, car_id: u32) -> impl Iterator<Item = &BoostPickup> {
+        self.history.iter().filter(move |pickup| pickup.car_id == car_id)
+    }
+
+    /// Compares the given game state against the previous one and returns all new pickups
+    pub fn update(&mut self, game_state: &GameState) -> Vec<BoostPickup> {
+        let mut pickups = Vec::new();
+
+        // the pads can't be matched up if the game mode changed
+        if self.prev_pads_active.len() == game_state.pads.len() {
+            let mut collected = Vec::new();
+
+            for (pad_index, (pad, &was_active)) in game_state.pads.iter().zip(&self.prev_pads_active).enumerate() {
+                if !was_active || pad.state.is_active {
+                    continue;
+                }
+
+                let prev_boost = |car_id| self.prev_boosts.iter().find(|(id, _)| *id == car_id).map(|(_, boost)| *boost);
+
+                let car = game_state
+                    .cars
+                    .iter()
+                    .find(|car| car.id == pad.state.prev_locked_car_id)
+                    .or_else(|| {
+                        game_state
+                            .cars
+                            .iter()
+                            .filter(|car| !collected.contains(&car.id))
+                            .filter(|car| prev_boost(car.id).is_some_and(|boost| car.state.boost > boost))
+                            .min_by(|a, b| {
+                                a.state
+                                    .pos
+                                    .distance_squared(pad.config.position)
+                                    .total_cmp(&b.state.pos.distance_squared(pad.config.position))
+                            })
+                    });
+
+                let Some(car) = car else {
+                    continue;
+                };
+
+                let pad_amount = if pad.config.is_big {
+                    boostpads::BOOST_AMOUNT_BIG
+                } else {
+                    boostpads::BOOST_AMOUNT_SMALL
+                };
+                let prev_boost = prev_boost(car.id).unwrap_or(car.state.boost);
+
+                collected.push(car.id);
+                pickups.push(BoostPickup {
+                    car_id: car.id,
+                    tick_count: game_state.tick_count,
+                    pad_index,
+                    is_big: pad.config.is_big,
+                    amount: pad_amount.min(consts::BOOST_MAX - prev_boost).max(0.),
+                });
+            }
+        }
+
+        self.prev_pads_active = game_state.pads.iter().map(|pad| pad.state.is_active).collect();
+        self.prev_boosts = game_state.cars.iter().map(|car| (car.id, car.state.boost)).collect();
+        self.history.extend_from_slice(&pickups);
+        pickups
+    }
+}
+
+/// Minimum sideways (or forward) share of a dodge direction for a flip to count as diagonal, instead of straight
+pub const DIAGONAL_FLIP_MIN_RATIO: f32 = 0.4;
+/// Minimum absolute yaw input for a double jump to count as a stall, when cancelled out by the roll input
+pub const STALL_MIN_YAW: f32 = 0.5;
+/// Maximum time (in seconds) after a flip starts for pulling back to count as a cancel
+pub const FLIP_CANCEL_MAX_TIME: f32 = consts::FLIP_TORQUE_MIN_TIME;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The direction of a flip, relative to the car
+pub enum FlipDirection {
+    Forward,
+    Backward,
+    Left,
+    Right,
+    ForwardLeft,
+    ForwardRight,
+    BackwardLeft,
+    BackwardRight,
+}
+
+impl FlipDirection {
+    #[must_use]
+    /// Classifies a dodge direction, where X is forward and Y is right
+    ///
+    /// Returns `None` if the direction is zero
+    pub fn from_dodge_dir(forward: f32, right: f32) -> Option<Self> {
+        let len = forward.hypot(right);
+        if len == 0. {
+            return None;
+        }
+
+        let is_straight = |share: f32| share.abs() / len < DIAGONAL_FLIP_MIN_RATIO;
+
+        Some(if is_straight(right) {
+            if forward > 0. {
+                Self::Forward
+            } else {
+                Self::Backward
+            }
+        } else if is_straight(forward) {
+            if right > 0. {
+                Self::Right
+            } else {
+                Self::Left
+            }
+        } else {
+            match (forward > 0., right > 0.) {
+                (true, true) => Self::ForwardRight,
+                (true, false) => Self::ForwardLeft,
+                (false, true) => Self::BackwardRight,
+                (false, false) => Self::BackwardLeft,
+            }
+        })
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn is_diagonal(self) -> bool {
+        matches!(
+            self,
+            Self::ForwardLeft | Self::ForwardRight | Self::BackwardLeft | Self::BackwardRight
+        )
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn is_forward(self) -> bool {
+        matches!(self, Self::Forward | Self::ForwardLeft | Self::ForwardRight)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlipEventKind {
+    /// The car started a flip
+    Flip { direction: FlipDirection },
+    /// The car used its second jump without flipping
+    DoubleJump,
+    /// The car used its second jump with the yaw and air roll inputs cancelling each other out
+    Stall,
+    /// The car pulled back right after starting a forward diagonal flip, cancelling its rotation like a speed flip
+    SpeedFlipAttempt { direction: FlipDirection },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FlipEvent {
+    pub car_id: u32,
+    /// The tick count of the state in which the flip (or jump) was first observed
+    pub tick_count: u64,
+    pub kind: FlipEventKind,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct CarFlipInfo {
+    has_flipped: bool,
+    has_double_jumped: bool,
+    /// The direction of the flip that's in progress, if a cancel hasn't been reported for it yet
+    cancellable: Option<FlipDirection>,
+}
+
+#[derive(Clone, Debug, Default)]
+/// Detects and classifies flips, double jumps and stalls from RocketSim's flip state and the car's controls
+///
+/// Feed it every game state you observe (e.g. after each call to `step`) and it will
+/// return the flips that happened since the last update.
+/// Cars that appear for the first time never produce events.
+pub struct FlipEventTracker {
+    prev: Vec<(u32, CarFlipInfo)>,
+}
+
+impl FlipEventTracker {
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { prev: Vec::new() }
+    }
+
+    /// Forget all previously observed car states
+    pub fn clear(&mut self) {
+        self.prev.clear();
+    }
+
+    /// Compares the given game state against the previous one and returns all new events
+    pub fn update(&mut self, game_state: &GameState) -> Vec<FlipEvent> {
+        let mut events = Vec::new();
+        let mut next = Vec::with_capacity(game_state.cars.len());
+
+        for car in &game_state.cars {
+            let state = &car.state;
+            let controls = state.last_controls;
+
+            // RocketSim stores the dodge direction (X forward, Y right) rotated into a torque axis
+            let direction = FlipDirection::from_dodge_dir(state.flip_rel_torque.y, -state.flip_rel_torque.x);
+
+            let mut info = CarFlipInfo {
+                has_flipped: state.has_flipped,
+                has_double_jumped: state.has_double_jumped,
+                cancellable: None,
+            };
+
+            if let Some(prev) = self.prev.iter().find(|(id, _)| *id == car.id).map(|(_, prev)| prev) {
+                let mut push = |kind| {
+                    events.push(FlipEvent {
+                        car_id: car.id,
+                        tick_count: game_state.tick_count,
+                        kind,
+                    });
+                };
+
+                info.cancellable = prev.cancellable;
+
+                if state.has_flipped && !prev.has_flipped {
+                    if let Some(direction) = direction {
+                        push(FlipEventKind::Flip { direction });
+                        info.cancellable = (direction.is_diagonal() && direction.is_forward()).then_some(direction);
+                    }
+                }
+
+                if state.has_double_jumped && !prev.has_double_jumped {
+                    if controls.yaw.abs() >= STALL_MIN_YAW && (controls.yaw + controls.roll).abs() < 0.1 {
+                        push(FlipEventKind::Stall);
+                    } else {
+                        push(FlipEventKind::DoubleJump);
+                    }
+                }
+
+                if let Some(direction) = info.cancellable {
+                    if !state.is_flipping || state.flip_time > FLIP_CANCEL_MAX_TIME {
+                        info.cancellable = None;
+                    } else if controls.pitch > 0.5 {
+                        push(FlipEventKind::SpeedFlipAttempt { direction });
+                        info.cancellable = None;
+                    }
+                }
+            }
+
+            if !state.has_flipped {
+                info.cancellable = None;
+            }
+
+            next.push((car.id, info));
+        }
+
+        self.prev = next;
+        events
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// A car touching the ball, along with where the ball is headed afterwards
+pub struct BallTouch {
+    pub car_id: u32,
+    pub team: Team,
+    /// The tick count of the touch, from the car's `ball_hit_info`
+    pub tick_count: u64,
+    /// The ball's position in the game state where the touch was first observed
+    pub ball_pos: Vec3,
+    pub ball_vel: Vec3,
+    /// Where the ball crosses a goal line, if it's heading into either net (see `analysis::is_shot_on_target`)
+    pub shot: Option<GoalCrossing>,
+    /// The expected goals estimate towards the opponent's net (see `analysis::expected_goal`),
+    /// which is 0 if the ball isn't heading into the opponent's net
+    pub xg: f32,
+}
+
+impl BallTouch {
+    #[inline]
+    #[must_use]
+    /// Returns true if the ball is heading into the opponent's net
+    pub fn is_shot(&self) -> bool {
+        self.shot.is_some_and(|shot| shot.net_team != self.team)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns true if the ball is heading into the car's own net
+    pub fn is_own_goal_risk(&self) -> bool {
+        self.shot.is_some_and(|shot| shot.net_team == self.team)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+/// Detects cars touching the ball between consecutive game states,
+/// and predicts whether each touch sent the ball on target
+pub struct BallTouchTracker {
+    prev_tick_count: Option<u64>,
+}
+
+impl BallTouchTracker {
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { prev_tick_count: None }
+    }
+
+    /// Forget the previously observed state
+    pub fn clear(&mut self) {
+        self.prev_tick_count = None;
+    }
+
+    /// Compares the given game state against the previous one and returns all new touches, oldest first
+    pub fn update(&mut self, game_state: &GameState) -> Vec<BallTouch> {
+        let Some(prev_tick_count) = self.prev_tick_count.replace(game_state.tick_count) else {
+            return Vec::new();
+        };
+
+        let ball = game_state.ball;
+        let shot = is_shot_on_target(&ball);
+
+        let mut touches = game_state
+            .cars
+            .iter()
+            .filter(|car| {
+                let hit_info = car.state.ball_hit_info;
+                hit_info.is_valid && hit_info.tick_count_when_hit > prev_tick_count
+            })
+            .map(|car| {
+                let on_target = shot.filter(|shot| shot.net_team != car.team);
+                BallTouch {
+                    car_id: car.id,
+                    team: car.team,
+                    tick_count: car.state.ball_hit_info.tick_count_when_hit,
+                    ball_pos: ball.pos,
+                    ball_vel: ball.vel,
+                    shot,
+                    xg: on_target.map_or(0., |shot| expected_goal(&ball, shot.net_team)),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        touches.sort_unstable_by_key(|touch| touch.tick_count);
+        touches
+    }
+}
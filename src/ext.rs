@@ -1,13 +1,20 @@
 use crate::{
     base, consts,
+    events::Surface,
+    geometry::StaticGeometry,
     math::{Angle, RotMat, Vec3},
     sim::{
-        Arena, ArenaConfig, ArenaMemWeightMode, BallHitInfo, BallState, BoostPadConfig, BoostPadState, CarConfig,
-        CarContact, CarControls, CarState, DemoMode, GameMode, HeatseekerInfo, MutatorConfig, Team, WorldContact,
+        Arena, ArenaConfig, ArenaMemWeightMode, BallHitInfo, BallState, BallStateRef, BoostPadConfig, BoostPadState,
+        CarConfig, CarContact, CarControls, CarState, CarStateRef, DemoMode, GameMode, GoalZone, HeatseekerInfo,
+        MutatorConfig, StepTimings, Team, WorldContact,
     },
 };
-use core::pin::Pin;
+use core::{
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    pin::Pin,
+};
 use cxx::UniquePtr;
+use smallvec::SmallVec;
 use std::{error::Error, fmt};
 
 #[cfg(feature = "serde_utils")]
@@ -147,6 +154,30 @@ impl fmt::Display for NoCarFound {
     }
 }
 
+#[derive(Clone, Copy, Debug)]
+/// Error for when a car can't be added with a given ID, because it's already taken or is 0
+pub struct CarIdTaken(pub u32);
+
+impl Error for CarIdTaken {}
+impl fmt::Display for CarIdTaken {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The car ID {} is already taken in the given arena.", self.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+/// Error for when a car can't be added because the arena already has its max number of cars
+pub struct ArenaFull(pub usize);
+
+impl Error for ArenaFull {}
+impl fmt::Display for ArenaFull {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The arena already has the max number of cars ({}).", self.0)
+    }
+}
+
 #[derive(Clone, Copy, Debug, Default)]
 #[cfg_attr(feature = "serde_utils", derive(Serialize, Deserialize))]
 pub struct BoostPad {
@@ -167,26 +198,313 @@ pub struct CarInfo {
     pub config: CarConfig,
 }
 
+/// The number of cars that a `GameState` can hold without a heap allocation, enough for 3v3
+pub const INLINE_CARS: usize = 6;
+/// The number of boost pads that a `GameState` can hold without a heap allocation, enough for soccar
+pub const INLINE_PADS: usize = 34;
+
+pub type CarInfos = SmallVec<[CarInfo; INLINE_CARS]>;
+pub type BoostPads = SmallVec<[BoostPad; INLINE_PADS]>;
+
 #[derive(Clone, Debug, Default)]
 #[cfg_attr(feature = "serde_utils", derive(Serialize, Deserialize))]
 pub struct GameState {
     pub tick_rate: f32,
     pub tick_count: u64,
     pub game_mode: GameMode,
-    pub cars: Vec<CarInfo>,
+    pub cars: CarInfos,
     #[cfg_attr(feature = "serde_utils", serde(with = "serde_utils::BallStateDerive"))]
     pub ball: BallState,
-    pub pads: Vec<BoostPad>,
+    pub pads: BoostPads,
+}
+
+#[derive(Clone, Copy, Debug)]
+/// Error for when a tick rate is outside of `Arena::MIN_TICK_RATE..=Arena::MAX_TICK_RATE`
+pub struct InvalidTickRate(pub f32);
+
+impl Error for InvalidTickRate {}
+impl fmt::Display for InvalidTickRate {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid tick rate {}, must be between {} and {}.",
+            self.0,
+            Arena::MIN_TICK_RATE,
+            Arena::MAX_TICK_RATE
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Error for when the spawns given to `Arena::reset_to_kickoff` can't be used
+pub enum InvalidKickoffSpawns {
+    /// The spawn index is past the end of the game mode's kickoff spawn locations
+    OutOfRange(usize),
+    /// The spawn index is used more than once
+    Duplicate(usize),
+    /// A team has more cars than there are spawns
+    TooFew { num_cars: usize, num_spawns: usize },
+}
+
+impl Error for InvalidKickoffSpawns {}
+impl fmt::Display for InvalidKickoffSpawns {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfRange(index) => write!(f, "Kickoff spawn index {index} is out of range."),
+            Self::Duplicate(index) => write!(f, "Kickoff spawn index {index} is used more than once."),
+            Self::TooFew { num_cars, num_spawns } => {
+                write!(
+                    f,
+                    "A team has {num_cars} cars, but only {num_spawns} kickoff spawns were given."
+                )
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_utils", derive(Serialize, Deserialize))]
+/// Where a car was placed for a kickoff
+pub struct KickoffSpawn {
+    pub car_id: u32,
+    pub team: Team,
+    /// Index into the game mode's kickoff spawn locations (see `kickoff_spawn_locations`),
+    /// mirrored for the orange team
+    ///
+    /// `None` if the car was placed somewhere else,
+    /// like a respawn location when the team has more cars than there are kickoff spawns
+    pub spawn_index: Option<usize>,
+}
+
+#[must_use]
+/// Returns the kickoff spawn locations of the blue team in the given game mode, which are mirrored for the orange team
+pub const fn kickoff_spawn_locations(game_mode: GameMode) -> &'static [CarSpawnPos] {
+    match game_mode {
+        GameMode::Hoops => &consts::CAR_SPAWN_LOCATIONS_HOOPS,
+        GameMode::Heatseeker => &consts::CAR_SPAWN_LOCATIONS_HEATSEEKER,
+        _ => &consts::CAR_SPAWN_LOCATIONS_SOCCAR,
+    }
+}
+
+/// Returns the position and yaw of a car on the given team at the spawn location
+fn kickoff_spawn_pos(spawn: &CarSpawnPos, team: Team) -> (Vec3, f32) {
+    match team {
+        Team::Blue => (Vec3::new(spawn.x, spawn.y, consts::CAR_SPAWN_REST_Z), spawn.yaw_ang),
+        Team::Orange => (
+            Vec3::new(-spawn.x, -spawn.y, consts::CAR_SPAWN_REST_Z),
+            spawn.yaw_ang + std::f32::consts::PI,
+        ),
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Everything that's needed to create an arena, see `Arena::from_config`
+///
+/// ```ignore
+/// let arena = Arena::from_config(
+///     ArenaSettings::new(GameMode::Soccar)
+///         .with_tick_rate(60)
+///         .with_mem_weight_mode(ArenaMemWeightMode::Light)
+///         .with_max_cars(2),
+/// )?;
+/// ```
+pub struct ArenaSettings {
+    pub game_mode: GameMode,
+    /// Ticks per second, between `Arena::MIN_TICK_RATE` and `Arena::MAX_TICK_RATE`
+    pub tick_rate: u8,
+    pub config: ArenaConfig,
+    /// The mutators to start with, `None` for the defaults of the game mode
+    pub mutators: Option<MutatorConfig>,
+    /// The boost pads to use instead of the game mode's layout, if any
+    pub boost_pads: Option<Vec<BoostPadConfig>>,
+    /// The most cars the arena can have, `None` for no limit
+    pub max_cars: Option<usize>,
+    /// Adds an infinite flat floor at `z = 0`, only used with `GameMode::TheVoid`
+    pub void_floor: bool,
+}
+
+impl Default for ArenaSettings {
+    #[inline]
+    fn default() -> Self {
+        Self::new(GameMode::Soccar)
+    }
+}
+
+impl From<GameMode> for ArenaSettings {
+    #[inline]
+    fn from(game_mode: GameMode) -> Self {
+        Self::new(game_mode)
+    }
+}
+
+impl ArenaSettings {
+    #[inline]
+    #[must_use]
+    /// The default settings for the game mode, running at 120 ticks per second
+    pub fn new(game_mode: GameMode) -> Self {
+        Self {
+            game_mode,
+            tick_rate: Arena::MAX_NATIVE_TICK_RATE,
+            config: ArenaConfig::default(),
+            mutators: None,
+            boost_pads: None,
+            max_cars: None,
+            void_floor: false,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn with_tick_rate(mut self, tick_rate: u8) -> Self {
+        self.tick_rate = tick_rate;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn with_config(mut self, config: ArenaConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn with_mem_weight_mode(mut self, mem_weight_mode: ArenaMemWeightMode) -> Self {
+        self.config.mem_weight_mode = mem_weight_mode;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn with_mutators(mut self, mutators: MutatorConfig) -> Self {
+        self.mutators = Some(mutators);
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Replaces the game mode's boost pads, an empty list removes all of them
+    pub fn with_boost_pads(mut self, boost_pads: Vec<BoostPadConfig>) -> Self {
+        self.boost_pads = Some(boost_pads);
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn with_max_cars(mut self, max_cars: usize) -> Self {
+        self.max_cars = Some(max_cars);
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn with_void_floor(mut self, void_floor: bool) -> Self {
+        self.void_floor = void_floor;
+        self
+    }
 }
 
 impl Arena {
+    pub const MIN_TICK_RATE: u8 = 15;
+    /// The highest tick rate that RocketSim can create an arena with directly
+    ///
+    /// Higher tick rates (up to `MAX_TICK_RATE`) are reached by creating the arena at this rate, then changing it
+    pub const MAX_NATIVE_TICK_RATE: u8 = 120;
+    pub const MAX_TICK_RATE: u8 = 240;
+
     #[inline]
     #[must_use]
     /// Create a new arena with the given game mode, arena config, and tick rate
     ///
-    /// Tick rate MUST be equal to or between 15 and 120
+    /// See `try_new` for a version that returns an error instead of panicking,
+    /// or `from_config` for everything else that can be set up when creating an arena
+    ///
+    /// # Panics
+    ///
+    /// If the tick rate is not equal to or between 15 and 240
     pub fn new(game_mode: GameMode, config: ArenaConfig, tick_rate: u8) -> UniquePtr<Self> {
-        base::CreateArena(game_mode, config, tick_rate)
+        Self::try_new(game_mode, config, tick_rate).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    #[inline]
+    /// Create a new arena with the given game mode, arena config, and tick rate
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidTickRate` if the tick rate is not equal to or between 15 and 240
+    pub fn try_new(game_mode: GameMode, config: ArenaConfig, tick_rate: u8) -> Result<UniquePtr<Self>, InvalidTickRate> {
+        if !(Self::MIN_TICK_RATE..=Self::MAX_TICK_RATE).contains(&tick_rate) {
+            return Err(InvalidTickRate(f32::from(tick_rate)));
+        }
+
+        let mut arena = base::CreateArena(game_mode, config, tick_rate.min(Self::MAX_NATIVE_TICK_RATE));
+        if tick_rate > Self::MAX_NATIVE_TICK_RATE {
+            arena.pin_mut().stkr(f32::from(tick_rate));
+        }
+
+        Ok(arena)
+    }
+
+    /// Changes the tick rate (in ticks per second) of all future ticks
+    ///
+    /// The tick count is left as-is, so it no longer maps to a constant amount of time across the change.
+    /// Timers in the car/ball/pad states are in seconds and are unaffected.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidTickRate` if the tick rate is not equal to or between 15 and 240
+    pub fn set_tick_rate(self: Pin<&mut Self>, tick_rate: f32) -> Result<(), InvalidTickRate> {
+        if !(f32::from(Self::MIN_TICK_RATE)..=f32::from(Self::MAX_TICK_RATE)).contains(&tick_rate) {
+            return Err(InvalidTickRate(tick_rate));
+        }
+
+        self.stkr(tick_rate);
+        Ok(())
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the duration of a single tick, in seconds
+    pub fn get_tick_time(&self) -> f32 {
+        1. / self.get_tick_rate()
+    }
+
+    /// Create a new arena from the settings, or from just a game mode
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidTickRate` if the tick rate is not equal to or between 15 and 240
+    pub fn from_config(settings: impl Into<ArenaSettings>) -> Result<UniquePtr<Self>, InvalidTickRate> {
+        let settings = settings.into();
+        if !(Self::MIN_TICK_RATE..=Self::MAX_TICK_RATE).contains(&settings.tick_rate) {
+            return Err(InvalidTickRate(f32::from(settings.tick_rate)));
+        }
+
+        let native_tick_rate = settings.tick_rate.min(Self::MAX_NATIVE_TICK_RATE);
+        let mut arena = match &settings.boost_pads {
+            Some(boost_pads) => {
+                base::CreateArenaWithBoostPads(settings.game_mode, settings.config, native_tick_rate, boost_pads)
+            }
+            None => base::CreateArena(settings.game_mode, settings.config, native_tick_rate),
+        };
+
+        if settings.tick_rate > Self::MAX_NATIVE_TICK_RATE {
+            arena.pin_mut().stkr(f32::from(settings.tick_rate));
+        }
+
+        if let Some(mutators) = settings.mutators {
+            arena.pin_mut().set_mutator_config(mutators);
+        }
+
+        arena.pin_mut().set_max_cars(settings.max_cars);
+
+        if settings.void_floor && settings.game_mode == GameMode::TheVoid {
+            arena.pin_mut().add_static_geometry(&StaticGeometry::FLOOR);
+        }
+
+        Ok(arena)
     }
 
     #[inline]
@@ -218,9 +536,209 @@ impl Arena {
     }
 
     #[inline]
-    /// Start ball and cars from random valid kickoff positions
-    pub fn reset_to_random_kickoff(self: Pin<&mut Self>, seed: Option<i32>) {
-        self.rtrk(seed.unwrap_or(-1));
+    #[must_use]
+    /// Create a new arena without any field geometry running at the max TPS
+    ///
+    /// Useful for pure car-control tasks (like recoveries or aerial orientation) where the field would get in the way
+    pub fn default_void() -> cxx::UniquePtr<Self> {
+        Self::new_void(ArenaConfig::default(), 120, false)
+    }
+
+    #[must_use]
+    /// Create a new arena without any field geometry (`GameMode::TheVoid`)
+    ///
+    /// If `with_floor` is true, an infinite flat floor is added at `z = 0`, otherwise there is nothing at all
+    pub fn new_void(config: ArenaConfig, tick_rate: u8, with_floor: bool) -> UniquePtr<Self> {
+        let mut arena = Self::new(GameMode::TheVoid, config, tick_rate);
+
+        if with_floor {
+            arena.pin_mut().add_static_geometry(&StaticGeometry::FLOOR);
+        }
+
+        arena
+    }
+
+    #[must_use]
+    /// Create a new arena that matches the given game state, including the IDs, teams and configs of the cars
+    ///
+    /// The tick rate is rounded to the nearest whole number
+    ///
+    /// # Panics
+    ///
+    /// If the game state has multiple cars with the same ID, a car with an ID of 0,
+    /// or a tick rate that doesn't round to between 15 and 240
+    pub fn from_game_state(game_state: &GameState, config: ArenaConfig) -> UniquePtr<Self> {
+        let tick_rate = game_state.tick_rate.round().clamp(0., f32::from(u8::MAX)) as u8;
+        let mut arena = Self::new(game_state.game_mode, config, tick_rate);
+
+        for car in &game_state.cars {
+            arena.pin_mut().add_car_with_id(car.id, car.team, &car.config).unwrap();
+        }
+
+        arena.pin_mut().set_game_state(game_state).unwrap();
+        arena.pin_mut().set_tick_count(game_state.tick_count);
+        arena
+    }
+
+    /// Start ball and cars from random valid kickoff positions, returning where each car was placed
+    ///
+    /// The same seed always gives the same spawns for the same cars,
+    /// `None` uses RocketSim's global random number generator instead.
+    /// See `reset_to_kickoff` for choosing the spawns.
+    pub fn reset_to_random_kickoff(mut self: Pin<&mut Self>, seed: Option<i32>) -> Vec<KickoffSpawn> {
+        self.as_mut().rtrk(seed.unwrap_or(-1));
+
+        let locations = kickoff_spawn_locations(self.get_game_mode());
+        let mut spawns = Vec::with_capacity(self.num_cars());
+
+        for car_id in self.get_cars() {
+            let team = self.get_car_team(car_id);
+            let pos = self.as_mut().get_car(car_id).pos;
+            let spawn_index = locations.iter().position(|spawn| {
+                let spawn_pos = kickoff_spawn_pos(spawn, team).0;
+                (spawn_pos.x - pos.x).abs() < 1. && (spawn_pos.y - pos.y).abs() < 1.
+            });
+
+            spawns.push(KickoffSpawn {
+                car_id,
+                team,
+                spawn_index,
+            });
+        }
+
+        spawns
+    }
+
+    /// Start ball and cars from a kickoff with the given spawns, returning where each car was placed
+    ///
+    /// The `i`th car of each team (in the order of `get_cars`) is placed at `spawns[i]`,
+    /// which is an index into `kickoff_spawn_locations` for the game mode. The orange team uses the mirrored spawns.
+    /// Everything else is reset like in `reset_to_random_kickoff`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidKickoffSpawns` if a spawn index is out of range or used more than once,
+    /// or if a team has more cars than there are spawns
+    pub fn reset_to_kickoff(mut self: Pin<&mut Self>, spawns: &[usize]) -> Result<Vec<KickoffSpawn>, InvalidKickoffSpawns> {
+        let locations = kickoff_spawn_locations(self.get_game_mode());
+
+        for (i, &index) in spawns.iter().enumerate() {
+            if index >= locations.len() {
+                return Err(InvalidKickoffSpawns::OutOfRange(index));
+            }
+
+            if spawns[..i].contains(&index) {
+                return Err(InvalidKickoffSpawns::Duplicate(index));
+            }
+        }
+
+        let cars = self
+            .get_cars()
+            .into_iter()
+            .map(|car_id| (car_id, self.get_car_team(car_id)))
+            .collect::<Vec<_>>();
+        for team in [Team::Blue, Team::Orange] {
+            let num_cars = cars.iter().filter(|(_, car_team)| *car_team == team).count();
+            if num_cars > spawns.len() {
+                return Err(InvalidKickoffSpawns::TooFew {
+                    num_cars,
+                    num_spawns: spawns.len(),
+                });
+            }
+        }
+
+        // the ball, boost pads and the rest of the car states are reset the same way as a random kickoff
+        self.as_mut().rtrk(0);
+
+        let mut team_counts = [0; 2];
+        let mut kickoff_spawns = Vec::with_capacity(cars.len());
+
+        for (car_id, team) in cars {
+            let spawn_index = spawns[team_counts[team as usize]];
+            team_counts[team as usize] += 1;
+
+            let (pos, yaw) = kickoff_spawn_pos(&locations[spawn_index], team);
+            let mut car_state = self.as_mut().get_car(car_id);
+            car_state.pos = pos;
+            car_state.rot_mat = Angle {
+                yaw,
+                pitch: 0.,
+                roll: 0.,
+            }
+            .to_rotmat();
+            let _ = self.as_mut().set_car(car_id, car_state);
+
+            kickoff_spawns.push(KickoffSpawn {
+                car_id,
+                team,
+                spawn_index: Some(spawn_index),
+            });
+        }
+
+        Ok(kickoff_spawns)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Adds a car to the arena with the given team and car config, returning its ID
+    ///
+    /// # Panics
+    ///
+    /// If the arena already has its max number of cars, see `try_add_car`
+    pub fn add_car(self: Pin<&mut Self>, team: Team, car_config: &CarConfig) -> u32 {
+        match self.try_add_car(team, car_config) {
+            Ok(car_id) => car_id,
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    #[inline]
+    /// Adds a car to the arena with the given team and car config, returning its ID
+    ///
+    /// # Errors
+    ///
+    /// If the arena already has its max number of cars, see `set_max_cars`
+    pub fn try_add_car(mut self: Pin<&mut Self>, team: Team, car_config: &CarConfig) -> Result<u32, ArenaFull> {
+        match self.as_mut().ac(team, car_config) {
+            0 => Err(ArenaFull(self.gmc())),
+            car_id => Ok(car_id),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The most cars that the arena can have, `None` if there's no limit
+    pub fn max_cars(&self) -> Option<usize> {
+        Some(self.gmc()).filter(|&max_cars| max_cars != 0)
+    }
+
+    #[inline]
+    /// Limits the number of cars that can be added, cars that are already in the arena aren't removed
+    pub fn set_max_cars(self: Pin<&mut Self>, max_cars: Option<usize>) {
+        self.smc(max_cars.unwrap_or_default());
+    }
+
+    #[inline]
+    /// Adds a car to the arena with the given ID, team and car config
+    ///
+    /// Useful for reconstructing an arena from recorded data, so the IDs match up.
+    /// Cars that are added normally afterwards will always get a higher ID.
+    ///
+    /// # Errors
+    ///
+    /// If there is already a car with the given ID, or the ID is 0 (which is reserved), this will return an error
+    ///
+    /// # Panics
+    ///
+    /// If the arena already has its max number of cars
+    pub fn add_car_with_id(self: Pin<&mut Self>, car_id: u32, team: Team, car_config: &CarConfig) -> Result<(), CarIdTaken> {
+        assert!(!self.is_full(), "{}", ArenaFull(self.gmc()));
+
+        if self.acwi(car_id, team, car_config) {
+            Ok(())
+        } else {
+            Err(CarIdTaken(car_id))
+        }
     }
 
     #[inline]
@@ -237,6 +755,36 @@ impl Arena {
         }
     }
 
+    #[inline]
+    /// Moves the car at the given ID to the other team, without changing its state
+    ///
+    /// # Errors
+    ///
+    /// If there is no car with the given ID, this will return an error
+    pub fn set_car_team(self: Pin<&mut Self>, car_id: u32, team: Team) -> Result<(), NoCarFound> {
+        if self.sct(car_id, team) {
+            Ok(())
+        } else {
+            Err(NoCarFound(car_id))
+        }
+    }
+
+    #[inline]
+    /// Changes the config (hitbox, wheels, etc.) of the car at the given ID
+    ///
+    /// The car is rebuilt with the new config, but keeps its ID, team, state and controls
+    ///
+    /// # Errors
+    ///
+    /// If there is no car with the given ID, this will return an error
+    pub fn set_car_config(self: Pin<&mut Self>, car_id: u32, car_config: &CarConfig) -> Result<(), NoCarFound> {
+        if self.scfg(car_id, car_config) {
+            Ok(())
+        } else {
+            Err(NoCarFound(car_id))
+        }
+    }
+
     #[inline]
     /// Sets the state of the car at the given ID
     ///
@@ -265,6 +813,23 @@ impl Arena {
         }
     }
 
+    #[inline]
+    /// Applies an impulse to the car with the given ID at the world-space point `at_point`
+    ///
+    /// The impulse is in the same units as `apply_impulse_to_ball` (see `consts::CAR_MASS_BT`).
+    /// An impulse that isn't applied at the car's center of mass also spins the car.
+    ///
+    /// # Errors
+    ///
+    /// If there is no car with the given ID, this will return an error
+    pub fn apply_impulse_to_car(self: Pin<&mut Self>, car_id: u32, impulse: Vec3, at_point: Vec3) -> Result<(), NoCarFound> {
+        if self.aci(car_id, impulse, at_point) {
+            Ok(())
+        } else {
+            Err(NoCarFound(car_id))
+        }
+    }
+
     #[inline]
     /// Demolishes the car with the given ID
     ///
@@ -302,8 +867,29 @@ impl Arena {
     }
 
     #[inline]
-    #[must_use]
-    /// Get all the avalible information on a car
+    /// Returns a read-only view into the state of the car with the given ID, without copying the whole state
+    ///
+    /// # Errors
+    ///
+    /// If there is no car with the given ID, this will return an error
+    pub fn car_state_ref(&self, car_id: u32) -> Result<CarStateRef<'_>, NoCarFound> {
+        if self.has_car(car_id) {
+            Ok(CarStateRef::new(self, car_id))
+        } else {
+            Err(NoCarFound(car_id))
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns a read-only view into the state of the ball, without copying the whole state
+    pub fn ball_state_ref(&self) -> BallStateRef<'_> {
+        BallStateRef::new(self)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Get all the avalible information on a car
     pub fn get_car_info(self: Pin<&mut Self>, car_id: u32) -> CarInfo {
         CarInfo {
             id: car_id,
@@ -350,10 +936,19 @@ impl Arena {
     /// # Errors
     ///
     /// Returns `NoCarFound` upon the first car that cannot be found from a given ID
-    pub fn set_all_controls(mut self: Pin<&mut Self>, controls: &[(u32, CarControls)]) -> Result<(), NoCarFound> {
+    pub fn set_all_controls(
+        mut self: Pin<&mut Self>,
+        controls: impl IntoIterator<Item = (u32, CarControls)>,
+    ) -> Result<(), NoCarFound> {
         controls
-            .iter()
-            .try_for_each(|&(car_id, car_controls)| self.as_mut().set_car_controls(car_id, car_controls))
+            .into_iter()
+            .try_for_each(|(car_id, car_controls)| self.as_mut().set_car_controls(car_id, car_controls))
+    }
+
+    #[inline]
+    /// Sets the controls of every car on the given team
+    pub fn set_controls_for_team(self: Pin<&mut Self>, team: Team, car_controls: CarControls) {
+        self.stcc(team, car_controls);
     }
 
     #[inline]
@@ -366,13 +961,18 @@ impl Arena {
             game_mode: self.get_game_mode(),
             pads: self.iter_pads().collect(),
             ball: self.as_mut().get_ball(),
-            cars: self.get_car_infos(),
+            cars: self
+                .get_cars()
+                .into_iter()
+                .map(|car_id| self.as_mut().get_car_info(car_id))
+                .collect(),
         }
     }
 
     /// Full game state setter
     ///
     /// Note: Some things cannot be state set, such game tick count/tick rate - these will be ignored
+    /// (see `set_tick_count` to set the tick count separately)
     ///
     /// # Errors
     ///
@@ -406,6 +1006,114 @@ impl Arena {
     pub fn is_ball_probably_going_in(&self, max_time: Option<f32>, extra_margin: Option<f32>) -> bool {
         self.ibpgi(max_time.unwrap_or(0.2), extra_margin.unwrap_or_default())
     }
+
+    #[inline]
+    /// Iterates over the custom goal zones, see `set_goal_zones`
+    pub fn iter_goal_zones(&self) -> impl Iterator<Item = GoalZone> + '_ {
+        (0..self.num_goal_zones()).map(|i| self.get_goal_zone(i))
+    }
+
+    #[inline]
+    /// Disables scoring entirely, until `use_default_goal_zones` or `set_goal_zones` is called
+    pub fn disable_scoring(self: Pin<&mut Self>) {
+        self.set_goal_zones(&[]);
+    }
+}
+
+#[cfg(feature = "bullet")]
+impl Arena {
+    #[inline]
+    #[must_use]
+    /// Returns a pointer to the arena's underlying `btDiscreteDynamicsWorld`
+    ///
+    /// This is an escape hatch for adding custom constraints or extra rigid bodies,
+    /// or running queries (like ray or convex sweeps) that this crate doesn't expose.
+    /// The pointer has to be cast to the world type of your own Bullet bindings,
+    /// which must be built from the same Bullet sources and config (including `double_precision`) as RocketSim.
+    ///
+    /// # Safety
+    ///
+    /// The pointer is only valid for as long as the arena is alive, and must not be used while the arena is stepping.
+    /// RocketSim keeps its own references to the bodies it created,
+    /// so they must not be removed from the world or destroyed, and anything added to the world
+    /// must be removed again before the arena is dropped.
+    /// Bodies added this way aren't included in `clone`, serialization or any of the state getters.
+    pub unsafe fn bullet_world(self: Pin<&mut Self>) -> *mut std::ffi::c_void {
+        self.bworld().cast()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the number of collision objects in the Bullet world, including the arena's own meshes
+    ///
+    /// Useful for checking that bodies added through `bullet_world` were removed again
+    pub fn bullet_num_collision_objects(&self) -> usize {
+        self.bnco()
+    }
+}
+
+impl StepTimings {
+    #[must_use]
+    /// Returns the average time spent per tick, with `ticks` set to 1
+    pub fn per_tick(&self) -> Self {
+        if self.ticks == 0 {
+            return Self::default();
+        }
+
+        let ticks = self.ticks as f64;
+        Self {
+            ticks: 1,
+            total: self.total / ticks,
+            cars_and_ball: self.cars_and_ball / ticks,
+            broadphase: self.broadphase / ticks,
+            narrowphase: self.narrowphase / ticks,
+            solver: self.solver / ticks,
+            other_bullet: self.other_bullet / ticks,
+            callbacks: self.callbacks / ticks,
+        }
+    }
+}
+
+impl fmt::Display for StepTimings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let per_tick = self.per_tick();
+        writeln!(f, "{} ticks, {:.2}us per tick", self.ticks, per_tick.total * 1e6)?;
+
+        for (name, time) in [
+            ("cars and ball", per_tick.cars_and_ball),
+            ("broadphase", per_tick.broadphase),
+            ("narrowphase", per_tick.narrowphase),
+            ("solver", per_tick.solver),
+            ("other bullet", per_tick.other_bullet),
+            ("callbacks", per_tick.callbacks),
+        ] {
+            let percent = if per_tick.total > 0. {
+                time / per_tick.total * 100.
+            } else {
+                0.
+            };
+            writeln!(f, "  {name:<14}{:>9.2}us {percent:>5.1}%", time * 1e6)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl GoalZone {
+    #[inline]
+    #[must_use]
+    /// A zone that `team` scores in when the ball enters the box between `min` and `max`
+    pub const fn new(min: Vec3, max: Vec3, team: Team) -> Self {
+        Self { min, max, team }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, pos: Vec3) -> bool {
+        (self.min.x..=self.max.x).contains(&pos.x)
+            && (self.min.y..=self.max.y).contains(&pos.y)
+            && (self.min.z..=self.max.z).contains(&pos.z)
+    }
 }
 
 impl Default for HeatseekerInfo {
@@ -478,7 +1186,202 @@ impl Default for CarState {
     }
 }
 
+impl CarControls {
+    #[must_use]
+    /// Converts a sequence of per-tick controls recorded at one tick rate to another tick rate
+    ///
+    /// Each output tick covers a window of time; analog inputs are averaged over the source ticks
+    /// that overlap that window, and buttons are held if they were held in any of those source ticks
+    /// (so short presses are never dropped when downsampling).
+    pub fn resample(controls: &[Self], from_tick_rate: f32, to_tick_rate: f32) -> Vec<Self> {
+        if controls.is_empty() {
+            return Vec::new();
+        }
+
+        let duration = controls.len() as f32 / from_tick_rate;
+        let num_ticks = (duration * to_tick_rate).round().max(1.) as usize;
+
+        (0..num_ticks)
+            .map(|i| {
+                let start = i as f32 / to_tick_rate * from_tick_rate;
+                let end = (i + 1) as f32 / to_tick_rate * from_tick_rate;
+
+                let first = (start.floor() as usize).min(controls.len() - 1);
+                let last = ((end.ceil() as usize).max(first + 1)).min(controls.len());
+
+                let mut out = Self::default();
+                let mut total_weight = 0.;
+
+                for (j, source) in controls.iter().enumerate().take(last).skip(first) {
+                    let weight = end.min((j + 1) as f32) - start.max(j as f32);
+                    if weight <= 0. {
+                        continue;
+                    }
+
+                    out.throttle += source.throttle * weight;
+                    out.steer += source.steer * weight;
+                    out.pitch += source.pitch * weight;
+                    out.yaw += source.yaw * weight;
+                    out.roll += source.roll * weight;
+                    out.jump |= source.jump;
+                    out.boost |= source.boost;
+                    out.handbrake |= source.handbrake;
+                    total_weight += weight;
+                }
+
+                if total_weight <= 0. {
+                    return controls[first];
+                }
+
+                out.throttle /= total_weight;
+                out.steer /= total_weight;
+                out.pitch /= total_weight;
+                out.yaw /= total_weight;
+                out.roll /= total_weight;
+                out
+            })
+            .collect()
+    }
+}
+
 impl CarState {
+    #[inline]
+    #[must_use]
+    /// Converts a point in world space to the car's local space, where X is forward, Y is right, and Z is up
+    pub fn to_local(&self, point: Vec3) -> Vec3 {
+        self.rot_mat.to_local(point - self.pos)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Converts a point in the car's local space to world space
+    pub fn to_world(&self, local_point: Vec3) -> Vec3 {
+        self.pos + self.rot_mat * local_point
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns true if the car is supersonic, as RocketSim reports it
+    ///
+    /// States that weren't made by RocketSim (e.g. from a replay) may not set `is_supersonic`,
+    /// so cars at or above `SUPERSONIC_START_SPEED` also count
+    pub fn is_supersonic(&self) -> bool {
+        self.is_supersonic || self.vel.length_squared() >= consts::SUPERSONIC_START_SPEED * consts::SUPERSONIC_START_SPEED
+    }
+
+    #[inline]
+    #[must_use]
+    /// The surface that the car is driving on, or `None` if it's not on the ground
+    ///
+    /// Uses the same thresholds as `events::CarEventTracker`
+    pub fn surface(&self) -> Option<Surface> {
+        self.is_on_ground.then(|| Surface::from_normal(self.rot_mat.up))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns true if the car is driving on a wall
+    pub fn is_on_wall(&self) -> bool {
+        self.surface() == Some(Surface::Wall)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns true if the car is driving on the ceiling
+    pub fn is_on_ceiling(&self) -> bool {
+        self.surface() == Some(Surface::Ceiling)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Seconds since the car last had its wheels on a surface, or 0 if it's on the ground
+    ///
+    /// This is counted by RocketSim every tick, so it's exact for the arena's tick rate
+    pub fn time_airborne(&self) -> f32 {
+        if self.is_on_ground {
+            0.
+        } else {
+            self.air_time
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns true if the car can still dodge (or double jump) right now
+    ///
+    /// Cars that drove off of a surface without jumping can dodge at any time,
+    /// otherwise it has to be within `DOUBLEJUMP_MAX_DELAY` of the end of the jump
+    pub fn can_dodge(&self) -> bool {
+        !self.is_on_ground
+            && !self.is_demoed
+            && !self.has_flipped
+            && !self.has_double_jumped
+            && self.air_time_since_jump < consts::DOUBLEJUMP_MAX_DELAY
+    }
+
+    #[inline]
+    #[must_use]
+    /// Seconds left for the car to dodge (or double jump), or `None` if it can't
+    ///
+    /// This is infinite if the car didn't jump, and doesn't count down until the jump is finished
+    pub fn dodge_time_left(&self) -> Option<f32> {
+        if !self.can_dodge() {
+            None
+        } else if self.has_jumped {
+            Some(consts::DOUBLEJUMP_MAX_DELAY - self.air_time_since_jump)
+        } else {
+            Some(f32::INFINITY)
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Seconds left until the torque of the current flip stops, or `None` if the car isn't flipping
+    pub fn flip_time_left(&self) -> Option<f32> {
+        self.is_flipping.then(|| (consts::FLIP_TORQUE_TIME - self.flip_time).max(0.))
+    }
+
+    #[must_use]
+    /// The instant change in velocity that a dodge towards the (world) direction would produce right now,
+    /// using RocketSim's flip impulse model
+    ///
+    /// Only the horizontal part of the direction matters, since the dodge is relative to the car's heading.
+    /// A direction with no horizontal part is a double jump, which pushes the car along its up direction instead.
+    /// The speed-dependent scaling makes dodges against the car's direction of travel (and sideways dodges) stronger.
+    /// The vertical damping that's applied over the course of the flip isn't included.
+    pub fn dodge_delta_vel(&self, direction: Vec3) -> Vec3 {
+        let forward = Vec3::new(self.rot_mat.forward.x, self.rot_mat.forward.y, 0.).normalize_or_zero();
+        let right = Vec3::new(-forward.y, forward.x, 0.);
+
+        let dodge_dir = Vec3::new(direction.dot(forward), direction.dot(right), 0.).normalize_or_zero();
+        if dodge_dir == Vec3::ZERO {
+            return self.rot_mat.up * consts::JUMP_IMMEDIATE_FORCE;
+        }
+
+        let forward_speed = self.vel.dot(self.rot_mat.forward);
+        let forward_speed_ratio = forward_speed.abs() / consts::CAR_MAX_SPEED;
+        let dodging_backwards = if forward_speed.abs() < 100. {
+            dodge_dir.x < 0.
+        } else {
+            (dodge_dir.x >= 0.) != (forward_speed > 0.)
+        };
+
+        let mut dodge_vel = dodge_dir * consts::FLIP_INITIAL_VEL_SCALE;
+        let max_speed_scale_x = if dodging_backwards {
+            consts::FLIP_BACKWARD_IMPULSE_MAX_SPEED_SCALE
+        } else {
+            consts::FLIP_FORWARD_IMPULSE_MAX_SPEED_SCALE
+        };
+
+        dodge_vel.x *= (max_speed_scale_x - 1.) * forward_speed_ratio + 1.;
+        dodge_vel.y *= (consts::FLIP_SIDE_IMPULSE_MAX_SPEED_SCALE - 1.) * forward_speed_ratio + 1.;
+        if dodging_backwards {
+            dodge_vel.x *= consts::FLIP_BACKWARD_IMPULSE_SCALE_X;
+        }
+
+        forward * dodge_vel.x + right * dodge_vel.y
+    }
+
     #[inline]
     #[must_use]
     /// Returns the other Car that this Car is currently contacting, if any
@@ -510,6 +1413,86 @@ impl RotMat {
     pub const fn new(forward: Vec3, right: Vec3, up: Vec3) -> Self {
         Self { forward, right, up }
     }
+
+    #[must_use]
+    /// Removes any skew and scale from the matrix, keeping the direction of `forward`
+    ///
+    /// `up` is made perpendicular to `forward` and `right` is rebuilt from the two (Gram-Schmidt).
+    /// Returns the identity matrix if `forward` is zero or `up` is parallel to it.
+    pub fn orthonormalize(self) -> Self {
+        Self::look_at(self.forward, self.up)
+    }
+
+    #[must_use]
+    /// A rotation matrix facing `forward`, with its up axis as close to `up` as possible
+    ///
+    /// Neither vector has to be normalized.
+    /// Returns the identity matrix if `forward` is zero or `up` is parallel to it.
+    pub fn look_at(forward: Vec3, up: Vec3) -> Self {
+        let forward = forward.normalize_or_zero();
+        let up = (up - forward * forward.dot(up)).normalize_or_zero();
+
+        if forward == Vec3::ZERO || up == Vec3::ZERO {
+            return Self::IDENTITY;
+        }
+
+        Self::new(forward, up.cross(forward), up)
+    }
+
+    #[must_use]
+    /// A rotation matrix facing `forward`, with no roll
+    ///
+    /// If `forward` is straight up or down, the up axis points towards -X or +X respectively instead.
+    pub fn from_forward(forward: Vec3) -> Self {
+        let dir = forward.normalize_or_zero();
+        if dir.x.abs() < f32::EPSILON && dir.y.abs() < f32::EPSILON {
+            return Self::look_at(forward, Vec3::X * -dir.z.signum());
+        }
+
+        Self::look_at(forward, Vec3::Z)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Swaps the rows and columns, which is the inverse of an orthonormal matrix
+    pub fn transpose(self) -> Self {
+        let Self { forward, right, up } = self;
+
+        Self::new(
+            Vec3::new(forward.x, right.x, up.x),
+            Vec3::new(forward.y, right.y, up.y),
+            Vec3::new(forward.z, right.z, up.z),
+        )
+    }
+
+    #[inline]
+    #[must_use]
+    /// Converts a direction in world space to this matrix's local space, where X is forward, Y is right, and Z is up
+    ///
+    /// The inverse of `self * vec`
+    pub fn to_local(self, vec: Vec3) -> Vec3 {
+        Vec3::new(self.forward.dot(vec), self.right.dot(vec), self.up.dot(vec))
+    }
+}
+
+impl Mul<Vec3> for RotMat {
+    type Output = Vec3;
+
+    #[inline]
+    /// Rotates the vector, converting it from this matrix's local space to world space
+    fn mul(self, rhs: Vec3) -> Vec3 {
+        self.forward * rhs.x + self.right * rhs.y + self.up * rhs.z
+    }
+}
+
+impl Mul for RotMat {
+    type Output = Self;
+
+    #[inline]
+    /// Applies `rhs` first, then `self`
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(self * rhs.forward, self * rhs.right, self * rhs.up)
+    }
 }
 
 impl Angle {
@@ -545,6 +1528,246 @@ impl Vec3 {
     pub const fn new(x: f32, y: f32, z: f32) -> Self {
         Self { x, y, z, w: 0. }
     }
+
+    #[inline]
+    #[must_use]
+    pub fn dot(self, other: Self) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn length_squared(self) -> f32 {
+        self.dot(self)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn length(self) -> f32 {
+        self.length_squared().sqrt()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn cross(self, other: Self) -> Self {
+        Self::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn distance(self, other: Self) -> f32 {
+        (self - other).length()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn distance_squared(self, other: Self) -> f32 {
+        (self - other).length_squared()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the vector scaled to a length of 1, or `None` if the length is zero or not finite
+    pub fn try_normalize(self) -> Option<Self> {
+        let length = self.length();
+        if length > 0. && length.is_finite() {
+            Some(self / length)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the vector scaled to a length of 1, or zero if the length is zero or not finite
+    pub fn normalize_or_zero(self) -> Self {
+        self.try_normalize().unwrap_or(Self::ZERO)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the vector scaled down to at most `max` length
+    pub fn clamp_length_max(self, max: f32) -> Self {
+        let length_squared = self.length_squared();
+        if length_squared > max * max {
+            self * (max / length_squared.sqrt())
+        } else {
+            self
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the angle between the two vectors in radians, in `0..=PI`
+    ///
+    /// Returns 0 if either vector is zero
+    pub fn angle_between(self, other: Self) -> f32 {
+        let lengths = (self.length_squared() * other.length_squared()).sqrt();
+        if lengths == 0. {
+            return 0.;
+        }
+
+        (self.dot(other) / lengths).clamp(-1., 1.).acos()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Linear interpolation, where `alpha` of 0 is `self` and 1 is `other`
+    pub fn lerp(self, other: Self, alpha: f32) -> Self {
+        self + (other - self) * alpha
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn abs(self) -> Self {
+        Self::new(self.x.abs(), self.y.abs(), self.z.abs())
+    }
+
+    #[inline]
+    #[must_use]
+    /// Component-wise minimum
+    pub fn min(self, other: Self) -> Self {
+        Self::new(self.x.min(other.x), self.y.min(other.y), self.z.min(other.z))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Component-wise maximum
+    pub fn max(self, other: Self) -> Self {
+        Self::new(self.x.max(other.x), self.y.max(other.y), self.z.max(other.z))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Component-wise clamp
+    pub fn clamp(self, min: Self, max: Self) -> Self {
+        self.max(min).min(max)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn min_element(self) -> f32 {
+        self.x.min(self.y).min(self.z)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn max_element(self) -> f32 {
+        self.x.max(self.y).max(self.z)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns true if all of the components are neither NaN nor infinite
+    pub fn is_finite(self) -> bool {
+        self.x.is_finite() && self.y.is_finite() && self.z.is_finite()
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl Mul for Vec3 {
+    type Output = Self;
+
+    #[inline]
+    /// Component-wise multiplication
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(self.x * rhs.x, self.y * rhs.y, self.z * rhs.z)
+    }
+}
+
+impl Div for Vec3 {
+    type Output = Self;
+
+    #[inline]
+    /// Component-wise division
+    fn div(self, rhs: Self) -> Self {
+        Self::new(self.x / rhs.x, self.y / rhs.y, self.z / rhs.z)
+    }
+}
+
+impl Mul<Vec3> for f32 {
+    type Output = Vec3;
+
+    #[inline]
+    fn mul(self, rhs: Vec3) -> Vec3 {
+        rhs * self
+    }
+}
+
+impl AddAssign for Vec3 {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Vec3 {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign<f32> for Vec3 {
+    #[inline]
+    fn mul_assign(&mut self, rhs: f32) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign<f32> for Vec3 {
+    #[inline]
+    fn div_assign(&mut self, rhs: f32) {
+        *self = *self / rhs;
+    }
+}
+
+impl Mul<f32> for Vec3 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: f32) -> Self {
+        Self::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl Div<f32> for Vec3 {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, rhs: f32) -> Self {
+        Self::new(self.x / rhs, self.y / rhs, self.z / rhs)
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z)
+    }
 }
 
 pub struct LinearPieceCurve<const N: usize> {
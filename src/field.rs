@@ -0,0 +1,276 @@
+use crate::{
+    consts,
+    math::Vec3,
+    sim::{GameMode, Team},
+};
+use std::f32::consts::FRAC_1_SQRT_2;
+
+pub mod soccar {
+    /// The 45 degree corner walls are where `|x| + |y|` equals this
+    pub const CORNER_SUM: f32 = 8064.;
+    pub const GOAL_HALF_WIDTH: f32 = 892.755;
+    pub const GOAL_HEIGHT: f32 = 642.775;
+    /// How far the goal extends past the back wall
+    pub const GOAL_DEPTH: f32 = 880.;
+}
+
+pub mod hoops {
+    use crate::{consts, math::Vec3, sim::Team};
+
+    /// The scoring area of each hoop is an ellipse: `x^2 + (|y| * GOAL_SCALE_Y - GOAL_OFFSET_Y)^2 < GOAL_RADIUS^2`
+    pub const GOAL_SCALE_Y: f32 = 0.9;
+    pub const GOAL_OFFSET_Y: f32 = 2770.;
+    pub const GOAL_RADIUS: f32 = 716.;
+
+    /// Approximate height of the top of the rim
+    pub const RIM_HEIGHT: f32 = 365.;
+    /// Distance from the center of the field to the center of each rim
+    pub const RIM_CENTER_Y: f32 = GOAL_OFFSET_Y / GOAL_SCALE_Y;
+    /// Half of the width of the rim ellipse
+    pub const RIM_RADIUS_X: f32 = GOAL_RADIUS;
+    /// Half of the depth of the rim ellipse
+    pub const RIM_RADIUS_Y: f32 = GOAL_RADIUS / GOAL_SCALE_Y;
+
+    /// The backboard is the part of the back wall above each rim
+    pub const BACKBOARD_Y: f32 = consts::ARENA_EXTENT_Y_HOOPS;
+    /// Approximate half of the width of the backboard
+    pub const BACKBOARD_HALF_WIDTH: f32 = 1000.;
+    pub const BACKBOARD_MIN_Z: f32 = RIM_HEIGHT;
+    /// Approximate height of the top of the backboard
+    pub const BACKBOARD_MAX_Z: f32 = 1300.;
+
+    #[inline]
+    #[must_use]
+    /// Returns the team that defends the hoop on the side of the given position
+    pub fn hoop_team(pos: Vec3) -> Team {
+        if pos.y < 0. {
+            Team::Blue
+        } else {
+            Team::Orange
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns true if the position is above a rim and within its ellipse (i.e. the ball could drop straight in)
+    pub fn is_above_rim(pos: Vec3) -> bool {
+        let dx = pos.x / RIM_RADIUS_X;
+        let dy = (pos.y.abs() - RIM_CENTER_Y) / RIM_RADIUS_Y;
+
+        pos.z > RIM_HEIGHT && dx * dx + dy * dy < 1.
+    }
+
+    #[must_use]
+    /// Approximate distance from the position to the nearest point on the rim
+    ///
+    /// The rim is treated as an infinitely thin ellipse at `RIM_HEIGHT`
+    pub fn distance_to_rim(pos: Vec3) -> f32 {
+        let local_y = pos.y.abs() - RIM_CENTER_Y;
+        let angle = (local_y / RIM_RADIUS_Y).atan2(pos.x / RIM_RADIUS_X);
+        let rim_point = Vec3::new(
+            RIM_RADIUS_X * angle.cos(),
+            RIM_CENTER_Y + RIM_RADIUS_Y * angle.sin(),
+            RIM_HEIGHT,
+        );
+
+        (Vec3::new(pos.x, pos.y.abs(), pos.z) - rim_point).length()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Distance from the position to the plane of the nearest backboard,
+    /// or `None` if the position is outside of the backboard's bounds
+    pub fn distance_to_backboard(pos: Vec3) -> Option<f32> {
+        (pos.x.abs() < BACKBOARD_HALF_WIDTH && (BACKBOARD_MIN_Z..BACKBOARD_MAX_Z).contains(&pos.z))
+            .then(|| BACKBOARD_Y - pos.y.abs())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// An infinite plane, with the normal facing into the field
+pub struct Plane {
+    pub normal: Vec3,
+    pub offset: f32,
+}
+
+impl Plane {
+    #[inline]
+    #[must_use]
+    pub const fn new(normal: Vec3, offset: f32) -> Self {
+        Self { normal, offset }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Signed distance from the plane, positive if the point is on the field side
+    pub fn distance(&self, pos: Vec3) -> f32 {
+        pos.dot(self.normal) - self.offset
+    }
+
+    #[inline]
+    #[must_use]
+    /// Projects the point onto the plane
+    pub fn project(&self, pos: Vec3) -> Vec3 {
+        pos - self.normal * self.distance(pos)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SurfacePoint {
+    pub pos: Vec3,
+    /// Normal of the surface, facing into the field
+    pub normal: Vec3,
+    pub distance: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Simplified field geometry for a game mode
+///
+/// Walls are approximated by flat planes, so the curved ramps between surfaces are ignored
+pub struct Field {
+    pub game_mode: GameMode,
+    pub extent_x: f32,
+    pub extent_y: f32,
+    pub height: f32,
+    /// `|x| + |y|` of the corner walls, if the field has them
+    pub corner_sum: Option<f32>,
+}
+
+impl Field {
+    pub const SOCCAR: Self = Self {
+        game_mode: GameMode::Soccar,
+        extent_x: consts::ARENA_EXTENT_X,
+        extent_y: consts::ARENA_EXTENT_Y,
+        height: consts::ARENA_HEIGHT,
+        corner_sum: Some(soccar::CORNER_SUM),
+    };
+
+    pub const HOOPS: Self = Self {
+        game_mode: GameMode::Hoops,
+        extent_x: consts::ARENA_EXTENT_X_HOOPS,
+        extent_y: consts::ARENA_EXTENT_Y_HOOPS,
+        height: consts::ARENA_HEIGHT_HOOPS,
+        corner_sum: None,
+    };
+
+    #[inline]
+    #[must_use]
+    /// Returns the field of the given game mode, or `None` for `TheVoid`
+    pub const fn new(game_mode: GameMode) -> Option<Self> {
+        match game_mode {
+            GameMode::Soccar | GameMode::Heatseeker | GameMode::Snowday => Some(Self {
+                game_mode,
+                ..Self::SOCCAR
+            }),
+            GameMode::Hoops => Some(Self::HOOPS),
+            GameMode::TheVoid => None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn has_box_goals(&self) -> bool {
+        !matches!(self.game_mode, GameMode::Hoops)
+    }
+
+    #[must_use]
+    /// Returns the goal that the position is inside of, if any
+    pub fn goal_at(&self, pos: Vec3) -> Option<Team> {
+        let in_goal = if self.has_box_goals() {
+            pos.y.abs() > self.extent_y && pos.x.abs() < soccar::GOAL_HALF_WIDTH && pos.z < soccar::GOAL_HEIGHT
+        } else {
+            let dy = pos.y.abs() * hoops::GOAL_SCALE_Y - hoops::GOAL_OFFSET_Y;
+            pos.z < consts::HOOPS_GOAL_SCORE_THRESHOLD_Z && pos.x * pos.x + dy * dy < hoops::GOAL_RADIUS * hoops::GOAL_RADIUS
+        };
+
+        in_goal.then_some(if pos.y < 0. { Team::Blue } else { Team::Orange })
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns true if the position is inside of either goal
+    pub fn is_in_goal(&self, pos: Vec3) -> bool {
+        self.goal_at(pos).is_some()
+    }
+
+    /// Returns the walls (excluding the floor and ceiling) that are relevant at the given position
+    ///
+    /// Inside of a goal, these are the walls of the goal instead of the field
+    fn walls_at(&self, pos: Vec3) -> Vec<Plane> {
+        if self.has_box_goals() && pos.y.abs() > self.extent_y && pos.x.abs() < soccar::GOAL_HALF_WIDTH {
+            let sign_y = pos.y.signum();
+            return vec![
+                Plane::new(Vec3::X, -soccar::GOAL_HALF_WIDTH),
+                Plane::new(-Vec3::X, -soccar::GOAL_HALF_WIDTH),
+                Plane::new(Vec3::new(0., -sign_y, 0.), -(self.extent_y + soccar::GOAL_DEPTH)),
+            ];
+        }
+
+        let mut walls = vec![
+            Plane::new(Vec3::X, -self.extent_x),
+            Plane::new(-Vec3::X, -self.extent_x),
+            Plane::new(Vec3::Y, -self.extent_y),
+            Plane::new(-Vec3::Y, -self.extent_y),
+        ];
+
+        if let Some(corner_sum) = self.corner_sum {
+            for (sign_x, sign_y) in [(1., 1.), (1., -1.), (-1., 1.), (-1., -1.)] {
+                let normal = Vec3::new(-sign_x * FRAC_1_SQRT_2, -sign_y * FRAC_1_SQRT_2, 0.);
+                walls.push(Plane::new(normal, -corner_sum * FRAC_1_SQRT_2));
+            }
+        }
+
+        walls
+    }
+
+    /// Returns the floor and ceiling at the given position
+    fn floor_and_ceiling_at(&self, pos: Vec3) -> [Plane; 2] {
+        let height = if self.has_box_goals() && pos.y.abs() > self.extent_y {
+            soccar::GOAL_HEIGHT
+        } else {
+            self.height
+        };
+
+        [Plane::new(Vec3::Z, 0.), Plane::new(-Vec3::Z, -height)]
+    }
+
+    #[must_use]
+    /// Returns the nearest point on any wall (side, back, or corner, but not the floor or ceiling)
+    pub fn nearest_wall_point(&self, pos: Vec3) -> SurfacePoint {
+        Self::nearest_point(pos, self.walls_at(pos))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the distance to the nearest wall (side, back, or corner, but not the floor or ceiling)
+    pub fn distance_to_nearest_wall(&self, pos: Vec3) -> f32 {
+        self.nearest_wall_point(pos).distance
+    }
+
+    #[must_use]
+    /// Returns the nearest point on any surface of the field, including the floor and ceiling
+    pub fn nearest_surface_point(&self, pos: Vec3) -> SurfacePoint {
+        let mut planes = self.walls_at(pos);
+        planes.extend(self.floor_and_ceiling_at(pos));
+        Self::nearest_point(pos, planes)
+    }
+
+    fn nearest_point(pos: Vec3, planes: Vec<Plane>) -> SurfacePoint {
+        planes
+            .into_iter()
+            .map(|plane| SurfacePoint {
+                pos: plane.project(pos),
+                normal: plane.normal,
+                distance: plane.distance(pos),
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+            .unwrap()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns true if the position is inside of the playable area (or inside of a goal)
+    pub fn contains(&self, pos: Vec3) -> bool {
+        self.nearest_surface_point(pos).distance >= 0.
+    }
+}
@@ -0,0 +1,142 @@
+//! User-defined static collision geometry
+//!
+//! Extra geometry is added to the arena's physics world alongside the field,
+//! so it can be used to build obstacle courses, training maps, or custom game modes.
+//! All positions and sizes are in Unreal units.
+
+use crate::{
+    math::{RotMat, Vec3},
+    sim::{Arena, ArenaConfig, GameMode},
+};
+use core::pin::Pin;
+use cxx::UniquePtr;
+
+#[derive(Clone, Debug, PartialEq)]
+/// A static collision shape that can be added to an arena
+pub enum StaticGeometry {
+    /// A box, rotated around its center
+    Box {
+        center: Vec3,
+        half_extents: Vec3,
+        rot_mat: RotMat,
+    },
+    Sphere {
+        center: Vec3,
+        radius: f32,
+    },
+    /// An infinite plane, where everything behind it (on the opposite side of `normal`) is solid
+    Plane {
+        normal: Vec3,
+        offset: f32,
+    },
+    /// A triangle mesh, where every 3 `indices` are the indices of the `vertices` of a triangle
+    ///
+    /// Meshes have no volume, so fast objects can pass through thin parts of them
+    Mesh {
+        vertices: Vec<Vec3>,
+        indices: Vec<u32>,
+    },
+}
+
+impl StaticGeometry {
+    /// An infinite flat floor at `z = 0`
+    pub const FLOOR: Self = Self::Plane {
+        normal: Vec3::Z,
+        offset: 0.,
+    };
+
+    #[inline]
+    #[must_use]
+    /// An axis-aligned box
+    pub fn aabb(min: Vec3, max: Vec3) -> Self {
+        Self::Box {
+            center: Vec3::new((min.x + max.x) / 2., (min.y + max.y) / 2., (min.z + max.z) / 2.),
+            half_extents: Vec3::new((max.x - min.x) / 2., (max.y - min.y) / 2., (max.z - min.z) / 2.),
+            rot_mat: RotMat::IDENTITY,
+        }
+    }
+
+    #[must_use]
+    /// Checks that the geometry can be added to an arena without panicking
+    ///
+    /// Sizes must be positive, normals must be non-zero, and meshes must be made of whole triangles with valid indices.
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::Box { half_extents, .. } => half_extents.x > 0. && half_extents.y > 0. && half_extents.z > 0.,
+            Self::Sphere { radius, .. } => *radius > 0.,
+            Self::Plane { normal, .. } => normal.length() > f32::EPSILON,
+            Self::Mesh { vertices, indices } => {
+                !indices.is_empty() && indices.len() % 3 == 0 && indices.iter().all(|&i| (i as usize) < vertices.len())
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+/// An arena config with extra static geometry, created by `ArenaConfig::with_extra_geometry`
+pub struct ArenaConfigWithGeometry {
+    pub config: ArenaConfig,
+    pub extra_geometry: Vec<StaticGeometry>,
+}
+
+impl ArenaConfigWithGeometry {
+    #[inline]
+    #[must_use]
+    /// Adds more static geometry
+    pub fn with_extra_geometry<I: IntoIterator<Item = StaticGeometry>>(mut self, geometry: I) -> Self {
+        self.extra_geometry.extend(geometry);
+        self
+    }
+}
+
+impl ArenaConfig {
+    #[inline]
+    #[must_use]
+    /// Adds static geometry that will be added to the arena when it's created with `Arena::new_with_geometry`
+    pub fn with_extra_geometry<I: IntoIterator<Item = StaticGeometry>>(self, geometry: I) -> ArenaConfigWithGeometry {
+        ArenaConfigWithGeometry {
+            config: self,
+            extra_geometry: geometry.into_iter().collect(),
+        }
+    }
+}
+
+impl Arena {
+    #[must_use]
+    /// Create a new arena with extra static geometry
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the geometry isn't valid (see `StaticGeometry::is_valid`)
+    pub fn new_with_geometry(game_mode: GameMode, config: ArenaConfigWithGeometry, tick_rate: u8) -> UniquePtr<Self> {
+        let mut arena = Self::new(game_mode, config.config, tick_rate);
+
+        for geometry in &config.extra_geometry {
+            arena.pin_mut().add_static_geometry(geometry);
+        }
+
+        arena
+    }
+
+    /// Adds static geometry to the arena
+    ///
+    /// The geometry is kept when the arena is cloned
+    ///
+    /// # Panics
+    ///
+    /// Panics if the geometry isn't valid (see `StaticGeometry::is_valid`)
+    pub fn add_static_geometry(self: Pin<&mut Self>, geometry: &StaticGeometry) {
+        assert!(geometry.is_valid(), "Invalid static geometry: {geometry:?}");
+
+        match geometry {
+            StaticGeometry::Box {
+                center,
+                half_extents,
+                rot_mat,
+            } => self.asb(*center, *half_extents, *rot_mat),
+            StaticGeometry::Sphere { center, radius } => self.ass(*center, *radius),
+            StaticGeometry::Plane { normal, offset } => self.asp(*normal, *offset),
+            StaticGeometry::Mesh { vertices, indices } => self.asm(vertices, indices),
+        }
+    }
+}
@@ -194,6 +194,16 @@ impl From<BoostPadConfig> for BoostPadConfigA {
     }
 }
 
+impl From<BoostPadConfigA> for BoostPadConfig {
+    #[inline]
+    fn from(value: BoostPadConfigA) -> Self {
+        Self {
+            position: value.position.into(),
+            is_big: value.is_big,
+        }
+    }
+}
+
 impl BoostPadConfig {
     #[inline]
     #[must_use]
@@ -218,6 +228,16 @@ impl From<BoostPad> for BoostPadA {
     }
 }
 
+impl From<BoostPadA> for BoostPad {
+    #[inline]
+    fn from(value: BoostPadA) -> Self {
+        Self {
+            config: value.config.into(),
+            state: value.state,
+        }
+    }
+}
+
 impl BoostPad {
     #[inline]
     #[must_use]
@@ -332,6 +352,15 @@ impl BallState {
     }
 }
 
+impl BallA {
+    #[inline]
+    #[must_use]
+    /// The rotation of the ball as a quaternion
+    pub fn quat(&self) -> Quat {
+        Quat::from_mat3a(&self.rot_mat)
+    }
+}
+
 #[derive(Clone, Copy, Debug, Default)]
 pub struct WheelPairConfigA {
     pub wheel_radius: f32,
@@ -639,6 +668,13 @@ impl CarStateA {
         self.into()
     }
 
+    #[inline]
+    #[must_use]
+    /// The rotation of the car as a quaternion
+    pub fn quat(&self) -> Quat {
+        Quat::from_mat3a(&self.rot_mat)
+    }
+
     #[inline]
     #[must_use]
     /// Returns the other Car that this Car is currently contacting, if any
@@ -707,6 +743,29 @@ impl From<GameState> for GameStateA {
     }
 }
 
+impl From<GameStateA> for GameState {
+    #[inline]
+    fn from(value: GameStateA) -> Self {
+        Self {
+            tick_rate: value.tick_rate,
+            tick_count: value.tick_count,
+            game_mode: value.game_mode,
+            cars: value.cars.into_iter().map(CarInfo::from).collect(),
+            ball: value.ball.into(),
+            pads: value.pads.into_iter().map(BoostPad::from).collect(),
+        }
+    }
+}
+
+impl GameStateA {
+    #[inline]
+    #[must_use]
+    /// Converts back to a `GameState`, which can be passed to `Arena::set_game_state`
+    pub fn to_game_state(self) -> GameState {
+        self.into()
+    }
+}
+
 impl GameState {
     #[inline]
     #[must_use]
@@ -0,0 +1,365 @@
+//! Golden-state regression testing
+//!
+//! Record a hash of the ball and every car after each tick of a scripted scenario,
+//! save it to a "golden" file, and check that future runs (e.g. after updating rocketsim-rs) still match it.
+//!
+//! Set the `ROCKETSIM_UPDATE_GOLDEN` environment variable to overwrite the golden files instead of checking them.
+//! Files that don't exist yet are always written.
+
+use crate::{
+    math::{RotMat, Vec3},
+    sim::{Arena, BallState, CarState},
+};
+use core::{pin::Pin, str::FromStr};
+use std::{env, error::Error, fmt, fs, io, path::Path};
+
+/// If this environment variable is set, golden files are overwritten instead of checked
+pub const UPDATE_ENV_VAR: &str = "ROCKETSIM_UPDATE_GOLDEN";
+
+/// 64-bit FNV-1a, which (unlike `std`'s hashers) is guaranteed to be the same across Rust versions
+struct Fnv(u64);
+
+impl Fnv {
+    const fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn f32(&mut self, value: f32) {
+        self.bytes(&value.to_bits().to_le_bytes());
+    }
+
+    fn bool(&mut self, value: bool) {
+        self.bytes(&[u8::from(value)]);
+    }
+
+    fn vec(&mut self, vec: Vec3) {
+        self.f32(vec.x);
+        self.f32(vec.y);
+        self.f32(vec.z);
+    }
+
+    fn rot_mat(&mut self, rot_mat: RotMat) {
+        self.vec(rot_mat.forward);
+        self.vec(rot_mat.right);
+        self.vec(rot_mat.up);
+    }
+}
+
+#[must_use]
+/// A hash of the ball's position, rotation, and velocities
+pub fn hash_ball(ball: &BallState) -> u64 {
+    let mut hasher = Fnv::new();
+    hasher.vec(ball.pos);
+    hasher.rot_mat(ball.rot_mat);
+    hasher.vec(ball.vel);
+    hasher.vec(ball.ang_vel);
+    hasher.0
+}
+
+#[must_use]
+/// A hash of the car's position, rotation, velocities, boost, and jump/flip/demo state
+pub fn hash_car(car: &CarState) -> u64 {
+    let mut hasher = Fnv::new();
+    hasher.vec(car.pos);
+    hasher.rot_mat(car.rot_mat);
+    hasher.vec(car.vel);
+    hasher.vec(car.ang_vel);
+    hasher.f32(car.boost);
+    hasher.bool(car.is_on_ground);
+    hasher.bool(car.has_jumped);
+    hasher.bool(car.has_double_jumped);
+    hasher.bool(car.has_flipped);
+    hasher.bool(car.is_demoed);
+    hasher.0
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// The hashes of everything in the arena after a tick
+pub struct TickHashes {
+    pub tick_count: u64,
+    pub ball: u64,
+    /// `(car id, hash)`, sorted by the car IDs
+    pub cars: Vec<(u32, u64)>,
+}
+
+impl TickHashes {
+    #[must_use]
+    pub fn from_arena(arena: &Arena) -> Self {
+        let mut car_ids = arena.get_cars();
+        car_ids.sort_unstable();
+
+        Self {
+            tick_count: arena.get_tick_count(),
+            ball: hash_ball(&arena.ball_state_ref().to_state()),
+            cars: car_ids
+                .into_iter()
+                .map(|id| (id, hash_car(&arena.car_state_ref(id).unwrap().to_state())))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+/// The hashes of every tick of a run
+pub struct Trajectory {
+    pub ticks: Vec<TickHashes>,
+}
+
+impl Trajectory {
+    /// Runs the scenario for the given number of ticks, hashing the arena after each one
+    ///
+    /// `script` is called before each tick with the arena and the index of the tick, so it can set the controls
+    pub fn record(mut arena: Pin<&mut Arena>, num_ticks: u32, mut script: impl FnMut(Pin<&mut Arena>, u32)) -> Self {
+        let ticks = (0..num_ticks)
+            .map(|i| {
+                script(arena.as_mut(), i);
+                arena.as_mut().step(1);
+                TickHashes::from_arena(&arena)
+            })
+            .collect();
+
+        Self { ticks }
+    }
+
+    #[must_use]
+    /// Returns where this trajectory first stops matching the expected one
+    pub fn first_mismatch(&self, expected: &Self) -> Option<Mismatch> {
+        for (index, (actual, expected)) in self.ticks.iter().zip(&expected.ticks).enumerate() {
+            if actual == expected {
+                continue;
+            }
+
+            let mut parts = Vec::new();
+            if actual.tick_count != expected.tick_count {
+                parts.push(MismatchedPart::TickCount {
+                    expected: expected.tick_count,
+                    actual: actual.tick_count,
+                });
+            }
+
+            if actual.ball != expected.ball {
+                parts.push(MismatchedPart::Ball);
+            }
+
+            for &(id, hash) in &expected.cars {
+                match actual.cars.iter().find(|(actual_id, _)| *actual_id == id) {
+                    Some(&(_, actual_hash)) if actual_hash == hash => {}
+                    Some(_) => parts.push(MismatchedPart::Car(id)),
+                    None => parts.push(MismatchedPart::MissingCar(id)),
+                }
+            }
+
+            for &(id, _) in &actual.cars {
+                if !expected.cars.iter().any(|(expected_id, _)| *expected_id == id) {
+                    parts.push(MismatchedPart::ExtraCar(id));
+                }
+            }
+
+            return Some(Mismatch {
+                index,
+                tick_count: expected.tick_count,
+                parts,
+            });
+        }
+
+        (self.ticks.len() != expected.ticks.len()).then(|| Mismatch {
+            index: self.ticks.len().min(expected.ticks.len()),
+            tick_count: 0,
+            parts: vec![MismatchedPart::Length {
+                expected: expected.ticks.len(),
+                actual: self.ticks.len(),
+            }],
+        })
+    }
+}
+
+impl fmt::Display for Trajectory {
+    /// One line per tick: `<tick count> <ball hash> <car id>:<car hash>...`, with the hashes in hex
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for tick in &self.ticks {
+            write!(f, "{} {:016x}", tick.tick_count, tick.ball)?;
+
+            for (id, hash) in &tick.cars {
+                write!(f, " {id}:{hash:016x}")?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Error for when a golden file can't be parsed, with the (1-based) line number
+pub struct ParseTrajectoryError(pub usize);
+
+impl Error for ParseTrajectoryError {}
+impl fmt::Display for ParseTrajectoryError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid golden trajectory on line {}.", self.0)
+    }
+}
+
+impl FromStr for Trajectory {
+    type Err = ParseTrajectoryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let ticks = s
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                let error = ParseTrajectoryError(i + 1);
+                let mut parts = line.split_whitespace();
+
+                let tick_count = parts.next().and_then(|s| s.parse().ok()).ok_or(error)?;
+                let ball = parts.next().and_then(|s| u64::from_str_radix(s, 16).ok()).ok_or(error)?;
+                let cars = parts
+                    .map(|car| {
+                        let (id, hash) = car.split_once(':')?;
+                        Some((id.parse().ok()?, u64::from_str_radix(hash, 16).ok()?))
+                    })
+                    .collect::<Option<_>>()
+                    .ok_or(error)?;
+
+                Ok(TickHashes { tick_count, ball, cars })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { ticks })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MismatchedPart {
+    TickCount {
+        expected: u64,
+        actual: u64,
+    },
+    Ball,
+    Car(u32),
+    /// The car is in the golden trajectory, but not in this run
+    MissingCar(u32),
+    /// The car is in this run, but not in the golden trajectory
+    ExtraCar(u32),
+    /// The trajectories have a different number of ticks
+    Length {
+        expected: usize,
+        actual: usize,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// The first tick where a run didn't match the golden trajectory
+pub struct Mismatch {
+    /// The index of the tick in the trajectory
+    pub index: usize,
+    /// The expected tick count
+    pub tick_count: u64,
+    pub parts: Vec<MismatchedPart>,
+}
+
+impl Error for Mismatch {}
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Run diverged from the golden trajectory at tick {} (#{} of the run):",
+            self.tick_count, self.index
+        )?;
+
+        for part in &self.parts {
+            match part {
+                MismatchedPart::TickCount { expected, actual } => {
+                    writeln!(f, "  - tick count: expected {expected}, got {actual}")
+                }
+                MismatchedPart::Ball => writeln!(f, "  - ball state differs"),
+                MismatchedPart::Car(id) => writeln!(f, "  - car {id} state differs"),
+                MismatchedPart::MissingCar(id) => writeln!(f, "  - car {id} is missing"),
+                MismatchedPart::ExtraCar(id) => writeln!(f, "  - car {id} is unexpected"),
+                MismatchedPart::Length { expected, actual } => {
+                    writeln!(f, "  - expected {expected} ticks, got {actual}")
+                }
+            }?;
+        }
+
+        write!(
+            f,
+            "Use `debug::compare_arenas` to find the diverging fields, or set {UPDATE_ENV_VAR} to accept the new behavior"
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum GoldenError {
+    Io(io::Error),
+    Parse(ParseTrajectoryError),
+    Mismatch(Mismatch),
+}
+
+impl Error for GoldenError {}
+impl fmt::Display for GoldenError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Couldn't access the golden file: {e}"),
+            Self::Parse(e) => e.fmt(f),
+            Self::Mismatch(e) => e.fmt(f),
+        }
+    }
+}
+
+impl From<io::Error> for GoldenError {
+    #[inline]
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// Checks the trajectory against the golden file at the given path
+///
+/// The file is written instead if it doesn't exist, or if `ROCKETSIM_UPDATE_GOLDEN` is set
+///
+/// # Errors
+///
+/// If the file can't be read, written or parsed, or if the trajectory doesn't match it
+pub fn check_golden(path: impl AsRef<Path>, trajectory: &Trajectory) -> Result<(), GoldenError> {
+    let path = path.as_ref();
+
+    if env::var_os(UPDATE_ENV_VAR).is_some() || !path.exists() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(path, trajectory.to_string())?;
+        return Ok(());
+    }
+
+    let expected = fs::read_to_string(path)?.parse::<Trajectory>().map_err(GoldenError::Parse)?;
+
+    match trajectory.first_mismatch(&expected) {
+        Some(mismatch) => Err(GoldenError::Mismatch(mismatch)),
+        None => Ok(()),
+    }
+}
+
+#[track_caller]
+/// Like `check_golden`, but panics with a readable report if the check fails
+///
+/// # Panics
+///
+/// If the file can't be read, written or parsed, or if the trajectory doesn't match it
+pub fn assert_golden(path: impl AsRef<Path>, trajectory: &Trajectory) {
+    if let Err(e) = check_golden(path, trajectory) {
+        panic!("{e}");
+    }
+}
@@ -0,0 +1,294 @@
+//! A gRPC service for creating and driving arenas remotely, so clients in any language (Go, Python, TypeScript...)
+//! can run simulations
+//!
+//! The service is defined in `proto/rocketsim.proto`, and `proto` has the generated messages, client and server.
+//! It's served with tonic, inside of a tokio runtime:
+//!
+//! ```ignore
+//! tonic::transport::Server::builder()
+//!     .add_service(ArenaServiceServer::new(GrpcService::new()))
+//!     .serve("127.0.0.1:50051".parse()?)
+//!     .await?;
+//! ```
+//!
+//! Steps run on tokio's blocking threads, and other requests for an arena that's being stepped wait for it
+//! without blocking the runtime, so long steps don't hold up other requests.
+
+#[rustfmt::skip]
+#[allow(clippy::all, clippy::pedantic)]
+pub mod proto;
+
+use crate::{
+    math::Vec3,
+    sim::{Arena, ArenaConfig, CarConfig, CarControls, GameMode, Team},
+    GameState,
+};
+use core::pin::Pin;
+use cxx::UniquePtr;
+use proto::arena_service_server::ArenaService;
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+use tokio::sync::{broadcast, Mutex as AsyncMutex};
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
+use tonic::{Request, Response, Status};
+
+pub use proto::{arena_service_client::ArenaServiceClient, arena_service_server::ArenaServiceServer};
+
+/// How many game states a slow `StreamStates` client can fall behind by before it starts missing some
+pub const STREAM_CAPACITY: usize = 256;
+
+impl From<Vec3> for proto::Vec3 {
+    #[inline]
+    fn from(vec: Vec3) -> Self {
+        Self {
+            x: vec.x,
+            y: vec.y,
+            z: vec.z,
+        }
+    }
+}
+
+impl From<Team> for proto::Team {
+    #[inline]
+    fn from(team: Team) -> Self {
+        match team {
+            Team::Blue => Self::Blue,
+            Team::Orange => Self::Orange,
+        }
+    }
+}
+
+impl From<proto::Team> for Team {
+    #[inline]
+    fn from(team: proto::Team) -> Self {
+        match team {
+            proto::Team::Blue => Self::Blue,
+            proto::Team::Orange => Self::Orange,
+        }
+    }
+}
+
+impl From<proto::GameMode> for GameMode {
+    #[inline]
+    fn from(game_mode: proto::GameMode) -> Self {
+        match game_mode {
+            proto::GameMode::Soccar => Self::Soccar,
+            proto::GameMode::Hoops => Self::Hoops,
+            proto::GameMode::Heatseeker => Self::Heatseeker,
+            proto::GameMode::Snowday => Self::Snowday,
+            proto::GameMode::TheVoid => Self::TheVoid,
+        }
+    }
+}
+
+impl From<proto::CarPreset> for &'static CarConfig {
+    #[inline]
+    fn from(preset: proto::CarPreset) -> Self {
+        match preset {
+            proto::CarPreset::Octane => CarConfig::octane(),
+            proto::CarPreset::Dominus => CarConfig::dominus(),
+            proto::CarPreset::Plank => CarConfig::plank(),
+            proto::CarPreset::Breakout => CarConfig::breakout(),
+            proto::CarPreset::Hybrid => CarConfig::hybrid(),
+            proto::CarPreset::Merc => CarConfig::merc(),
+        }
+    }
+}
+
+impl From<proto::CarControls> for CarControls {
+    #[inline]
+    fn from(controls: proto::CarControls) -> Self {
+        Self {
+            throttle: controls.throttle,
+            steer: controls.steer,
+            pitch: controls.pitch,
+            yaw: controls.yaw,
+            roll: controls.roll,
+            boost: controls.boost,
+            jump: controls.jump,
+            handbrake: controls.handbrake,
+        }
+    }
+}
+
+impl From<&GameState> for proto::GameState {
+    fn from(game_state: &GameState) -> Self {
+        let ball = &game_state.ball;
+
+        Self {
+            tick_count: game_state.tick_count,
+            tick_rate: game_state.tick_rate,
+            ball: Some(proto::BallState {
+                pos: Some(ball.pos.into()),
+                vel: Some(ball.vel.into()),
+                ang_vel: Some(ball.ang_vel.into()),
+            }),
+            cars: game_state
+                .cars
+                .iter()
+                .map(|car| proto::CarState {
+                    id: car.id,
+                    team: proto::Team::from(car.team).into(),
+                    pos: Some(car.state.pos.into()),
+                    vel: Some(car.state.vel.into()),
+                    ang_vel: Some(car.state.ang_vel.into()),
+                    forward: Some(car.state.rot_mat.forward.into()),
+                    up: Some(car.state.rot_mat.up.into()),
+                    boost: car.state.boost,
+                    is_on_ground: car.state.is_on_ground,
+                    is_demoed: car.state.is_demoed,
+                })
+                .collect(),
+        }
+    }
+}
+
+struct ServiceArena {
+    /// Locked asynchronously by requests, and with `blocking_lock` by steps on the blocking threads
+    arena: AsyncMutex<UniquePtr<Arena>>,
+    /// Sends the game state after every step to the `StreamStates` clients
+    states: broadcast::Sender<proto::GameState>,
+}
+
+#[derive(Default)]
+/// The implementation of `ArenaService`, which owns all of the arenas that were created through it
+pub struct GrpcService {
+    arenas: Mutex<HashMap<u64, Arc<ServiceArena>>>,
+    next_id: AtomicU64,
+}
+
+impl fmt::Debug for GrpcService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GrpcService")
+            .field("num_arenas", &self.num_arenas())
+            .finish_non_exhaustive()
+    }
+}
+
+fn no_arena(arena_id: u64) -> Status {
+    Status::not_found(format!("No arena with id {arena_id}"))
+}
+
+fn invalid_enum<T>(name: &str, value: i32) -> impl FnOnce(T) -> Status + '_ {
+    move |_| Status::invalid_argument(format!("Invalid {name} {value}"))
+}
+
+impl GrpcService {
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn num_arenas(&self) -> usize {
+        self.arenas.lock().unwrap().len()
+    }
+
+    fn get(&self, arena_id: u64) -> Option<Arc<ServiceArena>> {
+        self.arenas.lock().unwrap().get(&arena_id).cloned()
+    }
+}
+
+#[tonic::async_trait]
+impl ArenaService for GrpcService {
+    async fn create_arena(&self, request: Request<proto::CreateArenaRequest>) -> Result<Response<proto::ArenaId>, Status> {
+        let request = request.into_inner();
+        let game_mode =
+            proto::GameMode::try_from(request.game_mode).map_err(invalid_enum("game mode", request.game_mode))?;
+
+        let tick_rate = match request.tick_rate {
+            0 => Arena::MAX_NATIVE_TICK_RATE,
+            tick_rate => u8::try_from(tick_rate).unwrap_or(u8::MAX),
+        };
+        let arena = Arena::try_new(game_mode.into(), ArenaConfig::default(), tick_rate)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let arena = ServiceArena {
+            arena: AsyncMutex::new(arena),
+            states: broadcast::channel(STREAM_CAPACITY).0,
+        };
+        self.arenas.lock().unwrap().insert(id, Arc::new(arena));
+
+        Ok(Response::new(proto::ArenaId { id }))
+    }
+
+    async fn remove_arena(&self, request: Request<proto::ArenaId>) -> Result<Response<proto::Empty>, Status> {
+        let arena_id = request.into_inner().id;
+        match self.arenas.lock().unwrap().remove(&arena_id) {
+            Some(_) => Ok(Response::new(proto::Empty {})),
+            None => Err(no_arena(arena_id)),
+        }
+    }
+
+    async fn add_car(&self, request: Request<proto::AddCarRequest>) -> Result<Response<proto::CarId>, Status> {
+        let request = request.into_inner();
+        let team = proto::Team::try_from(request.team).map_err(invalid_enum("team", request.team))?;
+        let preset = proto::CarPreset::try_from(request.preset).map_err(invalid_enum("car preset", request.preset))?;
+
+        let arena = self.get(request.arena_id).ok_or_else(|| no_arena(request.arena_id))?;
+        let id = arena.arena.lock().await.pin_mut().add_car(team.into(), preset.into());
+
+        Ok(Response::new(proto::CarId { id }))
+    }
+
+    async fn set_controls(&self, request: Request<proto::SetControlsRequest>) -> Result<Response<proto::Empty>, Status> {
+        let request = request.into_inner();
+        let arena = self.get(request.arena_id).ok_or_else(|| no_arena(request.arena_id))?;
+        let mut arena = arena.arena.lock().await;
+
+        for entry in request.controls {
+            arena
+                .pin_mut()
+                .set_car_controls(entry.car_id, entry.controls.unwrap_or_default().into())
+                .map_err(|e| Status::not_found(e.to_string()))?;
+        }
+
+        Ok(Response::new(proto::Empty {}))
+    }
+
+    async fn step(&self, request: Request<proto::StepRequest>) -> Result<Response<proto::GameState>, Status> {
+        let request = request.into_inner();
+        let arena = self.get(request.arena_id).ok_or_else(|| no_arena(request.arena_id))?;
+
+        let game_state = tokio::task::spawn_blocking(move || {
+            let mut guard = arena.arena.blocking_lock();
+            guard.pin_mut().step(request.ticks);
+            let game_state = proto::GameState::from(&guard.pin_mut().get_game_state());
+
+            // it's fine if no one is listening
+            let _ = arena.states.send(game_state.clone());
+            game_state
+        })
+        .await
+        .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(game_state))
+    }
+
+    async fn get_game_state(&self, request: Request<proto::ArenaId>) -> Result<Response<proto::GameState>, Status> {
+        let arena_id = request.into_inner().id;
+        let arena = self.get(arena_id).ok_or_else(|| no_arena(arena_id))?;
+        let game_state = arena.arena.lock().await.pin_mut().get_game_state();
+
+        Ok(Response::new((&game_state).into()))
+    }
+
+    type StreamStatesStream = Pin<Box<dyn Stream<Item = Result<proto::GameState, Status>> + Send>>;
+
+    async fn stream_states(&self, request: Request<proto::ArenaId>) -> Result<Response<Self::StreamStatesStream>, Status> {
+        let arena_id = request.into_inner().id;
+        let receiver = self.get(arena_id).ok_or_else(|| no_arena(arena_id))?.states.subscribe();
+
+        // clients that fall too far behind skip the states they missed
+        let stream = BroadcastStream::new(receiver).filter_map(Result::ok).map(Ok);
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
@@ -0,0 +1,951 @@
+// This file is generated from proto/rocketsim.proto by tonic-build, don't edit it by hand
+
+// This file is @generated by prost-build.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct Empty {}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct ArenaId {
+    #[prost(uint64, tag = "1")]
+    pub id: u64,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct CarId {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct CreateArenaRequest {
+    #[prost(enumeration = "GameMode", tag = "1")]
+    pub game_mode: i32,
+    /// Ticks per second, 120 if 0
+    #[prost(uint32, tag = "2")]
+    pub tick_rate: u32,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct AddCarRequest {
+    #[prost(uint64, tag = "1")]
+    pub arena_id: u64,
+    #[prost(enumeration = "Team", tag = "2")]
+    pub team: i32,
+    #[prost(enumeration = "CarPreset", tag = "3")]
+    pub preset: i32,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct CarControls {
+    #[prost(float, tag = "1")]
+    pub throttle: f32,
+    #[prost(float, tag = "2")]
+    pub steer: f32,
+    #[prost(float, tag = "3")]
+    pub pitch: f32,
+    #[prost(float, tag = "4")]
+    pub yaw: f32,
+    #[prost(float, tag = "5")]
+    pub roll: f32,
+    #[prost(bool, tag = "6")]
+    pub boost: bool,
+    #[prost(bool, tag = "7")]
+    pub jump: bool,
+    #[prost(bool, tag = "8")]
+    pub handbrake: bool,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct CarControlsEntry {
+    #[prost(uint32, tag = "1")]
+    pub car_id: u32,
+    #[prost(message, optional, tag = "2")]
+    pub controls: ::core::option::Option<CarControls>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetControlsRequest {
+    #[prost(uint64, tag = "1")]
+    pub arena_id: u64,
+    #[prost(message, repeated, tag = "2")]
+    pub controls: ::prost::alloc::vec::Vec<CarControlsEntry>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct StepRequest {
+    #[prost(uint64, tag = "1")]
+    pub arena_id: u64,
+    #[prost(uint32, tag = "2")]
+    pub ticks: u32,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct Vec3 {
+    #[prost(float, tag = "1")]
+    pub x: f32,
+    #[prost(float, tag = "2")]
+    pub y: f32,
+    #[prost(float, tag = "3")]
+    pub z: f32,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct BallState {
+    #[prost(message, optional, tag = "1")]
+    pub pos: ::core::option::Option<Vec3>,
+    #[prost(message, optional, tag = "2")]
+    pub vel: ::core::option::Option<Vec3>,
+    #[prost(message, optional, tag = "3")]
+    pub ang_vel: ::core::option::Option<Vec3>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct CarState {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+    #[prost(enumeration = "Team", tag = "2")]
+    pub team: i32,
+    #[prost(message, optional, tag = "3")]
+    pub pos: ::core::option::Option<Vec3>,
+    #[prost(message, optional, tag = "4")]
+    pub vel: ::core::option::Option<Vec3>,
+    #[prost(message, optional, tag = "5")]
+    pub ang_vel: ::core::option::Option<Vec3>,
+    #[prost(message, optional, tag = "6")]
+    pub forward: ::core::option::Option<Vec3>,
+    #[prost(message, optional, tag = "7")]
+    pub up: ::core::option::Option<Vec3>,
+    #[prost(float, tag = "8")]
+    pub boost: f32,
+    #[prost(bool, tag = "9")]
+    pub is_on_ground: bool,
+    #[prost(bool, tag = "10")]
+    pub is_demoed: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GameState {
+    #[prost(uint64, tag = "1")]
+    pub tick_count: u64,
+    #[prost(float, tag = "2")]
+    pub tick_rate: f32,
+    #[prost(message, optional, tag = "3")]
+    pub ball: ::core::option::Option<BallState>,
+    #[prost(message, repeated, tag = "4")]
+    pub cars: ::prost::alloc::vec::Vec<CarState>,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum GameMode {
+    Soccar = 0,
+    Hoops = 1,
+    Heatseeker = 2,
+    Snowday = 3,
+    TheVoid = 4,
+}
+impl GameMode {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Soccar => "SOCCAR",
+            Self::Hoops => "HOOPS",
+            Self::Heatseeker => "HEATSEEKER",
+            Self::Snowday => "SNOWDAY",
+            Self::TheVoid => "THE_VOID",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "SOCCAR" => Some(Self::Soccar),
+            "HOOPS" => Some(Self::Hoops),
+            "HEATSEEKER" => Some(Self::Heatseeker),
+            "SNOWDAY" => Some(Self::Snowday),
+            "THE_VOID" => Some(Self::TheVoid),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum Team {
+    Blue = 0,
+    Orange = 1,
+}
+impl Team {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Blue => "BLUE",
+            Self::Orange => "ORANGE",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "BLUE" => Some(Self::Blue),
+            "ORANGE" => Some(Self::Orange),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum CarPreset {
+    Octane = 0,
+    Dominus = 1,
+    Plank = 2,
+    Breakout = 3,
+    Hybrid = 4,
+    Merc = 5,
+}
+impl CarPreset {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Octane => "OCTANE",
+            Self::Dominus => "DOMINUS",
+            Self::Plank => "PLANK",
+            Self::Breakout => "BREAKOUT",
+            Self::Hybrid => "HYBRID",
+            Self::Merc => "MERC",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "OCTANE" => Some(Self::Octane),
+            "DOMINUS" => Some(Self::Dominus),
+            "PLANK" => Some(Self::Plank),
+            "BREAKOUT" => Some(Self::Breakout),
+            "HYBRID" => Some(Self::Hybrid),
+            "MERC" => Some(Self::Merc),
+            _ => None,
+        }
+    }
+}
+/// Generated client implementations.
+pub mod arena_service_client {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    #[derive(Debug, Clone)]
+    pub struct ArenaServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl ArenaServiceClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> ArenaServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> ArenaServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + std::marker::Send + std::marker::Sync,
+        {
+            ArenaServiceClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        /// Creates a new arena and returns its id
+        pub async fn create_arena(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateArenaRequest>,
+        ) -> std::result::Result<tonic::Response<super::ArenaId>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rocketsim.v1.ArenaService/CreateArena",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("rocketsim.v1.ArenaService", "CreateArena"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn remove_arena(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ArenaId>,
+        ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rocketsim.v1.ArenaService/RemoveArena",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("rocketsim.v1.ArenaService", "RemoveArena"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Adds a car to the arena and returns its id
+        pub async fn add_car(
+            &mut self,
+            request: impl tonic::IntoRequest<super::AddCarRequest>,
+        ) -> std::result::Result<tonic::Response<super::CarId>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rocketsim.v1.ArenaService/AddCar",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("rocketsim.v1.ArenaService", "AddCar"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Sets the controls of cars in the arena, which are kept until they're set again
+        pub async fn set_controls(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SetControlsRequest>,
+        ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rocketsim.v1.ArenaService/SetControls",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("rocketsim.v1.ArenaService", "SetControls"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Steps the arena and returns the new game state
+        pub async fn step(
+            &mut self,
+            request: impl tonic::IntoRequest<super::StepRequest>,
+        ) -> std::result::Result<tonic::Response<super::GameState>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rocketsim.v1.ArenaService/Step",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("rocketsim.v1.ArenaService", "Step"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_game_state(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ArenaId>,
+        ) -> std::result::Result<tonic::Response<super::GameState>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rocketsim.v1.ArenaService/GetGameState",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("rocketsim.v1.ArenaService", "GetGameState"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Streams the game state of the arena after every step, until the arena is removed
+        pub async fn stream_states(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ArenaId>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::GameState>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rocketsim.v1.ArenaService/StreamStates",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("rocketsim.v1.ArenaService", "StreamStates"));
+            self.inner.server_streaming(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod arena_service_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with ArenaServiceServer.
+    #[async_trait]
+    pub trait ArenaService: std::marker::Send + std::marker::Sync + 'static {
+        /// Creates a new arena and returns its id
+        async fn create_arena(
+            &self,
+            request: tonic::Request<super::CreateArenaRequest>,
+        ) -> std::result::Result<tonic::Response<super::ArenaId>, tonic::Status>;
+        async fn remove_arena(
+            &self,
+            request: tonic::Request<super::ArenaId>,
+        ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status>;
+        /// Adds a car to the arena and returns its id
+        async fn add_car(
+            &self,
+            request: tonic::Request<super::AddCarRequest>,
+        ) -> std::result::Result<tonic::Response<super::CarId>, tonic::Status>;
+        /// Sets the controls of cars in the arena, which are kept until they're set again
+        async fn set_controls(
+            &self,
+            request: tonic::Request<super::SetControlsRequest>,
+        ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status>;
+        /// Steps the arena and returns the new game state
+        async fn step(
+            &self,
+            request: tonic::Request<super::StepRequest>,
+        ) -> std::result::Result<tonic::Response<super::GameState>, tonic::Status>;
+        async fn get_game_state(
+            &self,
+            request: tonic::Request<super::ArenaId>,
+        ) -> std::result::Result<tonic::Response<super::GameState>, tonic::Status>;
+        /// Server streaming response type for the StreamStates method.
+        type StreamStatesStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::GameState, tonic::Status>,
+            >
+            + std::marker::Send
+            + 'static;
+        /// Streams the game state of the arena after every step, until the arena is removed
+        async fn stream_states(
+            &self,
+            request: tonic::Request<super::ArenaId>,
+        ) -> std::result::Result<
+            tonic::Response<Self::StreamStatesStream>,
+            tonic::Status,
+        >;
+    }
+    #[derive(Debug)]
+    pub struct ArenaServiceServer<T> {
+        inner: Arc<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    impl<T> ArenaServiceServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for ArenaServiceServer<T>
+    where
+        T: ArenaService,
+        B: Body + std::marker::Send + 'static,
+        B::Error: Into<StdError> + std::marker::Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            match req.uri().path() {
+                "/rocketsim.v1.ArenaService/CreateArena" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateArenaSvc<T: ArenaService>(pub Arc<T>);
+                    impl<
+                        T: ArenaService,
+                    > tonic::server::UnaryService<super::CreateArenaRequest>
+                    for CreateArenaSvc<T> {
+                        type Response = super::ArenaId;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateArenaRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ArenaService>::create_arena(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CreateArenaSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rocketsim.v1.ArenaService/RemoveArena" => {
+                    #[allow(non_camel_case_types)]
+                    struct RemoveArenaSvc<T: ArenaService>(pub Arc<T>);
+                    impl<T: ArenaService> tonic::server::UnaryService<super::ArenaId>
+                    for RemoveArenaSvc<T> {
+                        type Response = super::Empty;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ArenaId>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ArenaService>::remove_arena(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = RemoveArenaSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rocketsim.v1.ArenaService/AddCar" => {
+                    #[allow(non_camel_case_types)]
+                    struct AddCarSvc<T: ArenaService>(pub Arc<T>);
+                    impl<
+                        T: ArenaService,
+                    > tonic::server::UnaryService<super::AddCarRequest>
+                    for AddCarSvc<T> {
+                        type Response = super::CarId;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::AddCarRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ArenaService>::add_car(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = AddCarSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rocketsim.v1.ArenaService/SetControls" => {
+                    #[allow(non_camel_case_types)]
+                    struct SetControlsSvc<T: ArenaService>(pub Arc<T>);
+                    impl<
+                        T: ArenaService,
+                    > tonic::server::UnaryService<super::SetControlsRequest>
+                    for SetControlsSvc<T> {
+                        type Response = super::Empty;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SetControlsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ArenaService>::set_controls(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SetControlsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rocketsim.v1.ArenaService/Step" => {
+                    #[allow(non_camel_case_types)]
+                    struct StepSvc<T: ArenaService>(pub Arc<T>);
+                    impl<T: ArenaService> tonic::server::UnaryService<super::StepRequest>
+                    for StepSvc<T> {
+                        type Response = super::GameState;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::StepRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ArenaService>::step(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = StepSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rocketsim.v1.ArenaService/GetGameState" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetGameStateSvc<T: ArenaService>(pub Arc<T>);
+                    impl<T: ArenaService> tonic::server::UnaryService<super::ArenaId>
+                    for GetGameStateSvc<T> {
+                        type Response = super::GameState;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ArenaId>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ArenaService>::get_game_state(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetGameStateSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rocketsim.v1.ArenaService/StreamStates" => {
+                    #[allow(non_camel_case_types)]
+                    struct StreamStatesSvc<T: ArenaService>(pub Arc<T>);
+                    impl<
+                        T: ArenaService,
+                    > tonic::server::ServerStreamingService<super::ArenaId>
+                    for StreamStatesSvc<T> {
+                        type Response = super::GameState;
+                        type ResponseStream = T::StreamStatesStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ArenaId>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ArenaService>::stream_states(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = StreamStatesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => {
+                    Box::pin(async move {
+                        let mut response = http::Response::new(empty_body());
+                        let headers = response.headers_mut();
+                        headers
+                            .insert(
+                                tonic::Status::GRPC_STATUS,
+                                (tonic::Code::Unimplemented as i32).into(),
+                            );
+                        headers
+                            .insert(
+                                http::header::CONTENT_TYPE,
+                                tonic::metadata::GRPC_CONTENT_TYPE,
+                            );
+                        Ok(response)
+                    })
+                }
+            }
+        }
+    }
+    impl<T> Clone for ArenaServiceServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+                max_decoding_message_size: self.max_decoding_message_size,
+                max_encoding_message_size: self.max_encoding_message_size,
+            }
+        }
+    }
+    /// Generated gRPC service name
+    pub const SERVICE_NAME: &str = "rocketsim.v1.ArenaService";
+    impl<T> tonic::server::NamedService for ArenaServiceServer<T> {
+        const NAME: &'static str = SERVICE_NAME;
+    }
+}
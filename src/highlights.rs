@@ -0,0 +1,234 @@
+//! Finding highlights (goals, saves, demos and high aerial hits) in recordings, and cutting them into clips
+//!
+//! Goals and saves are detected the same way as in `stats::CarballTracker`.
+//! Each highlight comes with a clip range around it, which can be written out as a standalone replay
+//! with `Highlight::write_clip` (with the `bin` feature).
+
+#[cfg(feature = "bin")]
+use crate::replay::{IndexedReplay, ReplayWriter};
+use crate::{sim::Team, stats::CarballTracker, GameState};
+#[cfg(feature = "bin")]
+use std::io::{self, Write};
+
+/// The default minimum height of the ball for a hit to count as an aerial highlight
+pub const DEFAULT_AERIAL_MIN_HEIGHT: f32 = 800.;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_utils", derive(serde::Serialize, serde::Deserialize))]
+pub enum HighlightKind {
+    Goal {
+        team: Team,
+        scorer: Option<u32>,
+    },
+    Save {
+        car_id: u32,
+    },
+    Demo {
+        /// The car that bumped the victim, if it could be found
+        attacker: Option<u32>,
+        victim: u32,
+    },
+    /// A hit by a car that wasn't on the ground, with the ball above the minimum height
+    Aerial {
+        car_id: u32,
+        /// The height of the ball when it was hit
+        height: f32,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_utils", derive(serde::Serialize, serde::Deserialize))]
+pub struct Highlight {
+    pub kind: HighlightKind,
+    /// The tick count of the state in which the highlight was first observed
+    pub tick_count: u64,
+    /// The first tick of the clip
+    pub start_tick: u64,
+    /// The last tick of the clip, which can be past the end of the recording
+    pub end_tick: u64,
+}
+
+impl Highlight {
+    #[inline]
+    #[must_use]
+    pub const fn contains_tick(&self, tick_count: u64) -> bool {
+        self.start_tick <= tick_count && tick_count <= self.end_tick
+    }
+
+    #[inline]
+    #[must_use]
+    /// The start and end of the clip in seconds since tick 0
+    pub fn time_range(&self, tick_rate: f32) -> (f32, f32) {
+        (self.start_tick as f32 / tick_rate, self.end_tick as f32 / tick_rate)
+    }
+
+    #[cfg(feature = "bin")]
+    /// Writes the game states of the clip as a standalone replay, with the metadata of the original
+    ///
+    /// Returns the writer, which has been flushed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the clip can't be written
+    pub fn write_clip<B: AsRef<[u8]>, W: Write>(&self, replay: &IndexedReplay<B>, writer: W) -> io::Result<W> {
+        let mut clip = ReplayWriter::with_metadata(writer, replay.metadata())?;
+        let start = replay.index_at_tick(self.start_tick).unwrap_or_default();
+
+        for i in start..replay.len() {
+            if replay.ticks()[i] > self.end_tick {
+                break;
+            }
+
+            if replay.ticks()[i] >= self.start_tick {
+                clip.write_bytes(replay.bytes_at(i).unwrap())?;
+            }
+        }
+
+        clip.flush()?;
+        Ok(clip.into_inner())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_utils", derive(serde::Serialize, serde::Deserialize))]
+pub struct HighlightConfig {
+    /// The minimum height of the ball for a hit to count as an aerial highlight
+    pub aerial_min_height: f32,
+    /// How many seconds of lead-up are included in the clip before the highlight
+    pub seconds_before: f32,
+    /// How many seconds are included in the clip after the highlight
+    pub seconds_after: f32,
+}
+
+impl Default for HighlightConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            aerial_min_height: DEFAULT_AERIAL_MIN_HEIGHT,
+            seconds_before: 5.,
+            seconds_after: 2.,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+/// Finds highlights in consecutive game states
+///
+/// Feed it every game state of a recording (e.g. from a `replay::ReplayReader`)
+/// and it will return the highlights that happened since the last update.
+pub struct HighlightFinder {
+    pub config: HighlightConfig,
+    stats: CarballTracker,
+    prev: Option<GameState>,
+}
+
+impl HighlightFinder {
+    #[inline]
+    #[must_use]
+    pub const fn new(config: HighlightConfig) -> Self {
+        Self {
+            config,
+            stats: CarballTracker::new(),
+            prev: None,
+        }
+    }
+
+    /// Forget all previously observed game states
+    pub fn clear(&mut self) {
+        self.stats.clear();
+        self.prev = None;
+    }
+
+    fn highlight(&self, kind: HighlightKind, game_state: &GameState) -> Highlight {
+        let tick_rate = game_state.tick_rate.max(1.);
+        let before = (self.config.seconds_before.max(0.) * tick_rate) as u64;
+        let after = (self.config.seconds_after.max(0.) * tick_rate) as u64;
+
+        Highlight {
+            kind,
+            tick_count: game_state.tick_count,
+            start_tick: game_state.tick_count.saturating_sub(before),
+            end_tick: game_state.tick_count.saturating_add(after),
+        }
+    }
+
+    /// Compares the given game state against the previous one and returns all new highlights
+    pub fn update(&mut self, game_state: &GameState) -> Vec<Highlight> {
+        let num_goals = self.stats.goals().len();
+        let saves = self
+            .stats
+            .players()
+            .iter()
+            .map(|player| (player.id, player.hit_counts.total_saves))
+            .collect::<Vec<_>>();
+
+        self.stats.update(game_state);
+
+        let mut highlights = Vec::new();
+        let Some(prev) = self.prev.replace(game_state.clone()) else {
+            return highlights;
+        };
+
+        if game_state.tick_count <= prev.tick_count {
+            return highlights;
+        }
+
+        for goal in &self.stats.goals()[num_goals..] {
+            let kind = HighlightKind::Goal {
+                team: goal.team,
+                scorer: goal.scorer,
+            };
+            highlights.push(self.highlight(kind, game_state));
+        }
+
+        for player in self.stats.players() {
+            let prev_saves = saves.iter().find(|(id, _)| *id == player.id).map_or(0, |(_, saves)| *saves);
+            for _ in prev_saves..player.hit_counts.total_saves {
+                highlights.push(self.highlight(HighlightKind::Save { car_id: player.id }, game_state));
+            }
+        }
+
+        for car in &game_state.cars {
+            let Some(prev_car) = prev.cars.iter().find(|prev_car| prev_car.id == car.id) else {
+                continue;
+            };
+
+            if car.state.is_demoed && !prev_car.state.is_demoed {
+                // the attacker bumped the victim, so it has the victim as its last contact
+                let attacker = game_state
+                    .cars
+                    .iter()
+                    .chain(&prev.cars)
+                    .find(|other| other.id != car.id && other.state.car_contact.other_car_id == car.id)
+                    .map(|other| other.id);
+
+                let kind = HighlightKind::Demo {
+                    attacker,
+                    victim: car.id,
+                };
+                highlights.push(self.highlight(kind, game_state));
+            }
+
+            let hit_info = car.state.ball_hit_info;
+            if hit_info.is_valid
+                && hit_info.tick_count_when_hit > prev.tick_count
+                && !car.state.is_on_ground
+                && hit_info.ball_pos.z >= self.config.aerial_min_height
+            {
+                let kind = HighlightKind::Aerial {
+                    car_id: car.id,
+                    height: hit_info.ball_pos.z,
+                };
+                highlights.push(self.highlight(kind, game_state));
+            }
+        }
+
+        highlights
+    }
+}
+
+/// Finds every highlight in a sequence of game states, e.g. the states of a replay
+pub fn find_highlights<'a>(states: impl IntoIterator<Item = &'a GameState>, config: HighlightConfig) -> Vec<Highlight> {
+    let mut finder = HighlightFinder::new(config);
+    states.into_iter().flat_map(|state| finder.update(state)).collect()
+}
@@ -0,0 +1,150 @@
+//! Recent ball and car states, recorded by the arena itself
+//!
+//! The history is opt-in, see `Arena::set_history_capacity`.
+//! A state is recorded after every tick, so the newest entry is always the current state of the arena.
+//! The history only covers consecutive ticks, and is cleared if the tick count jumps (e.g. from `set_tick_count`).
+
+use crate::sim::{Arena, BallState, CarState};
+use core::pin::Pin;
+use std::{error::Error, fmt};
+
+#[derive(Clone, Copy, Debug)]
+/// Error for when the arena can't step back, because it has no history
+pub struct NoHistory;
+
+impl Error for NoHistory {}
+impl fmt::Display for NoHistory {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The arena has no recorded history to step back through.")
+    }
+}
+
+#[derive(Clone, Copy)]
+/// A read-only view into the arena's history
+pub struct History<'a> {
+    arena: &'a Arena,
+}
+
+impl<'a> History<'a> {
+    #[inline]
+    #[must_use]
+    /// The number of recorded ticks
+    pub fn len(&self) -> usize {
+        self.arena.hlen()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    #[must_use]
+    /// The tick count of the oldest recorded state
+    pub fn oldest_tick(&self) -> Option<u64> {
+        (!self.is_empty()).then(|| self.arena.htick(0))
+    }
+
+    #[inline]
+    #[must_use]
+    /// The tick count of the newest recorded state
+    pub fn newest_tick(&self) -> Option<u64> {
+        self.len().checked_sub(1).map(|i| self.arena.htick(i))
+    }
+
+    /// Returns the index of the entry for the given tick count
+    fn index_of(&self, tick: u64) -> Option<usize> {
+        let index = usize::try_from(tick.checked_sub(self.oldest_tick()?)?).ok()?;
+        (index < self.len()).then_some(index)
+    }
+
+    /// Returns the tick count from the given number of seconds before the newest recorded state
+    fn tick_seconds_ago(&self, seconds: f32) -> Option<u64> {
+        let ticks_ago = (seconds.max(0.) * self.arena.get_tick_rate()).round() as u64;
+        self.newest_tick()?.checked_sub(ticks_ago)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the state of the ball after the given tick, if it's in the history
+    pub fn ball_at(&self, tick: u64) -> Option<BallState> {
+        self.index_of(tick).map(|i| self.arena.hball(i))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the state of the car after the given tick, if it's in the history and the car existed
+    pub fn car_at(&self, car_id: u32, tick: u64) -> Option<CarState> {
+        let index = self.index_of(tick)?;
+        self.arena.hhcar(index, car_id).then(|| self.arena.hcar(index, car_id))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the state of the ball from the given number of seconds ago, rounded to the nearest tick
+    pub fn ball_seconds_ago(&self, seconds: f32) -> Option<BallState> {
+        self.ball_at(self.tick_seconds_ago(seconds)?)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the state of the car from the given number of seconds ago, rounded to the nearest tick
+    pub fn car_seconds_ago(&self, car_id: u32, seconds: f32) -> Option<CarState> {
+        self.car_at(car_id, self.tick_seconds_ago(seconds)?)
+    }
+
+    #[inline]
+    /// Iterates over the recorded `(tick count, ball state)`s, from oldest to newest
+    pub fn iter_ball(&self) -> impl Iterator<Item = (u64, BallState)> + 'a {
+        let arena = self.arena;
+        (0..self.len()).map(move |i| (arena.htick(i), arena.hball(i)))
+    }
+
+    #[inline]
+    /// Iterates over the recorded `(tick count, car state)`s of the car, from oldest to newest
+    ///
+    /// Ticks in which the car didn't exist are skipped
+    pub fn iter_car(&self, car_id: u32) -> impl Iterator<Item = (u64, CarState)> + 'a {
+        let arena = self.arena;
+        (0..self.len())
+            .filter(move |&i| arena.hhcar(i, car_id))
+            .map(move |i| (arena.htick(i), arena.hcar(i, car_id)))
+    }
+}
+
+impl Arena {
+    #[inline]
+    #[must_use]
+    /// Returns the recent states that the arena recorded, see `set_history_capacity`
+    pub fn history(&self) -> History<'_> {
+        History { arena: self }
+    }
+
+    /// Restores the ball, cars and boost pads (and the tick count) to how they were the given number of ticks ago
+    ///
+    /// If the history doesn't go back far enough, the oldest recorded tick is restored instead.
+    /// Everything after the restored tick is dropped from the history, so stepping forward records it again.
+    /// Returns the tick count that was restored.
+    ///
+    /// Cars that have been added since the restored tick are left as they are,
+    /// and Bullet's contact caches aren't part of the history, so the simulation can diverge slightly from the original.
+    /// For bit-exact rewinds, keep `clone`s of the arena instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NoHistory` if nothing has been recorded, see `set_history_capacity`
+    pub fn step_back(self: Pin<&mut Self>, ticks: u64) -> Result<u64, NoHistory> {
+        let history = self.history();
+        let (Some(oldest), Some(newest)) = (history.oldest_tick(), history.newest_tick()) else {
+            return Err(NoHistory);
+        };
+
+        let tick = self.get_tick_count().saturating_sub(ticks).clamp(oldest, newest);
+        let index = history.index_of(tick).ok_or(NoHistory)?;
+        self.rsth(index);
+
+        Ok(tick)
+    }
+}
@@ -0,0 +1,191 @@
+use super::Controller;
+use crate::{sim::CarControls, GameState};
+use gilrs::{Axis, Button, Gamepad, GamepadId, Gilrs, GilrsBuilder};
+use std::{error::Error, fmt};
+
+#[derive(Debug)]
+/// Error for when gamepads can't be accessed
+pub struct GamepadError(pub Box<gilrs::Error>);
+
+impl Error for GamepadError {}
+impl fmt::Display for GamepadError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Couldn't access the gamepads: {}", self.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// How the stick deadzone is applied, same as the options in Rocket League
+pub enum DeadzoneShape {
+    /// The deadzone is applied to each axis separately,
+    /// so small movements along one axis don't leak into the other
+    #[default]
+    Cross,
+    /// The deadzone is applied to the distance of the stick from the center
+    Circle,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Rocket League's controller settings
+pub struct GamepadSettings {
+    /// Stick input below this is ignored, and the rest is rescaled to start from 0
+    pub deadzone: f32,
+    pub deadzone_shape: DeadzoneShape,
+    /// Multiplier for the stick when steering on the ground
+    pub steering_sensitivity: f32,
+    /// Multiplier for the stick when rotating in the air
+    pub aerial_sensitivity: f32,
+    /// Triggers below this are ignored
+    pub trigger_deadzone: f32,
+}
+
+impl Default for GamepadSettings {
+    /// Rocket League's default settings
+    #[inline]
+    fn default() -> Self {
+        Self {
+            deadzone: 0.1,
+            deadzone_shape: DeadzoneShape::Cross,
+            steering_sensitivity: 1.,
+            aerial_sensitivity: 1.,
+            trigger_deadzone: 0.05,
+        }
+    }
+}
+
+/// Removes the deadzone from an input and rescales the rest to `0..=1`
+fn rescale(value: f32, deadzone: f32) -> f32 {
+    if value.abs() <= deadzone {
+        0.
+    } else {
+        value.signum() * ((value.abs() - deadzone) / (1. - deadzone)).min(1.)
+    }
+}
+
+impl GamepadSettings {
+    #[must_use]
+    /// Applies the deadzone to the position of a stick
+    pub fn apply_deadzone(&self, x: f32, y: f32) -> (f32, f32) {
+        match self.deadzone_shape {
+            DeadzoneShape::Cross => (rescale(x, self.deadzone), rescale(y, self.deadzone)),
+            DeadzoneShape::Circle => {
+                let len = x.hypot(y);
+                if len <= self.deadzone {
+                    (0., 0.)
+                } else {
+                    let scale = rescale(len, self.deadzone) / len;
+                    (x * scale, y * scale)
+                }
+            }
+        }
+    }
+}
+
+/// Controls a car with a physical gamepad, using Rocket League's default bindings
+///
+/// | Input | Action |
+/// | --- | --- |
+/// | Right/left trigger | Throttle/reverse |
+/// | Left stick | Steer, pitch and yaw |
+/// | A (south) | Jump |
+/// | B (east) | Boost |
+/// | X (west) | Powerslide and free air roll |
+/// | Left/right bumper | Air roll left/right |
+///
+/// The most recently used gamepad is the one that's read.
+/// Dodge deadzones are handled by RocketSim itself, so aren't applied here.
+pub struct GamepadController {
+    gilrs: Gilrs,
+    active: Option<GamepadId>,
+    pub settings: GamepadSettings,
+}
+
+impl GamepadController {
+    /// Connects to the gamepads with Rocket League's default settings
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if gamepads aren't supported on this platform, or if they can't be accessed
+    pub fn new() -> Result<Self, GamepadError> {
+        Self::with_settings(GamepadSettings::default())
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if gamepads aren't supported on this platform, or if they can't be accessed
+    pub fn with_settings(settings: GamepadSettings) -> Result<Self, GamepadError> {
+        // the deadzone is handled by the settings, not gilrs
+        let gilrs = GilrsBuilder::new()
+            .with_default_filters(false)
+            .build()
+            .map_err(|e| GamepadError(Box::new(e)))?;
+
+        Ok(Self {
+            gilrs,
+            active: None,
+            settings,
+        })
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns true if a gamepad is connected
+    pub fn is_connected(&self) -> bool {
+        self.gamepad().is_some()
+    }
+
+    fn gamepad(&self) -> Option<Gamepad<'_>> {
+        self.active
+            .and_then(|id| self.gilrs.connected_gamepad(id))
+            .or_else(|| self.gilrs.gamepads().next().map(|(_, gamepad)| gamepad))
+    }
+
+    /// Processes the pending gamepad events, and returns the current controls
+    ///
+    /// Returns the default controls if no gamepad is connected
+    pub fn poll(&mut self) -> CarControls {
+        while let Some(event) = self.gilrs.next_event() {
+            self.active = Some(event.id);
+        }
+
+        let Some(gamepad) = self.gamepad() else {
+            return CarControls::default();
+        };
+
+        let settings = self.settings;
+        let trigger = |button: Button, axis: Axis| {
+            let value = gamepad
+                .button_data(button)
+                .map_or_else(|| gamepad.value(axis).max(0.), |data| data.value());
+            rescale(value, settings.trigger_deadzone)
+        };
+
+        let (x, y) = settings.apply_deadzone(gamepad.value(Axis::LeftStickX), gamepad.value(Axis::LeftStickY));
+        let air_roll = gamepad.is_pressed(Button::West);
+        let directional_roll = f32::from(u8::from(gamepad.is_pressed(Button::RightTrigger)))
+            - f32::from(u8::from(gamepad.is_pressed(Button::LeftTrigger)));
+
+        let aerial = |value: f32| (value * settings.aerial_sensitivity).clamp(-1., 1.);
+
+        CarControls {
+            throttle: (trigger(Button::RightTrigger2, Axis::RightZ) - trigger(Button::LeftTrigger2, Axis::LeftZ))
+                .clamp(-1., 1.),
+            steer: (x * settings.steering_sensitivity).clamp(-1., 1.),
+            // pushing the stick forward tilts the nose down
+            pitch: aerial(-y),
+            yaw: if air_roll { 0. } else { aerial(x) },
+            roll: if air_roll { aerial(x) } else { directional_roll },
+            jump: gamepad.is_pressed(Button::South),
+            boost: gamepad.is_pressed(Button::East),
+            handbrake: air_roll,
+        }
+    }
+}
+
+impl Controller for GamepadController {
+    #[inline]
+    fn controls(&mut self, _car_id: u32, _game_state: &GameState) -> CarControls {
+        self.poll()
+    }
+}
@@ -0,0 +1,167 @@
+use super::Controller;
+use crate::{sim::CarControls, GameState};
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    terminal,
+};
+use std::{
+    collections::HashMap,
+    io,
+    time::{Duration, Instant},
+};
+
+/// Controls a car with the keyboard, read from the terminal
+///
+/// | Key | Action |
+/// | --- | --- |
+/// | W/S | Throttle/reverse, and pitch down/up |
+/// | A/D | Steer and yaw |
+/// | Q/E | Air roll left/right |
+/// | Space or J | Jump |
+/// | K | Boost |
+/// | L | Powerslide |
+/// | Esc or Ctrl+C | Quit, see `quit_requested` |
+///
+/// The terminal is put into raw mode until the controller is dropped.
+///
+/// Most terminals only report key presses (and repeats), not releases.
+/// In that case, a key is held until `hold_timeout` after its last press or repeat,
+/// so the timeout should be longer than the OS's key repeat delay.
+/// On Windows, and on terminals that support the kitty keyboard protocol, releases are reported exactly.
+pub struct KeyboardController {
+    /// The last time that each held key was pressed or repeated
+    held: HashMap<KeyCode, Instant>,
+    reports_releases: bool,
+    enhanced: bool,
+    quit_requested: bool,
+    pub hold_timeout: Duration,
+}
+
+impl KeyboardController {
+    /// The default `hold_timeout`, a bit longer than the usual key repeat delay
+    pub const DEFAULT_HOLD_TIMEOUT: Duration = Duration::from_millis(600);
+
+    /// Puts the terminal into raw mode, and asks it to report key releases if it can
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the terminal can't be put into raw mode
+    pub fn new() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+
+        let enhanced = !cfg!(windows) && terminal::supports_keyboard_enhancement().unwrap_or(false) && {
+            crossterm::execute!(
+                io::stdout(),
+                event::PushKeyboardEnhancementFlags(event::KeyboardEnhancementFlags::REPORT_EVENT_TYPES)
+            )
+            .is_ok()
+        };
+
+        Ok(Self {
+            held: HashMap::new(),
+            reports_releases: cfg!(windows) || enhanced,
+            enhanced,
+            quit_requested: false,
+            hold_timeout: Self::DEFAULT_HOLD_TIMEOUT,
+        })
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns true if key releases are reported by the terminal, so `hold_timeout` isn't used
+    pub const fn reports_releases(&self) -> bool {
+        self.reports_releases
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns true once Esc or Ctrl+C has been pressed, since raw mode stops Ctrl+C from interrupting the program
+    pub const fn quit_requested(&self) -> bool {
+        self.quit_requested
+    }
+
+    fn is_held(&self, key: char) -> bool {
+        self.held.contains_key(&KeyCode::Char(key))
+    }
+
+    fn axis(&self, positive: char, negative: char) -> f32 {
+        f32::from(u8::from(self.is_held(positive))) - f32::from(u8::from(self.is_held(negative)))
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, now: Instant) {
+        let code = match key.code {
+            KeyCode::Char(c) => KeyCode::Char(c.to_ascii_lowercase()),
+            code => code,
+        };
+
+        match key.kind {
+            KeyEventKind::Press | KeyEventKind::Repeat => {
+                if code == KeyCode::Esc || (code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL)) {
+                    self.quit_requested = true;
+                }
+
+                self.held.insert(code, now);
+            }
+            KeyEventKind::Release => {
+                self.held.remove(&code);
+            }
+        }
+    }
+
+    /// Processes the pending terminal events, and returns the current controls
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the terminal events can't be read
+    pub fn poll(&mut self) -> io::Result<CarControls> {
+        let now = Instant::now();
+
+        while event::poll(Duration::ZERO)? {
+            if let Event::Key(key) = event::read()? {
+                self.handle_key(key, now);
+            }
+        }
+
+        if !self.reports_releases {
+            let hold_timeout = self.hold_timeout;
+            self.held
+                .retain(|_, last_pressed| now.duration_since(*last_pressed) < hold_timeout);
+        }
+
+        let turn = self.axis('d', 'a');
+
+        Ok(CarControls {
+            throttle: self.axis('w', 's'),
+            steer: turn,
+            pitch: self.axis('s', 'w'),
+            yaw: turn,
+            roll: self.axis('e', 'q'),
+            jump: self.is_held(' ') || self.is_held('j'),
+            boost: self.is_held('k'),
+            handbrake: self.is_held('l'),
+        })
+    }
+}
+
+impl Controller for KeyboardController {
+    /// Returns the default controls if the terminal events can't be read
+    #[inline]
+    fn controls(&mut self, _car_id: u32, _game_state: &GameState) -> CarControls {
+        self.poll().unwrap_or_default()
+    }
+
+    #[inline]
+    fn quit_requested(&self) -> bool {
+        self.quit_requested
+    }
+}
+
+impl Drop for KeyboardController {
+    fn drop(&mut self) {
+        if self.enhanced {
+            let _ = crossterm::execute!(io::stdout(), event::PopKeyboardEnhancementFlags);
+        }
+
+        let _ = terminal::disable_raw_mode();
+    }
+}
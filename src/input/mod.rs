@@ -0,0 +1,34 @@
+//! Sources of car controls, like bots and human input devices
+
+#[cfg(feature = "gilrs")]
+mod gamepad;
+#[cfg(feature = "keyboard")]
+mod keyboard;
+
+#[cfg(feature = "gilrs")]
+pub use gamepad::{DeadzoneShape, GamepadController, GamepadError, GamepadSettings};
+#[cfg(feature = "keyboard")]
+pub use keyboard::KeyboardController;
+
+use crate::{sim::CarControls, GameState};
+
+/// Something that decides the controls of a car every tick, like a bot or a human input device
+///
+/// Closures of the form `FnMut(u32, &GameState) -> CarControls` also implement this trait
+pub trait Controller {
+    /// Returns the controls for the car with the given ID, in the given game state
+    fn controls(&mut self, car_id: u32, game_state: &GameState) -> CarControls;
+
+    #[inline]
+    /// Returns true if the controller wants to end the session, e.g. a human pressed a quit key
+    fn quit_requested(&self) -> bool {
+        false
+    }
+}
+
+impl<F: FnMut(u32, &GameState) -> CarControls> Controller for F {
+    #[inline]
+    fn controls(&mut self, car_id: u32, game_state: &GameState) -> CarControls {
+        self(car_id, game_state)
+    }
+}
@@ -0,0 +1,275 @@
+//! A compact on-disk format for recording every car's controls, and replaying them into an arena
+//!
+//! Given an arena in the same state as when the recording started (e.g. restored from the same snapshot,
+//! or reset with the same kickoff seed), playing the inputs back reproduces the run exactly.
+//!
+//! An input file is a header (`MAGIC`, a `VERSION` byte, and the `u64` starting tick count)
+//! followed by frames of controls. Each frame is a `u32` number of ticks, a `u8` number of cars,
+//! and then the `u32` ID and `CarControls` of each car. Consecutive ticks with the same controls share a frame.
+
+use crate::{
+    bytes::{FromBytes, FromBytesExact, ToBytesExact},
+    sim::{Arena, CarControls},
+    NoCarFound,
+};
+use core::pin::Pin;
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, ErrorKind, Read, Write},
+    path::Path,
+};
+
+pub const MAGIC: [u8; 4] = *b"RSIN";
+pub const VERSION: u8 = 1;
+
+#[derive(Clone, Debug, Default)]
+/// The controls of every car, held for a number of ticks
+pub struct InputFrame {
+    pub num_ticks: u32,
+    /// `(car id, controls)`, sorted by the car IDs
+    pub controls: Vec<(u32, CarControls)>,
+}
+
+impl InputFrame {
+    fn has_same_controls(&self, controls: &[(u32, CarControls)]) -> bool {
+        self.controls.len() == controls.len()
+            && self
+                .controls
+                .iter()
+                .zip(controls)
+                .all(|((id_a, a), (id_b, b))| id_a == id_b && a.to_bytes() == b.to_bytes())
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+/// Every car's controls for each tick of a run
+pub struct InputRecording {
+    /// The tick count of the arena when the recording started
+    pub start_tick: u64,
+    pub frames: Vec<InputFrame>,
+}
+
+impl InputRecording {
+    #[inline]
+    #[must_use]
+    pub const fn new(start_tick: u64) -> Self {
+        Self {
+            start_tick,
+            frames: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    /// The total number of recorded ticks
+    pub fn num_ticks(&self) -> u64 {
+        self.frames.iter().map(|frame| u64::from(frame.num_ticks)).sum()
+    }
+
+    /// Adds controls that were held for the given number of ticks
+    ///
+    /// The controls are sorted by car ID, and merged into the last frame if they're the same
+    pub fn push(&mut self, num_ticks: u32, mut controls: Vec<(u32, CarControls)>) {
+        if num_ticks == 0 {
+            return;
+        }
+
+        controls.sort_unstable_by_key(|(id, _)| *id);
+
+        if let Some(frame) = self.frames.last_mut() {
+            if frame.has_same_controls(&controls) {
+                if let Some(total) = frame.num_ticks.checked_add(num_ticks) {
+                    frame.num_ticks = total;
+                    return;
+                }
+            }
+        }
+
+        self.frames.push(InputFrame { num_ticks, controls });
+    }
+
+    /// Returns the controls of every car at the given tick count, if it's in the recording
+    #[must_use]
+    pub fn controls_at(&self, tick_count: u64) -> Option<&[(u32, CarControls)]> {
+        let mut tick = self.start_tick;
+
+        for frame in &self.frames {
+            tick += u64::from(frame.num_ticks);
+            if tick_count < tick {
+                return (tick_count >= self.start_tick).then_some(&frame.controls);
+            }
+        }
+
+        None
+    }
+
+    /// Writes the recording in the format described in the module documentation
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the recording can't be written
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        writer.write_all(&self.start_tick.to_le_bytes())?;
+
+        for frame in &self.frames {
+            let num_cars = u8::try_from(frame.controls.len())
+                .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "Too many cars in an input frame"))?;
+
+            writer.write_all(&frame.num_ticks.to_le_bytes())?;
+            writer.write_all(&[num_cars])?;
+
+            for (id, controls) in &frame.controls {
+                writer.write_all(&id.to_le_bytes())?;
+                writer.write_all(&controls.to_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads a recording in the format described in the module documentation
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the recording can't be read, isn't an input recording, or ends in the middle of a frame
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut header = [0; MAGIC.len() + 1];
+        reader.read_exact(&mut header)?;
+
+        if header[..MAGIC.len()] != MAGIC {
+            return Err(io::Error::new(ErrorKind::InvalidData, "Not an input recording"));
+        }
+
+        if header[MAGIC.len()] != VERSION {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("Unsupported input recording version {}", header[MAGIC.len()]),
+            ));
+        }
+
+        let mut start_tick = [0; 8];
+        reader.read_exact(&mut start_tick)?;
+
+        let mut recording = Self::new(u64::from_le_bytes(start_tick));
+        let mut frame_header = [0; 5];
+        let mut car = [0; 4 + CarControls::NUM_BYTES];
+
+        loop {
+            let mut num_read = 0;
+            while num_read < frame_header.len() {
+                match reader.read(&mut frame_header[num_read..])? {
+                    0 if num_read == 0 => return Ok(recording),
+                    0 => return Err(ErrorKind::UnexpectedEof.into()),
+                    n => num_read += n,
+                }
+            }
+
+            let num_ticks = u32::from_le_bytes([frame_header[0], frame_header[1], frame_header[2], frame_header[3]]);
+            let controls = (0..frame_header[4])
+                .map(|_| {
+                    reader.read_exact(&mut car)?;
+                    Ok((
+                        u32::from_le_bytes([car[0], car[1], car[2], car[3]]),
+                        CarControls::from_bytes(&car[4..]),
+                    ))
+                })
+                .collect::<io::Result<_>>()?;
+
+            recording.frames.push(InputFrame { num_ticks, controls });
+        }
+    }
+
+    /// Creates (or truncates) the file at the given path and writes the recording to it
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be created or written to
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Reads the recording at the given path
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or isn't an input recording
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+}
+
+/// Records the controls that every car in an arena used
+///
+/// Call `record` after every call to `step`.
+/// Since controls are held between steps, stepping multiple ticks at once is fine.
+pub struct InputRecorder {
+    recording: InputRecording,
+    last_tick: u64,
+}
+
+impl InputRecorder {
+    #[inline]
+    #[must_use]
+    /// Starts recording from the arena's current tick count
+    pub fn new(arena: &Arena) -> Self {
+        let start_tick = arena.get_tick_count();
+
+        Self {
+            recording: InputRecording::new(start_tick),
+            last_tick: start_tick,
+        }
+    }
+
+    /// Records the controls that were used since the last call
+    pub fn record(&mut self, arena: &Arena) {
+        let tick_count = arena.get_tick_count();
+        let num_ticks = tick_count.saturating_sub(self.last_tick);
+        self.last_tick = tick_count;
+
+        let controls = arena
+            .get_cars()
+            .into_iter()
+            .filter_map(|id| Some((id, arena.car_state_ref(id).ok()?.internal().last_controls)))
+            .collect::<Vec<_>>();
+
+        let mut num_ticks = num_ticks;
+        while num_ticks != 0 {
+            let chunk = num_ticks.min(u64::from(u32::MAX));
+            self.recording.push(chunk as u32, controls.clone());
+            num_ticks -= chunk;
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn recording(&self) -> &InputRecording {
+        &self.recording
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn into_recording(self) -> InputRecording {
+        self.recording
+    }
+}
+
+impl Arena {
+    /// Sets the recorded controls and steps the arena for every frame of the recording
+    ///
+    /// For an exact reproduction, the arena must be in the same state as when the recording started
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a car in the recording isn't in the arena, after stepping all of the frames before it
+    pub fn play_inputs(mut self: Pin<&mut Self>, recording: &InputRecording) -> Result<(), NoCarFound> {
+        for frame in &recording.frames {
+            self.as_mut().set_all_controls(frame.controls.iter().copied())?;
+            self.as_mut().step(frame.num_ticks);
+        }
+
+        Ok(())
+    }
+}
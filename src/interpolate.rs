@@ -0,0 +1,229 @@
+use crate::{
+    math::{RotMat, Vec3},
+    sim::{BallState, CarState},
+    GameState,
+};
+
+/// Any object that would have to move faster than this (in uu/s) between two states
+/// is assumed to have been teleported (e.g. respawned or reset after a goal) and is not interpolated
+pub const TELEPORT_SPEED: f32 = 10000.;
+
+#[derive(Clone, Copy, Debug)]
+struct Quat {
+    w: f32,
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+impl Quat {
+    fn from_rot_mat(mat: RotMat) -> Self {
+        let (f, r, u) = (mat.forward, mat.right, mat.up);
+        let trace = f.x + r.y + u.z;
+
+        let quat = if trace > 0. {
+            let s = (trace + 1.).sqrt() * 2.;
+            Self {
+                w: 0.25 * s,
+                x: (r.z - u.y) / s,
+                y: (u.x - f.z) / s,
+                z: (f.y - r.x) / s,
+            }
+        } else if f.x > r.y && f.x > u.z {
+            let s = (1. + f.x - r.y - u.z).sqrt() * 2.;
+            Self {
+                w: (r.z - u.y) / s,
+                x: 0.25 * s,
+                y: (r.x + f.y) / s,
+                z: (u.x + f.z) / s,
+            }
+        } else if r.y > u.z {
+            let s = (1. + r.y - f.x - u.z).sqrt() * 2.;
+            Self {
+                w: (u.x - f.z) / s,
+                x: (r.x + f.y) / s,
+                y: 0.25 * s,
+                z: (u.y + r.z) / s,
+            }
+        } else {
+            let s = (1. + u.z - f.x - r.y).sqrt() * 2.;
+            Self {
+                w: (f.y - r.x) / s,
+                x: (u.x + f.z) / s,
+                y: (u.y + r.z) / s,
+                z: 0.25 * s,
+            }
+        };
+
+        quat.normalize()
+    }
+
+    fn to_rot_mat(self) -> RotMat {
+        let Self { w, x, y, z } = self;
+
+        RotMat::new(
+            Vec3::new(1. - 2. * (y * y + z * z), 2. * (x * y + w * z), 2. * (x * z - w * y)),
+            Vec3::new(2. * (x * y - w * z), 1. - 2. * (x * x + z * z), 2. * (y * z + w * x)),
+            Vec3::new(2. * (x * z + w * y), 2. * (y * z - w * x), 1. - 2. * (x * x + y * y)),
+        )
+    }
+
+    fn dot(self, other: Self) -> f32 {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    fn scale(self, s: f32) -> Self {
+        Self {
+            w: self.w * s,
+            x: self.x * s,
+            y: self.y * s,
+            z: self.z * s,
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            w: self.w + other.w,
+            x: self.x + other.x,
+            y: self.y + other.y,
+            z: self.z + other.z,
+        }
+    }
+
+    fn normalize(self) -> Self {
+        self.scale(1. / self.dot(self).sqrt())
+    }
+
+    fn slerp(self, mut other: Self, alpha: f32) -> Self {
+        let mut cos_theta = self.dot(other);
+
+        // take the shortest path
+        if cos_theta < 0. {
+            other = other.scale(-1.);
+            cos_theta = -cos_theta;
+        }
+
+        // nearly identical, so fall back to a normalized lerp to avoid dividing by ~0
+        if cos_theta > 0.9995 {
+            return self.scale(1. - alpha).add(other.scale(alpha)).normalize();
+        }
+
+        let theta = cos_theta.acos();
+        let sin_theta = theta.sin();
+
+        self.scale(((1. - alpha) * theta).sin() / sin_theta)
+            .add(other.scale((alpha * theta).sin() / sin_theta))
+    }
+}
+
+#[inline]
+fn lerp(a: f32, b: f32, alpha: f32) -> f32 {
+    a + (b - a) * alpha
+}
+
+#[inline]
+fn lerp_vec(a: Vec3, b: Vec3, alpha: f32) -> Vec3 {
+    a + (b - a) * alpha
+}
+
+impl RotMat {
+    #[must_use]
+    /// Spherically interpolates between two rotations, where `alpha` is in `0..=1`
+    pub fn slerp(self, other: Self, alpha: f32) -> Self {
+        Quat::from_rot_mat(self).slerp(Quat::from_rot_mat(other), alpha).to_rot_mat()
+    }
+}
+
+/// Returns true if moving between the two positions in `dt` seconds would be faster than `TELEPORT_SPEED`
+fn is_teleport(a: Vec3, b: Vec3, dt: f32) -> bool {
+    (b - a).length() > TELEPORT_SPEED * dt
+}
+
+impl BallState {
+    #[must_use]
+    /// Interpolates between two ball states, where `alpha` is in `0..=1`
+    ///
+    /// Positions and velocities are lerped and the rotation is slerped,
+    /// everything else is taken from whichever state is closer.
+    pub fn interpolate(&self, next: &Self, alpha: f32) -> Self {
+        let base = if alpha < 0.5 { self } else { next };
+
+        Self {
+            pos: lerp_vec(self.pos, next.pos, alpha),
+            rot_mat: self.rot_mat.slerp(next.rot_mat, alpha),
+            vel: lerp_vec(self.vel, next.vel, alpha),
+            ang_vel: lerp_vec(self.ang_vel, next.ang_vel, alpha),
+            ..*base
+        }
+    }
+}
+
+impl CarState {
+    #[must_use]
+    /// Interpolates between two car states, where `alpha` is in `0..=1`
+    ///
+    /// Positions, velocities and boost are lerped and the rotation is slerped,
+    /// everything else is taken from whichever state is closer.
+    pub fn interpolate(&self, next: &Self, alpha: f32) -> Self {
+        let base = if alpha < 0.5 { self } else { next };
+
+        Self {
+            pos: lerp_vec(self.pos, next.pos, alpha),
+            rot_mat: self.rot_mat.slerp(next.rot_mat, alpha),
+            vel: lerp_vec(self.vel, next.vel, alpha),
+            ang_vel: lerp_vec(self.ang_vel, next.ang_vel, alpha),
+            boost: lerp(self.boost, next.boost, alpha),
+            ..*base
+        }
+    }
+}
+
+impl GameState {
+    #[must_use]
+    /// Interpolates between this state and the next one, where `alpha` is in `0..=1`
+    ///
+    /// Intended for rendering at a higher frame rate than the simulation.
+    /// Discontinuities are not interpolated and instead snap to whichever state is closer:
+    /// cars that are demolished in either state, cars that only exist in one of the states,
+    /// and objects that were teleported (see `TELEPORT_SPEED`), like the ball after a goal.
+    pub fn interpolate(&self, next: &Self, alpha: f32) -> Self {
+        let alpha = alpha.clamp(0., 1.);
+        let base = if alpha < 0.5 { self } else { next };
+
+        let ticks = next.tick_count.saturating_sub(self.tick_count).max(1);
+        let dt = ticks as f32 / self.tick_rate;
+
+        let ball = if is_teleport(self.ball.pos, next.ball.pos, dt) {
+            base.ball
+        } else {
+            self.ball.interpolate(&next.ball, alpha)
+        };
+
+        let cars = base
+            .cars
+            .iter()
+            .map(|car| {
+                let prev = self.cars.iter().find(|prev| prev.id == car.id);
+                let after = next.cars.iter().find(|after| after.id == car.id);
+
+                let mut car = *car;
+                if let (Some(prev), Some(after)) = (prev, after) {
+                    if !prev.state.is_demoed && !after.state.is_demoed && !is_teleport(prev.state.pos, after.state.pos, dt) {
+                        car.state = prev.state.interpolate(&after.state, alpha);
+                    }
+                }
+
+                car
+            })
+            .collect();
+
+        Self {
+            tick_rate: base.tick_rate,
+            tick_count: base.tick_count,
+            game_mode: base.game_mode,
+            cars,
+            ball,
+            pads: base.pads.clone(),
+        }
+    }
+}
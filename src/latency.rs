@@ -0,0 +1,194 @@
+//! Input latency and dropout, for emulating online play and testing how robust controllers are to it
+//!
+//! Controls go through a per-car queue before they reach the arena.
+//! Latency delays them by a number of ticks, and dropped inputs are never received,
+//! so the car keeps using the last controls that arrived, like the server does in Rocket League.
+
+use crate::{
+    input::Controller,
+    sim::{Arena, CarControls},
+    GameState, NoCarFound,
+};
+use rand::Rng;
+use std::{collections::VecDeque, pin::Pin};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+/// The network conditions of a car, which are perfect by default
+pub struct NetworkConditions {
+    /// How many ticks the controls are delayed by
+    pub latency_ticks: usize,
+    /// The chance (`0..=1`) that an input is lost
+    pub drop_chance: f32,
+}
+
+impl NetworkConditions {
+    #[inline]
+    #[must_use]
+    pub const fn new(latency_ticks: usize, drop_chance: f32) -> Self {
+        Self {
+            latency_ticks,
+            drop_chance,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+/// The controls of a single car that haven't arrived yet
+pub struct InputQueue {
+    pub conditions: NetworkConditions,
+    /// `None` for inputs that were dropped
+    pending: VecDeque<Option<CarControls>>,
+    applied: CarControls,
+}
+
+impl InputQueue {
+    #[inline]
+    #[must_use]
+    pub fn new(conditions: NetworkConditions) -> Self {
+        Self {
+            conditions,
+            pending: VecDeque::new(),
+            applied: CarControls::default(),
+        }
+    }
+
+    /// Forgets the pending inputs, and goes back to the default controls
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.applied = CarControls::default();
+    }
+
+    #[inline]
+    #[must_use]
+    /// The controls that were returned by the last call to `push`
+    pub const fn applied(&self) -> CarControls {
+        self.applied
+    }
+
+    /// Sends the controls for this tick, and returns the controls that should be applied this tick
+    ///
+    /// This should be called exactly once per tick.
+    /// If the latency is lowered, the inputs that would now be late are skipped.
+    pub fn push<R: Rng + ?Sized>(&mut self, rng: &mut R, controls: CarControls) -> CarControls {
+        let drop_chance = self.conditions.drop_chance.clamp(0., 1.);
+        let dropped = drop_chance > 0. && rng.gen_bool(f64::from(drop_chance));
+        self.pending.push_back((!dropped).then_some(controls));
+
+        while self.pending.len() > self.conditions.latency_ticks {
+            if let Some(Some(controls)) = self.pending.pop_front() {
+                self.applied = controls;
+            }
+        }
+
+        self.applied
+    }
+}
+
+/// The input queues of every car in an arena, with the network conditions configurable per car
+pub struct InputLatency<R: Rng> {
+    /// The conditions of cars that don't have their own
+    pub default_conditions: NetworkConditions,
+    queues: Vec<(u32, InputQueue)>,
+    rng: R,
+}
+
+impl<R: Rng> InputLatency<R> {
+    #[inline]
+    #[must_use]
+    pub const fn new(default_conditions: NetworkConditions, rng: R) -> Self {
+        Self {
+            default_conditions,
+            queues: Vec::new(),
+            rng,
+        }
+    }
+
+    /// The index of the car's queue, which is created if it doesn't exist yet
+    fn queue_index(&mut self, car_id: u32) -> usize {
+        self.queues.iter().position(|(id, _)| *id == car_id).unwrap_or_else(|| {
+            self.queues.push((car_id, InputQueue::new(self.default_conditions)));
+            self.queues.len() - 1
+        })
+    }
+
+    #[must_use]
+    /// The network conditions of the car
+    pub fn conditions(&self, car_id: u32) -> NetworkConditions {
+        self.queues
+            .iter()
+            .find(|(id, _)| *id == car_id)
+            .map_or(self.default_conditions, |(_, queue)| queue.conditions)
+    }
+
+    /// Changes the network conditions of the car, which takes effect from the next tick
+    pub fn set_conditions(&mut self, car_id: u32, conditions: NetworkConditions) {
+        let i = self.queue_index(car_id);
+        self.queues[i].1.conditions = conditions;
+    }
+
+    /// Forgets the pending inputs of every car, e.g. when the episode is reset
+    pub fn clear(&mut self) {
+        for (_, queue) in &mut self.queues {
+            queue.clear();
+        }
+    }
+
+    /// Sends the controls of the car for this tick, and returns the controls that should be applied this tick
+    ///
+    /// This should be called exactly once per tick for each car
+    pub fn push(&mut self, car_id: u32, controls: CarControls) -> CarControls {
+        let i = self.queue_index(car_id);
+        self.queues[i].1.push(&mut self.rng, controls)
+    }
+
+    /// Sends the controls of the car for this tick, and sets the controls that arrived in the arena
+    ///
+    /// This should be called exactly once per tick for each car, before stepping
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the car isn't in the arena
+    pub fn set_car_controls(
+        &mut self,
+        arena: Pin<&mut Arena>,
+        car_id: u32,
+        controls: CarControls,
+    ) -> Result<(), NoCarFound> {
+        let controls = self.push(car_id, controls);
+        arena.set_car_controls(car_id, controls)
+    }
+}
+
+/// Wraps a controller so that its controls arrive late, or not at all
+///
+/// The wrapped controller should be called once per tick, so that the latency is in ticks
+pub struct LaggyController<C: Controller, R: Rng> {
+    pub controller: C,
+    pub queue: InputQueue,
+    rng: R,
+}
+
+impl<C: Controller, R: Rng> LaggyController<C, R> {
+    #[inline]
+    #[must_use]
+    pub fn new(controller: C, conditions: NetworkConditions, rng: R) -> Self {
+        Self {
+            controller,
+            queue: InputQueue::new(conditions),
+            rng,
+        }
+    }
+}
+
+impl<C: Controller, R: Rng> Controller for LaggyController<C, R> {
+    #[inline]
+    fn controls(&mut self, car_id: u32, game_state: &GameState) -> CarControls {
+        let controls = self.controller.controls(car_id, game_state);
+        self.queue.push(&mut self.rng, controls)
+    }
+
+    #[inline]
+    fn quit_requested(&self) -> bool {
+        self.controller.quit_requested()
+    }
+}
@@ -5,25 +5,97 @@
     feature(portable_simd)
 )]
 
+#[cfg(feature = "bevy")]
+pub mod bevy_plugin;
+#[cfg(feature = "burn")]
+pub mod burn_ext;
 #[cfg(feature = "bin")]
 pub mod bytes;
+#[cfg(any(feature = "zstd", feature = "lz4"))]
+pub mod compression;
+#[cfg(feature = "rand")]
+pub mod curriculum;
+#[cfg(feature = "arrow")]
+pub mod dataset;
+#[cfg(feature = "bin")]
+pub mod distributed;
+#[cfg(feature = "gilrs")]
+pub use gilrs;
 #[cfg(feature = "glam")]
 pub mod glam_ext;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "bin")]
+pub mod inputs;
+#[cfg(feature = "rand")]
+pub mod latency;
+#[cfg(feature = "rand")]
+pub mod noise;
+#[cfg(feature = "bin")]
+pub mod playback;
+#[cfg(feature = "bin")]
+pub mod playtest;
+#[cfg(feature = "bin")]
+pub mod protocol;
+#[cfg(feature = "rand")]
+pub mod randomization;
 #[cfg(feature = "bin")]
 pub mod render;
+#[cfg(feature = "bin")]
+pub mod replay;
+#[cfg(any(feature = "scenario", feature = "rand"))]
+pub mod scenario;
+#[cfg(feature = "rand")]
+pub mod selfplay;
 #[cfg(feature = "serde_utils")]
 mod serde_utils;
+#[cfg(feature = "bin")]
+pub mod snapshot;
 #[cfg(feature = "serde_utils")]
 pub use serde;
+#[cfg(feature = "uom")]
+pub mod uom_ext;
 
+pub mod analysis;
+pub mod console;
 pub mod consts;
+pub mod control;
+pub mod debug;
+pub mod episodes;
+pub mod events;
+pub mod field;
+pub mod geometry;
+pub mod golden;
+pub mod highlights;
+pub mod history;
+pub mod input;
 pub mod math;
+pub mod metadata;
+pub mod pool;
+pub mod prediction;
+pub mod props;
+pub mod realtime;
+pub mod registry;
+pub mod rules;
+pub mod rumble;
 pub mod sim;
+pub mod stats;
+pub mod targets;
+pub mod telemetry;
+pub mod triggers;
+pub mod tuning;
+pub mod units;
+pub mod validate;
+pub mod watchdog;
 
 mod ext;
+mod interpolate;
+mod mirror;
 
 pub use cxx;
 pub use ext::*;
+pub use interpolate::TELEPORT_SPEED;
+pub use smallvec;
 
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
@@ -57,6 +129,7 @@ mod base {
         type GameMode = crate::sim::GameMode;
         #[cxx_name = "EArenaConfig"]
         type ArenaConfig = crate::sim::ArenaConfig;
+        type BoostPadConfig = crate::sim::BoostPadConfig;
         #[rust_name = "Arena"]
         type Arenar = crate::sim::Arena;
 
@@ -79,6 +152,15 @@ mod base {
         #[doc(hidden)]
         fn CreateArena(game_mode: GameMode, arena_config: ArenaConfig, tick_rate: u8) -> UniquePtr<Arena>;
 
+        #[must_use]
+        #[doc(hidden)]
+        fn CreateArenaWithBoostPads(
+            game_mode: GameMode,
+            arena_config: ArenaConfig,
+            tick_rate: u8,
+            boost_pads: &[BoostPadConfig],
+        ) -> UniquePtr<Arena>;
+
         #[rust_name = "get_octane"]
         fn getOctane() -> &'static CarConfig;
 
@@ -8,6 +8,7 @@ pub struct Vec3 {
     pub x: f32,
     pub y: f32,
     pub z: f32,
+    #[cfg_attr(feature = "serde_utils", serde(default))]
     pub(crate) w: f32,
 }
 
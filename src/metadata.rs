@@ -0,0 +1,211 @@
+//! Display names, team names and tags for the cars in an arena
+//!
+//! The registry is saved alongside replays, and used by stats exports in place of the car ids.
+//! Cars that aren't in the registry fall back to `"Car <id>"`.
+
+use crate::sim::Team;
+use std::io::{self, ErrorKind, Read, Write};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_utils", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlayerMetadata {
+    pub car_id: u32,
+    pub name: String,
+    /// Arbitrary labels, e.g. the name of the policy or checkpoint that's controlling the car
+    pub tags: Vec<String>,
+}
+
+impl PlayerMetadata {
+    #[inline]
+    #[must_use]
+    pub fn new(car_id: u32, name: impl Into<String>) -> Self {
+        Self {
+            car_id,
+            name: name.into(),
+            tags: Vec::new(),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_utils", derive(serde::Serialize, serde::Deserialize))]
+/// The names of the teams and players in a match
+pub struct Metadata {
+    /// `[blue, orange]`
+    pub team_names: [Option<String>; 2],
+    pub players: Vec<PlayerMetadata>,
+}
+
+impl Metadata {
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            team_names: [None, None],
+            players: Vec::new(),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn with_player(mut self, player: PlayerMetadata) -> Self {
+        self.set_player(player);
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn with_team_name(mut self, team: Team, name: impl Into<String>) -> Self {
+        self.set_team_name(team, name);
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.players.is_empty() && self.team_names.iter().all(Option::is_none)
+    }
+
+    /// Adds a player, replacing the player with the same car id if there is one
+    pub fn set_player(&mut self, player: PlayerMetadata) {
+        match self.player_mut(player.car_id) {
+            Some(existing) => *existing = player,
+            None => self.players.push(player),
+        }
+    }
+
+    /// Removes the player with the car id, returning it if there was one
+    pub fn remove_player(&mut self, car_id: u32) -> Option<PlayerMetadata> {
+        let index = self.players.iter().position(|player| player.car_id == car_id)?;
+        Some(self.players.remove(index))
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn player(&self, car_id: u32) -> Option<&PlayerMetadata> {
+        self.players.iter().find(|player| player.car_id == car_id)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn player_mut(&mut self, car_id: u32) -> Option<&mut PlayerMetadata> {
+        self.players.iter_mut().find(|player| player.car_id == car_id)
+    }
+
+    #[must_use]
+    /// The display name of the car, or `"Car <id>"` if it isn't in the registry
+    pub fn name(&self, car_id: u32) -> String {
+        self.player(car_id)
+            .map_or_else(|| format!("Car {car_id}"), |player| player.name.clone())
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn team_name(&self, team: Team) -> Option<&str> {
+        self.team_names[team as usize].as_deref()
+    }
+
+    pub fn set_team_name(&mut self, team: Team, name: impl Into<String>) {
+        self.team_names[team as usize] = Some(name.into());
+    }
+
+    /// The car ids of the players that have the tag
+    pub fn cars_with_tag<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = u32> + 'a {
+        self.players
+            .iter()
+            .filter(move |player| player.has_tag(tag))
+            .map(|player| player.car_id)
+    }
+
+    /// Writes the registry as little-endian, length-prefixed UTF-8 strings
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the registry can't be written, or if it has more than `u16::MAX` players
+    /// or tags, or a string longer than `u16::MAX` bytes
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for name in &self.team_names {
+            write_str(&mut writer, name.as_deref().unwrap_or_default())?;
+        }
+
+        write_len(&mut writer, self.players.len())?;
+        for player in &self.players {
+            writer.write_all(&player.car_id.to_le_bytes())?;
+            write_str(&mut writer, &player.name)?;
+
+            write_len(&mut writer, player.tags.len())?;
+            for tag in &player.tags {
+                write_str(&mut writer, tag)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads a registry that was written with `write_to`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the registry can't be read, or has a string that isn't valid UTF-8
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut metadata = Self::new();
+
+        for name in &mut metadata.team_names {
+            let team_name = read_str(&mut reader)?;
+            *name = (!team_name.is_empty()).then_some(team_name);
+        }
+
+        let num_players = read_len(&mut reader)?;
+        metadata.players.reserve(num_players);
+        for _ in 0..num_players {
+            let mut car_id = [0; 4];
+            reader.read_exact(&mut car_id)?;
+
+            let mut player = PlayerMetadata::new(u32::from_le_bytes(car_id), read_str(&mut reader)?);
+            let num_tags = read_len(&mut reader)?;
+            player.tags.reserve(num_tags);
+            for _ in 0..num_tags {
+                player.tags.push(read_str(&mut reader)?);
+            }
+
+            metadata.players.push(player);
+        }
+
+        Ok(metadata)
+    }
+}
+
+fn write_len<W: Write>(writer: &mut W, len: usize) -> io::Result<()> {
+    let len = u16::try_from(len).map_err(|_| io::Error::new(ErrorKind::InvalidInput, "Too many items in the metadata"))?;
+    writer.write_all(&len.to_le_bytes())
+}
+
+fn read_len<R: Read>(reader: &mut R) -> io::Result<usize> {
+    let mut len = [0; 2];
+    reader.read_exact(&mut len)?;
+    Ok(usize::from(u16::from_le_bytes(len)))
+}
+
+fn write_str<W: Write>(writer: &mut W, s: &str) -> io::Result<()> {
+    write_len(writer, s.len())?;
+    writer.write_all(s.as_bytes())
+}
+
+fn read_str<R: Read>(reader: &mut R) -> io::Result<String> {
+    let mut bytes = vec![0; read_len(reader)?];
+    reader.read_exact(&mut bytes)?;
+    String::from_utf8(bytes).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+}
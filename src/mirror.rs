@@ -0,0 +1,219 @@
+use crate::{
+    math::{RotMat, Vec3},
+    sim::{BallHitInfo, BallState, CarControls, CarState, Team},
+    GameState,
+};
+
+#[derive(Clone, Copy, Debug)]
+/// A reflection of the X and/or Y axes
+struct Reflection {
+    x: f32,
+    y: f32,
+}
+
+impl Reflection {
+    #[inline]
+    const fn new(flip_x: bool, flip_y: bool) -> Self {
+        Self {
+            x: if flip_x { -1. } else { 1. },
+            y: if flip_y { -1. } else { 1. },
+        }
+    }
+
+    #[inline]
+    /// True if the reflection swaps left and right (a single axis is flipped)
+    fn is_handed(self) -> bool {
+        self.x * self.y < 0.
+    }
+
+    #[inline]
+    fn det(self) -> f32 {
+        self.x * self.y
+    }
+
+    #[inline]
+    fn vec(self, v: Vec3) -> Vec3 {
+        Vec3::new(v.x * self.x, v.y * self.y, v.z)
+    }
+
+    #[inline]
+    /// Angular velocity (and other pseudovectors) also flip sign when handedness changes
+    fn pseudo_vec(self, v: Vec3) -> Vec3 {
+        self.vec(v) * self.det()
+    }
+
+    #[inline]
+    /// The right axis gets flipped when handedness changes, so the result is still a rotation
+    fn rot_mat(self, mat: RotMat) -> RotMat {
+        RotMat::new(self.vec(mat.forward), self.vec(mat.right) * self.det(), self.vec(mat.up))
+    }
+
+    #[inline]
+    /// A pseudovector in local (forward, right, up) coordinates
+    fn local_pseudo_vec(self, v: Vec3) -> Vec3 {
+        Vec3::new(v.x * self.det(), v.y, v.z * self.det())
+    }
+
+    fn hit_info(self, info: BallHitInfo) -> BallHitInfo {
+        BallHitInfo {
+            relative_pos_on_ball: self.vec(info.relative_pos_on_ball),
+            ball_pos: self.vec(info.ball_pos),
+            extra_hit_vel: self.vec(info.extra_hit_vel),
+            ..info
+        }
+    }
+}
+
+impl Team {
+    #[inline]
+    #[must_use]
+    pub const fn opposite(self) -> Self {
+        match self {
+            Self::Blue => Self::Orange,
+            Self::Orange => Self::Blue,
+        }
+    }
+}
+
+impl CarControls {
+    #[inline]
+    #[must_use]
+    /// Swaps left and right, for use with a mirrored car
+    pub fn mirrored(self) -> Self {
+        Self {
+            steer: -self.steer,
+            yaw: -self.yaw,
+            roll: -self.roll,
+            ..self
+        }
+    }
+}
+
+impl BallState {
+    #[must_use]
+    /// Reflects the ball by negating the X and/or Y axes
+    pub fn mirrored(&self, flip_x: bool, flip_y: bool) -> Self {
+        let r = Reflection::new(flip_x, flip_y);
+        let mut hs_info = self.hs_info;
+        hs_info.y_target_dir *= r.y;
+
+        Self {
+            pos: r.vec(self.pos),
+            rot_mat: r.rot_mat(self.rot_mat),
+            vel: r.vec(self.vel),
+            ang_vel: r.pseudo_vec(self.ang_vel),
+            hs_info,
+            ..*self
+        }
+    }
+}
+
+impl CarState {
+    #[must_use]
+    /// Reflects the car by negating the X and/or Y axes
+    ///
+    /// If only one axis is flipped, left and right are swapped,
+    /// so steering, yaw, roll, in-progress flips, auto flips and the wheels in contact are mirrored too
+    pub fn mirrored(&self, flip_x: bool, flip_y: bool) -> Self {
+        let r = Reflection::new(flip_x, flip_y);
+
+        let mut world_contact = self.world_contact;
+        world_contact.contact_normal = r.vec(world_contact.contact_normal);
+
+        let [front_left, front_right, back_left, back_right] = self.wheels_with_contact;
+
+        Self {
+            pos: r.vec(self.pos),
+            rot_mat: r.rot_mat(self.rot_mat),
+            vel: r.vec(self.vel),
+            ang_vel: r.pseudo_vec(self.ang_vel),
+            flip_rel_torque: r.local_pseudo_vec(self.flip_rel_torque),
+            world_contact,
+            ball_hit_info: r.hit_info(self.ball_hit_info),
+            last_controls: if r.is_handed() {
+                self.last_controls.mirrored()
+            } else {
+                self.last_controls
+            },
+            wheels_with_contact: if r.is_handed() {
+                [front_right, front_left, back_right, back_left]
+            } else {
+                self.wheels_with_contact
+            },
+            auto_flip_torque_scale: if r.is_handed() {
+                -self.auto_flip_torque_scale
+            } else {
+                self.auto_flip_torque_scale
+            },
+            ..*self
+        }
+    }
+}
+
+impl GameState {
+    #[must_use]
+    /// Reflects the entire game state by negating the X and/or Y axes
+    ///
+    /// Teams are left as-is, see `normalized_for_team` for swapping sides.
+    /// Boost pad states are moved to the pad at the mirrored location, so the pad order is preserved.
+    pub fn mirrored(&self, flip_x: bool, flip_y: bool) -> Self {
+        let r = Reflection::new(flip_x, flip_y);
+
+        let pads = self
+            .pads
+            .iter()
+            .map(|pad| {
+                let target = r.vec(pad.config.position);
+                let source = self
+                    .pads
+                    .iter()
+                    .min_by(|a, b| {
+                        let dist_a = (a.config.position - target).length_squared();
+                        let dist_b = (b.config.position - target).length_squared();
+                        dist_a.total_cmp(&dist_b)
+                    })
+                    .unwrap_or(pad);
+
+                let mut pad = *pad;
+                pad.state = source.state;
+                pad
+            })
+            .collect();
+
+        Self {
+            tick_rate: self.tick_rate,
+            tick_count: self.tick_count,
+            game_mode: self.game_mode,
+            cars: self
+                .cars
+                .iter()
+                .map(|car| {
+                    let mut car = *car;
+                    car.state = car.state.mirrored(flip_x, flip_y);
+                    car
+                })
+                .collect(),
+            ball: self.ball.mirrored(flip_x, flip_y),
+            pads,
+        }
+    }
+
+    #[must_use]
+    /// Returns the game state from the perspective of the given team, as if it were blue
+    ///
+    /// For blue, this is an unchanged copy.
+    /// For orange, the field is rotated 180 degrees (both X and Y are flipped) and all teams are swapped,
+    /// so that the given team always defends the goal at negative Y.
+    pub fn normalized_for_team(&self, team: Team) -> Self {
+        match team {
+            Team::Blue => self.clone(),
+            Team::Orange => {
+                let mut state = self.mirrored(true, true);
+                for car in &mut state.cars {
+                    car.team = car.team.opposite();
+                }
+                state
+            }
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! Observation noise, for training and evaluating controllers that are robust to imperfect sensors
+//!
+//! Noise is only applied to the copies of the game state that are given to controllers,
+//! so the simulation itself stays exact.
+
+use crate::{
+    input::Controller,
+    math::{RotMat, Vec3},
+    sim::CarControls,
+    GameState,
+};
+use rand::Rng;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+/// How a single field of the game state is corrupted
+///
+/// Noise is applied first, then quantization, then dropout
+pub struct FieldNoise {
+    /// The standard deviation of the Gaussian noise added to each component
+    pub std_dev: f32,
+    /// The chance (`0..=1`) that the field isn't updated, so the last observed value is repeated instead
+    pub dropout: f32,
+    /// If not zero, each component is rounded to the nearest multiple of this
+    pub quantization: f32,
+}
+
+impl FieldNoise {
+    /// Leaves the field exact
+    pub const NONE: Self = Self {
+        std_dev: 0.,
+        dropout: 0.,
+        quantization: 0.,
+    };
+
+    #[inline]
+    #[must_use]
+    pub const fn gaussian(std_dev: f32) -> Self {
+        Self {
+            std_dev,
+            dropout: 0.,
+            quantization: 0.,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn with_dropout(mut self, dropout: f32) -> Self {
+        self.dropout = dropout;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn with_quantization(mut self, quantization: f32) -> Self {
+        self.quantization = quantization;
+        self
+    }
+
+    fn is_dropped<R: Rng + ?Sized>(&self, rng: &mut R) -> bool {
+        self.dropout > 0. && rng.gen_bool(f64::from(self.dropout.clamp(0., 1.)))
+    }
+
+    fn apply<R: Rng + ?Sized>(&self, rng: &mut R, value: f32) -> f32 {
+        let value = if self.std_dev > 0. {
+            value + gaussian(rng) * self.std_dev
+        } else {
+            value
+        };
+
+        if self.quantization > 0. {
+            (value / self.quantization).round() * self.quantization
+        } else {
+            value
+        }
+    }
+
+    fn apply_vec<R: Rng + ?Sized>(&self, rng: &mut R, vec: Vec3) -> Vec3 {
+        Vec3::new(self.apply(rng, vec.x), self.apply(rng, vec.y), self.apply(rng, vec.z))
+    }
+
+    /// Rotates the matrix by noise in each axis, with `std_dev` being roughly in radians
+    ///
+    /// Quantization isn't applied to rotations
+    fn apply_rot_mat<R: Rng + ?Sized>(&self, rng: &mut R, rot_mat: RotMat) -> RotMat {
+        let noise = Self::gaussian(self.std_dev);
+        RotMat::look_at(
+            noise.apply_vec(rng, rot_mat.forward).normalize_or_zero(),
+            noise.apply_vec(rng, rot_mat.up).normalize_or_zero(),
+        )
+    }
+}
+
+/// A sample from the standard normal distribution, using the Box-Muller transform
+fn gaussian<R: Rng + ?Sized>(rng: &mut R) -> f32 {
+    let u1 = rng.gen_range(f32::EPSILON..1.);
+    let u2 = rng.gen::<f32>();
+    (-2. * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+/// The noise for each field of the game state, everything is exact by default
+pub struct NoiseConfig {
+    pub ball_pos: FieldNoise,
+    pub ball_vel: FieldNoise,
+    pub ball_ang_vel: FieldNoise,
+    pub car_pos: FieldNoise,
+    pub car_rot: FieldNoise,
+    pub car_vel: FieldNoise,
+    pub car_ang_vel: FieldNoise,
+    pub car_boost: FieldNoise,
+}
+
+/// Produces noisy copies of game states
+///
+/// Dropped fields repeat the last value that was observed through this, so each controller should have its own
+pub struct ObservationNoise<R: Rng> {
+    pub config: NoiseConfig,
+    rng: R,
+    last: Option<GameState>,
+}
+
+impl<R: Rng> ObservationNoise<R> {
+    #[inline]
+    #[must_use]
+    pub const fn new(config: NoiseConfig, rng: R) -> Self {
+        Self { config, rng, last: None }
+    }
+
+    /// Forgets the last observation, e.g. when the episode is reset
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+
+    /// Returns a copy of the game state with the configured noise applied
+    pub fn observe(&mut self, game_state: &GameState) -> GameState {
+        let config = &self.config;
+        let rng = &mut self.rng;
+        let last = self.last.as_ref();
+        let mut observed = game_state.clone();
+
+        let vec = |rng: &mut R, noise: &FieldNoise, value: Vec3, last: Option<Vec3>| match last {
+            Some(last) if noise.is_dropped(rng) => last,
+            _ => noise.apply_vec(rng, value),
+        };
+
+        let ball = &mut observed.ball;
+        ball.pos = vec(rng, &config.ball_pos, ball.pos, last.map(|last| last.ball.pos));
+        ball.vel = vec(rng, &config.ball_vel, ball.vel, last.map(|last| last.ball.vel));
+        ball.ang_vel = vec(rng, &config.ball_ang_vel, ball.ang_vel, last.map(|last| last.ball.ang_vel));
+
+        for car in &mut observed.cars {
+            let last_car = last.and_then(|last| last.cars.iter().find(|last_car| last_car.id == car.id));
+            let state = &mut car.state;
+
+            state.pos = vec(rng, &config.car_pos, state.pos, last_car.map(|last| last.state.pos));
+            state.vel = vec(rng, &config.car_vel, state.vel, last_car.map(|last| last.state.vel));
+            state.ang_vel = vec(
+                rng,
+                &config.car_ang_vel,
+                state.ang_vel,
+                last_car.map(|last| last.state.ang_vel),
+            );
+
+            state.rot_mat = match last_car {
+                Some(last) if config.car_rot.is_dropped(rng) => last.state.rot_mat,
+                _ if config.car_rot.std_dev > 0. => config.car_rot.apply_rot_mat(rng, state.rot_mat),
+                _ => state.rot_mat,
+            };
+
+            state.boost = match last_car {
+                Some(last) if config.car_boost.is_dropped(rng) => last.state.boost,
+                _ => config.car_boost.apply(rng, state.boost),
+            };
+        }
+
+        self.last = Some(observed.clone());
+        observed
+    }
+}
+
+/// Wraps a controller so that it only sees noisy observations
+pub struct NoisyController<C: Controller, R: Rng> {
+    pub controller: C,
+    pub noise: ObservationNoise<R>,
+}
+
+impl<C: Controller, R: Rng> NoisyController<C, R> {
+    #[inline]
+    #[must_use]
+    pub const fn new(controller: C, config: NoiseConfig, rng: R) -> Self {
+        Self {
+            controller,
+            noise: ObservationNoise::new(config, rng),
+        }
+    }
+}
+
+impl<C: Controller, R: Rng> Controller for NoisyController<C, R> {
+    #[inline]
+    fn controls(&mut self, car_id: u32, game_state: &GameState) -> CarControls {
+        let observed = self.noise.observe(game_state);
+        self.controller.controls(car_id, &observed)
+    }
+
+    #[inline]
+    fn quit_requested(&self) -> bool {
+        self.controller.quit_requested()
+    }
+}
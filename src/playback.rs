@@ -0,0 +1,241 @@
+//! Watching recorded replays in rlviser, without re-running the simulation
+//!
+//! The game states are streamed in real time (or a multiple of it),
+//! and rlviser (or the code driving the player) can pause, single-step, change the speed and seek.
+//!
+//! ```no_run
+//! rocketsim_rs::playback::play("game.rsrp").unwrap();
+//! ```
+
+use crate::{
+    playtest::{RLVISER_PORT, ROCKETSIM_PORT},
+    protocol::{SessionCommand, SessionControl, UdpPacketTypes},
+    replay::IndexedReplay,
+    GameState,
+};
+use std::{
+    io,
+    net::{SocketAddr, UdpSocket},
+    path::Path,
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+/// Streams a replay to rlviser
+pub struct ReplayPlayer<B: AsRef<[u8]>> {
+    replay: IndexedReplay<B>,
+    socket: UdpSocket,
+    rlviser_addr: SocketAddr,
+    control: SessionControl,
+    /// The playback position, in (fractional) ticks
+    position: f64,
+    /// The index of the game state that was last sent, `None` if nothing has been sent yet
+    sent_index: Option<usize>,
+    /// The pause state that rlviser was last told about
+    sent_paused: bool,
+    last_update: Instant,
+    buffer: Vec<u8>,
+}
+
+impl<B: AsRef<[u8]>> ReplayPlayer<B> {
+    /// Connects to rlviser on the default ports
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket can't be bound, or if rlviser can't be sent to
+    pub fn new(replay: IndexedReplay<B>) -> io::Result<Self> {
+        Self::with_ports(replay, ROCKETSIM_PORT, RLVISER_PORT)
+    }
+
+    /// Connects to rlviser on localhost, with the ports that were passed to rlviser
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket can't be bound, or if rlviser can't be sent to
+    pub fn with_ports(replay: IndexedReplay<B>, rocketsim_port: u16, rlviser_port: u16) -> io::Result<Self> {
+        let socket = UdpSocket::bind(("0.0.0.0", rocketsim_port))?;
+        socket.set_nonblocking(true)?;
+
+        let rlviser_addr = SocketAddr::from(([127, 0, 0, 1], rlviser_port));
+        socket.send_to(&[UdpPacketTypes::Connection as u8], rlviser_addr)?;
+
+        Ok(Self {
+            position: replay.ticks().first().map_or(0., |&tick| tick as f64),
+            replay,
+            socket,
+            rlviser_addr,
+            control: SessionControl::new(),
+            sent_index: None,
+            sent_paused: false,
+            last_update: Instant::now(),
+            buffer: Vec::new(),
+        })
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn replay(&self) -> &IndexedReplay<B> {
+        &self.replay
+    }
+
+    #[inline]
+    #[must_use]
+    /// The pause state, speed and pending steps of the playback
+    pub const fn control(&self) -> &SessionControl {
+        &self.control
+    }
+
+    /// Applies a command, the same way as if rlviser had sent it
+    pub fn apply(&mut self, command: SessionCommand) {
+        self.control.apply(command);
+    }
+
+    #[inline]
+    #[must_use]
+    /// The index of the game state at the playback position
+    pub fn index(&self) -> usize {
+        self.replay.index_at_tick(self.position as u64).unwrap_or_default()
+    }
+
+    #[inline]
+    #[must_use]
+    /// The game state at the playback position
+    pub fn state(&self) -> Option<GameState> {
+        self.replay.state(self.index())
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns true if the playback position is at the last game state
+    pub fn is_at_end(&self) -> bool {
+        self.index() + 1 >= self.replay.len()
+    }
+
+    /// The tick rate of the game state at the playback position, for pacing
+    fn tick_rate(&self) -> f64 {
+        self.replay
+            .bytes_at(self.index())
+            .map_or(120., |bytes| f64::from(GameState::read_tick_rate(bytes)))
+    }
+
+    /// Handles the pending packets from rlviser
+    fn handle_packets(&mut self) {
+        let mut packet_type = [0];
+
+        while let Ok((_, src)) = self.socket.recv_from(&mut packet_type) {
+            if src != self.rlviser_addr {
+                continue;
+            }
+
+            let Ok(packet_type) = UdpPacketTypes::try_from(packet_type[0]) else {
+                continue;
+            };
+
+            match SessionCommand::payload_num_bytes(packet_type) {
+                Some(num_bytes) => {
+                    let mut payload = [0; 8];
+                    if self.socket.recv_from(&mut payload[..num_bytes]).is_ok() {
+                        self.control.handle_packet(packet_type, &payload[..num_bytes]);
+                    }
+                }
+                None if matches!(packet_type, UdpPacketTypes::GameState | UdpPacketTypes::AuthGameState) => {
+                    // replays can't be changed, so just discard the game state
+                    self.buffer.resize(u16::MAX as usize, 0);
+                    let _ = self.socket.recv_from(&mut self.buffer);
+                }
+                None => {}
+            }
+        }
+    }
+
+    /// Handles packets and commands, advances the playback position by the time since the last update,
+    /// and sends the game state at the new position to rlviser if it changed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if rlviser can't be sent to
+    pub fn update(&mut self) -> io::Result<()> {
+        self.handle_packets();
+
+        let now = Instant::now();
+        let elapsed = (now - self.last_update).as_secs_f64();
+        self.last_update = now;
+
+        if let Some(tick_count) = self.control.take_seek() {
+            self.position = tick_count as f64;
+        }
+
+        if self.control.is_paused() {
+            self.position += f64::from(self.control.take_pending_steps());
+        } else {
+            self.position += elapsed * self.tick_rate() * f64::from(self.control.speed());
+        }
+
+        // don't run off either end of the replay
+        if let (Some(&first), Some(&last)) = (self.replay.ticks().first(), self.replay.ticks().last()) {
+            self.position = self.position.clamp(first as f64, last as f64);
+        }
+
+        if self.control.is_paused() != self.sent_paused {
+            self.sent_paused = self.control.is_paused();
+            let command = SessionCommand::paused(self.sent_paused);
+            self.socket.send_to(&[command.packet_type() as u8], self.rlviser_addr)?;
+            self.socket.send_to(&command.payload(), self.rlviser_addr)?;
+        }
+
+        let index = self.index();
+        if self.sent_index != Some(index) {
+            if let Some(bytes) = self.replay.bytes_at(index) {
+                self.socket.send_to(&[UdpPacketTypes::GameState as u8], self.rlviser_addr)?;
+                self.socket.send_to(bytes, self.rlviser_addr)?;
+                self.sent_index = Some(index);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Plays the replay until the end is reached while not paused
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if rlviser can't be sent to
+    pub fn run(&mut self) -> io::Result<()> {
+        self.run_until(|player| player.is_at_end() && !player.control.is_paused())
+    }
+
+    /// Plays the replay until `should_stop` returns true, which is checked after every update
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if rlviser can't be sent to
+    pub fn run_until(&mut self, mut should_stop: impl FnMut(&Self) -> bool) -> io::Result<()> {
+        self.last_update = Instant::now();
+
+        loop {
+            self.update()?;
+            if should_stop(self) {
+                break;
+            }
+
+            sleep(Duration::from_secs_f64(1. / self.tick_rate()));
+        }
+
+        self.socket.send_to(&[UdpPacketTypes::Quit as u8], self.rlviser_addr)?;
+        Ok(())
+    }
+}
+
+/// Opens the replay at the given path (memory-mapped with the `mmap` feature), and plays it in rlviser
+///
+/// # Errors
+///
+/// Returns an error if the replay can't be read, or rlviser can't be connected to
+pub fn play<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    #[cfg(feature = "mmap")]
+    let replay = IndexedReplay::open(path)?;
+    #[cfg(not(feature = "mmap"))]
+    let replay = IndexedReplay::new(std::fs::read(path)?)?;
+
+    ReplayPlayer::new(replay)?.run()
+}
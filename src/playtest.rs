@@ -0,0 +1,233 @@
+//! Play against bots in real time, watching the match in rlviser
+//!
+//! This combines real-time stepping, a human input `Controller` (like `input::KeyboardController`),
+//! and the rlviser connection, so that a playable match is one call:
+//!
+//! ```no_run
+//! use rocketsim_rs::{input::Controller, playtest, sim::CarControls, GameState};
+//!
+//! rocketsim_rs::init(None, true);
+//!
+//! let human = |_: u32, _: &GameState| CarControls::default();
+//! let bot: Box<dyn Controller> = Box::new(|_: u32, _: &GameState| CarControls {
+//!     throttle: 1.,
+//!     ..Default::default()
+//! });
+//!
+//! playtest::play(human, [bot]).unwrap();
+//! ```
+//!
+//! rlviser can pause, single-step and change the speed of the match, but isn't allowed to set the game state.
+
+use crate::{
+    bytes::ToBytes,
+    cxx::UniquePtr,
+    input::Controller,
+    protocol::{SessionCommand, SessionControl, StateSetPolicy, UdpPacketTypes},
+    realtime::RealTimeStepper,
+    sim::{Arena, CarConfig, Team},
+    GameState,
+};
+use std::{
+    io,
+    net::{SocketAddr, UdpSocket},
+    pin::Pin,
+};
+
+/// The port that rlviser listens on by default
+pub const RLVISER_PORT: u16 = 45243;
+/// The port that rlviser sends to by default
+pub const ROCKETSIM_PORT: u16 = 34254;
+
+/// A real-time match where every car (with a controller) is driven by a `Controller`, streamed to rlviser
+pub struct Session {
+    arena: UniquePtr<Arena>,
+    controllers: Vec<(u32, Box<dyn Controller>)>,
+    socket: UdpSocket,
+    rlviser_addr: SocketAddr,
+    control: SessionControl,
+    stepper: RealTimeStepper,
+    buffer: Vec<u8>,
+    /// The pause state of the arena that rlviser was last told about
+    arena_paused: bool,
+}
+
+impl Session {
+    /// Connects to rlviser on the default ports
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket can't be bound, or if rlviser can't be sent to
+    pub fn new(arena: UniquePtr<Arena>) -> io::Result<Self> {
+        Self::with_ports(arena, ROCKETSIM_PORT, RLVISER_PORT)
+    }
+
+    /// Connects to rlviser on localhost, with the ports that were passed to rlviser
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket can't be bound, or if rlviser can't be sent to
+    pub fn with_ports(arena: UniquePtr<Arena>, rocketsim_port: u16, rlviser_port: u16) -> io::Result<Self> {
+        let socket = UdpSocket::bind(("0.0.0.0", rocketsim_port))?;
+        socket.set_nonblocking(true)?;
+
+        let rlviser_addr = SocketAddr::from(([127, 0, 0, 1], rlviser_port));
+        socket.send_to(&[UdpPacketTypes::Connection as u8], rlviser_addr)?;
+        socket.send_to(&[UdpPacketTypes::StateSetPermission as u8], rlviser_addr)?;
+        socket.send_to(&StateSetPolicy::Deny.permission_packet(), rlviser_addr)?;
+
+        Ok(Self {
+            stepper: RealTimeStepper::from_arena(&arena),
+            arena,
+            controllers: Vec::new(),
+            socket,
+            rlviser_addr,
+            control: SessionControl::new(),
+            buffer: Vec::new(),
+            arena_paused: false,
+        })
+    }
+
+    /// Creates a soccar arena with an Octane for the human on blue, and an Octane for each bot on orange
+    ///
+    /// Goals reset the arena to a random kickoff
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if rlviser can't be connected to, see `new`
+    pub fn human_vs_bots(
+        human: impl Controller + 'static,
+        bots: impl IntoIterator<Item = Box<dyn Controller>>,
+    ) -> io::Result<Self> {
+        let mut arena = Arena::default_standard();
+        arena.pin_mut().set_goal_scored_callback(
+            |arena, _, _| {
+                arena.reset_to_random_kickoff(None);
+            },
+            0,
+        );
+
+        let human_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+        let bots = bots
+            .into_iter()
+            .map(|bot| (arena.pin_mut().add_car(Team::Orange, CarConfig::octane()), bot))
+            .collect::<Vec<_>>();
+
+        arena.pin_mut().reset_to_random_kickoff(None);
+
+        let mut session = Self::new(arena)?;
+        session.add_controller(human_id, human);
+        session.controllers.extend(bots);
+
+        Ok(session)
+    }
+
+    /// Drives the car with the given ID with the controller, replacing its previous controller
+    pub fn add_controller(&mut self, car_id: u32, controller: impl Controller + 'static) {
+        self.controllers.retain(|(id, _)| *id != car_id);
+        self.controllers.push((car_id, Box::new(controller)));
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn arena(&self) -> &UniquePtr<Arena> {
+        &self.arena
+    }
+
+    #[inline]
+    pub fn arena_mut(&mut self) -> Pin<&mut Arena> {
+        self.arena.pin_mut()
+    }
+
+    /// Handles the pending packets from rlviser
+    fn handle_packets(&mut self) {
+        let mut packet_type = [0];
+
+        while let Ok((_, src)) = self.socket.recv_from(&mut packet_type) {
+            if src != self.rlviser_addr {
+                continue;
+            }
+
+            let Ok(packet_type) = UdpPacketTypes::try_from(packet_type[0]) else {
+                continue;
+            };
+
+            match SessionCommand::payload_num_bytes(packet_type) {
+                Some(num_bytes) => {
+                    let mut payload = [0; 8];
+                    if self.socket.recv_from(&mut payload[..num_bytes]).is_ok() {
+                        self.control.handle_packet(packet_type, &payload[..num_bytes]);
+                    }
+                }
+                None if matches!(packet_type, UdpPacketTypes::GameState | UdpPacketTypes::AuthGameState) => {
+                    // state setting isn't allowed, so just discard the game state
+                    self.buffer.resize(u16::MAX as usize, 0);
+                    let _ = self.socket.recv_from(&mut self.buffer);
+                }
+                None => {}
+            }
+        }
+    }
+
+    /// Runs the match in real time until any of the controllers requests to quit
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game state can't be sent to rlviser
+    pub fn run(&mut self) -> io::Result<()> {
+        self.run_until(|_| false)
+    }
+
+    /// Runs the match in real time until `should_stop` returns true for the latest game state,
+    /// or any of the controllers requests to quit
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game state can't be sent to rlviser
+    pub fn run_until(&mut self, mut should_stop: impl FnMut(&GameState) -> bool) -> io::Result<()> {
+        self.stepper.reset();
+        let mut game_state = self.arena.pin_mut().get_game_state();
+
+        while !should_stop(&game_state) && !self.controllers.iter().any(|(_, controller)| controller.quit_requested()) {
+            self.handle_packets();
+
+            let controls = self
+                .controllers
+                .iter_mut()
+                .map(|(car_id, controller)| (*car_id, controller.controls(*car_id, &game_state)))
+                .collect::<Vec<_>>();
+
+            // cars can be removed while running, so missing cars are skipped
+            for (car_id, car_controls) in controls {
+                let _ = self.arena.pin_mut().set_car_controls(car_id, car_controls);
+            }
+
+            if self.arena.is_paused() != self.arena_paused {
+                self.arena_paused = self.arena.is_paused();
+                let command = SessionCommand::paused(self.arena_paused);
+                self.socket.send_to(&[command.packet_type() as u8], self.rlviser_addr)?;
+                self.socket.send_to(&command.payload(), self.rlviser_addr)?;
+            }
+
+            if self.control.step(&mut self.stepper, self.arena.pin_mut()) == 0 {
+                continue;
+            }
+
+            game_state = self.arena.pin_mut().get_game_state();
+            self.socket.send_to(&[UdpPacketTypes::GameState as u8], self.rlviser_addr)?;
+            self.socket.send_to(&game_state.to_bytes(), self.rlviser_addr)?;
+        }
+
+        self.socket.send_to(&[UdpPacketTypes::Quit as u8], self.rlviser_addr)?;
+        Ok(())
+    }
+}
+
+/// Starts a match of the human against the bots, and runs it until the human quits, see `Session::human_vs_bots`
+///
+/// # Errors
+///
+/// Returns an error if rlviser can't be connected to
+pub fn play(human: impl Controller + 'static, bots: impl IntoIterator<Item = Box<dyn Controller>>) -> io::Result<()> {
+    Session::human_vs_bots(human, bots)?.run()
+}
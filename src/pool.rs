@@ -0,0 +1,807 @@
+//! Stepping a batch of arenas in parallel, for training on machines with many cores
+//!
+//! Every thread of the pool is started once and lives as long as the pool, and every arena belongs to one thread
+//! for as long as it's in the pool, so an arena's memory stays in the caches
+//! (and with the `affinity` feature, the NUMA node) of the core stepping it.
+//! `ArenaPool::thread_stats` measures the ticks per second of every thread, to check that a placement actually helps.
+//!
+//! Arenas that are waiting on something (like a finished episode, or a policy that hasn't answered yet)
+//! can be suspended with `ArenaPool::suspend`, so they're skipped until they wake up.
+//!
+//! Inference threads can send controls to an arena through its mailbox from `ArenaPool::sender`,
+//! without waiting for (or locking) the pool. The latest controls are applied at the start of every step.
+//! Or, with an `InferenceHook`, the pool gathers the observations of every arena into one batch before each step,
+//! so a single forward pass of a policy can control all of them.
+
+use crate::{
+    sim::{Arena, CarControls},
+    GameState,
+};
+use core::pin::Pin;
+use cxx::UniquePtr;
+use std::{
+    error::Error,
+    fmt, mem,
+    ops::Range,
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Arc,
+    },
+    thread::{self, available_parallelism, JoinHandle},
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "affinity")]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+/// Where the threads of a pool run
+pub enum Placement {
+    #[default]
+    /// Let the OS move the threads between cores
+    Unpinned,
+    /// Pin every thread to its own core, in order
+    Cores,
+    /// Pin the threads to these cores, in order, and repeated if there are more threads than cores
+    CoreList(Vec<usize>),
+    /// Spread the threads evenly over the NUMA nodes, pinning every thread to a core of its node
+    ///
+    /// Arenas that are created with `ArenaPool::add_arenas` are allocated on the node that steps them.
+    /// Falls back to `Cores` if the NUMA topology isn't known.
+    Numa,
+}
+
+#[cfg(feature = "affinity")]
+#[must_use]
+/// The ids of the cores of every NUMA node, from `/sys/devices/system/node`
+///
+/// Empty if the topology isn't known, like on platforms other than Linux
+pub fn numa_nodes() -> Vec<Vec<usize>> {
+    fn parse_cpu_list(list: &str) -> Option<Vec<usize>> {
+        let mut cores = Vec::new();
+
+        for part in list.trim().split(',').filter(|part| !part.is_empty()) {
+            match part.split_once('-') {
+                Some((start, end)) => cores.extend(start.parse::<usize>().ok()?..=end.parse().ok()?),
+                None => cores.push(part.parse().ok()?),
+            }
+        }
+
+        Some(cores)
+    }
+
+    let Ok(entries) = std::fs::read_dir("/sys/devices/system/node") else {
+        return Vec::new();
+    };
+
+    let mut nodes = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let node = entry.file_name().to_str()?.strip_prefix("node")?.parse::<usize>().ok()?;
+            let cores = parse_cpu_list(&std::fs::read_to_string(entry.path().join("cpulist")).ok()?)?;
+
+            (!cores.is_empty()).then_some((node, cores))
+        })
+        .collect::<Vec<_>>();
+
+    nodes.sort_unstable_by_key(|(node, _)| *node);
+    nodes.into_iter().map(|(_, cores)| cores).collect()
+}
+
+#[cfg(feature = "affinity")]
+impl Placement {
+    /// The core (and NUMA node) of every thread, `None` for threads that aren't pinned
+    fn cores(&self, num_threads: usize) -> Vec<(Option<usize>, Option<usize>)> {
+        let all_cores = || {
+            core_affinity::get_core_ids()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|core| core.id)
+                .collect::<Vec<_>>()
+        };
+        let repeat = |cores: &[usize]| {
+            (0..num_threads)
+                .map(|i| (cores.get(i % cores.len().max(1)).copied(), None))
+                .collect()
+        };
+
+        match self {
+            Self::Unpinned => vec![(None, None); num_threads],
+            Self::Cores => repeat(&all_cores()),
+            Self::CoreList(cores) => repeat(cores),
+            Self::Numa => {
+                let nodes = numa_nodes();
+                if nodes.is_empty() {
+                    return repeat(&all_cores());
+                }
+
+                // thread i goes to node i % n, and takes the next core of that node
+                (0..num_threads)
+                    .map(|i| {
+                        let node = i % nodes.len();
+                        let cores = &nodes[node];
+                        (Some(cores[(i / nodes.len()) % cores.len()]), Some(node))
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+/// What a thread of a pool has done since the stats were last reset
+pub struct ThreadStats {
+    /// The core that the thread is pinned to
+    pub core: Option<usize>,
+    /// The NUMA node of the core, with `Placement::Numa`
+    pub numa_node: Option<usize>,
+    pub num_arenas: usize,
+    /// The ticks of all of the thread's arenas combined, not counting suspended arenas
+    pub ticks: u64,
+    /// The time the thread spent stepping its arenas
+    pub busy: Duration,
+}
+
+impl ThreadStats {
+    #[must_use]
+    /// Arena ticks per second of the time spent stepping, 0 if the thread hasn't stepped yet
+    pub fn ticks_per_second(&self) -> f64 {
+        let busy = self.busy.as_secs_f64();
+        if busy > 0. {
+            self.ticks as f64 / busy
+        } else {
+            0.
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// When a suspended arena wakes up by itself, it can always be woken up with `ArenaPool::wake`
+pub enum WakeCondition {
+    /// Only when it's woken up
+    Manual,
+    /// At the first step of the pool at or after the instant
+    At(Instant),
+    /// After the pool has stepped this many more ticks
+    Ticks(u64),
+    /// When controls are sent to its mailbox
+    Controls,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The pool that owned the arena of a mailbox was dropped
+pub struct PoolDropped;
+
+impl Error for PoolDropped {}
+impl fmt::Display for PoolDropped {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The pool of the arena was dropped.")
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Sends controls to the mailbox of an arena in a pool, from any thread
+pub struct ActionSender {
+    arena_id: usize,
+    sender: Sender<Vec<(u32, CarControls)>>,
+}
+
+impl ActionSender {
+    #[inline]
+    #[must_use]
+    pub const fn arena_id(&self) -> usize {
+        self.arena_id
+    }
+
+    /// Sends controls for cars of the arena, by car id
+    ///
+    /// They're applied at the start of the arena's next step, and cars keep their controls until they're sent new ones.
+    /// If several sets of controls arrive between two steps, the latest controls of every car are used.
+    /// Controls for cars that aren't in the arena (anymore) are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pool was dropped
+    pub fn send(&self, controls: Vec<(u32, CarControls)>) -> Result<(), PoolDropped> {
+        self.sender.send(controls).map_err(|_| PoolDropped)
+    }
+}
+
+/// Writes the observation of the arena with the id into the slice, which is `obs_len` long
+pub type ObserveFn = Arc<dyn Fn(usize, Pin<&mut Arena>, &mut [f32]) + Send + Sync>;
+/// Gets the controls of every arena from their stacked observations and the number of arenas
+pub type InferFn = Box<dyn FnMut(&[f32], usize) -> Vec<Vec<(u32, CarControls)>> + Send>;
+
+/// Batched inference for all of the arenas of a pool, once per step
+///
+/// Before each step, `observe` is called for every arena that isn't suspended (on the arena's thread),
+/// and the observations are stacked in the order of the arena ids into a single `n_envs * obs_len` slice.
+/// `infer` is then called once with that slice, and returns the controls for each of those arenas, in the same order.
+/// The controls are applied after the ones from the mailboxes.
+pub struct InferenceHook {
+    pub obs_len: usize,
+    pub observe: ObserveFn,
+    pub infer: InferFn,
+}
+
+impl fmt::Debug for InferenceHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InferenceHook")
+            .field("obs_len", &self.obs_len)
+            .finish_non_exhaustive()
+    }
+}
+
+impl InferenceHook {
+    #[inline]
+    #[must_use]
+    pub fn new<O, I>(obs_len: usize, observe: O, infer: I) -> Self
+    where
+        O: Fn(usize, Pin<&mut Arena>, &mut [f32]) + Send + Sync + 'static,
+        I: FnMut(&[f32], usize) -> Vec<Vec<(u32, CarControls)>> + Send + 'static,
+    {
+        Self {
+            obs_len,
+            observe: Arc::new(observe),
+            infer: Box::new(infer),
+        }
+    }
+}
+
+struct PoolArena {
+    id: usize,
+    arena: UniquePtr<Arena>,
+    /// The condition and the ticks of the pool when the arena was suspended
+    idle: Option<(WakeCondition, u64)>,
+    mailbox: Receiver<Vec<(u32, CarControls)>>,
+    /// Controls that were taken out of the mailbox to wake the arena up, but not applied yet
+    pending: Vec<(u32, CarControls)>,
+    /// Controls from the inference hook for the next step
+    inferred: Vec<(u32, CarControls)>,
+}
+
+impl PoolArena {
+    fn should_wake(&mut self, now: Instant, pool_ticks: u64) -> bool {
+        match self.idle {
+            None | Some((WakeCondition::Manual, _)) => false,
+            Some((WakeCondition::At(instant), _)) => now >= instant,
+            Some((WakeCondition::Ticks(ticks), suspended_at)) => pool_ticks >= suspended_at.saturating_add(ticks),
+            Some((WakeCondition::Controls, _)) => match self.mailbox.try_recv() {
+                Ok(controls) => {
+                    self.pending = controls;
+                    true
+                }
+                Err(_) => false,
+            },
+        }
+    }
+
+    fn apply_controls(&mut self) {
+        let mut arena = self.arena.pin_mut();
+
+        for (car_id, controls) in self
+            .pending
+            .drain(..)
+            .chain(self.mailbox.try_iter().flatten())
+            .chain(self.inferred.drain(..))
+        {
+            // the car may have been removed since the controls were sent
+            let _ = arena.as_mut().set_car_controls(car_id, controls);
+        }
+    }
+}
+
+#[derive(Default)]
+struct Shard {
+    /// The arenas of the thread, in the order they were added
+    arenas: Vec<PoolArena>,
+    num_idle: usize,
+    stats: ThreadStats,
+    /// The observations of the arenas that were observed by the last inference, in order
+    observations: Vec<f32>,
+    /// The arenas that were created by the thread, to be added to the pool
+    created: Vec<(usize, UniquePtr<Arena>)>,
+}
+
+/// Work for a thread of the pool, which gets the thread's shard while it runs
+type Task = Box<dyn FnOnce(&mut Shard) + Send>;
+
+const WORKER_PANICKED: &str = "A thread of the pool panicked";
+
+/// A thread of the pool, which is given its shard together with every task and gives it back when it's done
+struct Worker {
+    tasks: Option<Sender<(Shard, Task)>>,
+    done: Receiver<Shard>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Worker {
+    fn spawn(index: usize) -> Self {
+        let (tasks, task_receiver) = channel::<(Shard, Task)>();
+        let (done_sender, done) = channel();
+
+        let handle = thread::Builder::new()
+            .name(format!("arena-pool-{index}"))
+            .spawn(move || {
+                for (mut shard, task) in task_receiver {
+                    task(&mut shard);
+                    if done_sender.send(shard).is_err() {
+                        break;
+                    }
+                }
+            })
+            .expect("Failed to start a thread of the pool");
+
+        Self {
+            tasks: Some(tasks),
+            done,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        // closing the channel stops the thread
+        self.tasks = None;
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(feature = "affinity")]
+/// Pins the current thread to the core, if there is one
+fn pin_to(core: Option<usize>) {
+    if let Some(id) = core {
+        core_affinity::set_for_current(core_affinity::CoreId { id });
+    }
+}
+
+impl Shard {
+    fn num_active(&self) -> usize {
+        self.arenas.len() - self.num_idle
+    }
+
+    fn wake_due(&mut self, now: Instant, pool_ticks: u64) {
+        if self.num_idle == 0 {
+            return;
+        }
+
+        for arena in &mut self.arenas {
+            if arena.should_wake(now, pool_ticks) {
+                arena.idle = None;
+                self.num_idle -= 1;
+            }
+        }
+    }
+
+    fn step(&mut self, ticks: u32) {
+        let start = Instant::now();
+
+        for arena in self.arenas.iter_mut().filter(|arena| arena.idle.is_none()) {
+            arena.apply_controls();
+            arena.arena.pin_mut().step(ticks);
+        }
+
+        self.stats.busy += start.elapsed();
+        self.stats.ticks += u64::from(ticks) * self.num_active() as u64;
+    }
+}
+
+/// A batch of arenas that are stepped in parallel, each one always by the same thread
+///
+/// Arenas are identified by the order they were added in, starting at 0.
+pub struct ArenaPool {
+    /// The shard of every thread, which is only away while the thread is running a task
+    shards: Vec<Shard>,
+    workers: Vec<Worker>,
+    /// The shard and the index in the shard of every arena, by id
+    ids: Vec<(usize, usize)>,
+    /// The senders to the mailbox of every arena, by id
+    senders: Vec<Sender<Vec<(u32, CarControls)>>>,
+    inference: Option<InferenceHook>,
+    /// The stacked observations, kept between steps to reuse the allocation
+    observations: Vec<f32>,
+    ticks: u64,
+}
+
+impl fmt::Debug for ArenaPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArenaPool")
+            .field("num_arenas", &self.ids.len())
+            .field("num_idle", &self.num_idle())
+            .field("ticks", &self.ticks)
+            .field("inference", &self.inference)
+            .field("threads", &self.thread_stats())
+            .finish()
+    }
+}
+
+impl Default for ArenaPool {
+    #[inline]
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl ArenaPool {
+    #[must_use]
+    /// An empty pool with the number of threads, or all of the available threads if it's 0
+    pub fn new(num_threads: usize) -> Self {
+        let num_threads = match num_threads {
+            0 => available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        };
+
+        Self {
+            shards: (0..num_threads).map(|_| Shard::default()).collect(),
+            workers: (0..num_threads).map(Worker::spawn).collect(),
+            ids: Vec::new(),
+            senders: Vec::new(),
+            inference: None,
+            observations: Vec::new(),
+            ticks: 0,
+        }
+    }
+
+    #[cfg(feature = "affinity")]
+    #[must_use]
+    /// Places the threads of the pool, which should be done before any arenas are added
+    ///
+    /// Every thread is pinned once, here, and keeps running on its core from then on.
+    pub fn with_placement(mut self, placement: &Placement) -> Self {
+        let cores = placement.cores(self.shards.len());
+        let tasks = self
+            .shards
+            .iter_mut()
+            .zip(cores)
+            .map(|(shard, (core, numa_node))| {
+                shard.stats.core = core;
+                shard.stats.numa_node = numa_node;
+                Some(Box::new(move |_: &mut Shard| pin_to(core)) as Task)
+            })
+            .collect();
+
+        self.run(tasks);
+        self
+    }
+
+    /// Runs the tasks on the threads of their shards in parallel, and waits for all of them to finish
+    fn run(&mut self, tasks: Vec<Option<Task>>) {
+        let mut running = Vec::with_capacity(tasks.len());
+
+        for (i, task) in tasks.into_iter().enumerate() {
+            let Some(task) = task else {
+                continue;
+            };
+
+            let shard = mem::take(&mut self.shards[i]);
+            let sender = self.workers[i].tasks.as_ref().expect(WORKER_PANICKED);
+            sender.send((shard, task)).expect(WORKER_PANICKED);
+            running.push(i);
+        }
+
+        for i in running {
+            self.shards[i] = self.workers[i].done.recv().expect(WORKER_PANICKED);
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn num_threads(&self) -> usize {
+        self.shards.len()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    #[inline]
+    #[must_use]
+    /// The ticks that the pool has been stepped by
+    pub const fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    fn least_loaded_shard(&self) -> usize {
+        self.shards
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, shard)| shard.arenas.len())
+            .map_or(0, |(i, _)| i)
+    }
+
+    fn insert(&mut self, shard: usize, arena: UniquePtr<Arena>) -> usize {
+        let id = self.ids.len();
+        let arenas = &mut self.shards[shard].arenas;
+
+        self.ids.push((shard, arenas.len()));
+        let (sender, mailbox) = channel();
+        self.senders.push(sender);
+        arenas.push(PoolArena {
+            id,
+            arena,
+            idle: None,
+            mailbox,
+            pending: Vec::new(),
+            inferred: Vec::new(),
+        });
+        self.shards[shard].stats.num_arenas += 1;
+
+        id
+    }
+
+    /// Adds the arena to the thread with the fewest arenas, and returns its id
+    pub fn push(&mut self, arena: UniquePtr<Arena>) -> usize {
+        let shard = self.least_loaded_shard();
+        self.insert(shard, arena)
+    }
+
+    /// Creates `num_arenas` arenas from their ids, and returns the range of the ids
+    ///
+    /// Every arena is created by the thread that will step it, so its memory is local to that thread's core.
+    pub fn add_arenas<F>(&mut self, num_arenas: usize, make: F) -> Range<usize>
+    where
+        F: Fn(usize) -> UniquePtr<Arena> + Send + Sync + 'static,
+    {
+        let start = self.ids.len();
+
+        // decide where every new arena goes first, so the ids can be given to the threads
+        let mut new_arenas = vec![Vec::new(); self.shards.len()];
+        let mut loads = self.shards.iter().map(|shard| shard.arenas.len()).collect::<Vec<_>>();
+        for id in start..start + num_arenas {
+            let shard = (0..loads.len()).min_by_key(|&i| loads[i]).unwrap_or(0);
+            loads[shard] += 1;
+            new_arenas[shard].push(id);
+        }
+
+        let make = Arc::new(make);
+        let tasks = new_arenas
+            .into_iter()
+            .map(|ids| {
+                let make = Arc::clone(&make);
+                (!ids.is_empty()).then(|| {
+                    Box::new(move |shard: &mut Shard| shard.created.extend(ids.into_iter().map(|id| (id, make(id))))) as Task
+                })
+            })
+            .collect();
+        self.run(tasks);
+
+        let mut created = self
+            .shards
+            .iter_mut()
+            .enumerate()
+            .flat_map(|(shard, arenas)| {
+                mem::take(&mut arenas.created)
+                    .into_iter()
+                    .map(move |(id, arena)| (id, shard, arena))
+            })
+            .collect::<Vec<_>>();
+        created.sort_unstable_by_key(|(id, _, _)| *id);
+
+        for (_, shard, arena) in created {
+            self.insert(shard, arena);
+        }
+
+        start..start + num_arenas
+    }
+
+    fn pool_arena(&self, id: usize) -> Option<&PoolArena> {
+        let &(shard, index) = self.ids.get(id)?;
+        Some(&self.shards[shard].arenas[index])
+    }
+
+    #[must_use]
+    pub fn get(&self, id: usize) -> Option<&Arena> {
+        self.pool_arena(id)?.arena.as_ref()
+    }
+
+    #[must_use]
+    pub fn get_mut(&mut self, id: usize) -> Option<Pin<&mut Arena>> {
+        let &(shard, index) = self.ids.get(id)?;
+        self.shards[shard].arenas[index].arena.as_mut()
+    }
+
+    #[must_use]
+    /// A sender to the mailbox of the arena, see `ActionSender::send`
+    pub fn sender(&self, id: usize) -> Option<ActionSender> {
+        Some(ActionSender {
+            arena_id: id,
+            sender: self.senders.get(id)?.clone(),
+        })
+    }
+
+    /// Stops stepping the arena until the condition is met or it's woken up,
+    /// replacing the condition if it's already suspended
+    ///
+    /// Returns `false` if there's no arena with the id
+    pub fn suspend(&mut self, id: usize, condition: WakeCondition) -> bool {
+        let Some(&(shard, index)) = self.ids.get(id) else {
+            return false;
+        };
+
+        let shard = &mut self.shards[shard];
+        let arena = &mut shard.arenas[index];
+        if arena.idle.is_none() {
+            shard.num_idle += 1;
+        }
+
+        arena.idle = Some((condition, self.ticks));
+        true
+    }
+
+    /// Starts stepping the arena again from the next step
+    ///
+    /// Returns `false` if there's no arena with the id, or it wasn't suspended
+    pub fn wake(&mut self, id: usize) -> bool {
+        let Some(&(shard, index)) = self.ids.get(id) else {
+            return false;
+        };
+
+        let shard = &mut self.shards[shard];
+        if shard.arenas[index].idle.take().is_none() {
+            return false;
+        }
+
+        shard.num_idle -= 1;
+        true
+    }
+
+    #[must_use]
+    pub fn is_idle(&self, id: usize) -> bool {
+        self.pool_arena(id).is_some_and(|arena| arena.idle.is_some())
+    }
+
+    #[must_use]
+    pub fn num_idle(&self) -> usize {
+        self.shards.iter().map(|shard| shard.num_idle).sum()
+    }
+
+    /// The ids of the suspended arenas, in order
+    pub fn idle_ids(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.ids.len()).filter(|&id| self.is_idle(id))
+    }
+
+    /// The arenas with their ids, in the order of the ids
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &Arena)> {
+        self.ids
+            .iter()
+            .enumerate()
+            .filter_map(|(id, &(shard, index))| Some((id, self.shards[shard].arenas[index].arena.as_ref()?)))
+    }
+
+    /// Steps every arena that isn't suspended in parallel
+    ///
+    /// Suspended arenas whose wake conditions are met are woken up first, and stepped too.
+    /// Threads that only have suspended arenas are left waiting.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the inference hook doesn't return controls for every arena it was given,
+    /// or if a thread of the pool panicked (like in an `ObserveFn`)
+    pub fn step(&mut self, ticks: u32) {
+        let now = Instant::now();
+        for shard in &mut self.shards {
+            shard.wake_due(now, self.ticks);
+        }
+
+        self.run_inference();
+
+        let tasks = self
+            .shards
+            .iter()
+            .map(|shard| (shard.num_active() != 0).then(|| Box::new(move |shard: &mut Shard| shard.step(ticks)) as Task))
+            .collect();
+        self.run(tasks);
+
+        self.ticks += u64::from(ticks);
+    }
+
+    /// Sets (or removes) the hook for batched inference before every step, see `InferenceHook`
+    pub fn set_inference_hook(&mut self, hook: Option<InferenceHook>) {
+        self.inference = hook;
+    }
+
+    fn run_inference(&mut self) {
+        let Some(hook) = &mut self.inference else {
+            return;
+        };
+
+        let active = (0..self.ids.len())
+            .filter(|&id| {
+                let (shard, index) = self.ids[id];
+                self.shards[shard].arenas[index].idle.is_none()
+            })
+            .collect::<Vec<_>>();
+        if active.is_empty() {
+            return;
+        }
+
+        // every thread observes its own arenas, in the order of their ids
+        let mut indices = self.shards.iter().map(|_| Vec::new()).collect::<Vec<_>>();
+        for &id in &active {
+            let (shard, index) = self.ids[id];
+            indices[shard].push(index);
+        }
+
+        let obs_len = hook.obs_len;
+        let tasks = indices
+            .into_iter()
+            .map(|indices| {
+                let observe = Arc::clone(&hook.observe);
+                (!indices.is_empty()).then(|| {
+                    Box::new(move |shard: &mut Shard| {
+                        shard.observations.clear();
+                        shard.observations.resize(indices.len() * obs_len, 0.);
+
+                        let slots = shard.observations.chunks_mut(obs_len.max(1));
+                        for (index, slot) in indices.into_iter().zip(slots) {
+                            let arena = &mut shard.arenas[index];
+                            observe(arena.id, arena.arena.pin_mut(), slot);
+                        }
+                    }) as Task
+                })
+            })
+            .collect();
+        self.run(tasks);
+
+        // then they're stacked in the order of the ids
+        self.observations.clear();
+        let mut observed = vec![0; self.shards.len()];
+        for &id in &active {
+            let shard = self.ids[id].0;
+            let start = observed[shard] * obs_len;
+            self.observations
+                .extend_from_slice(&self.shards[shard].observations[start..start + obs_len]);
+            observed[shard] += 1;
+        }
+
+        let Some(hook) = &mut self.inference else {
+            return;
+        };
+
+        let controls = (hook.infer)(&self.observations, active.len());
+        assert_eq!(
+            controls.len(),
+            active.len(),
+            "The inference hook returned controls for {} arenas, but there are {}",
+            controls.len(),
+            active.len()
+        );
+
+        for (id, controls) in active.into_iter().zip(controls) {
+            let (shard, index) = self.ids[id];
+            self.shards[shard].arenas[index].inferred = controls;
+        }
+    }
+
+    #[must_use]
+    /// The game states of every arena, in the order of the ids
+    pub fn get_game_states(&mut self) -> Vec<GameState> {
+        (0..self.ids.len())
+            .map(|id| self.get_mut(id).unwrap().get_game_state())
+            .collect()
+    }
+
+    #[must_use]
+    /// The stats of every thread of the pool
+    pub fn thread_stats(&self) -> Vec<ThreadStats> {
+        self.shards.iter().map(|shard| shard.stats).collect()
+    }
+
+    /// Starts measuring the stats of the threads from now
+    pub fn reset_stats(&mut self) {
+        for shard in &mut self.shards {
+            shard.stats.ticks = 0;
+            shard.stats.busy = Duration::ZERO;
+        }
+    }
+}
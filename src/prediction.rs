@@ -0,0 +1,154 @@
+use crate::{
+    consts,
+    events::Surface,
+    math::Vec3,
+    sim::{Arena, BallState, Team},
+};
+
+/// Minimum unexplained change in ball velocity (uu/s) in a single tick for it to count as a bounce
+///
+/// Gravity is already accounted for, so a ball rolling along a surface will not trigger this
+pub const BOUNCE_MIN_DELTA_VEL: f32 = 50.;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BallPredEvent {
+    /// The ball bounced off of the arena geometry
+    Bounce { surface: Surface, normal: Vec3 },
+    /// The ball fully crossed a goal line, `scoring_team` is the team that would be awarded the goal
+    GoalCrossing { scoring_team: Team },
+    /// Optimistic estimate of the first slice the car could reach, driving in a straight line at max speed
+    FirstReachable { car_id: u32 },
+}
+
+#[derive(Clone, Debug)]
+pub struct BallSlice {
+    /// Seconds from the moment the prediction was made
+    pub time: f32,
+    pub tick_count: u64,
+    pub state: BallState,
+    pub events: Vec<BallPredEvent>,
+}
+
+#[derive(Clone, Debug, Default)]
+/// A ball-only prediction of the future, one slice per tick
+pub struct BallPrediction {
+    pub slices: Vec<BallSlice>,
+}
+
+impl BallPrediction {
+    #[must_use]
+    /// Predicts the path of the ball for the given number of ticks
+    ///
+    /// The arena is cloned (without callbacks) and all cars are removed from the clone,
+    /// so the real arena is left untouched and car touches are not simulated
+    pub fn new(arena: &Arena, num_ticks: u32) -> Self {
+        let mut arena = arena.clone(false);
+        let dt = 1. / arena.get_tick_rate();
+        let gravity = arena.get_mutator_config().gravity;
+        let ball_radius = arena.get_ball_radius();
+
+        let cars = arena.pin_mut().get_car_infos();
+        for car in &cars {
+            arena.pin_mut().remove_car(car.id).unwrap();
+        }
+
+        let mut reachable = cars.iter().map(|car| (car.id, car.state.pos, false)).collect::<Vec<_>>();
+        let mut prev_ball = arena.pin_mut().get_ball();
+        let mut scored = arena.is_ball_scored();
+        let mut slices = Vec::with_capacity(num_ticks as usize);
+
+        for i in 1..=num_ticks {
+            arena.pin_mut().step(1);
+
+            let time = i as f32 * dt;
+            let ball = arena.pin_mut().get_ball();
+            let mut events = Vec::new();
+
+            let unexplained_vel = ball.vel - prev_ball.vel - gravity * dt;
+            let delta_vel = unexplained_vel.length();
+
+            if delta_vel > BOUNCE_MIN_DELTA_VEL {
+                let normal = unexplained_vel / delta_vel;
+                events.push(BallPredEvent::Bounce {
+                    surface: Surface::from_normal(normal),
+                    normal,
+                });
+            }
+
+            if !scored && arena.is_ball_scored() {
+                scored = true;
+                events.push(BallPredEvent::GoalCrossing {
+                    scoring_team: if ball.pos.y > 0. { Team::Blue } else { Team::Orange },
+                });
+            }
+
+            for (car_id, car_pos, found) in &mut reachable {
+                if !*found && (ball.pos - *car_pos).length() - ball_radius <= consts::CAR_MAX_SPEED * time {
+                    *found = true;
+                    events.push(BallPredEvent::FirstReachable { car_id: *car_id });
+                }
+            }
+
+            slices.push(BallSlice {
+                time,
+                tick_count: arena.get_tick_count(),
+                state: ball,
+                events,
+            });
+            prev_ball = ball;
+        }
+
+        Self { slices }
+    }
+
+    #[inline]
+    /// Iterates over every slice that has an event, along with the event
+    pub fn iter_events(&self) -> impl Iterator<Item = (&BallSlice, &BallPredEvent)> {
+        self.slices
+            .iter()
+            .flat_map(|slice| slice.events.iter().map(move |event| (slice, event)))
+    }
+
+    #[inline]
+    /// Iterates over all of the slices where the ball bounced
+    pub fn bounces(&self) -> impl Iterator<Item = &BallSlice> {
+        self.slices
+            .iter()
+            .filter(|slice| slice.events.iter().any(|event| matches!(event, BallPredEvent::Bounce { .. })))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the first slice where the ball bounces, if any
+    pub fn next_bounce(&self) -> Option<&BallSlice> {
+        self.bounces().next()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the slice where the ball first fully crosses a goal line, if any
+    pub fn goal_crossing(&self) -> Option<&BallSlice> {
+        self.slices.iter().find(|slice| {
+            slice
+                .events
+                .iter()
+                .any(|event| matches!(event, BallPredEvent::GoalCrossing { .. }))
+        })
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the number of seconds until the ball is in a goal, if it goes in within the prediction
+    pub fn time_until_goal(&self) -> Option<f32> {
+        self.goal_crossing().map(|slice| slice.time)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the first slice that the car with the given ID could optimistically reach
+    pub fn first_reachable(&self, car_id: u32) -> Option<&BallSlice> {
+        self.slices
+            .iter()
+            .find(|slice| slice.events.contains(&BallPredEvent::FirstReachable { car_id }))
+    }
+}
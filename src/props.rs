@@ -0,0 +1,194 @@
+//! Simple dynamic rigid bodies ("props") that interact with the cars and the ball
+//!
+//! Props are simulated by Bullet alongside everything else in the arena,
+//! but they aren't part of RocketSim's own game logic (so they can't score goals or pick up boost).
+
+use crate::{
+    consts,
+    math::{RotMat, Vec3},
+    sim::{Arena, PropConfig, PropShape, PropState},
+    GameState, NoCarFound,
+};
+use core::pin::Pin;
+use std::{error::Error, fmt};
+
+#[derive(Clone, Copy, Debug)]
+pub struct NoPropFound(pub u32);
+
+impl Error for NoPropFound {}
+impl fmt::Display for NoPropFound {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "No prop found in the given arena at the given ID {}.", self.0)
+    }
+}
+
+impl PropConfig {
+    #[inline]
+    #[must_use]
+    pub const fn sphere(radius: f32, mass: f32) -> Self {
+        Self {
+            shape: PropShape::Sphere,
+            radius,
+            half_extents: Vec3::ZERO,
+            mass,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn cuboid(half_extents: Vec3, mass: f32) -> Self {
+        Self {
+            shape: PropShape::Box,
+            radius: 0.,
+            half_extents,
+            mass,
+        }
+    }
+}
+
+impl Default for PropConfig {
+    #[inline]
+    /// A sphere with the same size and mass as the ball
+    fn default() -> Self {
+        Self::sphere(consts::BALL_COLLISION_RADIUS_SOCCAR, consts::BALL_MASS_BT)
+    }
+}
+
+impl Default for PropState {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            pos: Vec3::ZERO,
+            rot_mat: RotMat::IDENTITY,
+            vel: Vec3::ZERO,
+            ang_vel: Vec3::ZERO,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PropInfo {
+    pub id: u32,
+    pub config: PropConfig,
+    pub state: PropState,
+}
+
+#[derive(Clone, Debug, Default)]
+/// A `GameState` that also includes all of the props in the arena
+pub struct GameStateWithProps {
+    pub game_state: GameState,
+    pub props: Vec<PropInfo>,
+}
+
+impl Arena {
+    #[inline]
+    #[must_use]
+    /// Spawns a prop with the given config and initial state, returning its ID
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mass or size of the prop isn't positive
+    pub fn add_prop(self: Pin<&mut Self>, config: PropConfig, state: PropState) -> u32 {
+        let size_is_valid = match config.shape {
+            PropShape::Sphere => config.radius > 0.,
+            PropShape::Box => config.half_extents.x > 0. && config.half_extents.y > 0. && config.half_extents.z > 0.,
+        };
+        assert!(size_is_valid && config.mass > 0., "Invalid prop config: {config:?}");
+
+        self.adp(config, state)
+    }
+
+    #[inline]
+    /// # Errors
+    ///
+    /// If there is no prop with the given ID, this will return an error
+    pub fn remove_prop(self: Pin<&mut Self>, prop_id: u32) -> Result<(), NoPropFound> {
+        if self.rmvp(prop_id) {
+            Ok(())
+        } else {
+            Err(NoPropFound(prop_id))
+        }
+    }
+
+    #[inline]
+    /// Returns the state of the prop at the given ID
+    ///
+    /// # Errors
+    ///
+    /// If there is no prop with the given ID, this will return an error
+    pub fn get_prop(&self, prop_id: u32) -> Result<PropState, NoPropFound> {
+        if self.has_prop(prop_id) {
+            Ok(self.gp(prop_id))
+        } else {
+            Err(NoPropFound(prop_id))
+        }
+    }
+
+    #[inline]
+    /// Sets the state of the prop at the given ID
+    ///
+    /// # Errors
+    ///
+    /// If there is no prop with the given ID, this will return an error
+    pub fn set_prop(self: Pin<&mut Self>, prop_id: u32, state: PropState) -> Result<(), NoPropFound> {
+        if self.sp(prop_id, state) {
+            Ok(())
+        } else {
+            Err(NoPropFound(prop_id))
+        }
+    }
+
+    #[inline]
+    /// Returns the config of the prop at the given ID
+    ///
+    /// # Errors
+    ///
+    /// If there is no prop with the given ID, this will return an error
+    pub fn get_prop_config(&self, prop_id: u32) -> Result<PropConfig, NoPropFound> {
+        if self.has_prop(prop_id) {
+            Ok(self.gpc(prop_id))
+        } else {
+            Err(NoPropFound(prop_id))
+        }
+    }
+
+    #[must_use]
+    /// Returns all of the `PropInfo`s in the arena
+    pub fn get_prop_infos(&self) -> Vec<PropInfo> {
+        self.get_props()
+            .into_iter()
+            .map(|id| PropInfo {
+                id,
+                config: self.gpc(id),
+                state: self.gp(id),
+            })
+            .collect()
+    }
+
+    #[must_use]
+    /// Full game state getter, including props
+    pub fn get_game_state_with_props(mut self: Pin<&mut Self>) -> GameStateWithProps {
+        GameStateWithProps {
+            props: self.get_prop_infos(),
+            game_state: self.as_mut().get_game_state(),
+        }
+    }
+
+    /// Full game state setter, including props
+    ///
+    /// Props that aren't in the arena are ignored, props are not spawned or removed
+    ///
+    /// # Errors
+    ///
+    /// Returns `NoCarFound` upon the first car that cannot be found from a given ID
+    pub fn set_game_state_with_props(mut self: Pin<&mut Self>, state: &GameStateWithProps) -> Result<(), NoCarFound> {
+        self.as_mut().set_game_state(&state.game_state)?;
+
+        for prop in &state.props {
+            let _ = self.as_mut().sp(prop.id, prop.state);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,441 @@
+//! Packet types and handlers for the UDP protocol spoken with rlviser
+
+use crate::{
+    bytes::{FromBytes, FromBytesExact, ToBytesExact},
+    realtime::RealTimeStepper,
+    sim::Arena,
+    GameState,
+};
+use hmac_sha256::HMAC;
+use std::{error::Error, fmt, pin::Pin};
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UdpPacketTypes {
+    Quit,
+    GameState,
+    Connection,
+    Paused,
+    Speed,
+    Render,
+    /// A game state that is signed, see `StateSetAuth`
+    AuthGameState,
+    /// Tells the viewer whether it may set the game state, followed by a single `StateSetPermission` byte
+    StateSetPermission,
+    /// Steps a paused simulation, followed by the number of ticks as a `u32`
+    Step,
+    /// Seeks a replay to a tick count, followed by the tick count as a `u64`
+    Seek,
+}
+
+#[derive(Clone, Copy, Debug)]
+/// Error for when a packet type byte isn't known
+pub struct InvalidPacketType(pub u8);
+
+impl Error for InvalidPacketType {}
+impl fmt::Display for InvalidPacketType {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid packet type {}.", self.0)
+    }
+}
+
+impl TryFrom<u8> for UdpPacketTypes {
+    type Error = InvalidPacketType;
+
+    #[inline]
+    fn try_from(val: u8) -> Result<Self, Self::Error> {
+        Ok(match val {
+            0 => Self::Quit,
+            1 => Self::GameState,
+            2 => Self::Connection,
+            3 => Self::Paused,
+            4 => Self::Speed,
+            5 => Self::Render,
+            6 => Self::AuthGameState,
+            7 => Self::StateSetPermission,
+            8 => Self::Step,
+            9 => Self::Seek,
+            _ => return Err(InvalidPacketType(val)),
+        })
+    }
+}
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// What a connected viewer is allowed to do with the game state
+pub enum StateSetPermission {
+    /// Game state packets from the viewer are ignored
+    Deny,
+    /// Any game state packet from the viewer is applied
+    Allow,
+    /// Only `AuthGameState` packets with a valid signature are applied
+    Authenticated,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Reasons that a state-set packet can be rejected
+pub enum StateSetError {
+    /// State setting is disabled
+    NotAllowed,
+    /// The packet wasn't signed, but a signature is required
+    Unauthenticated,
+    /// The packet was too short to contain a signature, or didn't contain a valid game state
+    Malformed,
+    /// The signature didn't match the contents of the packet
+    BadSignature,
+    /// The nonce wasn't greater than the last accepted nonce, so this may be a replayed packet
+    Replayed,
+}
+
+impl Error for StateSetError {}
+impl fmt::Display for StateSetError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::NotAllowed => "Setting the game state is not allowed.",
+            Self::Unauthenticated => "Setting the game state requires an authenticated packet.",
+            Self::Malformed => "The packet is too short or isn't a valid game state.",
+            Self::BadSignature => "The authenticated packet has an invalid signature.",
+            Self::Replayed => "The authenticated packet has already been seen.",
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Signs and verifies game state packets with a shared key
+///
+/// An `AuthGameState` payload is a little-endian `u64` nonce,
+/// followed by the 32 byte HMAC-SHA256 of the nonce and the game state bytes, followed by the game state bytes.
+/// Nonces must always increase, which prevents old packets from being replayed.
+pub struct StateSetAuth {
+    key: Vec<u8>,
+    last_nonce: Option<u64>,
+}
+
+impl StateSetAuth {
+    pub const HEADER_NUM_BYTES: usize = u64::NUM_BYTES + 32;
+
+    #[inline]
+    #[must_use]
+    pub fn new<K: Into<Vec<u8>>>(key: K) -> Self {
+        Self {
+            key: key.into(),
+            last_nonce: None,
+        }
+    }
+
+    fn mac(&self, nonce: &[u8], game_state: &[u8]) -> [u8; 32] {
+        let mut mac = HMAC::new(&self.key);
+        mac.update(nonce);
+        mac.update(game_state);
+        mac.finalize()
+    }
+
+    #[must_use]
+    /// Creates a signed `AuthGameState` payload from the bytes of a game state
+    pub fn sign(&self, nonce: u64, game_state: &[u8]) -> Vec<u8> {
+        let nonce = nonce.to_bytes();
+
+        let mut bytes = Vec::with_capacity(Self::HEADER_NUM_BYTES + game_state.len());
+        bytes.extend_from_slice(&nonce);
+        bytes.extend_from_slice(&self.mac(&nonce, game_state));
+        bytes.extend_from_slice(game_state);
+        bytes
+    }
+
+    /// Verifies a signed `AuthGameState` payload, returning the bytes of the game state
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the payload is malformed, the signature is invalid, or the nonce was already used
+    pub fn verify<'a>(&mut self, payload: &'a [u8]) -> Result<&'a [u8], StateSetError> {
+        if payload.len() < Self::HEADER_NUM_BYTES {
+            return Err(StateSetError::Malformed);
+        }
+
+        let (nonce, rest) = payload.split_at(u64::NUM_BYTES);
+        let (signature, game_state) = rest.split_at(32);
+
+        let mut expected = HMAC::new(&self.key);
+        expected.update(nonce);
+        expected.update(game_state);
+        if !expected.finalize_verify(signature.try_into().unwrap()) {
+            return Err(StateSetError::BadSignature);
+        }
+
+        let nonce = u64::from_bytes(nonce);
+        if self.last_nonce.is_some_and(|last| nonce <= last) {
+            return Err(StateSetError::Replayed);
+        }
+
+        self.last_nonce = Some(nonce);
+        Ok(game_state)
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Decides whether game state packets from a viewer should be applied
+pub enum StateSetPolicy {
+    Deny,
+    Allow,
+    Authenticated(StateSetAuth),
+}
+
+impl Default for StateSetPolicy {
+    #[inline]
+    fn default() -> Self {
+        Self::Allow
+    }
+}
+
+impl StateSetPolicy {
+    #[inline]
+    #[must_use]
+    pub const fn permission(&self) -> StateSetPermission {
+        match self {
+            Self::Deny => StateSetPermission::Deny,
+            Self::Allow => StateSetPermission::Allow,
+            Self::Authenticated(_) => StateSetPermission::Authenticated,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The packet that informs the viewer of the policy, to be sent after the packet type
+    pub const fn permission_packet(&self) -> [u8; 1] {
+        [self.permission() as u8]
+    }
+
+    /// Checks a `GameState` or `AuthGameState` packet against the policy, returning the game state to apply
+    ///
+    /// With `Allow`, signed packets are accepted without checking the signature
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the packet should not be applied, or `StateSetError::Malformed` if it isn't a valid game state
+    pub fn accept(&mut self, packet_type: UdpPacketTypes, payload: &[u8]) -> Result<GameState, StateSetError> {
+        let game_state = match (self, packet_type) {
+            (Self::Deny, _) => return Err(StateSetError::NotAllowed),
+            (Self::Allow, UdpPacketTypes::GameState) => payload,
+            (Self::Allow, _) => payload
+                .get(StateSetAuth::HEADER_NUM_BYTES..)
+                .ok_or(StateSetError::Malformed)?,
+            (Self::Authenticated(_), UdpPacketTypes::GameState) => return Err(StateSetError::Unauthenticated),
+            (Self::Authenticated(auth), _) => auth.verify(payload)?,
+        };
+
+        if GameState::check_bytes(game_state) != Some(game_state.len()) {
+            return Err(StateSetError::Malformed);
+        }
+
+        Ok(GameState::from_bytes(game_state))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// A request from the viewer to control the flow of the simulation
+pub enum SessionCommand {
+    Pause,
+    Resume,
+    /// Step a paused simulation by the given number of ticks
+    Step(u32),
+    /// Change the speed multiplier, i.e. `0.5` for half speed and `2` for double speed
+    Speed(f32),
+    /// Jump to the given tick count, which only applies to replays (see `playback`)
+    Seek(u64),
+}
+
+impl SessionCommand {
+    #[inline]
+    #[must_use]
+    /// `Pause` or `Resume`, e.g. for telling the viewer about `Arena::is_paused`
+    pub const fn paused(paused: bool) -> Self {
+        if paused {
+            Self::Pause
+        } else {
+            Self::Resume
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn packet_type(&self) -> UdpPacketTypes {
+        match self {
+            Self::Pause | Self::Resume => UdpPacketTypes::Paused,
+            Self::Step(_) => UdpPacketTypes::Step,
+            Self::Speed(_) => UdpPacketTypes::Speed,
+            Self::Seek(_) => UdpPacketTypes::Seek,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The number of bytes that follow a packet of the given type, if it is a session command
+    pub const fn payload_num_bytes(packet_type: UdpPacketTypes) -> Option<usize> {
+        match packet_type {
+            UdpPacketTypes::Paused => Some(bool::NUM_BYTES),
+            UdpPacketTypes::Step => Some(u32::NUM_BYTES),
+            UdpPacketTypes::Speed => Some(f32::NUM_BYTES),
+            UdpPacketTypes::Seek => Some(u64::NUM_BYTES),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    /// The bytes that are sent after the packet type
+    pub fn payload(&self) -> Vec<u8> {
+        match self {
+            Self::Pause => vec![1],
+            Self::Resume => vec![0],
+            Self::Step(ticks) => ticks.to_bytes().to_vec(),
+            Self::Speed(speed) => speed.to_bytes().to_vec(),
+            Self::Seek(tick_count) => tick_count.to_bytes().to_vec(),
+        }
+    }
+
+    #[must_use]
+    /// Parses a session command from its packet type and payload
+    pub fn from_packet(packet_type: UdpPacketTypes, payload: &[u8]) -> Option<Self> {
+        if payload.len() < Self::payload_num_bytes(packet_type)? {
+            return None;
+        }
+
+        Some(match packet_type {
+            UdpPacketTypes::Paused if bool::from_bytes(payload) => Self::Pause,
+            UdpPacketTypes::Paused => Self::Resume,
+            UdpPacketTypes::Step => Self::Step(u32::from_bytes(payload)),
+            UdpPacketTypes::Speed => Self::Speed(f32::from_bytes(payload)),
+            UdpPacketTypes::Seek => Self::Seek(u64::from_bytes(payload)),
+            _ => unreachable!(),
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+/// Tracks the pause state, speed, and requested steps of a viewer-controlled simulation
+pub struct SessionControl {
+    paused: bool,
+    speed: f32,
+    speed_changed: bool,
+    pending_steps: u32,
+    pending_seek: Option<u64>,
+}
+
+impl Default for SessionControl {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SessionControl {
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            paused: false,
+            speed: 1.,
+            speed_changed: false,
+            pending_steps: 0,
+            pending_seek: None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    #[inline]
+    #[must_use]
+    /// The number of ticks that have been requested by `Step` but not yet stepped
+    pub const fn pending_steps(&self) -> u32 {
+        self.pending_steps
+    }
+
+    /// Takes the ticks that have been requested by `Step` but not yet stepped, for stepping them elsewhere
+    pub fn take_pending_steps(&mut self) -> u32 {
+        core::mem::take(&mut self.pending_steps)
+    }
+
+    /// Takes the tick count of the last `Seek` that hasn't been handled yet
+    ///
+    /// Seeks only mean something for replays, so `step` ignores them
+    pub fn take_seek(&mut self) -> Option<u64> {
+        self.pending_seek.take()
+    }
+
+    /// Applies a command from the viewer
+    ///
+    /// Invalid speeds (NaN or not positive) are ignored, other speeds are clamped to `RealTimeStepper::SPEED_RANGE`,
+    /// and steps are only accepted while paused
+    pub fn apply(&mut self, command: SessionCommand) {
+        match command {
+            SessionCommand::Pause => self.paused = true,
+            SessionCommand::Resume => {
+                self.paused = false;
+                self.pending_steps = 0;
+            }
+            SessionCommand::Step(ticks) => {
+                if self.paused {
+                    self.pending_steps = self.pending_steps.saturating_add(ticks);
+                }
+            }
+            SessionCommand::Speed(speed) => {
+                if speed > 0. {
+                    let range = RealTimeStepper::SPEED_RANGE;
+                    self.speed = speed.clamp(*range.start(), *range.end());
+                    self.speed_changed = true;
+                }
+            }
+            SessionCommand::Seek(tick_count) => self.pending_seek = Some(tick_count),
+        }
+    }
+
+    /// Parses and applies a session command packet, returning true if the packet was a session command
+    pub fn handle_packet(&mut self, packet_type: UdpPacketTypes, payload: &[u8]) -> bool {
+        SessionCommand::from_packet(packet_type, payload)
+            .map(|command| self.apply(command))
+            .is_some()
+    }
+
+    /// Steps the arena according to the current session state
+    ///
+    /// While running, this paces the arena in real time with the stepper.
+    /// While paused, this only steps the ticks requested with `Step` (one per call, paced like normal),
+    /// and otherwise just waits for the duration of a tick.
+    /// If the arena itself is paused (see `Arena::set_paused`), this never steps.
+    ///
+    /// Returns the number of ticks that were stepped
+    pub fn step(&mut self, stepper: &mut RealTimeStepper, arena: Pin<&mut Arena>) -> u32 {
+        if self.speed_changed {
+            self.speed_changed = false;
+            stepper.set_speed(self.speed);
+        }
+
+        if !self.paused && !arena.is_paused() {
+            return stepper.step(arena);
+        }
+
+        // don't count the time spent paused as falling behind
+        stepper.reset();
+        stepper.wait();
+
+        // the arena itself being paused takes priority over the viewer's steps
+        if self.pending_steps == 0 || arena.is_paused() {
+            return 0;
+        }
+
+        self.pending_steps -= 1;
+        arena.step(1);
+        1
+    }
+}
@@ -0,0 +1,187 @@
+//! Domain randomization: perturbing the physics every episode, for training policies that are robust to sim inaccuracies
+
+use crate::{
+    sim::{Arena, CarConfig, MutatorConfig, WheelPairConfig},
+    NoCarFound,
+};
+use core::{ops::RangeInclusive, pin::Pin};
+use rand::Rng;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_utils", derive(serde::Serialize, serde::Deserialize))]
+/// The values that were sampled for an episode, as multipliers of the base values
+///
+/// Store these alongside the episode to know what physics it was played with
+pub struct DomainSample {
+    pub gravity_scale: f32,
+    pub ball_restitution_scale: f32,
+    pub ball_drag_scale: f32,
+    /// Scales the boost acceleration, both on the ground and in the air
+    pub boost_strength_scale: f32,
+    /// `(car id, scale)`, where the scale is applied to the hitbox and wheels of the car
+    pub car_scales: Vec<(u32, f32)>,
+}
+
+impl DomainSample {
+    #[must_use]
+    /// The mutator config with the sampled scales applied to the base
+    pub fn mutator_config(&self, base: &MutatorConfig) -> MutatorConfig {
+        MutatorConfig {
+            gravity: base.gravity * self.gravity_scale,
+            ball_world_restitution: base.ball_world_restitution * self.ball_restitution_scale,
+            ball_drag: base.ball_drag * self.ball_drag_scale,
+            boost_accel_ground: base.boost_accel_ground * self.boost_strength_scale,
+            boost_accel_air: base.boost_accel_air * self.boost_strength_scale,
+            ..*base
+        }
+    }
+}
+
+fn scale_wheels(wheels: WheelPairConfig, scale: f32) -> WheelPairConfig {
+    WheelPairConfig {
+        wheel_radius: wheels.wheel_radius * scale,
+        suspension_rest_length: wheels.suspension_rest_length * scale,
+        connection_point_offset: wheels.connection_point_offset * scale,
+    }
+}
+
+/// Scales the hitbox and wheels of a car config, keeping everything else the same
+#[must_use]
+pub fn scale_car_config(config: &CarConfig, scale: f32) -> CarConfig {
+    CarConfig {
+        hitbox_size: config.hitbox_size * scale,
+        hitbox_pos_offset: config.hitbox_pos_offset * scale,
+        front_wheels: scale_wheels(config.front_wheels, scale),
+        back_wheels: scale_wheels(config.back_wheels, scale),
+        dodge_deadzone: config.dodge_deadzone,
+    }
+}
+
+fn sample_range<R: Rng + ?Sized>(rng: &mut R, range: &RangeInclusive<f32>) -> f32 {
+    if range.start() < range.end() {
+        rng.gen_range(range.clone())
+    } else {
+        *range.start()
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Samples physics parameters within the configured ranges, relative to a base arena
+///
+/// Every range is a multiplier of the base value, and defaults to `1.0..=1.0` (no randomization)
+pub struct DomainRandomizer {
+    /// The mutator config that the scales are applied to
+    pub base_mutators: MutatorConfig,
+    /// The car configs that the car scales are applied to, cars that aren't in here aren't changed
+    pub base_cars: Vec<(u32, CarConfig)>,
+    pub gravity_scale: RangeInclusive<f32>,
+    pub ball_restitution_scale: RangeInclusive<f32>,
+    pub ball_drag_scale: RangeInclusive<f32>,
+    pub boost_strength_scale: RangeInclusive<f32>,
+    /// Sampled separately for every car
+    pub car_scale: RangeInclusive<f32>,
+}
+
+impl DomainRandomizer {
+    #[must_use]
+    /// Uses the current mutators and car configs of the arena as the base
+    ///
+    /// This should be called before the arena is randomized, so the perturbations don't compound
+    pub fn from_arena(arena: &Arena) -> Self {
+        Self {
+            base_mutators: arena.get_mutator_config(),
+            base_cars: arena
+                .get_cars()
+                .into_iter()
+                .map(|id| (id, arena.get_car_config(id)))
+                .collect(),
+            gravity_scale: 1.0..=1.,
+            ball_restitution_scale: 1.0..=1.,
+            ball_drag_scale: 1.0..=1.,
+            boost_strength_scale: 1.0..=1.,
+            car_scale: 1.0..=1.,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn with_gravity_scale(mut self, range: RangeInclusive<f32>) -> Self {
+        self.gravity_scale = range;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn with_ball_restitution_scale(mut self, range: RangeInclusive<f32>) -> Self {
+        self.ball_restitution_scale = range;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn with_ball_drag_scale(mut self, range: RangeInclusive<f32>) -> Self {
+        self.ball_drag_scale = range;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn with_boost_strength_scale(mut self, range: RangeInclusive<f32>) -> Self {
+        self.boost_strength_scale = range;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn with_car_scale(mut self, range: RangeInclusive<f32>) -> Self {
+        self.car_scale = range;
+        self
+    }
+
+    /// Samples the values for an episode, without applying them
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> DomainSample {
+        DomainSample {
+            gravity_scale: sample_range(rng, &self.gravity_scale),
+            ball_restitution_scale: sample_range(rng, &self.ball_restitution_scale),
+            ball_drag_scale: sample_range(rng, &self.ball_drag_scale),
+            boost_strength_scale: sample_range(rng, &self.boost_strength_scale),
+            car_scales: self
+                .base_cars
+                .iter()
+                .map(|(id, _)| (*id, sample_range(rng, &self.car_scale)))
+                .collect(),
+        }
+    }
+
+    /// Applies a sample to the arena, e.g. to replay an episode with the same physics
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a car in the sample isn't in the arena (the mutators are still applied)
+    pub fn apply(&self, mut arena: Pin<&mut Arena>, sample: &DomainSample) -> Result<(), NoCarFound> {
+        arena.as_mut().set_mutator_config(sample.mutator_config(&self.base_mutators));
+
+        for &(id, scale) in &sample.car_scales {
+            let Some((_, base)) = self.base_cars.iter().find(|(base_id, _)| *base_id == id) else {
+                continue;
+            };
+
+            arena.as_mut().set_car_config(id, &scale_car_config(base, scale))?;
+        }
+
+        Ok(())
+    }
+
+    /// Samples new values and applies them to the arena, returning the sample
+    ///
+    /// Call this when resetting the arena for a new episode
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a car in the base isn't in the arena anymore (the mutators are still applied)
+    pub fn randomize<R: Rng + ?Sized>(&self, rng: &mut R, arena: Pin<&mut Arena>) -> Result<DomainSample, NoCarFound> {
+        let sample = self.sample(rng);
+        self.apply(arena, &sample)?;
+        Ok(sample)
+    }
+}
@@ -0,0 +1,202 @@
+use crate::sim::Arena;
+use std::{
+    ops::RangeInclusive,
+    pin::Pin,
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+#[derive(Clone, Debug)]
+/// Paces an arena so that it runs in real time (or at a multiple of it)
+///
+/// Ticks are scheduled against a fixed starting point instead of repeatedly adding an interval to the last deadline,
+/// so rounding errors and oversleeping don't accumulate into drift.
+/// If stepping falls behind (e.g. after a stall), multiple ticks are stepped at once to catch up,
+/// up to `max_catch_up_ticks` - anything further behind is dropped.
+pub struct RealTimeStepper {
+    tick_rate: f32,
+    speed: f32,
+    max_catch_up_ticks: u32,
+    epoch: Instant,
+    ticks_since_epoch: u64,
+    total_ticks: u64,
+    dropped_ticks: u64,
+}
+
+impl RealTimeStepper {
+    /// The default maximum number of ticks that will be stepped at once to catch up
+    pub const DEFAULT_MAX_CATCH_UP_TICKS: u32 = 30;
+    /// The range that speeds are clamped to, see `set_speed`
+    pub const SPEED_RANGE: RangeInclusive<f32> = 0.01..=100.;
+    /// The range that tick rates are clamped to
+    pub const TICK_RATE_RANGE: RangeInclusive<f32> = 1.0..=1000.;
+
+    /// Clamps the value to the range, non-finite values become `default`
+    fn clamp(value: f32, range: &RangeInclusive<f32>, default: f32) -> f32 {
+        if value.is_nan() {
+            default
+        } else {
+            value.clamp(*range.start(), *range.end())
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Creates a stepper for the tick rate, which is clamped to `TICK_RATE_RANGE`
+    pub fn new(tick_rate: f32) -> Self {
+        Self {
+            tick_rate: Self::clamp(tick_rate, &Self::TICK_RATE_RANGE, 120.),
+            speed: 1.,
+            max_catch_up_ticks: Self::DEFAULT_MAX_CATCH_UP_TICKS,
+            epoch: Instant::now(),
+            ticks_since_epoch: 0,
+            total_ticks: 0,
+            dropped_ticks: 0,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Creates a stepper that matches the tick rate of the arena
+    pub fn from_arena(arena: &Arena) -> Self {
+        Self::new(arena.get_tick_rate())
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Sets the speed multiplier, i.e. `0.5` for half speed and `2` for double speed
+    ///
+    /// The speed is clamped to `SPEED_RANGE`, and NaN is ignored
+    pub fn set_speed(&mut self, speed: f32) {
+        self.rebase();
+        self.speed = Self::clamp(speed, &Self::SPEED_RANGE, self.speed);
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn tick_rate(&self) -> f32 {
+        self.tick_rate
+    }
+
+    /// Sets the tick rate that is being paced, this should match the arena
+    ///
+    /// The tick rate is clamped to `TICK_RATE_RANGE`, and NaN is ignored
+    pub fn set_tick_rate(&mut self, tick_rate: f32) {
+        self.rebase();
+        self.tick_rate = Self::clamp(tick_rate, &Self::TICK_RATE_RANGE, self.tick_rate);
+    }
+
+    #[inline]
+    /// Sets the maximum number of ticks that will be stepped at once to catch up after falling behind
+    pub fn set_max_catch_up_ticks(&mut self, max_catch_up_ticks: u32) {
+        self.max_catch_up_ticks = max_catch_up_ticks.max(1);
+    }
+
+    #[inline]
+    #[must_use]
+    /// The total number of ticks that have been stepped
+    pub const fn total_ticks(&self) -> u64 {
+        self.total_ticks
+    }
+
+    #[inline]
+    #[must_use]
+    /// The total number of ticks that were skipped because they were too far behind schedule
+    pub const fn dropped_ticks(&self) -> u64 {
+        self.dropped_ticks
+    }
+
+    /// Restarts the schedule from now, without any catching up
+    ///
+    /// Call this after pausing, otherwise the paused time will be treated as a stall
+    pub fn reset(&mut self) {
+        self.epoch = Instant::now();
+        self.ticks_since_epoch = 0;
+    }
+
+    /// Moves the epoch up to the current deadline, so changes to the pacing only apply to future ticks
+    fn rebase(&mut self) {
+        match Duration::try_from_secs_f64(self.elapsed_target())
+            .ok()
+            .and_then(|elapsed| self.epoch.checked_add(elapsed))
+        {
+            Some(epoch) => {
+                self.epoch = epoch;
+                self.ticks_since_epoch = 0;
+            }
+            None => self.reset(),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Seconds of wall time per tick
+    fn interval(&self) -> f64 {
+        1. / (f64::from(self.tick_rate) * f64::from(self.speed))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Seconds from the epoch that the last stepped tick was scheduled at
+    fn elapsed_target(&self) -> f64 {
+        self.ticks_since_epoch as f64 * self.interval()
+    }
+
+    #[must_use]
+    /// How far behind schedule the simulation is, in seconds of wall time
+    ///
+    /// Negative if the next tick isn't due yet
+    pub fn drift(&self) -> f64 {
+        self.epoch.elapsed().as_secs_f64() - (self.elapsed_target() + self.interval())
+    }
+
+    #[must_use]
+    /// Returns the number of ticks that are currently due, without waiting
+    ///
+    /// The returned ticks are counted as stepped, so this should only be called if you are going to step them
+    pub fn ticks_due(&mut self) -> u32 {
+        let due = (self.epoch.elapsed().as_secs_f64() / self.interval()).floor() as u64;
+        let behind = due.saturating_sub(self.ticks_since_epoch);
+
+        let ticks = behind.min(u64::from(self.max_catch_up_ticks));
+        if behind > ticks {
+            self.dropped_ticks += behind - ticks;
+            self.ticks_since_epoch = due - ticks;
+        }
+
+        self.ticks_since_epoch += ticks;
+        self.total_ticks += ticks;
+        ticks as u32
+    }
+
+    /// Sleeps until the next tick is due
+    pub fn wait(&self) {
+        if let Ok(duration) = Duration::try_from_secs_f64(-self.drift()) {
+            sleep(duration);
+        }
+    }
+
+    /// Waits until at least one tick is due, then steps the arena by all due ticks
+    ///
+    /// Returns the number of ticks that were stepped, which is 0 while the arena is paused
+    pub fn step(&mut self, arena: Pin<&mut Arena>) -> u32 {
+        self.wait();
+
+        // don't count the time spent paused as falling behind
+        if arena.is_paused() {
+            self.reset();
+            return 0;
+        }
+
+        let ticks = self.ticks_due();
+        if ticks != 0 {
+            arena.step(ticks);
+        }
+
+        ticks
+    }
+}
@@ -0,0 +1,217 @@
+//! Labeling the arenas of a process that runs many of them, like a server hosting named matches
+//!
+//! An `ArenaRegistry` owns arenas by label, and records every arena that's added, removed or renamed
+//! so other parts of the process (like metrics, logs or a lobby list) can keep up with them.
+//! There's one registry per pool of arenas, or a process-wide one from `ArenaRegistry::global`.
+
+use crate::sim::Arena;
+use core::pin::Pin;
+use cxx::UniquePtr;
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt, mem,
+    sync::{Mutex, OnceLock},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RegistryEventKind {
+    Added,
+    Removed,
+    /// The arena was renamed from `old_label`
+    Renamed {
+        old_label: String,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A change to the arenas of a registry
+pub struct RegistryEvent {
+    /// The (new) label of the arena
+    pub label: String,
+    pub kind: RegistryEventKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Reasons that an arena can't be renamed
+pub enum RenameError {
+    /// There's no arena with the old label
+    NotFound(String),
+    /// There's already an arena with the new label
+    LabelTaken(String),
+}
+
+impl Error for RenameError {}
+impl fmt::Display for RenameError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(label) => write!(f, "There's no arena labeled {label}."),
+            Self::LabelTaken(label) => write!(f, "There's already an arena labeled {label}."),
+        }
+    }
+}
+
+#[derive(Default)]
+/// Arenas by label, iterated in the order of their labels
+pub struct ArenaRegistry {
+    arenas: BTreeMap<String, UniquePtr<Arena>>,
+    events: Vec<RegistryEvent>,
+}
+
+impl fmt::Debug for ArenaRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArenaRegistry")
+            .field("labels", &self.arenas.keys().collect::<Vec<_>>())
+            .field("events", &self.events)
+            .finish()
+    }
+}
+
+impl ArenaRegistry {
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            arenas: BTreeMap::new(),
+            events: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    /// The registry that's shared by the whole process, which starts empty
+    pub fn global() -> &'static Mutex<Self> {
+        static GLOBAL: OnceLock<Mutex<ArenaRegistry>> = OnceLock::new();
+        GLOBAL.get_or_init(|| Mutex::new(Self::new()))
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.arenas.len()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.arenas.is_empty()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, label: &str) -> bool {
+        self.arenas.contains_key(label)
+    }
+
+    /// Adds the arena with the label, and returns the arena that previously had the label (which counts as removed)
+    pub fn insert<S: Into<String>>(&mut self, label: S, arena: UniquePtr<Arena>) -> Option<UniquePtr<Arena>> {
+        let label = label.into();
+        let old = self.arenas.insert(label.clone(), arena);
+
+        if old.is_some() {
+            self.events.push(RegistryEvent {
+                label: label.clone(),
+                kind: RegistryEventKind::Removed,
+            });
+        }
+        self.events.push(RegistryEvent {
+            label,
+            kind: RegistryEventKind::Added,
+        });
+
+        old
+    }
+
+    /// Removes the arena with the label and gives it back, if there is one
+    pub fn remove(&mut self, label: &str) -> Option<UniquePtr<Arena>> {
+        let arena = self.arenas.remove(label)?;
+        self.events.push(RegistryEvent {
+            label: label.to_string(),
+            kind: RegistryEventKind::Removed,
+        });
+
+        Some(arena)
+    }
+
+    /// Gives the arena with the old label the new label
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there's no arena with the old label, or there's already one with the new label
+    pub fn rename<S: Into<String>>(&mut self, old_label: &str, new_label: S) -> Result<(), RenameError> {
+        let new_label = new_label.into();
+        if self.arenas.contains_key(&new_label) {
+            return Err(RenameError::LabelTaken(new_label));
+        }
+
+        let arena = self
+            .arenas
+            .remove(old_label)
+            .ok_or_else(|| RenameError::NotFound(old_label.to_string()))?;
+        self.arenas.insert(new_label.clone(), arena);
+        self.events.push(RegistryEvent {
+            label: new_label,
+            kind: RegistryEventKind::Renamed {
+                old_label: old_label.to_string(),
+            },
+        });
+
+        Ok(())
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn get(&self, label: &str) -> Option<&Arena> {
+        self.arenas.get(label).and_then(UniquePtr::as_ref)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn get_mut(&mut self, label: &str) -> Option<Pin<&mut Arena>> {
+        self.arenas.get_mut(label).and_then(UniquePtr::as_mut)
+    }
+
+    #[must_use]
+    /// Returns the label of the arena, if it's in the registry
+    ///
+    /// Useful for labeling metrics or logs from code that only has the arena
+    pub fn label_of(&self, arena: &Arena) -> Option<&str> {
+        self.iter()
+            .find(|(_, other)| core::ptr::eq(*other, arena))
+            .map(|(label, _)| label)
+    }
+
+    #[inline]
+    pub fn labels(&self) -> impl Iterator<Item = &str> {
+        self.arenas.keys().map(String::as_str)
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Arena)> {
+        self.arenas
+            .iter()
+            .filter_map(|(label, arena)| Some((label.as_str(), arena.as_ref()?)))
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&str, Pin<&mut Arena>)> {
+        self.arenas
+            .iter_mut()
+            .filter_map(|(label, arena)| Some((label.as_str(), arena.as_mut()?)))
+    }
+
+    /// Returns the arenas that were added, removed or renamed since the last call, in the order they happened
+    pub fn take_events(&mut self) -> Vec<RegistryEvent> {
+        mem::take(&mut self.events)
+    }
+
+    /// Removes every arena
+    pub fn clear(&mut self) {
+        for label in mem::take(&mut self.arenas).into_keys() {
+            self.events.push(RegistryEvent {
+                label,
+                kind: RegistryEventKind::Removed,
+            });
+        }
+    }
+}
@@ -53,9 +53,44 @@ impl Color {
 
 #[derive(Clone, Debug)]
 pub enum Render {
-    Line2D { start: Vec2, end: Vec2, color: Color },
-    Line { start: Vec3, end: Vec3, color: Color },
-    LineStrip { positions: Vec<Vec3>, color: Color },
+    Line2D {
+        start: Vec2,
+        end: Vec2,
+        color: Color,
+    },
+    Line {
+        start: Vec3,
+        end: Vec3,
+        color: Color,
+    },
+    LineStrip {
+        positions: Vec<Vec3>,
+        color: Color,
+    },
+    /// Wireframe sphere
+    Sphere {
+        center: Vec3,
+        radius: f32,
+        color: Color,
+    },
+    /// Text at a position on the screen, from the top left
+    ///
+    /// Only the first `u16::MAX` bytes of the text are sent
+    Text2D {
+        pos: Vec2,
+        text: String,
+        scale: f32,
+        color: Color,
+    },
+    /// Text anchored to a position in the world
+    ///
+    /// Only the first `u16::MAX` bytes of the text are sent
+    Text {
+        pos: Vec3,
+        text: String,
+        scale: f32,
+        color: Color,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -63,3 +98,104 @@ pub enum RenderMessage {
     AddRender(i32, Vec<Render>),
     RemoveRender(i32),
 }
+
+#[derive(Clone, Debug, Default)]
+/// Collects debug primitives for a single render group
+///
+/// Rendering a group replaces everything previously rendered in that group,
+/// so a bot would typically clear and redraw its group every tick.
+pub struct Renderer {
+    pub group_id: i32,
+    renders: Vec<Render>,
+}
+
+impl Renderer {
+    #[inline]
+    #[must_use]
+    pub const fn new(group_id: i32) -> Self {
+        Self {
+            group_id,
+            renders: Vec::new(),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn renders(&self) -> &[Render] {
+        &self.renders
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.renders.is_empty()
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.renders.clear();
+    }
+
+    #[inline]
+    pub fn draw(&mut self, render: Render) {
+        self.renders.push(render);
+    }
+
+    #[inline]
+    pub fn draw_line_2d(&mut self, start: Vec2, end: Vec2, color: Color) {
+        self.draw(Render::Line2D { start, end, color });
+    }
+
+    #[inline]
+    pub fn draw_line(&mut self, start: Vec3, end: Vec3, color: Color) {
+        self.draw(Render::Line { start, end, color });
+    }
+
+    #[inline]
+    /// Draws connected line segments through all of the points, i.e. a planned trajectory
+    pub fn draw_polyline<I: IntoIterator<Item = Vec3>>(&mut self, points: I, color: Color) {
+        self.draw(Render::LineStrip {
+            positions: points.into_iter().collect(),
+            color,
+        });
+    }
+
+    #[inline]
+    pub fn draw_sphere(&mut self, center: Vec3, radius: f32, color: Color) {
+        self.draw(Render::Sphere { center, radius, color });
+    }
+
+    #[inline]
+    pub fn draw_text_2d<S: Into<String>>(&mut self, pos: Vec2, text: S, scale: f32, color: Color) {
+        self.draw(Render::Text2D {
+            pos,
+            text: text.into(),
+            scale,
+            color,
+        });
+    }
+
+    #[inline]
+    pub fn draw_text<S: Into<String>>(&mut self, pos: Vec3, text: S, scale: f32, color: Color) {
+        self.draw(Render::Text {
+            pos,
+            text: text.into(),
+            scale,
+            color,
+        });
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the message that will render everything that was drawn, and clears the renderer
+    pub fn take_message(&mut self) -> RenderMessage {
+        RenderMessage::AddRender(self.group_id, std::mem::take(&mut self.renders))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the message that will remove everything previously rendered in this group
+    pub const fn remove_message(&self) -> RenderMessage {
+        RenderMessage::RemoveRender(self.group_id)
+    }
+}
@@ -0,0 +1,421 @@
+//! A simple on-disk format for recording sequences of game states
+//!
+//! A replay file is a header (`MAGIC` followed by a `VERSION` byte), then the player and team
+//! `Metadata` (see `Metadata::write_to`), followed by the bytes of each `GameState`,
+//! exactly as they are sent to rlviser.
+//! Game states are self-describing in length, so no extra framing is needed.
+//!
+//! Version 1 replays, which don't have the metadata, can still be read.
+//!
+//! With the `zstd` or `lz4` features, the whole file can be compressed, see `ReplayWriter::create_compressed`.
+//! For random access into large replays, see `IndexedReplay` (memory-mapped with the `mmap` feature).
+
+#[cfg(any(feature = "zstd", feature = "lz4"))]
+use crate::compression::{CompressedReader, CompressedWriter, Compression};
+use crate::{
+    bytes::{FromBytes, ToBytes},
+    metadata::Metadata,
+    GameState,
+};
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, ErrorKind, Read, Write},
+    path::Path,
+};
+
+pub const MAGIC: [u8; 4] = *b"RSRP";
+pub const VERSION: u8 = 2;
+
+/// Writes game states to a replay
+pub struct ReplayWriter<W: Write> {
+    writer: W,
+    num_frames: u64,
+}
+
+impl ReplayWriter<BufWriter<File>> {
+    /// Creates (or truncates) the file at the given path and writes the replay header
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be created or written to
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+
+    /// Creates (or truncates) the file at the given path and writes the replay header with the metadata
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be created or written to
+    pub fn create_with_metadata<P: AsRef<Path>>(path: P, metadata: &Metadata) -> io::Result<Self> {
+        Self::with_metadata(BufWriter::new(File::create(path)?), metadata)
+    }
+}
+
+#[cfg(any(feature = "zstd", feature = "lz4"))]
+impl ReplayWriter<CompressedWriter<BufWriter<File>>> {
+    /// Creates (or truncates) the file at the given path and writes the replay header, compressing the whole file
+    ///
+    /// `finish` must be called once the replay is done, or the end of the file is lost
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be created or written to
+    pub fn create_compressed<P: AsRef<Path>>(path: P, compression: Compression) -> io::Result<Self> {
+        Self::create_compressed_with_metadata(path, compression, &Metadata::new())
+    }
+
+    /// Like `create_compressed`, but with the player and team metadata
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be created or written to
+    pub fn create_compressed_with_metadata<P: AsRef<Path>>(
+        path: P,
+        compression: Compression,
+        metadata: &Metadata,
+    ) -> io::Result<Self> {
+        Self::with_metadata(
+            CompressedWriter::new(BufWriter::new(File::create(path)?), compression)?,
+            metadata,
+        )
+    }
+}
+
+#[cfg(any(feature = "zstd", feature = "lz4"))]
+impl<W: Write> ReplayWriter<CompressedWriter<W>> {
+    /// Writes the end of the compressed stream, and returns the underlying writer
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the end of the stream can't be written
+    pub fn finish(self) -> io::Result<W> {
+        let mut writer = self.writer.finish()?;
+        writer.flush()?;
+        Ok(writer)
+    }
+}
+
+impl<W: Write> ReplayWriter<W> {
+    #[inline]
+    /// Writes the replay header to the writer, with empty metadata
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the header can't be written
+    pub fn new(writer: W) -> io::Result<Self> {
+        Self::with_metadata(writer, &Metadata::new())
+    }
+
+    /// Writes the replay header and the player and team metadata to the writer
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the header can't be written
+    pub fn with_metadata(mut writer: W, metadata: &Metadata) -> io::Result<Self> {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        metadata.write_to(&mut writer)?;
+
+        Ok(Self { writer, num_frames: 0 })
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn num_frames(&self) -> u64 {
+        self.num_frames
+    }
+
+    /// Writes the bytes of a game state, as returned by `GameState::to_bytes`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes can't be written
+    pub fn write_bytes(&mut self, game_state: &[u8]) -> io::Result<()> {
+        debug_assert_eq!(game_state.len(), GameState::get_num_bytes(game_state));
+
+        self.writer.write_all(game_state)?;
+        self.num_frames += 1;
+        Ok(())
+    }
+
+    #[inline]
+    /// Writes a game state
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game state can't be written
+    pub fn write_state(&mut self, game_state: &GameState) -> io::Result<()> {
+        self.write_bytes(&game_state.to_bytes())
+    }
+
+    #[inline]
+    /// # Errors
+    ///
+    /// Returns an error if the underlying writer can't be flushed
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Reads and checks the replay header, returning the metadata
+fn read_header<R: Read>(reader: &mut R) -> io::Result<Metadata> {
+    let mut header = [0; MAGIC.len() + 1];
+    reader.read_exact(&mut header)?;
+
+    if header[..MAGIC.len()] != MAGIC {
+        return Err(io::Error::new(ErrorKind::InvalidData, "Not a replay file"));
+    }
+
+    match header[MAGIC.len()] {
+        1 => Ok(Metadata::new()),
+        VERSION => Metadata::read_from(reader),
+        version => Err(io::Error::new(
+            ErrorKind::InvalidData,
+            format!("Unsupported replay version {version}"),
+        )),
+    }
+}
+
+/// Reads game states from a replay, as an iterator
+pub struct ReplayReader<R: Read> {
+    reader: R,
+    buffer: Vec<u8>,
+    metadata: Metadata,
+}
+
+impl ReplayReader<BufReader<File>> {
+    /// Opens the replay at the given path and checks the header
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or isn't a replay
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+#[cfg(any(feature = "zstd", feature = "lz4"))]
+impl ReplayReader<CompressedReader<File>> {
+    /// Opens the replay at the given path and checks the header, decompressing it if needed
+    ///
+    /// Uncompressed replays can be opened with this as well
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or isn't a replay
+    pub fn open_compressed<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::new(CompressedReader::new(File::open(path)?)?)
+    }
+}
+
+impl<R: Read> ReplayReader<R> {
+    /// Reads and checks the replay header
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the header can't be read, or if it isn't a supported replay
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let metadata = read_header(&mut reader)?;
+
+        Ok(Self {
+            reader,
+            buffer: Vec::new(),
+            metadata,
+        })
+    }
+
+    #[inline]
+    #[must_use]
+    /// The player and team metadata of the replay, which is empty for version 1 replays
+    pub const fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Reads the bytes of the next game state, or `None` at the end of the replay
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the replay can't be read or ends in the middle of a game state
+    pub fn next_bytes(&mut self) -> io::Result<Option<&[u8]>> {
+        self.buffer.resize(GameState::MIN_NUM_BYTES, 0);
+
+        let mut num_read = 0;
+        while num_read < GameState::MIN_NUM_BYTES {
+            match self.reader.read(&mut self.buffer[num_read..])? {
+                0 if num_read == 0 => return Ok(None),
+                0 => return Err(ErrorKind::UnexpectedEof.into()),
+                n => num_read += n,
+            }
+        }
+
+        let num_bytes = GameState::get_num_bytes(&self.buffer);
+        self.buffer.resize(num_bytes, 0);
+        self.reader.read_exact(&mut self.buffer[GameState::MIN_NUM_BYTES..])?;
+
+        Ok(Some(&self.buffer))
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> Iterator for ReplayReader<R> {
+    type Item = io::Result<GameState>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_bytes().map(|bytes| bytes.map(GameState::from_bytes)).transpose()
+    }
+}
+
+/// A replay that's fully in memory (or memory-mapped), with an index of where each game state starts
+///
+/// Only the small header of each game state is read when indexing,
+/// so seeking to any tick of a multi-gigabyte recording is quick, and only touches the pages that are needed.
+/// Compressed replays have to be decompressed first.
+///
+/// A game state that's cut off at the end of the replay (e.g. it's still being written) is ignored.
+pub struct IndexedReplay<B: AsRef<[u8]>> {
+    bytes: B,
+    metadata: Metadata,
+    /// The offset of each game state in `bytes`
+    offsets: Vec<usize>,
+    /// The tick count of each game state
+    ticks: Vec<u64>,
+}
+
+#[cfg(feature = "mmap")]
+impl IndexedReplay<memmap2::Mmap> {
+    /// Memory-maps the replay at the given path, then checks the header and indexes it
+    ///
+    /// The file must not be truncated while it's mapped
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be mapped or isn't a replay
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: the replay is only ever read, see the note about truncation above
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+        Self::new(mmap)
+    }
+}
+
+impl<B: AsRef<[u8]>> IndexedReplay<B> {
+    /// Checks the header of the replay and indexes the game states
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes aren't a replay
+    pub fn new(bytes: B) -> io::Result<Self> {
+        let data = bytes.as_ref();
+        let mut reader = data;
+        let metadata = read_header(&mut reader)?;
+
+        let mut offset = data.len() - reader.len();
+        let mut offsets = Vec::new();
+        let mut ticks = Vec::new();
+        while data.len() - offset >= GameState::MIN_NUM_BYTES {
+            let state = &data[offset..];
+            let num_bytes = GameState::get_num_bytes(state);
+            if state.len() < num_bytes {
+                break;
+            }
+
+            offsets.push(offset);
+            ticks.push(GameState::read_tick_count(state));
+            offset += num_bytes;
+        }
+
+        Ok(Self {
+            bytes,
+            metadata,
+            offsets,
+            ticks,
+        })
+    }
+
+    #[inline]
+    #[must_use]
+    /// The player and team metadata of the replay, which is empty for version 1 replays
+    pub const fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    #[inline]
+    #[must_use]
+    /// The number of game states in the replay
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    #[inline]
+    #[must_use]
+    /// The tick count of each game state, in the order they were written
+    pub fn ticks(&self) -> &[u64] {
+        &self.ticks
+    }
+
+    #[must_use]
+    /// The bytes of the game state at the index, as returned by `GameState::to_bytes`
+    pub fn bytes_at(&self, index: usize) -> Option<&[u8]> {
+        let state = &self.bytes.as_ref()[*self.offsets.get(index)?..];
+        Some(&state[..GameState::get_num_bytes(state)])
+    }
+
+    #[inline]
+    #[must_use]
+    /// The game state at the index
+    pub fn state(&self, index: usize) -> Option<GameState> {
+        self.bytes_at(index).map(GameState::from_bytes)
+    }
+
+    #[must_use]
+    /// The index of the last game state with a tick count that's at most `tick_count`
+    ///
+    /// This assumes the tick counts never decrease, i.e. the arena wasn't reset during the recording
+    pub fn index_at_tick(&self, tick_count: u64) -> Option<usize> {
+        self.ticks.partition_point(|&tick| tick <= tick_count).checked_sub(1)
+    }
+
+    #[inline]
+    #[must_use]
+    /// The latest game state at or before `tick_count`, or `None` if the replay starts after it
+    ///
+    /// See `index_at_tick`
+    pub fn state_at_tick(&self, tick_count: u64) -> Option<GameState> {
+        self.state(self.index_at_tick(tick_count)?)
+    }
+
+    /// Iterates over the game states, in the order they were written
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = GameState> + ExactSizeIterator + '_ {
+        (0..self.len()).map(|i| self.state(i).unwrap())
+    }
+
+    /// Iterates over the game states from the latest one at or before `tick_count`, see `index_at_tick`
+    pub fn iter_from_tick(&self, tick_count: u64) -> impl Iterator<Item = GameState> + '_ {
+        (self.index_at_tick(tick_count).unwrap_or_default()..self.len()).map(|i| self.state(i).unwrap())
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> B {
+        self.bytes
+    }
+}
@@ -0,0 +1,457 @@
+//! Match rules on top of an arena: the game clock, kickoff countdowns, and the stoppage after goals
+//!
+//! RocketSim only simulates the physics, so an arena on its own plays forever and never resets after a goal.
+//! `MatchClock` steps the arena like Rocket League's rules would, and reports what happened as `MatchEvent`s:
+//!
+//! - Every kickoff starts with a countdown, during which the arena is paused
+//! - The clock doesn't run until the ball is touched on the kickoff
+//! - After a goal, play stops, and a new kickoff is started once the stoppage is over
+//! - When the clock hits 0:00, the match ends as soon as the ball touches the ground
+//! - If the score is tied at that point, overtime starts with a kickoff, and the next goal wins
+//!
+//! Matches can also be ended early with `Termination`s, e.g. to not waste time on decided games in batch evaluations.
+//! Once a match has ended, `MatchClock::result` says who won and why.
+
+use crate::sim::{Arena, Team};
+use std::{cmp::Ordering, fmt, pin::Pin};
+
+/// How high above its resting height the ball can be and still count as touching the ground
+pub const BALL_GROUND_MARGIN: f32 = 5.;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// The timings of a match, in seconds
+pub struct MatchRules {
+    /// The length of regulation time
+    pub match_length: f32,
+    /// How long the countdown before each kickoff is
+    pub countdown_time: f32,
+    /// How long play is stopped after a goal, before the next kickoff's countdown
+    pub goal_reset_time: f32,
+    /// Whether a tie at the end of regulation goes to overtime, otherwise the match ends in a draw
+    pub overtime: bool,
+    /// The longest that overtime can go on for before the match ends in a draw, or `None` for unlimited overtime
+    pub max_overtime_length: Option<f32>,
+    /// If set, the match ends as soon as a team is ahead by this many goals
+    pub mercy_goal_difference: Option<u32>,
+}
+
+impl Default for MatchRules {
+    /// Rocket League's 5 minute matches, with replays skipped
+    #[inline]
+    fn default() -> Self {
+        Self {
+            match_length: 300.,
+            countdown_time: 3.,
+            goal_reset_time: 3.,
+            overtime: true,
+            max_overtime_length: None,
+            mercy_goal_difference: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatchPhase {
+    /// The countdown before a kickoff, where nothing can move
+    Countdown,
+    /// The countdown is over, but the ball hasn't been touched yet so the clock isn't running
+    Kickoff,
+    /// The ball is in play and the clock is running
+    Playing,
+    /// The clock hit 0:00 while the ball was in the air, so play continues until it touches the ground
+    BallInAir,
+    /// A goal was scored and play is stopped until the next kickoff
+    GoalReset,
+    /// The match is over
+    Ended,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatchEventKind {
+    PhaseChanged {
+        from: MatchPhase,
+        to: MatchPhase,
+    },
+    /// `team` scored a goal
+    Goal {
+        team: Team,
+    },
+    /// The clock hit 0:00
+    ClockExpired,
+    /// Regulation ended in a tie, so overtime was started
+    OvertimeStarted,
+    /// Overtime reached `MatchRules::max_overtime_length` without a goal
+    OvertimeExpired,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MatchEvent {
+    /// The arena's tick count when the event happened
+    pub tick_count: u64,
+    pub kind: MatchEventKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Why a match ended
+pub enum EndReason {
+    /// Regulation time ran out without a tie, or with a tie and overtime disabled
+    Regulation,
+    /// A goal was scored in overtime
+    GoldenGoal,
+    /// Overtime reached `MatchRules::max_overtime_length`
+    OvertimeExpired,
+    /// A team got ahead by `MatchRules::mercy_goal_difference` goals
+    Mercy,
+    /// The termination at this index (in the order they were added) was met
+    Terminated(usize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The outcome of a finished match
+pub struct MatchResult {
+    /// The team with more goals, or `None` for a draw
+    pub winner: Option<Team>,
+    pub blue_score: u32,
+    pub orange_score: u32,
+    pub reason: EndReason,
+    /// Whether the match went to overtime
+    pub overtime: bool,
+    /// The number of ticks from the start of the match to the end, including countdowns and stoppages
+    pub ticks: u64,
+}
+
+/// A condition for ending a match early, checked after every tick
+pub enum Termination {
+    /// Ends the match once a team is ahead by this many goals, same as `MatchRules::mercy_goal_difference`
+    /// but also checked between goals
+    ScoreDifference(u32),
+    /// Ends the match after this many ticks (including countdowns and stoppages)
+    MaxTicks(u64),
+    /// Ends the match once the function returns true
+    Custom(Box<dyn Fn(&MatchClock) -> bool>),
+}
+
+impl fmt::Debug for Termination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ScoreDifference(difference) => f.debug_tuple("ScoreDifference").field(difference).finish(),
+            Self::MaxTicks(ticks) => f.debug_tuple("MaxTicks").field(ticks).finish(),
+            Self::Custom(_) => f.write_str("Custom"),
+        }
+    }
+}
+
+impl Termination {
+    #[must_use]
+    pub fn is_met(&self, clock: &MatchClock) -> bool {
+        match self {
+            Self::ScoreDifference(difference) => clock.scores[0].abs_diff(clock.scores[1]) >= *difference,
+            Self::MaxTicks(ticks) => clock.ticks >= *ticks,
+            Self::Custom(is_met) => is_met(clock),
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Steps an arena with the match rules, see the module docs
+///
+/// The arena should only be stepped through `step` once the match is started,
+/// and it shouldn't have a goal scored callback that resets it.
+pub struct MatchClock {
+    pub rules: MatchRules,
+    terminations: Vec<Termination>,
+    end_reason: Option<EndReason>,
+    /// Ticks since the match was started
+    ticks: u64,
+    phase: MatchPhase,
+    /// Seconds left in the current countdown or stoppage
+    phase_time_left: f32,
+    time_remaining: f32,
+    overtime: bool,
+    overtime_elapsed: f32,
+    scores: [u32; 2],
+    kickoff_tick: u64,
+    events: Vec<MatchEvent>,
+}
+
+impl MatchClock {
+    #[inline]
+    #[must_use]
+    pub const fn new(rules: MatchRules) -> Self {
+        Self {
+            terminations: Vec::new(),
+            end_reason: None,
+            ticks: 0,
+            phase: MatchPhase::Ended,
+            phase_time_left: 0.,
+            time_remaining: rules.match_length,
+            overtime: false,
+            overtime_elapsed: 0.,
+            scores: [0; 2],
+            kickoff_tick: 0,
+            events: Vec::new(),
+            rules,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn with_termination(mut self, termination: Termination) -> Self {
+        self.add_termination(termination);
+        self
+    }
+
+    /// Adds a condition for ending the match early
+    pub fn add_termination(&mut self, termination: Termination) {
+        self.terminations.push(termination);
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn phase(&self) -> MatchPhase {
+        self.phase
+    }
+
+    #[inline]
+    #[must_use]
+    /// Seconds left on the game clock
+    pub const fn time_remaining(&self) -> f32 {
+        self.time_remaining
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn is_overtime(&self) -> bool {
+        self.overtime
+    }
+
+    #[inline]
+    #[must_use]
+    /// Seconds of play in overtime, which counts up like Rocket League's overtime clock
+    pub const fn overtime_elapsed(&self) -> f32 {
+        self.overtime_elapsed
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn score(&self, team: Team) -> u32 {
+        self.scores[team as usize]
+    }
+
+    #[inline]
+    #[must_use]
+    /// Ticks since the match was started, including countdowns and stoppages
+    pub const fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_ended(&self) -> bool {
+        self.phase == MatchPhase::Ended
+    }
+
+    #[must_use]
+    /// The result of the match, or `None` if it hasn't ended
+    pub fn result(&self) -> Option<MatchResult> {
+        let reason = self.end_reason?;
+        let [blue_score, orange_score] = self.scores;
+
+        Some(MatchResult {
+            winner: match blue_score.cmp(&orange_score) {
+                Ordering::Greater => Some(Team::Blue),
+                Ordering::Less => Some(Team::Orange),
+                Ordering::Equal => None,
+            },
+            blue_score,
+            orange_score,
+            reason,
+            overtime: self.overtime,
+            ticks: self.ticks,
+        })
+    }
+
+    fn end(&mut self, arena: Pin<&mut Arena>, reason: EndReason) {
+        self.end_reason = Some(reason);
+        self.set_phase(arena, MatchPhase::Ended);
+    }
+
+    fn set_phase(&mut self, mut arena: Pin<&mut Arena>, phase: MatchPhase) {
+        arena.as_mut().set_paused(matches!(
+            phase,
+            MatchPhase::Countdown | MatchPhase::GoalReset | MatchPhase::Ended
+        ));
+
+        if phase != self.phase {
+            self.events.push(MatchEvent {
+                tick_count: arena.get_tick_count(),
+                kind: MatchEventKind::PhaseChanged {
+                    from: self.phase,
+                    to: phase,
+                },
+            });
+            self.phase = phase;
+        }
+    }
+
+    fn start_kickoff(&mut self, mut arena: Pin<&mut Arena>) {
+        arena.as_mut().reset_to_random_kickoff(None);
+        self.kickoff_tick = arena.get_tick_count();
+        self.phase_time_left = self.rules.countdown_time;
+        self.set_phase(arena, MatchPhase::Countdown);
+    }
+
+    /// Resets the clock and the score, and starts the countdown of the first kickoff
+    pub fn start(&mut self, arena: Pin<&mut Arena>) -> Vec<MatchEvent> {
+        self.time_remaining = self.rules.match_length;
+        self.overtime = false;
+        self.overtime_elapsed = 0.;
+        self.scores = [0; 2];
+        self.ticks = 0;
+        self.end_reason = None;
+        self.start_kickoff(arena);
+        std::mem::take(&mut self.events)
+    }
+
+    fn ball_touched_since_kickoff(&self, mut arena: Pin<&mut Arena>) -> bool {
+        arena.as_mut().get_cars().into_iter().any(|car_id| {
+            let hit_info = arena.as_mut().get_car(car_id).ball_hit_info;
+            hit_info.is_valid && hit_info.tick_count_when_hit >= self.kickoff_tick
+        })
+    }
+
+    fn is_ball_on_ground(mut arena: Pin<&mut Arena>) -> bool {
+        let radius = arena.get_ball_radius();
+        arena.as_mut().get_ball().pos.z <= radius + BALL_GROUND_MARGIN
+    }
+
+    fn push_event(&mut self, arena: &Arena, kind: MatchEventKind) {
+        self.events.push(MatchEvent {
+            tick_count: arena.get_tick_count(),
+            kind,
+        });
+    }
+
+    /// Called when the clock has run out during play, ends regulation once the ball is on the ground
+    fn on_clock_expired(&mut self, mut arena: Pin<&mut Arena>) {
+        if !Self::is_ball_on_ground(arena.as_mut()) {
+            self.set_phase(arena, MatchPhase::BallInAir);
+        } else if self.rules.overtime && self.scores[0] == self.scores[1] {
+            self.overtime = true;
+            self.push_event(&arena, MatchEventKind::OvertimeStarted);
+            self.start_kickoff(arena);
+        } else {
+            self.end(arena, EndReason::Regulation);
+        }
+    }
+
+    /// Called when a goal is scored, returns why the match ended, or `None` if it continues
+    fn on_goal(&self) -> Option<EndReason> {
+        let goal_difference = self.scores[0].abs_diff(self.scores[1]);
+
+        // goals in overtime are sudden death
+        if self.overtime {
+            Some(EndReason::GoldenGoal)
+        } else if self.rules.mercy_goal_difference.is_some_and(|mercy| goal_difference >= mercy) {
+            Some(EndReason::Mercy)
+        } else if self.time_remaining <= 0. {
+            Some(EndReason::Regulation)
+        } else {
+            None
+        }
+    }
+
+    fn tick(&mut self, mut arena: Pin<&mut Arena>) {
+        let dt = 1. / arena.get_tick_rate();
+
+        match self.phase {
+            MatchPhase::Countdown | MatchPhase::GoalReset => {
+                self.phase_time_left -= dt;
+                if self.phase_time_left > 0. {
+                    return;
+                }
+
+                if self.phase == MatchPhase::Countdown {
+                    self.set_phase(arena, MatchPhase::Kickoff);
+                } else {
+                    self.start_kickoff(arena);
+                }
+            }
+            MatchPhase::Kickoff | MatchPhase::Playing | MatchPhase::BallInAir => {
+                arena.as_mut().step(1);
+
+                if self.phase == MatchPhase::Kickoff && self.ball_touched_since_kickoff(arena.as_mut()) {
+                    self.set_phase(arena.as_mut(), MatchPhase::Playing);
+                }
+
+                if arena.is_ball_scored() {
+                    // the goal on the positive y side is orange's
+                    let team = if arena.as_mut().get_ball().pos.y > 0. {
+                        Team::Blue
+                    } else {
+                        Team::Orange
+                    };
+
+                    self.scores[team as usize] += 1;
+                    self.push_event(&arena, MatchEventKind::Goal { team });
+
+                    self.phase_time_left = self.rules.goal_reset_time;
+                    match self.on_goal() {
+                        Some(reason) => self.end(arena, reason),
+                        None => self.set_phase(arena, MatchPhase::GoalReset),
+                    }
+                    return;
+                }
+
+                match self.phase {
+                    MatchPhase::Playing if self.overtime => {
+                        self.overtime_elapsed += dt;
+                        if self
+                            .rules
+                            .max_overtime_length
+                            .is_some_and(|max_length| self.overtime_elapsed >= max_length)
+                        {
+                            self.push_event(&arena, MatchEventKind::OvertimeExpired);
+                            self.end(arena, EndReason::OvertimeExpired);
+                        }
+                    }
+                    MatchPhase::Playing => {
+                        self.time_remaining = (self.time_remaining - dt).max(0.);
+                        if self.time_remaining <= 0. {
+                            self.push_event(&arena, MatchEventKind::ClockExpired);
+                            self.on_clock_expired(arena);
+                        }
+                    }
+                    MatchPhase::BallInAir if Self::is_ball_on_ground(arena.as_mut()) => {
+                        self.on_clock_expired(arena);
+                    }
+                    _ => {}
+                }
+            }
+            MatchPhase::Ended => {}
+        }
+    }
+
+    /// Steps the match by the given number of ticks, returning the events that happened
+    ///
+    /// During countdowns and stoppages the arena is paused, but the ticks still count towards their time.
+    /// Does nothing once the match has ended.
+    pub fn step(&mut self, mut arena: Pin<&mut Arena>, ticks: u32) -> Vec<MatchEvent> {
+        for _ in 0..ticks {
+            if self.is_ended() {
+                break;
+            }
+
+            self.tick(arena.as_mut());
+            self.ticks += 1;
+
+            if !self.is_ended() {
+                if let Some(i) = self.terminations.iter().position(|termination| termination.is_met(self)) {
+                    self.end(arena.as_mut(), EndReason::Terminated(i));
+                }
+            }
+        }
+
+        std::mem::take(&mut self.events)
+    }
+}
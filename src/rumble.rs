@@ -0,0 +1,495 @@
+//! A Rumble item layer on top of the regular simulation
+//!
+//! RocketSim doesn't simulate Rumble, so items are implemented here by state-setting the arena.
+//! Items are given to each car on a timer, and can be activated at any time to apply their effect.
+//!
+//! Only items that can be reproduced with state-setting alone are supported.
+//! The grappling hook, tornado, disruptor and power hitter are not.
+
+use crate::{
+    math::Vec3,
+    sim::{Arena, BallState, CarState, Team},
+    GameState,
+};
+use core::pin::Pin;
+use std::{collections::BTreeMap, error::Error, fmt};
+
+/// Seconds after spawning (or using an item) before a car is given a new item
+pub const ITEM_GIVE_DELAY: f32 = 10.;
+/// Items that target the ball can only be used when the ball is within this distance of the car
+pub const BALL_ITEM_RANGE: f32 = 2500.;
+/// Items that target a car can only be used on opponents within this distance
+pub const CAR_ITEM_RANGE: f32 = 2500.;
+/// Horizontal velocity change applied to a car that is hit by a boot
+pub const BOOT_VEL_CHANGE: f32 = 2000.;
+/// Upwards velocity change applied to a car that is hit by a boot
+pub const BOOT_UP_VEL_CHANGE: f32 = 700.;
+/// Speed of the ball after it's hit by a haymaker
+pub const HAYMAKER_BALL_SPEED: f32 = 3000.;
+/// Speed of the ball towards the car after it's pulled by a plunger
+pub const PLUNGER_BALL_SPEED: f32 = 2500.;
+/// Maximum number of seconds the ball stays frozen for, unless it's touched first
+pub const FREEZE_DURATION: f32 = 4.;
+/// Number of seconds that spikes stay active for
+pub const SPIKES_DURATION: f32 = 12.;
+/// The ball sticks to a car with spikes when it's within this distance of the car's center
+pub const SPIKES_ATTACH_DISTANCE: f32 = 200.;
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RumbleItem {
+    /// Freezes the ball in place until it's touched
+    BallFreezer,
+    /// Kicks the nearest opponent away
+    Boot,
+    /// Punches the ball away from the car
+    Haymaker,
+    /// Pulls the ball towards the car
+    Plunger,
+    /// Makes the ball stick to the car until it dodges
+    Spikes,
+    /// Swaps places with the nearest opponent
+    Swapper,
+}
+
+impl RumbleItem {
+    pub const ALL: [Self; 6] = [
+        Self::BallFreezer,
+        Self::Boot,
+        Self::Haymaker,
+        Self::Plunger,
+        Self::Spikes,
+        Self::Swapper,
+    ];
+
+    #[inline]
+    #[must_use]
+    /// Returns what the item needs to be in range of to be used, or `None` if it can always be used
+    pub const fn target_kind(self) -> Option<TargetKind> {
+        match self {
+            Self::BallFreezer | Self::Haymaker | Self::Plunger => Some(TargetKind::Ball),
+            Self::Boot | Self::Swapper => Some(TargetKind::Car),
+            Self::Spikes => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum TargetKind {
+    Ball,
+    Car,
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum ItemTarget {
+    Ball,
+    Car(u32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RumbleEventKind {
+    ItemGiven {
+        item: RumbleItem,
+    },
+    ItemUsed {
+        item: RumbleItem,
+        target: Option<ItemTarget>,
+    },
+    /// The effect of an item that lasts over time (freezer or spikes) ended
+    ItemExpired {
+        item: RumbleItem,
+    },
+    /// The ball stuck to the car's spikes
+    BallAttached,
+    /// The ball came off of the car's spikes
+    BallReleased,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RumbleEvent {
+    /// The tick count of the arena when the event happened
+    pub tick_count: u64,
+    /// The car that was given or used the item
+    pub car_id: u32,
+    pub kind: RumbleEventKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ActivationError {
+    /// The car isn't holding an item (or isn't in the arena)
+    NoItem(u32),
+    /// There was nothing in range for the item to be used on
+    NoTarget(RumbleItem),
+}
+
+impl Error for ActivationError {}
+impl fmt::Display for ActivationError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoItem(car_id) => write!(f, "Car {car_id} isn't holding an item."),
+            Self::NoTarget(item) => write!(f, "Nothing in range to use {item:?} on."),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct CarItems {
+    held: Option<RumbleItem>,
+    give_timer: f32,
+    spikes_timer: f32,
+}
+
+impl Default for CarItems {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            held: None,
+            give_timer: ITEM_GIVE_DELAY,
+            spikes_timer: 0.,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Freeze {
+    car_id: u32,
+    timer: f32,
+    last_hit_tick: u64,
+    pos: Vec3,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Spiked {
+    car_id: u32,
+    /// Position of the ball relative to the car, in the car's local axes
+    local_offset: Vec3,
+}
+
+#[derive(Clone, Debug)]
+/// Tracks the items of every car in an arena and applies their effects
+///
+/// Call `update` after every call to `step` (stepping one tick at a time is the most accurate),
+/// and `activate` whenever a car wants to use its item.
+pub struct Rumble {
+    cars: BTreeMap<u32, CarItems>,
+    item_pool: Vec<RumbleItem>,
+    rng_state: u64,
+    last_tick_count: Option<u64>,
+    freeze: Option<Freeze>,
+    spiked: Option<Spiked>,
+}
+
+impl Rumble {
+    #[must_use]
+    /// Creates a new item layer that gives out all supported items, using the given seed to pick them
+    pub fn new(seed: u64) -> Self {
+        Self::with_item_pool(seed, RumbleItem::ALL.to_vec())
+    }
+
+    #[must_use]
+    /// Creates a new item layer that only gives out items from the given pool
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pool is empty
+    pub fn with_item_pool(seed: u64, item_pool: Vec<RumbleItem>) -> Self {
+        assert!(!item_pool.is_empty(), "The item pool can't be empty");
+
+        Self {
+            cars: BTreeMap::new(),
+            item_pool,
+            // xorshift gets stuck at 0
+            rng_state: seed.max(1),
+            last_tick_count: None,
+            freeze: None,
+            spiked: None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn held_item(&self, car_id: u32) -> Option<RumbleItem> {
+        self.cars.get(&car_id).and_then(|car| car.held)
+    }
+
+    /// Gives the car an item right away, replacing whatever it was holding
+    pub fn give_item(&mut self, car_id: u32, item: RumbleItem) {
+        self.cars.entry(car_id).or_default().held = Some(item);
+    }
+
+    fn next_item(&mut self) -> RumbleItem {
+        self.rng_state ^= self.rng_state << 13;
+        self.rng_state ^= self.rng_state >> 7;
+        self.rng_state ^= self.rng_state << 17;
+
+        self.item_pool[(self.rng_state % self.item_pool.len() as u64) as usize]
+    }
+
+    fn last_ball_hit_tick(game_state: &GameState) -> u64 {
+        game_state
+            .cars
+            .iter()
+            .filter(|car| car.state.ball_hit_info.is_valid)
+            .map(|car| car.state.ball_hit_info.tick_count_when_hit)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Advances item timers and applies lasting effects, returning the events that happened since the last update
+    pub fn update(&mut self, mut arena: Pin<&mut Arena>) -> Vec<RumbleEvent> {
+        let game_state = arena.as_mut().get_game_state();
+        let tick_count = game_state.tick_count;
+        let dt = self
+            .last_tick_count
+            .map_or(0., |last| tick_count.saturating_sub(last) as f32 / game_state.tick_rate);
+        self.last_tick_count = Some(tick_count);
+
+        let mut events = Vec::new();
+        let mut push = |car_id, kind| {
+            events.push(RumbleEvent {
+                tick_count,
+                car_id,
+                kind,
+            });
+        };
+
+        self.cars.retain(|id, _| game_state.cars.iter().any(|car| car.id == *id));
+        for car in &game_state.cars {
+            self.cars.entry(car.id).or_default();
+        }
+
+        let ids = self.cars.keys().copied().collect::<Vec<_>>();
+        for car_id in ids {
+            let needs_item = {
+                let items = self.cars.get_mut(&car_id).unwrap();
+
+                if items.spikes_timer > 0. {
+                    items.spikes_timer -= dt;
+                    if items.spikes_timer <= 0. {
+                        push(
+                            car_id,
+                            RumbleEventKind::ItemExpired {
+                                item: RumbleItem::Spikes,
+                            },
+                        );
+                    }
+                }
+
+                if items.held.is_none() {
+                    items.give_timer -= dt;
+                }
+
+                items.held.is_none() && items.give_timer <= 0.
+            };
+
+            if needs_item {
+                let item = self.next_item();
+                self.cars.get_mut(&car_id).unwrap().held = Some(item);
+                push(car_id, RumbleEventKind::ItemGiven { item });
+            }
+        }
+
+        if let Some(freeze) = &mut self.freeze {
+            freeze.timer -= dt;
+
+            if freeze.timer <= 0. || Self::last_ball_hit_tick(&game_state) > freeze.last_hit_tick {
+                push(
+                    freeze.car_id,
+                    RumbleEventKind::ItemExpired {
+                        item: RumbleItem::BallFreezer,
+                    },
+                );
+                self.freeze = None;
+            } else {
+                arena.as_mut().set_ball(BallState {
+                    pos: freeze.pos,
+                    vel: Vec3::ZERO,
+                    ang_vel: Vec3::ZERO,
+                    ..game_state.ball
+                });
+            }
+        }
+
+        self.update_spikes(arena, &game_state, &mut push);
+
+        events
+    }
+
+    fn update_spikes(
+        &mut self,
+        arena: Pin<&mut Arena>,
+        game_state: &GameState,
+        push: &mut impl FnMut(u32, RumbleEventKind),
+    ) {
+        let has_spikes = |car_id| self.cars.get(&car_id).is_some_and(|items| items.spikes_timer > 0.);
+        let find_car = |car_id| game_state.cars.iter().find(|car| car.id == car_id).map(|car| &car.state);
+
+        if let Some(spiked) = self.spiked {
+            match find_car(spiked.car_id) {
+                Some(car) if has_spikes(spiked.car_id) && !car.is_demoed && !car.is_flipping => {
+                    arena.set_ball(BallState {
+                        pos: car.pos + to_world(car, spiked.local_offset),
+                        vel: car.vel,
+                        ang_vel: Vec3::ZERO,
+                        ..game_state.ball
+                    });
+                }
+                _ => {
+                    push(spiked.car_id, RumbleEventKind::BallReleased);
+                    self.spiked = None;
+                }
+            }
+
+            return;
+        }
+
+        let ball_pos = game_state.ball.pos;
+        let attached = game_state
+            .cars
+            .iter()
+            .filter(|car| has_spikes(car.id) && !car.state.is_demoed)
+            .map(|car| (car, (ball_pos - car.state.pos).length()))
+            .filter(|(_, dist)| *dist < SPIKES_ATTACH_DISTANCE)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+
+        if let Some((car, _)) = attached {
+            self.spiked = Some(Spiked {
+                car_id: car.id,
+                local_offset: to_local(&car.state, ball_pos - car.state.pos),
+            });
+            push(car.id, RumbleEventKind::BallAttached);
+        }
+    }
+
+    /// Uses the car's held item on the nearest valid target
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the car isn't holding an item, or if there's nothing in range to use it on.
+    /// The car keeps its item if there was nothing in range.
+    pub fn activate(&mut self, mut arena: Pin<&mut Arena>, car_id: u32) -> Result<RumbleEvent, ActivationError> {
+        let item = self.held_item(car_id).ok_or(ActivationError::NoItem(car_id))?;
+
+        let game_state = arena.as_mut().get_game_state();
+        let Some(car) = game_state.cars.iter().find(|car| car.id == car_id) else {
+            return Err(ActivationError::NoItem(car_id));
+        };
+        let ball = game_state.ball;
+
+        let target = match item.target_kind() {
+            Some(TargetKind::Ball) if (ball.pos - car.state.pos).length() <= BALL_ITEM_RANGE => Some(ItemTarget::Ball),
+            Some(TargetKind::Car) => Self::nearest_opponent(&game_state, car.team, car.state.pos).map(ItemTarget::Car),
+            Some(_) => None,
+            None => return Ok(self.use_item(arena, &game_state, car_id, item, None)),
+        };
+
+        let Some(target) = target else {
+            return Err(ActivationError::NoTarget(item));
+        };
+
+        Ok(self.use_item(arena, &game_state, car_id, item, Some(target)))
+    }
+
+    fn nearest_opponent(game_state: &GameState, team: Team, pos: Vec3) -> Option<u32> {
+        game_state
+            .cars
+            .iter()
+            .filter(|other| other.team != team && !other.state.is_demoed)
+            .map(|other| (other.id, (other.state.pos - pos).length()))
+            .filter(|(_, dist)| *dist <= CAR_ITEM_RANGE)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(id, _)| id)
+    }
+
+    fn use_item(
+        &mut self,
+        mut arena: Pin<&mut Arena>,
+        game_state: &GameState,
+        car_id: u32,
+        item: RumbleItem,
+        target: Option<ItemTarget>,
+    ) -> RumbleEvent {
+        let car = game_state.cars.iter().find(|car| car.id == car_id).unwrap().state;
+        let ball = game_state.ball;
+
+        match (item, target) {
+            (RumbleItem::BallFreezer, _) => {
+                self.freeze = Some(Freeze {
+                    car_id,
+                    timer: FREEZE_DURATION,
+                    last_hit_tick: Self::last_ball_hit_tick(game_state),
+                    pos: ball.pos,
+                });
+                self.spiked = None;
+
+                arena.set_ball(BallState {
+                    vel: Vec3::ZERO,
+                    ang_vel: Vec3::ZERO,
+                    ..ball
+                });
+            }
+            (RumbleItem::Haymaker | RumbleItem::Plunger, _) => {
+                let away = (ball.pos - car.pos).normalize_or_zero();
+                let vel = if item == RumbleItem::Haymaker {
+                    away * HAYMAKER_BALL_SPEED
+                } else {
+                    away * -PLUNGER_BALL_SPEED
+                };
+
+                self.freeze = None;
+                arena.set_ball(BallState { vel, ..ball });
+            }
+            (RumbleItem::Spikes, _) => {
+                self.cars.entry(car_id).or_default().spikes_timer = SPIKES_DURATION;
+            }
+            (RumbleItem::Boot, Some(ItemTarget::Car(target_id))) => {
+                let mut target = game_state.cars.iter().find(|other| other.id == target_id).unwrap().state;
+
+                let mut away = target.pos - car.pos;
+                away.z = 0.;
+                target.vel = target.vel + away.normalize_or_zero() * BOOT_VEL_CHANGE + Vec3::Z * BOOT_UP_VEL_CHANGE;
+                target.is_on_ground = false;
+
+                let _ = arena.set_car(target_id, target);
+            }
+            (RumbleItem::Swapper, Some(ItemTarget::Car(target_id))) => {
+                let target = game_state.cars.iter().find(|other| other.id == target_id).unwrap().state;
+
+                let swapped = |state: CarState, with: CarState| CarState {
+                    pos: with.pos,
+                    rot_mat: with.rot_mat,
+                    vel: with.vel,
+                    ang_vel: with.ang_vel,
+                    ..state
+                };
+
+                let _ = arena.as_mut().set_car(car_id, swapped(car, target));
+                let _ = arena.set_car(target_id, swapped(target, car));
+            }
+            (RumbleItem::Boot | RumbleItem::Swapper, _) => unreachable!("{item:?} always has a car target"),
+        }
+
+        let items = self.cars.entry(car_id).or_default();
+        items.held = None;
+        items.give_timer = ITEM_GIVE_DELAY;
+
+        RumbleEvent {
+            tick_count: game_state.tick_count,
+            car_id,
+            kind: RumbleEventKind::ItemUsed { item, target },
+        }
+    }
+}
+
+fn to_local(car: &CarState, vec: Vec3) -> Vec3 {
+    Vec3::new(
+        vec.dot(car.rot_mat.forward),
+        vec.dot(car.rot_mat.right),
+        vec.dot(car.rot_mat.up),
+    )
+}
+
+fn to_world(car: &CarState, local: Vec3) -> Vec3 {
+    car.rot_mat.forward * local.x + car.rot_mat.right * local.y + car.rot_mat.up * local.z
+}
@@ -0,0 +1,481 @@
+use crate::{
+    consts,
+    field::Field,
+    math::{Angle, Vec3},
+    sim::{Arena, ArenaConfig, BallState, CarConfig, CarState, DemoMode, GameMode, Team},
+    targets::Region,
+};
+use core::pin::Pin;
+use cxx::UniquePtr;
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fmt, fs, io, path::Path};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CarPreset {
+    #[default]
+    Octane,
+    Dominus,
+    Plank,
+    Breakout,
+    Hybrid,
+    Merc,
+}
+
+impl CarPreset {
+    #[must_use]
+    pub fn config(self) -> &'static CarConfig {
+        match self {
+            Self::Octane => CarConfig::octane(),
+            Self::Dominus => CarConfig::dominus(),
+            Self::Plank => CarConfig::plank(),
+            Self::Breakout => CarConfig::breakout(),
+            Self::Hybrid => CarConfig::hybrid(),
+            Self::Merc => CarConfig::merc(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+/// A rotation, in radians
+pub struct Rotation {
+    pub yaw: f32,
+    pub pitch: f32,
+    pub roll: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioCar {
+    pub team: Team,
+    #[serde(default)]
+    pub config: CarPreset,
+    pub pos: Vec3,
+    #[serde(default)]
+    pub rot: Rotation,
+    #[serde(default)]
+    pub vel: Vec3,
+    #[serde(default)]
+    pub ang_vel: Vec3,
+    #[serde(default = "default_boost")]
+    pub boost: f32,
+}
+
+const fn default_boost() -> f32 {
+    consts::BOOST_SPAWN_AMOUNT
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScenarioBall {
+    pub pos: Vec3,
+    pub vel: Vec3,
+    pub ang_vel: Vec3,
+}
+
+impl Default for ScenarioBall {
+    #[inline]
+    fn default() -> Self {
+        let ball = BallState::default();
+
+        Self {
+            pos: ball.pos,
+            vel: ball.vel,
+            ang_vel: ball.ang_vel,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+/// Overrides for the game mode's default mutators, anything left as `None` is unchanged
+pub struct ScenarioMutators {
+    pub gravity: Option<Vec3>,
+    pub ball_max_speed: Option<f32>,
+    pub boost_used_per_second: Option<f32>,
+    pub unlimited_flips: Option<bool>,
+    pub unlimited_double_jumps: Option<bool>,
+    pub demo_mode: Option<DemoMode>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Condition {
+    /// A goal is scored, by the given team or by either team
+    Goal {
+        #[serde(default)]
+        team: Option<Team>,
+    },
+    /// The ball is touched, by the given team or by either team
+    BallTouched {
+        #[serde(default)]
+        team: Option<Team>,
+    },
+    BallInRegion {
+        region: Region,
+    },
+    /// The car at the given index in the scenario's list of cars is in the region
+    CarInRegion {
+        car: usize,
+        region: Region,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Scenario {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub game_mode: GameMode,
+    #[serde(default = "default_tick_rate")]
+    pub tick_rate: u8,
+    #[serde(default)]
+    pub cars: Vec<ScenarioCar>,
+    #[serde(default)]
+    pub ball: ScenarioBall,
+    #[serde(default)]
+    pub mutators: ScenarioMutators,
+    /// Seconds before the scenario is failed
+    pub time_limit: f32,
+    /// The scenario succeeds when any of these are met
+    pub success: Vec<Condition>,
+    /// The scenario fails when any of these are met, checked before the success conditions
+    #[serde(default)]
+    pub failure: Vec<Condition>,
+}
+
+const fn default_tick_rate() -> u8 {
+    120
+}
+
+#[derive(Debug)]
+pub enum ScenarioError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Toml(toml::de::Error),
+    /// The scenario was parsed, but doesn't make sense
+    Invalid(String),
+}
+
+impl Error for ScenarioError {}
+impl fmt::Display for ScenarioError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Failed to read scenario: {e}"),
+            Self::Json(e) => write!(f, "Failed to parse scenario: {e}"),
+            Self::Toml(e) => write!(f, "Failed to parse scenario: {e}"),
+            Self::Invalid(reason) => write!(f, "Invalid scenario: {reason}"),
+        }
+    }
+}
+
+impl From<io::Error> for ScenarioError {
+    #[inline]
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for ScenarioError {
+    #[inline]
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+impl From<toml::de::Error> for ScenarioError {
+    #[inline]
+    fn from(e: toml::de::Error) -> Self {
+        Self::Toml(e)
+    }
+}
+
+impl Scenario {
+    /// # Errors
+    ///
+    /// Returns an error if the JSON can't be parsed, or if the scenario isn't valid
+    pub fn from_json(json: &str) -> Result<Self, ScenarioError> {
+        let scenario: Self = serde_json::from_str(json)?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if the TOML can't be parsed, or if the scenario isn't valid
+    pub fn from_toml(toml: &str) -> Result<Self, ScenarioError> {
+        let scenario: Self = toml::from_str(toml)?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    /// Checks that the scenario can be run
+    ///
+    /// # Errors
+    ///
+    /// Returns `ScenarioError::Invalid` with the reason if it can't
+    pub fn validate(&self) -> Result<(), ScenarioError> {
+        if !(Arena::MIN_TICK_RATE..=Arena::MAX_TICK_RATE).contains(&self.tick_rate) {
+            return Err(ScenarioError::Invalid(format!(
+                "tick rate {} is out of range",
+                self.tick_rate
+            )));
+        }
+
+        if self.time_limit.is_nan() || self.time_limit <= 0. {
+            return Err(ScenarioError::Invalid("the time limit must be positive".to_string()));
+        }
+
+        if self.success.is_empty() {
+            return Err(ScenarioError::Invalid("there are no success conditions".to_string()));
+        }
+
+        for condition in self.success.iter().chain(&self.failure) {
+            if let Condition::CarInRegion { car, .. } = condition {
+                if *car >= self.cars.len() {
+                    return Err(ScenarioError::Invalid(format!("there is no car at index {car}")));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[must_use]
+    /// Creates an arena that is set up for the scenario
+    pub fn runner(self) -> ScenarioRunner {
+        ScenarioRunner::new(self)
+    }
+}
+
+/// Loads the scenario at the given path and sets up an arena for it
+///
+/// # Errors
+///
+/// Returns an error if the file can't be read or parsed, or if the scenario isn't valid
+pub fn load<P: AsRef<Path>>(path: P) -> Result<ScenarioRunner, ScenarioError> {
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path)?;
+
+    let scenario = if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("toml")) {
+        Scenario::from_toml(&contents)?
+    } else {
+        Scenario::from_json(&contents)?
+    };
+
+    Ok(scenario.runner())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome {
+    /// The success condition at the given index was met
+    Success { condition: usize, time: f32 },
+    /// The failure condition at the given index was met, or `None` if the time ran out
+    Failure { condition: Option<usize>, time: f32 },
+}
+
+impl Outcome {
+    #[inline]
+    #[must_use]
+    pub const fn is_success(&self) -> bool {
+        matches!(self, Self::Success { .. })
+    }
+}
+
+/// An arena that is set up for a scenario, which checks the outcome as it's stepped
+pub struct ScenarioRunner {
+    scenario: Scenario,
+    arena: UniquePtr<Arena>,
+    car_ids: Vec<u32>,
+    start_tick: u64,
+    outcome: Option<Outcome>,
+}
+
+impl ScenarioRunner {
+    #[must_use]
+    pub fn new(scenario: Scenario) -> Self {
+        let mut arena = Arena::new(scenario.game_mode, ArenaConfig::default(), scenario.tick_rate);
+
+        let car_ids = scenario
+            .cars
+            .iter()
+            .map(|car| arena.pin_mut().add_car(car.team, car.config.config()))
+            .collect();
+
+        let mut runner = Self {
+            scenario,
+            arena,
+            car_ids,
+            start_tick: 0,
+            outcome: None,
+        };
+        runner.reset();
+        runner
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn scenario(&self) -> &Scenario {
+        &self.scenario
+    }
+
+    #[inline]
+    #[must_use]
+    /// The IDs of the cars in the arena, in the same order as the scenario's cars
+    pub fn car_ids(&self) -> &[u32] {
+        &self.car_ids
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn arena(&self) -> &Arena {
+        &self.arena
+    }
+
+    #[inline]
+    /// The arena, for setting car controls
+    pub fn arena_mut(&mut self) -> Pin<&mut Arena> {
+        self.arena.pin_mut()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Seconds since the scenario was (re)started
+    pub fn elapsed(&self) -> f32 {
+        self.arena.get_tick_count().saturating_sub(self.start_tick) as f32 / self.arena.get_tick_rate()
+    }
+
+    #[inline]
+    #[must_use]
+    /// The outcome of the scenario, or `None` if it's still running
+    pub const fn outcome(&self) -> Option<Outcome> {
+        self.outcome
+    }
+
+    /// Puts the arena back into the scenario's starting state
+    pub fn reset(&mut self) {
+        let scenario = &self.scenario;
+        let mut arena = self.arena.pin_mut();
+
+        let mut mutators = arena.get_mutator_config();
+        let overrides = scenario.mutators;
+        mutators.gravity = overrides.gravity.unwrap_or(mutators.gravity);
+        mutators.ball_max_speed = overrides.ball_max_speed.unwrap_or(mutators.ball_max_speed);
+        mutators.boost_used_per_second = overrides.boost_used_per_second.unwrap_or(mutators.boost_used_per_second);
+        mutators.unlimited_flips = overrides.unlimited_flips.unwrap_or(mutators.unlimited_flips);
+        mutators.unlimited_double_jumps = overrides.unlimited_double_jumps.unwrap_or(mutators.unlimited_double_jumps);
+        mutators.demo_mode = overrides.demo_mode.unwrap_or(mutators.demo_mode);
+        arena.as_mut().set_mutator_config(mutators);
+
+        for (car, &car_id) in scenario.cars.iter().zip(&self.car_ids) {
+            let rot = car.rot;
+            let state = CarState {
+                pos: car.pos,
+                rot_mat: Angle {
+                    yaw: rot.yaw,
+                    pitch: rot.pitch,
+                    roll: rot.roll,
+                }
+                .to_rotmat(),
+                vel: car.vel,
+                ang_vel: car.ang_vel,
+                boost: car.boost,
+                is_on_ground: car.pos.z < 20.,
+                ..Default::default()
+            };
+
+            arena.as_mut().set_car(car_id, state).unwrap();
+        }
+
+        arena.as_mut().set_ball(BallState {
+            pos: scenario.ball.pos,
+            vel: scenario.ball.vel,
+            ang_vel: scenario.ball.ang_vel,
+            ..Default::default()
+        });
+
+        self.start_tick = arena.get_tick_count();
+        self.outcome = None;
+    }
+
+    fn is_met(&mut self, condition: &Condition) -> bool {
+        let mut arena = self.arena.pin_mut();
+
+        match *condition {
+            Condition::Goal { team } => {
+                let Some(field) = Field::new(self.scenario.game_mode) else {
+                    return false;
+                };
+
+                let ball = arena.get_ball();
+                field
+                    .goal_at(ball.pos)
+                    .is_some_and(|goal| team.is_none_or(|team| team == goal.opposite()))
+            }
+            Condition::BallTouched { team } => self.car_ids.iter().any(|&car_id| {
+                let hit_info = arena.as_mut().get_car(car_id).ball_hit_info;
+                hit_info.is_valid
+                    && hit_info.tick_count_when_hit >= self.start_tick
+                    && team.is_none_or(|team| team == arena.get_car_team(car_id))
+            }),
+            Condition::BallInRegion { region } => region.contains(arena.get_ball().pos),
+            Condition::CarInRegion { car, region } => region.contains(arena.get_car(self.car_ids[car]).pos),
+        }
+    }
+
+    /// Checks whether the scenario is over, without stepping
+    pub fn check(&mut self) -> Option<Outcome> {
+        if self.outcome.is_some() {
+            return self.outcome;
+        }
+
+        let time = self.elapsed();
+
+        for i in 0..self.scenario.failure.len() {
+            let condition = self.scenario.failure[i];
+            if self.is_met(&condition) {
+                self.outcome = Some(Outcome::Failure {
+                    condition: Some(i),
+                    time,
+                });
+                return self.outcome;
+            }
+        }
+
+        for i in 0..self.scenario.success.len() {
+            let condition = self.scenario.success[i];
+            if self.is_met(&condition) {
+                self.outcome = Some(Outcome::Success { condition: i, time });
+                return self.outcome;
+            }
+        }
+
+        if time >= self.scenario.time_limit {
+            self.outcome = Some(Outcome::Failure { condition: None, time });
+        }
+
+        self.outcome
+    }
+
+    /// Steps the arena by one tick and checks the outcome
+    ///
+    /// Does nothing once the scenario is over, call `reset` to try again
+    pub fn step(&mut self) -> Option<Outcome> {
+        if self.outcome.is_none() {
+            self.arena.pin_mut().step(1);
+        }
+
+        self.check()
+    }
+
+    /// Steps until the scenario is over, calling `act` before every tick to set the controls of the cars
+    pub fn run<F: FnMut(Pin<&mut Arena>, &[u32])>(&mut self, mut act: F) -> Outcome {
+        loop {
+            act(self.arena.pin_mut(), &self.car_ids);
+
+            if let Some(outcome) = self.step() {
+                return outcome;
+            }
+        }
+    }
+}
@@ -0,0 +1,36 @@
+//! Training scenarios
+//!
+//! With the `scenario` feature, this includes a file format for shareable training scenarios.
+//! A scenario describes the starting state of an arena (cars, ball, and mutators)
+//! along with the conditions for succeeding or failing, like custom training packs.
+//! Scenarios can be written in TOML (if the file ends in `.toml`) or JSON (otherwise).
+//!
+//! ```toml
+//! name = "Top corner shot"
+//! time_limit = 5.0
+//!
+//! [ball]
+//! pos = { x = 0.0, y = 3000.0, z = 93.15 }
+//!
+//! [[cars]]
+//! team = "Blue"
+//! pos = { x = 0.0, y = 2000.0, z = 17.0 }
+//! rot = { yaw = 1.5708 }
+//!
+//! [[success]]
+//! type = "Goal"
+//! team = "Blue"
+//! ```
+//!
+//! With the `rand` feature, this includes generators for random (but physically valid) starting states,
+//! with a difficulty knob for curriculum training.
+
+#[cfg(feature = "scenario")]
+mod file;
+#[cfg(feature = "rand")]
+mod random;
+
+#[cfg(feature = "scenario")]
+pub use file::*;
+#[cfg(feature = "rand")]
+pub use random::*;
@@ -0,0 +1,262 @@
+use crate::{
+    consts,
+    field::{soccar, Field},
+    math::{Angle, Vec3},
+    sim::{BallState, CarState, Team},
+    GameState,
+};
+use rand::Rng;
+use std::f32::consts::{FRAC_PI_2, PI};
+
+/// Minimum distance between the centers of generated cars and walls
+pub const WALL_MARGIN: f32 = 300.;
+/// Minimum distance between the centers of any two generated cars, or a car and the ball
+pub const MIN_SEPARATION: f32 = 250.;
+/// How many times a random spot is re-rolled before giving up on the separation requirements
+const MAX_ATTEMPTS: usize = 100;
+
+/// A car that is at rest on the ground, with all jump/flip state cleared
+fn grounded_car(pos: Vec3, yaw: f32, boost: f32) -> CarState {
+    CarState {
+        pos: Vec3::new(pos.x, pos.y, consts::CAR_SPAWN_REST_Z),
+        rot_mat: Angle {
+            yaw,
+            pitch: 0.,
+            roll: 0.,
+        }
+        .to_rotmat(),
+        boost: boost.clamp(0., consts::BOOST_MAX),
+        ..Default::default()
+    }
+}
+
+fn random_yaw<R: Rng + ?Sized>(rng: &mut R) -> f32 {
+    rng.gen_range(-PI..PI)
+}
+
+fn yaw_towards(from: Vec3, to: Vec3) -> f32 {
+    (to.y - from.y).atan2(to.x - from.x)
+}
+
+fn is_clear(field: &Field, pos: Vec3, wall_margin: f32, occupied: &[Vec3]) -> bool {
+    field.distance_to_nearest_wall(pos) > wall_margin
+        && occupied.iter().all(|other| {
+            let mut offset = *other - pos;
+            offset.z = 0.;
+            offset.length() > MIN_SEPARATION
+        })
+}
+
+/// Picks a random spot on the ground that is away from the walls and everything in `occupied`
+fn open_spot<R: Rng + ?Sized>(rng: &mut R, field: &Field, wall_margin: f32, occupied: &[Vec3]) -> Vec3 {
+    let max_x = field.extent_x - wall_margin;
+    let max_y = field.extent_y - wall_margin;
+
+    let mut pos = Vec3::ZERO;
+    for _ in 0..MAX_ATTEMPTS {
+        pos = Vec3::new(
+            rng.gen_range(-max_x..max_x),
+            rng.gen_range(-max_y..max_y),
+            consts::CAR_SPAWN_REST_Z,
+        );
+
+        if is_clear(field, pos, wall_margin, occupied) {
+            break;
+        }
+    }
+
+    pos
+}
+
+/// Puts every car but the first at random open spots
+fn scatter_others<R: Rng + ?Sized>(rng: &mut R, field: &Field, state: &mut GameState, mut occupied: Vec<Vec3>) {
+    for car in state.cars.iter_mut().skip(1) {
+        let pos = open_spot(rng, field, WALL_MARGIN, &occupied);
+        occupied.push(pos);
+
+        car.state = grounded_car(pos, random_yaw(rng), rng.gen_range(0.0..=consts::BOOST_MAX));
+    }
+}
+
+/// Generates everything from blue's point of view, then flips it if the first car is on orange
+fn finish(template: &GameState, mut state: GameState) -> GameState {
+    if template.cars.first().is_some_and(|car| car.team == Team::Orange) {
+        state = state.mirrored(true, true);
+    }
+
+    state
+}
+
+fn field_for(template: &GameState) -> Field {
+    Field::new(template.game_mode).unwrap_or(Field::SOCCAR)
+}
+
+/// A random aerial: the ball is in the air and the first car is on the ground, facing it
+///
+/// Higher `difficulty` (`0..=1`) means a higher and faster ball, further away, with less boost.
+/// All other cars are placed randomly on the ground.
+/// `template` should be the arena's current state, so the returned state can be passed to `set_game_state`.
+pub fn random_aerial<R: Rng + ?Sized>(rng: &mut R, template: &GameState, difficulty: f32) -> GameState {
+    let difficulty = difficulty.clamp(0., 1.);
+    let field = field_for(template);
+    let mut state = template.clone();
+
+    let ball_margin = WALL_MARGIN + 500.;
+    let ball_ground = open_spot(rng, &field, ball_margin, &[]);
+    let height = rng.gen_range(400.0..700.) + difficulty * 1200.;
+    let ball_pos = Vec3::new(ball_ground.x, ball_ground.y, height.min(field.height - 300.));
+
+    let drift_dir = random_yaw(rng);
+    state.ball = BallState {
+        pos: ball_pos,
+        vel: Vec3::new(drift_dir.cos(), drift_dir.sin(), 0.) * (difficulty * 800.)
+            + Vec3::Z * rng.gen_range(-100.0..300.) * difficulty,
+        ..Default::default()
+    };
+
+    let mut occupied = vec![ball_ground];
+    if let Some(car) = state.cars.first_mut() {
+        let dist = 800. + difficulty * 2000.;
+
+        let mut pos = ball_ground;
+        for _ in 0..MAX_ATTEMPTS {
+            // approach from roughly behind the ball, relative to the goal that's being attacked
+            let angle = -FRAC_PI_2 + rng.gen_range(-1.0..1.);
+            pos = ball_ground + Vec3::new(angle.cos(), angle.sin(), 0.) * dist;
+
+            if is_clear(&field, pos, WALL_MARGIN, &occupied) {
+                break;
+            }
+        }
+        occupied.push(pos);
+
+        let yaw = yaw_towards(pos, ball_ground) + rng.gen_range(-0.5..=0.5) * difficulty;
+        car.state = grounded_car(pos, yaw, 100. - difficulty * 50.);
+    }
+
+    scatter_others(rng, &field, &mut state, occupied);
+    finish(template, state)
+}
+
+/// A random dribble: the ball is balanced on the roof of the first car
+///
+/// Higher `difficulty` (`0..=1`) means the ball is further from the center of the roof,
+/// and the car and ball are moving faster and less in sync.
+/// All other cars are placed randomly on the ground.
+/// `template` should be the arena's current state, so the returned state can be passed to `set_game_state`.
+pub fn random_dribble<R: Rng + ?Sized>(rng: &mut R, template: &GameState, difficulty: f32) -> GameState {
+    let difficulty = difficulty.clamp(0., 1.);
+    let field = field_for(template);
+    let mut state = template.clone();
+
+    let mut occupied = Vec::new();
+    if let Some(car) = state.cars.first_mut() {
+        let pos = open_spot(rng, &field, WALL_MARGIN + 1000., &[]);
+        occupied.push(pos);
+
+        let mut car_state = grounded_car(pos, random_yaw(rng), rng.gen_range(0.0..=consts::BOOST_MAX));
+        car_state.vel = car_state.rot_mat.forward * rng.gen_range(0.0..=1.) * difficulty * 1200.;
+
+        let hitbox = car.config.hitbox_size;
+        let roof_z = car_state.pos.z + car.config.hitbox_pos_offset.z + hitbox.z / 2.;
+        let offset = car_state.rot_mat.forward * rng.gen_range(-1.0..=1.) * difficulty * hitbox.x / 4.
+            + car_state.rot_mat.right * rng.gen_range(-1.0..=1.) * difficulty * hitbox.y / 4.;
+
+        let wobble = random_yaw(rng);
+        state.ball = BallState {
+            pos: Vec3::new(pos.x, pos.y, roof_z + consts::BALL_COLLISION_RADIUS_SOCCAR + 5.) + offset,
+            vel: car_state.vel + Vec3::new(wobble.cos(), wobble.sin(), 0.) * difficulty * 200.,
+            ..Default::default()
+        };
+
+        car.state = car_state;
+    }
+
+    scatter_others(rng, &field, &mut state, occupied);
+    finish(template, state)
+}
+
+/// A variation of a kickoff, where the cars and ball are nudged from their kickoff positions
+///
+/// `template` should be a state right after `Arena::reset_to_random_kickoff`.
+/// Higher `difficulty` (`0..=1`) means larger nudges, and the ball starts rolling.
+pub fn random_kickoff_variation<R: Rng + ?Sized>(rng: &mut R, template: &GameState, difficulty: f32) -> GameState {
+    let difficulty = difficulty.clamp(0., 1.);
+    let field = field_for(template);
+    let mut state = template.clone();
+
+    let ball_pos = template.ball.pos + Vec3::new(rng.gen_range(-1.0..=1.), rng.gen_range(-1.0..=1.), 0.) * difficulty * 200.;
+    let roll_dir = random_yaw(rng);
+    state.ball = BallState {
+        pos: ball_pos,
+        vel: Vec3::new(roll_dir.cos(), roll_dir.sin(), 0.) * rng.gen_range(0.0..=1.) * difficulty * 300.,
+        ..Default::default()
+    };
+
+    let mut occupied = vec![ball_pos];
+    for car in &mut state.cars {
+        let kickoff = car.state;
+
+        let mut pos = kickoff.pos;
+        for _ in 0..MAX_ATTEMPTS {
+            let nudge = Vec3::new(rng.gen_range(-1.0..=1.), rng.gen_range(-1.0..=1.), 0.) * difficulty * 300.;
+            if is_clear(&field, kickoff.pos + nudge, WALL_MARGIN, &occupied) {
+                pos = kickoff.pos + nudge;
+                break;
+            }
+        }
+        occupied.push(pos);
+
+        let yaw = kickoff.rot_mat.forward.y.atan2(kickoff.rot_mat.forward.x) + rng.gen_range(-0.6..=0.6) * difficulty;
+        car.state = grounded_car(pos, yaw, kickoff.boost);
+    }
+
+    state
+}
+
+/// A random shot on the first car's goal, with that car in position to defend it
+///
+/// Higher `difficulty` (`0..=1`) means a faster and higher shot, with the defender further out of position and with less boost.
+/// All other cars are placed randomly on the ground.
+/// `template` should be the arena's current state, so the returned state can be passed to `set_game_state`.
+pub fn random_defense<R: Rng + ?Sized>(rng: &mut R, template: &GameState, difficulty: f32) -> GameState {
+    let difficulty = difficulty.clamp(0., 1.);
+    let field = field_for(template);
+    let mut state = template.clone();
+
+    let ball_pos = Vec3::new(
+        rng.gen_range(-2500.0..2500.),
+        rng.gen_range(-1000.0..2000.),
+        consts::BALL_COLLISION_RADIUS_SOCCAR + rng.gen_range(0.0..=1.) * difficulty * 600.,
+    );
+    let target = Vec3::new(
+        rng.gen_range(-0.8..0.8) * soccar::GOAL_HALF_WIDTH,
+        -field.extent_y,
+        rng.gen_range(100.0..300.) + difficulty * 200.,
+    );
+
+    let speed = 1000. + difficulty * 2500.;
+    state.ball = BallState {
+        pos: ball_pos,
+        vel: (target - ball_pos).normalize_or_zero() * speed,
+        ..Default::default()
+    };
+
+    let mut occupied = vec![ball_pos];
+    if let Some(car) = state.cars.first_mut() {
+        let side = if rng.gen_bool(0.5) { 1. } else { -1. };
+        let pos = Vec3::new(
+            (target.x + side * (300. + difficulty * 1500.))
+                .clamp(-field.extent_x + WALL_MARGIN, field.extent_x - WALL_MARGIN),
+            -field.extent_y + WALL_MARGIN + rng.gen_range(0.0..500.),
+            consts::CAR_SPAWN_REST_Z,
+        );
+        occupied.push(pos);
+
+        let yaw = FRAC_PI_2 + rng.gen_range(-1.0..=1.) * difficulty * 0.8;
+        car.state = grounded_car(pos, yaw, 100. - difficulty * 70.);
+    }
+
+    scatter_others(rng, &field, &mut state, occupied);
+    finish(template, state)
+}
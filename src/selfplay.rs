@@ -0,0 +1,160 @@
+//! Self-play: a pool of frozen snapshots of a policy that the current policy plays against
+//!
+//! Snapshots are added as `Controller`s (e.g. a callback that runs an older copy of the policy),
+//! an opponent is sampled for every episode, and the result of the episode is reported back
+//! so that the record against each opponent can be tracked.
+
+use crate::input::Controller;
+use rand::Rng;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The result of an episode, from the point of view of the policy that's being trained
+pub enum EpisodeResult {
+    Win,
+    Loss,
+    Draw,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// The results of the episodes played against an opponent
+pub struct Record {
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+impl Record {
+    #[inline]
+    #[must_use]
+    pub const fn episodes(&self) -> u32 {
+        self.wins + self.losses + self.draws
+    }
+
+    #[inline]
+    #[must_use]
+    /// The ratio of wins to episodes, with draws counting as half a win, or `None` if no episodes have been played
+    pub fn win_rate(&self) -> Option<f32> {
+        let episodes = self.episodes();
+        (episodes != 0).then(|| (self.wins as f32 + self.draws as f32 / 2.) / episodes as f32)
+    }
+
+    pub fn add(&mut self, result: EpisodeResult) {
+        match result {
+            EpisodeResult::Win => self.wins += 1,
+            EpisodeResult::Loss => self.losses += 1,
+            EpisodeResult::Draw => self.draws += 1,
+        }
+    }
+}
+
+/// A frozen opponent in the pool
+pub struct Opponent {
+    pub name: String,
+    pub controller: Box<dyn Controller>,
+    /// How likely this opponent is to be sampled, relative to the others
+    pub weight: f32,
+    pub record: Record,
+}
+
+/// A pool of opponents, sampled with their weights
+pub struct OpponentPool<R: Rng> {
+    opponents: Vec<Opponent>,
+    max_opponents: Option<usize>,
+    rng: R,
+}
+
+impl<R: Rng> OpponentPool<R> {
+    #[inline]
+    #[must_use]
+    pub const fn new(rng: R) -> Self {
+        Self {
+            opponents: Vec::new(),
+            max_opponents: None,
+            rng,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Limits the number of opponents, the oldest ones are removed once the pool is full
+    pub const fn with_max_opponents(mut self, max_opponents: usize) -> Self {
+        self.max_opponents = Some(max_opponents);
+        self
+    }
+
+    /// Adds a snapshot to the pool, removing the oldest one if the pool is full
+    pub fn add(&mut self, name: impl Into<String>, controller: impl Controller + 'static, weight: f32) {
+        if let Some(max_opponents) = self.max_opponents {
+            let excess = (self.opponents.len() + 1).saturating_sub(max_opponents.max(1));
+            self.opponents.drain(..excess);
+        }
+
+        self.opponents.push(Opponent {
+            name: name.into(),
+            controller: Box::new(controller),
+            weight,
+            record: Record::default(),
+        });
+    }
+
+    #[inline]
+    #[must_use]
+    /// The opponents, from oldest to newest
+    pub fn opponents(&self) -> &[Opponent] {
+        &self.opponents
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn opponent_mut(&mut self, index: usize) -> Option<&mut Opponent> {
+        self.opponents.get_mut(index)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.opponents.len()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.opponents.is_empty()
+    }
+
+    /// Picks the index of the opponent for the next episode, with the chance of each being proportional to its weight
+    ///
+    /// Opponents with a weight of zero or less are never picked,
+    /// and `None` is returned if there are no opponents that can be picked
+    pub fn sample(&mut self) -> Option<usize> {
+        let total = self.opponents.iter().map(|opponent| opponent.weight.max(0.)).sum::<f32>();
+        if total <= 0. {
+            return None;
+        }
+
+        let mut remaining = self.rng.gen_range(0.0..total);
+        let mut last = None;
+        for (i, opponent) in self.opponents.iter().enumerate() {
+            if opponent.weight <= 0. {
+                continue;
+            }
+
+            if remaining < opponent.weight {
+                return Some(i);
+            }
+
+            remaining -= opponent.weight;
+            last = Some(i);
+        }
+
+        // only reachable through float rounding
+        last
+    }
+
+    /// Records the result of an episode played against the opponent at the index
+    pub fn report(&mut self, index: usize, result: EpisodeResult) {
+        if let Some(opponent) = self.opponents.get_mut(index) {
+            opponent.record.add(result);
+        }
+    }
+}
@@ -25,6 +25,12 @@ mod base {
         #[rust_name = "Arena"]
         type Arenar;
 
+        #[rust_name = "Vec3"]
+        #[namespace = "RocketSim"]
+        type Vec = crate::math::Vec3;
+        #[namespace = "RocketSim"]
+        type RotMat = crate::math::RotMat;
+
         #[namespace = "RocketSim"]
         type CarState = crate::sim::CarState;
         #[namespace = "RocketSim"]
@@ -42,6 +48,21 @@ mod base {
         #[namespace = "RocketSim"]
         type GameMode = crate::sim::GameMode;
         type BoostPadConfig = crate::sim::BoostPadConfig;
+        #[cxx_name = "EGoalZone"]
+        type GoalZone = crate::sim::GoalZone;
+        #[cxx_name = "EStepTimings"]
+        type StepTimings = crate::sim::StepTimings;
+        #[cxx_name = "EPropConfig"]
+        type PropConfig = crate::sim::PropConfig;
+        #[cxx_name = "EPropState"]
+        type PropState = crate::sim::PropState;
+        #[cxx_name = "ETriggerVolume"]
+        type TriggerVolume = crate::sim::TriggerVolume;
+        #[cxx_name = "EArenaConfig"]
+        type ArenaConfig = crate::sim::ArenaConfig;
+
+        #[doc(hidden)]
+        type btDiscreteDynamicsWorld;
 
         #[must_use]
         #[doc(hidden)]
@@ -53,11 +74,30 @@ mod base {
         #[rust_name = "rscc"]
         fn SetCarControls(self: Pin<&mut Arena>, car_id: u32, car_controls: CarControls) -> bool;
 
+        #[doc(hidden)]
+        #[rust_name = "stcc"]
+        fn SetTeamControls(self: Pin<&mut Arena>, team: Team, car_controls: CarControls);
+
+        #[must_use]
+        #[doc(hidden)]
+        #[rust_name = "acwi"]
+        fn AddCarWithId(self: Pin<&mut Arena>, car_id: u32, team: Team, car_config: &CarConfig) -> bool;
+
         #[must_use]
         #[doc(hidden)]
         #[rust_name = "rmvc"]
         fn RemoveCar(self: Pin<&mut Arena>, car_id: u32) -> bool;
 
+        #[must_use]
+        #[doc(hidden)]
+        #[rust_name = "sct"]
+        fn SetCarTeam(self: Pin<&mut Arena>, car_id: u32, team: Team) -> bool;
+
+        #[must_use]
+        #[doc(hidden)]
+        #[rust_name = "scfg"]
+        fn SetCarConfig(self: Pin<&mut Arena>, car_id: u32, config: &CarConfig) -> bool;
+
         #[doc(hidden)]
         #[rust_name = "rtrk"]
         fn ResetToRandomKickoff(self: Pin<&mut Arena>, seed: i32);
@@ -77,20 +117,119 @@ mod base {
         #[rust_name = "ibpgi"]
         fn IsBallProbablyGoingIn(self: &Arena, max_time: f32, extra_margin: f32) -> bool;
 
+        #[doc(hidden)]
+        #[rust_name = "stkr"]
+        fn SetTickRate(self: Pin<&mut Arena>, tick_rate: f32);
+
+        #[doc(hidden)]
+        #[rust_name = "asb"]
+        fn AddStaticBox(self: Pin<&mut Arena>, center: Vec3, half_extents: Vec3, rot_mat: RotMat);
+
+        #[doc(hidden)]
+        #[rust_name = "ass"]
+        fn AddStaticSphere(self: Pin<&mut Arena>, center: Vec3, radius: f32);
+
+        #[doc(hidden)]
+        #[rust_name = "asp"]
+        fn AddStaticPlane(self: Pin<&mut Arena>, normal: Vec3, offset: f32);
+
+        #[doc(hidden)]
+        #[rust_name = "asm"]
+        fn AddStaticMesh(self: Pin<&mut Arena>, vertices: &[Vec3], indices: &[u32]);
+
+        #[must_use]
+        #[doc(hidden)]
+        #[rust_name = "adp"]
+        fn AddProp(self: Pin<&mut Arena>, config: PropConfig, state: PropState) -> u32;
+
+        #[must_use]
+        #[doc(hidden)]
+        #[rust_name = "rmvp"]
+        fn RemoveProp(self: Pin<&mut Arena>, prop_id: u32) -> bool;
+
+        #[must_use]
+        #[doc(hidden)]
+        #[rust_name = "gpc"]
+        fn GetPropConfig(self: &Arena, prop_id: u32) -> PropConfig;
+
+        #[must_use]
+        #[doc(hidden)]
+        #[rust_name = "gp"]
+        fn GetProp(self: &Arena, prop_id: u32) -> PropState;
+
+        #[must_use]
+        #[doc(hidden)]
+        #[rust_name = "sp"]
+        fn SetProp(self: Pin<&mut Arena>, prop_id: u32, state: PropState) -> bool;
+
+        /// Returns all of the prop ids
+        #[must_use]
+        #[cxx_name = "GetProps"]
+        fn get_props(self: &Arena) -> Vec<u32>;
+
+        /// Returns true if there is a prop with the given id
+        #[must_use]
+        #[cxx_name = "HasProp"]
+        fn has_prop(self: &Arena, prop_id: u32) -> bool;
+
+        #[must_use]
+        #[doc(hidden)]
+        #[rust_name = "adtv"]
+        fn AddTriggerVolume(self: Pin<&mut Arena>, volume: TriggerVolume) -> u32;
+
+        #[must_use]
+        #[doc(hidden)]
+        #[rust_name = "rmvtv"]
+        fn RemoveTriggerVolume(self: Pin<&mut Arena>, volume_id: u32) -> bool;
+
+        #[must_use]
+        #[doc(hidden)]
+        #[rust_name = "gtv"]
+        fn GetTriggerVolume(self: &Arena, volume_id: u32) -> TriggerVolume;
+
+        /// Returns all of the trigger volume ids
+        #[must_use]
+        #[cxx_name = "GetTriggerVolumes"]
+        fn get_trigger_volumes(self: &Arena) -> Vec<u32>;
+
+        /// Returns true if there is a trigger volume with the given id
+        #[must_use]
+        #[cxx_name = "HasTriggerVolume"]
+        fn has_trigger_volume(self: &Arena, volume_id: u32) -> bool;
+
         /// Returns all of the car ids"
         #[must_use]
         #[cxx_name = "GetCars"]
         fn get_cars(self: &Arena) -> Vec<u32>;
 
+        /// Returns true if there is a car with the given id
+        #[must_use]
+        #[cxx_name = "HasCar"]
+        fn has_car(self: &Arena, car_id: u32) -> bool;
+
         /// Returns the car state of the car with the given id
         #[must_use]
         #[cxx_name = "GetCar"]
         fn get_car(self: Pin<&mut Arena>, car_id: u32) -> CarState;
 
-        /// Adds a car to the arena with the given team and car config
         #[must_use]
-        #[cxx_name = "AddCar"]
-        fn add_car(self: Pin<&mut Arena>, team: Team, car_config: &CarConfig) -> u32;
+        #[doc(hidden)]
+        #[rust_name = "ac"]
+        fn AddCar(self: Pin<&mut Arena>, team: Team, car_config: &CarConfig) -> u32;
+
+        /// Returns true if the arena has as many cars as it can have, see `set_max_cars`
+        #[must_use]
+        #[cxx_name = "IsFull"]
+        fn is_full(self: &Arena) -> bool;
+
+        #[doc(hidden)]
+        #[rust_name = "smc"]
+        fn SetMaxCars(self: Pin<&mut Arena>, max_cars: usize);
+
+        #[must_use]
+        #[doc(hidden)]
+        #[rust_name = "gmc"]
+        fn GetMaxCars(self: &Arena) -> usize;
 
         /// Returns the ball state
         #[must_use]
@@ -141,11 +280,200 @@ mod base {
             user_data: usize,
         );
 
+        /// Sets the car-car contact callback
+        ///
+        /// Unlike the bump callback, this is called every tick for every pair of cars that are touching,
+        /// with the world-space contact point and the total impulse (in UU) that was applied between them
+        #[cxx_name = "SetCarContactCallback"]
+        fn set_car_contact_callback(
+            self: Pin<&mut Arena>,
+            callback: fn(
+                arena: Pin<&mut Arena>,
+                car_1: u32,
+                car_2: u32,
+                contact_point: Vec3,
+                impulse: f32,
+                user_data: usize,
+            ),
+            user_data: usize,
+        );
+
+        /// Replaces the goals with the given scoring zones
+        ///
+        /// The goal scored callback is called (once) whenever the ball enters one of the zones,
+        /// and the goal zone callback is also called with the index of the zone.
+        /// Passing no zones disables scoring entirely, see `use_default_goal_zones` to restore the normal goals.
+        #[cxx_name = "SetGoalZones"]
+        fn set_goal_zones(self: Pin<&mut Arena>, zones: &[GoalZone]);
+
+        /// Restores the normal goals after `set_goal_zones`
+        #[cxx_name = "UseDefaultGoalZones"]
+        fn use_default_goal_zones(self: Pin<&mut Arena>);
+
+        /// Enables or disables measuring how long each part of `step` takes
+        ///
+        /// Timing adds a small overhead to every tick.
+        /// Bullet's part of the breakdown relies on its built-in profiler hooks, so it's all zero if they were compiled out.
+        #[cxx_name = "SetStepTimingsEnabled"]
+        fn set_step_timings_enabled(self: Pin<&mut Arena>, enabled: bool);
+
+        /// Returns true if the steps are being timed
+        #[must_use]
+        #[cxx_name = "StepTimingsEnabled"]
+        fn step_timings_enabled(self: &Arena) -> bool;
+
+        /// Returns the time spent in each part of `step`, summed over every tick since timing was enabled or reset
+        #[must_use]
+        #[cxx_name = "GetStepTimings"]
+        fn get_step_timings(self: &Arena) -> StepTimings;
+
+        /// Clears the accumulated step timings
+        #[cxx_name = "ResetStepTimings"]
+        fn reset_step_timings(self: Pin<&mut Arena>);
+
+        /// Sets how many of the most recent ticks are kept in the arena's history, see `Arena::history`
+        ///
+        /// Setting this to 0 (the default) disables the history. The history is cleared whenever this is called.
+        #[cxx_name = "SetHistoryCapacity"]
+        fn set_history_capacity(self: Pin<&mut Arena>, ticks: usize);
+
+        /// Returns how many ticks are kept in the arena's history
+        #[must_use]
+        #[cxx_name = "HistoryCapacity"]
+        fn history_capacity(self: &Arena) -> usize;
+
+        /// Clears the arena's history, without changing the capacity
+        #[cxx_name = "ClearHistory"]
+        fn clear_history(self: Pin<&mut Arena>);
+
+        #[must_use]
+        #[doc(hidden)]
+        #[rust_name = "hlen"]
+        fn HistoryLen(self: &Arena) -> usize;
+
+        #[must_use]
+        #[doc(hidden)]
+        #[rust_name = "htick"]
+        fn HistoryTick(self: &Arena, index: usize) -> u64;
+
+        #[must_use]
+        #[doc(hidden)]
+        #[rust_name = "hball"]
+        fn HistoryBall(self: &Arena, index: usize) -> BallState;
+
+        #[must_use]
+        #[doc(hidden)]
+        #[rust_name = "hhcar"]
+        fn HistoryHasCar(self: &Arena, index: usize, car_id: u32) -> bool;
+
+        #[must_use]
+        #[doc(hidden)]
+        #[rust_name = "hcar"]
+        fn HistoryCar(self: &Arena, index: usize, car_id: u32) -> CarState;
+
+        #[must_use]
+        #[doc(hidden)]
+        #[rust_name = "hcars"]
+        fn HistoryCars(self: &Arena, index: usize) -> Vec<u32>;
+
+        #[must_use]
+        #[doc(hidden)]
+        #[rust_name = "hpad"]
+        fn HistoryPad(self: &Arena, index: usize, pad_index: usize) -> BoostPadState;
+
+        #[doc(hidden)]
+        #[rust_name = "rsth"]
+        fn RestoreHistory(self: Pin<&mut Arena>, index: usize);
+
+        #[doc(hidden)]
+        #[rust_name = "phist"]
+        fn PushHistory(
+            self: Pin<&mut Arena>,
+            tick: u64,
+            ball: BallState,
+            car_ids: &[u32],
+            cars: &[CarState],
+            pads: &[BoostPadState],
+        );
+
+        /// Returns the number of custom goal zones
+        #[must_use]
+        #[cxx_name = "NumGoalZones"]
+        fn num_goal_zones(self: &Arena) -> usize;
+
+        /// Returns the custom goal zone with the given index
+        #[must_use]
+        #[cxx_name = "GetGoalZone"]
+        fn get_goal_zone(self: &Arena, index: usize) -> GoalZone;
+
+        /// Returns true if the normal goals have been replaced by `set_goal_zones`
+        #[must_use]
+        #[cxx_name = "HasCustomGoalZones"]
+        fn has_custom_goal_zones(self: &Arena) -> bool;
+
+        /// Sets the goal zone callback, which is called with the index of the custom goal zone that the ball entered
+        ///
+        /// It is not called for the normal goals
+        #[cxx_name = "SetGoalZoneCallback"]
+        fn set_goal_zone_callback(
+            self: Pin<&mut Arena>,
+            callback: fn(arena: Pin<&mut Arena>, team: Team, zone_index: usize, user_data: usize),
+            user_data: usize,
+        );
+
+        /// Sets the trigger volume callback, which is called after a tick for every time the ball or a car
+        /// entered (`entered` is true) or left one of the trigger volumes
+        ///
+        /// `car_id` is 0 for the ball. Only the centers of the ball and the cars are checked.
+        #[cxx_name = "SetTriggerCallback"]
+        fn set_trigger_callback(
+            self: Pin<&mut Arena>,
+            callback: fn(arena: Pin<&mut Arena>, volume_id: u32, car_id: u32, entered: bool, user_data: usize),
+            user_data: usize,
+        );
+
+        /// Sets the force callback, which is called before every tick
+        ///
+        /// This is the place to apply custom forces like wind or a magnet, with `apply_impulse_to_ball` and `apply_impulse_to_car`.
+        /// A force is applied for one tick by applying `force / tick_rate` as an impulse.
+        #[cxx_name = "SetForceCallback"]
+        fn set_force_callback(
+            self: Pin<&mut Arena>,
+            callback: fn(arena: Pin<&mut Arena>, user_data: usize),
+            user_data: usize,
+        );
+
+        /// Applies an impulse to the center of the ball, changing its velocity by `impulse` divided by its mass
+        ///
+        /// The impulse is in UU/s times the mass units of `consts::BALL_MASS_BT`,
+        /// so an impulse of `BALL_MASS_BT * 1000` along an axis adds 1000 UU/s to the ball's velocity.
+        #[cxx_name = "ApplyBallImpulse"]
+        fn apply_impulse_to_ball(self: Pin<&mut Arena>, impulse: Vec3);
+
+        #[must_use]
+        #[doc(hidden)]
+        #[rust_name = "aci"]
+        fn ApplyCarImpulse(self: Pin<&mut Arena>, car_id: u32, impulse: Vec3, point: Vec3) -> bool;
+
+        /// Returns the config that the arena was created with
+        #[must_use]
+        #[cxx_name = "GetArenaConfig"]
+        fn get_arena_config(self: &Arena) -> ArenaConfig;
+
         /// Returns the mutator config
         #[must_use]
         #[cxx_name = "GetMutatorConfig"]
         fn get_mutator_config(self: &Arena) -> MutatorConfig;
 
+        #[doc(hidden)]
+        #[rust_name = "bworld"]
+        fn BulletWorld(self: Pin<&mut Arena>) -> *mut btDiscreteDynamicsWorld;
+
+        #[must_use]
+        #[doc(hidden)]
+        #[rust_name = "bnco"]
+        fn BulletNumCollisionObjects(self: &Arena) -> usize;
+
         /// Sets the mutator config
         #[cxx_name = "SetMutatorConfig"]
         fn set_mutator_config(self: Pin<&mut Arena>, config: MutatorConfig);
@@ -177,12 +505,16 @@ mod base {
         #[cxx_name = "ResetTickCount"]
         fn reset_tick_count(self: Pin<&mut Arena>);
 
+        /// Sets the tick count, e.g. to match a restored game state or an external timeline
+        #[cxx_name = "SetTickCount"]
+        fn set_tick_count(self: Pin<&mut Arena>, tick_count: u64);
+
         /// Returns the tick count
         #[must_use]
         #[cxx_name = "GetTickCount"]
         fn get_tick_count(self: &Arena) -> u64;
 
-        /// Returns the tick rate (i.e. `0.008333` aka `1 / 120`)
+        /// Returns the tick rate in ticks per second (i.e. `120`)
         #[must_use]
         #[cxx_name = "GetTickRate"]
         fn get_tick_rate(self: &Arena) -> f32;
@@ -193,9 +525,23 @@ mod base {
         fn get_game_mode(self: &Arena) -> GameMode;
 
         /// Steps the simulation by the given number of ticks
+        ///
+        /// Does nothing while the arena is paused
         #[cxx_name = "Step"]
         fn step(self: Pin<&mut Arena>, num_ticks: u32);
 
+        /// Pauses or resumes the arena
+        ///
+        /// While paused, `step` doesn't advance the physics, the tick count or any callbacks,
+        /// but the state can still be read and set, and car controls can still be changed.
+        #[cxx_name = "SetPaused"]
+        fn set_paused(self: Pin<&mut Arena>, paused: bool);
+
+        /// Returns true if the arena is paused
+        #[must_use]
+        #[cxx_name = "IsPaused"]
+        fn is_paused(self: &Arena) -> bool;
+
         /// Returns if the ball is within a goal
         #[must_use]
         #[cxx_name = "IsBallScored"]
@@ -87,6 +87,7 @@ mod base {
         is_demoed: bool,
         demo_respawn_timer: f32,
         ball_hit_info: BallHitInfo,
+        /// The controls that were applied on the last tick, after RocketSim clamped them to their valid ranges
         last_controls: CarControls,
     }
 
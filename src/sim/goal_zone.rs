@@ -0,0 +1,26 @@
+#[cxx::bridge]
+mod goalzone {
+    unsafe extern "C++" {
+        include!("arenar.h");
+
+        #[rust_name = "Vec3"]
+        #[namespace = "RocketSim"]
+        type Vec = crate::math::Vec3;
+        #[namespace = "RocketSim"]
+        type Team = crate::sim::Team;
+
+        type EGoalZone;
+    }
+
+    #[derive(Clone, Copy, Debug)]
+    struct EGoalZone {
+        /// The minimum corner of the zone's axis-aligned bounding box
+        min: Vec3,
+        /// The maximum corner of the zone's axis-aligned bounding box
+        max: Vec3,
+        /// The team that scores when the ball enters the zone
+        team: Team,
+    }
+}
+
+pub use goalzone::EGoalZone as GoalZone;
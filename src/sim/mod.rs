@@ -6,7 +6,12 @@ mod boost_pad;
 mod car_config;
 mod car_controls;
 mod car_state;
+mod goal_zone;
 mod mutator_config;
+mod prop;
+mod state_ref;
+mod step_timings;
+mod trigger;
 
 pub use arena::{Arena, GameMode};
 pub use arena_config::{ArenaConfig, ArenaMemWeightMode};
@@ -16,4 +21,9 @@ pub use boost_pad::{BoostPadConfig, BoostPadState};
 pub use car_config::{CarConfig, WheelPairConfig};
 pub use car_controls::CarControls;
 pub use car_state::{CarContact, CarState, Team, WorldContact};
+pub use goal_zone::GoalZone;
 pub use mutator_config::{DemoMode, MutatorConfig};
+pub use prop::{PropConfig, PropShape, PropState};
+pub use state_ref::{BallStateRef, CarStateRef};
+pub use step_timings::StepTimings;
+pub use trigger::{TriggerShape, TriggerVolume};
@@ -0,0 +1,53 @@
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_utils", derive(serde::Serialize, serde::Deserialize))]
+pub enum PropShape {
+    #[default]
+    Sphere,
+    Box,
+}
+
+unsafe impl cxx::ExternType for PropShape {
+    #[allow(unused_attributes)]
+    #[doc(hidden)]
+    type Id = cxx::type_id!("PropShape");
+    type Kind = cxx::kind::Trivial;
+}
+
+#[cxx::bridge]
+mod propstate {
+    unsafe extern "C++" {
+        include!("arenar.h");
+
+        #[rust_name = "Vec3"]
+        #[namespace = "RocketSim"]
+        type Vec = crate::math::Vec3;
+        #[namespace = "RocketSim"]
+        type RotMat = crate::math::RotMat;
+
+        type PropShape = crate::sim::PropShape;
+        type EPropConfig;
+        type EPropState;
+    }
+
+    #[derive(Clone, Copy, Debug)]
+    struct EPropConfig {
+        shape: PropShape,
+        /// Only used by spheres
+        radius: f32,
+        /// Only used by boxes
+        half_extents: Vec3,
+        /// In the same units as `consts::BALL_MASS_BT`
+        mass: f32,
+    }
+
+    #[derive(Clone, Copy, Debug)]
+    struct EPropState {
+        pos: Vec3,
+        rot_mat: RotMat,
+        vel: Vec3,
+        ang_vel: Vec3,
+    }
+}
+
+pub use propstate::{EPropConfig as PropConfig, EPropState as PropState};
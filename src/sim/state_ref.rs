@@ -0,0 +1,213 @@
+#[cxx::bridge]
+mod stateref {
+    unsafe extern "C++" {
+        include!("arenar.h");
+
+        #[rust_name = "Arena"]
+        type Arenar = crate::sim::Arena;
+
+        #[rust_name = "Vec3"]
+        #[namespace = "RocketSim"]
+        type Vec = crate::math::Vec3;
+        #[namespace = "RocketSim"]
+        type RotMat = crate::math::RotMat;
+        #[namespace = "RocketSim"]
+        type CarState = crate::sim::CarState;
+        #[namespace = "RocketSim"]
+        type BallState = crate::sim::BallState;
+
+        #[namespace = "RocketSim"]
+        type Car;
+        #[namespace = "RocketSim"]
+        type Ball;
+
+        #[must_use]
+        #[doc(hidden)]
+        #[rust_name = "gcrf"]
+        fn GetCarRef(self: &Arena, car_id: u32) -> &Car;
+
+        #[must_use]
+        #[doc(hidden)]
+        #[rust_name = "gbrf"]
+        fn GetBallRef(self: &Arena) -> &Ball;
+
+        #[must_use]
+        #[rust_name = "car_internal_state"]
+        fn CarInternalState(car: &Car) -> &CarState;
+
+        #[must_use]
+        #[rust_name = "car_pos"]
+        fn CarPos(car: &Car) -> Vec3;
+
+        #[must_use]
+        #[rust_name = "car_rot_mat"]
+        fn CarRotMat(car: &Car) -> RotMat;
+
+        #[must_use]
+        #[rust_name = "car_vel"]
+        fn CarVel(car: &Car) -> Vec3;
+
+        #[must_use]
+        #[rust_name = "car_ang_vel"]
+        fn CarAngVel(car: &Car) -> Vec3;
+
+        #[must_use]
+        #[rust_name = "ball_internal_state"]
+        fn BallInternalState(ball: &Ball) -> &BallState;
+
+        #[must_use]
+        #[rust_name = "ball_pos"]
+        fn BallPos(ball: &Ball) -> Vec3;
+
+        #[must_use]
+        #[rust_name = "ball_rot_mat"]
+        fn BallRotMat(ball: &Ball) -> RotMat;
+
+        #[must_use]
+        #[rust_name = "ball_vel"]
+        fn BallVel(ball: &Ball) -> Vec3;
+
+        #[must_use]
+        #[rust_name = "ball_ang_vel"]
+        fn BallAngVel(ball: &Ball) -> Vec3;
+    }
+}
+
+use crate::{
+    math::{RotMat, Vec3},
+    sim::{Arena, BallState, CarState},
+};
+use stateref::{Ball, Car};
+
+#[derive(Clone, Copy)]
+/// A read-only view into the state of a car, that borrows the arena instead of copying the state
+pub struct CarStateRef<'a> {
+    id: u32,
+    car: &'a Car,
+}
+
+impl<'a> CarStateRef<'a> {
+    #[inline]
+    /// The car has to be in the arena, see `Arena::car_state_ref`
+    pub(crate) fn new(arena: &'a Arena, car_id: u32) -> Self {
+        debug_assert!(arena.has_car(car_id));
+
+        Self {
+            id: car_id,
+            car: arena.gcrf(car_id),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn id(&self) -> u32 {
+        self.id
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn pos(&self) -> Vec3 {
+        stateref::car_pos(self.car)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn rot_mat(&self) -> RotMat {
+        stateref::car_rot_mat(self.car)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn vel(&self) -> Vec3 {
+        stateref::car_vel(self.car)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn ang_vel(&self) -> Vec3 {
+        stateref::car_ang_vel(self.car)
+    }
+
+    #[inline]
+    #[must_use]
+    /// The rest of the car state (boost, jump/flip state, contacts, etc.) as of the last tick
+    ///
+    /// RocketSim only updates `pos`, `rot_mat`, `vel` and `ang_vel` in here when the full state is fetched,
+    /// so use the methods on the view for those instead.
+    pub fn internal(&self) -> &'a CarState {
+        stateref::car_internal_state(self.car)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Copies the full car state, the same as `Arena::get_car`
+    pub fn to_state(&self) -> CarState {
+        CarState {
+            pos: self.pos(),
+            rot_mat: self.rot_mat(),
+            vel: self.vel(),
+            ang_vel: self.ang_vel(),
+            ..*self.internal()
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+/// A read-only view into the state of the ball, that borrows the arena instead of copying the state
+pub struct BallStateRef<'a> {
+    ball: &'a Ball,
+}
+
+impl<'a> BallStateRef<'a> {
+    #[inline]
+    pub(crate) fn new(arena: &'a Arena) -> Self {
+        Self { ball: arena.gbrf() }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn pos(&self) -> Vec3 {
+        stateref::ball_pos(self.ball)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn rot_mat(&self) -> RotMat {
+        stateref::ball_rot_mat(self.ball)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn vel(&self) -> Vec3 {
+        stateref::ball_vel(self.ball)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn ang_vel(&self) -> Vec3 {
+        stateref::ball_ang_vel(self.ball)
+    }
+
+    #[inline]
+    #[must_use]
+    /// The rest of the ball state (like the heatseeker info) as of the last tick
+    ///
+    /// RocketSim only updates `pos`, `rot_mat`, `vel` and `ang_vel` in here when the full state is fetched,
+    /// so use the methods on the view for those instead.
+    pub fn internal(&self) -> &'a BallState {
+        stateref::ball_internal_state(self.ball)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Copies the full ball state, the same as `Arena::get_ball`
+    pub fn to_state(&self) -> BallState {
+        BallState {
+            pos: self.pos(),
+            rot_mat: self.rot_mat(),
+            vel: self.vel(),
+            ang_vel: self.ang_vel(),
+            ..*self.internal()
+        }
+    }
+}
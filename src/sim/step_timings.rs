@@ -0,0 +1,32 @@
+#[cxx::bridge]
+mod steptimings {
+    unsafe extern "C++" {
+        include!("arenar.h");
+
+        type EStepTimings;
+    }
+
+    #[derive(Clone, Copy, Debug, Default)]
+    struct EStepTimings {
+        /// The number of ticks that were timed
+        ticks: u64,
+        /// Seconds spent in `step`
+        total: f64,
+        /// Seconds spent in RocketSim's own car and ball logic (suspension, boosting, jumping, ball mutators, etc.)
+        cars_and_ball: f64,
+        /// Seconds spent updating bounding boxes and finding overlapping pairs in Bullet
+        broadphase: f64,
+        /// Seconds spent generating contacts between the overlapping pairs in Bullet
+        narrowphase: f64,
+        /// Seconds spent in Bullet's constraint solver
+        solver: f64,
+        /// Seconds spent in the rest of Bullet's step, like integrating the velocities
+        other_bullet: f64,
+        /// Seconds spent in the Rust callbacks
+        ///
+        /// Callbacks that RocketSim calls from inside of Bullet are also included in Bullet's timings
+        callbacks: f64,
+    }
+}
+
+pub use steptimings::EStepTimings as StepTimings;
@@ -0,0 +1,48 @@
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_utils", derive(serde::Serialize, serde::Deserialize))]
+pub enum TriggerShape {
+    #[default]
+    /// An axis-aligned box
+    Aabb,
+    Sphere,
+    /// A box rotated by the volume's `rot_mat`
+    Obb,
+}
+
+unsafe impl cxx::ExternType for TriggerShape {
+    #[allow(unused_attributes)]
+    #[doc(hidden)]
+    type Id = cxx::type_id!("TriggerShape");
+    type Kind = cxx::kind::Trivial;
+}
+
+#[cxx::bridge]
+mod triggervolume {
+    unsafe extern "C++" {
+        include!("arenar.h");
+
+        #[rust_name = "Vec3"]
+        #[namespace = "RocketSim"]
+        type Vec = crate::math::Vec3;
+        #[namespace = "RocketSim"]
+        type RotMat = crate::math::RotMat;
+
+        type TriggerShape = crate::sim::TriggerShape;
+        type ETriggerVolume;
+    }
+
+    #[derive(Clone, Copy, Debug)]
+    struct ETriggerVolume {
+        shape: TriggerShape,
+        center: Vec3,
+        /// Only used by spheres
+        radius: f32,
+        /// Only used by boxes
+        half_extents: Vec3,
+        /// Only used by oriented boxes
+        rot_mat: RotMat,
+    }
+}
+
+pub use triggervolume::ETriggerVolume as TriggerVolume;
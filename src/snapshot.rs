@@ -0,0 +1,191 @@
+//! Saving an entire arena to a file, and loading it back, for checkpointing long experiments
+//! or attaching an exact reproducible arena to a bug report
+//!
+//! A snapshot is a header (`MAGIC` followed by a `VERSION` byte), then:
+//!
+//! - the `ArenaConfig` and `MutatorConfig`
+//! - a byte that's 1 if the arena is paused
+//! - the `GameState`, exactly as it's sent to rlviser, which includes the tick count, tick rate and car configs
+//! - the history capacity as a `u64`, and the number of saved history entries as a `u32`
+//! - for every history entry, from oldest to newest: the tick count, the `BallState`,
+//!   the number of cars followed by each car's id and `CarState`, then the number of pads followed by each `BoostPadState`
+//!
+//! Everything is little-endian.
+//! Callbacks, custom goal zones, props and extra static geometry aren't part of the snapshot,
+//! so they have to be set up again after loading.
+
+use crate::{
+    bytes::{FromBytes, FromBytesExact, ToBytes, ToBytesExact},
+    sim::{Arena, ArenaConfig, BallState, BoostPadState, CarState, MutatorConfig},
+    GameState,
+};
+use cxx::UniquePtr;
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, ErrorKind, Read, Write},
+    path::Path,
+    pin::Pin,
+};
+
+pub const MAGIC: [u8; 4] = *b"RSAS";
+pub const VERSION: u8 = 1;
+
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, error)
+}
+
+fn read_exact<T: FromBytesExact, R: Read>(reader: &mut R) -> io::Result<T> {
+    let mut bytes = vec![0; T::NUM_BYTES];
+    reader.read_exact(&mut bytes)?;
+    Ok(T::from_bytes(&bytes))
+}
+
+fn write_len<W: Write>(writer: &mut W, len: usize) -> io::Result<()> {
+    let len = u32::try_from(len).map_err(|_| io::Error::new(ErrorKind::InvalidInput, "Too many items in the snapshot"))?;
+    writer.write_all(&len.to_le_bytes())
+}
+
+fn read_game_state<R: Read>(reader: &mut R) -> io::Result<GameState> {
+    let mut bytes = vec![0; GameState::MIN_NUM_BYTES];
+    reader.read_exact(&mut bytes)?;
+
+    let num_bytes = GameState::get_num_bytes(&bytes);
+    bytes.resize(num_bytes, 0);
+    reader.read_exact(&mut bytes[GameState::MIN_NUM_BYTES..])?;
+
+    Ok(GameState::from_bytes(&bytes))
+}
+
+impl Arena {
+    /// Writes the arena in the format described in the `snapshot` module documentation
+    ///
+    /// If `include_history` is false, the history capacity is still saved but none of the recorded ticks are
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the snapshot can't be written
+    pub fn write_snapshot<W: Write>(mut self: Pin<&mut Self>, mut writer: W, include_history: bool) -> io::Result<()> {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        writer.write_all(&self.get_arena_config().to_bytes())?;
+        writer.write_all(&self.get_mutator_config().to_bytes())?;
+        writer.write_all(&[u8::from(self.is_paused())])?;
+        writer.write_all(&self.as_mut().get_game_state().to_bytes())?;
+
+        let history = self.history();
+        let num_entries = if include_history { history.len() } else { 0 };
+        writer.write_all(&(self.history_capacity() as u64).to_le_bytes())?;
+        write_len(&mut writer, num_entries)?;
+
+        let num_pads = self.num_pads();
+        for i in 0..num_entries {
+            writer.write_all(&self.htick(i).to_le_bytes())?;
+            writer.write_all(&self.hball(i).to_bytes())?;
+
+            let cars = self.hcars(i);
+            write_len(&mut writer, cars.len())?;
+            for id in cars {
+                writer.write_all(&id.to_le_bytes())?;
+                writer.write_all(&self.hcar(i, id).to_bytes())?;
+            }
+
+            write_len(&mut writer, num_pads)?;
+            for pad in 0..num_pads {
+                writer.write_all(&self.hpad(i, pad).to_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Creates an arena from a snapshot that was written with `write_snapshot`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the snapshot can't be read, isn't a supported snapshot,
+    /// or has an invalid tick rate or duplicate car ids
+    pub fn read_snapshot<R: Read>(mut reader: R) -> io::Result<UniquePtr<Self>> {
+        let mut header = [0; MAGIC.len() + 1];
+        reader.read_exact(&mut header)?;
+
+        if header[..MAGIC.len()] != MAGIC {
+            return Err(invalid_data("Not an arena snapshot"));
+        }
+
+        if header[MAGIC.len()] != VERSION {
+            return Err(invalid_data(format!("Unsupported snapshot version {}", header[MAGIC.len()])));
+        }
+
+        let config = read_exact::<ArenaConfig, _>(&mut reader)?;
+        let mutators = read_exact::<MutatorConfig, _>(&mut reader)?;
+        let paused = read_exact::<bool, _>(&mut reader)?;
+        let game_state = read_game_state(&mut reader)?;
+
+        let tick_rate = game_state.tick_rate.round().clamp(0., f32::from(Self::MAX_NATIVE_TICK_RATE)) as u8;
+        let mut arena = Self::try_new(game_state.game_mode, config, tick_rate).map_err(invalid_data)?;
+        arena.pin_mut().set_tick_rate(game_state.tick_rate).map_err(invalid_data)?;
+        arena.pin_mut().set_mutator_config(mutators);
+
+        for car in &game_state.cars {
+            arena
+                .pin_mut()
+                .add_car_with_id(car.id, car.team, &car.config)
+                .map_err(invalid_data)?;
+        }
+
+        arena.pin_mut().set_game_state(&game_state).map_err(invalid_data)?;
+        arena.pin_mut().set_tick_count(game_state.tick_count);
+        arena.pin_mut().set_paused(paused);
+
+        let capacity = read_exact::<u64, _>(&mut reader)?;
+        let num_entries = read_exact::<u32, _>(&mut reader)?;
+        arena
+            .pin_mut()
+            .set_history_capacity(usize::try_from(capacity).map_err(invalid_data)?);
+
+        let mut ids = Vec::new();
+        let mut cars = Vec::new();
+        let mut pads = Vec::new();
+        for _ in 0..num_entries {
+            let tick = read_exact::<u64, _>(&mut reader)?;
+            let ball = read_exact::<BallState, _>(&mut reader)?;
+
+            ids.clear();
+            cars.clear();
+            for _ in 0..read_exact::<u32, _>(&mut reader)? {
+                ids.push(read_exact::<u32, _>(&mut reader)?);
+                cars.push(read_exact::<CarState, _>(&mut reader)?);
+            }
+
+            pads.clear();
+            for _ in 0..read_exact::<u32, _>(&mut reader)? {
+                pads.push(read_exact::<BoostPadState, _>(&mut reader)?);
+            }
+
+            arena.pin_mut().phist(tick, ball, &ids, &cars, &pads);
+        }
+
+        Ok(arena)
+    }
+
+    /// Creates (or truncates) the file at the given path and writes a snapshot of the arena to it,
+    /// see `write_snapshot`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be created or written to
+    pub fn save<P: AsRef<Path>>(self: Pin<&mut Self>, path: P, include_history: bool) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_snapshot(&mut writer, include_history)?;
+        writer.flush()
+    }
+
+    /// Loads an arena from a snapshot file, see `read_snapshot`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or isn't a valid snapshot
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<UniquePtr<Self>> {
+        Self::read_snapshot(BufReader::new(File::open(path)?))
+    }
+}
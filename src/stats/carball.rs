@@ -0,0 +1,806 @@
+use crate::{
+    consts::{self, boostpads},
+    field::soccar,
+    metadata::Metadata,
+    sim::{BallState, CarState, GameMode, Team},
+    GameState,
+};
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+};
+
+/// Touches by the same car that are closer together than this (in seconds) are merged into one hit
+pub const HIT_MERGE_TIME: f32 = 0.2;
+/// A hit is a dribble if the same car also made the previous hit, at most this many seconds ago
+pub const DRIBBLE_TIME: f32 = 1.;
+/// A hit is a shot (or a save, if it stops one) if the ball is heading into the net,
+/// and would reach it within this many seconds
+pub const SHOT_TIME: f32 = 2.;
+/// Cars above this height are "high in the air", same as carball
+pub const HIGH_AIR_Z: f32 = soccar::GOAL_HEIGHT;
+/// A hit is an aerial if the car isn't on the ground and the ball is above this height
+pub const AERIAL_BALL_Z: f32 = 300.;
+/// Cars within this distance of a side or back wall are "near a wall"
+pub const NEAR_WALL_DISTANCE: f32 = 500.;
+/// Cars within this distance (in both X and Y) of a corner are "in a corner"
+pub const CORNER_DISTANCE: f32 = 1000.;
+/// Cars with less boost than this are "low on boost"
+pub const LOW_BOOST: f32 = 25.;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BoostStats {
+    /// Total boost used
+    pub boost_usage: f32,
+    pub num_small_boosts: u32,
+    pub num_large_boosts: u32,
+    /// Boost from pickups that went over the maximum
+    pub wasted_collection: f32,
+    /// Pads picked up in the opponent's half
+    pub num_stolen_boosts: u32,
+    pub time_full_boost: f32,
+    pub time_low_boost: f32,
+    pub time_no_boost: f32,
+    /// The sum of the boost amount times the elapsed time, for the average boost level
+    boost_time: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+/// Seconds spent in each part of the field, relative to the car's team
+pub struct PositionalTendencies {
+    pub time_on_ground: f32,
+    pub time_low_in_air: f32,
+    pub time_high_in_air: f32,
+    pub time_in_defending_half: f32,
+    pub time_in_attacking_half: f32,
+    pub time_in_defending_third: f32,
+    pub time_in_neutral_third: f32,
+    pub time_in_attacking_third: f32,
+    /// Between the ball and the car's own net
+    pub time_behind_ball: f32,
+    pub time_in_front_of_ball: f32,
+    pub time_near_wall: f32,
+    pub time_in_corner: f32,
+    pub time_on_wall: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HitCounts {
+    pub total_hits: u32,
+    pub total_passes: u32,
+    pub total_shots: u32,
+    pub total_aerials: u32,
+    pub total_dribbles: u32,
+    pub total_saves: u32,
+    pub total_goals: u32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Possession {
+    /// Seconds during which this car made the last hit
+    pub possession_time: f32,
+    /// Hits that were followed by a hit from the other team
+    pub turnovers: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlayerStats {
+    pub id: u32,
+    pub team: Team,
+    /// Seconds that the car was in the game and not demoed
+    pub time_in_game: f32,
+    pub distance_traveled: f32,
+    pub boost: BoostStats,
+    pub positional_tendencies: PositionalTendencies,
+    pub hit_counts: HitCounts,
+    pub possession: Possession,
+}
+
+impl PlayerStats {
+    #[inline]
+    #[must_use]
+    const fn new(id: u32, team: Team) -> Self {
+        Self {
+            id,
+            team,
+            time_in_game: 0.,
+            distance_traveled: 0.,
+            boost: BoostStats {
+                boost_usage: 0.,
+                num_small_boosts: 0,
+                num_large_boosts: 0,
+                wasted_collection: 0.,
+                num_stolen_boosts: 0,
+                time_full_boost: 0.,
+                time_low_boost: 0.,
+                time_no_boost: 0.,
+                boost_time: 0.,
+            },
+            positional_tendencies: PositionalTendencies {
+                time_on_ground: 0.,
+                time_low_in_air: 0.,
+                time_high_in_air: 0.,
+                time_in_defending_half: 0.,
+                time_in_attacking_half: 0.,
+                time_in_defending_third: 0.,
+                time_in_neutral_third: 0.,
+                time_in_attacking_third: 0.,
+                time_behind_ball: 0.,
+                time_in_front_of_ball: 0.,
+                time_near_wall: 0.,
+                time_in_corner: 0.,
+                time_on_wall: 0.,
+            },
+            hit_counts: HitCounts {
+                total_hits: 0,
+                total_passes: 0,
+                total_shots: 0,
+                total_aerials: 0,
+                total_dribbles: 0,
+                total_saves: 0,
+                total_goals: 0,
+            },
+            possession: Possession {
+                possession_time: 0.,
+                turnovers: 0,
+            },
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn average_boost_level(&self) -> f32 {
+        if self.time_in_game > 0. {
+            self.boost.boost_time / self.time_in_game
+        } else {
+            0.
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn average_speed(&self) -> f32 {
+        if self.time_in_game > 0. {
+            self.distance_traveled / self.time_in_game
+        } else {
+            0.
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Goal {
+    /// The tick count of the state in which the goal was first observed
+    pub tick_count: u64,
+    /// The team that scored
+    pub team: Team,
+    /// The car that made the last hit for the scoring team, if any
+    pub scorer: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct LastHit {
+    car_id: u32,
+    team: Team,
+    tick_count: u64,
+}
+
+#[derive(Clone, Debug)]
+struct Prev {
+    tick_count: u64,
+    ball: BallState,
+    cars: Vec<(u32, CarState)>,
+    pads_active: Vec<bool>,
+}
+
+/// Y multiplier that makes the team's attacking direction positive
+const fn attack_dir(team: Team) -> f32 {
+    match team {
+        Team::Blue => 1.,
+        Team::Orange => -1.,
+    }
+}
+
+/// Returns true if the ball is moving (in a straight line) into the net on the given team's side, within `SHOT_TIME`
+fn is_heading_into_net(ball: &BallState, net_team: Team) -> bool {
+    let dir = -attack_dir(net_team);
+    let vel_y = ball.vel.y * dir;
+    if vel_y <= 0. {
+        return false;
+    }
+
+    let time = (consts::SOCCAR_GOAL_SCORE_BASE_THRESHOLD_Y - ball.pos.y * dir) / vel_y;
+    let x = ball.pos.x + ball.vel.x * time;
+    (0.0..=SHOT_TIME).contains(&time) && x.abs() < soccar::GOAL_HALF_WIDTH
+}
+
+/// Returns the team that the ball has been scored against, if it's inside of a net
+fn scored_net(ball: &BallState) -> Option<Team> {
+    (ball.pos.y.abs() > consts::SOCCAR_GOAL_SCORE_BASE_THRESHOLD_Y + consts::BALL_COLLISION_RADIUS_SOCCAR)
+        .then_some(if ball.pos.y < 0. { Team::Blue } else { Team::Orange })
+}
+
+#[derive(Clone, Debug, Default)]
+/// Accumulates carball-style statistics (boost usage, positioning, hits and possession) over a match
+///
+/// Feed it every game state you observe (e.g. after each call to `step`), then export the stats with `write_json`.
+/// Time-based stats are accumulated using the elapsed time between consecutive states,
+/// so states don't have to be observed every tick.
+///
+/// Goals are only detected in game modes with box goals.
+pub struct CarballTracker {
+    prev: Option<Prev>,
+    players: Vec<PlayerStats>,
+    goals: Vec<Goal>,
+    last_hit: Option<LastHit>,
+    in_net: bool,
+    game_mode: GameMode,
+    num_states: u64,
+    seconds: f32,
+}
+
+impl CarballTracker {
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            prev: None,
+            players: Vec::new(),
+            goals: Vec::new(),
+            last_hit: None,
+            in_net: false,
+            game_mode: GameMode::Soccar,
+            num_states: 0,
+            seconds: 0.,
+        }
+    }
+
+    /// Forget the previously observed state and reset the stats
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    #[inline]
+    #[must_use]
+    /// The stats of every car that has been observed, in the order they first appeared
+    pub fn players(&self) -> &[PlayerStats] {
+        &self.players
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn player(&self, car_id: u32) -> Option<&PlayerStats> {
+        self.players.iter().find(|player| player.id == car_id)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn goals(&self) -> &[Goal] {
+        &self.goals
+    }
+
+    #[must_use]
+    pub fn score(&self, team: Team) -> u32 {
+        self.goals.iter().filter(|goal| goal.team == team).count() as u32
+    }
+
+    #[must_use]
+    /// The sum of the hit counts of every car on the team
+    pub fn team_hit_counts(&self, team: Team) -> HitCounts {
+        self.players
+            .iter()
+            .filter(|player| player.team == team)
+            .fold(HitCounts::default(), |mut total, player| {
+                let hits = player.hit_counts;
+                total.total_hits += hits.total_hits;
+                total.total_passes += hits.total_passes;
+                total.total_shots += hits.total_shots;
+                total.total_aerials += hits.total_aerials;
+                total.total_dribbles += hits.total_dribbles;
+                total.total_saves += hits.total_saves;
+                total.total_goals += hits.total_goals;
+                total
+            })
+    }
+
+    #[must_use]
+    /// The sum of the possession time of every car on the team
+    pub fn team_possession(&self, team: Team) -> Possession {
+        self.players
+            .iter()
+            .filter(|player| player.team == team)
+            .fold(Possession::default(), |mut total, player| {
+                total.possession_time += player.possession.possession_time;
+                total.turnovers += player.possession.turnovers;
+                total
+            })
+    }
+
+    fn player_mut(&mut self, car_id: u32, team: Team) -> &mut PlayerStats {
+        match self.players.iter().position(|player| player.id == car_id) {
+            Some(i) => {
+                // the car might have been moved to another team
+                self.players[i].team = team;
+                &mut self.players[i]
+            }
+            None => {
+                self.players.push(PlayerStats::new(car_id, team));
+                self.players.last_mut().unwrap()
+            }
+        }
+    }
+
+    /// Adds the stats from the time between the previous state and the given one
+    fn accumulate_time(&mut self, game_state: &GameState, prev: &Prev, dt: f32) {
+        let ball_pos = game_state.ball.pos;
+
+        for car in &game_state.cars {
+            let state = &car.state;
+            let prev_state = prev.cars.iter().find(|(id, _)| *id == car.id).map(|(_, state)| state);
+            let player = self.player_mut(car.id, car.team);
+
+            if state.is_demoed {
+                continue;
+            }
+
+            player.time_in_game += dt;
+            if let Some(prev_state) = prev_state.filter(|prev_state| !prev_state.is_demoed) {
+                player.distance_traveled += state.pos.distance(prev_state.pos);
+            }
+
+            let boost = &mut player.boost;
+            boost.boost_time += state.boost * dt;
+            if state.boost >= consts::BOOST_MAX {
+                boost.time_full_boost += dt;
+            } else if state.boost <= 0. {
+                boost.time_no_boost += dt;
+            }
+
+            if state.boost < LOW_BOOST {
+                boost.time_low_boost += dt;
+            }
+
+            let dir = attack_dir(car.team);
+            let y = state.pos.y * dir;
+            let tendencies = &mut player.positional_tendencies;
+
+            if state.is_on_ground {
+                tendencies.time_on_ground += dt;
+                if state.rot_mat.up.z < 0.5 {
+                    tendencies.time_on_wall += dt;
+                }
+            } else if state.pos.z < HIGH_AIR_Z {
+                tendencies.time_low_in_air += dt;
+            } else {
+                tendencies.time_high_in_air += dt;
+            }
+
+            if y < 0. {
+                tendencies.time_in_defending_half += dt;
+            } else {
+                tendencies.time_in_attacking_half += dt;
+            }
+
+            let third = consts::ARENA_EXTENT_Y / 3.;
+            if y < -third {
+                tendencies.time_in_defending_third += dt;
+            } else if y > third {
+                tendencies.time_in_attacking_third += dt;
+            } else {
+                tendencies.time_in_neutral_third += dt;
+            }
+
+            if y < ball_pos.y * dir {
+                tendencies.time_behind_ball += dt;
+            } else {
+                tendencies.time_in_front_of_ball += dt;
+            }
+
+            let wall_x = consts::ARENA_EXTENT_X - state.pos.x.abs();
+            let wall_y = consts::ARENA_EXTENT_Y - state.pos.y.abs();
+            if wall_x.min(wall_y) < NEAR_WALL_DISTANCE {
+                tendencies.time_near_wall += dt;
+            }
+
+            if wall_x < CORNER_DISTANCE && wall_y < CORNER_DISTANCE {
+                tendencies.time_in_corner += dt;
+            }
+        }
+
+        if let Some(last_hit) = self.last_hit {
+            self.player_mut(last_hit.car_id, last_hit.team).possession.possession_time += dt;
+        }
+    }
+
+    /// Counts boost usage and pickups
+    fn update_boost(&mut self, game_state: &GameState, prev: &Prev) {
+        // pads that were picked up since the previous state
+        let mut picked_up = game_state
+            .pads
+            .iter()
+            .zip(&prev.pads_active)
+            .filter(|(pad, &was_active)| was_active && !pad.state.is_active)
+            .map(|(pad, _)| pad.config)
+            .collect::<Vec<_>>();
+
+        for car in &game_state.cars {
+            let Some((_, prev_state)) = prev.cars.iter().find(|(id, _)| *id == car.id) else {
+                continue;
+            };
+
+            if car.state.is_demoed || prev_state.is_demoed {
+                continue;
+            }
+
+            let change = car.state.boost - prev_state.boost;
+            let player = self.player_mut(car.id, car.team);
+            if change < 0. {
+                player.boost.boost_usage -= change;
+                continue;
+            }
+
+            // boost can also be gained from respawning or a kickoff reset, so only count it if a nearby pad was picked up
+            let Some(i) = picked_up
+                .iter()
+                .enumerate()
+                .map(|(i, pad)| (i, pad.position.distance_squared(car.state.pos)))
+                .filter(|(_, dist_sq)| *dist_sq < boostpads::CYL_RAD_BIG * boostpads::CYL_RAD_BIG * 4.)
+                .min_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map(|(i, _)| i)
+            else {
+                continue;
+            };
+
+            let pad = picked_up.swap_remove(i);
+            let amount = if pad.is_big {
+                player.boost.num_large_boosts += 1;
+                boostpads::BOOST_AMOUNT_BIG
+            } else {
+                player.boost.num_small_boosts += 1;
+                boostpads::BOOST_AMOUNT_SMALL
+            };
+
+            player.boost.wasted_collection += (amount - change).max(0.);
+            if pad.position.y * attack_dir(car.team) > 0. {
+                player.boost.num_stolen_boosts += 1;
+            }
+        }
+    }
+
+    /// Counts the new hits, and the shots, saves and goals that they lead to
+    fn update_hits(&mut self, game_state: &GameState, prev: &Prev) {
+        let tick_rate = game_state.tick_rate.max(1.);
+        let ball = &game_state.ball;
+
+        let mut touches = game_state
+            .cars
+            .iter()
+            .filter(|car| {
+                let hit_info = car.state.ball_hit_info;
+                hit_info.is_valid && hit_info.tick_count_when_hit > prev.tick_count
+            })
+            .collect::<Vec<_>>();
+        touches.sort_unstable_by_key(|car| car.state.ball_hit_info.tick_count_when_hit);
+
+        for car in touches {
+            let tick_count = car.state.ball_hit_info.tick_count_when_hit;
+            let since_last_hit = self
+                .last_hit
+                .map(|last_hit| tick_count.saturating_sub(last_hit.tick_count) as f32 / tick_rate);
+            let same_car = self.last_hit.is_some_and(|last_hit| last_hit.car_id == car.id);
+
+            if same_car && since_last_hit.is_some_and(|time| time < HIT_MERGE_TIME) {
+                // still the same touch
+                self.last_hit = Some(LastHit {
+                    car_id: car.id,
+                    team: car.team,
+                    tick_count,
+                });
+                continue;
+            }
+
+            if let Some(last_hit) = self.last_hit.filter(|last_hit| last_hit.car_id != car.id) {
+                let last_player = self.player_mut(last_hit.car_id, last_hit.team);
+                if last_hit.team == car.team {
+                    last_player.hit_counts.total_passes += 1;
+                } else {
+                    last_player.possession.turnovers += 1;
+                }
+            }
+
+            let is_dribble = same_car && since_last_hit.is_some_and(|time| time < DRIBBLE_TIME);
+            let is_aerial = !car.state.is_on_ground && ball.pos.z > AERIAL_BALL_Z;
+            let opponent = match car.team {
+                Team::Blue => Team::Orange,
+                Team::Orange => Team::Blue,
+            };
+            let is_shot = is_heading_into_net(ball, opponent);
+            let is_save = is_heading_into_net(&prev.ball, car.team) && !is_heading_into_net(ball, car.team);
+
+            let hits = &mut self.player_mut(car.id, car.team).hit_counts;
+            hits.total_hits += 1;
+            hits.total_dribbles += u32::from(is_dribble);
+            hits.total_aerials += u32::from(is_aerial);
+            hits.total_shots += u32::from(is_shot);
+            hits.total_saves += u32::from(is_save);
+
+            self.last_hit = Some(LastHit {
+                car_id: car.id,
+                team: car.team,
+                tick_count,
+            });
+        }
+
+        if !matches!(self.game_mode, GameMode::Soccar | GameMode::Heatseeker | GameMode::Snowday) {
+            return;
+        }
+
+        match scored_net(ball) {
+            Some(net_team) if !self.in_net => {
+                self.in_net = true;
+
+                let team = match net_team {
+                    Team::Blue => Team::Orange,
+                    Team::Orange => Team::Blue,
+                };
+                let scorer = self.last_hit.filter(|last_hit| last_hit.team == team).map(|last_hit| {
+                    self.player_mut(last_hit.car_id, last_hit.team).hit_counts.total_goals += 1;
+                    last_hit.car_id
+                });
+
+                self.goals.push(Goal {
+                    tick_count: game_state.tick_count,
+                    team,
+                    scorer,
+                });
+                self.last_hit = None;
+            }
+            Some(_) => {}
+            None => self.in_net = false,
+        }
+    }
+
+    /// Adds the stats from the time since the previous game state
+    ///
+    /// If the tick count didn't increase (e.g. the arena was reset), only the new state is remembered
+    pub fn update(&mut self, game_state: &GameState) {
+        self.game_mode = game_state.game_mode;
+
+        for car in &game_state.cars {
+            self.player_mut(car.id, car.team);
+        }
+
+        if let Some(prev) = self.prev.take() {
+            if game_state.tick_count > prev.tick_count {
+                let dt = (game_state.tick_count - prev.tick_count) as f32 / game_state.tick_rate.max(1.);
+                self.num_states += 1;
+                self.seconds += dt;
+
+                self.accumulate_time(game_state, &prev, dt);
+                self.update_boost(game_state, &prev);
+                self.update_hits(game_state, &prev);
+            }
+        }
+
+        self.prev = Some(Prev {
+            tick_count: game_state.tick_count,
+            ball: game_state.ball,
+            cars: game_state.cars.iter().map(|car| (car.id, car.state)).collect(),
+            pads_active: game_state.pads.iter().map(|pad| pad.state.is_active).collect(),
+        });
+    }
+
+    #[must_use]
+    /// The stats as JSON, in the same structure as carball's analysis output
+    ///
+    /// Car IDs are used as the player IDs, and the player names are `"Car <id>"`
+    pub fn to_json(&self) -> String {
+        self.to_json_with_metadata(&Metadata::new())
+    }
+
+    #[must_use]
+    /// The stats as JSON, like `to_json`, but with the player names, tags and team names from the metadata
+    pub fn to_json_with_metadata(&self, metadata: &Metadata) -> String {
+        let mut json = String::new();
+
+        write!(
+            json,
+            "{{\"gameMetadata\":{{\"length\":{},\"frames\":{},\"score\":{{\"team0Score\":{},\"team1Score\":{}}},\"goals\":[",
+            self.seconds,
+            self.num_states,
+            self.score(Team::Blue),
+            self.score(Team::Orange)
+        )
+        .unwrap();
+
+        for (i, goal) in self.goals.iter().enumerate() {
+            if i != 0 {
+                json.push(',');
+            }
+
+            write!(json, "{{\"frameNumber\":{}", goal.tick_count).unwrap();
+            if let Some(scorer) = goal.scorer {
+                write!(json, ",\"playerId\":{{\"id\":\"{scorer}\"}}").unwrap();
+            }
+            json.push('}');
+        }
+
+        json.push_str("]},\"players\":[");
+
+        for (i, player) in self.players.iter().enumerate() {
+            if i != 0 {
+                json.push(',');
+            }
+
+            let boost = &player.boost;
+            let tendencies = &player.positional_tendencies;
+            let hits = &player.hit_counts;
+
+            write!(json, "{{\"id\":{{\"id\":\"{}\"}},\"name\":", player.id).unwrap();
+            write_json_str(&mut json, &metadata.name(player.id));
+
+            json.push_str(",\"tags\":[");
+            for (j, tag) in metadata
+                .player(player.id)
+                .into_iter()
+                .flat_map(|player| &player.tags)
+                .enumerate()
+            {
+                if j != 0 {
+                    json.push(',');
+                }
+
+                write_json_str(&mut json, tag);
+            }
+
+            write!(
+                json,
+                "],\"isOrange\":{},\"goals\":{},\"saves\":{},\"shots\":{},\"timeInGame\":{},\"stats\":{{",
+                player.team == Team::Orange,
+                hits.total_goals,
+                hits.total_saves,
+                hits.total_shots,
+                player.time_in_game
+            )
+            .unwrap();
+
+            write!(
+                json,
+                "\"boost\":{{\"boostUsage\":{},\"numSmallBoosts\":{},\"numLargeBoosts\":{},\"wastedCollection\":{},\
+                \"numStolenBoosts\":{},\"timeFullBoost\":{},\"timeLowBoost\":{},\"timeNoBoost\":{},\
+                \"averageBoostLevel\":{}}},",
+                boost.boost_usage,
+                boost.num_small_boosts,
+                boost.num_large_boosts,
+                boost.wasted_collection,
+                boost.num_stolen_boosts,
+                boost.time_full_boost,
+                boost.time_low_boost,
+                boost.time_no_boost,
+                player.average_boost_level()
+            )
+            .unwrap();
+
+            write!(
+                json,
+                "\"positionalTendencies\":{{\"timeOnGround\":{},\"timeLowInAir\":{},\"timeHighInAir\":{},\
+                \"timeInDefendingHalf\":{},\"timeInAttackingHalf\":{},\"timeInDefendingThird\":{},\
+                \"timeInNeutralThird\":{},\"timeInAttackingThird\":{},\"timeBehindBall\":{},\"timeInFrontBall\":{},\
+                \"timeNearWall\":{},\"timeInCorner\":{},\"timeOnWall\":{}}},",
+                tendencies.time_on_ground,
+                tendencies.time_low_in_air,
+                tendencies.time_high_in_air,
+                tendencies.time_in_defending_half,
+                tendencies.time_in_attacking_half,
+                tendencies.time_in_defending_third,
+                tendencies.time_in_neutral_third,
+                tendencies.time_in_attacking_third,
+                tendencies.time_behind_ball,
+                tendencies.time_in_front_of_ball,
+                tendencies.time_near_wall,
+                tendencies.time_in_corner,
+                tendencies.time_on_wall
+            )
+            .unwrap();
+
+            write_hit_counts(&mut json, hits);
+            json.push(',');
+            write_possession(&mut json, &player.possession);
+
+            write!(
+                json,
+                ",\"averages\":{{\"averageSpeed\":{},\"distanceTraveled\":{}}}}}}}",
+                player.average_speed(),
+                player.distance_traveled
+            )
+            .unwrap();
+        }
+
+        json.push_str("],\"teams\":[");
+
+        for (i, team) in [Team::Blue, Team::Orange].into_iter().enumerate() {
+            if i != 0 {
+                json.push(',');
+            }
+
+            json.push('{');
+            if let Some(name) = metadata.team_name(team) {
+                json.push_str("\"name\":");
+                write_json_str(&mut json, name);
+                json.push(',');
+            }
+
+            write!(
+                json,
+                "\"isOrange\":{},\"score\":{},\"playerIds\":[",
+                team == Team::Orange,
+                self.score(team)
+            )
+            .unwrap();
+
+            for (j, player) in self.players.iter().filter(|player| player.team == team).enumerate() {
+                if j != 0 {
+                    json.push(',');
+                }
+
+                write!(json, "{{\"id\":\"{}\"}}", player.id).unwrap();
+            }
+
+            json.push_str("],\"stats\":{");
+            write_hit_counts(&mut json, &self.team_hit_counts(team));
+            json.push(',');
+            write_possession(&mut json, &self.team_possession(team));
+            json.push_str("}}");
+        }
+
+        json.push_str("]}");
+        json
+    }
+
+    /// Writes the stats as JSON, see `to_json`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON can't be written
+    pub fn write_json<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.to_json().as_bytes())
+    }
+}
+
+fn write_json_str(json: &mut String, s: &str) {
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if c.is_control() => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+fn write_hit_counts(json: &mut String, hits: &HitCounts) {
+    write!(
+        json,
+        "\"hitCounts\":{{\"totalHits\":{},\"totalPasses\":{},\"totalShots\":{},\"totalAerials\":{},\
+        \"totalDribbles\":{},\"totalSaves\":{},\"totalGoals\":{}}}",
+        hits.total_hits,
+        hits.total_passes,
+        hits.total_shots,
+        hits.total_aerials,
+        hits.total_dribbles,
+        hits.total_saves,
+        hits.total_goals
+    )
+    .unwrap();
+}
+
+fn write_possession(json: &mut String, possession: &Possession) {
+    write!(
+        json,
+        "\"possession\":{{\"possessionTime\":{},\"turnovers\":{}}}",
+        possession.possession_time, possession.turnovers
+    )
+    .unwrap();
+}
@@ -0,0 +1,170 @@
+use crate::{consts::heatseeker, sim::Team, GameState};
+
+/// A return only counts as a save if the ball was within this distance (in Y) of the returning team's back wall
+pub const HEATSEEKER_SAVE_DISTANCE: f32 = 2000.;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HeatseekerEventKind {
+    /// The ball started seeking a different net
+    ///
+    /// `touched_by` is the team that touched the ball to cause the switch,
+    /// or `None` if it was caused by something else (like bouncing off of a back wall)
+    TargetSwitch { y_target_dir: f32, touched_by: Option<Team> },
+    /// The ball's target speed increased after a touch
+    SpeedUp { target_speed: f32 },
+    /// A team touched the ball away from their own net
+    Return { team: Team },
+    /// A return that happened close to the returning team's net
+    Save { team: Team },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HeatseekerEvent {
+    /// The tick count of the state in which the event was first observed
+    pub tick_count: u64,
+    pub kind: HeatseekerEventKind,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// Running totals of heatseeker events
+pub struct HeatseekerStats {
+    pub target_switches: u32,
+    pub speed_ups: u32,
+    /// Indexed by `Team as usize`
+    pub returns: [u32; 2],
+    /// Indexed by `Team as usize`
+    pub saves: [u32; 2],
+}
+
+impl HeatseekerStats {
+    #[inline]
+    #[must_use]
+    pub const fn returns(&self, team: Team) -> u32 {
+        self.returns[team as usize]
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn saves(&self, team: Team) -> u32 {
+        self.saves[team as usize]
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct PrevBall {
+    tick_count: u64,
+    y: f32,
+    y_target_dir: f32,
+    target_speed: f32,
+}
+
+#[derive(Clone, Debug, Default)]
+/// Detects heatseeker target switches, speed-ups, returns and saves between consecutive game states
+///
+/// Feed it every game state you observe (e.g. after each call to `step`) and it will
+/// return the events that happened since the last update, while also keeping running totals.
+pub struct HeatseekerTracker {
+    prev: Option<PrevBall>,
+    stats: HeatseekerStats,
+}
+
+/// The team that defends the net at the given Y direction
+fn defending_team(y_dir: f32) -> Team {
+    if y_dir < 0. {
+        Team::Blue
+    } else {
+        Team::Orange
+    }
+}
+
+impl HeatseekerTracker {
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            prev: None,
+            stats: HeatseekerStats {
+                target_switches: 0,
+                speed_ups: 0,
+                returns: [0; 2],
+                saves: [0; 2],
+            },
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn stats(&self) -> &HeatseekerStats {
+        &self.stats
+    }
+
+    /// Forget the previously observed state and reset the stats
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Returns the team of the car that most recently touched the ball after the given tick, if any
+    fn touched_by(game_state: &GameState, after_tick: u64) -> Option<Team> {
+        game_state
+            .cars
+            .iter()
+            .filter(|car| car.state.ball_hit_info.is_valid && car.state.ball_hit_info.tick_count_when_hit > after_tick)
+            .max_by_key(|car| car.state.ball_hit_info.tick_count_when_hit)
+            .map(|car| car.team)
+    }
+
+    /// Compares the given game state against the previous one and returns all new events
+    pub fn update(&mut self, game_state: &GameState) -> Vec<HeatseekerEvent> {
+        let hs_info = game_state.ball.hs_info;
+        let mut events = Vec::new();
+
+        if let Some(prev) = self.prev {
+            let mut push = |kind| {
+                events.push(HeatseekerEvent {
+                    tick_count: game_state.tick_count,
+                    kind,
+                });
+            };
+
+            let touched_by = Self::touched_by(game_state, prev.tick_count);
+
+            if hs_info.y_target_dir != prev.y_target_dir {
+                self.stats.target_switches += 1;
+                push(HeatseekerEventKind::TargetSwitch {
+                    y_target_dir: hs_info.y_target_dir,
+                    touched_by,
+                });
+
+                // the ball was seeking this team's net, and they sent it away
+                if let Some(team) = touched_by {
+                    if prev.y_target_dir != 0. && defending_team(prev.y_target_dir) == team {
+                        self.stats.returns[team as usize] += 1;
+                        push(HeatseekerEventKind::Return { team });
+
+                        let own_back_wall = prev.y_target_dir.signum() * heatseeker::TARGET_Y;
+                        if (own_back_wall - prev.y).abs() < HEATSEEKER_SAVE_DISTANCE {
+                            self.stats.saves[team as usize] += 1;
+                            push(HeatseekerEventKind::Save { team });
+                        }
+                    }
+                }
+            }
+
+            if hs_info.cur_target_speed > prev.target_speed {
+                self.stats.speed_ups += 1;
+                push(HeatseekerEventKind::SpeedUp {
+                    target_speed: hs_info.cur_target_speed,
+                });
+            }
+        }
+
+        self.prev = Some(PrevBall {
+            tick_count: game_state.tick_count,
+            y: game_state.ball.pos.y,
+            y_target_dir: hs_info.y_target_dir,
+            target_speed: hs_info.cur_target_speed,
+        });
+
+        events
+    }
+}
@@ -0,0 +1,13 @@
+mod carball;
+mod heatseeker;
+mod possession;
+
+pub use carball::{
+    BoostStats, CarballTracker, Goal, HitCounts, PlayerStats, PositionalTendencies, Possession, AERIAL_BALL_Z,
+    CORNER_DISTANCE, DRIBBLE_TIME, HIGH_AIR_Z, HIT_MERGE_TIME, LOW_BOOST, NEAR_WALL_DISTANCE, SHOT_TIME,
+};
+pub use heatseeker::{HeatseekerEvent, HeatseekerEventKind, HeatseekerStats, HeatseekerTracker, HEATSEEKER_SAVE_DISTANCE};
+pub use possession::{
+    PossessionChange, PossessionTracker, Possessor, TeamPossessionStats, TouchAgreement, CONTROL_MAX_REL_SPEED,
+    LOOSE_BALL_TIME, POSSESSION_MAX_DISTANCE, PRESSURE_DISTANCE,
+};
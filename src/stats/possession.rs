@@ -0,0 +1,324 @@
+use crate::{sim::Team, GameState};
+
+/// A car has to be within this distance (uu, between the car's and the ball's centers) to gain possession
+pub const POSSESSION_MAX_DISTANCE: f32 = 400.;
+/// A car only gains possession without touching the ball if it's moving at most this fast (uu/s) relative to the ball
+pub const CONTROL_MAX_REL_SPEED: f32 = 1000.;
+/// Possession is lost if the possessing car hasn't been in control for this many seconds
+pub const LOOSE_BALL_TIME: f32 = 1.;
+/// A car within this distance (uu) of the ball puts pressure on the team in possession
+pub const PRESSURE_DISTANCE: f32 = 1000.;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_utils", derive(serde::Serialize, serde::Deserialize))]
+pub struct Possessor {
+    pub car_id: u32,
+    pub team: Team,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The ball changing hands, or becoming loose
+pub struct PossessionChange {
+    /// The tick count of the state in which the change was first observed
+    pub tick_count: u64,
+    pub from: Option<Possessor>,
+    pub to: Option<Possessor>,
+}
+
+impl PossessionChange {
+    #[inline]
+    #[must_use]
+    /// Returns true if the ball went from one team to the other
+    pub fn is_turnover(&self) -> bool {
+        matches!((self.from, self.to), (Some(from), Some(to)) if from.team != to.team)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde_utils", derive(serde::Serialize, serde::Deserialize))]
+/// Possession and pressure totals for one team
+pub struct TeamPossessionStats {
+    /// Seconds that a car on the team had possession
+    pub possession_time: f32,
+    /// Seconds of possession with the ball in the opponent's half
+    pub attacking_possession_time: f32,
+    /// The number of times the team gained possession, either from the other team or from a loose ball
+    pub possessions_gained: u32,
+    /// The number of times the team lost possession directly to the other team
+    pub turnovers: u32,
+    /// Seconds that the other team had possession while a car on this team was within `PRESSURE_DISTANCE` of the ball
+    pub time_pressuring: f32,
+    /// Seconds that this team had possession while a car on the other team was within `PRESSURE_DISTANCE` of the ball
+    pub time_under_pressure: f32,
+    /// The sum of the closest car's distance to the ball times the elapsed time, while the other team had possession
+    pressure_distance_time: f32,
+}
+
+impl TeamPossessionStats {
+    #[inline]
+    #[must_use]
+    /// The share of the team's possession time where it was under pressure
+    pub fn pressure_ratio(&self) -> f32 {
+        if self.possession_time > 0. {
+            self.time_under_pressure / self.possession_time
+        } else {
+            0.
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The average distance of the team's closest car to the ball while the other team had possession
+    pub fn average_pressure_distance(&self, opponent: &Self) -> f32 {
+        if opponent.possession_time > 0. {
+            self.pressure_distance_time / opponent.possession_time
+        } else {
+            0.
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_utils", derive(serde::Serialize, serde::Deserialize))]
+/// How often the closest-car heuristic agreed with the touches that RocketSim reported
+pub struct TouchAgreement {
+    /// Touches where some car already had possession
+    pub touches: u32,
+    /// Touches by a car on the same team as the car that had possession
+    pub matched: u32,
+}
+
+impl TouchAgreement {
+    #[inline]
+    #[must_use]
+    pub fn ratio(&self) -> f32 {
+        if self.touches == 0 {
+            1.
+        } else {
+            self.matched as f32 / self.touches as f32
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+/// Attributes possession of the ball to a car on every observed tick, and accumulates possession and pressure stats
+///
+/// A car gains possession by touching the ball, or by being the closest car to the ball while within
+/// `POSSESSION_MAX_DISTANCE` and moving with it (slower than `CONTROL_MAX_REL_SPEED` relative to it).
+/// Touches reported by RocketSim always take priority over the heuristic.
+/// The possessing car keeps possession until another car gains it,
+/// or it hasn't been in control of the ball for `LOOSE_BALL_TIME`.
+///
+/// Feed it every game state you observe (e.g. after each call to `step`) and it will
+/// return the change in possession since the last update, if there was one.
+pub struct PossessionTracker {
+    prev_tick_count: Option<u64>,
+    possessor: Option<Possessor>,
+    /// Seconds since the possessing car was last in control
+    loose_time: f32,
+    teams: [TeamPossessionStats; 2],
+    players: Vec<(u32, f32)>,
+    agreement: TouchAgreement,
+}
+
+impl PossessionTracker {
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            prev_tick_count: None,
+            possessor: None,
+            loose_time: 0.,
+            teams: [TeamPossessionStats {
+                possession_time: 0.,
+                attacking_possession_time: 0.,
+                possessions_gained: 0,
+                turnovers: 0,
+                time_pressuring: 0.,
+                time_under_pressure: 0.,
+                pressure_distance_time: 0.,
+            }; 2],
+            players: Vec::new(),
+            agreement: TouchAgreement { touches: 0, matched: 0 },
+        }
+    }
+
+    /// Forget the previously observed state and reset the stats
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    #[inline]
+    #[must_use]
+    /// The car that had possession in the last observed state
+    pub const fn possessor(&self) -> Option<Possessor> {
+        self.possessor
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn team_stats(&self, team: Team) -> &TeamPossessionStats {
+        &self.teams[team as usize]
+    }
+
+    #[inline]
+    #[must_use]
+    /// Seconds that the car had possession
+    pub fn player_possession_time(&self, car_id: u32) -> f32 {
+        self.players
+            .iter()
+            .find(|(id, _)| *id == car_id)
+            .map_or(0., |(_, time)| *time)
+    }
+
+    #[inline]
+    #[must_use]
+    /// The share of the total possession time that the team had
+    pub fn possession_share(&self, team: Team) -> f32 {
+        let total = self.teams[0].possession_time + self.teams[1].possession_time;
+        if total > 0. {
+            self.teams[team as usize].possession_time / total
+        } else {
+            0.5
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn touch_agreement(&self) -> TouchAgreement {
+        self.agreement
+    }
+
+    /// The closest car that's in control of the ball according to the heuristic
+    fn in_control(game_state: &GameState) -> Option<Possessor> {
+        let ball = &game_state.ball;
+
+        game_state
+            .cars
+            .iter()
+            .filter(|car| !car.state.is_demoed)
+            .map(|car| (car, car.state.pos.distance(ball.pos)))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .filter(|(car, distance)| {
+                *distance < POSSESSION_MAX_DISTANCE && (car.state.vel - ball.vel).length() < CONTROL_MAX_REL_SPEED
+            })
+            .map(|(car, _)| Possessor {
+                car_id: car.id,
+                team: car.team,
+            })
+    }
+
+    fn accumulate(&mut self, game_state: &GameState, dt: f32) {
+        let Some(possessor) = self.possessor else {
+            return;
+        };
+
+        let ball = &game_state.ball;
+        let opponent = match possessor.team {
+            Team::Blue => Team::Orange,
+            Team::Orange => Team::Blue,
+        };
+
+        let team = &mut self.teams[possessor.team as usize];
+        team.possession_time += dt;
+        let attack_dir = if possessor.team == Team::Blue { 1. } else { -1. };
+        if ball.pos.y * attack_dir > 0. {
+            team.attacking_possession_time += dt;
+        }
+
+        match self.players.iter_mut().find(|(id, _)| *id == possessor.car_id) {
+            Some((_, time)) => *time += dt,
+            None => self.players.push((possessor.car_id, dt)),
+        }
+
+        let closest_opponent = game_state
+            .cars
+            .iter()
+            .filter(|car| car.team == opponent && !car.state.is_demoed)
+            .map(|car| car.state.pos.distance(ball.pos))
+            .min_by(f32::total_cmp);
+
+        if let Some(distance) = closest_opponent {
+            let pressuring = distance < PRESSURE_DISTANCE;
+            if pressuring {
+                self.teams[possessor.team as usize].time_under_pressure += dt;
+            }
+
+            let opponent_stats = &mut self.teams[opponent as usize];
+            opponent_stats.pressure_distance_time += distance * dt;
+            if pressuring {
+                opponent_stats.time_pressuring += dt;
+            }
+        }
+    }
+
+    /// Attributes possession for the given game state, and adds the stats from the time since the previous one
+    ///
+    /// If the tick count didn't increase (e.g. the arena was reset), only the new state is remembered
+    pub fn update(&mut self, game_state: &GameState) -> Option<PossessionChange> {
+        let prev_tick_count = self.prev_tick_count.replace(game_state.tick_count);
+        let Some(prev_tick_count) = prev_tick_count.filter(|&tick_count| game_state.tick_count > tick_count) else {
+            self.possessor = Self::in_control(game_state);
+            self.loose_time = 0.;
+            return None;
+        };
+
+        let dt = (game_state.tick_count - prev_tick_count) as f32 / game_state.tick_rate.max(1.);
+
+        // the previous possessor held the ball until this state
+        self.accumulate(game_state, dt);
+
+        let toucher = game_state
+            .cars
+            .iter()
+            .filter(|car| car.state.ball_hit_info.is_valid && car.state.ball_hit_info.tick_count_when_hit > prev_tick_count)
+            .max_by_key(|car| car.state.ball_hit_info.tick_count_when_hit)
+            .map(|car| Possessor {
+                car_id: car.id,
+                team: car.team,
+            });
+
+        if let (Some(toucher), Some(possessor)) = (toucher, self.possessor) {
+            self.agreement.touches += 1;
+            self.agreement.matched += u32::from(toucher.team == possessor.team);
+        }
+
+        let prev_possessor = self.possessor;
+        match toucher.or_else(|| Self::in_control(game_state)) {
+            Some(possessor) => {
+                self.possessor = Some(possessor);
+                self.loose_time = 0.;
+            }
+            None => {
+                self.loose_time += dt;
+                if self.loose_time >= LOOSE_BALL_TIME {
+                    self.possessor = None;
+                }
+            }
+        }
+
+        if self.possessor == prev_possessor {
+            return None;
+        }
+
+        let change = PossessionChange {
+            tick_count: game_state.tick_count,
+            from: prev_possessor,
+            to: self.possessor,
+        };
+
+        if let Some(to) = change.to {
+            if prev_possessor.is_none_or(|from| from.team != to.team) {
+                self.teams[to.team as usize].possessions_gained += 1;
+            }
+        }
+
+        if change.is_turnover() {
+            if let Some(from) = change.from {
+                self.teams[from.team as usize].turnovers += 1;
+            }
+        }
+
+        Some(change)
+    }
+}
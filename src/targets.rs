@@ -0,0 +1,301 @@
+//! Custom 3D target regions that the ball or cars can enter
+//!
+//! A general alternative to goal detection for training scenarios,
+//! like "hit the ball into the top corner" or "park inside this circle for 2 seconds".
+
+use crate::{math::Vec3, sim::Team, GameState};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_utils", derive(serde::Serialize, serde::Deserialize))]
+pub enum Region {
+    /// An axis-aligned box
+    Box {
+        min: Vec3,
+        max: Vec3,
+    },
+    Sphere {
+        center: Vec3,
+        radius: f32,
+    },
+    /// An upright cylinder, with `base` at the center of its bottom face
+    Cylinder {
+        base: Vec3,
+        radius: f32,
+        height: f32,
+    },
+}
+
+impl Region {
+    #[must_use]
+    pub fn contains(&self, pos: Vec3) -> bool {
+        match *self {
+            Self::Box { min, max } => {
+                (min.x..=max.x).contains(&pos.x) && (min.y..=max.y).contains(&pos.y) && (min.z..=max.z).contains(&pos.z)
+            }
+            Self::Sphere { center, radius } => (pos - center).length_squared() <= radius * radius,
+            Self::Cylinder { base, radius, height } => {
+                let offset = pos - base;
+                (0. ..=height).contains(&offset.z) && offset.x * offset.x + offset.y * offset.y <= radius * radius
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// What can trigger a target
+pub enum Subject {
+    #[default]
+    Ball,
+    /// Any car that isn't demolished
+    AnyCar,
+    /// Any car on the given team that isn't demolished
+    Team(Team),
+    /// The car with the given ID, while it isn't demolished
+    Car(u32),
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+/// An object that is in a target
+pub enum Object {
+    Ball,
+    Car(u32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Target {
+    pub region: Region,
+    pub subject: Subject,
+    /// Seconds that an object has to stay inside the region before the target is triggered
+    pub dwell_time: f32,
+    /// If true, the target is removed after it's triggered for the first time
+    pub once: bool,
+}
+
+impl Target {
+    #[inline]
+    #[must_use]
+    /// A target that is triggered as soon as the ball enters the region
+    pub const fn new(region: Region) -> Self {
+        Self {
+            region,
+            subject: Subject::Ball,
+            dwell_time: 0.,
+            once: false,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn with_subject(mut self, subject: Subject) -> Self {
+        self.subject = subject;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn with_dwell_time(mut self, dwell_time: f32) -> Self {
+        self.dwell_time = dwell_time;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn once(mut self) -> Self {
+        self.once = true;
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TargetId(pub u32);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TargetEventKind {
+    Entered,
+    /// The object has been inside the region for the target's dwell time
+    ///
+    /// Happens on the same tick as `Entered` if there is no dwell time
+    Triggered,
+    Exited,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TargetEvent {
+    /// The tick count of the state in which the event was first observed
+    pub tick_count: u64,
+    pub target: TargetId,
+    pub object: Object,
+    pub kind: TargetEventKind,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Occupant {
+    entered_tick: u64,
+    triggered: bool,
+}
+
+#[derive(Clone, Debug)]
+struct TrackedTarget {
+    target: Target,
+    occupants: HashMap<Object, Occupant>,
+}
+
+#[derive(Clone, Debug, Default)]
+/// A set of targets, and which objects are currently inside of them
+///
+/// Feed it every game state you observe (e.g. after each call to `step`)
+/// and it will return the events that happened since the last update.
+pub struct Targets {
+    targets: BTreeMap<TargetId, TrackedTarget>,
+    next_id: u32,
+}
+
+impl Targets {
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a target, returning its ID
+    pub fn add(&mut self, target: Target) -> TargetId {
+        let id = TargetId(self.next_id);
+        self.next_id += 1;
+
+        self.targets.insert(
+            id,
+            TrackedTarget {
+                target,
+                occupants: HashMap::new(),
+            },
+        );
+
+        id
+    }
+
+    /// Removes a target, returning it if it existed
+    pub fn remove(&mut self, id: TargetId) -> Option<Target> {
+        self.targets.remove(&id).map(|tracked| tracked.target)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn get(&self, id: TargetId) -> Option<&Target> {
+        self.targets.get(&id).map(|tracked| &tracked.target)
+    }
+
+    #[inline]
+    /// Iterates over all of the targets
+    pub fn iter(&self) -> impl Iterator<Item = (TargetId, &Target)> {
+        self.targets.iter().map(|(id, tracked)| (*id, &tracked.target))
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.targets.len()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.targets.is_empty()
+    }
+
+    #[inline]
+    /// Removes all of the targets
+    pub fn clear(&mut self) {
+        self.targets.clear();
+    }
+
+    /// Returns the objects that are currently inside the target
+    pub fn occupants(&self, id: TargetId) -> impl Iterator<Item = Object> + '_ {
+        self.targets
+            .get(&id)
+            .into_iter()
+            .flat_map(|tracked| tracked.occupants.keys().copied())
+    }
+
+    /// Returns the events that happened since the last update
+    ///
+    /// Events are ordered by target ID, then by object
+    pub fn update(&mut self, game_state: &GameState) -> Vec<TargetEvent> {
+        let tick_count = game_state.tick_count;
+        let mut events = Vec::new();
+        let mut finished = Vec::new();
+
+        for (&id, tracked) in &mut self.targets {
+            let target = tracked.target;
+            let mut inside = Vec::new();
+
+            if target.subject == Subject::Ball && target.region.contains(game_state.ball.pos) {
+                inside.push(Object::Ball);
+            }
+
+            inside.extend(
+                game_state
+                    .cars
+                    .iter()
+                    .filter(|car| !car.state.is_demoed)
+                    .filter(|car| match target.subject {
+                        Subject::Ball => false,
+                        Subject::AnyCar => true,
+                        Subject::Team(team) => car.team == team,
+                        Subject::Car(car_id) => car.id == car_id,
+                    })
+                    .filter(|car| target.region.contains(car.state.pos))
+                    .map(|car| Object::Car(car.id)),
+            );
+
+            let mut push = |object, kind| {
+                events.push(TargetEvent {
+                    tick_count,
+                    target: id,
+                    object,
+                    kind,
+                });
+            };
+
+            let mut exited = tracked
+                .occupants
+                .keys()
+                .copied()
+                .filter(|object| !inside.contains(object))
+                .collect::<Vec<_>>();
+            exited.sort_unstable();
+
+            for object in exited {
+                tracked.occupants.remove(&object);
+                push(object, TargetEventKind::Exited);
+            }
+
+            for object in inside {
+                let occupant = tracked.occupants.entry(object).or_insert_with(|| {
+                    push(object, TargetEventKind::Entered);
+                    Occupant {
+                        entered_tick: tick_count,
+                        triggered: false,
+                    }
+                });
+
+                let dwelled = tick_count.saturating_sub(occupant.entered_tick) as f32 / game_state.tick_rate;
+                if !occupant.triggered && dwelled >= target.dwell_time {
+                    occupant.triggered = true;
+                    push(object, TargetEventKind::Triggered);
+
+                    if target.once {
+                        finished.push(id);
+                        break;
+                    }
+                }
+            }
+        }
+
+        for id in finished {
+            self.targets.remove(&id);
+        }
+
+        events
+    }
+}
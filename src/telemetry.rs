@@ -0,0 +1,490 @@
+//! Exporters for per-tick telemetry, for analysis outside of Rust
+
+use crate::{
+    math::Vec3,
+    sim::{CarState, Team},
+    GameState,
+};
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::{self, BufWriter, Stdout, Write},
+    net::{TcpStream, ToSocketAddrs},
+    path::Path,
+};
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+/// A (group of) CSV column(s)
+///
+/// Vectors are split into `_x`, `_y` and `_z` columns,
+/// and car columns are repeated for every car, prefixed with `car_<id>_`
+pub enum Column {
+    TickCount,
+    /// Seconds since tick 0
+    Time,
+    BallPos,
+    BallVel,
+    BallAngVel,
+    CarPos,
+    CarVel,
+    CarAngVel,
+    CarForward,
+    CarBoost,
+    CarIsOnGround,
+    CarIsDemoed,
+    /// The controls that the car used in the last tick
+    ///
+    /// Split into `throttle`, `steer`, `pitch`, `yaw`, `roll`, `boost`, `jump` and `handbrake` columns
+    CarControls,
+}
+
+impl Column {
+    pub const ALL: [Self; 13] = [
+        Self::TickCount,
+        Self::Time,
+        Self::BallPos,
+        Self::BallVel,
+        Self::BallAngVel,
+        Self::CarPos,
+        Self::CarVel,
+        Self::CarAngVel,
+        Self::CarForward,
+        Self::CarBoost,
+        Self::CarIsOnGround,
+        Self::CarIsDemoed,
+        Self::CarControls,
+    ];
+
+    /// The columns that are written by default: the tick count, ball position/velocity, and car position/boost/controls
+    pub const DEFAULT: [Self; 6] = [
+        Self::TickCount,
+        Self::BallPos,
+        Self::BallVel,
+        Self::CarPos,
+        Self::CarBoost,
+        Self::CarControls,
+    ];
+
+    #[inline]
+    #[must_use]
+    pub const fn is_per_car(self) -> bool {
+        matches!(
+            self,
+            Self::CarPos
+                | Self::CarVel
+                | Self::CarAngVel
+                | Self::CarForward
+                | Self::CarBoost
+                | Self::CarIsOnGround
+                | Self::CarIsDemoed
+                | Self::CarControls
+        )
+    }
+
+    #[must_use]
+    /// The key of the column in JSON lines, see `JsonLinesWriter`
+    pub const fn key(self) -> &'static str {
+        match self {
+            Self::TickCount => "tick_count",
+            Self::Time => "time",
+            Self::BallPos => "ball_pos",
+            Self::BallVel => "ball_vel",
+            Self::BallAngVel => "ball_ang_vel",
+            Self::CarPos => "pos",
+            Self::CarVel => "vel",
+            Self::CarAngVel => "ang_vel",
+            Self::CarForward => "forward",
+            Self::CarBoost => "boost",
+            Self::CarIsOnGround => "is_on_ground",
+            Self::CarIsDemoed => "is_demoed",
+            Self::CarControls => "controls",
+        }
+    }
+
+    #[must_use]
+    /// The names of the CSV columns, without the car prefix
+    pub const fn names(self) -> &'static [&'static str] {
+        match self {
+            Self::TickCount => &["tick_count"],
+            Self::Time => &["time"],
+            Self::BallPos => &["ball_pos_x", "ball_pos_y", "ball_pos_z"],
+            Self::BallVel => &["ball_vel_x", "ball_vel_y", "ball_vel_z"],
+            Self::BallAngVel => &["ball_ang_vel_x", "ball_ang_vel_y", "ball_ang_vel_z"],
+            Self::CarPos => &["pos_x", "pos_y", "pos_z"],
+            Self::CarVel => &["vel_x", "vel_y", "vel_z"],
+            Self::CarAngVel => &["ang_vel_x", "ang_vel_y", "ang_vel_z"],
+            Self::CarForward => &["forward_x", "forward_y", "forward_z"],
+            Self::CarBoost => &["boost"],
+            Self::CarIsOnGround => &["is_on_ground"],
+            Self::CarIsDemoed => &["is_demoed"],
+            Self::CarControls => &[
+                "throttle",
+                "steer",
+                "pitch",
+                "yaw",
+                "roll",
+                "boost_pressed",
+                "jump",
+                "handbrake",
+            ],
+        }
+    }
+}
+
+fn write_vec(row: &mut String, vec: Vec3) {
+    write!(row, ",{},{},{}", vec.x, vec.y, vec.z).unwrap();
+}
+
+fn write_car_column(row: &mut String, column: Column, car: &CarState) {
+    match column {
+        Column::CarPos => write_vec(row, car.pos),
+        Column::CarVel => write_vec(row, car.vel),
+        Column::CarAngVel => write_vec(row, car.ang_vel),
+        Column::CarForward => write_vec(row, car.rot_mat.forward),
+        Column::CarBoost => write!(row, ",{}", car.boost).unwrap(),
+        Column::CarIsOnGround => write!(row, ",{}", u8::from(car.is_on_ground)).unwrap(),
+        Column::CarIsDemoed => write!(row, ",{}", u8::from(car.is_demoed)).unwrap(),
+        Column::CarControls => {
+            let controls = car.last_controls;
+            write!(
+                row,
+                ",{},{},{},{},{},{},{},{}",
+                controls.throttle,
+                controls.steer,
+                controls.pitch,
+                controls.yaw,
+                controls.roll,
+                u8::from(controls.boost),
+                u8::from(controls.jump),
+                u8::from(controls.handbrake)
+            )
+            .unwrap();
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// Writes selected fields of game states to a CSV file, one row per sampled tick
+///
+/// The cars are fixed by the first game state that's written:
+/// cars that are added later are left out, and cars that are removed get empty cells.
+pub struct CsvWriter<W: Write> {
+    writer: W,
+    columns: Vec<Column>,
+    sample_interval: u64,
+    car_ids: Option<Vec<u32>>,
+    last_sampled_tick: Option<u64>,
+    row: String,
+}
+
+impl CsvWriter<BufWriter<File>> {
+    /// Creates (or truncates) the file at the given path
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be created
+    pub fn create<P: AsRef<Path>>(path: P, columns: &[Column]) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?), columns))
+    }
+}
+
+impl<W: Write> CsvWriter<W> {
+    #[must_use]
+    /// Writes the given columns, in the given order, for every tick
+    pub fn new(writer: W, columns: &[Column]) -> Self {
+        Self {
+            writer,
+            columns: columns.to_vec(),
+            sample_interval: 1,
+            car_ids: None,
+            last_sampled_tick: None,
+            row: String::new(),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Only write a row if at least the given number of ticks have passed since the last row
+    pub fn with_sample_interval(mut self, ticks: u64) -> Self {
+        self.sample_interval = ticks.max(1);
+        self
+    }
+
+    fn write_header(&mut self, car_ids: &[u32]) -> io::Result<()> {
+        let mut names = Vec::new();
+
+        for &column in self.columns.iter().filter(|column| !column.is_per_car()) {
+            names.extend(column.names().iter().map(ToString::to_string));
+        }
+
+        for &id in car_ids {
+            for &column in self.columns.iter().filter(|column| column.is_per_car()) {
+                names.extend(column.names().iter().map(|name| format!("car_{id}_{name}")));
+            }
+        }
+
+        writeln!(self.writer, "{}", names.join(","))
+    }
+
+    /// Writes a row for the game state, unless it's too soon after the last row
+    ///
+    /// Returns true if a row was written
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the row can't be written
+    pub fn write(&mut self, game_state: &GameState) -> io::Result<bool> {
+        if self
+            .last_sampled_tick
+            .is_some_and(|last| game_state.tick_count < last.saturating_add(self.sample_interval))
+        {
+            return Ok(false);
+        }
+
+        if self.car_ids.is_none() {
+            let car_ids = game_state.cars.iter().map(|car| car.id).collect::<Vec<_>>();
+            self.write_header(&car_ids)?;
+            self.car_ids = Some(car_ids);
+        }
+
+        let row = &mut self.row;
+        row.clear();
+
+        for &column in self.columns.iter().filter(|column| !column.is_per_car()) {
+            match column {
+                Column::TickCount => write!(row, ",{}", game_state.tick_count).unwrap(),
+                Column::Time => write!(row, ",{}", game_state.tick_count as f32 / game_state.tick_rate).unwrap(),
+                Column::BallPos => write_vec(row, game_state.ball.pos),
+                Column::BallVel => write_vec(row, game_state.ball.vel),
+                Column::BallAngVel => write_vec(row, game_state.ball.ang_vel),
+                _ => unreachable!(),
+            }
+        }
+
+        for &id in self.car_ids.as_deref().unwrap_or_default() {
+            let car = game_state.cars.iter().find(|car| car.id == id);
+
+            for &column in self.columns.iter().filter(|column| column.is_per_car()) {
+                match car {
+                    Some(car) => write_car_column(row, column, &car.state),
+                    None => column.names().iter().for_each(|_| row.push(',')),
+                }
+            }
+        }
+
+        // every value is written with a leading comma
+        writeln!(self.writer, "{}", row.get(1..).unwrap_or_default())?;
+        self.last_sampled_tick = Some(game_state.tick_count);
+
+        Ok(true)
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if the writer can't be flushed
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// JSON has no NaN or infinity, so they're written as null
+fn write_json_f32(line: &mut String, value: f32) {
+    if value.is_finite() {
+        write!(line, "{value}").unwrap();
+    } else {
+        line.push_str("null");
+    }
+}
+
+fn write_json_vec(line: &mut String, vec: Vec3) {
+    line.push('[');
+    write_json_f32(line, vec.x);
+    line.push(',');
+    write_json_f32(line, vec.y);
+    line.push(',');
+    write_json_f32(line, vec.z);
+    line.push(']');
+}
+
+fn write_json_car_column(line: &mut String, column: Column, car: &CarState) {
+    match column {
+        Column::CarPos => write_json_vec(line, car.pos),
+        Column::CarVel => write_json_vec(line, car.vel),
+        Column::CarAngVel => write_json_vec(line, car.ang_vel),
+        Column::CarForward => write_json_vec(line, car.rot_mat.forward),
+        Column::CarBoost => write_json_f32(line, car.boost),
+        Column::CarIsOnGround => write!(line, "{}", car.is_on_ground).unwrap(),
+        Column::CarIsDemoed => write!(line, "{}", car.is_demoed).unwrap(),
+        Column::CarControls => {
+            let controls = car.last_controls;
+            for (i, (name, value)) in [
+                ("throttle", controls.throttle),
+                ("steer", controls.steer),
+                ("pitch", controls.pitch),
+                ("yaw", controls.yaw),
+                ("roll", controls.roll),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                line.push(if i == 0 { '{' } else { ',' });
+                write!(line, "\"{name}\":").unwrap();
+                write_json_f32(line, value);
+            }
+
+            write!(
+                line,
+                ",\"boost\":{},\"jump\":{},\"handbrake\":{}}}",
+                controls.boost, controls.jump, controls.handbrake
+            )
+            .unwrap();
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// Streams selected fields of game states as newline-delimited JSON, one object per sampled tick
+///
+/// Every line is flushed as soon as it's written, so scripts and dashboards can tail the output live.
+/// Non-car columns are top-level keys, and every car is an object in `"cars"` with its `"id"`, `"team"`
+/// and the car columns, like `{"tick_count":8,"ball_pos":[0,0,93.15],"cars":[{"id":1,"team":"blue","boost":33.3}]}`.
+/// NaN and infinite values are written as `null`.
+pub struct JsonLinesWriter<W: Write> {
+    writer: W,
+    columns: Vec<Column>,
+    sample_interval: u64,
+    /// Lines per second of simulated time, which overrides `sample_interval`
+    sample_rate: Option<f32>,
+    last_sampled_tick: Option<u64>,
+    line: String,
+}
+
+impl JsonLinesWriter<Stdout> {
+    #[must_use]
+    pub fn stdout(columns: &[Column]) -> Self {
+        Self::new(io::stdout(), columns)
+    }
+}
+
+impl JsonLinesWriter<TcpStream> {
+    /// Connects to a TCP server, which gets every line
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection fails
+    pub fn connect<A: ToSocketAddrs>(addr: A, columns: &[Column]) -> io::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        // lines are small and should arrive as soon as possible
+        stream.set_nodelay(true)?;
+        Ok(Self::new(stream, columns))
+    }
+}
+
+impl<W: Write> JsonLinesWriter<W> {
+    #[must_use]
+    /// Writes the given columns, in the given order, for every tick
+    pub fn new(writer: W, columns: &[Column]) -> Self {
+        Self {
+            writer,
+            columns: columns.to_vec(),
+            sample_interval: 1,
+            sample_rate: None,
+            last_sampled_tick: None,
+            line: String::new(),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Only write a line if at least the given number of ticks have passed since the last line
+    pub fn with_sample_interval(mut self, ticks: u64) -> Self {
+        self.sample_interval = ticks.max(1);
+        self.sample_rate = None;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Write at most the given number of lines per second of simulated time, whatever the arena's tick rate is
+    pub fn with_sample_rate(mut self, lines_per_second: f32) -> Self {
+        self.sample_rate = Some(lines_per_second);
+        self
+    }
+
+    fn interval(&self, tick_rate: f32) -> u64 {
+        self.sample_rate
+            .map_or(self.sample_interval, |rate| (tick_rate / rate).round().max(1.) as u64)
+    }
+
+    /// Writes and flushes a line for the game state, unless it's too soon after the last line
+    ///
+    /// Returns true if a line was written
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the line can't be written, for example because a TCP consumer disconnected
+    pub fn write(&mut self, game_state: &GameState) -> io::Result<bool> {
+        let interval = self.interval(game_state.tick_rate);
+        if self
+            .last_sampled_tick
+            .is_some_and(|last| game_state.tick_count < last.saturating_add(interval))
+        {
+            return Ok(false);
+        }
+
+        let line = &mut self.line;
+        line.clear();
+        line.push('{');
+
+        for &column in self.columns.iter().filter(|column| !column.is_per_car()) {
+            write!(line, "\"{}\":", column.key()).unwrap();
+            match column {
+                Column::TickCount => write!(line, "{}", game_state.tick_count).unwrap(),
+                Column::Time => write_json_f32(line, game_state.tick_count as f32 / game_state.tick_rate),
+                Column::BallPos => write_json_vec(line, game_state.ball.pos),
+                Column::BallVel => write_json_vec(line, game_state.ball.vel),
+                Column::BallAngVel => write_json_vec(line, game_state.ball.ang_vel),
+                _ => unreachable!(),
+            }
+            line.push(',');
+        }
+
+        line.push_str("\"cars\":[");
+        for (i, car) in game_state.cars.iter().enumerate() {
+            if i != 0 {
+                line.push(',');
+            }
+
+            let team = match car.team {
+                Team::Blue => "blue",
+                Team::Orange => "orange",
+            };
+            write!(line, "{{\"id\":{},\"team\":\"{team}\"", car.id).unwrap();
+
+            for &column in self.columns.iter().filter(|column| column.is_per_car()) {
+                write!(line, ",\"{}\":", column.key()).unwrap();
+                write_json_car_column(line, column, &car.state);
+            }
+            line.push('}');
+        }
+        line.push_str("]}\n");
+
+        self.writer.write_all(line.as_bytes())?;
+        self.writer.flush()?;
+        self.last_sampled_tick = Some(game_state.tick_count);
+
+        Ok(true)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
@@ -0,0 +1,138 @@
+//! Trigger volumes, regions of the arena that report when the ball or a car enters or leaves them
+//!
+//! They are checked after every tick, and the events are passed to the callback from `Arena::set_trigger_callback`.
+//! This covers custom goals, target practice regions, or checking whether a player is out of position.
+
+use crate::{
+    math::{RotMat, Vec3},
+    sim::{Arena, TriggerShape, TriggerVolume},
+};
+use core::pin::Pin;
+use std::{error::Error, fmt};
+
+#[derive(Clone, Copy, Debug)]
+pub struct NoTriggerVolumeFound(pub u32);
+
+impl Error for NoTriggerVolumeFound {}
+impl fmt::Display for NoTriggerVolumeFound {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "No trigger volume found in the given arena at the given ID {}.", self.0)
+    }
+}
+
+impl TriggerVolume {
+    #[inline]
+    #[must_use]
+    /// An axis-aligned box
+    pub const fn aabb(center: Vec3, half_extents: Vec3) -> Self {
+        Self {
+            shape: TriggerShape::Aabb,
+            center,
+            radius: 0.,
+            half_extents,
+            rot_mat: RotMat::IDENTITY,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// An axis-aligned box between the two corners
+    pub fn from_corners(min: Vec3, max: Vec3) -> Self {
+        Self::aabb((min + max) / 2., (max - min) / 2.)
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn sphere(center: Vec3, radius: f32) -> Self {
+        Self {
+            shape: TriggerShape::Sphere,
+            center,
+            radius,
+            half_extents: Vec3::ZERO,
+            rot_mat: RotMat::IDENTITY,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// A box that's rotated by `rot_mat`, with `half_extents` along its forward, right and up directions
+    pub const fn oriented_box(center: Vec3, half_extents: Vec3, rot_mat: RotMat) -> Self {
+        Self {
+            shape: TriggerShape::Obb,
+            center,
+            radius: 0.,
+            half_extents,
+            rot_mat,
+        }
+    }
+
+    #[must_use]
+    /// Returns true if the point is inside of the volume (or on its surface), like the check that's done every tick
+    pub fn contains(&self, pos: Vec3) -> bool {
+        let mut delta = pos - self.center;
+
+        match self.shape {
+            TriggerShape::Sphere => return delta.length() <= self.radius,
+            TriggerShape::Obb => {
+                delta = Vec3::new(
+                    delta.dot(self.rot_mat.forward),
+                    delta.dot(self.rot_mat.right),
+                    delta.dot(self.rot_mat.up),
+                );
+            }
+            TriggerShape::Aabb => {}
+        }
+
+        delta.x.abs() <= self.half_extents.x && delta.y.abs() <= self.half_extents.y && delta.z.abs() <= self.half_extents.z
+    }
+}
+
+impl Arena {
+    #[inline]
+    #[must_use]
+    /// Adds a trigger volume to the arena, returning its ID
+    ///
+    /// Whatever is already inside of the volume when it's added doesn't trigger an enter event.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size of the volume isn't positive
+    pub fn add_trigger_volume(self: Pin<&mut Self>, volume: TriggerVolume) -> u32 {
+        let size_is_valid = match volume.shape {
+            TriggerShape::Sphere => volume.radius > 0.,
+            TriggerShape::Aabb | TriggerShape::Obb => {
+                volume.half_extents.x > 0. && volume.half_extents.y > 0. && volume.half_extents.z > 0.
+            }
+        };
+        assert!(size_is_valid, "Invalid trigger volume: {volume:?}");
+
+        self.adtv(volume)
+    }
+
+    #[inline]
+    /// # Errors
+    ///
+    /// If there is no trigger volume with the given ID, this will return an error
+    pub fn remove_trigger_volume(self: Pin<&mut Self>, volume_id: u32) -> Result<(), NoTriggerVolumeFound> {
+        if self.rmvtv(volume_id) {
+            Ok(())
+        } else {
+            Err(NoTriggerVolumeFound(volume_id))
+        }
+    }
+
+    #[inline]
+    /// Returns the trigger volume at the given ID
+    ///
+    /// # Errors
+    ///
+    /// If there is no trigger volume with the given ID, this will return an error
+    pub fn get_trigger_volume(&self, volume_id: u32) -> Result<TriggerVolume, NoTriggerVolumeFound> {
+        if self.has_trigger_volume(volume_id) {
+            Ok(self.gtv(volume_id))
+        } else {
+            Err(NoTriggerVolumeFound(volume_id))
+        }
+    }
+}
@@ -0,0 +1,341 @@
+//! Changing mutators while an arena is running, for tweaking physics live from a console or a file
+//!
+//! Every change is reported as a `MutatorChange`, so other parts of a session (like a HUD, a log or a viewer)
+//! can react to it. Mutators are named after the fields of `MutatorConfig`, and values are written as:
+//!
+//! - numbers: `650`, `-0.5`
+//! - vectors: `0 0 -650` or `[0, 0, -650]`
+//! - booleans: `true` or `false`
+//! - demo modes: `normal`, `on_contact` or `disabled`
+
+use crate::{
+    math::Vec3,
+    sim::{Arena, DemoMode, MutatorConfig},
+};
+use core::pin::Pin;
+use std::{
+    error::Error,
+    fmt, fs,
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// The value of a single mutator
+pub enum MutatorValue {
+    Float(f32),
+    Vec3(Vec3),
+    Bool(bool),
+    DemoMode(DemoMode),
+}
+
+impl fmt::Display for MutatorValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Float(value) => write!(f, "{value}"),
+            Self::Vec3(vec) => write!(f, "{} {} {}", vec.x, vec.y, vec.z),
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::DemoMode(DemoMode::Normal) => f.write_str("normal"),
+            Self::DemoMode(DemoMode::OnContact) => f.write_str("on_contact"),
+            Self::DemoMode(DemoMode::Disabled) => f.write_str("disabled"),
+        }
+    }
+}
+
+impl MutatorValue {
+    /// Parses the text as the same kind of value as `self`
+    fn parse_like(self, text: &str) -> Option<Self> {
+        let text = text.trim();
+
+        Some(match self {
+            Self::Float(_) => Self::Float(text.parse().ok()?),
+            Self::Vec3(_) => {
+                let mut parts = text
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|part| !part.is_empty())
+                    .map(str::parse::<f32>);
+
+                let vec = Vec3::new(parts.next()?.ok()?, parts.next()?.ok()?, parts.next()?.ok()?);
+                if parts.next().is_some() {
+                    return None;
+                }
+
+                Self::Vec3(vec)
+            }
+            Self::Bool(_) => Self::Bool(text.parse().ok()?),
+            Self::DemoMode(_) => Self::DemoMode(match text {
+                "normal" => DemoMode::Normal,
+                "on_contact" => DemoMode::OnContact,
+                "disabled" => DemoMode::Disabled,
+                _ => return None,
+            }),
+        })
+    }
+}
+
+macro_rules! mutators {
+    ($($name:ident: $kind:ident),* $(,)?) => {
+        /// The names of all of the mutators, in the order of the fields of `MutatorConfig`
+        pub const MUTATOR_NAMES: &[&str] = &[$(stringify!($name)),*];
+
+        #[must_use]
+        /// Returns the value of the mutator with the given name, if there is one
+        pub fn get_mutator(config: &MutatorConfig, name: &str) -> Option<MutatorValue> {
+            match name {
+                $(stringify!($name) => Some(MutatorValue::$kind(config.$name)),)*
+                _ => None,
+            }
+        }
+
+        fn set_value(config: &mut MutatorConfig, name: &str, value: MutatorValue) {
+            match (name, value) {
+                $((stringify!($name), MutatorValue::$kind(value)) => config.$name = value,)*
+                _ => unreachable!(),
+            }
+        }
+    };
+}
+
+mutators!(
+    gravity: Vec3,
+    car_mass: Float,
+    car_world_friction: Float,
+    car_world_restitution: Float,
+    ball_mass: Float,
+    ball_max_speed: Float,
+    ball_drag: Float,
+    ball_world_friction: Float,
+    ball_world_restitution: Float,
+    jump_accel: Float,
+    jump_immediate_force: Float,
+    boost_accel_ground: Float,
+    boost_accel_air: Float,
+    boost_used_per_second: Float,
+    respawn_delay: Float,
+    bump_cooldown_time: Float,
+    boost_pad_cooldown_big: Float,
+    boost_pad_cooldown_small: Float,
+    car_spawn_boost_amount: Float,
+    ball_hit_extra_force_scale: Float,
+    bump_force_scale: Float,
+    ball_radius: Float,
+    unlimited_flips: Bool,
+    unlimited_double_jumps: Bool,
+    demo_mode: DemoMode,
+    enable_team_demos: Bool,
+    goal_base_threshold_y: Float,
+);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Reasons that a mutator can't be set from text
+pub enum InvalidMutator {
+    /// There's no mutator with the name
+    UnknownName(String),
+    /// The value can't be parsed as the kind of value the mutator has
+    InvalidValue { name: String, value: String },
+}
+
+impl Error for InvalidMutator {}
+impl fmt::Display for InvalidMutator {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownName(name) => write!(f, "There's no mutator named {name}."),
+            Self::InvalidValue { name, value } => write!(f, "Invalid value {value} for the mutator {name}."),
+        }
+    }
+}
+
+/// Sets the mutator with the given name from text, see the module documentation for the format
+///
+/// # Errors
+///
+/// Returns an error if there's no mutator with the name, or the value can't be parsed
+pub fn set_mutator(config: &mut MutatorConfig, name: &str, value: &str) -> Result<(), InvalidMutator> {
+    let current = get_mutator(config, name).ok_or_else(|| InvalidMutator::UnknownName(name.to_string()))?;
+    let value = current.parse_like(value).ok_or_else(|| InvalidMutator::InvalidValue {
+        name: name.to_string(),
+        value: value.to_string(),
+    })?;
+
+    set_value(config, name, value);
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// A mutator that was changed while the arena was running
+pub struct MutatorChange {
+    /// The tick count of the arena when the mutator was changed
+    pub tick_count: u64,
+    /// The name of the mutator, one of `MUTATOR_NAMES`
+    pub name: &'static str,
+    pub old: MutatorValue,
+    pub new: MutatorValue,
+}
+
+impl fmt::Display for MutatorChange {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.name, self.old, self.new)
+    }
+}
+
+#[must_use]
+/// Returns every mutator that's different between the configs, in the order of `MUTATOR_NAMES`
+pub fn diff_mutators(old: &MutatorConfig, new: &MutatorConfig, tick_count: u64) -> Vec<MutatorChange> {
+    MUTATOR_NAMES
+        .iter()
+        .filter_map(|&name| {
+            let old = get_mutator(old, name)?;
+            let new = get_mutator(new, name)?;
+
+            (old != new).then_some(MutatorChange {
+                tick_count,
+                name,
+                old,
+                new,
+            })
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+/// Detects mutator changes between consecutive observations, including changes made by other code
+pub struct MutatorTracker {
+    prev: Option<MutatorConfig>,
+}
+
+impl MutatorTracker {
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { prev: None }
+    }
+
+    /// Forget the previously observed mutators
+    pub fn clear(&mut self) {
+        self.prev = None;
+    }
+
+    /// Returns the changes since the last update, the first update never has any
+    pub fn update(&mut self, config: &MutatorConfig, tick_count: u64) -> Vec<MutatorChange> {
+        let changes = self
+            .prev
+            .map(|prev| diff_mutators(&prev, config, tick_count))
+            .unwrap_or_default();
+
+        self.prev = Some(*config);
+        changes
+    }
+}
+
+impl Arena {
+    /// Changes the mutators of the running arena with the function, and returns what was changed
+    pub fn update_mutators(self: Pin<&mut Self>, update: impl FnOnce(&mut MutatorConfig)) -> Vec<MutatorChange> {
+        let old = self.get_mutator_config();
+        let mut new = old;
+        update(&mut new);
+
+        let changes = diff_mutators(&old, &new, self.get_tick_count());
+        if !changes.is_empty() {
+            self.set_mutator_config(new);
+        }
+
+        changes
+    }
+
+    /// Sets a single mutator of the running arena from text, see the `tuning` module documentation for the format
+    ///
+    /// Returns `None` if the mutator already had the value
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there's no mutator with the name, or the value can't be parsed
+    pub fn set_mutator(self: Pin<&mut Self>, name: &str, value: &str) -> Result<Option<MutatorChange>, InvalidMutator> {
+        let mut config = self.get_mutator_config();
+        set_mutator(&mut config, name, value)?;
+
+        Ok(self.update_mutators(|mutators| *mutators = config).pop())
+    }
+}
+
+/// A file of mutators that's applied to an arena whenever it changes
+///
+/// Every line is `name = value`, lines starting with `#` are comments, and mutators that aren't in the file are left as-is.
+/// ```text
+/// # moon gravity
+/// gravity = 0 0 -108
+/// boost_accel_air = 1500
+/// ```
+#[derive(Clone, Debug)]
+pub struct MutatorFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl MutatorFile {
+    #[must_use]
+    /// Watches the file, which doesn't have to exist yet
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            modified: None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Parses the text of a mutator file into the config
+    ///
+    /// # Errors
+    ///
+    /// Returns the line number (starting at 1) and the reason of the first line that's invalid
+    pub fn parse(text: &str, config: &mut MutatorConfig) -> Result<(), (usize, InvalidMutator)> {
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (name, value) = line.split_once('=').unwrap_or((line, ""));
+            set_mutator(config, name.trim(), value).map_err(|e| (i + 1, e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Applies the file to the arena if it was modified since the last poll, and returns what was changed
+    ///
+    /// If the file doesn't exist, nothing happens. If the file is invalid, the arena is left as-is,
+    /// and the file is applied again once it's modified.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read, or has an invalid line
+    pub fn poll(&mut self, arena: Pin<&mut Arena>) -> io::Result<Vec<MutatorChange>> {
+        let modified = match fs::metadata(&self.path) {
+            Ok(metadata) => metadata.modified()?,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        if self.modified == Some(modified) {
+            return Ok(Vec::new());
+        }
+        self.modified = Some(modified);
+
+        let text = fs::read_to_string(&self.path)?;
+        let mut config = arena.get_mutator_config();
+        Self::parse(&text, &mut config)
+            .map_err(|(line, e)| io::Error::new(ErrorKind::InvalidData, format!("Line {line}: {e}")))?;
+
+        Ok(arena.update_mutators(|mutators| *mutators = config))
+    }
+}
@@ -0,0 +1,192 @@
+//! Conversions between Rocket League's units/axes and more common conventions
+//!
+//! Rocket League uses Unreal units (1 uu = 1 cm) in a left-handed, Z-up coordinate system.
+
+use crate::{
+    math::{RotMat, Vec3},
+    sim::{BallState, CarState},
+    GameState,
+};
+
+/// Meters per Unreal unit
+pub const UU_TO_M: f32 = 0.01;
+/// Unreal units per meter
+pub const M_TO_UU: f32 = 100.;
+/// km/h per uu/s
+pub const UUS_TO_KMH: f32 = 0.036;
+/// uu/s per km/h
+pub const KMH_TO_UUS: f32 = 1. / UUS_TO_KMH;
+
+#[inline]
+#[must_use]
+pub fn uu_to_m(uu: f32) -> f32 {
+    uu * UU_TO_M
+}
+
+#[inline]
+#[must_use]
+pub fn m_to_uu(m: f32) -> f32 {
+    m * M_TO_UU
+}
+
+#[inline]
+#[must_use]
+pub fn uus_to_kmh(uus: f32) -> f32 {
+    uus * UUS_TO_KMH
+}
+
+#[inline]
+#[must_use]
+pub fn kmh_to_uus(kmh: f32) -> f32 {
+    kmh * KMH_TO_UUS
+}
+
+impl Vec3 {
+    #[inline]
+    #[must_use]
+    /// Converts from Rocket League's left-handed Z-up axes to right-handed Y-up axes, by swapping Y and Z
+    ///
+    /// Only valid for positions and directions, use `ang_vel_to_y_up` for angular velocities
+    pub const fn to_y_up(self) -> Self {
+        Self::new(self.x, self.z, self.y)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Converts from right-handed Y-up axes back to Rocket League's left-handed Z-up axes
+    pub const fn from_y_up(self) -> Self {
+        self.to_y_up()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Converts an angular velocity to right-handed Y-up axes
+    ///
+    /// Handedness changes, so the direction of rotation is also reversed
+    pub const fn ang_vel_to_y_up(self) -> Self {
+        Self::new(-self.x, -self.z, -self.y)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Converts an angular velocity from right-handed Y-up axes back to Rocket League's axes
+    pub const fn ang_vel_from_y_up(self) -> Self {
+        self.ang_vel_to_y_up()
+    }
+}
+
+impl RotMat {
+    #[inline]
+    #[must_use]
+    /// Converts each of the axes to right-handed Y-up axes
+    pub const fn to_y_up(self) -> Self {
+        Self::new(self.forward.to_y_up(), self.right.to_y_up(), self.up.to_y_up())
+    }
+}
+
+/// How each kind of vector should be converted
+struct Conversion {
+    /// Positions, velocities, and other linear quantities
+    linear: fn(Vec3) -> Vec3,
+    /// Unit directions, like normals and rotation axes
+    dir: fn(Vec3) -> Vec3,
+    /// Angular velocities
+    ang: fn(Vec3) -> Vec3,
+}
+
+impl Conversion {
+    fn ball(&self, ball: &BallState) -> BallState {
+        BallState {
+            pos: (self.linear)(ball.pos),
+            rot_mat: RotMat::new(
+                (self.dir)(ball.rot_mat.forward),
+                (self.dir)(ball.rot_mat.right),
+                (self.dir)(ball.rot_mat.up),
+            ),
+            vel: (self.linear)(ball.vel),
+            ang_vel: (self.ang)(ball.ang_vel),
+            ..*ball
+        }
+    }
+
+    fn car(&self, car: &CarState) -> CarState {
+        let mut world_contact = car.world_contact;
+        world_contact.contact_normal = (self.dir)(world_contact.contact_normal);
+
+        let mut ball_hit_info = car.ball_hit_info;
+        ball_hit_info.relative_pos_on_ball = (self.linear)(ball_hit_info.relative_pos_on_ball);
+        ball_hit_info.ball_pos = (self.linear)(ball_hit_info.ball_pos);
+        ball_hit_info.extra_hit_vel = (self.linear)(ball_hit_info.extra_hit_vel);
+
+        CarState {
+            pos: (self.linear)(car.pos),
+            rot_mat: RotMat::new(
+                (self.dir)(car.rot_mat.forward),
+                (self.dir)(car.rot_mat.right),
+                (self.dir)(car.rot_mat.up),
+            ),
+            vel: (self.linear)(car.vel),
+            ang_vel: (self.ang)(car.ang_vel),
+            world_contact,
+            ball_hit_info,
+            ..*car
+        }
+    }
+
+    fn game_state(&self, state: &GameState) -> GameState {
+        GameState {
+            tick_rate: state.tick_rate,
+            tick_count: state.tick_count,
+            game_mode: state.game_mode,
+            cars: state
+                .cars
+                .iter()
+                .map(|car| {
+                    let mut car = *car;
+                    car.state = self.car(&car.state);
+                    car
+                })
+                .collect(),
+            ball: self.ball(&state.ball),
+            pads: state
+                .pads
+                .iter()
+                .map(|pad| {
+                    let mut pad = *pad;
+                    pad.config.position = (self.linear)(pad.config.position);
+                    pad
+                })
+                .collect(),
+        }
+    }
+}
+
+impl GameState {
+    #[must_use]
+    /// Returns a copy of the game state with all positions in meters and all velocities in m/s
+    ///
+    /// Angular velocities are already in rad/s and are left as-is, as are the axes.
+    /// The result should not be passed back to an arena.
+    pub fn to_si_units(&self) -> Self {
+        Conversion {
+            linear: |v| v * UU_TO_M,
+            dir: |v| v,
+            ang: |v| v,
+        }
+        .game_state(self)
+    }
+
+    #[must_use]
+    /// Returns a copy of the game state with all vectors converted to right-handed Y-up axes
+    ///
+    /// Units are left as-is, combine with `to_si_units` for meters.
+    /// The result should not be passed back to an arena.
+    pub fn to_y_up(&self) -> Self {
+        Conversion {
+            linear: Vec3::to_y_up,
+            dir: Vec3::to_y_up,
+            ang: Vec3::ang_vel_to_y_up,
+        }
+        .game_state(self)
+    }
+}
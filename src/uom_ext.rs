@@ -0,0 +1,210 @@
+//! Unit-safe views of the simulation state, using [uom](https://docs.rs/uom)
+//!
+//! RocketSim works in Unreal units (1 uu = 1 cm) and seconds, but nothing stops a raw `f32` in uu/s
+//! from being added to one in m/s, or a per-tick value from being used as a per-second one.
+//! The types here carry their dimension, so those mistakes become compile errors.
+
+pub use uom;
+
+use crate::{
+    math::{RotMat, Vec3},
+    sim::{BallState, CarState},
+};
+use uom::si::{
+    angular_velocity::radian_per_second,
+    f32::{AngularVelocity, Length, Time, Velocity},
+    length::centimeter,
+    time::second,
+    velocity::centimeter_per_second,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// A 3D vector of a physical quantity
+pub struct Vector<Q> {
+    pub x: Q,
+    pub y: Q,
+    pub z: Q,
+}
+
+impl<Q> Vector<Q> {
+    #[inline]
+    #[must_use]
+    pub const fn new(x: Q, y: Q, z: Q) -> Self {
+        Self { x, y, z }
+    }
+}
+
+pub type Position = Vector<Length>;
+pub type LinearVelocity = Vector<Velocity>;
+pub type AngularVelocity3 = Vector<AngularVelocity>;
+
+macro_rules! impl_vector {
+    ($quantity:ty, $unit:ty, $from_raw:ident, $to_raw:ident, $raw_unit:literal) => {
+        impl Vector<$quantity> {
+            #[inline]
+            #[must_use]
+            #[doc = concat!("Creates the vector from raw values in ", $raw_unit)]
+            pub fn $from_raw(vec: Vec3) -> Self {
+                Self::new(
+                    <$quantity>::new::<$unit>(vec.x),
+                    <$quantity>::new::<$unit>(vec.y),
+                    <$quantity>::new::<$unit>(vec.z),
+                )
+            }
+
+            #[inline]
+            #[must_use]
+            #[doc = concat!("Returns the raw values in ", $raw_unit)]
+            pub fn $to_raw(self) -> Vec3 {
+                Vec3::new(self.x.get::<$unit>(), self.y.get::<$unit>(), self.z.get::<$unit>())
+            }
+
+            #[inline]
+            #[must_use]
+            pub fn length(self) -> $quantity {
+                <$quantity>::new::<$unit>(self.$to_raw().length())
+            }
+        }
+
+        impl core::ops::Add for Vector<$quantity> {
+            type Output = Self;
+
+            #[inline]
+            fn add(self, rhs: Self) -> Self {
+                Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+            }
+        }
+
+        impl core::ops::Sub for Vector<$quantity> {
+            type Output = Self;
+
+            #[inline]
+            fn sub(self, rhs: Self) -> Self {
+                Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+            }
+        }
+
+        impl core::ops::Mul<f32> for Vector<$quantity> {
+            type Output = Self;
+
+            #[inline]
+            fn mul(self, rhs: f32) -> Self {
+                Self::new(self.x * rhs, self.y * rhs, self.z * rhs)
+            }
+        }
+    };
+}
+
+impl_vector!(Length, centimeter, from_uu, to_uu, "uu");
+impl_vector!(Velocity, centimeter_per_second, from_uus, to_uus, "uu/s");
+impl_vector!(AngularVelocity, radian_per_second, from_rads, to_rads, "rad/s");
+
+impl Vector<Velocity> {
+    #[inline]
+    #[must_use]
+    /// The distance travelled at this velocity over the given time
+    pub fn displacement(self, time: Time) -> Position {
+        Position::new(self.x * time, self.y * time, self.z * time)
+    }
+}
+
+#[inline]
+#[must_use]
+/// Converts a number of ticks to a duration
+pub fn ticks_to_time(ticks: u64, tick_rate: f32) -> Time {
+    Time::new::<second>(ticks as f32 / tick_rate)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// A unit-safe view of the physical state of the ball
+pub struct BallStateSI {
+    pub pos: Position,
+    pub rot_mat: RotMat,
+    pub vel: LinearVelocity,
+    pub ang_vel: AngularVelocity3,
+}
+
+impl BallStateSI {
+    /// Writes the physical state back into a ball state, leaving everything else as-is
+    pub fn apply_to(&self, ball: &mut BallState) {
+        ball.pos = self.pos.to_uu();
+        ball.rot_mat = self.rot_mat;
+        ball.vel = self.vel.to_uus();
+        ball.ang_vel = self.ang_vel.to_rads();
+    }
+}
+
+impl From<BallState> for BallStateSI {
+    #[inline]
+    fn from(ball: BallState) -> Self {
+        Self {
+            pos: Position::from_uu(ball.pos),
+            rot_mat: ball.rot_mat,
+            vel: LinearVelocity::from_uus(ball.vel),
+            ang_vel: AngularVelocity3::from_rads(ball.ang_vel),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// A unit-safe view of the physical state of a car
+pub struct CarStateSI {
+    pub pos: Position,
+    pub rot_mat: RotMat,
+    pub vel: LinearVelocity,
+    pub ang_vel: AngularVelocity3,
+    /// Boost amount, from 0 to 100
+    pub boost: f32,
+    pub is_on_ground: bool,
+    pub is_demoed: bool,
+    pub air_time: Time,
+    pub demo_respawn_timer: Time,
+}
+
+impl CarStateSI {
+    /// Writes the physical state back into a car state, leaving everything else as-is
+    pub fn apply_to(&self, car: &mut CarState) {
+        car.pos = self.pos.to_uu();
+        car.rot_mat = self.rot_mat;
+        car.vel = self.vel.to_uus();
+        car.ang_vel = self.ang_vel.to_rads();
+        car.boost = self.boost;
+        car.is_on_ground = self.is_on_ground;
+        car.is_demoed = self.is_demoed;
+        car.air_time = self.air_time.get::<second>();
+        car.demo_respawn_timer = self.demo_respawn_timer.get::<second>();
+    }
+}
+
+impl From<CarState> for CarStateSI {
+    #[inline]
+    fn from(car: CarState) -> Self {
+        Self {
+            pos: Position::from_uu(car.pos),
+            rot_mat: car.rot_mat,
+            vel: LinearVelocity::from_uus(car.vel),
+            ang_vel: AngularVelocity3::from_rads(car.ang_vel),
+            boost: car.boost,
+            is_on_ground: car.is_on_ground,
+            is_demoed: car.is_demoed,
+            air_time: Time::new::<second>(car.air_time),
+            demo_respawn_timer: Time::new::<second>(car.demo_respawn_timer),
+        }
+    }
+}
+
+impl CarState {
+    #[inline]
+    #[must_use]
+    pub fn to_si(self) -> CarStateSI {
+        self.into()
+    }
+}
+
+impl BallState {
+    #[inline]
+    #[must_use]
+    pub fn to_si(self) -> BallStateSI {
+        self.into()
+    }
+}
@@ -0,0 +1,479 @@
+//! Validation and sanitization of game states before they are set
+//!
+//! NaN/infinite values, objects outside of the field, overlapping cars, and rotation matrices
+//! that aren't orthonormal are all accepted by the physics engine, but silently break the simulation.
+
+use crate::{
+    consts,
+    field::Field,
+    math::{RotMat, Vec3},
+    sim::{Arena, BallState, CarConfig, CarControls, CarState, MutatorConfig},
+    targets::Object,
+    GameState, NoCarFound,
+};
+use core::pin::Pin;
+use std::{error::Error, fmt};
+
+/// The maximum error in the length of, or the dot product between, the axes of an orthonormal rotation matrix
+pub const ORTHONORMAL_TOLERANCE: f32 = 1e-3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Issue {
+    /// A value is NaN or infinite
+    NonFinite {
+        object: Object,
+        field: &'static str,
+    },
+    /// A value is finite, but outside of its valid range
+    OutOfRange {
+        object: Object,
+        field: &'static str,
+    },
+    /// The object is (partially) outside of the field
+    OutOfBounds {
+        object: Object,
+    },
+    NonOrthonormal {
+        object: Object,
+    },
+    /// The hitboxes of two cars are inside of each other
+    Overlapping {
+        car_1: u32,
+        car_2: u32,
+    },
+}
+
+impl fmt::Display for Issue {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NonFinite { object, field } => write!(f, "{object:?} has a non-finite {field}"),
+            Self::OutOfRange { object, field } => write!(f, "{object:?} has an out of range {field}"),
+            Self::OutOfBounds { object } => write!(f, "{object:?} is out of bounds"),
+            Self::NonOrthonormal { object } => write!(f, "{object:?} has a non-orthonormal rotation matrix"),
+            Self::Overlapping { car_1, car_2 } => write!(f, "Car {car_1} and car {car_2} are overlapping"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Error for when a game state has one or more issues
+pub struct InvalidState(pub Vec<Issue>);
+
+impl Error for InvalidState {}
+impl fmt::Display for InvalidState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid game state: ")?;
+
+        for (i, issue) in self.0.iter().enumerate() {
+            if i != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{issue}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SanitizeMode {
+    /// Don't set anything if there are any issues
+    #[default]
+    Reject,
+    /// Correct any issues before setting the state
+    Correct,
+}
+
+#[derive(Clone, Debug)]
+pub enum SetStateError {
+    Invalid(InvalidState),
+    NoCarFound(NoCarFound),
+}
+
+impl Error for SetStateError {}
+impl fmt::Display for SetStateError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid(e) => e.fmt(f),
+            Self::NoCarFound(e) => e.fmt(f),
+        }
+    }
+}
+
+impl From<NoCarFound> for SetStateError {
+    #[inline]
+    fn from(e: NoCarFound) -> Self {
+        Self::NoCarFound(e)
+    }
+}
+
+#[inline]
+fn is_rot_mat_finite(rot_mat: RotMat) -> bool {
+    rot_mat.forward.is_finite() && rot_mat.right.is_finite() && rot_mat.up.is_finite()
+}
+
+impl RotMat {
+    #[must_use]
+    /// Returns true if all of the axes are unit length and perpendicular to each other, within `ORTHONORMAL_TOLERANCE`
+    pub fn is_orthonormal(&self) -> bool {
+        let Self { forward, right, up } = *self;
+
+        [forward, right, up]
+            .into_iter()
+            .all(|axis| (axis.length() - 1.).abs() <= ORTHONORMAL_TOLERANCE)
+            && [forward.dot(right), forward.dot(up), right.dot(up)]
+                .into_iter()
+                .all(|dot| dot.abs() <= ORTHONORMAL_TOLERANCE)
+    }
+}
+
+/// Moves the position inwards until it's at least `margin` away from every surface of the field
+fn push_inside(field: &Field, mut pos: Vec3, margin: f32) -> Vec3 {
+    // corners need multiple pushes
+    for _ in 0..4 {
+        let surface = field.nearest_surface_point(pos);
+        if surface.distance >= margin {
+            break;
+        }
+
+        pos += surface.normal * (margin - surface.distance);
+    }
+
+    pos
+}
+
+/// A state that is checked, and optionally corrected
+struct Checker {
+    field: Option<Field>,
+    ball_radius: f32,
+    fix: bool,
+    issues: Vec<Issue>,
+}
+
+impl Checker {
+    fn new(game_state: &GameState, fix: bool) -> Self {
+        Self {
+            field: Field::new(game_state.game_mode),
+            ball_radius: MutatorConfig::default(game_state.game_mode).ball_radius,
+            fix,
+            issues: Vec::new(),
+        }
+    }
+
+    fn vec(&mut self, object: Object, field: &'static str, vec: &mut Vec3, default: Vec3) {
+        if !vec.is_finite() {
+            self.issues.push(Issue::NonFinite { object, field });
+
+            if self.fix {
+                *vec = default;
+            }
+        }
+    }
+
+    fn rot_mat(&mut self, object: Object, rot_mat: &mut RotMat) {
+        if !is_rot_mat_finite(*rot_mat) {
+            self.issues.push(Issue::NonFinite {
+                object,
+                field: "rot_mat",
+            });
+
+            if self.fix {
+                *rot_mat = RotMat::IDENTITY;
+            }
+        } else if !rot_mat.is_orthonormal() {
+            self.issues.push(Issue::NonOrthonormal { object });
+
+            if self.fix {
+                *rot_mat = rot_mat.orthonormalize();
+            }
+        }
+    }
+
+    fn bounds(&mut self, object: Object, pos: &mut Vec3, margin: f32) {
+        let Some(field) = self.field else {
+            return;
+        };
+
+        if field.nearest_surface_point(*pos).distance < margin {
+            self.issues.push(Issue::OutOfBounds { object });
+
+            if self.fix {
+                *pos = push_inside(&field, *pos, margin);
+            }
+        }
+    }
+
+    fn ball(&mut self, ball: &mut BallState) {
+        let object = Object::Ball;
+        let default = BallState::default();
+
+        self.vec(object, "pos", &mut ball.pos, default.pos);
+        self.vec(object, "vel", &mut ball.vel, Vec3::ZERO);
+        self.vec(object, "ang_vel", &mut ball.ang_vel, Vec3::ZERO);
+        self.rot_mat(object, &mut ball.rot_mat);
+
+        // allow the ball to be pressed slightly into a surface, like it is when resting on the floor
+        if ball.pos.is_finite() {
+            self.bounds(object, &mut ball.pos, self.ball_radius - 1.);
+        }
+    }
+
+    fn car(&mut self, id: u32, car: &mut CarState) {
+        let object = Object::Car(id);
+        let default = CarState::default();
+
+        self.vec(object, "pos", &mut car.pos, default.pos);
+        self.vec(object, "vel", &mut car.vel, Vec3::ZERO);
+        self.vec(object, "ang_vel", &mut car.ang_vel, Vec3::ZERO);
+        self.rot_mat(object, &mut car.rot_mat);
+
+        if !car.boost.is_finite() {
+            self.issues.push(Issue::NonFinite { object, field: "boost" });
+
+            if self.fix {
+                car.boost = 0.;
+            }
+        } else if !(0. ..=consts::BOOST_MAX).contains(&car.boost) {
+            self.issues.push(Issue::OutOfRange { object, field: "boost" });
+
+            if self.fix {
+                car.boost = car.boost.clamp(0., consts::BOOST_MAX);
+            }
+        }
+
+        if car.pos.is_finite() && !car.is_demoed {
+            self.bounds(object, &mut car.pos, 0.);
+        }
+    }
+
+    fn overlaps(&mut self, game_state: &mut GameState) {
+        for i in 0..game_state.cars.len() {
+            for j in i + 1..game_state.cars.len() {
+                let (a, b) = (&game_state.cars[i], &game_state.cars[j]);
+                if a.state.is_demoed || b.state.is_demoed {
+                    continue;
+                }
+
+                // the narrowest dimension of the hitboxes, so only definite overlaps are flagged
+                let overlap_distance = (a.config.hitbox_size.y + b.config.hitbox_size.y) / 2.;
+                let offset = b.state.pos - a.state.pos;
+                if offset.length() >= overlap_distance {
+                    continue;
+                }
+
+                self.issues.push(Issue::Overlapping {
+                    car_1: a.id,
+                    car_2: b.id,
+                });
+
+                if self.fix {
+                    // the longest dimension of the hitboxes, so they definitely aren't overlapping anymore
+                    let safe_distance = (a.config.hitbox_size.x + b.config.hitbox_size.x) / 2.;
+
+                    let mut dir = Vec3::new(offset.x, offset.y, 0.).normalize_or_zero();
+                    if dir == Vec3::ZERO {
+                        dir = Vec3::X;
+                    }
+
+                    let mut pos = a.state.pos + dir * safe_distance;
+                    pos.z = b.state.pos.z;
+                    if let Some(field) = self.field {
+                        pos = push_inside(&field, pos, 0.);
+                    }
+
+                    game_state.cars[j].state.pos = pos;
+                }
+            }
+        }
+    }
+
+    fn game_state(mut self, game_state: &mut GameState) -> Vec<Issue> {
+        self.ball(&mut game_state.ball);
+
+        for car in &mut game_state.cars {
+            self.car(car.id, &mut car.state);
+        }
+
+        self.overlaps(game_state);
+        self.issues
+    }
+}
+
+impl GameState {
+    #[must_use]
+    /// Returns all of the issues with the game state, without changing anything
+    pub fn issues(&self) -> Vec<Issue> {
+        Checker::new(self, false).game_state(&mut self.clone())
+    }
+
+    /// Checks the game state for NaN/infinite values, out-of-bounds positions,
+    /// overlapping cars, and non-orthonormal rotation matrices
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidState` with all of the issues that were found
+    pub fn validate(&self) -> Result<(), InvalidState> {
+        let issues = self.issues();
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(InvalidState(issues))
+        }
+    }
+
+    /// Corrects all of the issues that `validate` would find, returning the issues that were corrected
+    ///
+    /// Non-finite values are reset, rotation matrices are orthonormalized,
+    /// out-of-bounds objects are pushed back inside the field, and overlapping cars are pushed apart.
+    pub fn sanitize(&mut self) -> Vec<Issue> {
+        Checker::new(self, true).game_state(self)
+    }
+}
+
+impl Arena {
+    /// Full game state setter, that checks the state first
+    ///
+    /// Returns the issues that were corrected, which is always empty with `SanitizeMode::Reject`
+    ///
+    /// # Errors
+    ///
+    /// Returns `SetStateError::Invalid` if the state has issues and `mode` is `SanitizeMode::Reject`,
+    /// or `SetStateError::NoCarFound` upon the first car that cannot be found from a given ID
+    pub fn set_game_state_checked(
+        self: Pin<&mut Self>,
+        game_state: &GameState,
+        mode: SanitizeMode,
+    ) -> Result<Vec<Issue>, SetStateError> {
+        match mode {
+            SanitizeMode::Reject => {
+                game_state.validate().map_err(SetStateError::Invalid)?;
+                self.set_game_state(game_state)?;
+                Ok(Vec::new())
+            }
+            SanitizeMode::Correct => {
+                let mut game_state = game_state.clone();
+                let issues = game_state.sanitize();
+                self.set_game_state(&game_state)?;
+                Ok(issues)
+            }
+        }
+    }
+}
+
+/// RocketSim's dodge deadzone for every car preset
+pub const DEFAULT_DODGE_DEADZONE: f32 = 0.5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Error for when car controls have a NaN or infinite value, with the name of the first such field
+pub struct NonFiniteControls(pub &'static str);
+
+impl Error for NonFiniteControls {}
+impl fmt::Display for NonFiniteControls {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Car controls have a non-finite {}", self.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// How car controls are cleaned up by `CarControls::sanitize`
+pub struct ControlSettings {
+    /// When jumping, pitch/yaw/roll that add up to less than this are zeroed,
+    /// since RocketSim would double jump instead of dodging anyway
+    pub dodge_deadzone: f32,
+    /// If not zero, analog inputs are rounded to the nearest multiple of this,
+    /// e.g. `1. / 127.` for the resolution of an 8-bit controller axis
+    pub quantization: f32,
+}
+
+impl Default for ControlSettings {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            dodge_deadzone: DEFAULT_DODGE_DEADZONE,
+            quantization: 0.,
+        }
+    }
+}
+
+impl ControlSettings {
+    #[inline]
+    #[must_use]
+    /// Uses the dodge deadzone of the car, without quantization
+    pub const fn from_car_config(config: &CarConfig) -> Self {
+        Self {
+            dodge_deadzone: config.dodge_deadzone,
+            quantization: 0.,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn with_quantization(mut self, quantization: f32) -> Self {
+        self.quantization = quantization;
+        self
+    }
+}
+
+impl CarControls {
+    /// Checks that all of the analog inputs are finite
+    ///
+    /// # Errors
+    ///
+    /// Returns `NonFiniteControls` with the first field that's NaN or infinite
+    pub fn validate(&self) -> Result<(), NonFiniteControls> {
+        let fields = [
+            (self.throttle, "throttle"),
+            (self.steer, "steer"),
+            (self.pitch, "pitch"),
+            (self.yaw, "yaw"),
+            (self.roll, "roll"),
+        ];
+
+        match fields.into_iter().find(|(value, _)| !value.is_finite()) {
+            Some((_, field)) => Err(NonFiniteControls(field)),
+            None => Ok(()),
+        }
+    }
+
+    /// Makes the controls safe to pass to the sim, e.g. when they come straight from a neural network
+    ///
+    /// Analog inputs are clamped to `-1..=1` and quantized,
+    /// then the dodge deadzone is applied if the car is jumping.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NonFiniteControls` if any of the analog inputs are NaN or infinite, since there's no right value to use instead
+    pub fn sanitize(self, settings: &ControlSettings) -> Result<Self, NonFiniteControls> {
+        self.validate()?;
+
+        let analog = |value: f32| {
+            let value = value.clamp(-1., 1.);
+            if settings.quantization > 0. {
+                ((value / settings.quantization).round() * settings.quantization).clamp(-1., 1.)
+            } else {
+                value
+            }
+        };
+
+        let mut controls = Self {
+            throttle: analog(self.throttle),
+            steer: analog(self.steer),
+            pitch: analog(self.pitch),
+            yaw: analog(self.yaw),
+            roll: analog(self.roll),
+            ..self
+        };
+
+        if controls.jump && controls.pitch.abs() + controls.yaw.abs() + controls.roll.abs() < settings.dodge_deadzone {
+            controls.pitch = 0.;
+            controls.yaw = 0.;
+            controls.roll = 0.;
+        }
+
+        Ok(controls)
+    }
+}
@@ -0,0 +1,206 @@
+//! Detecting arenas that have ended up in a broken state, for long unattended runs
+//!
+//! NaN/infinite values and objects outside of the field are flagged immediately,
+//! and cars are flagged once they have been stuck inside of the field's geometry for too long.
+//! Depending on the `WatchdogConfig`, the arena is then also snapshotted and/or reset to a kickoff.
+
+use crate::{field::Field, math::Vec3, sim::Arena, targets::Object, GameState};
+use core::pin::Pin;
+use cxx::UniquePtr;
+use std::fmt;
+
+/// The default distance (uu) that the center of an object has to be outside of the field to be out of bounds
+pub const DEFAULT_OUT_OF_BOUNDS_MARGIN: f32 = 100.;
+/// The default distance (uu) from the center of a car to a surface, below which it's intersecting the geometry
+///
+/// A car resting on its wheels is about `consts::CAR_SPAWN_REST_Z` away from the floor
+pub const DEFAULT_STUCK_DISTANCE: f32 = 5.;
+/// The default number of ticks that a car has to be intersecting the geometry for to be stuck
+pub const DEFAULT_STUCK_TICKS: u32 = 360;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WatchdogIssue {
+    /// The position, velocity or angular velocity of the object is NaN or infinite
+    NonFinite { object: Object },
+    /// The center of the object is further than the margin outside of the field
+    OutOfBounds { object: Object },
+    /// The car has been intersecting the field's geometry for `ticks` updates in a row
+    Stuck { car_id: u32, ticks: u32 },
+}
+
+impl fmt::Display for WatchdogIssue {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NonFinite { object } => write!(f, "{object:?} has a non-finite state"),
+            Self::OutOfBounds { object } => write!(f, "{object:?} is out of bounds"),
+            Self::Stuck { car_id, ticks } => write!(f, "Car {car_id} has been stuck for {ticks} ticks"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_utils", derive(serde::Serialize, serde::Deserialize))]
+pub struct WatchdogConfig {
+    /// How far (uu) the center of an object has to be outside of the field to be out of bounds
+    pub out_of_bounds_margin: f32,
+    /// How close (uu) the center of a car has to be to a surface to be intersecting the geometry
+    pub stuck_distance: f32,
+    /// How many updates in a row a car has to be intersecting the geometry for to be stuck
+    pub stuck_ticks: u32,
+    /// Clone the arena (before resetting it) when there are issues, so it can be inspected or saved
+    pub snapshot: bool,
+    /// Reset the arena to a random kickoff when there are issues
+    pub reset: bool,
+}
+
+impl Default for WatchdogConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            out_of_bounds_margin: DEFAULT_OUT_OF_BOUNDS_MARGIN,
+            stuck_distance: DEFAULT_STUCK_DISTANCE,
+            stuck_ticks: DEFAULT_STUCK_TICKS,
+            snapshot: false,
+            reset: false,
+        }
+    }
+}
+
+/// The issues that were found in an update, and what was done about them
+pub struct WatchdogEvent {
+    /// The tick count of the arena when the issues were found
+    pub tick_count: u64,
+    pub issues: Vec<WatchdogIssue>,
+    /// A clone of the arena from before it was reset, if `WatchdogConfig::snapshot` is set
+    ///
+    /// It can be written to a file with `Arena::save` (with the `bin` feature)
+    pub snapshot: Option<UniquePtr<Arena>>,
+    /// Whether the arena was reset to a kickoff
+    pub reset: bool,
+}
+
+impl fmt::Debug for WatchdogEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WatchdogEvent")
+            .field("tick_count", &self.tick_count)
+            .field("issues", &self.issues)
+            .field("snapshot", &self.snapshot.is_some())
+            .field("reset", &self.reset)
+            .finish()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+/// Checks for broken states after every step
+///
+/// Call `update` (or `check`, which doesn't act on the issues) after every step of the arena.
+/// While an issue persists, it's reported on every update.
+pub struct Watchdog {
+    pub config: WatchdogConfig,
+    /// The number of updates in a row that each car has been intersecting the geometry for
+    stuck_ticks: Vec<(u32, u32)>,
+}
+
+impl Watchdog {
+    #[inline]
+    #[must_use]
+    pub const fn new(config: WatchdogConfig) -> Self {
+        Self {
+            config,
+            stuck_ticks: Vec::new(),
+        }
+    }
+
+    /// Forget how long the cars have been stuck for
+    pub fn clear(&mut self) {
+        self.stuck_ticks.clear();
+    }
+
+    fn check_object(
+        &self,
+        field: Option<&Field>,
+        object: Object,
+        pos: Vec3,
+        vel: Vec3,
+        ang_vel: Vec3,
+    ) -> Option<WatchdogIssue> {
+        if !pos.is_finite() || !vel.is_finite() || !ang_vel.is_finite() {
+            return Some(WatchdogIssue::NonFinite { object });
+        }
+
+        field
+            .filter(|field| field.nearest_surface_point(pos).distance < -self.config.out_of_bounds_margin)
+            .map(|_| WatchdogIssue::OutOfBounds { object })
+    }
+
+    /// Returns all of the issues with the game state, and updates how long the cars have been stuck for
+    pub fn check(&mut self, game_state: &GameState) -> Vec<WatchdogIssue> {
+        let field = Field::new(game_state.game_mode);
+        let mut issues = Vec::new();
+
+        let ball = &game_state.ball;
+        issues.extend(self.check_object(field.as_ref(), Object::Ball, ball.pos, ball.vel, ball.ang_vel));
+
+        let mut stuck_ticks = Vec::with_capacity(game_state.cars.len());
+        for car in &game_state.cars {
+            if car.state.is_demoed {
+                continue;
+            }
+
+            let state = &car.state;
+            if let Some(issue) = self.check_object(field.as_ref(), Object::Car(car.id), state.pos, state.vel, state.ang_vel)
+            {
+                issues.push(issue);
+                continue;
+            }
+
+            let is_intersecting = field
+                .as_ref()
+                .is_some_and(|field| field.nearest_surface_point(state.pos).distance < self.config.stuck_distance);
+            if !is_intersecting {
+                continue;
+            }
+
+            let ticks = self
+                .stuck_ticks
+                .iter()
+                .find(|(id, _)| *id == car.id)
+                .map_or(0, |(_, ticks)| *ticks)
+                + 1;
+            stuck_ticks.push((car.id, ticks));
+
+            if ticks >= self.config.stuck_ticks {
+                issues.push(WatchdogIssue::Stuck { car_id: car.id, ticks });
+            }
+        }
+
+        self.stuck_ticks = stuck_ticks;
+        issues
+    }
+
+    /// Checks the arena's current state, and snapshots and/or resets the arena if there are any issues
+    ///
+    /// Returns `None` if there were no issues
+    pub fn update(&mut self, mut arena: Pin<&mut Arena>) -> Option<WatchdogEvent> {
+        let game_state = arena.as_mut().get_game_state();
+        let issues = self.check(&game_state);
+        if issues.is_empty() {
+            return None;
+        }
+
+        let snapshot = self.config.snapshot.then(|| arena.clone(false));
+
+        if self.config.reset {
+            arena.reset_to_random_kickoff(None);
+            self.clear();
+        }
+
+        Some(WatchdogEvent {
+            tick_count: game_state.tick_count,
+            issues,
+            snapshot,
+            reset: self.config.reset,
+        })
+    }
+}
@@ -122,15 +122,35 @@ fn game_state() {
     assert!(game_state.ball.pos.z < 200.);
     assert_eq!(game_state.cars.len(), 2);
     assert_eq!(game_state.pads.len(), 34);
+    // standard matches shouldn't need any heap allocations
+    assert!(!game_state.cars.spilled());
+    assert!(!game_state.pads.spilled());
 
     #[cfg(feature = "glam")]
     {
         // test converison to glam
-        let glam_state = GameStateA::from(game_state);
+        let glam_state = GameStateA::from(game_state.clone());
         assert_eq!(glam_state.tick_count, 120);
         assert!(glam_state.ball.pos.z < 200.);
         assert_eq!(glam_state.cars.len(), 2);
         assert_eq!(glam_state.pads.len(), 34);
+
+        #[cfg(feature = "bin")]
+        {
+            use rocketsim_rs::bytes::{FromBytes, ToBytes};
+
+            // the glam variant has the same byte layout
+            let bytes = glam_state.to_bytes();
+            assert_eq!(bytes, game_state.to_bytes());
+
+            let from_bytes = GameStateA::from_bytes(&bytes);
+            assert_eq!(from_bytes.cars[1].state.pos, glam_state.cars[1].state.pos);
+            assert_eq!(from_bytes.ball.rot_mat, glam_state.ball.rot_mat);
+        }
+
+        let round_trip = glam_state.to_game_state();
+        assert_eq!(round_trip.ball.pos, game_state.ball.pos);
+        assert_eq!(round_trip.cars[0].state.rot_mat, game_state.cars[0].state.rot_mat);
     }
 
     arena.pin_mut().reset_tick_count();
@@ -171,6 +191,98 @@ fn angles() {
     }
 }
 
+#[test]
+fn rot_mat_helpers() {
+    INIT.call_once(|| init(None, true));
+
+    let close = |a: Vec3, b: Vec3| (a - b).length() < 1e-5;
+
+    // a slightly skewed and scaled matrix, like one built by hand in a state setter
+    let skewed = RotMat::new(
+        Vec3::new(1.02, 0.05, 0.),
+        Vec3::new(-0.03, 0.98, 0.01),
+        Vec3::new(0.02, 0., 1.01),
+    );
+    assert!(!skewed.is_orthonormal());
+
+    let fixed = skewed.orthonormalize();
+    assert!(fixed.is_orthonormal());
+    assert!(close(fixed.forward, skewed.forward.normalize_or_zero()));
+    assert!(fixed.up.dot(Vec3::Z) > 0.99);
+    assert_eq!(RotMat::IDENTITY.orthonormalize(), RotMat::IDENTITY);
+
+    // the same handedness as RocketSim's own matrices
+    let angle = Angle {
+        yaw: 0.7,
+        pitch: 0.3,
+        roll: 0.,
+    };
+    let rot_mat = angle.to_rotmat();
+    let looked = RotMat::look_at(rot_mat.forward * 3., rot_mat.up * 0.5);
+    assert!(close(looked.forward, rot_mat.forward));
+    assert!(close(looked.right, rot_mat.right));
+    assert!(close(looked.up, rot_mat.up));
+
+    let flat = RotMat::from_forward(Vec3::new(0., 2., 0.));
+    assert!(close(flat.forward, Vec3::Y));
+    assert!(close(flat.up, Vec3::Z));
+    assert!(flat.is_orthonormal());
+
+    let straight_up = RotMat::from_forward(Vec3::Z);
+    assert!(close(straight_up.forward, Vec3::Z));
+    assert!(straight_up.is_orthonormal());
+
+    assert_eq!(RotMat::from_forward(Vec3::ZERO), RotMat::IDENTITY);
+    assert_eq!(RotMat::look_at(Vec3::Z, Vec3::Z * 2.), RotMat::IDENTITY);
+}
+
+#[test]
+fn math_ops() {
+    INIT.call_once(|| init(None, true));
+
+    let close = |a: Vec3, b: Vec3| (a - b).length() < 1e-4;
+
+    let a = Vec3::new(1., 2., 3.);
+    let b = Vec3::new(-2., 0.5, 4.);
+    assert!(close(a * b, Vec3::new(-2., 1., 12.)));
+    assert!(close(2. * a, a * 2.));
+    assert!(close(a.min(b), Vec3::new(-2., 0.5, 3.)));
+    assert!(close(a.max(b), Vec3::new(1., 2., 4.)));
+    assert!(close(a.lerp(b, 0.5), Vec3::new(-0.5, 1.25, 3.5)));
+    assert!((a.distance(b) - (a - b).length()).abs() < f32::EPSILON);
+    assert!((Vec3::X.angle_between(Vec3::Y) - PI / 2.).abs() < 1e-5);
+    assert!((Vec3::X.angle_between(-Vec3::X) - PI).abs() < 1e-5);
+    assert!(Vec3::ZERO.try_normalize().is_none());
+    assert!((Vec3::new(300., 400., 0.).clamp_length_max(100.).length() - 100.).abs() < 1e-3);
+
+    let mut c = a;
+    c += b;
+    c -= b;
+    c *= 2.;
+    c /= 2.;
+    assert!(close(c, a));
+
+    let rot_mat = Angle {
+        yaw: 1.2,
+        pitch: -0.4,
+        roll: 0.3,
+    }
+    .to_rotmat();
+    assert!(close(rot_mat * Vec3::X, rot_mat.forward));
+    assert!(close(rot_mat.to_local(rot_mat * a), a));
+    assert!(close(rot_mat.transpose() * (rot_mat * a), a));
+    assert!(close((rot_mat.transpose() * rot_mat) * b, b));
+
+    let car = CarState {
+        pos: Vec3::new(100., 200., 17.),
+        rot_mat,
+        ..Default::default()
+    };
+    let ahead = car.pos + rot_mat.forward * 500.;
+    assert!(close(car.to_local(ahead), Vec3::new(500., 0., 0.)));
+    assert!(close(car.to_world(car.to_local(a)), a));
+}
+
 #[test]
 fn goal_score() {
     static SCORED: AtomicBool = AtomicBool::new(false);
@@ -319,6 +431,70 @@ fn demoed() {
 //     assert!(DEMOED.load(Ordering::Relaxed));
 // }
 
+#[test]
+fn car_contacts() {
+    use std::sync::Mutex;
+
+    struct Contact {
+        car_ids: [u32; 2],
+        contact_point: Vec3,
+        impulse: f32,
+        /// The X positions of the cars at the time of the contact
+        car_xs: [f32; 2],
+    }
+
+    static CONTACTS: Mutex<Vec<Contact>> = Mutex::new(Vec::new());
+    INIT.call_once(|| init(None, true));
+
+    let mut arena = Arena::default_standard();
+    // teammates, so neither is demoed
+    let left = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    let right = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+
+    for (car_id, x, vel_x) in [(left, -300., 800.), (right, 300., -800.)] {
+        arena
+            .pin_mut()
+            .set_car(
+                car_id,
+                CarState {
+                    pos: Vec3::new(x, 0., 17.),
+                    vel: Vec3::new(vel_x, 0., 0.),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+    }
+
+    arena.pin_mut().set_car_contact_callback(
+        |mut arena, car_1, car_2, contact_point, impulse, _| {
+            let x_1 = arena.as_mut().get_car(car_1).pos.x;
+            let x_2 = arena.as_mut().get_car(car_2).pos.x;
+            CONTACTS.lock().unwrap().push(Contact {
+                car_ids: [car_1, car_2],
+                contact_point,
+                impulse,
+                car_xs: [x_1, x_2],
+            });
+        },
+        0,
+    );
+
+    arena.pin_mut().step(60);
+
+    let contacts = CONTACTS.lock().unwrap();
+    assert!(!contacts.is_empty());
+    for contact in contacts.iter() {
+        let mut car_ids = contact.car_ids;
+        car_ids.sort_unstable();
+        assert_eq!(car_ids, [left, right]);
+
+        let [x_1, x_2] = contact.car_xs;
+        assert!(contact.contact_point.x > x_1.min(x_2) && contact.contact_point.x < x_1.max(x_2));
+        assert!(contact.impulse >= 0.);
+    }
+    assert!(contacts.iter().any(|contact| contact.impulse > 0.));
+}
+
 #[cfg(feature = "serde_utils")]
 #[test]
 fn game_state_serialize() {
@@ -353,3 +529,4548 @@ fn game_state_serialize() {
 
     arena.pin_mut().reset_tick_count();
 }
+
+#[test]
+fn car_events() {
+    use rocketsim_rs::events::{CarEventKind, CarEventTracker, Surface};
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+
+    let mut tracker = CarEventTracker::new();
+    assert!(tracker.update(&arena.pin_mut().get_game_state()).is_empty());
+
+    arena
+        .pin_mut()
+        .set_car(
+            car_id,
+            CarState {
+                pos: Vec3::new(0., 0., 300.),
+                is_on_ground: false,
+                wheels_with_contact: [false; 4],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    let events = tracker.update(&arena.pin_mut().get_game_state());
+    assert!(events.iter().any(|event| event.kind == CarEventKind::LeftGround));
+
+    let mut landed = false;
+    for _ in 0..240 {
+        arena.pin_mut().step(1);
+        let events = tracker.update(&arena.pin_mut().get_game_state());
+        if let Some(event) = events.iter().find(|event| matches!(event.kind, CarEventKind::Landed { .. })) {
+            assert_eq!(event.car_id, car_id);
+            assert!(matches!(
+                event.kind,
+                CarEventKind::Landed {
+                    surface: Surface::Floor,
+                    ..
+                }
+            ));
+            landed = true;
+            break;
+        }
+    }
+
+    assert!(landed);
+}
+
+#[test]
+fn propagate_ball_accuracy() {
+    use rocketsim_rs::analysis::{propagate_ball, BallPhysics};
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+
+    // heading for the side wall at y = 0, high enough to miss the ramp, then falling to the floor
+    let start = BallState {
+        pos: Vec3::new(2500., 0., 1000.),
+        vel: Vec3::new(1500., 0., 0.),
+        ..Default::default()
+    };
+    arena.pin_mut().set_ball(start);
+
+    let physics = BallPhysics::default();
+
+    // free flight should be almost exact
+    arena.pin_mut().step(60);
+    let sim = arena.pin_mut().get_ball();
+    let analytic = propagate_ball(&start, 0.5, &physics);
+    assert!(sim.vel.x > 0.);
+    assert!((sim.pos - analytic.pos).length() < 5.);
+    assert!((sim.vel - analytic.vel).length() < 5.);
+
+    // after bouncing off the wall (at ~1s) and the floor (at ~1.7s) the approximation is allowed to drift a bit
+    arena.pin_mut().step(240);
+    let sim = arena.pin_mut().get_ball();
+    let analytic = propagate_ball(&start, 2.5, &physics);
+    assert!(sim.vel.x < 0. && sim.vel.z > 0., "{sim:?}");
+    assert!(analytic.vel.x < 0. && analytic.vel.z > 0., "{analytic:?}");
+    assert!(
+        (sim.pos - analytic.pos).length() < 150.,
+        "sim {:?} vs analytic {:?}",
+        sim.pos,
+        analytic.pos
+    );
+}
+
+#[test]
+fn drive_time_accuracy() {
+    use rocketsim_rs::analysis::drive_time;
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+
+    let start = CarState {
+        pos: Vec3::new(0., -2000., 17.),
+        boost: 50.,
+        ..Default::default()
+    };
+    arena.pin_mut().set_car(car_id, start).unwrap();
+    arena
+        .pin_mut()
+        .set_car_controls(
+            car_id,
+            CarControls {
+                throttle: 1.,
+                boost: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    let target = Vec3::new(2500., -2000., 17.);
+    let estimate = drive_time(&start, target, start.boost);
+
+    let mut ticks = 0;
+    while arena.pin_mut().get_car(car_id).pos.x < target.x && ticks < 1200 {
+        arena.pin_mut().step(1);
+        ticks += 1;
+    }
+
+    let actual = ticks as f32 / 120.;
+    assert!(
+        (estimate - actual).abs() / actual < 0.1,
+        "estimate {estimate} vs actual {actual}"
+    );
+}
+
+#[test]
+fn field_queries() {
+    use rocketsim_rs::field::Field;
+    use rocketsim_rs::sim::GameMode;
+
+    let field = Field::new(GameMode::Soccar).unwrap();
+    assert!(Field::new(GameMode::TheVoid).is_none());
+
+    assert!(!field.is_in_goal(Vec3::new(0., 0., 93.)));
+    assert_eq!(field.goal_at(Vec3::new(0., -5300., 93.)), Some(Team::Blue));
+    assert_eq!(field.goal_at(Vec3::new(0., 5300., 93.)), Some(Team::Orange));
+    assert!(!field.is_in_goal(Vec3::new(0., 5300., 1000.)));
+
+    assert!((field.distance_to_nearest_wall(Vec3::new(4000., 0., 100.)) - 96.).abs() < 1e-3);
+    assert!((field.distance_to_nearest_wall(Vec3::new(0., 5400., 100.)) - 600.).abs() < 1e-3);
+
+    let corner = field.distance_to_nearest_wall(Vec3::new(3500., 4000., 100.));
+    assert!((corner - (8064. - 7500.) / 2f32.sqrt()).abs() < 1e-2);
+
+    let surface = field.nearest_surface_point(Vec3::new(0., 0., 50.));
+    assert_eq!(surface.normal, Vec3::Z);
+    assert!(surface.pos.z.abs() < 1e-3);
+    assert!(field.contains(Vec3::new(0., 0., 50.)));
+    assert!(!field.contains(Vec3::new(5000., 0., 50.)));
+}
+
+#[test]
+fn mirror_game_state() {
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let _ = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    let _ = arena.pin_mut().add_car(Team::Orange, CarConfig::octane());
+    arena.pin_mut().reset_to_random_kickoff(Some(3));
+
+    arena.pin_mut().set_ball(BallState {
+        pos: Vec3::new(500., -1000., 300.),
+        vel: Vec3::new(200., 300., 400.),
+        ang_vel: Vec3::new(1., 2., 3.),
+        ..Default::default()
+    });
+    arena.pin_mut().step(30);
+
+    let state = arena.pin_mut().get_game_state();
+
+    let mirrored = state.mirrored(true, false);
+    assert_eq!(
+        mirrored.ball.pos,
+        Vec3::new(-state.ball.pos.x, state.ball.pos.y, state.ball.pos.z)
+    );
+    assert_eq!(mirrored.ball.ang_vel.x, state.ball.ang_vel.x);
+    assert_eq!(mirrored.ball.ang_vel.y, -state.ball.ang_vel.y);
+    assert_eq!(mirrored.ball.ang_vel.z, -state.ball.ang_vel.z);
+
+    for (car, mirrored_car) in state.cars.iter().zip(&mirrored.cars) {
+        let rot = mirrored_car.state.rot_mat;
+        assert!((rot.forward.cross(rot.right) - rot.up).length() < 1e-4);
+        assert_eq!(car.team, mirrored_car.team);
+    }
+
+    let round_trip = mirrored.mirrored(true, false);
+    assert_eq!(round_trip.ball.pos, state.ball.pos);
+    assert_eq!(round_trip.ball.ang_vel, state.ball.ang_vel);
+    for (car, round_trip_car) in state.cars.iter().zip(&round_trip.cars) {
+        assert_eq!(car.state.pos, round_trip_car.state.pos);
+        assert_eq!(car.state.rot_mat.right, round_trip_car.state.rot_mat.right);
+    }
+    for (pad, round_trip_pad) in state.pads.iter().zip(&round_trip.pads) {
+        assert_eq!(pad.state, round_trip_pad.state);
+    }
+
+    let normalized = state.normalized_for_team(Team::Orange);
+    assert_eq!(
+        normalized.ball.pos,
+        Vec3::new(-state.ball.pos.x, -state.ball.pos.y, state.ball.pos.z)
+    );
+    for (car, normalized_car) in state.cars.iter().zip(&normalized.cars) {
+        assert_eq!(car.team.opposite(), normalized_car.team);
+        assert_eq!(car.state.vel.z, normalized_car.state.vel.z);
+    }
+}
+
+#[test]
+fn mirror_car_on_side_wall() {
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+
+    // driving along the left side wall
+    arena
+        .pin_mut()
+        .set_car(
+            car_id,
+            CarState {
+                pos: Vec3::new(-4096. + 17., 0., 1000.),
+                rot_mat: RotMat::look_at(Vec3::Y, Vec3::X),
+                vel: Vec3::new(0., 1000., 0.),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    arena
+        .pin_mut()
+        .set_car_controls(
+            car_id,
+            CarControls {
+                throttle: 1.,
+                steer: 1.,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    arena.pin_mut().step(10);
+
+    let mut state = arena.pin_mut().get_car(car_id);
+    assert!(state.pos.x < -3500. && state.rot_mat.up.x > 0.5, "{:?}", state.pos);
+    // as if the front right wheel lost contact, and the car started to auto flip
+    state.wheels_with_contact = [true, false, true, true];
+    state.auto_flip_torque_scale = 1.;
+
+    let mirrored = state.mirrored(true, false);
+    assert!(mirrored.pos.x > 3500.);
+    assert_eq!(mirrored.wheels_with_contact, [false, true, true, true]);
+    assert_eq!(mirrored.auto_flip_torque_scale, -1.);
+    assert_eq!(mirrored.last_controls.steer, -1.);
+
+    // rotating the field swaps nothing
+    let rotated = state.mirrored(true, true);
+    assert_eq!(rotated.wheels_with_contact, state.wheels_with_contact);
+    assert_eq!(rotated.auto_flip_torque_scale, state.auto_flip_torque_scale);
+
+    let round_trip = mirrored.mirrored(true, false);
+    assert_eq!(round_trip.wheels_with_contact, state.wheels_with_contact);
+    assert_eq!(round_trip.auto_flip_torque_scale, state.auto_flip_torque_scale);
+}
+
+#[test]
+fn unit_conversions() {
+    use rocketsim_rs::units;
+
+    assert!((units::uus_to_kmh(rocketsim_rs::consts::CAR_MAX_SPEED) - 82.8).abs() < 1e-3);
+    assert!((units::kmh_to_uus(units::uus_to_kmh(1234.)) - 1234.).abs() < 1e-2);
+    assert!((units::m_to_uu(units::uu_to_m(4096.)) - 4096.).abs() < 1e-3);
+
+    let v = Vec3::new(1., 2., 3.);
+    assert_eq!(v.to_y_up(), Vec3::new(1., 3., 2.));
+    assert_eq!(v.to_y_up().from_y_up(), v);
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let _ = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    arena.pin_mut().reset_to_random_kickoff(Some(1));
+
+    let state = arena.pin_mut().get_game_state();
+    let si = state.to_si_units();
+    assert!((si.ball.pos.z - state.ball.pos.z / 100.).abs() < 1e-5);
+    assert!((si.cars[0].state.pos.y - state.cars[0].state.pos.y / 100.).abs() < 1e-5);
+    assert_eq!(si.cars[0].state.rot_mat, state.cars[0].state.rot_mat);
+
+    let y_up = state.to_y_up();
+    assert_eq!(y_up.ball.pos.y, state.ball.pos.z);
+    assert_eq!(y_up.cars[0].state.rot_mat.up, state.cars[0].state.rot_mat.up.to_y_up());
+}
+
+#[test]
+#[should_panic = "Invalid tick rate 241"]
+fn tick_rate_above_max() {
+    use rocketsim_rs::sim::{ArenaConfig, GameMode};
+
+    INIT.call_once(|| init(None, true));
+    let _ = Arena::new(GameMode::Soccar, ArenaConfig::default(), 241);
+}
+
+#[test]
+fn tick_rates() {
+    use rocketsim_rs::sim::{ArenaConfig, GameMode};
+
+    INIT.call_once(|| init(None, true));
+
+    assert!(Arena::try_new(GameMode::Soccar, ArenaConfig::default(), 10).is_err());
+    assert!(Arena::try_new(GameMode::Soccar, ArenaConfig::default(), 250).is_err());
+
+    let mut arena = Arena::try_new(GameMode::Soccar, ArenaConfig::default(), 240).unwrap();
+    assert!((arena.get_tick_rate() - 240.).abs() < 1e-3);
+
+    arena.pin_mut().set_ball(BallState {
+        pos: Vec3::new(0., 0., 1000.),
+        ..Default::default()
+    });
+    arena.pin_mut().step(240);
+    let ball_240 = arena.pin_mut().get_ball();
+
+    let mut arena_120 = Arena::default_standard();
+    arena_120.pin_mut().set_ball(BallState {
+        pos: Vec3::new(0., 0., 1000.),
+        ..Default::default()
+    });
+    arena_120.pin_mut().step(120);
+    let ball_120 = arena_120.pin_mut().get_ball();
+    assert!((ball_240.pos.z - ball_120.pos.z).abs() < 5.);
+
+    assert!(arena.pin_mut().set_tick_rate(1000.).is_err());
+    arena.pin_mut().set_tick_rate(60.).unwrap();
+    assert!((arena.get_tick_time() - 1. / 60.).abs() < 1e-6);
+
+    let jump = CarControls {
+        jump: true,
+        ..Default::default()
+    };
+    let controls = [CarControls::default(), jump, CarControls::default(), CarControls::default()];
+
+    let upsampled = CarControls::resample(&controls, 120., 240.);
+    assert_eq!(upsampled.len(), 8);
+    assert_eq!(upsampled.iter().filter(|c| c.jump).count(), 2);
+
+    let downsampled = CarControls::resample(&controls, 120., 60.);
+    assert_eq!(downsampled.len(), 2);
+    assert!(downsampled[0].jump);
+    assert!(!downsampled[1].jump);
+}
+
+#[test]
+fn interpolate_game_state() {
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    arena.pin_mut().reset_to_random_kickoff(Some(0));
+
+    arena
+        .pin_mut()
+        .set_car_controls(
+            car_id,
+            CarControls {
+                throttle: 1.,
+                steer: 1.,
+                boost: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    arena.pin_mut().step(60);
+    let prev = arena.pin_mut().get_game_state();
+    arena.pin_mut().step(1);
+    let next = arena.pin_mut().get_game_state();
+
+    let start = prev.interpolate(&next, 0.);
+    assert!((start.cars[0].state.pos - prev.cars[0].state.pos).length() < 1e-3);
+    let end = prev.interpolate(&next, 1.);
+    assert!((end.cars[0].state.pos - next.cars[0].state.pos).length() < 1e-3);
+
+    let mid = prev.interpolate(&next, 0.5);
+    let expected = (prev.cars[0].state.pos + next.cars[0].state.pos) / 2.;
+    assert!((mid.cars[0].state.pos - expected).length() < 1e-3);
+
+    let rot = mid.cars[0].state.rot_mat;
+    assert!((rot.forward.length() - 1.).abs() < 1e-4);
+    assert!(rot.forward.dot(rot.up).abs() < 1e-4);
+    assert!(
+        (prev.cars[0].state.rot_mat.slerp(next.cars[0].state.rot_mat, 1.).forward - next.cars[0].state.rot_mat.forward)
+            .length()
+            < 1e-3
+    );
+
+    // teleporting the ball shouldn't be interpolated
+    let mut teleported = next.clone();
+    teleported.ball.pos = Vec3::new(3000., 3000., 500.);
+    let mid = prev.interpolate(&teleported, 0.25);
+    assert_eq!(mid.ball.pos, prev.ball.pos);
+}
+
+#[test]
+fn real_time_stepper() {
+    use rocketsim_rs::realtime::RealTimeStepper;
+    use std::time::{Duration, Instant};
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+
+    // 1 second of game time should take ~10ms at 100x speed
+    let mut stepper = RealTimeStepper::from_arena(&arena);
+    stepper.set_speed(100.);
+
+    let start = Instant::now();
+    while stepper.total_ticks() < 120 {
+        stepper.step(arena.pin_mut());
+    }
+
+    assert!(start.elapsed() >= Duration::from_millis(9));
+    assert_eq!(arena.get_tick_count(), stepper.total_ticks());
+
+    // stalling should be caught up on, but only up to the limit
+    stepper.set_max_catch_up_ticks(5);
+    std::thread::sleep(Duration::from_millis(50));
+    assert_eq!(stepper.ticks_due(), 5);
+    assert!(stepper.dropped_ticks() > 0);
+
+    // out of range speeds are clamped instead of breaking the schedule
+    for speed in [0., -1., 1e-30, f32::INFINITY] {
+        stepper.set_speed(speed);
+        assert!(RealTimeStepper::SPEED_RANGE.contains(&stepper.speed()));
+    }
+    stepper.wait();
+    stepper.set_speed(f32::NAN);
+    assert_eq!(stepper.speed(), *RealTimeStepper::SPEED_RANGE.end());
+    assert_eq!(RealTimeStepper::new(0.).tick_rate(), 1.);
+}
+
+#[cfg(feature = "bin")]
+#[test]
+fn render_message_bytes() {
+    use rocketsim_rs::{
+        bytes::{FromBytes, ToBytes},
+        render::{Color, Render, RenderMessage, Renderer, Vec2},
+    };
+
+    let mut renderer = Renderer::new(7);
+    renderer.draw_polyline((0..10).map(|i| Vec3::new(i as f32 * 10., 0., 100.)), Color::RED);
+    renderer.draw_sphere(Vec3::new(0., 0., 93.), 92.75, Color::GREEN);
+    renderer.draw_text(Vec3::new(0., 0., 200.), "target", 1., Color::WHITE);
+    renderer.draw_text_2d(Vec2::new(10., 10.), "ünïcödé", 2., Color::BLUE);
+    renderer.draw_line_2d(Vec2::ZERO, Vec2::new(100., 100.), Color::BLACK);
+
+    let message = renderer.take_message();
+    assert!(renderer.is_empty());
+
+    let bytes = message.to_bytes();
+    assert_eq!(RenderMessage::get_num_bytes(&bytes), bytes.len());
+    assert_eq!(format!("{:?}", RenderMessage::from_bytes(&bytes)), format!("{message:?}"));
+
+    let bytes = renderer.remove_message().to_bytes();
+    assert!(matches!(RenderMessage::from_bytes(&bytes), RenderMessage::RemoveRender(7)));
+
+    // too long for the length prefix, and the last character is cut in half at u16::MAX bytes
+    let long_text = format!("{}é", "a".repeat(u16::MAX as usize - 1));
+    renderer.draw_text(Vec3::ZERO, long_text.as_str(), 1., Color::WHITE);
+    let bytes = renderer.take_message().to_bytes();
+    assert_eq!(RenderMessage::get_num_bytes(&bytes), bytes.len());
+
+    let RenderMessage::AddRender(_, renders) = RenderMessage::from_bytes(&bytes) else {
+        panic!("Expected renders");
+    };
+    let [Render::Text { text, scale, .. }] = renders.as_slice() else {
+        panic!("Expected a single text, got {renders:?}");
+    };
+    assert_eq!(text.as_str(), &long_text[..u16::MAX as usize - 1]);
+    assert_eq!(*scale, 1.);
+}
+
+#[cfg(feature = "bin")]
+#[test]
+fn state_set_policy() {
+    use rocketsim_rs::{
+        bytes::ToBytes,
+        protocol::{StateSetAuth, StateSetError, StateSetPolicy, UdpPacketTypes},
+    };
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let _ = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    let state_bytes = arena.pin_mut().get_game_state().to_bytes();
+
+    let mut deny = StateSetPolicy::Deny;
+    assert_eq!(
+        deny.accept(UdpPacketTypes::GameState, &state_bytes).unwrap_err(),
+        StateSetError::NotAllowed
+    );
+
+    let mut allow = StateSetPolicy::Allow;
+    assert_eq!(allow.accept(UdpPacketTypes::GameState, &state_bytes).unwrap().cars.len(), 1);
+
+    // truncated or garbage game states are rejected, even when anything goes
+    let mut bad_game_mode = state_bytes.clone();
+    bad_game_mode[12] = 0xff;
+    for payload in [
+        &[][..],
+        &state_bytes[..10],
+        &state_bytes[..state_bytes.len() - 1],
+        &bad_game_mode,
+    ] {
+        assert_eq!(
+            allow.accept(UdpPacketTypes::GameState, payload).unwrap_err(),
+            StateSetError::Malformed
+        );
+    }
+    assert_eq!(
+        allow
+            .accept(UdpPacketTypes::AuthGameState, &state_bytes[..StateSetAuth::HEADER_NUM_BYTES])
+            .unwrap_err(),
+        StateSetError::Malformed
+    );
+
+    let client = StateSetAuth::new("secret");
+    let mut auth = StateSetPolicy::Authenticated(StateSetAuth::new("secret"));
+    assert_eq!(
+        auth.accept(UdpPacketTypes::GameState, &state_bytes).unwrap_err(),
+        StateSetError::Unauthenticated
+    );
+
+    let signed = client.sign(1, &state_bytes);
+    assert!(auth.accept(UdpPacketTypes::AuthGameState, &signed).is_ok());
+    assert_eq!(
+        auth.accept(UdpPacketTypes::AuthGameState, &signed).unwrap_err(),
+        StateSetError::Replayed
+    );
+
+    let forged = StateSetAuth::new("wrong").sign(2, &state_bytes);
+    assert_eq!(
+        auth.accept(UdpPacketTypes::AuthGameState, &forged).unwrap_err(),
+        StateSetError::BadSignature
+    );
+
+    let mut tampered = client.sign(3, &state_bytes);
+    *tampered.last_mut().unwrap() ^= 1;
+    assert_eq!(
+        auth.accept(UdpPacketTypes::AuthGameState, &tampered).unwrap_err(),
+        StateSetError::BadSignature
+    );
+}
+
+#[cfg(feature = "bin")]
+#[test]
+fn session_control() {
+    use rocketsim_rs::{
+        protocol::{SessionCommand, SessionControl},
+        realtime::RealTimeStepper,
+    };
+
+    for command in [
+        SessionCommand::Pause,
+        SessionCommand::Resume,
+        SessionCommand::Step(3),
+        SessionCommand::Speed(2.5),
+        SessionCommand::Seek(1 << 40),
+    ] {
+        assert_eq!(
+            SessionCommand::from_packet(command.packet_type(), &command.payload()),
+            Some(command)
+        );
+    }
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let mut stepper = RealTimeStepper::from_arena(&arena);
+    let mut session = SessionControl::new();
+
+    session.apply(SessionCommand::Speed(100.));
+    session.apply(SessionCommand::Step(5));
+    assert_eq!(session.pending_steps(), 0);
+
+    session.apply(SessionCommand::Pause);
+    session.apply(SessionCommand::Step(2));
+    assert_eq!(session.step(&mut stepper, arena.pin_mut()), 1);
+    assert_eq!(session.step(&mut stepper, arena.pin_mut()), 1);
+    assert_eq!(session.step(&mut stepper, arena.pin_mut()), 0);
+    assert_eq!(arena.get_tick_count(), 2);
+    assert_eq!(stepper.speed(), 100.);
+
+    // a viewer can't send speeds that the stepper can't handle
+    session.apply(SessionCommand::Speed(1e-30));
+    assert_eq!(session.speed(), *RealTimeStepper::SPEED_RANGE.start());
+    session.apply(SessionCommand::Speed(f32::NAN));
+    session.apply(SessionCommand::Speed(-1.));
+    session.apply(SessionCommand::Speed(f32::INFINITY));
+    assert_eq!(session.speed(), *RealTimeStepper::SPEED_RANGE.end());
+
+    session.apply(SessionCommand::Resume);
+    while arena.get_tick_count() < 10 {
+        session.step(&mut stepper, arena.pin_mut());
+    }
+}
+
+#[cfg(feature = "bin")]
+#[test]
+fn replay_round_trip() {
+    use rocketsim_rs::replay::{ReplayReader, ReplayWriter};
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let _ = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    let _ = arena.pin_mut().add_car(Team::Orange, CarConfig::dominus());
+    arena.pin_mut().reset_to_random_kickoff(Some(2));
+
+    let mut writer = ReplayWriter::new(Vec::new()).unwrap();
+    let mut states = Vec::new();
+    for _ in 0..10 {
+        arena.pin_mut().step(8);
+        let state = arena.pin_mut().get_game_state();
+        writer.write_state(&state).unwrap();
+        states.push(state);
+    }
+    assert_eq!(writer.num_frames(), 10);
+
+    let bytes = writer.into_inner();
+    let read = ReplayReader::new(bytes.as_slice())
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(read.len(), states.len());
+    for (state, read) in states.iter().zip(&read) {
+        assert_eq!(state.tick_count, read.tick_count);
+        assert_eq!(state.ball.pos, read.ball.pos);
+        assert_eq!(state.cars[1].state.pos, read.cars[1].state.pos);
+    }
+
+    assert!(ReplayReader::new(&bytes[..bytes.len() - 1]).unwrap().last().unwrap().is_err());
+    assert!(ReplayReader::new(&b"nope!"[..]).is_err());
+}
+
+#[test]
+fn heatseeker_stats() {
+    use rocketsim_rs::{
+        smallvec::smallvec,
+        stats::{HeatseekerEventKind, HeatseekerTracker},
+        CarInfo, GameState,
+    };
+
+    let mut state = GameState {
+        tick_rate: 120.,
+        cars: smallvec![
+            CarInfo {
+                id: 1,
+                team: Team::Blue,
+                ..Default::default()
+            },
+            CarInfo {
+                id: 2,
+                team: Team::Orange,
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+    state.ball.hs_info.y_target_dir = -1.;
+    state.ball.pos.y = -4500.;
+
+    let mut tracker = HeatseekerTracker::new();
+    assert!(tracker.update(&state).is_empty());
+
+    // blue touches the ball away from their own net
+    state.tick_count = 1;
+    state.ball.hs_info.y_target_dir = 1.;
+    state.ball.hs_info.cur_target_speed += 85.;
+    state.cars[0].state.ball_hit_info.is_valid = true;
+    state.cars[0].state.ball_hit_info.tick_count_when_hit = 1;
+
+    let events = tracker.update(&state);
+    let kinds = events.iter().map(|event| event.kind).collect::<Vec<_>>();
+    assert!(kinds.contains(&HeatseekerEventKind::TargetSwitch {
+        y_target_dir: 1.,
+        touched_by: Some(Team::Blue)
+    }));
+    assert!(kinds.contains(&HeatseekerEventKind::Return { team: Team::Blue }));
+    assert!(kinds.contains(&HeatseekerEventKind::Save { team: Team::Blue }));
+    assert!(kinds.iter().any(|kind| matches!(kind, HeatseekerEventKind::SpeedUp { .. })));
+
+    // the ball bounces off of orange's back wall without a touch
+    state.tick_count = 2;
+    state.ball.pos.y = 5000.;
+    state.ball.hs_info.y_target_dir = -1.;
+    let events = tracker.update(&state);
+    assert_eq!(
+        events[0].kind,
+        HeatseekerEventKind::TargetSwitch {
+            y_target_dir: -1.,
+            touched_by: None
+        }
+    );
+
+    let stats = tracker.stats();
+    assert_eq!(stats.target_switches, 2);
+    assert_eq!(stats.speed_ups, 1);
+    assert_eq!(stats.returns(Team::Blue), 1);
+    assert_eq!(stats.saves(Team::Blue), 1);
+    assert_eq!(stats.returns(Team::Orange), 0);
+}
+
+#[test]
+fn carball_stats() {
+    use rocketsim_rs::{
+        sim::{BoostPadConfig, BoostPadState},
+        smallvec::smallvec,
+        stats::CarballTracker,
+        BoostPad, CarInfo, GameState,
+    };
+
+    let mut state = GameState {
+        tick_rate: 120.,
+        cars: smallvec![
+            CarInfo {
+                id: 1,
+                team: Team::Blue,
+                ..Default::default()
+            },
+            CarInfo {
+                id: 2,
+                team: Team::Orange,
+                ..Default::default()
+            },
+        ],
+        pads: smallvec![BoostPad {
+            config: BoostPadConfig {
+                position: Vec3::new(3072., 4096., 73.),
+                is_big: true,
+            },
+            state: BoostPadState {
+                is_active: true,
+                ..Default::default()
+            },
+        }],
+        ..Default::default()
+    };
+    state.cars[0].state.pos = Vec3::new(0., -1000., 17.);
+    state.cars[0].state.is_on_ground = true;
+    state.cars[0].state.boost = 50.;
+    state.cars[1].state.pos = Vec3::new(3072., 4000., 17.);
+    state.cars[1].state.boost = 20.;
+
+    let mut tracker = CarballTracker::new();
+    tracker.update(&state);
+
+    // blue boosts and shoots, orange picks up its own big pad
+    state.tick_count = 120;
+    state.cars[0].state.boost = 40.;
+    state.cars[0].state.ball_hit_info.is_valid = true;
+    state.cars[0].state.ball_hit_info.tick_count_when_hit = 120;
+    state.ball.pos = Vec3::new(0., 3000., 93.);
+    state.ball.vel = Vec3::new(0., 2000., 0.);
+    state.cars[1].state.boost = 100.;
+    state.pads[0].state.is_active = false;
+    tracker.update(&state);
+
+    // the ball goes in
+    state.tick_count = 240;
+    state.ball.pos.y = 5300.;
+    tracker.update(&state);
+
+    let blue = tracker.player(1).unwrap();
+    assert_eq!(blue.boost.boost_usage, 10.);
+    assert_eq!(blue.hit_counts.total_hits, 1);
+    assert_eq!(blue.hit_counts.total_shots, 1);
+    assert_eq!(blue.hit_counts.total_goals, 1);
+    assert!((blue.time_in_game - 2.).abs() < 1e-4);
+    assert!((blue.positional_tendencies.time_on_ground - 2.).abs() < 1e-4);
+    assert!(blue.possession.possession_time > 0.);
+
+    let orange = tracker.player(2).unwrap();
+    assert_eq!(orange.boost.num_large_boosts, 1);
+    assert_eq!(orange.boost.num_stolen_boosts, 0);
+    assert_eq!(orange.boost.wasted_collection, 20.);
+
+    assert_eq!(tracker.score(Team::Blue), 1);
+    assert_eq!(tracker.goals()[0].scorer, Some(1));
+
+    let json = tracker.to_json();
+    assert!(json.starts_with("{\"gameMetadata\":"));
+    assert!(json.contains("\"team0Score\":1"));
+    assert!(json.contains("\"numLargeBoosts\":1"));
+    assert!(json.contains("\"totalGoals\":1"));
+}
+
+#[test]
+fn hoops_rim_and_backboard() {
+    use rocketsim_rs::{
+        events::{HoopsEventKind, HoopsEventTracker},
+        field::hoops,
+    };
+
+    assert!(hoops::is_above_rim(Vec3::new(0., hoops::RIM_CENTER_Y, 500.)));
+    assert!(hoops::is_above_rim(Vec3::new(0., -hoops::RIM_CENTER_Y, 500.)));
+    assert!(!hoops::is_above_rim(Vec3::new(0., hoops::RIM_CENTER_Y, 200.)));
+    assert!(!hoops::is_above_rim(Vec3::new(0., 0., 500.)));
+    assert!(hoops::distance_to_rim(Vec3::new(hoops::RIM_RADIUS_X, hoops::RIM_CENTER_Y, hoops::RIM_HEIGHT)) < 1e-2);
+    assert_eq!(hoops::hoop_team(Vec3::new(0., -100., 0.)), Team::Blue);
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_hoops();
+
+    // throw the ball into the backboard above orange's hoop
+    arena.pin_mut().set_ball(BallState {
+        pos: Vec3::new(0., hoops::BACKBOARD_Y - 600., 800.),
+        vel: Vec3::new(0., 2000., 300.),
+        ..Default::default()
+    });
+
+    let mut tracker = HoopsEventTracker::new();
+    let mut events = Vec::new();
+    for _ in 0..60 {
+        arena.pin_mut().step(1);
+        events.extend(tracker.update(&arena.pin_mut().get_game_state()));
+    }
+
+    assert!(events
+        .iter()
+        .any(|event| event.kind == HoopsEventKind::BackboardContact { team: Team::Orange }));
+}
+
+#[test]
+fn void_arenas() {
+    use rocketsim_rs::sim::{ArenaConfig, GameMode};
+
+    INIT.call_once(|| init(None, true));
+
+    let mut arena = Arena::default_void();
+    assert_eq!(arena.get_game_mode(), GameMode::TheVoid);
+    assert_eq!(arena.num_pads(), 0);
+
+    arena.pin_mut().set_ball(BallState {
+        pos: Vec3::new(0., 0., 100.),
+        ..Default::default()
+    });
+    arena.pin_mut().step(120);
+    assert!(arena.pin_mut().get_ball().pos.z < -100.);
+
+    let mut arena = Arena::new_void(ArenaConfig::default(), 120, true);
+    arena.pin_mut().set_ball(BallState {
+        pos: Vec3::new(0., 0., 500.),
+        ..Default::default()
+    });
+    arena.pin_mut().step(600);
+
+    let ball = arena.pin_mut().get_ball();
+    assert!((ball.pos.z - arena.get_ball_radius()).abs() < 5.);
+
+    // the floor should survive cloning
+    let mut clone = arena.clone(false);
+    clone.pin_mut().step(120);
+    assert!(clone.pin_mut().get_ball().pos.z > 0.);
+}
+
+#[test]
+fn extra_geometry() {
+    use rocketsim_rs::{
+        geometry::StaticGeometry,
+        sim::{ArenaConfig, GameMode},
+    };
+
+    INIT.call_once(|| init(None, true));
+
+    let platform = StaticGeometry::aabb(Vec3::new(-500., -500., 900.), Vec3::new(500., 500., 1000.));
+    let ramp = StaticGeometry::Mesh {
+        vertices: vec![
+            Vec3::new(2000., -500., 0.),
+            Vec3::new(3000., -500., 500.),
+            Vec3::new(3000., 500., 500.),
+            Vec3::new(2000., 500., 0.),
+        ],
+        indices: vec![0, 1, 2, 0, 2, 3],
+    };
+    assert!(platform.is_valid());
+    assert!(ramp.is_valid());
+    assert!(!StaticGeometry::Mesh {
+        vertices: vec![Vec3::ZERO],
+        indices: vec![0, 1, 2],
+    }
+    .is_valid());
+
+    let config = ArenaConfig::default().with_extra_geometry([platform, ramp]);
+    assert_eq!(config.extra_geometry.len(), 2);
+
+    let mut arena = Arena::new_with_geometry(GameMode::Soccar, config, 120);
+    arena.pin_mut().set_ball(BallState {
+        pos: Vec3::new(0., 0., 1500.),
+        ..Default::default()
+    });
+    arena.pin_mut().step(600);
+
+    // the ball should come to rest on top of the platform instead of the floor
+    let ball = arena.pin_mut().get_ball();
+    assert!((ball.pos.z - 1000. - arena.get_ball_radius()).abs() < 10.);
+
+    let mut clone = arena.clone(false);
+    clone.pin_mut().step(120);
+    assert!(clone.pin_mut().get_ball().pos.z > 900.);
+}
+
+#[test]
+fn props() {
+    use rocketsim_rs::{
+        props::NoPropFound,
+        sim::{PropConfig, PropState},
+    };
+
+    INIT.call_once(|| init(None, true));
+
+    let mut arena = Arena::default_standard();
+    let ball_id = arena.pin_mut().add_prop(
+        PropConfig::default(),
+        PropState {
+            pos: Vec3::new(0., 1000., 500.),
+            ..Default::default()
+        },
+    );
+    let box_id = arena.pin_mut().add_prop(
+        PropConfig::cuboid(Vec3::new(50., 50., 50.), 20.),
+        PropState {
+            pos: Vec3::new(1000., 0., 50.),
+            ..Default::default()
+        },
+    );
+    assert_eq!(arena.get_props(), vec![ball_id, box_id]);
+
+    arena.pin_mut().step(240);
+
+    // props fall and come to rest on the floor
+    let ball = arena.get_prop(ball_id).unwrap();
+    assert!((ball.pos.z - PropConfig::default().radius).abs() < 5.);
+    let cube = arena.get_prop(box_id).unwrap();
+    assert!((cube.pos.z - 50.).abs() < 5.);
+
+    // props are included in the game state and survive cloning
+    let state = arena.pin_mut().get_game_state_with_props();
+    assert_eq!(state.props.len(), 2);
+
+    let mut clone = arena.clone(false);
+    assert_eq!(clone.get_prop_infos().len(), 2);
+    clone.pin_mut().remove_prop(ball_id).unwrap();
+    assert!(matches!(clone.get_prop(ball_id), Err(NoPropFound(id)) if id == ball_id));
+    assert!(arena.has_prop(ball_id));
+}
+
+#[test]
+fn rumble_items() {
+    use rocketsim_rs::rumble::{ActivationError, ItemTarget, Rumble, RumbleEventKind, RumbleItem, ITEM_GIVE_DELAY};
+
+    INIT.call_once(|| init(None, true));
+
+    let mut arena = Arena::default_standard();
+    let blue = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    let orange = arena.pin_mut().add_car(Team::Orange, CarConfig::octane());
+    arena.pin_mut().reset_to_random_kickoff(Some(0));
+
+    let mut rumble = Rumble::with_item_pool(7, vec![RumbleItem::Haymaker]);
+    rumble.update(arena.pin_mut());
+    assert_eq!(rumble.held_item(blue), None);
+    assert_eq!(rumble.activate(arena.pin_mut(), blue), Err(ActivationError::NoItem(blue)));
+
+    // both cars should be given an item after the delay
+    let mut given = 0;
+    for _ in 0..(ITEM_GIVE_DELAY * 120.) as usize + 1 {
+        arena.pin_mut().step(1);
+        given += rumble
+            .update(arena.pin_mut())
+            .iter()
+            .filter(|event| matches!(event.kind, RumbleEventKind::ItemGiven { .. }))
+            .count();
+    }
+    assert_eq!(given, 2);
+    assert_eq!(rumble.held_item(orange), Some(RumbleItem::Haymaker));
+
+    // the ball is too far away from the kickoff spot
+    arena.pin_mut().set_ball(BallState {
+        pos: Vec3::new(0., 0., 1800.),
+        ..Default::default()
+    });
+    assert!(rumble.activate(arena.pin_mut(), blue).is_err());
+
+    arena.pin_mut().set_ball(BallState::default());
+    let event = rumble.activate(arena.pin_mut(), blue).unwrap();
+    assert_eq!(
+        event.kind,
+        RumbleEventKind::ItemUsed {
+            item: RumbleItem::Haymaker,
+            target: Some(ItemTarget::Ball)
+        }
+    );
+    assert_eq!(rumble.held_item(blue), None);
+    assert!(arena.pin_mut().get_ball().vel.length() > 2000.);
+
+    // spikes hold the ball on the car
+    rumble.give_item(blue, RumbleItem::Spikes);
+    rumble.activate(arena.pin_mut(), blue).unwrap();
+    let car = arena.pin_mut().get_car(blue);
+    arena.pin_mut().set_ball(BallState {
+        pos: car.pos + car.rot_mat.up * 150.,
+        ..Default::default()
+    });
+
+    rumble.update(arena.pin_mut());
+    for _ in 0..60 {
+        arena.pin_mut().step(1);
+        rumble.update(arena.pin_mut());
+    }
+
+    let car = arena.pin_mut().get_car(blue);
+    assert!((arena.pin_mut().get_ball().pos - car.pos).length() < 200.);
+}
+
+#[test]
+fn goal_zones() {
+    use rocketsim_rs::sim::GoalZone;
+    use std::sync::atomic::AtomicUsize;
+
+    static GOALS: AtomicUsize = AtomicUsize::new(0);
+    static ZONE: AtomicUsize = AtomicUsize::new(usize::MAX);
+    INIT.call_once(|| init(None, true));
+
+    let mut arena = Arena::default_standard();
+    arena.pin_mut().set_goal_scored_callback(
+        |_, _, _| {
+            GOALS.fetch_add(1, Ordering::Relaxed);
+        },
+        0,
+    );
+    arena.pin_mut().set_goal_zone_callback(
+        |_, team, zone_index, _| {
+            assert_eq!(team, Team::Blue);
+            ZONE.store(zone_index, Ordering::Relaxed);
+        },
+        0,
+    );
+
+    // scoring is disabled, so the ball can go into the net freely
+    arena.pin_mut().disable_scoring();
+    assert!(arena.has_custom_goal_zones());
+    arena.pin_mut().set_ball(BallState {
+        pos: Vec3::new(0., 5000., 100.),
+        vel: Vec3::new(0., 2000., 0.),
+        ..Default::default()
+    });
+    arena.pin_mut().step(30);
+    assert_eq!(GOALS.load(Ordering::Relaxed), 0);
+
+    // a "top-left corner" drill
+    let corner = GoalZone::new(Vec3::new(-900., 4900., 400.), Vec3::new(-500., 5200., 650.), Team::Blue);
+    let zones = [GoalZone::new(Vec3::ZERO, Vec3::ZERO, Team::Blue), corner];
+    arena.pin_mut().set_goal_zones(&zones);
+    assert_eq!(arena.iter_goal_zones().count(), 2);
+    assert!(corner.contains(Vec3::new(-700., 5000., 500.)));
+
+    arena.pin_mut().set_ball(BallState {
+        pos: Vec3::new(-700., 4500., 500.),
+        vel: Vec3::new(0., 2000., 0.),
+        ..Default::default()
+    });
+    arena.pin_mut().step(30);
+    assert_eq!(GOALS.load(Ordering::Relaxed), 1);
+    assert_eq!(ZONE.load(Ordering::Relaxed), 1);
+
+    arena.pin_mut().use_default_goal_zones();
+    assert!(!arena.has_custom_goal_zones());
+}
+
+#[test]
+fn target_regions() {
+    use rocketsim_rs::targets::{Object, Region, Subject, Target, TargetEventKind, Targets};
+
+    INIT.call_once(|| init(None, true));
+
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    arena
+        .pin_mut()
+        .set_car(
+            car_id,
+            CarState {
+                pos: Vec3::new(0., -1000., 17.),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    arena.pin_mut().set_ball(BallState {
+        pos: Vec3::new(0., 0., 1000.),
+        ..Default::default()
+    });
+
+    let mut targets = Targets::new();
+    let floor = targets.add(
+        Target::new(Region::Box {
+            min: Vec3::new(-500., -500., 0.),
+            max: Vec3::new(500., 500., 200.),
+        })
+        .once(),
+    );
+    let parking = targets.add(
+        Target::new(Region::Cylinder {
+            base: Vec3::new(0., -1000., 0.),
+            radius: 200.,
+            height: 100.,
+        })
+        .with_subject(Subject::Team(Team::Blue))
+        .with_dwell_time(1.),
+    );
+
+    let mut events = Vec::new();
+    for _ in 0..180 {
+        arena.pin_mut().step(1);
+        events.extend(targets.update(&arena.pin_mut().get_game_state()));
+    }
+
+    let find = |target, kind| events.iter().find(|event| event.target == target && event.kind == kind);
+
+    // the ball falls into the box, and the target is removed after it's triggered
+    let entered = find(floor, TargetEventKind::Entered).unwrap();
+    assert_eq!(entered.object, Object::Ball);
+    assert!(find(floor, TargetEventKind::Triggered).is_some());
+    assert!(targets.get(floor).is_none());
+
+    // the car has to stay parked for a second before the target is triggered
+    let entered = find(parking, TargetEventKind::Entered).unwrap();
+    let triggered = find(parking, TargetEventKind::Triggered).unwrap();
+    assert_eq!(triggered.object, Object::Car(car_id));
+    assert_eq!(triggered.tick_count - entered.tick_count, 120);
+    assert_eq!(targets.occupants(parking).collect::<Vec<_>>(), vec![Object::Car(car_id)]);
+}
+
+#[test]
+#[cfg(feature = "scenario")]
+fn scenario_runner() {
+    use rocketsim_rs::scenario::{Condition, Outcome, Scenario};
+
+    INIT.call_once(|| init(None, true));
+
+    let scenario = Scenario::from_toml(
+        r#"
+        name = "Open net"
+        time_limit = 3.0
+
+        [ball]
+        pos = { x = 0.0, y = 4000.0, z = 93.15 }
+        vel = { x = 0.0, y = 2000.0, z = 0.0 }
+
+        [[cars]]
+        team = "Blue"
+        config = "Dominus"
+        pos = { x = 0.0, y = 0.0, z = 17.0 }
+        rot = { yaw = 1.5708 }
+
+        [[success]]
+        type = "Goal"
+        team = "Blue"
+
+        [[failure]]
+        type = "Goal"
+        team = "Orange"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(scenario.cars.len(), 1);
+    assert_eq!(scenario.success, vec![Condition::Goal { team: Some(Team::Blue) }]);
+
+    // round trip through JSON
+    let json = serde_json::to_string(&scenario).unwrap();
+    assert_eq!(Scenario::from_json(&json).unwrap(), scenario);
+
+    let mut runner = scenario.clone().runner();
+    let outcome = runner.run(|_, _| {});
+    assert!(matches!(outcome, Outcome::Success { condition: 0, .. }));
+
+    // the ball is rolling away from the goal, so the time will run out
+    let mut slow = scenario;
+    slow.ball.vel = Vec3::new(0., -500., 0.);
+    let outcome = slow.runner().run(|_, _| {});
+    assert!(matches!(outcome, Outcome::Failure { condition: None, time } if time >= 3.));
+
+    assert!(Scenario::from_json(r#"{"time_limit": 1.0, "success": []}"#).is_err());
+}
+
+#[test]
+#[cfg(feature = "rand")]
+fn random_scenarios() {
+    use rand::{rngs::StdRng, SeedableRng};
+    use rocketsim_rs::{field::Field, scenario};
+
+    INIT.call_once(|| init(None, true));
+
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut arena = Arena::default_standard();
+    for team in [Team::Blue, Team::Orange, Team::Blue, Team::Orange] {
+        let _ = arena.pin_mut().add_car(team, CarConfig::octane());
+    }
+
+    let generators = [
+        scenario::random_aerial::<StdRng>,
+        scenario::random_dribble::<StdRng>,
+        scenario::random_kickoff_variation::<StdRng>,
+        scenario::random_defense::<StdRng>,
+    ];
+
+    for generator in generators {
+        for i in 0..20 {
+            arena.pin_mut().reset_to_random_kickoff(None);
+            let template = arena.pin_mut().get_game_state();
+
+            let state = generator(&mut rng, &template, i as f32 / 19.);
+            assert!(state.ball.pos.z >= arena.get_ball_radius() - 1.);
+            assert!(Field::SOCCAR.contains(state.ball.pos));
+
+            for (j, car) in state.cars.iter().enumerate() {
+                assert_eq!(car.id, template.cars[j].id);
+                assert!(Field::SOCCAR.distance_to_nearest_wall(car.state.pos) > 100.);
+
+                for other in &state.cars[j + 1..] {
+                    assert!((other.state.pos - car.state.pos).length() > 200.);
+                }
+            }
+
+            arena.pin_mut().set_game_state(&state).unwrap();
+            arena.pin_mut().step(30);
+            let ball = arena.pin_mut().get_ball();
+            assert!(ball.pos.x.is_finite() && ball.pos.z > 0.);
+        }
+    }
+}
+
+#[test]
+fn validate_state() {
+    use rocketsim_rs::{
+        targets::Object,
+        validate::{Issue, SanitizeMode, SetStateError},
+    };
+
+    INIT.call_once(|| init(None, true));
+
+    let mut arena = Arena::default_standard();
+    let car_1 = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    let car_2 = arena.pin_mut().add_car(Team::Orange, CarConfig::octane());
+
+    let mut state = arena.pin_mut().get_game_state();
+    state.cars[0].state.pos = Vec3::new(0., -1000., 17.);
+    state.cars[1].state.pos = Vec3::new(0., 1000., 17.);
+    assert!(state.validate().is_ok());
+
+    state.ball.vel.x = f32::NAN;
+    state.ball.pos.z = -500.;
+    state.cars[0].state.rot_mat.forward = Vec3::new(1., 0.5, 0.);
+    state.cars[0].state.boost = 150.;
+    state.cars[1].state.pos = Vec3::new(20., -1010., 17.);
+
+    let issues = state.validate().unwrap_err().0;
+    assert!(issues.contains(&Issue::NonFinite {
+        object: Object::Ball,
+        field: "vel"
+    }));
+    assert!(issues.contains(&Issue::OutOfBounds { object: Object::Ball }));
+    assert!(issues.contains(&Issue::NonOrthonormal {
+        object: Object::Car(car_1)
+    }));
+    assert!(issues.contains(&Issue::OutOfRange {
+        object: Object::Car(car_1),
+        field: "boost"
+    }));
+    assert!(issues.contains(&Issue::Overlapping { car_1, car_2 }));
+
+    // nothing should be set when rejecting
+    let before = arena.pin_mut().get_game_state();
+    assert!(matches!(
+        arena.pin_mut().set_game_state_checked(&state, SanitizeMode::Reject),
+        Err(SetStateError::Invalid(_))
+    ));
+    assert_eq!(arena.pin_mut().get_ball().pos, before.ball.pos);
+
+    let corrected = arena.pin_mut().set_game_state_checked(&state, SanitizeMode::Correct).unwrap();
+    assert_eq!(corrected, issues);
+
+    let state = arena.pin_mut().get_game_state();
+    assert!(state.ball.vel.x.is_finite());
+    assert!(state.ball.pos.z > 0.);
+    assert!(state.cars[0].state.rot_mat.is_orthonormal());
+    assert!((state.cars[0].state.boost - 100.).abs() < f32::EPSILON);
+    assert!((state.cars[1].state.pos - state.cars[0].state.pos).length() > 100.);
+
+    arena.pin_mut().step(8);
+    assert!(arena.pin_mut().get_game_state().validate().is_ok());
+}
+
+#[test]
+#[cfg(feature = "uom")]
+fn uom_views() {
+    use rocketsim_rs::uom_ext::{
+        ticks_to_time,
+        uom::si::{length::meter, velocity::meter_per_second},
+        Position,
+    };
+
+    INIT.call_once(|| init(None, true));
+
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    arena
+        .pin_mut()
+        .set_car(
+            car_id,
+            CarState {
+                pos: Vec3::new(0., 0., 17.),
+                vel: Vec3::new(0., 1000., 0.),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    let car = arena.pin_mut().get_car(car_id);
+    let si = car.to_si();
+    assert!((si.vel.y.get::<meter_per_second>() - 10.).abs() < 1e-4);
+    assert!((si.pos.z.get::<meter>() - car.pos.z / 100.).abs() < 1e-4);
+
+    // one second of travel, without mixing up uu and meters
+    let moved = si.pos + si.vel.displacement(ticks_to_time(120, 120.));
+    assert!((moved.y.get::<meter>() - 10.).abs() < 1e-3);
+
+    let mut state = car;
+    let mut si = state.to_si();
+    si.pos = Position::from_uu(Vec3::new(100., 200., 17.));
+    si.apply_to(&mut state);
+    assert_eq!(state.pos, Vec3::new(100., 200., 17.));
+    assert_eq!(state.vel, car.vel);
+}
+
+#[test]
+#[cfg(feature = "bevy")]
+fn bevy_plugin() {
+    use rocketsim_rs::bevy_plugin::{
+        bevy_app::{App, FixedUpdate},
+        RocketSimPlugin, SimArena, SimBallState, SimCar, SimCarControls, SimCarState,
+    };
+
+    INIT.call_once(|| init(None, true));
+
+    let mut app = App::new();
+    app.add_plugins(RocketSimPlugin::default());
+    app.update();
+
+    let car_id = app
+        .world_mut()
+        .resource_mut::<SimArena>()
+        .pin_mut()
+        .add_car(Team::Blue, CarConfig::octane());
+    app.world_mut().run_schedule(FixedUpdate);
+
+    let world = app.world_mut();
+    let mut cars = world.query::<(&SimCar, &mut SimCarControls)>();
+    let (car, mut controls) = cars.single_mut(world);
+    assert_eq!(car.id, car_id);
+    controls.0.throttle = 1.;
+
+    for _ in 0..60 {
+        app.world_mut().run_schedule(FixedUpdate);
+    }
+
+    let world = app.world_mut();
+    let state = world.query::<&SimCarState>().single(world).0;
+    assert!(state.vel.length() > 100.);
+    assert_eq!(state.pos, world.resource_mut::<SimArena>().pin_mut().get_car(car_id).pos);
+    assert!(world.query::<&SimBallState>().single(world).0.pos.z > 0.);
+
+    world.resource_mut::<SimArena>().pin_mut().remove_car(car_id).unwrap();
+    app.world_mut().run_schedule(FixedUpdate);
+    app.world_mut().run_schedule(FixedUpdate);
+    let world = app.world_mut();
+    assert_eq!(world.query::<&SimCar>().iter(world).count(), 0);
+}
+
+#[test]
+fn step_timings() {
+    INIT.call_once(|| init(None, true));
+
+    let mut arena = Arena::default_standard();
+    let _ = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    let _ = arena.pin_mut().add_car(Team::Orange, CarConfig::octane());
+
+    arena.pin_mut().step(10);
+    assert!(!arena.step_timings_enabled());
+    assert_eq!(arena.get_step_timings().ticks, 0);
+
+    arena.pin_mut().set_step_timings_enabled(true);
+    arena.pin_mut().reset_to_random_kickoff(None);
+    arena.pin_mut().step(120);
+
+    let timings = arena.get_step_timings();
+    assert_eq!(timings.ticks, 120);
+    assert!(timings.total > 0.);
+
+    let parts = timings.cars_and_ball
+        + timings.broadphase
+        + timings.narrowphase
+        + timings.solver
+        + timings.other_bullet
+        + timings.callbacks;
+    assert!((parts - timings.total).abs() < timings.total * 0.01);
+
+    let per_tick = timings.per_tick();
+    assert_eq!(per_tick.ticks, 1);
+    assert!((per_tick.total * 120. - timings.total).abs() < 1e-9);
+
+    let text = timings.to_string();
+    assert!(text.starts_with("120 ticks, "));
+    assert_eq!(text.lines().count(), 7);
+    assert!(text.contains("broadphase") && text.contains("callbacks"));
+
+    arena.pin_mut().reset_step_timings();
+    assert_eq!(arena.get_step_timings().ticks, 0);
+
+    arena.pin_mut().set_step_timings_enabled(false);
+    arena.pin_mut().step(10);
+    assert_eq!(arena.get_step_timings().ticks, 0);
+}
+
+#[test]
+fn state_refs() {
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    arena.pin_mut().reset_to_random_kickoff(Some(3));
+    arena
+        .pin_mut()
+        .set_car_controls(
+            car_id,
+            CarControls {
+                throttle: 1.,
+                boost: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    arena.pin_mut().step(30);
+
+    {
+        let car = arena.car_state_ref(car_id).unwrap();
+        let ball = arena.ball_state_ref();
+
+        // the kinematics are read live, even though the full state hasn't been fetched
+        assert!(car.vel().length() > 0.);
+        assert!(car.internal().boost < 100. / 3.);
+        assert_eq!(car.id(), car_id);
+        assert!(ball.pos().z > 0.);
+    }
+
+    let car = arena.pin_mut().get_car(car_id);
+    let car_ref = arena.car_state_ref(car_id).unwrap();
+    assert_eq!(car_ref.pos(), car.pos);
+    assert_eq!(car_ref.vel(), car.vel);
+    assert_eq!(car_ref.to_state().boost, car.boost);
+
+    assert!(arena.car_state_ref(car_id + 1).is_err());
+}
+
+#[test]
+fn bulk_controls() {
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let blue = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    let orange = arena.pin_mut().add_car(Team::Orange, CarConfig::octane());
+
+    let controls = CarControls {
+        throttle: 1.,
+        ..Default::default()
+    };
+
+    arena
+        .pin_mut()
+        .set_all_controls([blue, orange].into_iter().map(|id| (id, controls)))
+        .unwrap();
+    assert!(arena.pin_mut().set_all_controls([(orange + 1, controls)]).is_err());
+
+    arena.pin_mut().set_controls_for_team(
+        Team::Orange,
+        CarControls {
+            boost: true,
+            ..Default::default()
+        },
+    );
+    arena.pin_mut().step(1);
+
+    assert_eq!(arena.pin_mut().get_car(blue).last_controls.throttle, 1.);
+    assert!(!arena.pin_mut().get_car(blue).last_controls.boost);
+    assert!(arena.pin_mut().get_car(orange).last_controls.boost);
+    assert_eq!(arena.pin_mut().get_car(orange).last_controls.throttle, 0.);
+}
+
+#[test]
+fn roster_changes() {
+    use rocketsim_rs::events::{RosterEventKind, RosterTracker};
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let mut tracker = RosterTracker::new();
+
+    let car_1 = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    let car_2 = arena.pin_mut().add_car(Team::Orange, CarConfig::octane());
+    arena.pin_mut().reset_to_random_kickoff(Some(0));
+
+    let events = tracker.update(&arena.pin_mut().get_game_state());
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].kind, RosterEventKind::Added { team: Team::Blue });
+
+    let state = arena.pin_mut().get_car(car_1);
+    arena.pin_mut().set_car_team(car_1, Team::Orange).unwrap();
+    arena.pin_mut().set_car_config(car_1, CarConfig::plank()).unwrap();
+    arena.pin_mut().remove_car(car_2).unwrap();
+
+    // the id, state and team survive the config change
+    assert_eq!(arena.get_cars(), vec![car_1]);
+    assert_eq!(arena.get_car_team(car_1), Team::Orange);
+    assert_eq!(arena.get_car_config(car_1).hitbox_size, CarConfig::plank().hitbox_size);
+    assert_eq!(arena.pin_mut().get_car(car_1).pos, state.pos);
+
+    let events = tracker.update(&arena.pin_mut().get_game_state());
+    let kinds = events.iter().map(|event| (event.car_id, event.kind)).collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec![
+            (car_2, RosterEventKind::Removed { team: Team::Orange }),
+            (
+                car_1,
+                RosterEventKind::TeamChanged {
+                    from: Team::Blue,
+                    to: Team::Orange
+                }
+            ),
+            (car_1, RosterEventKind::ConfigChanged),
+        ]
+    );
+
+    // new cars never reuse an id
+    let car_3 = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    assert!(car_3 > car_2);
+    assert!(arena.pin_mut().set_car_team(car_2, Team::Blue).is_err());
+
+    arena.pin_mut().step(10);
+}
+
+#[test]
+fn stable_car_ids() {
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let _ = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    let removed = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    let _ = arena.pin_mut().add_car(Team::Orange, CarConfig::dominus());
+    arena.pin_mut().remove_car(removed).unwrap();
+
+    arena
+        .pin_mut()
+        .add_car_with_id(10, Team::Orange, CarConfig::breakout())
+        .unwrap();
+    assert!(arena.pin_mut().add_car_with_id(10, Team::Blue, CarConfig::octane()).is_err());
+    assert!(arena.pin_mut().add_car_with_id(0, Team::Blue, CarConfig::octane()).is_err());
+    assert_eq!(arena.get_car_team(10), Team::Orange);
+
+    arena.pin_mut().reset_to_random_kickoff(Some(1));
+    arena.pin_mut().step(60);
+
+    // the order that the cars are stored in isn't guaranteed
+    let sorted_ids = |arena: &Arena| {
+        let mut ids = arena.get_cars();
+        ids.sort_unstable();
+        ids
+    };
+
+    let ids = sorted_ids(&arena);
+    assert_eq!(ids.len(), 3);
+    assert_eq!(sorted_ids(&arena.clone(false)), ids);
+
+    let game_state = arena.pin_mut().get_game_state();
+    let mut restored = Arena::from_game_state(&game_state, Default::default());
+    assert_eq!(sorted_ids(&restored), ids);
+    assert_eq!(restored.get_car_team(10), Team::Orange);
+    assert_eq!(restored.get_tick_count(), game_state.tick_count);
+
+    let restored_state = restored.pin_mut().get_game_state();
+    for car in &game_state.cars {
+        let restored_car = restored_state.cars.iter().find(|c| c.id == car.id).unwrap();
+        assert_eq!(car.state.pos, restored_car.state.pos);
+    }
+
+    #[cfg(feature = "bin")]
+    {
+        use rocketsim_rs::{
+            bytes::{FromBytes, ToBytes},
+            GameState,
+        };
+
+        let from_bytes = GameState::from_bytes(&game_state.to_bytes());
+        assert_eq!(sorted_ids(&Arena::from_game_state(&from_bytes, Default::default())), ids);
+    }
+
+    // new cars are never given an id that was set manually
+    assert!(arena.pin_mut().add_car(Team::Blue, CarConfig::octane()) > 10);
+}
+
+#[test]
+fn set_tick_count() {
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+
+    arena.pin_mut().set_tick_count(1_000_000);
+    arena.pin_mut().step(10);
+    assert_eq!(arena.get_tick_count(), 1_000_010);
+    assert_eq!(arena.pin_mut().get_game_state().tick_count, 1_000_010);
+
+    arena.pin_mut().reset_tick_count();
+    assert_eq!(arena.get_tick_count(), 0);
+}
+
+#[test]
+fn history() {
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    arena.pin_mut().reset_to_random_kickoff(Some(2));
+
+    arena.pin_mut().step(10);
+    assert!(arena.history().is_empty());
+
+    arena.pin_mut().set_history_capacity(60);
+    arena.pin_mut().step(100);
+
+    let history = arena.history();
+    assert_eq!(history.len(), 60);
+    assert_eq!(history.newest_tick(), Some(110));
+    assert_eq!(history.oldest_tick(), Some(51));
+    assert!(history.ball_at(50).is_none());
+    assert!(history.ball_at(111).is_none());
+
+    let ball = arena.pin_mut().get_ball();
+    let car = arena.pin_mut().get_car(car_id);
+    let history = arena.history();
+    assert_eq!(history.ball_at(110).unwrap().pos, ball.pos);
+    assert_eq!(history.car_seconds_ago(car_id, 0.).unwrap().pos, car.pos);
+    assert_eq!(
+        history.car_seconds_ago(car_id, 0.25).unwrap().pos,
+        history.car_at(car_id, 80).unwrap().pos
+    );
+    assert!(history.ball_seconds_ago(1.).is_none());
+    assert!(history.car_at(car_id + 1, 110).is_none());
+
+    let ticks = history.iter_ball().map(|(tick, _)| tick).collect::<Vec<_>>();
+    assert_eq!(ticks, (51..=110).collect::<Vec<_>>());
+    assert_eq!(history.iter_car(car_id).count(), 60);
+
+    // the history only covers consecutive ticks
+    arena.pin_mut().reset_tick_count();
+    arena.pin_mut().step(1);
+    assert_eq!(arena.history().len(), 1);
+}
+
+#[test]
+fn step_back() {
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    arena.pin_mut().reset_to_random_kickoff(Some(4));
+    assert!(arena.pin_mut().step_back(1).is_err());
+
+    arena.pin_mut().set_history_capacity(120);
+    arena
+        .pin_mut()
+        .set_car_controls(
+            car_id,
+            CarControls {
+                throttle: 1.,
+                boost: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    arena.pin_mut().step(60);
+
+    let car = arena.pin_mut().get_car(car_id);
+    let ball = arena.pin_mut().get_ball();
+    arena.pin_mut().step(30);
+
+    assert_eq!(arena.pin_mut().step_back(30).unwrap(), 60);
+    assert_eq!(arena.get_tick_count(), 60);
+    assert_eq!(arena.history().newest_tick(), Some(60));
+    assert_eq!(arena.pin_mut().get_car(car_id).pos, car.pos);
+    assert_eq!(arena.pin_mut().get_car(car_id).boost, car.boost);
+    assert_eq!(arena.pin_mut().get_ball().pos, ball.pos);
+
+    // stepping forward records the history again
+    arena.pin_mut().step(10);
+    assert_eq!(arena.history().newest_tick(), Some(70));
+
+    // the oldest recorded tick is the furthest back it can go
+    assert_eq!(arena.pin_mut().step_back(1000).unwrap(), 1);
+}
+
+#[test]
+fn compare_arenas() {
+    use rocketsim_rs::debug::{self, Difference, Part};
+
+    INIT.call_once(|| init(None, true));
+    let mut a = Arena::default_standard();
+    let car_id = a.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    a.pin_mut().reset_to_random_kickoff(Some(5));
+
+    let mut b = a.clone(false);
+    assert!(debug::compare_arenas(&a, &b, 0.).is_empty());
+    assert!(debug::find_divergence(a.pin_mut(), b.pin_mut(), 60, 0.).is_none());
+
+    let controls = CarControls {
+        throttle: 1.,
+        ..Default::default()
+    };
+    b.pin_mut().set_car_controls(car_id, controls).unwrap();
+
+    let divergences = debug::find_divergence(a.pin_mut(), b.pin_mut(), 60, 1e-3).unwrap();
+    assert_eq!(divergences[0].tick_count, 61);
+    assert!(divergences.iter().all(|d| d.part == Part::Car(car_id)));
+    assert!(divergences.iter().any(|d| d.field.starts_with("vel.")));
+
+    let _ = b.pin_mut().add_car(Team::Orange, CarConfig::octane());
+    let divergences = debug::compare_arenas(&a, &b, 1e3);
+    assert!(divergences
+        .iter()
+        .any(|d| d.difference == Difference::Missing { in_a: false }));
+}
+
+#[test]
+fn golden_trajectory() {
+    use rocketsim_rs::golden::{self, GoldenError, MismatchedPart, Trajectory};
+
+    INIT.call_once(|| init(None, true));
+
+    let run = |throttle: f32| {
+        let mut arena = Arena::default_standard();
+        let _ = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+        arena.pin_mut().reset_to_random_kickoff(Some(6));
+
+        Trajectory::record(arena.pin_mut(), 120, |arena, tick| {
+            let controls = CarControls {
+                throttle: if tick < 60 { 1. } else { throttle },
+                ..Default::default()
+            };
+            arena.set_controls_for_team(Team::Blue, controls);
+        })
+    };
+
+    let expected = run(1.);
+    assert_eq!(expected.ticks.len(), 120);
+    assert_eq!(expected.to_string().parse::<Trajectory>().unwrap(), expected);
+    assert!(run(1.).first_mismatch(&expected).is_none());
+
+    let mismatch = run(-1.).first_mismatch(&expected).unwrap();
+    assert_eq!(mismatch.index, 60);
+    assert_eq!(mismatch.parts, vec![MismatchedPart::Car(1)]);
+
+    let path = std::env::temp_dir().join("rocketsim_rs_golden_test.txt");
+    let _ = std::fs::remove_file(&path);
+    golden::assert_golden(&path, &expected);
+    golden::assert_golden(&path, &run(1.));
+    assert!(matches!(
+        golden::check_golden(&path, &run(-1.)),
+        Err(GoldenError::Mismatch(_))
+    ));
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn csv_telemetry() {
+    use rocketsim_rs::telemetry::{Column, CsvWriter};
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    arena.pin_mut().reset_to_random_kickoff(Some(7));
+    arena.pin_mut().set_controls_for_team(
+        Team::Blue,
+        CarControls {
+            throttle: 1.,
+            ..Default::default()
+        },
+    );
+
+    let mut writer = CsvWriter::new(
+        Vec::new(),
+        &[Column::TickCount, Column::BallPos, Column::CarBoost, Column::CarControls],
+    )
+    .with_sample_interval(4);
+
+    for _ in 0..12 {
+        writer.write(&arena.pin_mut().get_game_state()).unwrap();
+        arena.pin_mut().step(1);
+    }
+
+    arena.pin_mut().remove_car(car_id).unwrap();
+    assert!(writer.write(&arena.pin_mut().get_game_state()).unwrap());
+
+    let csv = String::from_utf8(writer.into_inner()).unwrap();
+    let lines = csv.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines[0],
+        format!(
+            "tick_count,ball_pos_x,ball_pos_y,ball_pos_z,car_{car_id}_boost,car_{car_id}_throttle,car_{car_id}_steer,car_{car_id}_pitch,car_{car_id}_yaw,car_{car_id}_roll,car_{car_id}_boost_pressed,car_{car_id}_jump,car_{car_id}_handbrake"
+        )
+    );
+
+    // a header, ticks 0, 4 and 8, and the row without the car
+    assert_eq!(lines.len(), 5);
+    assert!(lines[2].starts_with("4,"));
+    assert!(lines.iter().all(|line| line.split(',').count() == 13));
+    assert!(lines[4].ends_with(",,,,,,,,,"));
+}
+
+#[test]
+#[cfg(feature = "arrow")]
+fn arrow_dataset() {
+    use rocketsim_rs::dataset::{self, BatchBuilder};
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let _ = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    let _ = arena.pin_mut().add_car(Team::Orange, CarConfig::octane());
+    arena.pin_mut().reset_to_random_kickoff(Some(8));
+
+    let mut builder = BatchBuilder::new();
+    for _ in 0..10 {
+        arena.pin_mut().step(1);
+        builder.push(&arena.pin_mut().get_game_state());
+    }
+    assert_eq!(builder.len(), 20);
+
+    let batch = builder.finish().unwrap();
+    assert!(builder.is_empty());
+    assert_eq!(batch.num_rows(), 20);
+    assert_eq!(batch.schema(), dataset::schema());
+    assert!(batch.column_by_name("ball_pos_z").is_some());
+    assert!(batch.column_by_name("throttle").is_some());
+
+    #[cfg(feature = "parquet")]
+    {
+        use rocketsim_rs::dataset::DatasetWriter;
+
+        let mut writer = DatasetWriter::new(Vec::new()).unwrap();
+        for _ in 0..10 {
+            arena.pin_mut().step(1);
+            writer.write(&arena.pin_mut().get_game_state()).unwrap();
+        }
+
+        let bytes = writer.finish().unwrap();
+        assert_eq!(&bytes[..4], b"PAR1");
+    }
+}
+
+#[test]
+#[cfg(feature = "bin")]
+fn input_recording() {
+    use rocketsim_rs::{
+        debug,
+        inputs::{InputRecorder, InputRecording},
+    };
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let blue = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    let orange = arena.pin_mut().add_car(Team::Orange, CarConfig::octane());
+    arena.pin_mut().reset_to_random_kickoff(Some(7));
+
+    let mut playback = arena.clone(false);
+    let mut recorder = InputRecorder::new(&arena);
+
+    for i in 0..20 {
+        let controls = CarControls {
+            throttle: 1.,
+            steer: if i % 4 < 2 { 0.5 } else { -0.5 },
+            boost: i % 3 == 0,
+            jump: i == 10,
+            ..Default::default()
+        };
+
+        arena.pin_mut().set_car_controls(blue, controls).unwrap();
+        arena.pin_mut().set_car_controls(orange, CarControls::default()).unwrap();
+        arena.pin_mut().step(if i % 2 == 0 { 1 } else { 8 });
+        recorder.record(&arena);
+    }
+
+    // held controls are merged into the last frame
+    for _ in 0..2 {
+        arena.pin_mut().step(5);
+        recorder.record(&arena);
+    }
+
+    let recording = recorder.into_recording();
+    assert_eq!(recording.num_ticks(), 100);
+    assert_eq!(recording.frames.len(), 18);
+    assert!(recording.controls_at(recording.start_tick).is_some());
+    assert!(recording.controls_at(recording.start_tick + 100).is_none());
+
+    let mut bytes = Vec::new();
+    recording.write_to(&mut bytes).unwrap();
+    let recording = InputRecording::read_from(&bytes[..]).unwrap();
+    assert_eq!(recording.num_ticks(), 100);
+    assert!(InputRecording::read_from(&bytes[..bytes.len() - 1]).is_err());
+
+    playback.pin_mut().play_inputs(&recording).unwrap();
+    assert!(debug::compare_arenas(&arena, &playback, 0.).is_empty());
+}
+
+#[test]
+fn controllers() {
+    use rocketsim_rs::{input::Controller, GameState};
+
+    let mut bot = |car_id: u32, game_state: &GameState| CarControls {
+        throttle: 1.,
+        boost: game_state.tick_count > u64::from(car_id),
+        ..Default::default()
+    };
+
+    let game_state = GameState {
+        tick_count: 10,
+        ..Default::default()
+    };
+    assert!(bot.controls(1, &game_state).boost);
+    assert!(!bot.controls(20, &game_state).boost);
+}
+
+#[test]
+#[cfg(feature = "gilrs")]
+fn gamepad_deadzone() {
+    use rocketsim_rs::input::{DeadzoneShape, GamepadSettings};
+
+    let mut settings = GamepadSettings::default();
+    assert_eq!(settings.apply_deadzone(0.05, -0.05), (0., 0.));
+    assert_eq!(settings.apply_deadzone(1., -1.), (1., -1.));
+
+    let (x, y) = settings.apply_deadzone(0.55, 0.05);
+    assert!((x - 0.5).abs() < 1e-6);
+    assert_eq!(y, 0.);
+
+    // a circular deadzone keeps the small axis of a diagonal input
+    settings.deadzone_shape = DeadzoneShape::Circle;
+    let (x, y) = settings.apply_deadzone(0.55, 0.05);
+    assert!(x > 0. && y > 0.);
+}
+
+#[test]
+#[cfg(feature = "rand")]
+fn observation_noise() {
+    use rand::{rngs::StdRng, SeedableRng};
+    use rocketsim_rs::{
+        noise::{FieldNoise, NoiseConfig, ObservationNoise},
+        smallvec::smallvec,
+        CarInfo, GameState,
+    };
+
+    let mut state = GameState {
+        cars: smallvec![CarInfo {
+            id: 1,
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    state.ball.pos = Vec3::new(100., 200., 300.);
+    state.cars[0].state.boost = 50.;
+
+    let config = NoiseConfig {
+        ball_pos: FieldNoise::gaussian(10.),
+        car_boost: FieldNoise::NONE.with_quantization(20.),
+        car_pos: FieldNoise::NONE.with_dropout(1.),
+        ..Default::default()
+    };
+    let mut noise = ObservationNoise::new(config, StdRng::seed_from_u64(0));
+
+    let observed = noise.observe(&state);
+    assert_ne!(observed.ball.pos.x, state.ball.pos.x);
+    assert!(observed.ball.pos.distance(state.ball.pos) < 100.);
+    assert_eq!(observed.ball.vel.x, state.ball.vel.x);
+    assert_eq!(observed.cars[0].state.boost, 60.);
+
+    // the car's position is always dropped, so it sticks at the first observation
+    state.cars[0].state.pos = Vec3::new(500., 0., 17.);
+    let observed = noise.observe(&state);
+    assert_eq!(observed.cars[0].state.pos.x, 0.);
+
+    // the real state is untouched
+    assert_eq!(state.ball.pos.x, 100.);
+}
+
+#[test]
+#[cfg(feature = "rand")]
+fn domain_randomization() {
+    use rand::{rngs::StdRng, SeedableRng};
+    use rocketsim_rs::randomization::DomainRandomizer;
+
+    INIT.call_once(|| init(None, true));
+
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    let base_gravity = arena.get_mutator_config().gravity.z;
+    let base_hitbox = arena.get_car_config(car_id).hitbox_size;
+
+    let randomizer = DomainRandomizer::from_arena(&arena)
+        .with_gravity_scale(0.8..=1.2)
+        .with_car_scale(0.9..=1.1);
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for _ in 0..5 {
+        let sample = randomizer.randomize(&mut rng, arena.pin_mut()).unwrap();
+        assert!((0.8..=1.2).contains(&sample.gravity_scale));
+        assert_eq!(sample.boost_strength_scale, 1.);
+        assert_eq!(sample.car_scales.len(), 1);
+
+        // the scales are always relative to the base, so they don't compound
+        let gravity = arena.get_mutator_config().gravity.z;
+        assert!((gravity - base_gravity * sample.gravity_scale).abs() < 1e-3);
+
+        let hitbox = arena.get_car_config(car_id).hitbox_size;
+        assert!((hitbox.x - base_hitbox.x * sample.car_scales[0].1).abs() < 1e-3);
+    }
+}
+
+#[test]
+#[cfg(feature = "rand")]
+fn curriculum_stages() {
+    use rand::{rngs::StdRng, SeedableRng};
+    use rocketsim_rs::{
+        curriculum::{Curriculum, Promotion, Stage},
+        scenario::{random_aerial, random_dribble},
+    };
+
+    INIT.call_once(|| init(None, true));
+
+    let mut arena = Arena::default_standard();
+    let _ = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+
+    let mut curriculum = Curriculum::new(StdRng::seed_from_u64(0))
+        .with_stage(Stage::new(
+            "dribbles",
+            |rng, template| random_dribble(rng, template, 0.),
+            Promotion::SuccessRate { rate: 0.5, window: 4 },
+        ))
+        .with_stage(Stage::new(
+            "aerials",
+            |rng, template| random_aerial(rng, template, 1.),
+            Promotion::Never,
+        ));
+
+    assert_eq!(curriculum.reset(arena.pin_mut()).unwrap(), 0);
+    for success in [false, false, true] {
+        curriculum.end_episode(success);
+        assert_eq!(curriculum.reset(arena.pin_mut()).unwrap(), 0);
+    }
+
+    // 2 of the last 4 episodes were successes
+    curriculum.end_episode(true);
+    assert_eq!(curriculum.reset(arena.pin_mut()).unwrap(), 1);
+    assert!(arena.pin_mut().get_ball().pos.z > 300.);
+    assert_eq!(curriculum.stats()[0].episodes(), 4);
+
+    curriculum.end_episode(true);
+    assert_eq!(curriculum.reset(arena.pin_mut()).unwrap(), 1);
+    assert!(curriculum.is_last_stage());
+}
+
+#[test]
+#[cfg(feature = "rand")]
+fn opponent_pool() {
+    use rand::{rngs::StdRng, SeedableRng};
+    use rocketsim_rs::{
+        selfplay::{EpisodeResult, OpponentPool},
+        GameState,
+    };
+
+    let idle = |_: u32, _: &GameState| CarControls::default();
+    let mut pool = OpponentPool::new(StdRng::seed_from_u64(0)).with_max_opponents(2);
+    assert_eq!(pool.sample(), None);
+
+    pool.add("v1", idle, 1.);
+    pool.add("v2", idle, 0.);
+    assert_eq!(pool.sample(), Some(0));
+
+    // the oldest snapshot is removed once the pool is full
+    pool.add("v3", idle, 1.);
+    assert_eq!(pool.len(), 2);
+    assert_eq!(pool.opponents()[0].name, "v2");
+
+    for _ in 0..20 {
+        let index = pool.sample().unwrap();
+        assert_eq!(index, 1);
+
+        let opponent = pool.opponent_mut(index).unwrap();
+        let controls = opponent.controller.controls(0, &GameState::default());
+        assert_eq!(controls.throttle, 0.);
+        pool.report(index, EpisodeResult::Win);
+    }
+
+    pool.report(1, EpisodeResult::Draw);
+    let record = pool.opponents()[1].record;
+    assert_eq!(record.episodes(), 21);
+    assert_eq!(record.win_rate(), Some(20.5 / 21.));
+}
+
+#[test]
+fn boost_pickups() {
+    use rocketsim_rs::{
+        events::BoostPickupTracker,
+        sim::{BoostPadConfig, BoostPadState},
+        smallvec::smallvec,
+        BoostPad, CarInfo, GameState,
+    };
+
+    let pad = |x: f32, is_big| BoostPad {
+        config: BoostPadConfig {
+            position: Vec3::new(x, 0., 73.),
+            is_big,
+        },
+        state: BoostPadState {
+            is_active: true,
+            ..Default::default()
+        },
+    };
+
+    let mut state = GameState {
+        cars: smallvec![
+            CarInfo {
+                id: 1,
+                ..Default::default()
+            },
+            CarInfo {
+                id: 2,
+                ..Default::default()
+            },
+        ],
+        pads: smallvec![pad(-1000., true), pad(1000., false)],
+        ..Default::default()
+    };
+    state.cars[0].state.pos = Vec3::new(-1000., 0., 17.);
+    state.cars[0].state.boost = 50.;
+    state.cars[1].state.pos = Vec3::new(1000., 0., 17.);
+    state.cars[1].state.boost = 95.;
+
+    let mut tracker = BoostPickupTracker::new();
+    assert!(tracker.update(&state).is_empty());
+
+    // car 1 takes the big pad (as reported by RocketSim), car 2 tops up from the small one
+    state.tick_count = 8;
+    state.pads[0].state.is_active = false;
+    state.pads[0].state.prev_locked_car_id = 1;
+    state.cars[0].state.boost = 100.;
+    state.pads[1].state.is_active = false;
+    state.cars[1].state.boost = 100.;
+
+    let pickups = tracker.update(&state);
+    assert_eq!(pickups.len(), 2);
+    assert_eq!(pickups[0].car_id, 1);
+    assert_eq!(pickups[0].pad_index, 0);
+    assert!(pickups[0].is_big);
+    assert_eq!(pickups[0].amount, 50.);
+    assert_eq!(pickups[1].car_id, 2);
+    assert_eq!(pickups[1].amount, 5.);
+
+    // inactive pads don't produce more pickups
+    state.tick_count = 16;
+    assert!(tracker.update(&state).is_empty());
+    assert_eq!(tracker.pickups_by(2).count(), 1);
+    assert_eq!(tracker.history()[0].tick_count, 8);
+}
+
+#[test]
+fn car_state_classification() {
+    let mut state = CarState {
+        is_on_ground: true,
+        rot_mat: RotMat::IDENTITY,
+        ..Default::default()
+    };
+    assert!(!state.is_on_wall() && !state.is_on_ceiling());
+    assert_eq!(state.time_airborne(), 0.);
+
+    state.vel = Vec3::new(2200., 0., 0.);
+    assert!(state.is_supersonic());
+    state.vel.x = 2150.;
+    assert!(!state.is_supersonic());
+    state.is_supersonic = true;
+    assert!(state.is_supersonic());
+
+    // driving up a side wall
+    state.rot_mat = RotMat::look_at(Vec3::Z, Vec3::X);
+    assert!(state.is_on_wall());
+
+    state.rot_mat = RotMat::look_at(Vec3::X, -Vec3::Z);
+    assert!(state.is_on_ceiling());
+
+    state.is_on_ground = false;
+    state.air_time = 0.5;
+    assert!(!state.is_on_ceiling());
+    assert_eq!(state.time_airborne(), 0.5);
+}
+
+#[test]
+fn flip_events() {
+    use rocketsim_rs::{
+        events::{FlipDirection, FlipEventKind, FlipEventTracker},
+        smallvec::smallvec,
+        CarInfo, GameState,
+    };
+
+    let mut state = GameState {
+        cars: smallvec![CarInfo {
+            id: 1,
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    let mut tracker = FlipEventTracker::new();
+    assert!(tracker.update(&state).is_empty());
+
+    // a forward-right diagonal flip
+    state.tick_count = 10;
+    let car = &mut state.cars[0].state;
+    car.has_flipped = true;
+    car.is_flipping = true;
+    car.flip_rel_torque = Vec3::new(-1., 1., 0.).normalize_or_zero();
+    car.last_controls.pitch = -1.;
+
+    let events = tracker.update(&state);
+    assert_eq!(
+        events.iter().map(|event| event.kind).collect::<Vec<_>>(),
+        [FlipEventKind::Flip {
+            direction: FlipDirection::ForwardRight
+        }]
+    );
+
+    // pulling back cancels it
+    state.tick_count = 14;
+    state.cars[0].state.flip_time = 0.05;
+    state.cars[0].state.last_controls.pitch = 1.;
+    let events = tracker.update(&state);
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        events[0].kind,
+        FlipEventKind::SpeedFlipAttempt {
+            direction: FlipDirection::ForwardRight
+        }
+    );
+    assert!(tracker.update(&state).is_empty());
+
+    // a new jump, then a stall
+    state.cars[0].state = CarState::default();
+    tracker.update(&state);
+
+    let car = &mut state.cars[0].state;
+    car.has_double_jumped = true;
+    car.last_controls.yaw = 1.;
+    car.last_controls.roll = -1.;
+    let events = tracker.update(&state);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].kind, FlipEventKind::Stall);
+
+    assert_eq!(FlipDirection::from_dodge_dir(-1., 0.1), Some(FlipDirection::Backward));
+    assert_eq!(FlipDirection::from_dodge_dir(0., -1.), Some(FlipDirection::Left));
+    assert_eq!(FlipDirection::from_dodge_dir(0., 0.), None);
+}
+
+#[test]
+fn sanitize_controls() {
+    use rocketsim_rs::validate::{ControlSettings, NonFiniteControls};
+
+    let controls = CarControls {
+        throttle: 2.,
+        steer: -0.33,
+        pitch: 0.1,
+        yaw: -0.2,
+        jump: true,
+        ..Default::default()
+    };
+
+    let settings = ControlSettings::default().with_quantization(0.25);
+    let sanitized = controls.sanitize(&settings).unwrap();
+    assert_eq!(sanitized.throttle, 1.);
+    assert_eq!(sanitized.steer, -0.25);
+    // inside of the dodge deadzone
+    assert_eq!(sanitized.pitch, 0.);
+    assert_eq!(sanitized.yaw, 0.);
+    assert!(sanitized.jump);
+
+    let without_jump = CarControls { jump: false, ..controls };
+    let sanitized = without_jump.sanitize(&ControlSettings::from_car_config(CarConfig::octane()));
+    assert_eq!(sanitized.map(|controls| controls.pitch), Ok(0.1));
+
+    let nan = CarControls {
+        roll: f32::NAN,
+        ..Default::default()
+    };
+    assert_eq!(nan.sanitize(&settings).unwrap_err(), NonFiniteControls("roll"));
+}
+
+#[test]
+#[cfg(feature = "rand")]
+fn input_latency() {
+    use rand::{rngs::StdRng, SeedableRng};
+    use rocketsim_rs::latency::{InputLatency, NetworkConditions};
+
+    let throttle = |throttle| CarControls {
+        throttle,
+        ..Default::default()
+    };
+
+    let mut latency = InputLatency::new(NetworkConditions::default(), StdRng::seed_from_u64(0));
+    latency.set_conditions(1, NetworkConditions::new(2, 0.));
+    latency.set_conditions(2, NetworkConditions::new(0, 1.));
+
+    // car 1's inputs arrive 2 ticks late
+    let applied = [0.1, 0.2, 0.3, 0.4].map(|value| latency.push(1, throttle(value)).throttle);
+    assert_eq!(applied, [0., 0., 0.1, 0.2]);
+
+    // all of car 2's inputs are dropped, and car 3 uses the default perfect conditions
+    assert_eq!(latency.push(2, throttle(1.)).throttle, 0.);
+    assert_eq!(latency.push(3, throttle(1.)).throttle, 1.);
+
+    // lowering the latency skips the late inputs
+    latency.set_conditions(1, NetworkConditions::default());
+    assert_eq!(latency.push(1, throttle(0.5)).throttle, 0.5);
+    assert_eq!(latency.conditions(1).latency_ticks, 0);
+}
+
+#[test]
+fn arena_pause() {
+    INIT.call_once(|| init(None, true));
+
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    arena.pin_mut().reset_to_random_kickoff(Some(0));
+    arena
+        .pin_mut()
+        .set_car_controls(
+            car_id,
+            CarControls {
+                throttle: 1.,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    assert!(!arena.is_paused());
+    arena.pin_mut().set_paused(true);
+    assert!(arena.is_paused());
+
+    let start = arena.pin_mut().get_game_state();
+    arena.pin_mut().step(60);
+    let paused = arena.pin_mut().get_game_state();
+    assert_eq!(paused.tick_count, start.tick_count);
+    assert_eq!(paused.cars[0].state.pos, start.cars[0].state.pos);
+
+    arena.pin_mut().set_paused(false);
+    arena.pin_mut().step(60);
+    assert_eq!(arena.get_tick_count(), start.tick_count + 60);
+    assert_ne!(arena.pin_mut().get_car(car_id).pos, start.cars[0].state.pos);
+}
+
+#[test]
+fn match_clock() {
+    use rocketsim_rs::rules::{MatchClock, MatchEventKind, MatchPhase, MatchRules};
+
+    INIT.call_once(|| init(None, true));
+
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+
+    let mut clock = MatchClock::new(MatchRules {
+        match_length: 2.,
+        countdown_time: 0.5,
+        goal_reset_time: 0.5,
+        ..Default::default()
+    });
+    clock.start(arena.pin_mut());
+    assert_eq!(clock.phase(), MatchPhase::Countdown);
+
+    // nothing moves during the countdown
+    let start_tick = arena.get_tick_count();
+    let events = clock.step(arena.pin_mut(), 60);
+    assert_eq!(arena.get_tick_count(), start_tick);
+    assert_eq!(clock.phase(), MatchPhase::Kickoff);
+    assert!(matches!(
+        events[0].kind,
+        MatchEventKind::PhaseChanged {
+            to: MatchPhase::Kickoff,
+            ..
+        }
+    ));
+
+    // the clock starts once the ball is touched
+    arena
+        .pin_mut()
+        .set_car_controls(
+            car_id,
+            CarControls {
+                throttle: 1.,
+                boost: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    for _ in 0..600 {
+        clock.step(arena.pin_mut(), 1);
+        if clock.phase() != MatchPhase::Kickoff {
+            break;
+        }
+    }
+    assert_eq!(clock.phase(), MatchPhase::Playing);
+    assert!(clock.time_remaining() > 1.9);
+
+    // blue scores
+    arena.pin_mut().set_ball(BallState {
+        pos: Vec3::new(0., 5000., 200.),
+        vel: Vec3::new(0., 2000., 0.),
+        ..Default::default()
+    });
+    let events = clock.step(arena.pin_mut(), 60);
+    assert!(events
+        .iter()
+        .any(|event| event.kind == MatchEventKind::Goal { team: Team::Blue }));
+    assert_eq!(clock.score(Team::Blue), 1);
+    assert_eq!(clock.phase(), MatchPhase::GoalReset);
+
+    // back to a kickoff after the stoppage
+    clock.step(arena.pin_mut(), 60);
+    assert_eq!(clock.phase(), MatchPhase::Countdown);
+}
+
+#[test]
+fn match_overtime() {
+    use rocketsim_rs::rules::{MatchClock, MatchEventKind, MatchPhase, MatchRules};
+
+    INIT.call_once(|| init(None, true));
+
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+
+    let mut clock = MatchClock::new(MatchRules {
+        match_length: 0.5,
+        countdown_time: 0.,
+        ..Default::default()
+    });
+    clock.start(arena.pin_mut());
+    arena
+        .pin_mut()
+        .set_car_controls(
+            car_id,
+            CarControls {
+                throttle: 1.,
+                boost: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    for _ in 0..600 {
+        clock.step(arena.pin_mut(), 1);
+        if clock.phase() == MatchPhase::Playing {
+            break;
+        }
+    }
+    assert_eq!(clock.phase(), MatchPhase::Playing);
+
+    // the clock runs out with the ball on the ground and the score tied
+    let rest_z = arena.get_ball_radius();
+    let mut events = Vec::new();
+    for _ in 0..120 {
+        arena.pin_mut().set_ball(BallState {
+            pos: Vec3::new(3000., -3000., rest_z),
+            ..Default::default()
+        });
+        events.extend(clock.step(arena.pin_mut(), 1));
+        if clock.is_overtime() {
+            break;
+        }
+    }
+
+    let kinds = events.iter().map(|event| event.kind).collect::<Vec<_>>();
+    assert!(kinds.contains(&MatchEventKind::ClockExpired));
+    assert!(kinds.contains(&MatchEventKind::OvertimeStarted));
+    assert!(clock.is_overtime());
+    assert_eq!(clock.time_remaining(), 0.);
+
+    // the next goal wins
+    clock.step(arena.pin_mut(), 1);
+    arena.pin_mut().set_ball(BallState {
+        pos: Vec3::new(0., -5000., 200.),
+        vel: Vec3::new(0., -2000., 0.),
+        ..Default::default()
+    });
+    let events = clock.step(arena.pin_mut(), 60);
+    assert!(events
+        .iter()
+        .any(|event| event.kind == MatchEventKind::Goal { team: Team::Orange }));
+    assert!(clock.is_ended());
+    assert_eq!(clock.score(Team::Orange), 1);
+
+    let result = clock.result().unwrap();
+    assert_eq!(result.winner, Some(Team::Orange));
+    assert_eq!(result.reason, rocketsim_rs::rules::EndReason::GoldenGoal);
+    assert!(result.overtime);
+}
+
+#[test]
+fn match_termination() {
+    use rocketsim_rs::rules::{EndReason, MatchClock, MatchRules, Termination};
+
+    INIT.call_once(|| init(None, true));
+
+    let mut arena = Arena::default_standard();
+    let _ = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+
+    let mut clock = MatchClock::new(MatchRules::default())
+        .with_termination(Termination::ScoreDifference(3))
+        .with_termination(Termination::Custom(Box::new(|clock| clock.ticks() >= 500)))
+        .with_termination(Termination::MaxTicks(100));
+    clock.start(arena.pin_mut());
+    assert!(clock.result().is_none());
+
+    clock.step(arena.pin_mut(), 1000);
+    assert!(clock.is_ended());
+
+    let result = clock.result().unwrap();
+    assert_eq!(result.reason, EndReason::Terminated(2));
+    assert_eq!(result.ticks, 100);
+    assert_eq!(result.winner, None);
+    assert!(!result.overtime);
+}
+
+#[test]
+#[cfg(feature = "bin")]
+fn replay_metadata() {
+    use rocketsim_rs::{
+        bytes::ToBytes,
+        metadata::{Metadata, PlayerMetadata},
+        replay::{ReplayReader, ReplayWriter},
+        stats::CarballTracker,
+    };
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let blue = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    let orange = arena.pin_mut().add_car(Team::Orange, CarConfig::dominus());
+
+    let metadata = Metadata::new()
+        .with_player(PlayerMetadata::new(blue, "Bot \"A\"").with_tag("ppo").with_tag("v3"))
+        .with_player(PlayerMetadata::new(orange, "Bot B"))
+        .with_team_name(Team::Orange, "Orange Team");
+    assert_eq!(metadata.name(blue), "Bot \"A\"");
+    assert_eq!(metadata.name(99), "Car 99");
+    assert_eq!(metadata.cars_with_tag("ppo").collect::<Vec<_>>(), [blue]);
+    assert_eq!(metadata.team_name(Team::Blue), None);
+
+    let mut writer = ReplayWriter::with_metadata(Vec::new(), &metadata).unwrap();
+    let state = arena.pin_mut().get_game_state();
+    writer.write_state(&state).unwrap();
+
+    let bytes = writer.into_inner();
+    let mut reader = ReplayReader::new(bytes.as_slice()).unwrap();
+    assert_eq!(reader.metadata(), &metadata);
+    assert_eq!(reader.next().unwrap().unwrap().tick_count, state.tick_count);
+    assert!(reader.next().is_none());
+
+    // version 1 replays have no metadata
+    let mut v1 = b"RSRP\x01".to_vec();
+    v1.extend_from_slice(&state.to_bytes());
+    let mut reader = ReplayReader::new(v1.as_slice()).unwrap();
+    assert!(reader.metadata().is_empty());
+    assert!(reader.next().unwrap().is_ok());
+
+    let mut tracker = CarballTracker::new();
+    tracker.update(&state);
+    let json = tracker.to_json_with_metadata(&metadata);
+    assert!(json.contains("\"name\":\"Bot \\\"A\\\"\",\"tags\":[\"ppo\",\"v3\"]"));
+    assert!(json.contains("\"name\":\"Orange Team\""));
+    assert!(tracker.to_json().contains(&format!("\"name\":\"Car {blue}\"")));
+}
+
+#[test]
+#[cfg(feature = "bin")]
+fn arena_snapshot() {
+    use rocketsim_rs::sim::DemoMode;
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let _ = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    let _ = arena.pin_mut().add_car(Team::Orange, CarConfig::dominus());
+    arena.pin_mut().reset_to_random_kickoff(Some(3));
+
+    let mut mutators = arena.get_mutator_config();
+    mutators.demo_mode = DemoMode::Disabled;
+    mutators.gravity.z = -400.;
+    arena.pin_mut().set_mutator_config(mutators);
+    arena.pin_mut().set_history_capacity(30);
+    arena.pin_mut().step(40);
+
+    let mut bytes = Vec::new();
+    arena.pin_mut().write_snapshot(&mut bytes, true).unwrap();
+    let mut loaded = Arena::read_snapshot(bytes.as_slice()).unwrap();
+
+    assert_eq!(loaded.get_tick_count(), arena.get_tick_count());
+    assert_eq!(loaded.get_mutator_config().demo_mode, DemoMode::Disabled);
+    assert_eq!(loaded.get_mutator_config().gravity.z, -400.);
+    assert_eq!(loaded.get_arena_config().max_objects, arena.get_arena_config().max_objects);
+    assert_eq!(loaded.history().len(), 30);
+    assert_eq!(loaded.history().oldest_tick(), arena.history().oldest_tick());
+    assert_eq!(
+        loaded.history().ball_at(20).unwrap().pos,
+        arena.history().ball_at(20).unwrap().pos
+    );
+
+    let state = arena.pin_mut().get_game_state();
+    let loaded_state = loaded.pin_mut().get_game_state();
+    assert_eq!(loaded_state.ball.pos, state.ball.pos);
+    assert_eq!(loaded_state.cars.len(), 2);
+    assert_eq!(loaded_state.cars[1].state.pos, state.cars[1].state.pos);
+    assert_eq!(loaded_state.cars[1].team, Team::Orange);
+
+    // without the history, only the capacity is kept
+    bytes.clear();
+    arena.pin_mut().write_snapshot(&mut bytes, false).unwrap();
+    let loaded = Arena::read_snapshot(bytes.as_slice()).unwrap();
+    assert!(loaded.history().is_empty());
+    assert_eq!(loaded.history_capacity(), 30);
+
+    assert!(Arena::read_snapshot(&bytes[..bytes.len() - 1]).is_err());
+    assert!(Arena::read_snapshot(&b"RSRP\x01"[..]).is_err());
+}
+
+#[test]
+#[cfg(feature = "zstd")]
+fn compressed_states() {
+    use rocketsim_rs::{
+        compression::{compress_states, decompress_states, CompressedReader, CompressedWriter, Compression},
+        replay::{ReplayReader, ReplayWriter},
+    };
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let _ = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    let _ = arena.pin_mut().add_car(Team::Orange, CarConfig::octane());
+    arena.pin_mut().reset_to_random_kickoff(Some(1));
+
+    let states = (0..60)
+        .map(|_| {
+            arena.pin_mut().step(2);
+            arena.pin_mut().get_game_state()
+        })
+        .collect::<Vec<_>>();
+
+    let uncompressed = compress_states(&states, Compression::None).unwrap();
+    let compressed = compress_states(&states, Compression::zstd()).unwrap();
+    assert!(compressed.len() < uncompressed.len() / 2);
+
+    let read = decompress_states(&compressed).unwrap();
+    assert_eq!(read.len(), states.len());
+    assert_eq!(read[59].ball.pos, states[59].ball.pos);
+    assert_eq!(decompress_states(&uncompressed).unwrap().len(), states.len());
+
+    let mut writer = ReplayWriter::new(CompressedWriter::new(Vec::new(), Compression::zstd()).unwrap()).unwrap();
+    for state in &states {
+        writer.write_state(state).unwrap();
+    }
+    let bytes = writer.finish().unwrap();
+
+    let reader = CompressedReader::new(bytes.as_slice()).unwrap();
+    assert_eq!(reader.compression(), Compression::zstd());
+    let read = ReplayReader::new(reader).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(read.len(), states.len());
+    assert_eq!(read[30].cars[1].state.pos, states[30].cars[1].state.pos);
+
+    #[cfg(feature = "lz4")]
+    {
+        let compressed = compress_states(&states, Compression::Lz4).unwrap();
+        assert!(compressed.len() < uncompressed.len());
+        assert_eq!(decompress_states(&compressed).unwrap().len(), states.len());
+    }
+}
+
+#[test]
+#[cfg(feature = "bin")]
+fn indexed_replay() {
+    use rocketsim_rs::replay::{IndexedReplay, ReplayWriter};
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let _ = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    arena.pin_mut().reset_to_random_kickoff(Some(0));
+
+    let mut writer = ReplayWriter::new(Vec::new()).unwrap();
+    let mut states = Vec::new();
+    for _ in 0..20 {
+        arena.pin_mut().step(4);
+        let state = arena.pin_mut().get_game_state();
+        writer.write_state(&state).unwrap();
+        states.push(state);
+    }
+
+    let mut bytes = writer.into_inner();
+    // a game state that's still being written
+    bytes.extend_from_slice(&[0; 10]);
+
+    let replay = IndexedReplay::new(bytes).unwrap();
+    assert_eq!(replay.len(), 20);
+    assert_eq!(replay.ticks()[0], 4);
+
+    assert_eq!(replay.state_at_tick(0).map(|state| state.tick_count), None);
+    assert_eq!(replay.state_at_tick(42).unwrap().tick_count, 40);
+    assert_eq!(replay.state_at_tick(1000).unwrap().tick_count, 80);
+    assert_eq!(replay.state(7).unwrap().ball.pos, states[7].ball.pos);
+    assert_eq!(replay.iter().count(), 20);
+    assert_eq!(replay.iter_from_tick(60).next().unwrap().tick_count, 60);
+
+    assert!(IndexedReplay::new(&b"nope!"[..]).is_err());
+}
+
+#[test]
+#[cfg(feature = "bin")]
+fn replay_playback() {
+    use rocketsim_rs::{
+        playback::ReplayPlayer,
+        protocol::SessionCommand,
+        replay::{IndexedReplay, ReplayWriter},
+    };
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let _ = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+
+    let mut writer = ReplayWriter::new(Vec::new()).unwrap();
+    for _ in 0..10 {
+        arena.pin_mut().step(10);
+        writer.write_state(&arena.pin_mut().get_game_state()).unwrap();
+    }
+
+    let replay = IndexedReplay::new(writer.into_inner()).unwrap();
+    let mut player = ReplayPlayer::with_ports(replay, 0, 45299).unwrap();
+    assert_eq!(player.state().unwrap().tick_count, 10);
+
+    player.apply(SessionCommand::Pause);
+    player.apply(SessionCommand::Seek(55));
+    player.update().unwrap();
+    assert_eq!(player.state().unwrap().tick_count, 50);
+
+    player.apply(SessionCommand::Step(5));
+    player.update().unwrap();
+    assert_eq!(player.state().unwrap().tick_count, 60);
+    assert_eq!(player.control().pending_steps(), 0);
+
+    // seeking past the end stays on the last state
+    player.apply(SessionCommand::Seek(1000));
+    player.update().unwrap();
+    assert!(player.is_at_end());
+    assert_eq!(player.index(), 9);
+}
+
+#[test]
+fn highlight_extraction() {
+    use rocketsim_rs::{
+        highlights::{find_highlights, HighlightConfig, HighlightKind},
+        smallvec::smallvec,
+        CarInfo, GameState,
+    };
+
+    let mut state = GameState {
+        tick_rate: 120.,
+        tick_count: 1000,
+        cars: smallvec![
+            CarInfo {
+                id: 1,
+                team: Team::Blue,
+                ..Default::default()
+            },
+            CarInfo {
+                id: 2,
+                team: Team::Orange,
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+    state.cars[0].state.pos = Vec3::new(0., -1000., 17.);
+    state.cars[1].state.pos = Vec3::new(0., 1000., 17.);
+    state.ball.pos = Vec3::new(0., 0., 93.);
+    let mut states = vec![state.clone()];
+
+    // blue hits the ball high in the air, towards the orange net
+    state.tick_count = 1120;
+    state.cars[0].state.pos.z = 900.;
+    state.cars[0].state.ball_hit_info.is_valid = true;
+    state.cars[0].state.ball_hit_info.tick_count_when_hit = 1110;
+    state.cars[0].state.ball_hit_info.ball_pos = Vec3::new(0., 0., 1000.);
+    state.ball.pos = Vec3::new(0., 3000., 1000.);
+    states.push(state.clone());
+
+    // orange demos blue
+    state.tick_count = 1240;
+    state.cars[1].state.car_contact.other_car_id = 1;
+    state.cars[0].state.is_demoed = true;
+    states.push(state.clone());
+
+    // the ball goes in
+    state.tick_count = 1360;
+    state.ball.pos = Vec3::new(0., 5300., 500.);
+    states.push(state.clone());
+
+    let config = HighlightConfig {
+        seconds_before: 1.,
+        seconds_after: 1.,
+        ..Default::default()
+    };
+    let highlights = find_highlights(&states, config);
+    assert_eq!(highlights.len(), 3);
+
+    assert_eq!(
+        highlights[0].kind,
+        HighlightKind::Aerial {
+            car_id: 1,
+            height: 1000.
+        }
+    );
+    assert_eq!(highlights[0].tick_count, 1120);
+    assert_eq!((highlights[0].start_tick, highlights[0].end_tick), (1000, 1240));
+    assert_eq!(
+        highlights[1].kind,
+        HighlightKind::Demo {
+            attacker: Some(2),
+            victim: 1
+        }
+    );
+    assert_eq!(
+        highlights[2].kind,
+        HighlightKind::Goal {
+            team: Team::Blue,
+            scorer: Some(1)
+        }
+    );
+    assert!(highlights[2].contains_tick(1400));
+    assert_eq!(highlights[2].time_range(120.), (1240. / 120., 1480. / 120.));
+}
+
+#[test]
+fn shot_on_target() {
+    use rocketsim_rs::{
+        analysis::{expected_goal, is_shot_on_target},
+        events::BallTouchTracker,
+        smallvec::smallvec,
+        CarInfo, GameState,
+    };
+
+    let ball = BallState {
+        pos: Vec3::new(0., 3000., 300.),
+        vel: Vec3::new(0., 3000., 0.),
+        ..Default::default()
+    };
+
+    let shot = is_shot_on_target(&ball).unwrap();
+    assert_eq!(shot.net_team, Team::Orange);
+    assert_eq!(shot.scoring_team(), Team::Blue);
+    assert!(shot.time > 0.5 && shot.time < 1.);
+    assert!(shot.pos.y > 5120.);
+
+    let mut wide = ball;
+    wide.vel = Vec3::new(3000., 1500., 0.);
+    assert!(is_shot_on_target(&wide).is_none());
+
+    let mut away = ball;
+    away.vel.y = -100.;
+    assert!(is_shot_on_target(&away).is_none());
+
+    let close = expected_goal(&ball, Team::Orange);
+    let mut far = ball;
+    far.pos.y = -3000.;
+    let mut slow = ball;
+    slow.vel = Vec3::ZERO;
+    assert!(close > expected_goal(&far, Team::Orange));
+    assert!(close > expected_goal(&slow, Team::Orange));
+    assert!(close > 0. && close <= 1.);
+    assert!(expected_goal(&ball, Team::Blue) < close);
+
+    let mut state = GameState {
+        tick_rate: 120.,
+        tick_count: 100,
+        cars: smallvec![CarInfo {
+            id: 1,
+            team: Team::Blue,
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    let mut tracker = BallTouchTracker::new();
+    assert!(tracker.update(&state).is_empty());
+
+    state.tick_count = 110;
+    state.ball = ball;
+    state.cars[0].state.ball_hit_info.is_valid = true;
+    state.cars[0].state.ball_hit_info.tick_count_when_hit = 108;
+
+    let touches = tracker.update(&state);
+    assert_eq!(touches.len(), 1);
+    assert_eq!(touches[0].car_id, 1);
+    assert_eq!(touches[0].tick_count, 108);
+    assert!(touches[0].is_shot());
+    assert!(!touches[0].is_own_goal_risk());
+    assert_eq!(touches[0].xg, close);
+
+    state.tick_count = 120;
+    assert!(tracker.update(&state).is_empty());
+}
+
+#[test]
+fn possession_tracking() {
+    use rocketsim_rs::{
+        smallvec::smallvec,
+        stats::{PossessionTracker, Possessor, LOOSE_BALL_TIME},
+        CarInfo, GameState,
+    };
+
+    let mut state = GameState {
+        tick_rate: 120.,
+        tick_count: 0,
+        cars: smallvec![
+            CarInfo {
+                id: 1,
+                team: Team::Blue,
+                ..Default::default()
+            },
+            CarInfo {
+                id: 2,
+                team: Team::Orange,
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+    state.cars[0].state.pos = Vec3::new(0., 200., 17.);
+    state.cars[1].state.pos = Vec3::new(0., 3000., 17.);
+    state.ball.pos = Vec3::new(0., 400., 93.);
+
+    let mut tracker = PossessionTracker::new();
+    assert!(tracker.update(&state).is_none());
+    let blue = Possessor {
+        car_id: 1,
+        team: Team::Blue,
+    };
+    assert_eq!(tracker.possessor(), Some(blue));
+
+    // blue dribbles for a second, with orange closing in
+    state.tick_count = 120;
+    state.cars[1].state.pos = Vec3::new(0., 1000., 17.);
+    assert!(tracker.update(&state).is_none());
+
+    let blue_stats = *tracker.team_stats(Team::Blue);
+    assert!((blue_stats.possession_time - 1.).abs() < 1e-4);
+    assert!((blue_stats.attacking_possession_time - 1.).abs() < 1e-4);
+    assert!((blue_stats.time_under_pressure - 1.).abs() < 1e-4);
+    assert!((tracker.team_stats(Team::Orange).time_pressuring - 1.).abs() < 1e-4);
+    assert!((tracker.player_possession_time(1) - 1.).abs() < 1e-4);
+
+    // orange touches the ball away
+    state.tick_count = 130;
+    state.cars[1].state.ball_hit_info.is_valid = true;
+    state.cars[1].state.ball_hit_info.tick_count_when_hit = 125;
+    state.ball.vel = Vec3::new(0., -3000., 0.);
+
+    let change = tracker.update(&state).unwrap();
+    assert!(change.is_turnover());
+    assert_eq!(change.from, Some(blue));
+    assert_eq!(change.to.unwrap().car_id, 2);
+    assert_eq!(tracker.team_stats(Team::Blue).turnovers, 1);
+    assert_eq!(tracker.team_stats(Team::Orange).possessions_gained, 1);
+    assert_eq!(tracker.touch_agreement().touches, 1);
+    assert_eq!(tracker.touch_agreement().matched, 0);
+
+    // nobody is in control for long enough, so the ball becomes loose
+    state.tick_count += (LOOSE_BALL_TIME * 120.) as u64;
+    state.ball.pos = Vec3::new(0., -3000., 93.);
+    let change = tracker.update(&state).unwrap();
+    assert!(!change.is_turnover());
+    assert_eq!(change.to, None);
+    assert!(tracker.possession_share(Team::Blue) > 0.5);
+}
+
+#[test]
+fn boost_path() {
+    use rocketsim_rs::{
+        analysis::best_boost_path,
+        sim::{BoostPadConfig, BoostPadState},
+        BoostPad,
+    };
+
+    let car_state = CarState {
+        pos: Vec3::new(0., 0., 17.),
+        rot_mat: RotMat::look_at(Vec3::Y, Vec3::Z),
+        vel: Vec3::new(0., 1000., 0.),
+        boost: 0.,
+        is_on_ground: true,
+        ..Default::default()
+    };
+
+    let pad = |x: f32, y: f32, is_big: bool, is_active: bool| BoostPad {
+        config: BoostPadConfig {
+            position: Vec3::new(x, y, 73.),
+            is_big,
+        },
+        state: BoostPadState {
+            is_active,
+            cooldown: if is_active { 0. } else { 10. },
+            ..Default::default()
+        },
+    };
+
+    let destination = Vec3::new(0., 4000., 17.);
+    let pads = [
+        // on cooldown, right on the way
+        pad(0., 1000., true, false),
+        // on the way
+        pad(0., 2000., true, true),
+        // way too far off
+        pad(-3500., 0., true, true),
+    ];
+
+    let path = best_boost_path(&car_state, &pads, destination);
+    assert_eq!(path.stops.len(), 1);
+    assert_eq!(path.stops[0].pad_index, 1);
+    assert!(path.stops[0].time < path.time);
+    assert!(path.boost > 0.);
+
+    let path = best_boost_path(&car_state, &pads[..1], destination);
+    assert!(path.stops.is_empty());
+    assert!(path.time > 0.);
+}
+
+#[test]
+fn aerial_intercepts() {
+    use rocketsim_rs::{analysis::aerial_intercept, prediction::BallPrediction};
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    arena
+        .pin_mut()
+        .set_car(
+            car_id,
+            CarState {
+                pos: Vec3::new(0., 0., 17.),
+                boost: 100.,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    arena.pin_mut().step(10);
+
+    // falling right above the car
+    arena.pin_mut().set_ball(BallState {
+        pos: Vec3::new(0., 500., 1200.),
+        ..Default::default()
+    });
+
+    let car_state = arena.pin_mut().get_car(car_id);
+    assert!(car_state.is_on_ground);
+    let prediction = BallPrediction::new(&arena, 240);
+
+    let intercept = aerial_intercept(&car_state, &prediction).expect("The ball should be reachable");
+    let slice = &prediction.slices[intercept.slice_index];
+    assert_eq!(intercept.time, slice.time);
+    assert_eq!(intercept.target, slice.state.pos);
+    assert!(intercept.target.z > 300.);
+    assert!(intercept.boost_needed > 0. && intercept.boost_needed <= car_state.boost);
+    assert!(intercept.orientation.forward.z > 0.);
+
+    // without any boost
+    let no_boost = CarState { boost: 0., ..car_state };
+    assert_eq!(aerial_intercept(&no_boost, &prediction), None);
+
+    // too high and too far to get to before the prediction ends
+    arena.pin_mut().set_ball(BallState {
+        pos: Vec3::new(3500., 4500., 1900.),
+        ..Default::default()
+    });
+    let prediction = BallPrediction::new(&arena, 30);
+    assert_eq!(aerial_intercept(&car_state, &prediction), None);
+}
+
+#[test]
+fn reachable_slices() {
+    use rocketsim_rs::{
+        analysis::{first_reachable_slice, jump_time, slice_margins, ReachMethod},
+        prediction::{BallPrediction, BallSlice},
+    };
+
+    let prediction = |pos: Vec3| BallPrediction {
+        slices: (1..=360)
+            .map(|i| BallSlice {
+                time: i as f32 / 120.,
+                tick_count: i,
+                state: BallState {
+                    pos,
+                    ..Default::default()
+                },
+                events: Vec::new(),
+            })
+            .collect(),
+    };
+
+    let car_state = CarState {
+        pos: Vec3::new(0., 0., 17.),
+        rot_mat: RotMat::look_at(Vec3::Y, Vec3::Z),
+        boost: 0.,
+        is_on_ground: true,
+        ..Default::default()
+    };
+
+    // a ball sitting on the ground in front of the car
+    let ground = prediction(Vec3::new(0., 1000., 93.));
+    let reach = first_reachable_slice(&car_state, &ground).unwrap();
+    assert_eq!(reach.method, Some(ReachMethod::Drive));
+    assert!(reach.time > 0.5 && reach.time < 2.);
+    assert!(reach.margin >= 0.);
+
+    let margins = slice_margins(&car_state, &ground);
+    assert_eq!(margins.len(), ground.slices.len());
+    assert!(!margins[reach.slice_index - 1].is_reachable());
+    assert!(margins[margins.len() - 1].margin > reach.margin);
+
+    // a ball that needs a jump
+    let low_air = prediction(Vec3::new(0., 1000., 250.));
+    let reach = first_reachable_slice(&car_state, &low_air).unwrap();
+    assert_eq!(reach.method, Some(ReachMethod::Jump));
+    assert!(jump_time(100.).unwrap() < jump_time(200.).unwrap());
+    assert!(jump_time(1000.).is_none());
+
+    // too high for a jump, and the car has no boost for an aerial
+    let high_air = prediction(Vec3::new(0., 1000., 1500.));
+    assert!(first_reachable_slice(&car_state, &high_air).is_none());
+
+    let boosted = CarState {
+        boost: 100.,
+        ..car_state
+    };
+    assert_eq!(
+        first_reachable_slice(&boosted, &high_air).unwrap().method,
+        Some(ReachMethod::Aerial)
+    );
+}
+
+#[test]
+fn ball_prediction_events() {
+    use rocketsim_rs::{
+        consts,
+        events::Surface,
+        prediction::{BallPredEvent, BallPrediction},
+    };
+
+    INIT.call_once(|| init(None, true));
+
+    // rolling into the orange goal
+    let mut arena = Arena::default_standard();
+    arena.pin_mut().set_ball(BallState {
+        pos: Vec3::new(0., 4500., 93.),
+        vel: Vec3::new(0., 2000., 0.),
+        ..Default::default()
+    });
+
+    let prediction = BallPrediction::new(&arena, 120);
+    let crossing = prediction.goal_crossing().expect("The ball should go in");
+    assert!(crossing.events.contains(&BallPredEvent::GoalCrossing {
+        scoring_team: Team::Blue
+    }));
+
+    while !arena.is_ball_scored() {
+        arena.pin_mut().step(1);
+    }
+    assert_eq!(crossing.tick_count, arena.get_tick_count());
+    assert_eq!(prediction.time_until_goal(), Some(crossing.time));
+
+    // dropped onto the floor
+    let mut arena = Arena::default_standard();
+    arena.pin_mut().set_ball(BallState {
+        pos: Vec3::new(0., 0., 500.),
+        ..Default::default()
+    });
+
+    let prediction = BallPrediction::new(&arena, 240);
+    assert!(prediction.goal_crossing().is_none());
+    let bounce = prediction.next_bounce().expect("The ball should bounce");
+    let Some(BallPredEvent::Bounce { surface, normal }) = bounce.events.first() else {
+        panic!("Expected a bounce, got {:?}", bounce.events);
+    };
+    assert_eq!(*surface, Surface::Floor);
+    assert!(normal.z > 0.9);
+
+    while arena.pin_mut().get_ball().vel.z <= 0. {
+        arena.pin_mut().step(1);
+    }
+    assert_eq!(bounce.tick_count, arena.get_tick_count());
+
+    // the car can't reach the ball any sooner than driving straight at it at max speed
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    arena
+        .pin_mut()
+        .set_car(
+            car_id,
+            CarState {
+                pos: Vec3::new(0., -2000., 17.),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    arena.pin_mut().set_ball(BallState {
+        pos: Vec3::new(0., 0., 93.),
+        ..Default::default()
+    });
+
+    let prediction = BallPrediction::new(&arena, 240);
+    assert_eq!(arena.num_cars(), 1);
+
+    let reachable = prediction.first_reachable(car_id).expect("The ball should be reachable");
+    let distance = (reachable.state.pos - Vec3::new(0., -2000., 17.)).length() - arena.get_ball_radius();
+    assert!(distance <= consts::CAR_MAX_SPEED * reachable.time);
+    assert!(distance > consts::CAR_MAX_SPEED * (reachable.time - 1. / 60.));
+    assert_eq!(
+        prediction
+            .iter_events()
+            .filter(|(_, event)| matches!(event, BallPredEvent::FirstReachable { .. }))
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn aerial_orientation() {
+    use rocketsim_rs::control::{orient_toward, orientation_error};
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+
+    let mut mutators = arena.get_mutator_config();
+    mutators.gravity = Vec3::ZERO;
+    arena.pin_mut().set_mutator_config(mutators);
+
+    let mut car_state = arena.pin_mut().get_car(car_id);
+    car_state.pos = Vec3::new(0., 0., 1000.);
+    car_state.vel = Vec3::ZERO;
+    car_state.ang_vel = Vec3::new(1., -2., 0.5);
+    car_state.is_on_ground = false;
+    arena.pin_mut().set_car(car_id, car_state).unwrap();
+
+    let target_forward = Vec3::new(-1., 1., 1.);
+    let target_up = Vec3::new(1., 0., 1.);
+    let target = RotMat::look_at(target_forward, target_up);
+
+    for _ in 0..240 {
+        let car_state = arena.pin_mut().get_car(car_id);
+        let (pitch, yaw, roll) = orient_toward(&car_state, target_forward, target_up);
+        assert!([pitch, yaw, roll].iter().all(|input| (-1.0..=1.).contains(input)));
+
+        let controls = CarControls {
+            pitch,
+            yaw,
+            roll,
+            ..Default::default()
+        };
+        arena.pin_mut().set_car_controls(car_id, controls).unwrap();
+        arena.pin_mut().step(1);
+    }
+
+    let car_state = arena.pin_mut().get_car(car_id);
+    assert!(orientation_error(&car_state.rot_mat, &target).length() < 0.1);
+    assert!(car_state.rot_mat.forward.dot(target.forward) > 0.99);
+    assert!(car_state.ang_vel.length() < 0.5);
+}
+
+#[test]
+fn drive_controllers() {
+    use rocketsim_rs::control::{angle_to, arrive, powerslide_toward, speed_controls, steer_toward};
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+
+    let start = CarState {
+        pos: Vec3::new(0., 0., 17.),
+        rot_mat: RotMat::IDENTITY,
+        is_on_ground: true,
+        boost: 100.,
+        ..Default::default()
+    };
+    arena.pin_mut().set_car(car_id, start).unwrap();
+
+    // positive steering turns towards the car's right
+    let target = Vec3::new(2500., 2000., 17.);
+    assert!(angle_to(&start, target) > 0.);
+    assert!(steer_toward(&start, target) > 0.);
+
+    let arrival_time = 2.;
+    let mut first_arrival = None;
+    for tick in 1..=480 {
+        let car_state = arena.pin_mut().get_car(car_id);
+        let time_left = arrival_time - (tick - 1) as f32 / 120.;
+        arena
+            .pin_mut()
+            .set_car_controls(car_id, arrive(&car_state, target, time_left))
+            .unwrap();
+        arena.pin_mut().step(1);
+
+        if first_arrival.is_none() && arena.pin_mut().get_car(car_id).pos.distance(target) < 200. {
+            first_arrival = Some(tick as f32 / 120.);
+        }
+    }
+
+    let first_arrival = first_arrival.unwrap();
+    assert!((first_arrival - arrival_time).abs() < 0.75, "{first_arrival}");
+
+    // holding a steady speed
+    let car_state = CarState {
+        vel: Vec3::new(1000., 0., 0.),
+        ..start
+    };
+    arena.pin_mut().set_car(car_id, car_state).unwrap();
+    for _ in 0..120 {
+        let car_state = arena.pin_mut().get_car(car_id);
+        let (throttle, boost) = speed_controls(&car_state, 1200.);
+        let controls = CarControls {
+            throttle,
+            boost,
+            ..Default::default()
+        };
+        arena.pin_mut().set_car_controls(car_id, controls).unwrap();
+        arena.pin_mut().step(1);
+    }
+    assert!((arena.pin_mut().get_car(car_id).vel.x - 1200.).abs() < 100.);
+
+    // a powerslide turn around to a target behind the car
+    let car_state = CarState {
+        vel: Vec3::new(1500., 0., 0.),
+        ..start
+    };
+    arena.pin_mut().set_car(car_id, car_state).unwrap();
+    let target = Vec3::new(-3000., 500., 17.);
+    assert!(powerslide_toward(&car_state, target).handbrake);
+
+    for _ in 0..180 {
+        let car_state = arena.pin_mut().get_car(car_id);
+        arena
+            .pin_mut()
+            .set_car_controls(car_id, powerslide_toward(&car_state, target))
+            .unwrap();
+        arena.pin_mut().step(1);
+    }
+
+    let car_state = arena.pin_mut().get_car(car_id);
+    assert!(angle_to(&car_state, target).abs() < 0.5);
+}
+
+#[test]
+fn air_recovery() {
+    use rocketsim_rs::{control::recover, field::Field};
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+
+    // upside down and tumbling, above the middle of the field
+    let car_state = CarState {
+        pos: Vec3::new(0., 0., 800.),
+        rot_mat: RotMat::look_at(Vec3::new(0., 1., 0.), Vec3::new(0., 0., -1.)),
+        vel: Vec3::new(0., 800., 200.),
+        ang_vel: Vec3::new(2., 0., 1.),
+        is_on_ground: false,
+        ..Default::default()
+    };
+    arena.pin_mut().set_car(car_id, car_state).unwrap();
+
+    let mut landed = None;
+    for _ in 0..360 {
+        let car_state = arena.pin_mut().get_car(car_id);
+        if car_state.is_on_ground {
+            landed = Some(car_state);
+            break;
+        }
+
+        arena
+            .pin_mut()
+            .set_car_controls(car_id, recover(&car_state, &Field::SOCCAR))
+            .unwrap();
+        arena.pin_mut().step(1);
+    }
+
+    let landed = landed.unwrap();
+    assert!(landed.rot_mat.up.z > 0.9);
+    assert!(landed.rot_mat.forward.y > 0.5);
+    assert_eq!(recover(&landed, &Field::SOCCAR).pitch, 0.);
+}
+
+#[test]
+fn landing_prediction() {
+    use rocketsim_rs::analysis::{predict_landing, predict_landing_sim};
+
+    // straight down onto the floor
+    let car_state = CarState {
+        pos: Vec3::new(0., 0., 500.),
+        vel: Vec3::new(1000., 0., 0.),
+        is_on_ground: false,
+        ..Default::default()
+    };
+    let landing = predict_landing(&car_state).unwrap();
+    assert_eq!(landing.surface_normal, Vec3::Z);
+    // z = 500 - 325 t^2 = 17
+    assert!((landing.time - (483f32 / 325.).sqrt()).abs() < 0.02);
+    assert!((landing.pos.x - 1000. * landing.time).abs() < 20.);
+
+    // flying into the side wall
+    let car_state = CarState {
+        pos: Vec3::new(3500., 0., 1000.),
+        vel: Vec3::new(2000., 0., 0.),
+        ..car_state
+    };
+    let landing = predict_landing(&car_state).unwrap();
+    assert_eq!(landing.surface_normal, -Vec3::X);
+    assert!(landing.time < 0.5);
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    let car_state = CarState {
+        pos: Vec3::new(0., 0., 500.),
+        rot_mat: RotMat::IDENTITY,
+        vel: Vec3::new(1000., 0., 0.),
+        is_on_ground: false,
+        ..Default::default()
+    };
+    arena.pin_mut().set_car(car_id, car_state).unwrap();
+
+    let predicted = predict_landing(&car_state).unwrap();
+    let simulated = predict_landing_sim(&arena, car_id, 3.).unwrap();
+    assert!((predicted.time - simulated.time).abs() < 0.1);
+    assert!(predicted.pos.distance(simulated.pos) < 150.);
+    assert!(simulated.surface_normal.z > 0.9);
+    assert!(predict_landing_sim(&arena, car_id + 1, 3.).is_none());
+}
+
+#[test]
+fn dodge_feasibility() {
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+
+    let car_state = CarState {
+        pos: Vec3::new(0., 0., 300.),
+        rot_mat: RotMat::IDENTITY,
+        vel: Vec3::new(1000., 0., 0.),
+        is_on_ground: false,
+        has_jumped: true,
+        air_time_since_jump: 0.25,
+        ..Default::default()
+    };
+    assert!(car_state.can_dodge());
+    assert_eq!(car_state.dodge_time_left(), Some(1.));
+    assert_eq!(car_state.flip_time_left(), None);
+
+    let expired = CarState {
+        air_time_since_jump: 1.3,
+        ..car_state
+    };
+    assert!(!expired.can_dodge());
+    assert_eq!(expired.dodge_time_left(), None);
+
+    let fell_off = CarState {
+        has_jumped: false,
+        air_time_since_jump: 0.,
+        ..car_state
+    };
+    assert_eq!(fell_off.dodge_time_left(), Some(f32::INFINITY));
+
+    // backwards and sideways dodges are stronger when moving forwards
+    let forward = car_state.dodge_delta_vel(Vec3::X);
+    let backward = car_state.dodge_delta_vel(-Vec3::X);
+    let right = car_state.dodge_delta_vel(car_state.rot_mat.right);
+    assert!(forward.x > 0. && backward.x < 0. && right.dot(car_state.rot_mat.right) > 0.);
+    assert!(-backward.x > forward.x);
+    assert!(right.length() > forward.length());
+
+    // compare against a real forward dodge
+    arena.pin_mut().set_car(car_id, car_state).unwrap();
+    let controls = CarControls {
+        jump: true,
+        pitch: -1.,
+        ..Default::default()
+    };
+    arena.pin_mut().set_car_controls(car_id, controls).unwrap();
+    arena.pin_mut().step(1);
+
+    let after = arena.pin_mut().get_car(car_id);
+    assert!(after.has_flipped);
+    assert!(after.flip_time_left().is_some());
+    let delta = after.vel - car_state.vel;
+    assert!((delta.x - forward.x).abs() < 30., "{delta} vs {forward}");
+    assert!((delta.y - forward.y).abs() < 30.);
+}
+
+#[cfg(feature = "bullet")]
+#[test]
+fn bullet_world() {
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+
+    let world = unsafe { arena.pin_mut().bullet_world() };
+    assert!(!world.is_null());
+    assert_eq!(world, unsafe { arena.pin_mut().bullet_world() });
+
+    // the world has the ball and the arena meshes, and gets a body for every car
+    let num_objects = arena.bullet_num_collision_objects();
+    assert!(num_objects > 1);
+
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    assert_eq!(arena.bullet_num_collision_objects(), num_objects + 1);
+    arena.pin_mut().remove_car(car_id).unwrap();
+    assert_eq!(arena.bullet_num_collision_objects(), num_objects);
+}
+
+#[test]
+fn custom_impulses() {
+    use rocketsim_rs::consts::{BALL_MASS_BT, CAR_MASS_BT, GRAVITY_Z};
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    arena.pin_mut().set_ball(BallState {
+        pos: Vec3::new(0., 0., 1000.),
+        ..Default::default()
+    });
+
+    arena.pin_mut().apply_impulse_to_ball(Vec3::new(BALL_MASS_BT * 1000., 0., 0.));
+    arena.pin_mut().step(1);
+    let ball = arena.pin_mut().get_ball();
+    assert!((ball.vel.x - 1000.).abs() < 1., "{}", ball.vel);
+
+    // an impulse away from the center spins the car
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    let car_state = arena.pin_mut().get_car(car_id);
+    let at_point = car_state.pos + car_state.rot_mat.forward * 50.;
+    arena
+        .pin_mut()
+        .apply_impulse_to_car(car_id, Vec3::new(0., 0., CAR_MASS_BT * 500.), at_point)
+        .unwrap();
+    assert!(arena.pin_mut().apply_impulse_to_car(car_id + 1, Vec3::X, at_point).is_err());
+    arena.pin_mut().step(1);
+    let car_state = arena.pin_mut().get_car(car_id);
+    assert!(car_state.vel.z > 100. && car_state.ang_vel.length() > 0.1);
+
+    // cancel out gravity on the ball with a constant force
+    arena.pin_mut().set_ball(BallState {
+        pos: Vec3::new(0., 0., 1000.),
+        ..Default::default()
+    });
+    arena.pin_mut().set_force_callback(
+        |arena, _| {
+            let tick_rate = arena.get_tick_rate();
+            arena.apply_impulse_to_ball(Vec3::new(0., 0., -GRAVITY_Z * BALL_MASS_BT / tick_rate));
+        },
+        0,
+    );
+    arena.pin_mut().step(120);
+
+    let ball = arena.pin_mut().get_ball();
+    assert!((ball.pos.z - 1000.).abs() < 1., "{}", ball.pos);
+}
+
+#[test]
+fn trigger_volumes() {
+    use rocketsim_rs::sim::TriggerVolume;
+    use std::sync::atomic::AtomicU32;
+
+    static BALL_ENTERED: AtomicU32 = AtomicU32::new(0);
+    static BALL_LEFT: AtomicU32 = AtomicU32::new(0);
+    static CAR_ENTERED: AtomicU32 = AtomicU32::new(0);
+
+    INIT.call_once(|| init(None, true));
+
+    let obb = TriggerVolume::oriented_box(Vec3::ZERO, Vec3::new(100., 10., 10.), RotMat::look_at(Vec3::Y, Vec3::Z));
+    assert!(obb.contains(Vec3::new(0., 90., 0.)));
+    assert!(!obb.contains(Vec3::new(90., 0., 0.)));
+
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    let car_pos = arena.pin_mut().get_car(car_id).pos;
+
+    // the car starts inside of this one, so it doesn't count as entering
+    let around_car = arena.pin_mut().add_trigger_volume(TriggerVolume::sphere(car_pos, 200.));
+    let target = arena
+        .pin_mut()
+        .add_trigger_volume(TriggerVolume::aabb(Vec3::new(0., 1000., 500.), Vec3::new(200., 200., 200.)));
+    assert_eq!(arena.get_trigger_volumes(), vec![around_car, target]);
+
+    arena.pin_mut().set_trigger_callback(
+        |_, volume_id, car_id, entered, target| {
+            if volume_id as usize != target {
+                return;
+            }
+
+            match (car_id, entered) {
+                (0, true) => BALL_ENTERED.fetch_add(1, Ordering::Relaxed),
+                (0, false) => BALL_LEFT.fetch_add(1, Ordering::Relaxed),
+                (_, true) => CAR_ENTERED.fetch_add(1, Ordering::Relaxed),
+                _ => 0,
+            };
+        },
+        target as usize,
+    );
+
+    // shoot the ball through the target
+    arena.pin_mut().set_ball(BallState {
+        pos: Vec3::new(0., 0., 500.),
+        vel: Vec3::new(0., 2000., 0.),
+        ..Default::default()
+    });
+    arena.pin_mut().step(120);
+
+    assert_eq!(BALL_ENTERED.load(Ordering::Relaxed), 1);
+    assert_eq!(BALL_LEFT.load(Ordering::Relaxed), 1);
+    assert_eq!(CAR_ENTERED.load(Ordering::Relaxed), 0);
+
+    arena.pin_mut().remove_trigger_volume(around_car).unwrap();
+    assert!(arena.get_trigger_volume(around_car).is_err());
+    assert!(arena.clone(false).has_trigger_volume(target));
+}
+
+#[test]
+fn watchdog() {
+    use rocketsim_rs::{
+        targets::Object,
+        watchdog::{Watchdog, WatchdogConfig, WatchdogIssue},
+    };
+
+    INIT.call_once(|| init(None, true));
+
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    arena.pin_mut().reset_to_random_kickoff(Some(0));
+
+    let mut watchdog = Watchdog::new(WatchdogConfig {
+        stuck_ticks: 3,
+        snapshot: true,
+        reset: true,
+        ..Default::default()
+    });
+    assert!(watchdog.update(arena.pin_mut()).is_none());
+
+    // a car sunk into the floor is only stuck after a few updates
+    let mut game_state = arena.pin_mut().get_game_state();
+    game_state.cars[0].state.pos.z = 0.;
+    assert!(watchdog.check(&game_state).is_empty());
+    assert!(watchdog.check(&game_state).is_empty());
+    assert_eq!(watchdog.check(&game_state), vec![WatchdogIssue::Stuck { car_id, ticks: 3 }]);
+
+    game_state.ball.vel.x = f32::NAN;
+    assert!(watchdog
+        .check(&game_state)
+        .contains(&WatchdogIssue::NonFinite { object: Object::Ball }));
+
+    // the ball falling through the floor gets the arena snapshotted and reset
+    arena.pin_mut().set_ball(BallState {
+        pos: Vec3::new(0., 0., -500.),
+        ..Default::default()
+    });
+    let event = watchdog.update(arena.pin_mut()).unwrap();
+    assert_eq!(event.issues, vec![WatchdogIssue::OutOfBounds { object: Object::Ball }]);
+    assert!(event.reset);
+
+    let mut snapshot = event.snapshot.unwrap();
+    assert!(snapshot.pin_mut().get_ball().pos.z < 0.);
+    assert!(arena.pin_mut().get_ball().pos.z > 0.);
+    assert!(watchdog.update(arena.pin_mut()).is_none());
+}
+
+#[test]
+fn kickoff_spawns() {
+    use rocketsim_rs::{kickoff_spawn_locations, sim::GameMode, InvalidKickoffSpawns};
+
+    INIT.call_once(|| init(None, true));
+
+    let mut arena = Arena::default_standard();
+    for team in [Team::Blue, Team::Blue, Team::Orange, Team::Orange] {
+        let _ = arena.pin_mut().add_car(team, CarConfig::octane());
+    }
+
+    // the same seed gives the same spawns, and every car is at a kickoff spawn
+    let spawns = arena.pin_mut().reset_to_random_kickoff(Some(7));
+    assert_eq!(spawns.len(), 4);
+    assert!(spawns.iter().all(|spawn| spawn.spawn_index.is_some()));
+    assert_eq!(arena.pin_mut().reset_to_random_kickoff(Some(7)), spawns);
+
+    let spawns = arena.pin_mut().reset_to_kickoff(&[4, 2]).unwrap();
+    let locations = kickoff_spawn_locations(GameMode::Soccar);
+    for spawn in &spawns {
+        let car_state = arena.pin_mut().get_car(spawn.car_id);
+        let location = &locations[spawn.spawn_index.unwrap()];
+        let sign = if spawn.team == Team::Blue { 1. } else { -1. };
+
+        assert!((car_state.pos.x - location.x * sign).abs() < 1e-3);
+        assert!((car_state.pos.y - location.y * sign).abs() < 1e-3);
+        // every car faces the ball
+        assert!(car_state.rot_mat.forward.dot(-car_state.pos.normalize_or_zero()) > 0.6);
+    }
+
+    assert_eq!(
+        arena.pin_mut().reset_to_kickoff(&[5, 0]),
+        Err(InvalidKickoffSpawns::OutOfRange(5))
+    );
+    assert_eq!(
+        arena.pin_mut().reset_to_kickoff(&[1, 1]),
+        Err(InvalidKickoffSpawns::Duplicate(1))
+    );
+    assert!(matches!(
+        arena.pin_mut().reset_to_kickoff(&[0]),
+        Err(InvalidKickoffSpawns::TooFew { num_cars: 2, .. })
+    ));
+}
+
+#[test]
+fn spawn_tables() {
+    use rocketsim_rs::consts::{
+        CAR_RESPAWN_LOCATIONS_HOOPS, CAR_RESPAWN_LOCATIONS_SOCCAR, CAR_SPAWN_LOCATIONS_HEATSEEKER,
+        CAR_SPAWN_LOCATIONS_HOOPS, CAR_SPAWN_LOCATIONS_SOCCAR,
+    };
+
+    // every spawn in a table is its own spot on the blue side
+    for table in [
+        &CAR_SPAWN_LOCATIONS_SOCCAR[..],
+        &CAR_SPAWN_LOCATIONS_HOOPS,
+        &CAR_SPAWN_LOCATIONS_HEATSEEKER,
+        &CAR_RESPAWN_LOCATIONS_SOCCAR,
+        &CAR_RESPAWN_LOCATIONS_HOOPS,
+    ] {
+        for (i, spawn) in table.iter().enumerate() {
+            assert!(spawn.y < 0.);
+            assert!(table[..i].iter().all(|other| other.x != spawn.x || other.y != spawn.y));
+        }
+    }
+}
+
+#[test]
+fn episode_runner() {
+    use rocketsim_rs::{
+        episodes::{EpisodeEnd, EpisodeHooks, EpisodeReset, EpisodeSummary, Runner, TerminalCondition},
+        GameState,
+    };
+
+    #[derive(Default)]
+    struct Counter {
+        resets: usize,
+        steps: usize,
+        ends: usize,
+    }
+
+    impl EpisodeHooks for Counter {
+        fn on_reset(&mut self, _: usize, _: &GameState) {
+            self.resets += 1;
+        }
+
+        fn on_step(&mut self, controls: &[(u32, CarControls)], _: &GameState) {
+            assert_eq!(controls.len(), 1);
+            self.steps += 1;
+        }
+
+        fn on_end(&mut self, _: &EpisodeSummary) {
+            self.ends += 1;
+        }
+    }
+
+    INIT.call_once(|| init(None, true));
+
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+
+    let mut runner = Runner::new()
+        .with_reset(EpisodeReset::RandomKickoff { seed: Some(3) })
+        .with_tick_skip(8)
+        .with_controller(car_id, |_, _: &GameState| CarControls {
+            throttle: 1.,
+            boost: true,
+            ..Default::default()
+        })
+        .with_terminal_condition(TerminalCondition::GoalScored)
+        .with_terminal_condition(TerminalCondition::MaxTicks(240));
+
+    let mut hooks = Counter::default();
+    let summaries = runner.run(arena.pin_mut(), 2, &mut hooks).unwrap();
+    assert_eq!(summaries.len(), 2);
+    assert_eq!(runner.episode(), 2);
+    assert_eq!((hooks.resets, hooks.steps, hooks.ends), (2, 60, 2));
+
+    for (i, summary) in summaries.iter().enumerate() {
+        assert_eq!(summary.index, i);
+        assert_eq!(summary.end, EpisodeEnd::MaxTicks);
+        assert_eq!(summary.ticks, 240);
+        assert!(summary.stats.player(car_id).is_some());
+    }
+
+    // a ball that's about to go in ends the episode with a goal
+    let mut runner = Runner::new()
+        .with_reset(EpisodeReset::Custom(Box::new(|_, arena| {
+            arena.set_ball(BallState {
+                pos: Vec3::new(0., 4500., 100.),
+                vel: Vec3::new(0., 2000., 0.),
+                ..Default::default()
+            });
+        })))
+        .with_terminal_condition(TerminalCondition::MaxTicks(240))
+        .with_terminal_condition(TerminalCondition::GoalScored);
+
+    let summary = runner.run_episode(arena.pin_mut(), &mut ()).unwrap();
+    assert_eq!(summary.end, EpisodeEnd::Goal(Team::Blue));
+    assert!(summary.ticks < 120);
+}
+
+#[cfg(feature = "bin")]
+#[test]
+fn distributed_workers() {
+    use rocketsim_rs::{
+        bytes::FromBytesExact,
+        distributed::{Coordinator, Request, Response, Worker},
+        sim::ArenaConfig,
+        BoostPad, GameState,
+    };
+    use std::{
+        io::{Cursor, ErrorKind},
+        net::{TcpListener, TcpStream},
+        thread,
+    };
+
+    INIT.call_once(|| init(None, true));
+
+    let mut arena = Arena::default_standard();
+    let _ = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    let _ = arena.pin_mut().add_car(Team::Orange, CarConfig::octane());
+    arena.pin_mut().reset_to_random_kickoff(Some(0));
+    let game_state = arena.pin_mut().get_game_state();
+
+    // requests survive the round trip, and a bad frame is an error instead of a panic
+    let request = Request::Step {
+        ticks: 8,
+        controls: vec![
+            vec![(
+                1,
+                CarControls {
+                    throttle: 1.,
+                    ..Default::default()
+                },
+            )],
+            Vec::new(),
+        ],
+        reset_on_goal: true,
+    };
+    let mut bytes = Vec::new();
+    request.write(&mut bytes).unwrap();
+    assert_eq!(
+        format!("{:?}", Request::read(Cursor::new(&bytes)).unwrap()),
+        format!("{request:?}")
+    );
+    assert!(Request::read(Cursor::new(&bytes[..bytes.len() - 1])).is_err());
+    assert!(Response::read(Cursor::new(&bytes)).is_err());
+
+    // garbage in a load is rejected before it gets anywhere near an arena
+    let load = Request::Load {
+        config: ArenaConfig::default(),
+        states: vec![game_state.clone()],
+    };
+    let mut bytes = Vec::new();
+    load.write(&mut bytes).unwrap();
+    assert!(Request::read(Cursor::new(&bytes)).is_ok());
+
+    let config_start = 1 + u32::NUM_BYTES;
+    let state_start = config_start + ArenaConfig::NUM_BYTES + u32::NUM_BYTES;
+    let game_mode = state_start + u64::NUM_BYTES + f32::NUM_BYTES;
+    let first_team = state_start
+        + GameState::MIN_NUM_BYTES
+        + BallState::NUM_BYTES
+        + game_state.pads.len() * BoostPad::NUM_BYTES
+        + u32::NUM_BYTES;
+    for (i, byte) in [(config_start, 2), (game_mode, 5), (game_mode + 1, 0xff), (first_team, 2)] {
+        let mut garbage = bytes.clone();
+        garbage[i] = byte;
+        let error = Request::read(Cursor::new(&garbage)).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData, "byte {i}");
+    }
+
+    // and a game state RocketSim can't run is an error, leaving the worker usable
+    let mut worker = Worker::new();
+    let Some(Response::Error(_)) = worker.handle(Request::Load {
+        config: ArenaConfig::default(),
+        states: vec![GameState::default()],
+    }) else {
+        panic!("Expected an error for a tick rate of 0");
+    };
+    assert_eq!(worker.num_arenas(), 0);
+    assert!(matches!(worker.handle(load), Some(Response::Ok)));
+    assert_eq!(worker.num_arenas(), 1);
+
+    let mut workers = Vec::new();
+    let mut handles = Vec::new();
+    for _ in 0..2 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        workers.push(TcpStream::connect(listener.local_addr().unwrap()).unwrap());
+        handles.push(thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            Worker::new().serve(stream).unwrap();
+        }));
+    }
+
+    let mut coordinator = Coordinator::new(workers).unwrap();
+    coordinator
+        .load(ArenaConfig::default(), &vec![game_state.clone(); 3])
+        .unwrap();
+    assert_eq!(coordinator.num_arenas(), 3);
+    assert_eq!(coordinator.get_states().unwrap().len(), 3);
+
+    // only the last arena's car drives, which is on the second worker
+    let controls = vec![
+        Vec::new(),
+        Vec::new(),
+        vec![(
+            1,
+            CarControls {
+                throttle: 1.,
+                ..Default::default()
+            },
+        )],
+    ];
+    let states = coordinator.step(60, &controls, false).unwrap();
+    assert_eq!(states.len(), 3);
+    assert_eq!(states[0].tick_count, game_state.tick_count + 60);
+    assert_eq!(states[0].cars[0].state.pos, states[1].cars[0].state.pos);
+    assert_ne!(states[0].cars[0].state.pos, states[2].cars[0].state.pos);
+
+    let stats = coordinator.get_stats().unwrap();
+    assert!(stats.iter().all(|stats| stats.ticks == 60));
+
+    coordinator.reset(Some(0)).unwrap();
+    coordinator.quit().unwrap();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
+
+#[cfg(feature = "grpc")]
+#[test]
+fn grpc_service() {
+    use rocketsim_rs::grpc::{
+        proto::{self, arena_service_server::ArenaService},
+        GrpcService,
+    };
+    use tonic::{Code, Request};
+
+    INIT.call_once(|| init(None, true));
+
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+    runtime.block_on(async {
+        let service = GrpcService::new();
+
+        let arena_id = service
+            .create_arena(Request::new(proto::CreateArenaRequest {
+                game_mode: proto::GameMode::Soccar.into(),
+                tick_rate: 0,
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .id;
+        let car_id = service
+            .add_car(Request::new(proto::AddCarRequest {
+                arena_id,
+                team: proto::Team::Orange.into(),
+                preset: proto::CarPreset::Dominus.into(),
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .id;
+
+        let mut states = service
+            .stream_states(Request::new(proto::ArenaId { id: arena_id }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        service
+            .set_controls(Request::new(proto::SetControlsRequest {
+                arena_id,
+                controls: vec![proto::CarControlsEntry {
+                    car_id,
+                    controls: Some(proto::CarControls {
+                        throttle: 1.,
+                        ..Default::default()
+                    }),
+                }],
+            }))
+            .await
+            .unwrap();
+
+        let game_state = service
+            .step(Request::new(proto::StepRequest { arena_id, ticks: 30 }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(game_state.tick_count, 30);
+        assert_eq!(game_state.cars.len(), 1);
+        assert_eq!(game_state.cars[0].team, i32::from(proto::Team::Orange));
+
+        let streamed = tokio_stream::StreamExt::next(&mut states).await.unwrap().unwrap();
+        assert_eq!(streamed, game_state);
+
+        let unknown_car = service
+            .set_controls(Request::new(proto::SetControlsRequest {
+                arena_id,
+                controls: vec![proto::CarControlsEntry {
+                    car_id: car_id + 1,
+                    controls: None,
+                }],
+            }))
+            .await;
+        assert_eq!(unknown_car.unwrap_err().code(), Code::NotFound);
+
+        service
+            .remove_arena(Request::new(proto::ArenaId { id: arena_id }))
+            .await
+            .unwrap();
+        assert_eq!(service.num_arenas(), 0);
+        assert!(tokio_stream::StreamExt::next(&mut states).await.is_none());
+
+        let removed = service.get_game_state(Request::new(proto::ArenaId { id: arena_id })).await;
+        assert_eq!(removed.unwrap_err().code(), Code::NotFound);
+    });
+}
+
+#[test]
+fn json_lines_telemetry() {
+    use rocketsim_rs::telemetry::{Column, JsonLinesWriter};
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let car_id = arena.pin_mut().add_car(Team::Orange, CarConfig::octane());
+    arena.pin_mut().reset_to_random_kickoff(Some(7));
+
+    // 30 lines per second at 120 ticks per second is every 4th tick
+    let mut writer =
+        JsonLinesWriter::new(Vec::new(), &[Column::TickCount, Column::BallPos, Column::CarBoost]).with_sample_rate(30.);
+
+    for _ in 0..12 {
+        writer.write(&arena.pin_mut().get_game_state()).unwrap();
+        arena.pin_mut().step(1);
+    }
+
+    let output = String::from_utf8(writer.into_inner()).unwrap();
+    let lines = output.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1].starts_with("{\"tick_count\":4,\"ball_pos\":[0,0,"));
+    assert!(lines[1].contains(&format!("\"cars\":[{{\"id\":{car_id},\"team\":\"orange\",\"boost\":")));
+    assert!(lines.iter().all(|line| line.ends_with("}]}")));
+}
+
+#[test]
+fn arena_settings() {
+    use rocketsim_rs::{
+        sim::{ArenaMemWeightMode, BoostPadConfig, GameMode, MutatorConfig},
+        ArenaSettings,
+    };
+
+    INIT.call_once(|| init(None, true));
+
+    let mut mutators = MutatorConfig::default(GameMode::Soccar);
+    mutators.ball_max_speed = 1000.;
+    let pads = vec![
+        BoostPadConfig {
+            position: Vec3::new(0., 1000., 73.),
+            is_big: true,
+        },
+        BoostPadConfig {
+            position: Vec3::new(0., -1000., 70.),
+            is_big: false,
+        },
+    ];
+
+    let mut arena = Arena::from_config(
+        ArenaSettings::new(GameMode::Soccar)
+            .with_tick_rate(60)
+            .with_mem_weight_mode(ArenaMemWeightMode::Light)
+            .with_mutators(mutators)
+            .with_boost_pads(pads)
+            .with_max_cars(2),
+    )
+    .unwrap();
+
+    assert_eq!(arena.get_tick_rate(), 60.);
+    assert_eq!(arena.get_arena_config().mem_weight_mode, ArenaMemWeightMode::Light);
+    assert_eq!(arena.get_mutator_config().ball_max_speed, 1000.);
+    assert_eq!(arena.num_pads(), 2);
+    assert!(arena.get_pad_config(0).is_big);
+    assert_eq!(arena.max_cars(), Some(2));
+
+    let _ = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+    let car_id = arena.pin_mut().try_add_car(Team::Orange, CarConfig::octane()).unwrap();
+    assert!(arena.is_full());
+    assert!(arena.pin_mut().try_add_car(Team::Orange, CarConfig::octane()).is_err());
+
+    // the limit applies to future cars only, and clones keep it
+    arena.pin_mut().remove_car(car_id).unwrap();
+    assert!(!arena.is_full());
+    assert_eq!(arena.clone(false).max_cars(), Some(2));
+    arena.pin_mut().set_max_cars(None);
+    assert_eq!(arena.max_cars(), None);
+
+    // a game mode on its own uses the defaults, and the tick rate is checked
+    let arena = Arena::from_config(GameMode::Hoops).unwrap();
+    assert_eq!(arena.get_tick_rate(), 120.);
+    assert_eq!(arena.max_cars(), None);
+    assert!(Arena::from_config(ArenaSettings::new(GameMode::Soccar).with_tick_rate(10)).is_err());
+
+    let mut arena = Arena::from_config(ArenaSettings::new(GameMode::TheVoid).with_void_floor(true)).unwrap();
+    arena.pin_mut().set_ball(BallState {
+        pos: Vec3::new(0., 0., 500.),
+        ..Default::default()
+    });
+    arena.pin_mut().step(240);
+    assert!(arena.pin_mut().get_ball().pos.z > 0.);
+}
+
+#[test]
+fn mutator_tuning() {
+    use rocketsim_rs::{
+        sim::DemoMode,
+        tuning::{InvalidMutator, MutatorFile, MutatorTracker, MutatorValue, MUTATOR_NAMES},
+    };
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+    let mut tracker = MutatorTracker::new();
+    assert!(tracker.update(&arena.get_mutator_config(), 0).is_empty());
+
+    let change = arena.pin_mut().set_mutator("gravity", "[0, 0, -108]").unwrap().unwrap();
+    assert_eq!(change.name, "gravity");
+    assert_eq!(change.new, MutatorValue::Vec3(Vec3::new(0., 0., -108.)));
+    assert_eq!(arena.get_mutator_config().gravity.z, -108.);
+
+    // setting the same value again isn't a change
+    assert!(arena.pin_mut().set_mutator("gravity", "0 0 -108").unwrap().is_none());
+    assert_eq!(
+        arena.pin_mut().set_mutator("gravity", "0 -108"),
+        Err(InvalidMutator::InvalidValue {
+            name: "gravity".to_string(),
+            value: "0 -108".to_string()
+        })
+    );
+    assert!(matches!(
+        arena.pin_mut().set_mutator("moon_gravity", "1"),
+        Err(InvalidMutator::UnknownName(_))
+    ));
+
+    arena.pin_mut().step(10);
+    let changes = arena.pin_mut().update_mutators(|mutators| {
+        mutators.boost_accel_air *= 2.;
+        mutators.unlimited_flips = true;
+    });
+    assert_eq!(
+        changes.iter().map(|change| change.name).collect::<Vec<_>>(),
+        ["boost_accel_air", "unlimited_flips"]
+    );
+    assert!(changes.iter().all(|change| change.tick_count == 10));
+
+    // the tracker sees everything that happened since it last looked
+    let changes = tracker.update(&arena.get_mutator_config(), 10);
+    assert_eq!(changes.len(), 3);
+    assert!(changes.iter().all(|change| MUTATOR_NAMES.contains(&change.name)));
+
+    let path = std::env::temp_dir().join(format!("rocketsim_mutators_{}.txt", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let mut file = MutatorFile::new(&path);
+    assert!(file.poll(arena.pin_mut()).unwrap().is_empty());
+
+    std::fs::write(&path, "# low gravity\ngravity = 0 0 -300\n\ndemo_mode = disabled\n").unwrap();
+    let changes = file.poll(arena.pin_mut()).unwrap();
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[1].new, MutatorValue::DemoMode(DemoMode::Disabled));
+
+    // unchanged files aren't applied again
+    assert!(file.poll(arena.pin_mut()).unwrap().is_empty());
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn console_commands() {
+    use rocketsim_rs::console::{execute, Console, ConsoleError};
+
+    INIT.call_once(|| init(None, true));
+    let mut arena = Arena::default_standard();
+
+    assert_eq!(execute(arena.pin_mut(), "spawn blue dominus").unwrap(), "Spawned car 1");
+    assert!(execute(arena.pin_mut(), "spawn green").is_err());
+
+    execute(arena.pin_mut(), "ball vel 0 0 2000").unwrap();
+    assert_eq!(arena.pin_mut().get_ball().vel, Vec3::new(0., 0., 2000.));
+
+    execute(arena.pin_mut(), "car 1 boost 12").unwrap();
+    assert_eq!(arena.pin_mut().get_car(1).boost, 12.);
+    assert!(matches!(execute(arena.pin_mut(), "car 2"), Err(ConsoleError::NoCar(_))));
+    assert!(matches!(
+        execute(arena.pin_mut(), "ball vel 0 0"),
+        Err(ConsoleError::Usage(_))
+    ));
+    assert!(matches!(
+        execute(arena.pin_mut(), "fly"),
+        Err(ConsoleError::UnknownCommand(_))
+    ));
+
+    assert_eq!(execute(arena.pin_mut(), "step 10").unwrap(), "Tick 10");
+    execute(arena.pin_mut(), "set boost_accel_air 0").unwrap();
+    assert_eq!(execute(arena.pin_mut(), "get boost_accel_air").unwrap(), "0");
+
+    let mut console = Console::new();
+    console.execute(&mut arena, "speed 2").unwrap();
+    assert_eq!(console.speed(), 2.);
+    assert!(console.execute(&mut arena, "speed -1").is_err());
+    assert!(console.execute(&mut arena, "speed 1e-30").is_err());
+    assert!(console.execute(&mut arena, "speed inf").is_err());
+    assert!(console.execute(&mut arena, "speed NaN").is_err());
+    assert_eq!(console.speed(), 2.);
+
+    console.execute(&mut arena, "snapshot save start").unwrap();
+    console.execute(&mut arena, "step 20").unwrap();
+    console.execute(&mut arena, "snapshot load start").unwrap();
+    assert_eq!(arena.get_tick_count(), 10);
+    assert!(matches!(
+        console.execute(&mut arena, "snapshot load other"),
+        Err(ConsoleError::NoSnapshot(_))
+    ));
+
+    let mut output = Vec::new();
+    console
+        .run(&mut arena, "step 5\nfly\nquit\nstep 5\n".as_bytes(), &mut output)
+        .unwrap();
+    assert!(console.quit_requested());
+    assert_eq!(arena.get_tick_count(), 15);
+    assert!(String::from_utf8(output).unwrap().starts_with("Tick 15\nError: "));
+}
+
+#[test]
+fn arena_registry() {
+    use rocketsim_rs::registry::{ArenaRegistry, RegistryEvent, RegistryEventKind, RenameError};
+
+    INIT.call_once(|| init(None, true));
+    let mut registry = ArenaRegistry::new();
+    assert!(registry.is_empty());
+
+    assert!(registry.insert("match-b", Arena::default_standard()).is_none());
+    assert!(registry.insert("match-a", Arena::default_standard()).is_none());
+    registry.get_mut("match-a").unwrap().step(5);
+    assert_eq!(registry.get("match-a").unwrap().get_tick_count(), 5);
+    assert_eq!(registry.labels().collect::<Vec<_>>(), ["match-a", "match-b"]);
+
+    let arena = registry.get("match-b").unwrap();
+    assert_eq!(registry.label_of(arena), Some("match-b"));
+
+    assert_eq!(
+        registry.rename("match-a", "match-b"),
+        Err(RenameError::LabelTaken("match-b".to_string()))
+    );
+    registry.rename("match-a", "final").unwrap();
+    assert!(registry.remove("match-b").is_some());
+    assert!(registry.remove("match-b").is_none());
+
+    let kinds = registry
+        .take_events()
+        .into_iter()
+        .map(|RegistryEvent { label, kind }| (label, kind))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [
+            ("match-b".to_string(), RegistryEventKind::Added),
+            ("match-a".to_string(), RegistryEventKind::Added),
+            (
+                "final".to_string(),
+                RegistryEventKind::Renamed {
+                    old_label: "match-a".to_string()
+                }
+            ),
+            ("match-b".to_string(), RegistryEventKind::Removed),
+        ]
+    );
+    assert!(registry.take_events().is_empty());
+
+    for (_, arena) in registry.iter_mut() {
+        arena.step(1);
+    }
+    assert_eq!(registry.get("final").unwrap().get_tick_count(), 6);
+
+    ArenaRegistry::global()
+        .lock()
+        .unwrap()
+        .insert("global", Arena::default_standard());
+    assert!(ArenaRegistry::global().lock().unwrap().contains("global"));
+}
+
+#[test]
+fn arena_pool() {
+    use rocketsim_rs::pool::ArenaPool;
+
+    INIT.call_once(|| init(None, true));
+    let mut pool = ArenaPool::new(3);
+    assert_eq!(pool.num_threads(), 3);
+
+    let ids = pool.add_arenas(7, |id| {
+        let mut arena = Arena::default_standard();
+        let _ = arena
+            .pin_mut()
+            .add_car(if id % 2 == 0 { Team::Blue } else { Team::Orange }, CarConfig::octane());
+        arena
+    });
+    assert_eq!(ids, 0..7);
+    assert_eq!(pool.push(Arena::default_standard()), 7);
+    assert_eq!(pool.len(), 8);
+
+    pool.step(8);
+    pool.get_mut(7).unwrap().step(2);
+    let states = pool.get_game_states();
+    assert_eq!(states.len(), 8);
+    assert!(states[..7].iter().all(|state| state.tick_count == 8 && state.cars.len() == 1));
+    assert_eq!(states[1].cars[0].team, Team::Orange);
+    assert_eq!(pool.get(7).unwrap().get_tick_count(), 10);
+
+    let stats = pool.thread_stats();
+    assert_eq!(stats.iter().map(|stats| stats.num_arenas).sum::<usize>(), 8);
+    assert_eq!(stats.iter().map(|stats| stats.ticks).sum::<u64>(), 64);
+    assert!(stats.iter().all(|stats| stats.ticks_per_second() > 0.));
+
+    pool.reset_stats();
+    assert!(pool.thread_stats().iter().all(|stats| stats.ticks == 0));
+
+    #[cfg(feature = "affinity")]
+    {
+        use rocketsim_rs::pool::Placement;
+
+        let mut pool = ArenaPool::new(2).with_placement(&Placement::Numa);
+        pool.add_arenas(4, |_| Arena::default_standard());
+        pool.step(1);
+        assert!(pool.thread_stats().iter().all(|stats| stats.core.is_some()));
+    }
+}
+
+#[test]
+fn pool_suspend() {
+    use rocketsim_rs::pool::{ArenaPool, WakeCondition};
+    use std::time::{Duration, Instant};
+
+    INIT.call_once(|| init(None, true));
+    let mut pool = ArenaPool::new(2);
+    pool.add_arenas(4, |_| Arena::default_standard());
+
+    assert!(pool.suspend(0, WakeCondition::Manual));
+    assert!(pool.suspend(1, WakeCondition::Ticks(10)));
+    assert!(pool.suspend(2, WakeCondition::At(Instant::now() + Duration::from_secs(3600))));
+    assert!(!pool.suspend(4, WakeCondition::Manual));
+    assert_eq!(pool.idle_ids().collect::<Vec<_>>(), [0, 1, 2]);
+
+    pool.step(8);
+    let ticks = |pool: &ArenaPool| pool.iter().map(|(_, arena)| arena.get_tick_count()).collect::<Vec<_>>();
+    assert_eq!(ticks(&pool), [0, 0, 0, 8]);
+    assert_eq!(pool.thread_stats().iter().map(|stats| stats.ticks).sum::<u64>(), 8);
+
+    // arena 1 has waited 8 ticks, so it wakes up during the step after the next one
+    pool.step(8);
+    assert!(pool.is_idle(1));
+    pool.step(8);
+    assert!(!pool.is_idle(1));
+    assert_eq!(ticks(&pool), [0, 8, 0, 24]);
+
+    assert!(pool.wake(0));
+    assert!(!pool.wake(0));
+    pool.step(1);
+    assert_eq!(ticks(&pool), [1, 9, 0, 25]);
+    assert_eq!(pool.num_idle(), 1);
+    assert_eq!(pool.ticks(), 25);
+}
+
+#[test]
+fn pool_mailboxes() {
+    use rocketsim_rs::pool::{ArenaPool, WakeCondition};
+
+    INIT.call_once(|| init(None, true));
+    let mut pool = ArenaPool::new(2);
+    pool.add_arenas(2, |_| {
+        let mut arena = Arena::default_standard();
+        let _ = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+        arena
+    });
+
+    let sender = pool.sender(1).unwrap();
+    assert_eq!(sender.arena_id(), 1);
+    assert!(pool.sender(2).is_none());
+
+    let throttle = |throttle| CarControls {
+        throttle,
+        ..Default::default()
+    };
+
+    // only the latest controls of a car are used
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            sender.send(vec![(1, throttle(-1.))]).unwrap();
+            sender.send(vec![(1, throttle(1.)), (5, throttle(1.))]).unwrap();
+        });
+    });
+    pool.step(1);
+    assert_eq!(pool.get_mut(1).unwrap().get_car(1).last_controls.throttle, 1.);
+    assert_eq!(pool.get_mut(0).unwrap().get_car(1).last_controls.throttle, 0.);
+
+    pool.suspend(1, WakeCondition::Controls);
+    pool.step(1);
+    assert!(pool.is_idle(1));
+
+    sender.send(vec![(1, throttle(0.5))]).unwrap();
+    pool.step(1);
+    assert!(!pool.is_idle(1));
+    assert_eq!(pool.get_mut(1).unwrap().get_car(1).last_controls.throttle, 0.5);
+    assert_eq!(pool.get(1).unwrap().get_tick_count(), 2);
+
+    drop(pool);
+    assert!(sender.send(Vec::new()).is_err());
+}
+
+#[test]
+fn pool_inference_hook() {
+    use rocketsim_rs::pool::{ArenaPool, InferenceHook, WakeCondition};
+    use std::{
+        collections::HashMap,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+        thread,
+    };
+
+    INIT.call_once(|| init(None, true));
+    let mut pool = ArenaPool::new(2);
+    pool.add_arenas(4, |_| {
+        let mut arena = Arena::default_standard();
+        let _ = arena.pin_mut().add_car(Team::Blue, CarConfig::octane());
+        arena
+    });
+    pool.suspend(2, WakeCondition::Manual);
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let hook_calls = calls.clone();
+    let threads = Arc::new(Mutex::new(HashMap::new()));
+    let observe_threads = threads.clone();
+    pool.set_inference_hook(Some(InferenceHook::new(
+        2,
+        move |id, arena, obs| {
+            // every arena is always observed by the same thread of the pool
+            let thread = thread::current().id();
+            assert_eq!(*observe_threads.lock().unwrap().entry(id).or_insert(thread), thread);
+
+            obs[0] = id as f32;
+            obs[1] = arena.get_tick_count() as f32;
+        },
+        move |obs, n_envs| {
+            hook_calls.fetch_add(1, Ordering::Relaxed);
+            assert_eq!(obs.len(), n_envs * 2);
+
+            // steer every car by the id of its arena
+            obs.chunks(2)
+                .map(|obs| {
+                    vec![(
+                        1,
+                        CarControls {
+                            steer: obs[0] / 4.,
+                            ..Default::default()
+                        },
+                    )]
+                })
+                .collect()
+        },
+    )));
+
+    pool.step(4);
+    pool.step(4);
+    assert_eq!(calls.load(Ordering::Relaxed), 2);
+    assert_eq!(threads.lock().unwrap().len(), 3);
+
+    let steers = (0..4)
+        .map(|id| pool.get_mut(id).unwrap().get_car(1).last_controls.steer)
+        .collect::<Vec<_>>();
+    assert_eq!(steers, [0., 0.25, 0., 0.75]);
+    assert_eq!(pool.get(2).unwrap().get_tick_count(), 0);
+}
+
+#[cfg(feature = "burn_ndarray")]
+#[test]
+fn burn_tensors() {
+    use rocketsim_rs::burn_ext::burn_ndarray::NdArray;
+    use rocketsim_rs::burn_ext::{controls_from_tensor, controls_to_tensor, observations_to_tensor, CONTROLS_LEN};
+
+    let device = Default::default();
+    let observations = observations_to_tensor::<NdArray>(&[0., 1., 2., 3., 4., 5.], 2, &device);
+    assert_eq!(observations.dims(), [2, 3]);
+
+    let controls = [
+        CarControls {
+            throttle: 1.,
+            steer: -0.5,
+            boost: true,
+            ..Default::default()
+        },
+        CarControls {
+            jump: true,
+            handbrake: true,
+            ..Default::default()
+        },
+    ];
+    let tensor = controls_to_tensor::<NdArray>(&controls, &device);
+    assert_eq!(tensor.dims(), [2, CONTROLS_LEN]);
+
+    // 2 environments with 1 car each, and out of range values are clamped
+    let actions = controls_from_tensor((tensor * 2.).reshape([2, 1, CONTROLS_LEN]));
+    assert_eq!(actions.len(), 2);
+    assert_eq!(actions[0][0].throttle, 1.);
+    assert_eq!(actions[0][0].steer, -1.);
+    assert!(actions[0][0].boost && !actions[0][0].jump);
+    assert!(actions[1][0].jump && actions[1][0].handbrake);
+}